@@ -0,0 +1,345 @@
+#[cfg(feature = "compression")]
+use bzip2::bufread::MultiBzDecoder;
+#[cfg(feature = "compression")]
+use bzip2::write::BzEncoder;
+#[cfg(feature = "compression")]
+use flate2::bufread::MultiGzDecoder;
+#[cfg(feature = "compression")]
+use flate2::write::GzEncoder;
+use std::io;
+#[cfg(feature = "compression")]
+use std::io::BufReader;
+use std::io::{BufRead, Chain, Cursor, Read, Write};
+
+/// Number of bytes needed to detect all the supported compressions
+#[cfg(feature = "compression")]
+const MAGIC_BYTES_LEN: usize = 4;
+
+/// A reader with the bytes read to detect the compression put back in front of it
+type PeekedReader<R> = Chain<Cursor<Vec<u8>>, R>;
+
+/// Compression formats of RDF files.
+///
+/// The parsers detect the compressed files from their first bytes
+/// and the serializers compress their output if a compression is provided using `with_compression`.
+///
+/// Requires the `compression` feature.
+#[cfg(feature = "compression")]
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash)]
+#[non_exhaustive]
+pub enum Compression {
+    /// [gzip](https://tools.ietf.org/html/rfc1952)
+    Gzip,
+    /// [bzip2](https://sourceware.org/bzip2/)
+    Bzip2,
+    /// [Zstandard](https://tools.ietf.org/html/rfc8878)
+    Zstd,
+}
+
+#[cfg(feature = "compression")]
+impl Compression {
+    /// The compression name used in the HTTP [`Content-Encoding`](https://tools.ietf.org/html/rfc7231#section-3.1.2.2) header.
+    ///
+    /// ```
+    /// use oxigraph::io::Compression;
+    ///
+    /// assert_eq!(Compression::Gzip.content_encoding(), "gzip")
+    /// ```
+    #[inline]
+    pub fn content_encoding(self) -> &'static str {
+        match self {
+            Compression::Gzip => "gzip",
+            Compression::Bzip2 => "bzip2",
+            Compression::Zstd => "zstd",
+        }
+    }
+
+    /// The usual file extension of the compressed files.
+    ///
+    /// ```
+    /// use oxigraph::io::Compression;
+    ///
+    /// assert_eq!(Compression::Zstd.file_extension(), "zst")
+    /// ```
+    #[inline]
+    pub fn file_extension(self) -> &'static str {
+        match self {
+            Compression::Gzip => "gz",
+            Compression::Bzip2 => "bz2",
+            Compression::Zstd => "zst",
+        }
+    }
+
+    /// Looks for a known compression from an HTTP [`Content-Encoding`](https://tools.ietf.org/html/rfc7231#section-3.1.2.2) header value.
+    ///
+    /// Example:
+    /// ```
+    /// use oxigraph::io::Compression;
+    ///
+    /// assert_eq!(Compression::from_content_encoding("x-gzip"), Some(Compression::Gzip))
+    /// ```
+    pub fn from_content_encoding(content_encoding: &str) -> Option<Self> {
+        match content_encoding.trim().to_ascii_lowercase().as_str() {
+            "gzip" | "x-gzip" => Some(Compression::Gzip),
+            "bzip2" | "x-bzip2" => Some(Compression::Bzip2),
+            "zstd" => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+
+    /// Looks for a known compression from a file extension.
+    ///
+    /// Example:
+    /// ```
+    /// use oxigraph::io::{Compression, GraphFormat};
+    /// use std::path::Path;
+    ///
+    /// let path = Path::new("/tmp/data.nt.gz");
+    /// let compression = path.extension().and_then(|e| Compression::from_extension(e.to_str()?));
+    /// assert_eq!(compression, Some(Compression::Gzip));
+    /// let format = path.file_stem().map(Path::new).and_then(|p| p.extension()).and_then(|e| GraphFormat::from_extension(e.to_str()?));
+    /// assert_eq!(format, Some(GraphFormat::NTriples));
+    /// ```
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "gz" | "gzip" => Some(Compression::Gzip),
+            "bz2" | "bzip2" => Some(Compression::Bzip2),
+            "zst" | "zstd" => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+
+    /// Looks for a known compression from the first bytes of a file.
+    ///
+    /// Example:
+    /// ```
+    /// use oxigraph::io::Compression;
+    ///
+    /// assert_eq!(Compression::from_magic_bytes(b"\x1f\x8b\x08\x00"), Some(Compression::Gzip));
+    /// assert_eq!(Compression::from_magic_bytes(b"<http://example.com/s>"), None);
+    /// ```
+    pub fn from_magic_bytes(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            [0x1f, 0x8b, ..] => Some(Compression::Gzip),
+            [b'B', b'Z', b'h', b'1'..=b'9', ..] => Some(Compression::Bzip2),
+            [0x28, 0xb5, 0x2f, 0xfd, ..] => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+}
+
+/// A reader decompressing its input if needed
+pub(crate) enum DecompressingReader<R: BufRead> {
+    Plain(PeekedReader<R>),
+    #[cfg(feature = "compression")]
+    Gzip(BufReader<MultiGzDecoder<PeekedReader<R>>>),
+    #[cfg(feature = "compression")]
+    Bzip2(BufReader<MultiBzDecoder<PeekedReader<R>>>),
+    #[cfg(feature = "compression")]
+    Zstd(BufReader<zstd::Decoder<'static, PeekedReader<R>>>),
+}
+
+impl<R: BufRead> DecompressingReader<R> {
+    /// Wraps the reader into a decompressor for the given compression or, if not provided, for the one detected from the first bytes
+    #[cfg(feature = "compression")]
+    pub fn new(mut reader: R, compression: Option<Compression>) -> io::Result<Self> {
+        let mut prefix = Vec::new();
+        let compression = if let Some(compression) = compression {
+            Some(compression)
+        } else {
+            // The inner buffer might return less bytes than the magic bytes on a single call
+            loop {
+                let buffer = reader.fill_buf()?;
+                if buffer.is_empty() || (prefix.is_empty() && buffer.len() >= MAGIC_BYTES_LEN) {
+                    break;
+                }
+                let read = buffer.len().min(MAGIC_BYTES_LEN - prefix.len());
+                prefix.extend_from_slice(&buffer[..read]);
+                reader.consume(read);
+                if prefix.len() >= MAGIC_BYTES_LEN {
+                    break;
+                }
+            }
+            if prefix.is_empty() {
+                Compression::from_magic_bytes(reader.fill_buf()?)
+            } else {
+                Compression::from_magic_bytes(&prefix)
+            }
+        };
+        let reader = Cursor::new(prefix).chain(reader);
+        Ok(match compression {
+            None => Self::Plain(reader),
+            Some(Compression::Gzip) => Self::Gzip(BufReader::new(MultiGzDecoder::new(reader))),
+            Some(Compression::Bzip2) => Self::Bzip2(BufReader::new(MultiBzDecoder::new(reader))),
+            Some(Compression::Zstd) => {
+                Self::Zstd(BufReader::new(zstd::Decoder::with_buffer(reader)?))
+            }
+        })
+    }
+
+    #[cfg(not(feature = "compression"))]
+    pub fn new(reader: R) -> io::Result<Self> {
+        Ok(Self::Plain(Cursor::new(Vec::new()).chain(reader)))
+    }
+}
+
+impl<R: BufRead> Read for DecompressingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(reader) => reader.read(buf),
+            #[cfg(feature = "compression")]
+            Self::Gzip(reader) => reader.read(buf),
+            #[cfg(feature = "compression")]
+            Self::Bzip2(reader) => reader.read(buf),
+            #[cfg(feature = "compression")]
+            Self::Zstd(reader) => reader.read(buf),
+        }
+    }
+}
+
+impl<R: BufRead> BufRead for DecompressingReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        match self {
+            Self::Plain(reader) => reader.fill_buf(),
+            #[cfg(feature = "compression")]
+            Self::Gzip(reader) => reader.fill_buf(),
+            #[cfg(feature = "compression")]
+            Self::Bzip2(reader) => reader.fill_buf(),
+            #[cfg(feature = "compression")]
+            Self::Zstd(reader) => reader.fill_buf(),
+        }
+    }
+
+    fn consume(&mut self, amt: usize) {
+        match self {
+            Self::Plain(reader) => reader.consume(amt),
+            #[cfg(feature = "compression")]
+            Self::Gzip(reader) => reader.consume(amt),
+            #[cfg(feature = "compression")]
+            Self::Bzip2(reader) => reader.consume(amt),
+            #[cfg(feature = "compression")]
+            Self::Zstd(reader) => reader.consume(amt),
+        }
+    }
+}
+
+/// A writer compressing its output if requested
+pub(crate) enum CompressingWriter<W: Write> {
+    Plain(W),
+    #[cfg(feature = "compression")]
+    Gzip(GzEncoder<W>),
+    #[cfg(feature = "compression")]
+    Bzip2(BzEncoder<W>),
+    #[cfg(feature = "compression")]
+    Zstd(zstd::Encoder<'static, W>),
+}
+
+impl<W: Write> CompressingWriter<W> {
+    #[cfg(feature = "compression")]
+    pub fn new(writer: W, compression: Option<Compression>) -> io::Result<Self> {
+        Ok(match compression {
+            None => Self::Plain(writer),
+            Some(Compression::Gzip) => {
+                Self::Gzip(GzEncoder::new(writer, flate2::Compression::default()))
+            }
+            Some(Compression::Bzip2) => {
+                Self::Bzip2(BzEncoder::new(writer, bzip2::Compression::default()))
+            }
+            Some(Compression::Zstd) => Self::Zstd(zstd::Encoder::new(writer, 0)?),
+        })
+    }
+
+    #[cfg(not(feature = "compression"))]
+    pub fn new(writer: W) -> io::Result<Self> {
+        Ok(Self::Plain(writer))
+    }
+
+    /// Writes the end of the compressed stream and returns the inner writer
+    pub fn finish(self) -> io::Result<W> {
+        match self {
+            Self::Plain(writer) => Ok(writer),
+            #[cfg(feature = "compression")]
+            Self::Gzip(writer) => writer.finish(),
+            #[cfg(feature = "compression")]
+            Self::Bzip2(writer) => writer.finish(),
+            #[cfg(feature = "compression")]
+            Self::Zstd(writer) => writer.finish(),
+        }
+    }
+}
+
+impl<W: Write> Write for CompressingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(writer) => writer.write(buf),
+            #[cfg(feature = "compression")]
+            Self::Gzip(writer) => writer.write(buf),
+            #[cfg(feature = "compression")]
+            Self::Bzip2(writer) => writer.write(buf),
+            #[cfg(feature = "compression")]
+            Self::Zstd(writer) => writer.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(writer) => writer.flush(),
+            #[cfg(feature = "compression")]
+            Self::Gzip(writer) => writer.flush(),
+            #[cfg(feature = "compression")]
+            Self::Bzip2(writer) => writer.flush(),
+            #[cfg(feature = "compression")]
+            Self::Zstd(writer) => writer.flush(),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "compression"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() -> io::Result<()> {
+        let data = b"<http://example.com/s> <http://example.com/p> <http://example.com/o> .\n";
+        for compression in &[Compression::Gzip, Compression::Bzip2, Compression::Zstd] {
+            let mut writer = CompressingWriter::new(Vec::new(), Some(*compression))?;
+            writer.write_all(data)?;
+            let compressed = writer.finish()?;
+            assert_eq!(
+                Compression::from_magic_bytes(&compressed),
+                Some(*compression)
+            );
+
+            let mut decompressed = Vec::new();
+            DecompressingReader::new(compressed.as_slice(), None)?
+                .read_to_end(&mut decompressed)?;
+            assert_eq!(decompressed, data);
+        }
+
+        let mut plain = Vec::new();
+        DecompressingReader::new(data.as_ref(), None)?.read_to_end(&mut plain)?;
+        assert_eq!(plain, data);
+        Ok(())
+    }
+
+    #[test]
+    fn test_magic_bytes_split_across_reads() -> io::Result<()> {
+        let data = b"<http://example.com/s> <http://example.com/p> <http://example.com/o> .\n";
+        for compression in &[Compression::Gzip, Compression::Bzip2, Compression::Zstd] {
+            let mut writer = CompressingWriter::new(Vec::new(), Some(*compression))?;
+            writer.write_all(data)?;
+            let compressed = writer.finish()?;
+
+            let mut decompressed = Vec::new();
+            DecompressingReader::new(BufReader::with_capacity(1, compressed.as_slice()), None)?
+                .read_to_end(&mut decompressed)?;
+            assert_eq!(decompressed, data);
+        }
+
+        let mut plain = Vec::new();
+        DecompressingReader::new(BufReader::with_capacity(1, data.as_ref()), None)?
+            .read_to_end(&mut plain)?;
+        assert_eq!(plain, data);
+        Ok(())
+    }
+}
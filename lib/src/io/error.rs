@@ -0,0 +1,355 @@
+use crate::error::invalid_data_error;
+use rio_api::parser::ParseError;
+use rio_turtle::TurtleError;
+use rio_xml::RdfXmlError;
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::io::{BufRead, Read};
+use std::rc::Rc;
+
+/// An error in the syntax of a parsed file.
+///
+/// The parsers return it wrapped into an [`io::Error`] with the [`InvalidData`](std::io::ErrorKind::InvalidData)
+/// or [`UnexpectedEof`](std::io::ErrorKind::UnexpectedEof) kinds:
+/// ```
+/// use oxigraph::io::{GraphFormat, GraphParser, SyntaxError};
+/// use std::io::Cursor;
+///
+/// let file = "<http://example.com/s> <http://example.com/p> <http://example.com/o> .\n<http://example.com/s> <http://example.com/p> foo .";
+///
+/// let error = GraphParser::from_format(GraphFormat::NTriples)
+///     .read_triples(Cursor::new(file))?
+///     .collect::<Result<Vec<_>,_>>()
+///     .unwrap_err();
+/// let syntax_error = error.get_ref().unwrap().downcast_ref::<SyntaxError>().unwrap();
+/// let position = syntax_error.position().unwrap();
+/// assert_eq!(position.line(), 1);
+/// assert_eq!(position.column(), 46);
+/// assert_eq!(position.offset(), 117);
+/// # std::io::Result::Ok(())
+/// ```
+#[derive(Debug)]
+pub struct SyntaxError {
+    message: String,
+    position: Option<TextPosition>,
+}
+
+impl SyntaxError {
+    pub(crate) fn new(message: impl Into<String>, position: Option<TextPosition>) -> Self {
+        Self {
+            message: message.into(),
+            position,
+        }
+    }
+
+    /// Builds the error from a Turtle family parser error.
+    ///
+    /// `position` maps a line and a column in the parser input (both starting from 0) to a position in the file.
+    pub(crate) fn from_turtle(
+        error: &TurtleError,
+        position: impl FnOnce(u64, u64) -> Option<TextPosition>,
+    ) -> Self {
+        let mut message = error.to_string();
+        let position = error.textual_position().and_then(|rio_position| {
+            // We remove the position added by Rio at the end of the message
+            let suffix = format!(
+                " on line {} at position {}",
+                rio_position.line_number(),
+                rio_position.byte_number()
+            );
+            if message.ends_with(&suffix) {
+                message.truncate(message.len() - suffix.len());
+            }
+            // Rio lines and bytes start from 1
+            position(
+                rio_position.line_number().saturating_sub(1),
+                rio_position.byte_number().saturating_sub(1),
+            )
+        });
+        Self { message, position }
+    }
+
+    /// The position of the error in the file, if known
+    pub fn position(&self) -> Option<TextPosition> {
+        self.position
+    }
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(position) = self.position {
+            write!(
+                f,
+                "{} at line {} column {}",
+                self.message,
+                position.line + 1,
+                position.column + 1
+            )
+        } else {
+            self.message.fmt(f)
+        }
+    }
+}
+
+impl Error for SyntaxError {}
+
+impl From<SyntaxError> for io::Error {
+    fn from(error: SyntaxError) -> Self {
+        invalid_data_error(error)
+    }
+}
+
+/// Wraps the RDF/XML syntax errors into a [`SyntaxError`]
+///
+/// The RDF/XML parser does not provide the error positions.
+pub(crate) fn rdf_xml_error(error: RdfXmlError) -> io::Error {
+    let error = io::Error::from(error);
+    if error.kind() == io::ErrorKind::InvalidData {
+        SyntaxError::new(error.to_string(), None).into()
+    } else {
+        error
+    }
+}
+
+/// A position in a text file.
+///
+/// Lines and columns both start from 0. Columns and offsets are counted in bytes.
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash)]
+pub struct TextPosition {
+    line: u64,
+    column: u64,
+    offset: u64,
+}
+
+impl TextPosition {
+    pub(crate) fn new(line: u64, column: u64, offset: u64) -> Self {
+        Self {
+            line,
+            column,
+            offset,
+        }
+    }
+
+    /// The line number, starting from 0
+    pub fn line(self) -> u64 {
+        self.line
+    }
+
+    /// The column number in bytes, starting from 0
+    pub fn column(self) -> u64 {
+        self.column
+    }
+
+    /// The number of bytes between the beginning of the file and this position
+    pub fn offset(self) -> u64 {
+        self.offset
+    }
+}
+
+/// Number of bytes the Rio parsers read at once
+const RIO_READ_SIZE: u64 = 8 * 1024;
+
+/// Number of the most recently read bytes whose line beginnings are remembered by [`LineOffsets`]
+///
+/// It covers the look ahead buffer of the Rio parsers (at most two reads).
+const LINE_OFFSETS_WINDOW: u64 = 2 * RIO_READ_SIZE;
+
+/// The byte offsets of the beginnings of the lines recently read from a file
+///
+/// The offsets are written by the [`LineOffsetReader`] and read by the parser using it when an error occurs.
+pub(crate) struct LineOffsets {
+    /// The offsets of the beginnings of the lines from the line `first_line`, never empty
+    line_starts: RefCell<VecDeque<u64>>,
+    /// Number of the first line in `line_starts`
+    first_line: Cell<u64>,
+}
+
+impl LineOffsets {
+    /// The position of the given line and column if the line has been read recently
+    pub fn position(&self, line: u64, column: u64) -> Option<TextPosition> {
+        let index = usize::try_from(line.checked_sub(self.first_line.get())?).ok()?;
+        let line_start = *self.line_starts.borrow().get(index)?;
+        Some(TextPosition::new(line, column, line_start + column))
+    }
+
+    /// The position of the beginning of the last line read
+    pub fn current_line(&self) -> TextPosition {
+        let line_starts = self.line_starts.borrow();
+        TextPosition::new(
+            self.first_line.get() + line_starts.len() as u64 - 1,
+            0,
+            line_starts.back().copied().unwrap_or(0),
+        )
+    }
+
+    /// Replaces the Rio Turtle family syntax errors wrapped into an [`io::Error`] by a [`SyntaxError`]
+    pub fn locate(&self, error: io::Error) -> io::Error {
+        if !error.get_ref().is_some_and(|e| e.is::<TurtleError>()) {
+            return error;
+        }
+        let kind = error.kind();
+        match error.into_inner().unwrap().downcast::<TurtleError>() {
+            Ok(error) => io::Error::new(
+                kind,
+                SyntaxError::from_turtle(&error, |line, column| self.position(line, column)),
+            ),
+            Err(error) => io::Error::new(kind, error),
+        }
+    }
+}
+
+/// A reader keeping track of the byte offsets of the beginnings of the lines it reads
+pub(crate) struct LineOffsetReader<R: BufRead> {
+    inner: R,
+    counter: LineCounter,
+    line_by_line: bool,
+}
+
+impl<R: BufRead> LineOffsetReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            counter: LineCounter {
+                offsets: Rc::new(LineOffsets {
+                    line_starts: RefCell::new(VecDeque::from(vec![0])),
+                    first_line: Cell::new(0),
+                }),
+                read: 0,
+            },
+            line_by_line: false,
+        }
+    }
+
+    /// Makes the reads stop just before the line ends
+    ///
+    /// The Rio parsers only read more data when their buffer is empty so, with this option,
+    /// [`LineOffsets::current_line`] is the line they are parsing when a statement begins.
+    pub fn line_by_line(mut self, line_by_line: bool) -> Self {
+        self.line_by_line = line_by_line;
+        self
+    }
+
+    pub fn offsets(&self) -> Rc<LineOffsets> {
+        self.counter.offsets.clone()
+    }
+}
+
+impl<R: BufRead> Read for LineOffsetReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = if self.line_by_line {
+            let data = self.inner.fill_buf()?;
+            let len = data
+                .iter()
+                .skip(1)
+                .position(|c| *c == b'\n')
+                .map_or(data.len(), |i| i + 1)
+                .min(buf.len());
+            buf[..len].copy_from_slice(&data[..len]);
+            self.inner.consume(len);
+            len
+        } else {
+            self.inner.read(buf)?
+        };
+        self.counter.add(&buf[..read]);
+        Ok(read)
+    }
+}
+
+impl<R: BufRead> BufRead for LineOffsetReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if let Ok(data) = self.inner.fill_buf() {
+            self.counter.add(&data[..amt]);
+        }
+        self.inner.consume(amt)
+    }
+}
+
+/// Counts the lines read by a [`LineOffsetReader`] and publishes their beginnings in [`LineOffsets`]
+struct LineCounter {
+    offsets: Rc<LineOffsets>,
+    read: u64,
+}
+
+impl LineCounter {
+    fn add(&mut self, data: &[u8]) {
+        let mut line_starts = self.offsets.line_starts.borrow_mut();
+        for (i, c) in data.iter().enumerate() {
+            if *c == b'\n' {
+                line_starts.push_back(self.read + i as u64 + 1);
+            }
+        }
+        self.read += data.len() as u64;
+        // We forget the lines ending before the window
+        let window_start = self.read.saturating_sub(LINE_OFFSETS_WINDOW);
+        while line_starts.len() > 1 && line_starts[1] <= window_start {
+            line_starts.pop_front();
+            self.offsets
+                .first_line
+                .set(self.offsets.first_line.get() + 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::{GraphFormat, GraphParser, SyntaxError};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_position_after_many_lines() {
+        let line = "<http://example.com/s> <http://example.com/p> <http://example.com/o> .\n";
+        let mut file = line.repeat(10_000);
+        file.push_str("\n<http://example.com/s> <http://example.com/p> foo .\n");
+        file.push_str(&line.repeat(10));
+        for format in [GraphFormat::NTriples, GraphFormat::Turtle] {
+            let error = GraphParser::from_format(format)
+                .read_triples(Cursor::new(file.as_str()))
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap_err();
+            let position = error
+                .get_ref()
+                .unwrap()
+                .downcast_ref::<SyntaxError>()
+                .unwrap()
+                .position()
+                .unwrap();
+            assert_eq!(position.line(), 10_001);
+            assert!(position.column() >= 46);
+            assert_eq!(
+                position.offset(),
+                (line.len() * 10_000 + 1) as u64 + position.column()
+            );
+        }
+    }
+
+    #[test]
+    fn test_position_followed_by_many_short_lines() {
+        let mut file = "<http://example.com/s> <http://example.com/p> foo .".to_owned();
+        file.push_str(&"\n".repeat(8000));
+        for format in [GraphFormat::NTriples, GraphFormat::Turtle] {
+            let error = GraphParser::from_format(format)
+                .read_triples(Cursor::new(file.as_str()))
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap_err();
+            let position = error
+                .get_ref()
+                .unwrap()
+                .downcast_ref::<SyntaxError>()
+                .unwrap()
+                .position()
+                .unwrap();
+            assert_eq!(position.line(), 0);
+            assert_eq!(position.offset(), position.column());
+        }
+    }
+}
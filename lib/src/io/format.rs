@@ -85,11 +85,34 @@ impl GraphFormat {
             None
         }
     }
+
+    /// Looks for a known format from a file extension.
+    ///
+    /// It supports some file extension aliases and is case insensitive.
+    ///
+    /// Example:
+    /// ```
+    /// use oxigraph::io::GraphFormat;
+    /// use std::path::Path;
+    ///
+    /// assert_eq!(GraphFormat::from_extension("nt"), Some(GraphFormat::NTriples));
+    ///
+    /// let path = Path::new("/tmp/data.ttl");
+    /// assert_eq!(path.extension().and_then(|e| GraphFormat::from_extension(e.to_str()?)), Some(GraphFormat::Turtle));
+    /// ```
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "nt" | "ntriples" => Some(GraphFormat::NTriples),
+            "ttl" | "turtle" => Some(GraphFormat::Turtle),
+            "rdf" | "owl" | "xml" => Some(GraphFormat::RdfXml),
+            _ => None,
+        }
+    }
 }
 
 /// [RDF dataset](https://www.w3.org/TR/rdf11-concepts/#dfn-rdf-dataset) serialization formats.
 ///
-/// This enumeration is non exhaustive. New formats like JSON-LD will be added in the future.
+/// This enumeration is non exhaustive. New formats might be added in the future.
 #[derive(Eq, PartialEq, Debug, Clone, Copy, Hash)]
 #[non_exhaustive]
 pub enum DatasetFormat {
@@ -97,6 +120,8 @@ pub enum DatasetFormat {
     NQuads,
     /// [TriG](https://www.w3.org/TR/trig/)
     TriG,
+    /// [JSON-LD](https://www.w3.org/TR/json-ld11/)
+    JsonLd,
 }
 
 impl DatasetFormat {
@@ -112,6 +137,7 @@ impl DatasetFormat {
         match self {
             DatasetFormat::NQuads => "http://www.w3.org/ns/formats/N-Quads",
             DatasetFormat::TriG => "http://www.w3.org/ns/formats/TriG",
+            DatasetFormat::JsonLd => "http://www.w3.org/ns/formats/JSON-LD",
         }
     }
 
@@ -127,6 +153,7 @@ impl DatasetFormat {
         match self {
             DatasetFormat::NQuads => "application/n-quads",
             DatasetFormat::TriG => "application/trig",
+            DatasetFormat::JsonLd => "application/ld+json",
         }
     }
 
@@ -142,6 +169,7 @@ impl DatasetFormat {
         match self {
             DatasetFormat::NQuads => "nq",
             DatasetFormat::TriG => "trig",
+            DatasetFormat::JsonLd => "jsonld",
         }
     }
     /// Looks for a known format from a media type.
@@ -161,10 +189,30 @@ impl DatasetFormat {
                     Some(DatasetFormat::NQuads)
                 }
                 "application/trig" | "application/x-trig" => Some(DatasetFormat::TriG),
+                "application/ld+json" => Some(DatasetFormat::JsonLd),
                 _ => None,
             }
         } else {
             None
         }
     }
+
+    /// Looks for a known format from a file extension.
+    ///
+    /// It supports some file extension aliases and is case insensitive.
+    ///
+    /// Example:
+    /// ```
+    /// use oxigraph::io::DatasetFormat;
+    ///
+    /// assert_eq!(DatasetFormat::from_extension("nq"), Some(DatasetFormat::NQuads))
+    /// ```
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "nq" | "nquads" => Some(DatasetFormat::NQuads),
+            "trig" => Some(DatasetFormat::TriG),
+            "jsonld" => Some(DatasetFormat::JsonLd),
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,407 @@
+use crate::io::jsonld::context::{Context, TermDefinition};
+use crate::io::jsonld::{as_array, is_keyword, is_list_object};
+use crate::io::relativize;
+use serde_json::{Map, Value};
+
+/// Implements the [compaction algorithm](https://www.w3.org/TR/json-ld11-api/#compaction-algorithm)
+/// on an expanded document and adds the given context to the result
+///
+/// Values are never compacted into language, index, id or type maps.
+pub(crate) fn compact(context: &Context, context_value: &Value, expanded: Vec<Value>) -> Value {
+    let compactor = Compactor { context };
+    let mut result = match compactor.compact_element(None, &Value::Array(expanded)) {
+        Value::Object(result) => result,
+        Value::Array(items) => {
+            let mut result = Map::new();
+            if !items.is_empty() {
+                result.insert(compactor.compact_iri("@graph", None, true), items.into());
+            }
+            result
+        }
+        _ => Map::new(),
+    };
+    if !context_value.is_null()
+        && context_value.as_object().is_none_or(|c| !c.is_empty())
+        && context_value.as_array().is_none_or(|c| !c.is_empty())
+    {
+        result.insert("@context".to_owned(), context_value.clone());
+    }
+    Value::Object(result)
+}
+
+struct Compactor<'a> {
+    context: &'a Context,
+}
+
+impl<'a> Compactor<'a> {
+    fn term(&self, active_property: Option<&str>) -> Option<&'a TermDefinition> {
+        active_property.and_then(|p| self.context.term(p))
+    }
+
+    fn compact_element(&self, active_property: Option<&str>, element: &Value) -> Value {
+        match element {
+            Value::Array(items) => {
+                let mut result = items
+                    .iter()
+                    .map(|item| self.compact_element(active_property, item))
+                    .filter(|item| !item.is_null())
+                    .collect::<Vec<_>>();
+                let container = self
+                    .term(active_property)
+                    .map(|d| d.container)
+                    .unwrap_or_default();
+                if result.len() == 1
+                    && !container.list
+                    && !container.set
+                    && !matches!(active_property, Some("@graph") | Some("@set"))
+                {
+                    result.pop().unwrap()
+                } else {
+                    Value::Array(result)
+                }
+            }
+            Value::Object(map) => {
+                if map.contains_key("@value")
+                    || (map.len() == 1 && map.contains_key("@id") && active_property.is_some())
+                {
+                    return self.compact_value(active_property, map);
+                }
+                if let Some(list) = map.get("@list") {
+                    if self.term(active_property).is_some_and(|d| d.container.list) {
+                        return Value::Array(as_array(self.compact_element(active_property, list)));
+                    }
+                }
+                let mut result = Map::new();
+                for (property, value) in map {
+                    match property.as_str() {
+                        "@id" => {
+                            if let Some(id) = value.as_str() {
+                                result.insert(
+                                    self.compact_iri("@id", None, true),
+                                    self.compact_iri(id, None, false).into(),
+                                );
+                            }
+                        }
+                        "@type" => {
+                            let mut types = as_array(value.clone())
+                                .iter()
+                                .filter_map(Value::as_str)
+                                .map(|t| Value::String(self.compact_iri(t, None, true)))
+                                .collect::<Vec<_>>();
+                            result.insert(
+                                self.compact_iri("@type", None, true),
+                                if types.len() == 1 {
+                                    types.pop().unwrap()
+                                } else {
+                                    Value::Array(types)
+                                },
+                            );
+                        }
+                        "@graph" => {
+                            result.insert(
+                                self.compact_iri("@graph", None, true),
+                                Value::Array(as_array(self.compact_element(Some("@graph"), value))),
+                            );
+                        }
+                        "@list" => {
+                            result.insert(
+                                self.compact_iri("@list", None, true),
+                                Value::Array(as_array(
+                                    self.compact_element(active_property, value),
+                                )),
+                            );
+                        }
+                        "@reverse" => {
+                            let mut reverse = Map::new();
+                            for (property, values) in value.as_object().into_iter().flatten() {
+                                let term = self.compact_iri(property, None, true);
+                                let values = as_array(self.compact_element(Some(&term), values));
+                                reverse.insert(
+                                    term,
+                                    if values.len() == 1 {
+                                        values.into_iter().next().unwrap()
+                                    } else {
+                                        Value::Array(values)
+                                    },
+                                );
+                            }
+                            result.insert(self.compact_iri("@reverse", None, true), reverse.into());
+                        }
+                        property if is_keyword(property) => {
+                            result.insert(self.compact_iri(property, None, true), value.clone());
+                        }
+                        property => self.compact_property(&mut result, property, value),
+                    }
+                }
+                Value::Object(result)
+            }
+            element => element.clone(),
+        }
+    }
+
+    fn compact_property(&self, result: &mut Map<String, Value>, property: &str, values: &Value) {
+        let values = values.as_array().map_or(&[][..], Vec::as_slice);
+        if values.is_empty() {
+            let term = self.compact_iri(property, Some(&Value::Array(Vec::new())), true);
+            result
+                .entry(term)
+                .or_insert_with(|| Value::Array(Vec::new()));
+            return;
+        }
+        for value in values {
+            let term = self.compact_iri(property, Some(value), true);
+            let container = self
+                .context
+                .term(&term)
+                .map(|d| d.container)
+                .unwrap_or_default();
+            if container.list && is_list_object(value) {
+                result.insert(
+                    term.clone(),
+                    Value::Array(as_array(self.compact_element(Some(&term), &value["@list"]))),
+                );
+                continue;
+            }
+            let compacted = self.compact_element(Some(&term), value);
+            let as_array = container.set || container.list;
+            match result.get_mut(&term) {
+                Some(Value::Array(previous)) => previous.extend(as_array_of(compacted)),
+                Some(previous) => {
+                    let mut all = vec![previous.take()];
+                    all.extend(as_array_of(compacted));
+                    *previous = Value::Array(all);
+                }
+                None => {
+                    result.insert(
+                        term,
+                        if as_array {
+                            Value::Array(as_array_of(compacted))
+                        } else {
+                            compacted
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    /// Implements the [value compaction algorithm](https://www.w3.org/TR/json-ld11-api/#value-compaction)
+    fn compact_value(&self, active_property: Option<&str>, value: &Map<String, Value>) -> Value {
+        let definition = self.term(active_property);
+        let type_mapping = definition.and_then(|d| d.type_mapping.as_deref());
+        let language_mapping = match definition.and_then(|d| d.language_mapping.as_ref()) {
+            Some(language) => language.as_deref(),
+            None => self.context.default_language.as_deref(),
+        };
+
+        if let (Some(id), None) = (
+            value.get("@id").and_then(Value::as_str),
+            value.get("@value"),
+        ) {
+            return match type_mapping {
+                Some("@id") => self.compact_iri(id, None, false).into(),
+                Some("@vocab") => self.compact_iri(id, None, true).into(),
+                _ => {
+                    let mut result = Map::new();
+                    result.insert(
+                        self.compact_iri("@id", None, true),
+                        self.compact_iri(id, None, false).into(),
+                    );
+                    result.into()
+                }
+            };
+        }
+
+        let literal = &value["@value"];
+        let datatype = value.get("@type").and_then(Value::as_str);
+        let language = value.get("@language").and_then(Value::as_str);
+        if !value.contains_key("@index") {
+            match (datatype, language) {
+                (Some(datatype), _) if type_mapping == Some(datatype) => return literal.clone(),
+                (None, Some(language))
+                    if type_mapping.is_none()
+                        && language_mapping.is_some_and(|l| l.eq_ignore_ascii_case(language)) =>
+                {
+                    return literal.clone()
+                }
+                (None, None)
+                    if matches!(type_mapping, None | Some("@none"))
+                        && (language_mapping.is_none() || !literal.is_string()) =>
+                {
+                    return literal.clone()
+                }
+                _ => (),
+            }
+        }
+
+        let mut result = Map::new();
+        for (key, entry) in value {
+            let entry = if key == "@type" {
+                entry
+                    .as_str()
+                    .map_or_else(|| entry.clone(), |t| self.compact_iri(t, None, true).into())
+            } else {
+                entry.clone()
+            };
+            result.insert(self.compact_iri(key, None, true), entry);
+        }
+        result.into()
+    }
+
+    /// Implements the [IRI compaction algorithm](https://www.w3.org/TR/json-ld11-api/#iri-compaction)
+    fn compact_iri(&self, iri: &str, value: Option<&Value>, vocab: bool) -> String {
+        if vocab {
+            // Keyword aliases and terms
+            let mut best: Option<(u8, &str)> = None;
+            for (term, definition) in &self.context.term_definitions {
+                if definition.iri.as_deref() != Some(iri) || definition.reverse {
+                    continue;
+                }
+                let rank = if is_keyword(iri) {
+                    Some(1)
+                } else {
+                    term_rank(definition, value, self.context.default_language.as_deref())
+                };
+                if let Some(rank) = rank {
+                    let is_better = best.is_none_or(|(best_rank, best_term)| {
+                        rank > best_rank
+                            || (rank == best_rank
+                                && (term.len(), term.as_str()) < (best_term.len(), best_term))
+                    });
+                    if is_better {
+                        best = Some((rank, term));
+                    }
+                }
+            }
+            if let Some((_, term)) = best {
+                return term.to_owned();
+            }
+            if is_keyword(iri) {
+                return iri.to_owned();
+            }
+            if let Some(vocab) = &self.context.vocab {
+                if let Some(suffix) = iri.strip_prefix(vocab.as_str()) {
+                    if !suffix.is_empty() && self.context.term(suffix).is_none() {
+                        return suffix.to_owned();
+                    }
+                }
+            }
+        }
+
+        // Compact IRIs
+        let mut candidate: Option<String> = None;
+        for (term, definition) in &self.context.term_definitions {
+            if !definition.prefix {
+                continue;
+            }
+            if let Some(suffix) = definition
+                .iri
+                .as_deref()
+                .and_then(|prefix| iri.strip_prefix(prefix))
+            {
+                if suffix.is_empty() {
+                    continue;
+                }
+                let compact_iri = format!("{}:{}", term, suffix);
+                let is_better = candidate
+                    .as_ref()
+                    .is_none_or(|c| (compact_iri.len(), &compact_iri) < (c.len(), c));
+                let is_free = self
+                    .context
+                    .term(&compact_iri)
+                    .is_none_or(|d| value.is_none() && d.iri.as_deref() == Some(iri));
+                if is_better && is_free {
+                    candidate = Some(compact_iri);
+                }
+            }
+        }
+        if let Some(candidate) = candidate {
+            return candidate;
+        }
+
+        if !vocab {
+            if let Some(base_iri) = &self.context.base_iri {
+                if let Some(relative) = relativize(base_iri, iri) {
+                    return relative;
+                }
+            }
+        }
+        iri.to_owned()
+    }
+}
+
+/// Returns how well a term definition fits a value, `None` if it can't be used for it
+fn term_rank(
+    definition: &TermDefinition,
+    value: Option<&Value>,
+    default_language: Option<&str>,
+) -> Option<u8> {
+    let container = definition.container;
+    if container.is_map() || container.graph {
+        return None;
+    }
+    let type_mapping = definition.type_mapping.as_deref();
+    let language_mapping = definition.language_mapping.as_ref();
+    let is_generic = type_mapping.is_none() && language_mapping.is_none();
+    let value = if let Some(value) = value {
+        value
+    } else {
+        return if is_generic { Some(1) } else { None };
+    };
+    if is_list_object(value) {
+        return if container.list {
+            Some(3)
+        } else if is_generic {
+            Some(1)
+        } else {
+            None
+        };
+    }
+    if container.list {
+        return None;
+    }
+    let value = if let Some(value) = value.as_object() {
+        value
+    } else {
+        return if is_generic { Some(1) } else { None };
+    };
+    if !value.contains_key("@value") {
+        // Node reference
+        return match type_mapping {
+            Some("@id") | Some("@vocab") => Some(3),
+            None if language_mapping.is_none() => Some(1),
+            _ => None,
+        };
+    }
+    let datatype = value.get("@type").and_then(Value::as_str);
+    let language = value.get("@language").and_then(Value::as_str);
+    match (datatype, language) {
+        (Some(datatype), _) => {
+            if type_mapping == Some(datatype) {
+                Some(3)
+            } else if is_generic {
+                Some(1)
+            } else {
+                None
+            }
+        }
+        (None, Some(language)) => match (type_mapping, language_mapping) {
+            (None, Some(Some(l))) if l.eq_ignore_ascii_case(language) => Some(3),
+            (None, None) => Some(1),
+            _ => None,
+        },
+        (None, None) => match (type_mapping, language_mapping) {
+            (None, Some(None)) | (Some("@none"), _) => Some(3),
+            (None, None) if default_language.is_none() || !value["@value"].is_string() => Some(2),
+            (None, None) => Some(1),
+            _ => None,
+        },
+    }
+}
+
+fn as_array_of(value: Value) -> Vec<Value> {
+    match value {
+        Value::Array(values) => values,
+        value => vec![value],
+    }
+}
@@ -0,0 +1,697 @@
+use crate::io::jsonld::{
+    is_absolute_iri, is_blank_node_id, is_keyword, looks_like_keyword, DocumentLoader, JsonLdError,
+};
+use oxiri::Iri;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Maximal number of nested remote contexts
+const MAX_REMOTE_CONTEXTS: usize = 32;
+
+/// A JSON-LD [active context](https://www.w3.org/TR/json-ld11-api/#dfn-active-context)
+#[derive(Clone, Default)]
+pub(crate) struct Context {
+    pub base_iri: Option<Iri<String>>,
+    original_base_iri: Option<Iri<String>>,
+    pub vocab: Option<String>,
+    pub default_language: Option<String>,
+    pub term_definitions: HashMap<String, TermDefinition>,
+    /// The context to restore when entering a new node object if this context should not be propagated
+    pub previous_context: Option<Box<Context>>,
+}
+
+/// A JSON-LD [term definition](https://www.w3.org/TR/json-ld11/#dfn-term-definition)
+#[derive(Clone, Default)]
+pub(crate) struct TermDefinition {
+    /// The IRI mapping, `None` if the term is explicitly not mapped
+    pub iri: Option<String>,
+    pub prefix: bool,
+    pub reverse: bool,
+    pub type_mapping: Option<String>,
+    /// `Some(None)` if the term is explicitly without language
+    pub language_mapping: Option<Option<String>>,
+    pub container: Container,
+    /// The scoped context and the base IRI to resolve it against
+    pub context: Option<(Value, Option<Iri<String>>)>,
+}
+
+/// A term definition [container mapping](https://www.w3.org/TR/json-ld11/#container-definitions)
+#[derive(Clone, Copy, Default, Eq, PartialEq)]
+pub(crate) struct Container {
+    pub list: bool,
+    pub set: bool,
+    pub language: bool,
+    pub index: bool,
+    pub graph: bool,
+    pub id: bool,
+    pub type_: bool,
+}
+
+impl Container {
+    fn parse(value: &Value) -> Option<Self> {
+        let mut container = Self::default();
+        let values = match value {
+            Value::Null => return Some(container),
+            Value::String(_) => std::slice::from_ref(value),
+            Value::Array(values) => values.as_slice(),
+            _ => return None,
+        };
+        for value in values {
+            match value.as_str()? {
+                "@list" => container.list = true,
+                "@set" => container.set = true,
+                "@language" => container.language = true,
+                "@index" => container.index = true,
+                "@graph" => container.graph = true,
+                "@id" => container.id = true,
+                "@type" => container.type_ = true,
+                _ => return None,
+            }
+        }
+        if container.list && values.len() > 1 {
+            return None;
+        }
+        Some(container)
+    }
+
+    /// Containers that are serialized as JSON objects
+    pub fn is_map(self) -> bool {
+        self.language || self.index || self.id || self.type_
+    }
+}
+
+impl Context {
+    pub fn new(base_iri: Option<&Iri<String>>) -> Self {
+        Self {
+            base_iri: base_iri.cloned(),
+            original_base_iri: base_iri.cloned(),
+            ..Self::default()
+        }
+    }
+
+    /// Returns the term definition of a term if it exists
+    pub fn term(&self, term: &str) -> Option<&TermDefinition> {
+        self.term_definitions.get(term)
+    }
+
+    /// Applies the [IRI expansion algorithm](https://www.w3.org/TR/json-ld11-api/#iri-expansion)
+    ///
+    /// Returns `None` if the value is mapped to nothing.
+    pub fn expand_iri(&self, value: &str, document_relative: bool, vocab: bool) -> Option<String> {
+        if is_keyword(value) {
+            return Some(value.to_owned());
+        }
+        if looks_like_keyword(value) {
+            return None;
+        }
+        if vocab {
+            if let Some(definition) = self.term_definitions.get(value) {
+                return definition.iri.clone();
+            }
+        }
+        if let Some(i) = value.get(1..).and_then(|v| v.find(':')) {
+            let (prefix, suffix) = (&value[..=i], &value[i + 2..]);
+            if prefix == "_" || suffix.starts_with("//") {
+                return Some(value.to_owned());
+            }
+            if let Some(definition) = self.term_definitions.get(prefix) {
+                if let Some(iri) = &definition.iri {
+                    if definition.prefix {
+                        return Some(format!("{}{}", iri, suffix));
+                    }
+                }
+            }
+            if is_absolute_iri(value) {
+                return Some(value.to_owned());
+            }
+        }
+        if vocab {
+            if let Some(vocab) = &self.vocab {
+                return Some(format!("{}{}", vocab, value));
+            }
+        }
+        if document_relative {
+            if let Some(base_iri) = &self.base_iri {
+                if let Ok(iri) = base_iri.resolve(value) {
+                    return Some(iri.into_inner());
+                }
+            }
+        }
+        Some(value.to_owned())
+    }
+}
+
+/// Implements the [context processing algorithm](https://www.w3.org/TR/json-ld11-api/#context-processing-algorithm)
+pub(crate) struct ContextProcessor<'a> {
+    loader: &'a dyn DocumentLoader,
+    remote_contexts: Vec<String>,
+}
+
+impl<'a> ContextProcessor<'a> {
+    pub fn new(loader: &'a dyn DocumentLoader) -> Self {
+        Self {
+            loader,
+            remote_contexts: Vec::new(),
+        }
+    }
+
+    pub fn process(
+        &mut self,
+        active: &Context,
+        local: &Value,
+        base_iri: Option<&Iri<String>>,
+    ) -> Result<Context, JsonLdError> {
+        self.process_with_propagation(active, local, base_iri, true)
+    }
+
+    pub fn process_with_propagation(
+        &mut self,
+        active: &Context,
+        local: &Value,
+        base_iri: Option<&Iri<String>>,
+        propagate: bool,
+    ) -> Result<Context, JsonLdError> {
+        let mut result = active.clone();
+        let local_contexts = match local {
+            Value::Array(contexts) => contexts.as_slice(),
+            local => std::slice::from_ref(local),
+        };
+        let propagate = match local_contexts.first() {
+            Some(Value::Object(map)) => match map.get("@propagate") {
+                Some(Value::Bool(propagate)) => *propagate,
+                Some(_) => {
+                    return Err(JsonLdError::new(
+                        "invalid @propagate value",
+                        "@propagate must be a boolean",
+                    ))
+                }
+                None => propagate,
+            },
+            _ => propagate,
+        };
+        if !propagate && result.previous_context.is_none() {
+            result.previous_context = Some(Box::new(active.clone()));
+        }
+        for context in local_contexts {
+            match context {
+                Value::Null => {
+                    result = Context {
+                        base_iri: active.original_base_iri.clone(),
+                        original_base_iri: active.original_base_iri.clone(),
+                        previous_context: result.previous_context.take(),
+                        ..Context::default()
+                    }
+                }
+                Value::String(iri) => {
+                    let iri = resolve_remote_iri(iri, base_iri)?;
+                    if self.remote_contexts.len() >= MAX_REMOTE_CONTEXTS {
+                        return Err(JsonLdError::new(
+                            "context overflow",
+                            format!("Too many nested remote contexts when loading {}", iri),
+                        ));
+                    }
+                    let context = self.load_context(&iri)?;
+                    self.remote_contexts.push(iri.clone());
+                    let remote_base = Iri::parse(iri).ok();
+                    let processed = self.process_with_propagation(
+                        &result,
+                        &context,
+                        remote_base.as_ref(),
+                        true,
+                    );
+                    self.remote_contexts.pop();
+                    result = processed?;
+                }
+                Value::Object(map) => self.process_local_context(&mut result, map, base_iri)?,
+                _ => {
+                    return Err(JsonLdError::new(
+                        "invalid local context",
+                        format!("{} is not a valid context", context),
+                    ))
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    fn process_local_context(
+        &mut self,
+        result: &mut Context,
+        map: &Map<String, Value>,
+        base_iri: Option<&Iri<String>>,
+    ) -> Result<(), JsonLdError> {
+        if let Some(version) = map.get("@version") {
+            if version.as_f64() != Some(1.1) {
+                return Err(JsonLdError::new(
+                    "invalid @version value",
+                    format!("{} is not a supported JSON-LD version", version),
+                ));
+            }
+        }
+        let mut map = map.clone();
+        if let Some(import) = map.remove("@import") {
+            let import = import.as_str().ok_or_else(|| {
+                JsonLdError::new("invalid @import value", "@import must be a string")
+            })?;
+            let iri = resolve_remote_iri(import, base_iri)?;
+            match self.load_context(&iri)? {
+                Value::Object(imported) => {
+                    if imported.contains_key("@import") {
+                        return Err(JsonLdError::new(
+                            "invalid context entry",
+                            format!("The imported context {} contains @import", iri),
+                        ));
+                    }
+                    for (key, value) in imported {
+                        map.entry(key).or_insert(value);
+                    }
+                }
+                _ => {
+                    return Err(JsonLdError::new(
+                        "invalid remote context",
+                        format!("The imported context {} is not an object", iri),
+                    ))
+                }
+            }
+        }
+        if self.remote_contexts.is_empty() {
+            match map.get("@base") {
+                None => (),
+                Some(Value::Null) => result.base_iri = None,
+                Some(Value::String(base)) => {
+                    result.base_iri = Some(
+                        if let Some(current) = &result.base_iri {
+                            current.resolve(base)
+                        } else {
+                            Iri::parse(base.clone())
+                        }
+                        .map_err(|e| {
+                            JsonLdError::new(
+                                "invalid base IRI",
+                                format!("Invalid base IRI {}: {}", base, e),
+                            )
+                        })?,
+                    )
+                }
+                Some(base) => {
+                    return Err(JsonLdError::new(
+                        "invalid base IRI",
+                        format!("{} is not a valid base IRI", base),
+                    ))
+                }
+            }
+        }
+        match map.get("@vocab") {
+            None => (),
+            Some(Value::Null) => result.vocab = None,
+            Some(Value::String(vocab)) => {
+                result.vocab = Some(
+                    if is_blank_node_id(vocab) || is_absolute_iri(vocab) {
+                        Some(vocab.clone())
+                    } else {
+                        result.expand_iri(vocab, true, true)
+                    }
+                    .ok_or_else(|| {
+                        JsonLdError::new(
+                            "invalid vocab mapping",
+                            format!("{} is not a valid vocabulary mapping", vocab),
+                        )
+                    })?,
+                )
+            }
+            Some(vocab) => {
+                return Err(JsonLdError::new(
+                    "invalid vocab mapping",
+                    format!("{} is not a valid vocabulary mapping", vocab),
+                ))
+            }
+        }
+        match map.get("@language") {
+            None => (),
+            Some(Value::Null) => result.default_language = None,
+            Some(Value::String(language)) => result.default_language = Some(language.clone()),
+            Some(language) => {
+                return Err(JsonLdError::new(
+                    "invalid default language",
+                    format!("{} is not a valid language", language),
+                ))
+            }
+        }
+        let mut defined = HashMap::new();
+        for term in map.keys() {
+            if !matches!(
+                term.as_str(),
+                "@base"
+                    | "@direction"
+                    | "@import"
+                    | "@language"
+                    | "@propagate"
+                    | "@protected"
+                    | "@version"
+                    | "@vocab"
+            ) {
+                self.create_term_definition(result, &map, term, &mut defined, base_iri)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Implements the [create term definition algorithm](https://www.w3.org/TR/json-ld11-api/#create-term-definition)
+    fn create_term_definition(
+        &mut self,
+        active: &mut Context,
+        local: &Map<String, Value>,
+        term: &str,
+        defined: &mut HashMap<String, bool>,
+        base_iri: Option<&Iri<String>>,
+    ) -> Result<(), JsonLdError> {
+        match defined.get(term) {
+            Some(true) => return Ok(()),
+            Some(false) => {
+                return Err(JsonLdError::new(
+                    "cyclic IRI mapping",
+                    format!("The definition of the term {} is cyclic", term),
+                ))
+            }
+            None => (),
+        }
+        if term.is_empty() {
+            return Err(JsonLdError::new(
+                "invalid term definition",
+                "The empty string is not a valid term",
+            ));
+        }
+        defined.insert(term.to_owned(), false);
+        let value = &local[term];
+        if is_keyword(term) {
+            if term == "@type" && value.is_object() {
+                defined.insert(term.to_owned(), true);
+                return Ok(());
+            }
+            return Err(JsonLdError::new(
+                "keyword redefinition",
+                format!("The keyword {} can't be redefined", term),
+            ));
+        }
+        if looks_like_keyword(term) {
+            defined.insert(term.to_owned(), true);
+            return Ok(());
+        }
+        active.term_definitions.remove(term);
+        let (map, simple_term) = match value {
+            Value::Null => {
+                active
+                    .term_definitions
+                    .insert(term.to_owned(), TermDefinition::default());
+                defined.insert(term.to_owned(), true);
+                return Ok(());
+            }
+            Value::String(id) => {
+                let mut map = Map::new();
+                map.insert("@id".to_owned(), Value::String(id.clone()));
+                (map, true)
+            }
+            Value::Object(map) => (map.clone(), false),
+            _ => {
+                return Err(JsonLdError::new(
+                    "invalid term definition",
+                    format!("The definition of the term {} is not valid", term),
+                ))
+            }
+        };
+        let mut definition = TermDefinition::default();
+
+        if let Some(type_mapping) = map.get("@type") {
+            let type_mapping = type_mapping
+                .as_str()
+                .and_then(|t| {
+                    self.expand_iri(active, local, defined, t, false, true, base_iri)
+                        .transpose()
+                })
+                .transpose()?
+                .filter(|t| {
+                    matches!(t.as_str(), "@id" | "@json" | "@none" | "@vocab") || is_absolute_iri(t)
+                })
+                .ok_or_else(|| {
+                    JsonLdError::new(
+                        "invalid type mapping",
+                        format!("The type mapping of the term {} is not valid", term),
+                    )
+                })?;
+            definition.type_mapping = Some(type_mapping);
+        }
+
+        if let Some(reverse) = map.get("@reverse") {
+            if map.contains_key("@id") || map.contains_key("@nest") {
+                return Err(JsonLdError::new(
+                    "invalid reverse property",
+                    format!("The reverse term {} can't have @id or @nest", term),
+                ));
+            }
+            let reverse = reverse.as_str().ok_or_else(|| {
+                JsonLdError::new("invalid IRI mapping", "@reverse value must be a string")
+            })?;
+            if looks_like_keyword(reverse) {
+                defined.insert(term.to_owned(), true);
+                return Ok(());
+            }
+            definition.iri = Some(
+                self.expand_iri(active, local, defined, reverse, false, true, base_iri)?
+                    .filter(|iri| is_absolute_iri(iri) || is_blank_node_id(iri))
+                    .ok_or_else(|| {
+                        JsonLdError::new(
+                            "invalid IRI mapping",
+                            format!("{} is not a valid reverse property", reverse),
+                        )
+                    })?,
+            );
+            if let Some(container) = map.get("@container") {
+                definition.container = Container::parse(container)
+                    .filter(|c| {
+                        *c == Container::default()
+                            || *c
+                                == Container {
+                                    set: true,
+                                    ..Container::default()
+                                }
+                            || *c
+                                == Container {
+                                    index: true,
+                                    ..Container::default()
+                                }
+                    })
+                    .ok_or_else(|| {
+                        JsonLdError::new(
+                            "invalid reverse property",
+                            format!("Invalid container for the reverse term {}", term),
+                        )
+                    })?;
+            }
+            definition.reverse = true;
+            active.term_definitions.insert(term.to_owned(), definition);
+            defined.insert(term.to_owned(), true);
+            return Ok(());
+        }
+
+        match map.get("@id").filter(|id| id.as_str() != Some(term)) {
+            Some(Value::Null) => (),
+            Some(Value::String(id)) => {
+                if !is_keyword(id) && looks_like_keyword(id) {
+                    defined.insert(term.to_owned(), true);
+                    return Ok(());
+                }
+                let iri = self
+                    .expand_iri(active, local, defined, id, false, true, base_iri)?
+                    .filter(|iri| is_keyword(iri) || is_absolute_iri(iri) || is_blank_node_id(iri))
+                    .ok_or_else(|| {
+                        JsonLdError::new(
+                            "invalid IRI mapping",
+                            format!("{} is not a valid IRI mapping for {}", id, term),
+                        )
+                    })?;
+                if iri == "@context" {
+                    return Err(JsonLdError::new(
+                        "invalid keyword alias",
+                        "@context can't be aliased",
+                    ));
+                }
+                if simple_term
+                    && !term.contains(':')
+                    && !term.contains('/')
+                    && (is_blank_node_id(&iri)
+                        || iri.ends_with(&[':', '/', '?', '#', '[', ']', '@'][..]))
+                {
+                    definition.prefix = true;
+                }
+                definition.iri = Some(iri);
+            }
+            Some(_) => {
+                return Err(JsonLdError::new(
+                    "invalid IRI mapping",
+                    format!("The @id of the term {} must be a string", term),
+                ))
+            }
+            None => {
+                definition.iri = Some(if let Some(i) = term.get(1..).and_then(|t| t.find(':')) {
+                    let prefix = &term[..=i];
+                    if local.contains_key(prefix) {
+                        self.create_term_definition(active, local, prefix, defined, base_iri)?;
+                    }
+                    match active
+                        .term_definitions
+                        .get(prefix)
+                        .and_then(|d| d.iri.as_ref())
+                    {
+                        Some(prefix_iri) => format!("{}{}", prefix_iri, &term[i + 2..]),
+                        None => term.to_owned(),
+                    }
+                } else if term.contains('/') {
+                    active
+                        .expand_iri(term, false, true)
+                        .filter(|iri| is_absolute_iri(iri))
+                        .ok_or_else(|| {
+                            JsonLdError::new(
+                                "invalid IRI mapping",
+                                format!("The term {} is not an IRI", term),
+                            )
+                        })?
+                } else if let Some(vocab) = &active.vocab {
+                    format!("{}{}", vocab, term)
+                } else {
+                    return Err(JsonLdError::new(
+                        "invalid IRI mapping",
+                        format!(
+                            "The term {} has no IRI mapping and there is no @vocab",
+                            term
+                        ),
+                    ));
+                });
+            }
+        }
+
+        if let Some(container) = map.get("@container") {
+            definition.container = Container::parse(container).ok_or_else(|| {
+                JsonLdError::new(
+                    "invalid container mapping",
+                    format!("Invalid container for the term {}", term),
+                )
+            })?;
+        }
+
+        if let Some(context) = map.get("@context") {
+            let mut processor = ContextProcessor {
+                loader: self.loader,
+                remote_contexts: self.remote_contexts.clone(),
+            };
+            processor.process(active, context, base_iri).map_err(|e| {
+                JsonLdError::new(
+                    "invalid scoped context",
+                    format!("Invalid scoped context of the term {}: {}", term, e),
+                )
+            })?;
+            definition.context = Some((context.clone(), base_iri.cloned()));
+        }
+
+        if !map.contains_key("@type") {
+            match map.get("@language") {
+                None => (),
+                Some(Value::Null) => definition.language_mapping = Some(None),
+                Some(Value::String(language)) => {
+                    definition.language_mapping = Some(Some(language.clone()))
+                }
+                Some(_) => {
+                    return Err(JsonLdError::new(
+                        "invalid language mapping",
+                        format!("Invalid language of the term {}", term),
+                    ))
+                }
+            }
+        }
+
+        match map.get("@prefix") {
+            None => (),
+            Some(Value::Bool(prefix)) => {
+                if term.contains(':') || term.contains('/') {
+                    return Err(JsonLdError::new(
+                        "invalid term definition",
+                        format!("The term {} can't be a prefix", term),
+                    ));
+                }
+                definition.prefix = *prefix;
+            }
+            Some(_) => {
+                return Err(JsonLdError::new(
+                    "invalid @prefix value",
+                    "@prefix must be a boolean",
+                ))
+            }
+        }
+
+        active.term_definitions.insert(term.to_owned(), definition);
+        defined.insert(term.to_owned(), true);
+        Ok(())
+    }
+
+    /// IRI expansion during context processing, the terms of the local context are defined on the fly
+    #[allow(clippy::too_many_arguments)]
+    fn expand_iri(
+        &mut self,
+        active: &mut Context,
+        local: &Map<String, Value>,
+        defined: &mut HashMap<String, bool>,
+        value: &str,
+        document_relative: bool,
+        vocab: bool,
+        base_iri: Option<&Iri<String>>,
+    ) -> Result<Option<String>, JsonLdError> {
+        if local.contains_key(value) && defined.get(value) != Some(&true) {
+            self.create_term_definition(active, local, value, defined, base_iri)?;
+        }
+        if let Some(i) = value.get(1..).and_then(|v| v.find(':')) {
+            let prefix = &value[..=i];
+            if local.contains_key(prefix) && defined.get(prefix) != Some(&true) {
+                self.create_term_definition(active, local, prefix, defined, base_iri)?;
+            }
+        }
+        Ok(active.expand_iri(value, document_relative, vocab))
+    }
+
+    fn load_context(&self, iri: &str) -> Result<Value, JsonLdError> {
+        let document = self.loader.load_document(iri).map_err(|e| {
+            JsonLdError::new(
+                "loading remote context failed",
+                format!("Failed to load {}: {}", iri, e),
+            )
+        })?;
+        let document: Value = serde_json::from_str(&document).map_err(|e| {
+            JsonLdError::new(
+                "loading remote context failed",
+                format!("The remote context {} is not valid JSON: {}", iri, e),
+            )
+        })?;
+        match document {
+            Value::Object(mut map) => map.remove("@context"),
+            _ => None,
+        }
+        .ok_or_else(|| {
+            JsonLdError::new(
+                "invalid remote context",
+                format!("The document {} does not contain a @context entry", iri),
+            )
+        })
+    }
+}
+
+fn resolve_remote_iri(iri: &str, base_iri: Option<&Iri<String>>) -> Result<String, JsonLdError> {
+    if let Some(base_iri) = base_iri {
+        base_iri.resolve(iri).map(|iri| iri.into_inner())
+    } else {
+        Iri::parse(iri.to_owned()).map(|iri| iri.into_inner())
+    }
+    .map_err(|e| {
+        JsonLdError::new(
+            "loading document failed",
+            format!("Invalid remote context IRI {}: {}", iri, e),
+        )
+    })
+}
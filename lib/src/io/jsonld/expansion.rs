@@ -0,0 +1,659 @@
+use crate::io::jsonld::context::{Context, ContextProcessor};
+use crate::io::jsonld::{
+    as_array, is_absolute_iri, is_blank_node_id, is_keyword, is_list_object, is_value_object,
+    DocumentLoader, JsonLdError,
+};
+use oxiri::Iri;
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+
+/// Implements the [expansion algorithm](https://www.w3.org/TR/json-ld11-api/#expansion-algorithm)
+pub(crate) struct Expander<'a> {
+    processor: ContextProcessor<'a>,
+    base_iri: Option<&'a Iri<String>>,
+}
+
+impl<'a> Expander<'a> {
+    pub fn new(loader: &'a dyn DocumentLoader, base_iri: Option<&'a Iri<String>>) -> Self {
+        Self {
+            processor: ContextProcessor::new(loader),
+            base_iri,
+        }
+    }
+
+    /// Expands a full document and returns the list of its top-level nodes
+    pub fn expand(
+        &mut self,
+        document: &Value,
+        context: &Context,
+    ) -> Result<Vec<Value>, JsonLdError> {
+        let expanded = match self.expand_element(context, None, document, false)? {
+            Value::Object(mut map) if map.len() == 1 && map.contains_key("@graph") => {
+                map.remove("@graph").unwrap_or(Value::Null)
+            }
+            expanded => expanded,
+        };
+        Ok(as_array(expanded))
+    }
+
+    fn expand_element(
+        &mut self,
+        active: &Context,
+        active_property: Option<&str>,
+        element: &Value,
+        from_map: bool,
+    ) -> Result<Value, JsonLdError> {
+        match element {
+            Value::Null => Ok(Value::Null),
+            Value::Array(items) => {
+                let is_list = active_property
+                    .and_then(|p| active.term(p))
+                    .is_some_and(|d| d.container.list);
+                let mut result = Vec::new();
+                for item in items {
+                    let mut expanded =
+                        self.expand_element(active, active_property, item, from_map)?;
+                    if is_list && expanded.is_array() {
+                        expanded = list_object(as_array(expanded));
+                    }
+                    match expanded {
+                        Value::Array(expanded) => result.extend(expanded),
+                        Value::Null => (),
+                        expanded => result.push(expanded),
+                    }
+                }
+                Ok(Value::Array(result))
+            }
+            Value::Object(map) => self.expand_object(active, active_property, map, from_map),
+            _ => {
+                let active_property = match active_property {
+                    None | Some("@graph") => return Ok(Value::Null),
+                    Some(active_property) => active_property,
+                };
+                let active = self.property_scoped_context(active, active_property)?;
+                Ok(expand_value(&active, active_property, element))
+            }
+        }
+    }
+
+    fn expand_object(
+        &mut self,
+        active: &Context,
+        active_property: Option<&str>,
+        map: &Map<String, Value>,
+        from_map: bool,
+    ) -> Result<Value, JsonLdError> {
+        let mut active = Cow::Borrowed(active);
+
+        // Contexts that are not propagated are reverted when entering a new node object
+        if let Some(previous_context) = &active.previous_context {
+            let is_value = map
+                .keys()
+                .any(|k| active.expand_iri(k, false, true).as_deref() == Some("@value"));
+            let is_reference = map.len() == 1
+                && map
+                    .keys()
+                    .all(|k| active.expand_iri(k, false, true).as_deref() == Some("@id"));
+            if !from_map && !is_value && !is_reference {
+                active = Cow::Owned(previous_context.as_ref().clone());
+            }
+        }
+        if let Some(active_property) = active_property {
+            if let Cow::Owned(scoped) = self.property_scoped_context(&active, active_property)? {
+                active = Cow::Owned(scoped);
+            }
+        }
+        if let Some(context) = map.get("@context") {
+            active = Cow::Owned(self.processor.process(&active, context, self.base_iri)?);
+        }
+
+        // Type-scoped contexts
+        let type_scoped_context = active.clone().into_owned();
+        let mut input_type = None;
+        for (key, value) in map {
+            if active.expand_iri(key, false, true).as_deref() != Some("@type") {
+                continue;
+            }
+            let mut types = as_array(value.clone())
+                .into_iter()
+                .filter_map(|t| t.as_str().map(ToOwned::to_owned))
+                .collect::<Vec<_>>();
+            types.sort();
+            for term in &types {
+                if let Some((context, base_iri)) = type_scoped_context
+                    .term(term)
+                    .and_then(|d| d.context.as_ref())
+                {
+                    active = Cow::Owned(self.processor.process_with_propagation(
+                        &active,
+                        context,
+                        base_iri.as_ref(),
+                        false,
+                    )?);
+                }
+            }
+            input_type = types.last().and_then(|t| active.expand_iri(t, true, true));
+        }
+
+        let mut result = Map::new();
+        self.expand_entries(
+            &mut result,
+            &active,
+            &type_scoped_context,
+            active_property,
+            map,
+            input_type.as_deref(),
+        )?;
+
+        if let Some(value) = result.get("@value") {
+            if result.keys().any(|k| {
+                !matches!(
+                    k.as_str(),
+                    "@value" | "@type" | "@language" | "@direction" | "@index"
+                )
+            }) || (result.contains_key("@type") && result.contains_key("@language"))
+            {
+                return Err(JsonLdError::new(
+                    "invalid value object",
+                    "A value object contains not allowed entries",
+                ));
+            }
+            if result.get("@type").and_then(Value::as_str) != Some("@json") {
+                if value.is_null() {
+                    return Ok(Value::Null);
+                }
+                if value.is_object() || value.is_array() {
+                    return Err(JsonLdError::new(
+                        "invalid value object value",
+                        format!("{} is not a valid literal value", value),
+                    ));
+                }
+                if result.contains_key("@language") && !value.is_string() {
+                    return Err(JsonLdError::new(
+                        "invalid language-tagged value",
+                        format!("{} can't have a language tag", value),
+                    ));
+                }
+                if let Some(datatype) = result.get("@type") {
+                    if !datatype
+                        .as_str()
+                        .is_some_and(|t| is_absolute_iri(t) || is_blank_node_id(t))
+                    {
+                        return Err(JsonLdError::new(
+                            "invalid typed value",
+                            format!("{} is not a valid datatype", datatype),
+                        ));
+                    }
+                }
+            }
+        } else if let Some(types) = result.get_mut("@type") {
+            if !types.is_array() {
+                *types = Value::Array(vec![types.take()]);
+            }
+        } else if result.contains_key("@set") || result.contains_key("@list") {
+            if result.len() > 2 || (result.len() == 2 && !result.contains_key("@index")) {
+                return Err(JsonLdError::new(
+                    "invalid set or list object",
+                    "A set or list object can only contain @index besides @set or @list",
+                ));
+            }
+            if let Some(set) = result.remove("@set") {
+                return Ok(set);
+            }
+        }
+
+        if result.len() == 1 && result.contains_key("@language") {
+            return Ok(Value::Null);
+        }
+        if matches!(active_property, None | Some("@graph"))
+            && (result.is_empty()
+                || result.contains_key("@value")
+                || result.contains_key("@list")
+                || (result.len() == 1 && result.contains_key("@id")))
+        {
+            return Ok(Value::Null);
+        }
+        Ok(Value::Object(result))
+    }
+
+    fn expand_entries(
+        &mut self,
+        result: &mut Map<String, Value>,
+        active: &Context,
+        type_scoped_context: &Context,
+        active_property: Option<&str>,
+        map: &Map<String, Value>,
+        input_type: Option<&str>,
+    ) -> Result<(), JsonLdError> {
+        let mut reverse_map = Map::new();
+        let mut nests = Vec::new();
+        for (key, value) in map {
+            if key == "@context" {
+                continue;
+            }
+            let expanded_property = match active.expand_iri(key, false, true) {
+                Some(p) if p.contains(':') || is_keyword(&p) => p,
+                _ => continue,
+            };
+            if is_keyword(&expanded_property) {
+                if active_property == Some("@reverse") {
+                    return Err(JsonLdError::new(
+                        "invalid reverse property map",
+                        "A reverse property map can't contain keywords",
+                    ));
+                }
+                if result.contains_key(&expanded_property)
+                    && !matches!(expanded_property.as_str(), "@included" | "@type")
+                {
+                    return Err(JsonLdError::new(
+                        "colliding keywords",
+                        format!("{} is used multiple times", expanded_property),
+                    ));
+                }
+                let expanded_value = match expanded_property.as_str() {
+                    "@id" => match value {
+                        Value::String(id) => active
+                            .expand_iri(id, true, false)
+                            .map_or(Value::Null, Value::String),
+                        _ => {
+                            return Err(JsonLdError::new(
+                                "invalid @id value",
+                                format!("{} is not a valid @id", value),
+                            ))
+                        }
+                    },
+                    "@type" => {
+                        let expand_type = |t: &Value| {
+                            t.as_str()
+                                .map(|t| {
+                                    type_scoped_context
+                                        .expand_iri(t, true, true)
+                                        .map_or(Value::Null, Value::String)
+                                })
+                                .ok_or_else(|| {
+                                    JsonLdError::new(
+                                        "invalid type value",
+                                        format!("{} is not a valid @type", t),
+                                    )
+                                })
+                        };
+                        let expanded = match value {
+                            Value::Array(types) => Value::Array(
+                                types.iter().map(expand_type).collect::<Result<_, _>>()?,
+                            ),
+                            value => expand_type(value)?,
+                        };
+                        match result.remove("@type") {
+                            Some(previous) => {
+                                let mut types = as_array(previous);
+                                types.extend(as_array(expanded));
+                                Value::Array(types)
+                            }
+                            None => expanded,
+                        }
+                    }
+                    "@graph" => Value::Array(as_array(self.expand_element(
+                        active,
+                        Some("@graph"),
+                        value,
+                        false,
+                    )?)),
+                    "@included" => {
+                        let included = as_array(self.expand_element(active, None, value, false)?);
+                        if included
+                            .iter()
+                            .any(|i| !i.is_object() || is_value_object(i) || is_list_object(i))
+                        {
+                            return Err(JsonLdError::new(
+                                "invalid @included value",
+                                "@included values must be node objects",
+                            ));
+                        }
+                        let mut previous =
+                            result.remove("@included").map_or_else(Vec::new, as_array);
+                        previous.extend(included);
+                        Value::Array(previous)
+                    }
+                    "@value" => {
+                        if input_type != Some("@json") && (value.is_object() || value.is_array()) {
+                            return Err(JsonLdError::new(
+                                "invalid value object value",
+                                format!("{} is not a valid literal value", value),
+                            ));
+                        }
+                        value.clone()
+                    }
+                    "@language" => match value {
+                        Value::String(_) => value.clone(),
+                        _ => {
+                            return Err(JsonLdError::new(
+                                "invalid language-tagged string",
+                                format!("{} is not a valid language tag", value),
+                            ))
+                        }
+                    },
+                    "@direction" => match value.as_str() {
+                        Some("ltr") | Some("rtl") => value.clone(),
+                        _ => {
+                            return Err(JsonLdError::new(
+                                "invalid base direction",
+                                format!("{} is not a valid direction", value),
+                            ))
+                        }
+                    },
+                    "@index" => match value {
+                        Value::String(_) => value.clone(),
+                        _ => {
+                            return Err(JsonLdError::new(
+                                "invalid @index value",
+                                format!("{} is not a valid @index", value),
+                            ))
+                        }
+                    },
+                    "@list" => {
+                        if matches!(active_property, None | Some("@graph")) {
+                            continue;
+                        }
+                        Value::Array(as_array(self.expand_element(
+                            active,
+                            active_property,
+                            value,
+                            false,
+                        )?))
+                    }
+                    "@set" => self.expand_element(active, active_property, value, false)?,
+                    "@reverse" => {
+                        if !value.is_object() {
+                            return Err(JsonLdError::new(
+                                "invalid @reverse value",
+                                "@reverse value must be an object",
+                            ));
+                        }
+                        if let Value::Object(mut expanded) =
+                            self.expand_element(active, Some("@reverse"), value, false)?
+                        {
+                            if let Some(Value::Object(reversed)) = expanded.remove("@reverse") {
+                                for (property, items) in reversed {
+                                    append(result, property, as_array(items));
+                                }
+                            }
+                            for (property, items) in expanded {
+                                let items = as_array(items);
+                                if items
+                                    .iter()
+                                    .any(|i| is_value_object(i) || is_list_object(i))
+                                {
+                                    return Err(JsonLdError::new(
+                                        "invalid reverse property value",
+                                        "Reverse properties values must be node objects",
+                                    ));
+                                }
+                                append(&mut reverse_map, property, items);
+                            }
+                        }
+                        continue;
+                    }
+                    "@nest" => {
+                        nests.push(key);
+                        continue;
+                    }
+                    _ => continue,
+                };
+                if expanded_property == "@id" && expanded_value.is_null() {
+                    continue;
+                }
+                result.insert(expanded_property, expanded_value);
+                continue;
+            }
+
+            let definition = active.term(key);
+            let container = definition.map(|d| d.container).unwrap_or_default();
+            let mut expanded_value = if definition.and_then(|d| d.type_mapping.as_deref())
+                == Some("@json")
+            {
+                let mut value_object = Map::new();
+                value_object.insert("@value".to_owned(), value.clone());
+                value_object.insert("@type".to_owned(), "@json".into());
+                Value::Object(value_object)
+            } else if let (true, Value::Object(language_map)) = (container.language, value) {
+                let mut items = Vec::new();
+                for (language, language_value) in language_map {
+                    for item in as_array(language_value.clone()) {
+                        match item {
+                            Value::Null => (),
+                            Value::String(_) => {
+                                let mut value_object = Map::new();
+                                value_object.insert("@value".to_owned(), item);
+                                if active.expand_iri(language, false, true).as_deref()
+                                    != Some("@none")
+                                {
+                                    value_object
+                                        .insert("@language".to_owned(), language.clone().into());
+                                }
+                                items.push(Value::Object(value_object));
+                            }
+                            _ => {
+                                return Err(JsonLdError::new(
+                                    "invalid language map value",
+                                    format!("{} is not a valid language map value", item),
+                                ))
+                            }
+                        }
+                    }
+                }
+                Value::Array(items)
+            } else if let (true, Value::Object(index_map)) =
+                (container.index || container.id || container.type_, value)
+            {
+                let mut items = Vec::new();
+                for (index, index_value) in index_map {
+                    let expanded_index = active.expand_iri(index, false, true);
+                    let index_value = Value::Array(as_array(index_value.clone()));
+                    let map_context = if container.type_ {
+                        match active.term(index).and_then(|d| d.context.as_ref()) {
+                            Some((context, base_iri)) => Cow::Owned(self.processor.process(
+                                active,
+                                context,
+                                base_iri.as_ref(),
+                            )?),
+                            None => Cow::Borrowed(active),
+                        }
+                    } else {
+                        Cow::Borrowed(active)
+                    };
+                    let index_value =
+                        self.expand_element(&map_context, Some(key), &index_value, true)?;
+                    for item in as_array(index_value) {
+                        let mut item = if container.graph && !is_graph_object(&item) {
+                            graph_object(item)
+                        } else {
+                            item
+                        };
+                        if let (Value::Object(item), false) =
+                            (&mut item, expanded_index.as_deref() == Some("@none"))
+                        {
+                            if container.index {
+                                item.entry("@index").or_insert_with(|| index.clone().into());
+                            } else if container.id {
+                                item.entry("@id").or_insert_with(|| {
+                                    active
+                                        .expand_iri(index, true, false)
+                                        .map_or(Value::Null, Value::String)
+                                });
+                            } else if let Some(expanded_index) = &expanded_index {
+                                let mut types = vec![Value::String(expanded_index.clone())];
+                                types.extend(item.remove("@type").map_or_else(Vec::new, as_array));
+                                item.insert("@type".to_owned(), Value::Array(types));
+                            }
+                        }
+                        items.push(item);
+                    }
+                }
+                Value::Array(items)
+            } else {
+                self.expand_element(active, Some(key), value, false)?
+            };
+            if expanded_value.is_null() {
+                continue;
+            }
+            if container.list && !is_list_object(&expanded_value) {
+                expanded_value = list_object(as_array(expanded_value));
+            }
+            if container.graph && !container.id && !container.index {
+                expanded_value = Value::Array(
+                    as_array(expanded_value)
+                        .into_iter()
+                        .map(graph_object)
+                        .collect(),
+                );
+            }
+            if definition.is_some_and(|d| d.reverse) {
+                let items = as_array(expanded_value);
+                if items
+                    .iter()
+                    .any(|i| is_value_object(i) || is_list_object(i))
+                {
+                    return Err(JsonLdError::new(
+                        "invalid reverse property value",
+                        "Reverse properties values must be node objects",
+                    ));
+                }
+                append(&mut reverse_map, expanded_property, items);
+            } else {
+                append(result, expanded_property, as_array(expanded_value));
+            }
+        }
+
+        if !reverse_map.is_empty() {
+            match result
+                .entry("@reverse")
+                .or_insert_with(|| Value::Object(Map::new()))
+            {
+                Value::Object(reverse) => {
+                    for (property, items) in reverse_map {
+                        append(reverse, property, as_array(items));
+                    }
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        for nest in nests {
+            for nested in as_array(map[nest].clone()) {
+                match nested {
+                    Value::Object(nested)
+                        if !nested.keys().any(|k| {
+                            active.expand_iri(k, false, true).as_deref() == Some("@value")
+                        }) =>
+                    {
+                        self.expand_entries(
+                            result,
+                            active,
+                            type_scoped_context,
+                            active_property,
+                            &nested,
+                            input_type,
+                        )?
+                    }
+                    _ => {
+                        return Err(JsonLdError::new(
+                            "invalid @nest value",
+                            "@nest values must be node objects",
+                        ))
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn property_scoped_context<'b>(
+        &mut self,
+        active: &'b Context,
+        active_property: &str,
+    ) -> Result<Cow<'b, Context>, JsonLdError> {
+        Ok(
+            match active
+                .term(active_property)
+                .and_then(|d| d.context.as_ref())
+            {
+                Some((context, base_iri)) => {
+                    Cow::Owned(self.processor.process(active, context, base_iri.as_ref())?)
+                }
+                None => Cow::Borrowed(active),
+            },
+        )
+    }
+}
+
+/// Implements the [value expansion algorithm](https://www.w3.org/TR/json-ld11-api/#value-expansion)
+fn expand_value(active: &Context, active_property: &str, value: &Value) -> Value {
+    let definition = active.term(active_property);
+    let type_mapping = definition.and_then(|d| d.type_mapping.as_deref());
+    let mut result = Map::new();
+    if let Value::String(id) = value {
+        let vocab = match type_mapping {
+            Some("@id") => Some(false),
+            Some("@vocab") => Some(true),
+            _ => None,
+        };
+        if let Some(vocab) = vocab {
+            result.insert(
+                "@id".to_owned(),
+                active
+                    .expand_iri(id, true, vocab)
+                    .map_or(Value::Null, Value::String),
+            );
+            return Value::Object(result);
+        }
+    }
+    result.insert("@value".to_owned(), value.clone());
+    match type_mapping {
+        Some(type_mapping) if !matches!(type_mapping, "@id" | "@vocab" | "@none") => {
+            result.insert("@type".to_owned(), type_mapping.into());
+        }
+        _ => {
+            if value.is_string() {
+                let language = match definition.and_then(|d| d.language_mapping.as_ref()) {
+                    Some(language) => language.as_ref(),
+                    None => active.default_language.as_ref(),
+                };
+                if let Some(language) = language {
+                    result.insert("@language".to_owned(), language.clone().into());
+                }
+            }
+        }
+    }
+    Value::Object(result)
+}
+
+fn append(map: &mut Map<String, Value>, key: String, values: Vec<Value>) {
+    match map.entry(key).or_insert_with(|| Value::Array(Vec::new())) {
+        Value::Array(previous) => previous.extend(values),
+        previous => {
+            let mut all = vec![previous.take()];
+            all.extend(values);
+            *previous = Value::Array(all);
+        }
+    }
+}
+
+fn list_object(items: Vec<Value>) -> Value {
+    let mut list = Map::new();
+    list.insert("@list".to_owned(), Value::Array(items));
+    Value::Object(list)
+}
+
+fn graph_object(item: Value) -> Value {
+    let mut graph = Map::new();
+    graph.insert("@graph".to_owned(), Value::Array(as_array(item)));
+    Value::Object(graph)
+}
+
+fn is_graph_object(value: &Value) -> bool {
+    value.as_object().is_some_and(|o| {
+        o.contains_key("@graph")
+            && o.keys()
+                .all(|k| matches!(k.as_str(), "@graph" | "@id" | "@index" | "@context"))
+    })
+}
@@ -0,0 +1,250 @@
+use crate::model::vocab::{rdf, xsd};
+use crate::model::*;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+type NodeMap = BTreeMap<String, Map<String, Value>>;
+
+/// Implements the [serialize RDF as JSON-LD algorithm](https://www.w3.org/TR/json-ld11-api/#serialize-rdf-as-json-ld-algorithm)
+///
+/// Quoted triples are not supported and must be filtered out before.
+/// Nodes are sorted by identifier to get a deterministic output.
+pub(crate) fn from_rdf(quads: &[Quad]) -> Vec<Value> {
+    let mut graphs = BTreeMap::<String, NodeMap>::new();
+    graphs.insert("@default".to_owned(), NodeMap::new());
+    // For each graph, the places where a blank node is used as object
+    let mut usages = HashMap::<(String, String), Vec<(String, String)>>::new();
+
+    for quad in quads {
+        let graph_key = match &quad.graph_name {
+            GraphName::NamedNode(node) => node.as_str().to_owned(),
+            GraphName::BlankNode(node) => format!("_:{}", node.as_str()),
+            GraphName::DefaultGraph => "@default".to_owned(),
+        };
+        if !quad.graph_name.is_default_graph() {
+            graphs
+                .get_mut("@default")
+                .unwrap()
+                .entry(graph_key.clone())
+                .or_insert_with(|| node_with_id(&graph_key));
+        }
+        let subject = match &quad.subject {
+            Subject::NamedNode(node) => node.as_str().to_owned(),
+            Subject::BlankNode(node) => format!("_:{}", node.as_str()),
+            Subject::Triple(_) => continue,
+        };
+        let object = match object_to_value(&quad.object) {
+            Some(object) => object,
+            None => continue,
+        };
+        let graph = graphs.entry(graph_key.clone()).or_default();
+        if let Some(id) = object.get("@id").and_then(Value::as_str) {
+            graph
+                .entry(id.to_owned())
+                .or_insert_with(|| node_with_id(id));
+            if id.starts_with("_:") || id == rdf::NIL.as_str() {
+                usages
+                    .entry((graph_key.clone(), id.to_owned()))
+                    .or_default()
+                    .push((subject.clone(), quad.predicate.as_str().to_owned()));
+            }
+        }
+        let node = graph
+            .entry(subject.clone())
+            .or_insert_with(|| node_with_id(&subject));
+        if quad.predicate.as_ref() == rdf::TYPE && !quad.object.is_literal() {
+            if let Some(id) = object.get("@id").cloned() {
+                add_value(node, "@type", id);
+                continue;
+            }
+        }
+        add_value(node, quad.predicate.as_str(), Value::Object(object));
+    }
+
+    for (graph_key, graph) in &mut graphs {
+        convert_lists(graph_key, graph, &usages);
+    }
+
+    let mut default_graph = graphs.remove("@default").unwrap_or_default();
+    for (graph_key, graph) in graphs {
+        let node = default_graph
+            .entry(graph_key.clone())
+            .or_insert_with(|| node_with_id(&graph_key));
+        node.insert(
+            "@graph".to_owned(),
+            Value::Array(
+                graph
+                    .into_iter()
+                    .filter(|(_, n)| n.len() > 1)
+                    .map(|(_, n)| Value::Object(n))
+                    .collect(),
+            ),
+        );
+    }
+    default_graph
+        .into_iter()
+        .filter(|(_, n)| n.len() > 1)
+        .map(|(_, n)| Value::Object(n))
+        .collect()
+}
+
+fn node_with_id(id: &str) -> Map<String, Value> {
+    let mut node = Map::new();
+    node.insert("@id".to_owned(), id.into());
+    node
+}
+
+fn add_value(node: &mut Map<String, Value>, property: &str, value: Value) {
+    if let Value::Array(values) = node
+        .entry(property)
+        .or_insert_with(|| Value::Array(Vec::new()))
+    {
+        if !values.contains(&value) {
+            values.push(value);
+        }
+    }
+}
+
+fn object_to_value(term: &Term) -> Option<Map<String, Value>> {
+    let mut value = Map::new();
+    match term {
+        Term::NamedNode(node) => {
+            value.insert("@id".to_owned(), node.as_str().into());
+        }
+        Term::BlankNode(node) => {
+            value.insert("@id".to_owned(), format!("_:{}", node.as_str()).into());
+        }
+        Term::Literal(literal) => {
+            let datatype = literal.datatype();
+            if let Some(language) = literal.language() {
+                value.insert("@value".to_owned(), literal.value().into());
+                value.insert("@language".to_owned(), language.into());
+            } else if datatype == rdf::JSON {
+                match serde_json::from_str::<Value>(literal.value()) {
+                    Ok(json) => {
+                        value.insert("@value".to_owned(), json);
+                        value.insert("@type".to_owned(), "@json".into());
+                    }
+                    Err(_) => {
+                        value.insert("@value".to_owned(), literal.value().into());
+                        value.insert("@type".to_owned(), datatype.as_str().into());
+                    }
+                }
+            } else {
+                value.insert("@value".to_owned(), literal.value().into());
+                if datatype != xsd::STRING {
+                    value.insert("@type".to_owned(), datatype.as_str().into());
+                }
+            }
+        }
+        Term::Triple(_) => return None,
+    }
+    Some(value)
+}
+
+/// Replaces the well-formed `rdf:first`/`rdf:rest` chains by `@list` objects
+fn convert_lists(
+    graph_key: &str,
+    graph: &mut NodeMap,
+    usages: &HashMap<(String, String), Vec<(String, String)>>,
+) {
+    let usage = |id: &str| usages.get(&(graph_key.to_owned(), id.to_owned()));
+
+    // A list cell is a blank node used once as object, with only one rdf:first and one rdf:rest values
+    let is_cell = |id: &str, graph: &NodeMap| {
+        id.starts_with("_:")
+            && usage(id).is_some_and(|u| u.len() == 1)
+            && graph.get(id).is_some_and(|node| {
+                node.len() == 3
+                    && [rdf::FIRST.as_str(), rdf::REST.as_str()].iter().all(|p| {
+                        node.get(*p)
+                            .and_then(Value::as_array)
+                            .is_some_and(|v| v.len() == 1)
+                    })
+            })
+    };
+
+    // Finds the list heads: cells not used by another cell
+    // Lists nested in other lists are kept as plain nodes
+    let mut heads = Vec::new();
+    for id in graph.keys() {
+        if !is_cell(id, graph) {
+            continue;
+        }
+        let (subject, _) = &usage(id).unwrap()[0];
+        if is_cell(subject, graph) {
+            continue;
+        }
+        heads.push(id.clone());
+    }
+
+    let mut removed = HashSet::new();
+    for head in heads {
+        let mut items = Vec::new();
+        let mut cells = Vec::new();
+        let mut current = head.clone();
+        let is_valid = loop {
+            if !is_cell(&current, graph) || cells.contains(&current) {
+                break false;
+            }
+            let node = &graph[&current];
+            items.push(node[rdf::FIRST.as_str()][0].clone());
+            cells.push(current.clone());
+            match node[rdf::REST.as_str()][0]
+                .get("@id")
+                .and_then(Value::as_str)
+            {
+                Some(next) if next == rdf::NIL.as_str() => break true,
+                Some(next) => current = next.to_owned(),
+                None => break false,
+            }
+        };
+        if !is_valid {
+            continue;
+        }
+        let (subject, predicate) = usage(&head).unwrap()[0].clone();
+        if let Some(Value::Array(values)) = graph
+            .get_mut(&subject)
+            .and_then(|node| node.get_mut(&predicate))
+        {
+            for value in values {
+                if value.get("@id").and_then(Value::as_str) == Some(&head) {
+                    *value = list_object(items.clone());
+                }
+            }
+        }
+        removed.extend(cells);
+    }
+    for id in removed {
+        graph.remove(&id);
+    }
+
+    // rdf:nil used as object is the empty list
+    for (subject, predicate) in usage(rdf::NIL.as_str()).into_iter().flatten() {
+        if predicate == rdf::REST.as_str() || predicate == rdf::FIRST.as_str() {
+            continue;
+        }
+        if let Some(Value::Array(values)) = graph
+            .get_mut(subject)
+            .and_then(|node| node.get_mut(predicate))
+        {
+            for value in values {
+                if value.get("@id").and_then(Value::as_str) == Some(rdf::NIL.as_str()) {
+                    *value = list_object(Vec::new());
+                }
+            }
+        }
+    }
+    if graph
+        .get(rdf::NIL.as_str())
+        .is_some_and(|node| node.len() == 1)
+    {
+        graph.remove(rdf::NIL.as_str());
+    }
+}
+
+fn list_object(items: Vec<Value>) -> Value {
+    let mut list = Map::new();
+    list.insert("@list".to_owned(), Value::Array(items));
+    Value::Object(list)
+}
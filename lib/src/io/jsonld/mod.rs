@@ -0,0 +1,556 @@
+//! Implementation of the [JSON-LD 1.1](https://www.w3.org/TR/json-ld11/) format
+//!
+//! The parser follows the [expansion](https://www.w3.org/TR/json-ld11-api/#expansion-algorithm)
+//! and [deserialize JSON-LD to RDF](https://www.w3.org/TR/json-ld11-api/#deserialize-json-ld-to-rdf-algorithm) algorithms.
+//! The serializer follows the [serialize RDF as JSON-LD](https://www.w3.org/TR/json-ld11-api/#serialize-rdf-as-json-ld-algorithm)
+//! algorithm and then, if a context is provided, the [compaction](https://www.w3.org/TR/json-ld11-api/#compaction-algorithm) algorithm.
+
+mod compaction;
+mod context;
+mod expansion;
+mod from_rdf;
+mod to_rdf;
+
+use crate::error::{invalid_data_error, invalid_input_error};
+use crate::model::*;
+use oxiri::Iri;
+use serde_json::Value;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::io::{Read, Write};
+
+pub(crate) use self::context::Context;
+
+/// Loads the remote documents referred to by [JSON-LD](https://www.w3.org/TR/json-ld11/) contexts.
+///
+/// Oxigraph never fetches remote contexts from the network by itself.
+/// A loader must be provided to resolve them, for example from a local cache.
+///
+/// It is implemented for closures:
+/// ```
+/// use oxigraph::io::{DatasetFormat, DatasetParser};
+/// use std::io::{Cursor, Error, ErrorKind};
+///
+/// let file = r#"{"@context": "http://example.com/context.jsonld", "@id": "http://example.com/s", "p": "o"}"#;
+///
+/// let parser = DatasetParser::from_format(DatasetFormat::JsonLd).with_document_loader(|iri: &str| {
+///     if iri == "http://example.com/context.jsonld" {
+///         Ok(r#"{"@context": {"p": "http://example.com/p"}}"#.to_owned())
+///     } else {
+///         Err(Error::new(ErrorKind::NotFound, format!("{} not found", iri)))
+///     }
+/// });
+/// let quads = parser.read_quads(Cursor::new(file))?.collect::<Result<Vec<_>,_>>()?;
+///
+///assert_eq!(quads.len(), 1);
+///assert_eq!(quads[0].predicate.as_str(), "http://example.com/p");
+/// # std::io::Result::Ok(())
+/// ```
+pub trait DocumentLoader: Send + Sync {
+    /// Returns the content of the JSON document identified by the given IRI
+    fn load_document(&self, iri: &str) -> Result<String, io::Error>;
+}
+
+impl<F: Fn(&str) -> Result<String, io::Error> + Send + Sync> DocumentLoader for F {
+    fn load_document(&self, iri: &str) -> Result<String, io::Error> {
+        self(iri)
+    }
+}
+
+/// The default [`DocumentLoader`] that does not allow any remote context
+pub(crate) struct NoDocumentLoader;
+
+impl DocumentLoader for NoDocumentLoader {
+    fn load_document(&self, iri: &str) -> Result<String, io::Error> {
+        Err(invalid_input_error(format!(
+            "No document loader has been provided to load the remote context {}",
+            iri
+        )))
+    }
+}
+
+/// Parses a JSON-LD document into quads
+pub(crate) fn read_json_ld(
+    reader: impl Read,
+    base_iri: Option<&Iri<String>>,
+    loader: &dyn DocumentLoader,
+) -> Result<Vec<Quad>, io::Error> {
+    let document: Value = serde_json::from_reader(reader).map_err(invalid_data_error)?;
+    let expanded =
+        expansion::Expander::new(loader, base_iri).expand(&document, &Context::new(base_iri))?;
+    Ok(to_rdf::to_rdf(&expanded)?)
+}
+
+/// Processes a context given to the serializer
+///
+/// The context might be a JSON object with a `@context` key or directly the context value.
+pub(crate) fn parse_serialization_context(
+    context: &str,
+    loader: &dyn DocumentLoader,
+) -> Result<(Value, Context), io::Error> {
+    let mut context: Value = serde_json::from_str(context).map_err(invalid_input_error)?;
+    if let Value::Object(map) = &mut context {
+        if let Some(inner) = map.remove("@context") {
+            context = inner;
+        }
+    }
+    let processed = context::ContextProcessor::new(loader)
+        .process(&Context::new(None), &context, None)
+        .map_err(invalid_input_error)?;
+    Ok((context, processed))
+}
+
+/// Buffers quads and writes them as a JSON-LD document when finished
+pub(crate) struct JsonLdWriter<W: Write> {
+    sink: W,
+    context: Option<(Value, Context)>,
+    quads: Vec<Quad>,
+}
+
+impl<W: Write> JsonLdWriter<W> {
+    pub fn new(sink: W, context: Option<(Value, Context)>) -> Self {
+        Self {
+            sink,
+            context,
+            quads: Vec::new(),
+        }
+    }
+
+    pub fn write(&mut self, quad: QuadRef<'_>) -> Result<(), io::Error> {
+        if quad.subject.is_triple() || quad.object.is_triple() {
+            return Err(invalid_input_error(
+                "JSON-LD does not support RDF-star quoted triples",
+            ));
+        }
+        self.quads.push(quad.into_owned());
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<W, io::Error> {
+        let expanded = from_rdf::from_rdf(&self.quads);
+        let document = if let Some((context_value, context)) = &self.context {
+            compaction::compact(context, context_value, expanded)
+        } else {
+            Value::Array(expanded)
+        };
+        serde_json::to_writer(&mut self.sink, &document)?;
+        self.sink.flush()?;
+        Ok(self.sink)
+    }
+}
+
+/// An error raised by one of the JSON-LD algorithms
+///
+/// The code is one of the [JSON-LD error codes](https://www.w3.org/TR/json-ld11-api/#jsonlderrorcode).
+#[derive(Debug)]
+pub(crate) struct JsonLdError {
+    code: &'static str,
+    message: String,
+}
+
+impl JsonLdError {
+    pub fn new(code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for JsonLdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.message, self.code)
+    }
+}
+
+impl Error for JsonLdError {}
+
+impl From<JsonLdError> for io::Error {
+    fn from(error: JsonLdError) -> Self {
+        invalid_data_error(error)
+    }
+}
+
+const KEYWORDS: [&str; 23] = [
+    "@base",
+    "@container",
+    "@context",
+    "@direction",
+    "@graph",
+    "@id",
+    "@import",
+    "@included",
+    "@index",
+    "@json",
+    "@language",
+    "@list",
+    "@nest",
+    "@none",
+    "@prefix",
+    "@propagate",
+    "@protected",
+    "@reverse",
+    "@set",
+    "@type",
+    "@value",
+    "@version",
+    "@vocab",
+];
+
+fn is_keyword(value: &str) -> bool {
+    KEYWORDS.contains(&value)
+}
+
+/// Values of the form `@` followed by letters are reserved for future keywords and ignored
+fn looks_like_keyword(value: &str) -> bool {
+    value.len() > 1 && value.starts_with('@') && value[1..].chars().all(|c| c.is_ascii_alphabetic())
+}
+
+/// Checks if the value starts with an IRI scheme
+fn is_absolute_iri(value: &str) -> bool {
+    if let Some(end) = value.find(':') {
+        let mut scheme = value[..end].chars();
+        scheme.next().is_some_and(|c| c.is_ascii_alphabetic())
+            && scheme.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    } else {
+        false
+    }
+}
+
+fn is_blank_node_id(value: &str) -> bool {
+    value.starts_with("_:")
+}
+
+fn as_array(value: Value) -> Vec<Value> {
+    match value {
+        Value::Array(values) => values,
+        Value::Null => Vec::new(),
+        value => vec![value],
+    }
+}
+
+fn is_list_object(value: &Value) -> bool {
+    value.as_object().is_some_and(|o| o.contains_key("@list"))
+}
+
+fn is_value_object(value: &Value) -> bool {
+    value.as_object().is_some_and(|o| o.contains_key("@value"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::{DatasetFormat, DatasetParser, DatasetSerializer, GraphFormat, GraphParser};
+    use crate::model::vocab::xsd;
+    use std::io::Cursor;
+
+    fn parse(json_ld: &str) -> Result<Vec<Quad>, io::Error> {
+        DatasetParser::from_format(DatasetFormat::JsonLd)
+            .with_base_iri("http://example.com/doc")
+            .unwrap()
+            .read_quads(Cursor::new(json_ld))?
+            .collect()
+    }
+
+    fn assert_isomorphic_to_turtle(json_ld: &str, turtle: &str) {
+        let mut actual = parse(json_ld)
+            .unwrap()
+            .into_iter()
+            .map(|q| q.to_string())
+            .collect::<Vec<_>>();
+        let mut expected = GraphParser::from_format(GraphFormat::Turtle)
+            .read_triples(Cursor::new(turtle))
+            .unwrap()
+            .map(|t| t.unwrap().in_graph(GraphName::DefaultGraph).to_string())
+            .collect::<Vec<_>>();
+        normalize_blank_nodes(&mut actual);
+        normalize_blank_nodes(&mut expected);
+        assert_eq!(actual, expected);
+    }
+
+    /// Blank node identifiers are not stable so we replace them all by the same one
+    fn normalize_blank_nodes(quads: &mut [String]) {
+        for quad in quads.iter_mut() {
+            *quad = quad
+                .split(' ')
+                .map(|t| if t.starts_with("_:") { "_:b" } else { t })
+                .collect::<Vec<_>>()
+                .join(" ");
+        }
+        quads.sort();
+    }
+
+    fn serialize(quads: &[Quad], context: Option<&str>) -> String {
+        let mut serializer = DatasetSerializer::from_format(DatasetFormat::JsonLd);
+        if let Some(context) = context {
+            serializer = serializer.with_json_ld_context(context);
+        }
+        let mut buffer = Vec::new();
+        let mut writer = serializer.quad_writer(&mut buffer).unwrap();
+        for quad in quads {
+            writer.write(quad).unwrap();
+        }
+        writer.finish().unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn test_to_rdf() {
+        assert_isomorphic_to_turtle(
+            r#"{
+                "@context": {
+                    "ex": "http://example.com/",
+                    "name": "ex:name",
+                    "knows": {"@id": "ex:knows", "@type": "@id"},
+                    "age": {"@id": "ex:age", "@type": "http://www.w3.org/2001/XMLSchema#integer"},
+                    "label": {"@id": "ex:label", "@language": "en"},
+                    "list": {"@id": "ex:list", "@container": "@list"}
+                },
+                "@id": "ex:alice",
+                "@type": "ex:Person",
+                "name": "Alice",
+                "knows": "bob",
+                "age": "42",
+                "label": "Alice in wonderland",
+                "list": [1, 2.5, true],
+                "ex:nested": {"name": "Carol"}
+            }"#,
+            r#"
+            @prefix ex: <http://example.com/> .
+            @prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+            ex:alice a ex:Person ;
+                ex:name "Alice" ;
+                ex:knows <http://example.com/bob> ;
+                ex:age "42"^^xsd:integer ;
+                ex:label "Alice in wonderland"@en ;
+                ex:list ( 1 "2.5E0"^^xsd:double true ) ;
+                ex:nested [ ex:name "Carol" ] .
+            "#,
+        );
+    }
+
+    #[test]
+    fn test_to_rdf_keywords() {
+        assert_isomorphic_to_turtle(
+            r#"{
+                "@context": {
+                    "@vocab": "http://example.com/",
+                    "@base": "http://example.com/base/",
+                    "id": "@id",
+                    "isKnownBy": {"@reverse": "knows"},
+                    "labels": {"@id": "label", "@container": "@language"},
+                    "data": {"@type": "@json"}
+                },
+                "id": "alice",
+                "isKnownBy": {"@id": "bob"},
+                "labels": {"en": "Alice", "fr": ["Alice", "Alicia"]},
+                "data": {"b": [1, 2], "a": null},
+                "@reverse": {"likes": {"@id": "carol"}},
+                "relative": {"@id": "../other"},
+                "unmapped:foo": "not a property",
+                "http://example.com/ignored-value": {"@value": null}
+            }"#,
+            r#"
+            @prefix ex: <http://example.com/> .
+            <http://example.com/base/bob> ex:knows <http://example.com/base/alice> .
+            <http://example.com/base/carol> ex:likes <http://example.com/base/alice> .
+            <http://example.com/base/alice> ex:label "Alice"@en, "Alice"@fr, "Alicia"@fr ;
+                ex:data "{\"a\":null,\"b\":[1,2]}"^^<http://www.w3.org/1999/02/22-rdf-syntax-ns#JSON> ;
+                ex:relative <http://example.com/other> ;
+                <unmapped:foo> "not a property" .
+            "#,
+        );
+    }
+
+    #[test]
+    fn test_to_rdf_scoped_contexts() {
+        assert_isomorphic_to_turtle(
+            r#"{
+                "@context": {
+                    "@vocab": "http://example.com/",
+                    "Person": {"@context": {"name": "http://xmlns.com/foaf/0.1/name"}},
+                    "address": {"@context": {"@vocab": "http://schema.org/"}}
+                },
+                "@id": "http://example.com/alice",
+                "@type": "Person",
+                "name": "Alice",
+                "address": {"street": "Main street", "friend": {"@type": "Person", "name": "Bob"}}
+            }"#,
+            r#"
+            @prefix ex: <http://example.com/> .
+            @prefix schema: <http://schema.org/> .
+            ex:alice a ex:Person ;
+                <http://xmlns.com/foaf/0.1/name> "Alice" ;
+                ex:address [
+                    schema:street "Main street" ;
+                    schema:friend [ a ex:Person ; <http://xmlns.com/foaf/0.1/name> "Bob" ]
+                ] .
+            "#,
+        );
+    }
+
+    #[test]
+    fn test_to_rdf_named_graphs() {
+        let quads = parse(
+            r#"{
+                "@context": {"@vocab": "http://example.com/"},
+                "@graph": [
+                    {"@id": "http://example.com/g", "@graph": {"@id": "http://example.com/s", "p": "o"}},
+                    {"@id": "http://example.com/s", "p": "default"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let mut quads = quads.into_iter().map(|q| q.to_string()).collect::<Vec<_>>();
+        quads.sort();
+        assert_eq!(
+            quads,
+            vec![
+                "<http://example.com/s> <http://example.com/p> \"default\"",
+                "<http://example.com/s> <http://example.com/p> \"o\" <http://example.com/g>"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(parse(r#"{"@context": {"@id": "http://example.com/"}}"#).is_err());
+        assert!(parse(r#"{"@context": {"p": {"@type": "foo"}}}"#).is_err());
+        assert!(
+            parse(r#"{"http://example.com/p": {"@value": "a", "@foo": "b", "@id": "c"}}"#).is_err()
+        );
+        assert!(parse(r#"{"@context": "http://example.com/context"}"#).is_err());
+        assert!(parse("{").is_err());
+    }
+
+    #[test]
+    fn test_remote_context() {
+        let loader = |iri: &str| match iri {
+            "http://example.com/context" => {
+                Ok(r#"{"@context": ["other", {"b": "http://example.com/b"}]}"#.to_owned())
+            }
+            "http://example.com/other" => {
+                Ok(r#"{"@context": {"a": "http://example.com/a"}}"#.to_owned())
+            }
+            "http://example.com/loop" => Ok(r#"{"@context": "loop"}"#.to_owned()),
+            _ => Err(io::Error::new(io::ErrorKind::NotFound, iri.to_owned())),
+        };
+        let quads = DatasetParser::from_format(DatasetFormat::JsonLd)
+            .with_document_loader(loader)
+            .read_quads(Cursor::new(
+                r#"{"@context": "http://example.com/context", "a": "1", "b": "2"}"#,
+            ))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(quads.len(), 2);
+        assert!(DatasetParser::from_format(DatasetFormat::JsonLd)
+            .with_document_loader(loader)
+            .read_quads(Cursor::new(r#"{"@context": "http://example.com/loop"}"#))
+            .is_err());
+    }
+
+    #[test]
+    fn test_serialization_round_trip() {
+        let trig = r#"
+            @prefix ex: <http://example.com/> .
+            @prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+            ex:alice a ex:Person ;
+                ex:name "Alice", "Alice"@en ;
+                ex:age 42 ;
+                ex:knows _:bob ;
+                ex:list ( 1 2 ex:alice ) ;
+                ex:empty () .
+            _:bob ex:name "Bob" .
+            ex:g { ex:alice ex:in ex:g }
+        "#;
+        let quads = DatasetParser::from_format(DatasetFormat::TriG)
+            .read_quads(Cursor::new(trig))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        for context in &[
+            None,
+            Some(
+                r#"{"@context": {
+                    "ex": "http://example.com/",
+                    "name": "ex:name",
+                    "nameEn": {"@id": "ex:name", "@language": "en"},
+                    "knows": {"@id": "ex:knows", "@type": "@id"},
+                    "list": {"@id": "ex:list", "@container": "@list"}
+                }}"#,
+            ),
+            Some(r#"{"@vocab": "http://example.com/", "@base": "http://example.com/"}"#),
+        ] {
+            let json_ld = serialize(&quads, *context);
+            let mut actual = DatasetParser::from_format(DatasetFormat::JsonLd)
+                .read_quads(Cursor::new(json_ld.as_str()))
+                .unwrap()
+                .map(|q| q.unwrap().to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(actual.len(), quads.len(), "{}", json_ld);
+            let mut expected = quads.iter().map(|q| q.to_string()).collect::<Vec<_>>();
+            normalize_blank_nodes(&mut actual);
+            normalize_blank_nodes(&mut expected);
+            assert_eq!(actual, expected, "{}", json_ld);
+        }
+    }
+
+    #[test]
+    fn test_compaction() {
+        let quads = vec![
+            Quad::new(
+                NamedNode::new_unchecked("http://example.com/alice"),
+                NamedNode::new_unchecked("http://example.com/knows"),
+                NamedNode::new_unchecked("http://example.com/bob"),
+                GraphName::DefaultGraph,
+            ),
+            Quad::new(
+                NamedNode::new_unchecked("http://example.com/alice"),
+                NamedNode::new_unchecked("http://example.com/name"),
+                Literal::new_language_tagged_literal("Alice", "en").unwrap(),
+                GraphName::DefaultGraph,
+            ),
+            Quad::new(
+                NamedNode::new_unchecked("http://example.com/alice"),
+                NamedNode::new_unchecked("http://xmlns.com/foaf/0.1/age"),
+                Literal::new_typed_literal("42", xsd::INTEGER),
+                GraphName::DefaultGraph,
+            ),
+        ];
+        assert_eq!(
+            serialize(
+                &quads,
+                Some(
+                    r#"{"@context": {"@vocab": "http://example.com/", "@language": "en", "knows": {"@type": "@id"}, "foaf": "http://xmlns.com/foaf/0.1/"}}"#
+                )
+            ),
+            r#"{"@context":{"@language":"en","@vocab":"http://example.com/","foaf":"http://xmlns.com/foaf/0.1/","knows":{"@type":"@id"}},"@id":"http://example.com/alice","foaf:age":{"@type":"http://www.w3.org/2001/XMLSchema#integer","@value":"42"},"knows":"http://example.com/bob","name":"Alice"}"#
+        );
+        assert_eq!(
+            serialize(&quads[..1], None),
+            r#"[{"@id":"http://example.com/alice","http://example.com/knows":[{"@id":"http://example.com/bob"}]}]"#
+        );
+    }
+
+    #[test]
+    fn test_serialization_quoted_triple() {
+        let mut writer = DatasetSerializer::from_format(DatasetFormat::JsonLd)
+            .quad_writer(Vec::new())
+            .unwrap();
+        let triple = Triple::new(
+            NamedNode::new_unchecked("http://example.com/s"),
+            NamedNode::new_unchecked("http://example.com/p"),
+            NamedNode::new_unchecked("http://example.com/o"),
+        );
+        assert!(writer
+            .write(&Quad::new(
+                triple.clone(),
+                NamedNode::new_unchecked("http://example.com/p"),
+                triple,
+                GraphName::DefaultGraph
+            ))
+            .is_err());
+    }
+}
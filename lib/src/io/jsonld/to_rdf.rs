@@ -0,0 +1,259 @@
+use crate::io::jsonld::{is_absolute_iri, is_blank_node_id, JsonLdError};
+use crate::model::vocab::{rdf, xsd};
+use crate::model::*;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Implements the [deserialize JSON-LD to RDF algorithm](https://www.w3.org/TR/json-ld11-api/#deserialize-json-ld-to-rdf-algorithm)
+/// on an expanded document
+///
+/// Triples with relative IRIs or invalid literals are dropped as the specification requires.
+pub(crate) fn to_rdf(expanded: &[Value]) -> Result<Vec<Quad>, JsonLdError> {
+    let mut generator = RdfGenerator::default();
+    for node in expanded {
+        if let Value::Object(node) = node {
+            generator.node(node, &GraphName::DefaultGraph)?;
+        }
+    }
+    Ok(generator.quads)
+}
+
+#[derive(Default)]
+struct RdfGenerator {
+    bnodes: HashMap<String, BlankNode>,
+    quads: Vec<Quad>,
+}
+
+impl RdfGenerator {
+    fn node(
+        &mut self,
+        node: &Map<String, Value>,
+        graph_name: &GraphName,
+    ) -> Result<Option<Subject>, JsonLdError> {
+        let subject = match node.get("@id") {
+            Some(Value::String(id)) => self.named_or_blank_node(id).map(Subject::from),
+            None | Some(Value::Null) => Some(BlankNode::default().into()),
+            Some(id) => {
+                return Err(JsonLdError::new(
+                    "invalid @id value",
+                    format!("{} is not a valid @id", id),
+                ))
+            }
+        };
+        for (property, values) in node {
+            match property.as_str() {
+                "@id" | "@index" => (),
+                "@type" => {
+                    for value in values.as_array().into_iter().flatten() {
+                        let object = value.as_str().and_then(|t| self.named_or_blank_node(t));
+                        if let (Some(subject), Some(object)) = (&subject, object) {
+                            self.quads.push(Quad::new(
+                                subject.clone(),
+                                rdf::TYPE,
+                                object,
+                                graph_name.clone(),
+                            ));
+                        }
+                    }
+                }
+                "@graph" => {
+                    let inner_graph_name = match &subject {
+                        Some(Subject::NamedNode(node)) => node.clone().into(),
+                        Some(Subject::BlankNode(node)) => node.clone().into(),
+                        _ => continue,
+                    };
+                    for value in values.as_array().into_iter().flatten() {
+                        if let Value::Object(inner) = value {
+                            if !inner.contains_key("@value") && !inner.contains_key("@list") {
+                                self.node(inner, &inner_graph_name)?;
+                            }
+                        }
+                    }
+                }
+                "@included" => {
+                    for value in values.as_array().into_iter().flatten() {
+                        if let Value::Object(included) = value {
+                            self.node(included, graph_name)?;
+                        }
+                    }
+                }
+                "@reverse" => {
+                    for (property, values) in values.as_object().into_iter().flatten() {
+                        let predicate = self.predicate(property);
+                        for value in values.as_array().into_iter().flatten() {
+                            if let Value::Object(value) = value {
+                                let object = self.node(value, graph_name)?;
+                                if let (Some(object), Some(predicate), Some(subject)) =
+                                    (object, &predicate, &subject)
+                                {
+                                    self.quads.push(Quad::new(
+                                        object,
+                                        predicate.clone(),
+                                        subject.clone(),
+                                        graph_name.clone(),
+                                    ));
+                                }
+                            }
+                        }
+                    }
+                }
+                property => {
+                    let predicate = self.predicate(property);
+                    for value in values.as_array().into_iter().flatten() {
+                        let object = self.object(value, graph_name)?;
+                        if let (Some(object), Some(predicate), Some(subject)) =
+                            (object, &predicate, &subject)
+                        {
+                            self.quads.push(Quad::new(
+                                subject.clone(),
+                                predicate.clone(),
+                                object,
+                                graph_name.clone(),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(subject)
+    }
+
+    fn object(
+        &mut self,
+        value: &Value,
+        graph_name: &GraphName,
+    ) -> Result<Option<Term>, JsonLdError> {
+        let value = if let Value::Object(value) = value {
+            value
+        } else {
+            return Ok(None);
+        };
+        Ok(if value.contains_key("@value") {
+            literal(value).map(Term::from)
+        } else if let Some(list) = value.get("@list") {
+            self.list(list.as_array().map_or(&[], Vec::as_slice), graph_name)?
+        } else {
+            self.node(value, graph_name)?.map(Term::from)
+        })
+    }
+
+    fn list(
+        &mut self,
+        items: &[Value],
+        graph_name: &GraphName,
+    ) -> Result<Option<Term>, JsonLdError> {
+        let cells = items
+            .iter()
+            .map(|_| BlankNode::default())
+            .collect::<Vec<_>>();
+        for (i, item) in items.iter().enumerate() {
+            if let Some(object) = self.object(item, graph_name)? {
+                self.quads.push(Quad::new(
+                    cells[i].clone(),
+                    rdf::FIRST,
+                    object,
+                    graph_name.clone(),
+                ));
+            }
+            let rest: Term = cells
+                .get(i + 1)
+                .map_or_else(|| rdf::NIL.into(), |cell| cell.clone().into());
+            self.quads.push(Quad::new(
+                cells[i].clone(),
+                rdf::REST,
+                rest,
+                graph_name.clone(),
+            ));
+        }
+        Ok(Some(
+            cells
+                .into_iter()
+                .next()
+                .map_or_else(|| rdf::NIL.into(), Term::from),
+        ))
+    }
+
+    fn named_or_blank_node(&mut self, id: &str) -> Option<NamedOrBlankNode> {
+        if is_blank_node_id(id) {
+            Some(self.blank_node(id).into())
+        } else if is_absolute_iri(id) {
+            NamedNode::new(id).ok().map(NamedOrBlankNode::from)
+        } else {
+            None
+        }
+    }
+
+    fn blank_node(&mut self, id: &str) -> BlankNode {
+        self.bnodes.entry(id.to_owned()).or_default().clone()
+    }
+
+    /// Properties that are blank nodes would require generalized RDF so they are dropped
+    fn predicate(&self, property: &str) -> Option<NamedNode> {
+        if is_absolute_iri(property) {
+            NamedNode::new(property).ok()
+        } else {
+            None
+        }
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn literal(value: &Map<String, Value>) -> Option<Literal> {
+    let datatype = value.get("@type").and_then(Value::as_str);
+    let language = value.get("@language").and_then(Value::as_str);
+    let literal = &value["@value"];
+    if datatype == Some("@json") {
+        return Some(Literal::new_typed_literal(literal.to_string(), rdf::JSON));
+    }
+    let datatype = match datatype {
+        Some(datatype) => Some(NamedNode::new(datatype).ok()?),
+        None => None,
+    };
+    Some(match literal {
+        Value::Bool(value) => Literal::new_typed_literal(
+            value.to_string(),
+            datatype.unwrap_or_else(|| xsd::BOOLEAN.into()),
+        ),
+        Value::Number(number) => {
+            let is_double = datatype.as_ref().is_some_and(|d| d.as_ref() == xsd::DOUBLE);
+            let integer = number.as_i64().map(i128::from).or_else(|| {
+                number.as_u64().map(i128::from).or_else(|| {
+                    number
+                        .as_f64()
+                        .filter(|f| f.fract() == 0. && f.abs() < 1e21)
+                        .map(|f| f as i128)
+                })
+            });
+            match integer {
+                Some(integer) if !is_double => Literal::new_typed_literal(
+                    integer.to_string(),
+                    datatype.unwrap_or_else(|| xsd::INTEGER.into()),
+                ),
+                _ => Literal::new_typed_literal(
+                    canonical_double(number.as_f64()?),
+                    datatype.unwrap_or_else(|| xsd::DOUBLE.into()),
+                ),
+            }
+        }
+        Value::String(value) => {
+            if let Some(language) = language {
+                Literal::new_language_tagged_literal(value.clone(), language).ok()?
+            } else if let Some(datatype) = datatype {
+                Literal::new_typed_literal(value.clone(), datatype)
+            } else {
+                Literal::new_simple_literal(value.clone())
+            }
+        }
+        _ => return None,
+    })
+}
+
+/// Serializes a double using the XSD canonical form (e.g. `1.5E1`)
+fn canonical_double(value: f64) -> String {
+    let value = format!("{:E}", value);
+    if value.contains('.') || !value.contains('E') {
+        value
+    } else {
+        value.replacen('E', ".0E", 1)
+    }
+}
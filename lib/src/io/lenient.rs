@@ -0,0 +1,742 @@
+//! Error recovery for the Turtle and TriG parsers.
+//!
+//! The input is split into statements using a lightweight tokenizer that only tracks strings, IRIs,
+//! comments and TriG graph blocks. Batches of statements are then given to fresh Rio parsers
+//! preceded by the prefix and base IRI declarations seen so far.
+//! The prefix and base IRI declarations are read from the statements that are successfully parsed.
+//! If a batch fails or contains rejected literals, its statements are parsed one by one in order to skip only the invalid ones.
+
+use crate::io::error::{SyntaxError, TextPosition};
+use crate::io::read::RioMapper;
+use crate::model::*;
+use oxiri::Iri;
+use rio_api::parser::{QuadsParser, TriplesParser};
+use rio_turtle::{TriGParser, TurtleError, TurtleParser};
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::io;
+use std::io::{BufRead, Cursor, Write};
+
+/// Size in bytes above which a batch of statements is parsed
+const BATCH_SIZE: usize = 64 * 1024;
+
+/// A Turtle or TriG parser that skips the invalid statements
+pub(crate) struct LenientTurtleParser<R: BufRead> {
+    splitter: StatementSplitter<R>,
+    base_iri: Option<Iri<String>>,
+    prefixes: HashMap<String, String>,
+    literal_validator: LiteralValidator,
+    is_end: bool,
+}
+
+impl<R: BufRead> LenientTurtleParser<R> {
+    pub fn new(
+        reader: R,
+        base_iri: Option<Iri<String>>,
+        with_graphs: bool,
+        literal_validator: LiteralValidator,
+    ) -> Self {
+        Self {
+            splitter: StatementSplitter {
+                reader,
+                buffer: Vec::new(),
+                is_reader_end: false,
+                with_graphs,
+                position: TextPosition::new(0, 0, 0),
+            },
+            base_iri,
+            prefixes: HashMap::new(),
+            literal_validator,
+            is_end: false,
+        }
+    }
+
+    pub fn prefixes(&self) -> &HashMap<String, String> {
+        &self.prefixes
+    }
+
+    pub fn base_iri(&self) -> Option<&Iri<String>> {
+        self.base_iri.as_ref()
+    }
+
+    /// Parses the next batch of statements, adds their quads to `buffer` in reverse order
+    /// and reports the invalid statements to `on_error`
+    pub fn read<T: From<Quad>>(
+        &mut self,
+        buffer: &mut Vec<T>,
+        mapper: &mut RioMapper,
+        on_error: &dyn Fn(SyntaxError),
+    ) -> Option<Result<(), io::Error>> {
+        if self.is_end {
+            return None;
+        }
+        Some(self.parse_step(buffer, mapper, on_error))
+    }
+
+    fn parse_step<T: From<Quad>>(
+        &mut self,
+        buffer: &mut Vec<T>,
+        mapper: &mut RioMapper,
+        on_error: &dyn Fn(SyntaxError),
+    ) -> Result<(), io::Error> {
+        let mut statements = Vec::new();
+        let mut start = 0;
+        while let Some((end, kind)) = self.splitter.find_statement_end(start)? {
+            statements.push((start, end, kind));
+            start = end;
+            if end >= BATCH_SIZE {
+                break;
+            }
+        }
+        if statements.is_empty() {
+            self.is_end = true;
+            return Ok(());
+        }
+
+        let mut quads = Vec::new();
+        let batch_end = start;
+        let position = self.splitter.position_at(0);
+        let quads = match self.parse_chunk(0, batch_end, position, mapper, &mut quads) {
+            Ok(()) => {
+                if let Some(quads) = self.validate_literals(quads) {
+                    for (start, end, kind) in statements {
+                        self.read_directive(start, end, kind);
+                    }
+                    quads
+                } else {
+                    self.parse_statements(&statements, mapper, on_error)
+                }
+            }
+            Err(error) if statements.len() == 1 => {
+                on_error(error);
+                Vec::new()
+            }
+            Err(_) => self.parse_statements(&statements, mapper, on_error),
+        };
+        self.splitter.consume(batch_end);
+        buffer.extend(quads.into_iter().rev().map(T::from));
+        Ok(())
+    }
+
+    /// Parses the statements one by one, skipping the invalid ones and the quads with rejected literals
+    fn parse_statements(
+        &mut self,
+        statements: &[(usize, usize, StatementKind)],
+        mapper: &mut RioMapper,
+        on_error: &dyn Fn(SyntaxError),
+    ) -> Vec<Quad> {
+        let mut quads = Vec::new();
+        for (start, end, kind) in statements {
+            let position = self.splitter.position_at(*start);
+            let mut statement_quads = Vec::new();
+            match self.parse_chunk(*start, *end, position, mapper, &mut statement_quads) {
+                Ok(()) => {
+                    self.read_directive(*start, *end, *kind);
+                    // The literal errors are reported at the beginning of the statement, after the spaces
+                    let statement_start = self.splitter.buffer[*start..*end]
+                        .iter()
+                        .position(|c| !c.is_ascii_whitespace())
+                        .map_or(*start, |i| start + i);
+                    let position = self.splitter.position_at(statement_start);
+                    for quad in statement_quads {
+                        match self.literal_validator.validate_quad(quad) {
+                            Ok(quad) => quads.push(quad),
+                            Err(error) => {
+                                on_error(SyntaxError::new(error.to_string(), Some(position)))
+                            }
+                        }
+                    }
+                }
+                Err(error) => on_error(error),
+            }
+        }
+        quads
+    }
+
+    /// Validates the literals of the quads, returns `None` if one of them is rejected
+    fn validate_literals(&self, quads: Vec<Quad>) -> Option<Vec<Quad>> {
+        if self.literal_validator.is_noop() {
+            return Some(quads);
+        }
+        quads
+            .into_iter()
+            .map(|quad| self.literal_validator.validate_quad(quad))
+            .collect::<Result<_, _>>()
+            .ok()
+    }
+
+    /// Parses the statements between `start` and `end` in the splitter buffer
+    ///
+    /// The quads are only added to `quads` if the parsing succeeds.
+    fn parse_chunk(
+        &mut self,
+        start: usize,
+        end: usize,
+        position: TextPosition,
+        mapper: &mut RioMapper,
+        quads: &mut Vec<Quad>,
+    ) -> Result<(), SyntaxError> {
+        let mut text = Vec::new();
+        let mut preamble_lines = 0;
+        if let Some(base_iri) = &self.base_iri {
+            writeln!(&mut text, "@base <{}> .", base_iri).unwrap();
+            preamble_lines += 1;
+        }
+        for (name, iri) in &self.prefixes {
+            writeln!(&mut text, "@prefix {}: <{}> .", name, iri).unwrap();
+            preamble_lines += 1;
+        }
+        text.extend_from_slice(&self.splitter.buffer[start..end]);
+
+        mapper.clear_generated_blank_nodes();
+        let mut new_quads = Vec::new();
+        let result = if self.splitter.with_graphs {
+            let mut parser = TriGParser::new(Cursor::new(text), None);
+            parser.parse_all(&mut |q| {
+                new_quads.push(mapper.quad(&q));
+                Ok(())
+            })
+        } else {
+            let mut parser = TurtleParser::new(Cursor::new(text), None);
+            parser.parse_all(&mut |t| {
+                new_quads.push(mapper.triple(&t).in_graph(GraphName::DefaultGraph));
+                Ok(())
+            })
+        };
+        result.map_err(|e: TurtleError| {
+            let chunk = &self.splitter.buffer[start..end];
+            SyntaxError::from_turtle(&e, |line, column| {
+                let line = line.checked_sub(preamble_lines)?;
+                let line_start = if line == 0 {
+                    0
+                } else {
+                    chunk
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, c)| **c == b'\n')
+                        .nth((line - 1).try_into().ok()?)?
+                        .0
+                        + 1
+                };
+                let line_start = advance(position, &chunk[..line_start]);
+                Some(TextPosition::new(
+                    line_start.line(),
+                    line_start.column() + column,
+                    line_start.offset() + column,
+                ))
+            })
+        })?;
+        quads.extend(new_quads);
+        Ok(())
+    }
+
+    /// Updates the base IRI or the prefixes if the statement between `start` and `end` is a successfully parsed directive
+    fn read_directive(&mut self, start: usize, end: usize, kind: StatementKind) {
+        if kind == StatementKind::Other {
+            return;
+        }
+        let statement = &self.splitter.buffer[start..end];
+        let (name, iri) = if let Some(directive) = parse_directive(statement, kind) {
+            directive
+        } else {
+            return;
+        };
+        let iri = if let Some(base_iri) = &self.base_iri {
+            base_iri.resolve(&iri)
+        } else {
+            Iri::parse(iri)
+        };
+        if let Ok(iri) = iri {
+            if let Some(name) = name {
+                self.prefixes.insert(name, iri.into_inner());
+            } else {
+                self.base_iri = Some(iri);
+            }
+        }
+    }
+}
+
+/// Returns the prefix name (`None` for a base IRI declaration) and the unescaped IRI of a valid directive
+fn parse_directive(statement: &[u8], kind: StatementKind) -> Option<(Option<String>, String)> {
+    let mut i = 0;
+    // We skip the whitespaces and comments before the directive
+    loop {
+        match *statement.get(i)? {
+            b'#' => {
+                i += statement[i..]
+                    .iter()
+                    .position(|c| *c == b'\n' || *c == b'\r')?
+            }
+            c if c.is_ascii_whitespace() => i += 1,
+            _ => break,
+        }
+    }
+    let statement = std::str::from_utf8(&statement[i..]).ok()?;
+    let statement = statement.strip_prefix('@').unwrap_or(statement);
+    let name = if kind == StatementKind::Prefix {
+        let statement = statement["prefix".len()..].trim_start();
+        Some(statement[..statement.find(':')?].to_owned())
+    } else {
+        None
+    };
+    let iri = &statement[statement.find('<')? + 1..];
+    let iri = &iri[..iri.find('>')?];
+    Some((name, unescape_iri(iri)?))
+}
+
+/// Decodes the `\uXXXX` and `\UXXXXXXXX` escape sequences allowed in Turtle IRIs
+fn unescape_iri(iri: &str) -> Option<String> {
+    let mut result = String::with_capacity(iri.len());
+    let mut chars = iri.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            let len = match chars.next()? {
+                'u' => 4,
+                'U' => 8,
+                _ => return None,
+            };
+            let code = chars.by_ref().take(len).collect::<String>();
+            result.push(char::from_u32(u32::from_str_radix(&code, 16).ok()?)?);
+        } else {
+            result.push(c);
+        }
+    }
+    Some(result)
+}
+
+#[derive(Eq, PartialEq, Clone, Copy)]
+enum StatementKind {
+    Base,
+    Prefix,
+    Other,
+}
+
+/// Splits a Turtle or TriG file into statements without validating them
+struct StatementSplitter<R: BufRead> {
+    reader: R,
+    buffer: Vec<u8>,
+    is_reader_end: bool,
+    with_graphs: bool,
+    /// Position in the file of the beginning of the buffer
+    position: TextPosition,
+}
+
+impl<R: BufRead> StatementSplitter<R> {
+    /// Returns the end of the statement starting at `start` or `None` if there is no statement left
+    ///
+    /// The returned statement includes the whitespaces and comments before it.
+    fn find_statement_end(
+        &mut self,
+        start: usize,
+    ) -> Result<Option<(usize, StatementKind)>, io::Error> {
+        let mut i = start;
+        let mut is_empty = true;
+        let mut kind = StatementKind::Other;
+        let mut is_sparql_directive = false;
+        let mut graph_depth = 0_usize;
+        let mut previous = b' ';
+        while let Some(c) = self.byte(i)? {
+            i += 1;
+            if c == b'#' {
+                // Comment
+                while let Some(c) = self.byte(i)? {
+                    i += 1;
+                    if c == b'\n' || c == b'\r' {
+                        break;
+                    }
+                }
+                previous = b'\n';
+                continue;
+            }
+            if c.is_ascii_whitespace() {
+                previous = c;
+                continue;
+            }
+            if is_empty {
+                is_empty = false;
+                if c == b'@' {
+                    if self.starts_with_keyword(i, b"base")? {
+                        kind = StatementKind::Base;
+                    } else if self.starts_with_keyword(i, b"prefix")? {
+                        kind = StatementKind::Prefix;
+                    }
+                } else if self.starts_with_keyword(i - 1, b"base")? {
+                    kind = StatementKind::Base;
+                    is_sparql_directive = true;
+                } else if self.starts_with_keyword(i - 1, b"prefix")? {
+                    kind = StatementKind::Prefix;
+                    is_sparql_directive = true;
+                }
+            }
+            match c {
+                b'"' | b'\'' => {
+                    let (end, is_valid) = self.skip_string(i, c)?;
+                    i = end;
+                    if !is_valid {
+                        // We consider that the statement ends with the line
+                        return Ok(Some((i, kind)));
+                    }
+                }
+                b'<' => {
+                    if self.byte(i)? == Some(b'<') {
+                        i += 1;
+                    } else {
+                        i = self.skip_iri(i)?;
+                        if is_sparql_directive {
+                            return Ok(Some((i, kind)));
+                        }
+                    }
+                }
+                b'{' if self.with_graphs => {
+                    if self.byte(i)? == Some(b'|') {
+                        i += 1; // Annotation
+                    } else {
+                        graph_depth += 1;
+                    }
+                }
+                b'|' if self.with_graphs && self.byte(i)? == Some(b'}') => i += 1,
+                b'}' if self.with_graphs => {
+                    graph_depth = graph_depth.saturating_sub(1);
+                    if graph_depth == 0 {
+                        return Ok(Some((i, kind)));
+                    }
+                }
+                b'.' if graph_depth == 0 && is_statement_end(previous, self.byte(i)?) => {
+                    return Ok(Some((i, kind)));
+                }
+                _ => (),
+            }
+            previous = self.buffer[i - 1];
+        }
+        Ok(if is_empty { None } else { Some((i, kind)) })
+    }
+
+    /// Skips a string whose opening quote is just before `i` and returns the position after it
+    ///
+    /// Also returns `false` if the string is a single line string that is not closed before the end of the line.
+    fn skip_string(&mut self, mut i: usize, quote: u8) -> Result<(usize, bool), io::Error> {
+        if self.byte(i)? == Some(quote) {
+            if self.byte(i + 1)? != Some(quote) {
+                return Ok((i + 1, true)); // Empty string
+            }
+            // Long string
+            i += 2;
+            while let Some(c) = self.byte(i)? {
+                i += 1;
+                if c == b'\\' {
+                    i += 1;
+                } else if c == quote
+                    && self.byte(i)? == Some(quote)
+                    && self.byte(i + 1)? == Some(quote)
+                {
+                    i += 2;
+                    // The string content might end with quotes
+                    for _ in 0..2 {
+                        if self.byte(i)? == Some(quote) {
+                            i += 1;
+                        }
+                    }
+                    return Ok((i, true));
+                }
+            }
+            return Ok((i, true));
+        }
+        while let Some(c) = self.byte(i)? {
+            match c {
+                b'\\' => i += 2,
+                b'\n' | b'\r' => return Ok((i, false)),
+                c => {
+                    i += 1;
+                    if c == quote {
+                        return Ok((i, true));
+                    }
+                }
+            }
+        }
+        Ok((i, true))
+    }
+
+    /// Skips an IRI whose opening bracket is just before `i` and returns the position after it
+    fn skip_iri(&mut self, mut i: usize) -> Result<usize, io::Error> {
+        while let Some(c) = self.byte(i)? {
+            if c.is_ascii_whitespace() {
+                return Ok(i); // Invalid IRI, we stop before the whitespace
+            }
+            i += 1;
+            if c == b'>' {
+                return Ok(i);
+            }
+        }
+        Ok(i)
+    }
+
+    /// Checks if the text at `i` is the given keyword (ASCII case insensitive) followed by a whitespace or an IRI
+    fn starts_with_keyword(&mut self, i: usize, keyword: &[u8]) -> Result<bool, io::Error> {
+        for (j, expected) in keyword.iter().enumerate() {
+            match self.byte(i + j)? {
+                Some(c) if c.eq_ignore_ascii_case(expected) => (),
+                _ => return Ok(false),
+            }
+        }
+        Ok(match self.byte(i + keyword.len())? {
+            Some(c) => c.is_ascii_whitespace() || c == b'<',
+            None => false,
+        })
+    }
+
+    /// Returns the byte at position `i` in the buffer, reading more data if required
+    fn byte(&mut self, i: usize) -> Result<Option<u8>, io::Error> {
+        while i >= self.buffer.len() && !self.is_reader_end {
+            let data = self.reader.fill_buf()?;
+            if data.is_empty() {
+                self.is_reader_end = true;
+            } else {
+                self.buffer.extend_from_slice(data);
+                let len = data.len();
+                self.reader.consume(len);
+            }
+        }
+        Ok(self.buffer.get(i).copied())
+    }
+
+    /// The position in the file of the byte at position `i` in the buffer
+    fn position_at(&self, i: usize) -> TextPosition {
+        advance(self.position, &self.buffer[..i])
+    }
+
+    /// Removes the first `i` bytes of the buffer
+    fn consume(&mut self, i: usize) {
+        self.position = self.position_at(i);
+        self.buffer.drain(..i);
+    }
+}
+
+/// The position after `text` if `text` starts at `start`
+fn advance(start: TextPosition, text: &[u8]) -> TextPosition {
+    let offset = start.offset() + text.len() as u64;
+    match text.iter().rposition(|c| *c == b'\n') {
+        Some(last_line_end) => TextPosition::new(
+            start.line() + text.iter().filter(|c| **c == b'\n').count() as u64,
+            (text.len() - last_line_end - 1) as u64,
+            offset,
+        ),
+        None => TextPosition::new(start.line(), start.column() + text.len() as u64, offset),
+    }
+}
+
+/// Checks if a `.` between the `previous` and `next` bytes is a statement end and not part of a name or of a number
+fn is_statement_end(previous: u8, next: Option<u8>) -> bool {
+    match next {
+        None => true,
+        Some(next) if next.is_ascii_whitespace() || next == b'#' => true,
+        Some(next) => {
+            if is_name_byte(previous) {
+                !is_name_byte(next)
+            } else {
+                !next.is_ascii_digit()
+            }
+        }
+    }
+}
+
+fn is_name_byte(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, b'_' | b'-' | b':' | b'%' | b'.' | b'\\') || c >= 0x80
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::{DatasetFormat, DatasetParser, GraphFormat, GraphParser, SyntaxError};
+    use crate::model::*;
+    use std::collections::HashSet;
+    use std::io::Cursor;
+    use std::sync::{Arc, Mutex};
+
+    fn parse_graph(format: GraphFormat, file: &str) -> (Vec<Triple>, Vec<SyntaxError>) {
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let errors_sink = errors.clone();
+        let triples = GraphParser::from_format(format)
+            .lenient(move |e| errors_sink.lock().unwrap().push(e))
+            .read_triples(Cursor::new(file))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let errors = errors.lock().unwrap().drain(..).collect();
+        (triples, errors)
+    }
+
+    fn parse_dataset(format: DatasetFormat, file: &str) -> (Vec<Quad>, Vec<SyntaxError>) {
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let errors_sink = errors.clone();
+        let quads = DatasetParser::from_format(format)
+            .lenient(move |e| errors_sink.lock().unwrap().push(e))
+            .read_quads(Cursor::new(file))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let errors = errors.lock().unwrap().drain(..).collect();
+        (quads, errors)
+    }
+
+    fn positions(errors: &[SyntaxError]) -> Vec<(u64, u64)> {
+        errors
+            .iter()
+            .map(|e| {
+                let position = e.position().unwrap();
+                (position.line(), position.column())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_n_triples_recovery() {
+        let (triples, errors) = parse_graph(
+            GraphFormat::NTriples,
+            "<http://example.com/s> <http://example.com/p> <http://example.com/o1> .\n\
+             <http://example.com/s> <http://example.com/p> \"foo .\n\
+             <http://example.com/s> <http://example.com/p> <http://example.com/o2> .\n\
+             <http://example.com/s> <http://example.com/p> _:b _:c .\n\
+             <http://example.com/s> <http://example.com/p> <http://example.com/o3> .\n",
+        );
+        assert_eq!(
+            triples
+                .iter()
+                .map(|t| t.object.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "<http://example.com/o1>",
+                "<http://example.com/o2>",
+                "<http://example.com/o3>"
+            ]
+        );
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].position().unwrap().line(), 1);
+        assert_eq!(errors[1].position().unwrap().line(), 3);
+    }
+
+    #[test]
+    fn test_turtle_recovery() {
+        let (triples, errors) = parse_graph(
+            GraphFormat::Turtle,
+            "@prefix ex: <http://example.com/> .\n\
+             ex:s ex:p ex:o1 ; ex:q \"a. # b\" .\n\
+             ex:s ex:p ex:o2 ; ex:q unknown:o .\n\
+             ex:s ex:p 1.5, ex:a.b .\n\
+             ex:s ex:p ex:o3 . ex:s ex:p \"unterminated .\n\
+             ex:s ex:p ex:o4 .\n",
+        );
+        assert_eq!(
+            triples
+                .iter()
+                .map(|t| t.object.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "<http://example.com/o1>",
+                "\"a. # b\"",
+                "\"1.5\"^^<http://www.w3.org/2001/XMLSchema#decimal>",
+                "<http://example.com/a.b>",
+                "<http://example.com/o3>",
+                "<http://example.com/o4>"
+            ]
+        );
+        assert_eq!(positions(&errors), vec![(2, 31), (4, 43)]);
+    }
+
+    #[test]
+    fn test_turtle_directives() {
+        let (triples, errors) = parse_graph(
+            GraphFormat::Turtle,
+            "PREFIX ex: <http://example.com/>\n\
+             BASE <http://example.com/dir/>\n\
+             <s> ex:p <o> .\n\
+             <s> ex:p ex:o ex:o .\n\
+             @base <other/> .\n\
+             <s> ex:p <o> .\n",
+        );
+        assert_eq!(
+            triples.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "<http://example.com/dir/s> <http://example.com/p> <http://example.com/dir/o>",
+                "<http://example.com/dir/other/s> <http://example.com/p> <http://example.com/dir/other/o>"
+            ]
+        );
+        assert_eq!(positions(&errors), vec![(3, 14)]);
+    }
+
+    #[test]
+    fn test_turtle_directives_tracking() {
+        let file = "# Comment with <http://example.com/comment>\n\
+             @base <http://example.com/dir/> .\n\
+             @prefix ex: <http://example.com/> .\n\
+             PREFIX rel: <rel/>\n\
+             <s> rel:p <o> .\n\
+             @base <bad iri> .\n\
+             BASE <\\u0041/>\n\
+             @prefix ex: <other/> .\n\
+             <s> ex:p <o> .\n";
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let errors_sink = errors.clone();
+        let mut reader = GraphParser::from_format(GraphFormat::Turtle)
+            .lenient(move |e| errors_sink.lock().unwrap().push(e))
+            .read_triples(Cursor::new(file))
+            .unwrap();
+        let triples = (&mut reader).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(
+            triples.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "<http://example.com/dir/s> <http://example.com/dir/rel/p> <http://example.com/dir/o>",
+                "<http://example.com/dir/A/s> <http://example.com/dir/A/other/p> <http://example.com/dir/A/o>"
+            ]
+        );
+        assert_eq!(positions(&errors.lock().unwrap()), vec![(5, 15)]);
+        assert_eq!(reader.base_iri(), Some("http://example.com/dir/A/"));
+        assert_eq!(reader.prefixes().len(), 2);
+        assert_eq!(reader.prefixes()["ex"], "http://example.com/dir/A/other/");
+        assert_eq!(reader.prefixes()["rel"], "http://example.com/dir/rel/");
+    }
+
+    #[test]
+    fn test_turtle_blank_nodes() {
+        let (triples, errors) = parse_graph(
+            GraphFormat::Turtle,
+            "_:a <http://example.com/p> [ <http://example.com/p> _:a ] .\n\
+             _:a <http://example.com/p> [] , \"\"\"bad\"\"\"@.\n\
+             _:a <http://example.com/p> [] .\n",
+        );
+        assert_eq!(errors.len(), 1);
+        assert_eq!(triples.len(), 3);
+        // _:a and the two anonymous blank nodes
+        let blank_nodes = triples
+            .iter()
+            .flat_map(|t| vec![Term::from(t.subject.clone()), t.object.clone()])
+            .filter(Term::is_blank_node)
+            .collect::<HashSet<_>>();
+        assert_eq!(blank_nodes.len(), 3);
+    }
+
+    #[test]
+    fn test_trig_recovery() {
+        let (quads, errors) = parse_dataset(
+            DatasetFormat::TriG,
+            "@prefix ex: <http://example.com/> .\n\
+             ex:g1 { ex:s ex:p ex:o1 . ex:s ex:p ex:o2 }\n\
+             ex:g2 { ex:s ex:p ex:o3 . ex:s ex:p <bad iri> }\n\
+             ex:s ex:p ex:o4 {| ex:q ex:r |} .\n\
+             GRAPH ex:g3 { ex:s ex:p ex:o5 }\n",
+        );
+        assert_eq!(
+            quads
+                .iter()
+                .map(|q| format!("{} {}", q.object, q.graph_name))
+                .collect::<Vec<_>>(),
+            vec![
+                "<http://example.com/o1> <http://example.com/g1>",
+                "<http://example.com/o2> <http://example.com/g1>",
+                "<http://example.com/o4> DEFAULT",
+                "<http://example.com/r> DEFAULT",
+                "<http://example.com/o5> <http://example.com/g3>"
+            ]
+        );
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].position().unwrap().line(), 2);
+    }
+}
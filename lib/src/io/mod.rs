@@ -1,12 +1,42 @@
 //! Utilities to read and write RDF graphs and datasets
 
+use oxiri::Iri;
+
+mod compression;
+mod error;
 mod format;
+mod jsonld;
+mod lenient;
 pub mod read;
+mod sniff;
+mod turtle;
 pub mod write;
 
+#[cfg(feature = "compression")]
+pub use self::compression::Compression;
+pub use self::error::SyntaxError;
+pub use self::error::TextPosition;
+pub(crate) use self::error::{rdf_xml_error, LineOffsetReader, LineOffsets};
 pub use self::format::DatasetFormat;
 pub use self::format::GraphFormat;
+pub use self::jsonld::DocumentLoader;
 pub use self::read::DatasetParser;
 pub use self::read::GraphParser;
+pub use self::sniff::{sniff_format, SniffedFormat};
 pub use self::write::DatasetSerializer;
 pub use self::write::GraphSerializer;
+
+/// Builds a relative IRI that resolves to the given IRI against the base IRI
+pub(crate) fn relativize(base_iri: &Iri<String>, iri: &str) -> Option<String> {
+    let base = base_iri.as_str();
+    let directory = &base[..=base.rfind('/')?];
+    let relative = iri.strip_prefix(directory)?;
+    if relative.is_empty() {
+        return None;
+    }
+    if base_iri.resolve(relative).ok()?.as_str() == iri {
+        Some(relative.to_owned())
+    } else {
+        None
+    }
+}
@@ -1,15 +1,24 @@
 //! Utilities to read RDF graphs and datasets
 
-use crate::io::{DatasetFormat, GraphFormat};
+use crate::io::compression::DecompressingReader;
+use crate::io::error::{rdf_xml_error, LineOffsetReader, LineOffsets, SyntaxError, TextPosition};
+use crate::io::jsonld::{read_json_ld, NoDocumentLoader};
+use crate::io::lenient::LenientTurtleParser;
+#[cfg(feature = "compression")]
+use crate::io::Compression;
+use crate::io::{DatasetFormat, DocumentLoader, GraphFormat};
 use crate::model::*;
 use oxiri::{Iri, IriParseError};
 use rio_api::model as rio;
 use rio_api::parser::{QuadsParser, TriplesParser};
-use rio_turtle::{NQuadsParser, NTriplesParser, TriGParser, TurtleParser};
+use rio_turtle::{NQuadsParser, NTriplesParser, TriGParser, TurtleError, TurtleParser};
 use rio_xml::RdfXmlParser;
 use std::collections::HashMap;
+use std::error::Error;
 use std::io;
 use std::io::BufRead;
+use std::rc::Rc;
+use std::sync::Arc;
 
 /// Parsers for RDF graph serialization formats.
 ///
@@ -18,6 +27,8 @@ use std::io::BufRead;
 /// * [Turtle](https://www.w3.org/TR/turtle/) ([`GraphFormat::Turtle`](super::GraphFormat::Turtle))
 /// * [RDF/XML](https://www.w3.org/TR/rdf-syntax-grammar/) ([`GraphFormat::RdfXml`](super::GraphFormat::RdfXml))
 ///
+/// The N-Triples and Turtle parsers also support [RDF-star](https://w3c.github.io/rdf-star/cg-spec/) quoted triples.
+///
 /// ```
 /// use oxigraph::io::{GraphFormat, GraphParser};
 /// use std::io::Cursor;
@@ -31,9 +42,28 @@ use std::io::BufRead;
 ///assert_eq!(triples[0].subject.to_string(), "<http://example.com/s>");
 /// # std::io::Result::Ok(())
 /// ```
+///
+/// Quoted triples are returned as [`Subject::Triple`] or [`Term::Triple`]:
+/// ```
+/// use oxigraph::io::{GraphFormat, GraphParser};
+/// use std::io::Cursor;
+///
+/// let file = "<< <http://example.com/s> <http://example.com/p> <http://example.com/o> >> <http://example.com/source> <http://example.com/w> .";
+///
+/// let parser = GraphParser::from_format(GraphFormat::Turtle);
+/// let triples = parser.read_triples(Cursor::new(file))?.collect::<Result<Vec<_>,_>>()?;
+///
+///assert_eq!(triples.len(), 1);
+///assert!(triples[0].subject.is_triple());
+/// # std::io::Result::Ok(())
+/// ```
 pub struct GraphParser {
     format: GraphFormat,
     base_iri: Option<Iri<String>>,
+    on_error: Option<ErrorCallback>,
+    literal_validator: LiteralValidator,
+    #[cfg(feature = "compression")]
+    compression: Option<Compression>,
 }
 
 impl GraphParser {
@@ -42,6 +72,10 @@ impl GraphParser {
         Self {
             format,
             base_iri: None,
+            on_error: None,
+            literal_validator: LiteralValidator::new(),
+            #[cfg(feature = "compression")]
+            compression: None,
         }
     }
 
@@ -65,24 +99,172 @@ impl GraphParser {
         Ok(self)
     }
 
+    /// Enables the lenient mode: invalid statements are skipped and reported to `on_error` instead of stopping the parsing
+    ///
+    /// The N-Triples parser continues at the next line and the Turtle parser at the next statement.
+    /// The RDF/XML parser does not support error recovery and still fails on the first error.
+    ///
+    /// ```
+    /// use oxigraph::io::{GraphFormat, GraphParser};
+    /// use std::io::Cursor;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let file = "@prefix ex: <http://example.com/> .
+    /// ex:s ex:p \"unterminated .
+    /// ex:s ex:p ex:o .";
+    ///
+    /// let errors = Arc::new(Mutex::new(Vec::new()));
+    /// let errors_sink = errors.clone();
+    /// let parser = GraphParser::from_format(GraphFormat::Turtle)
+    ///     .lenient(move |error| errors_sink.lock().unwrap().push(error));
+    /// let triples = parser.read_triples(Cursor::new(file))?.collect::<Result<Vec<_>,_>>()?;
+    ///
+    /// assert_eq!(triples.len(), 1);
+    /// assert_eq!(triples[0].object.to_string(), "<http://example.com/o>");
+    /// let errors = errors.lock().unwrap();
+    /// assert_eq!(errors.len(), 1);
+    /// assert_eq!(errors[0].position().unwrap().line(), 1);
+    /// # std::io::Result::Ok(())
+    /// ```
+    pub fn lenient(mut self, on_error: impl Fn(SyntaxError) + Send + Sync + 'static) -> Self {
+        self.on_error = Some(Arc::new(on_error));
+        self
+    }
+
+    /// Sets the compression of the file instead of detecting it from its first bytes
+    ///
+    /// By default, [gzip](https://tools.ietf.org/html/rfc1952), [bzip2](https://sourceware.org/bzip2/)
+    /// and [Zstandard](https://tools.ietf.org/html/rfc8878) compressed files are detected and decompressed.
+    ///
+    /// Requires the `compression` feature.
+    /// ```
+    /// use flate2::write::GzEncoder;
+    /// use oxigraph::io::{Compression, GraphFormat, GraphParser};
+    /// use std::io::{Cursor, Write};
+    ///
+    /// let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+    /// encoder.write_all(b"<http://example.com/s> <http://example.com/p> <http://example.com/o> .")?;
+    /// let file = encoder.finish()?;
+    ///
+    /// let parser = GraphParser::from_format(GraphFormat::NTriples).with_compression(Compression::Gzip);
+    /// let triples = parser.read_triples(Cursor::new(file))?.collect::<Result<Vec<_>,_>>()?;
+    ///
+    ///assert_eq!(triples.len(), 1);
+    ///assert_eq!(triples[0].subject.to_string(), "<http://example.com/s>");
+    /// # std::io::Result::Ok(())
+    /// ```
+    #[cfg(feature = "compression")]
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Validates and possibly canonicalizes the read literals using the given [`LiteralValidator`]
+    ///
+    /// The rejected literals are returned as [`SyntaxError`]s or reported to the [`lenient`](GraphParser::lenient) mode callback.
+    /// ```
+    /// use oxigraph::io::{GraphFormat, GraphParser};
+    /// use oxigraph::model::{Literal, LiteralValidator};
+    /// use std::io::Cursor;
+    ///
+    /// let file = "<http://example.com/s> <http://example.com/p> \"01\"^^<http://www.w3.org/2001/XMLSchema#integer> .
+    /// <http://example.com/s> <http://example.com/p> \"foo\"@EN .";
+    ///
+    /// let parser = GraphParser::from_format(GraphFormat::NTriples)
+    ///     .with_literal_validator(LiteralValidator::new().reject_invalid().canonicalize());
+    /// let triples = parser.read_triples(Cursor::new(file))?.collect::<Result<Vec<_>,_>>()?;
+    ///
+    /// assert_eq!(triples[0].object, Literal::from(1).into());
+    /// assert_eq!(triples[1].object, Literal::new_language_tagged_literal("foo", "en")?.into());
+    /// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn with_literal_validator(mut self, literal_validator: LiteralValidator) -> Self {
+        self.literal_validator = literal_validator;
+        self
+    }
+
+    pub(crate) fn format(&self) -> GraphFormat {
+        self.format
+    }
+
+    pub(crate) fn base_iri(&self) -> Option<&Iri<String>> {
+        self.base_iri.as_ref()
+    }
+
+    pub(crate) fn is_lenient(&self) -> bool {
+        self.on_error.is_some()
+    }
+
+    pub(crate) fn validates_literals(&self) -> bool {
+        !self.literal_validator.is_noop()
+    }
+
+    #[cfg(any(feature = "rocksdb", feature = "sled"))]
+    pub(crate) fn error_callback(&self) -> Option<&(dyn Fn(SyntaxError) + Send + Sync)> {
+        self.on_error.as_deref()
+    }
+
+    pub(crate) fn decompress<R: BufRead>(&self, reader: R) -> io::Result<DecompressingReader<R>> {
+        #[cfg(feature = "compression")]
+        return DecompressingReader::new(reader, self.compression);
+        #[cfg(not(feature = "compression"))]
+        return DecompressingReader::new(reader);
+    }
+
     /// Executes the parsing itself on a [`BufRead`](std::io::BufRead) implementation and returns an iterator of triples
     pub fn read_triples<R: BufRead>(&self, reader: R) -> Result<TripleReader<R>, io::Error> {
+        let reader = self.decompress(reader)?;
         Ok(TripleReader {
             mapper: RioMapper::default(),
             parser: match self.format {
-                GraphFormat::NTriples => TripleReaderKind::NTriples(NTriplesParser::new(reader)),
+                GraphFormat::NTriples => {
+                    let reader =
+                        LineOffsetReader::new(reader).line_by_line(self.validates_literals());
+                    let offsets = reader.offsets();
+                    TripleReaderKind::NTriples(NTriplesParser::new(reader), offsets)
+                }
+                GraphFormat::Turtle if self.is_lenient() => {
+                    TripleReaderKind::LenientTurtle(LenientTurtleParser::new(
+                        reader,
+                        self.base_iri.clone(),
+                        false,
+                        self.literal_validator.clone(),
+                    ))
+                }
                 GraphFormat::Turtle => {
-                    TripleReaderKind::Turtle(TurtleParser::new(reader, self.base_iri.clone()))
+                    let reader =
+                        LineOffsetReader::new(reader).line_by_line(self.validates_literals());
+                    let offsets = reader.offsets();
+                    TripleReaderKind::Turtle(
+                        TurtleParser::new(reader, self.base_iri.clone()),
+                        offsets,
+                    )
                 }
                 GraphFormat::RdfXml => {
                     TripleReaderKind::RdfXml(RdfXmlParser::new(reader, self.base_iri.clone()))
                 }
             },
             buffer: Vec::new(),
+            no_prefixes: HashMap::new(),
+            base_iri: self.base_iri.clone(),
+            on_error: self.on_error.clone(),
+            // The lenient parser validates the literals itself
+            literal_validator: if self.is_lenient() && self.format == GraphFormat::Turtle {
+                LiteralValidator::new()
+            } else {
+                self.literal_validator.clone()
+            },
+            position: None,
         })
     }
 }
 
+impl From<GraphFormat> for GraphParser {
+    fn from(format: GraphFormat) -> Self {
+        Self::from_format(format)
+    }
+}
+
 /// An iterator yielding read triples.
 /// Could be built using a [`GraphParser`].
 ///
@@ -104,12 +286,25 @@ pub struct TripleReader<R: BufRead> {
     mapper: RioMapper,
     parser: TripleReaderKind<R>,
     buffer: Vec<Triple>,
+    no_prefixes: HashMap<String, String>,
+    base_iri: Option<Iri<String>>,
+    on_error: Option<ErrorCallback>,
+    literal_validator: LiteralValidator,
+    /// The position of the beginning of the line where the parser was before reading the statements of the buffer
+    position: Option<TextPosition>,
 }
 
 enum TripleReaderKind<R: BufRead> {
-    NTriples(NTriplesParser<R>),
-    Turtle(TurtleParser<R>),
-    RdfXml(RdfXmlParser<R>),
+    NTriples(
+        NTriplesParser<LineOffsetReader<DecompressingReader<R>>>,
+        Rc<LineOffsets>,
+    ),
+    Turtle(
+        TurtleParser<LineOffsetReader<DecompressingReader<R>>>,
+        Rc<LineOffsets>,
+    ),
+    LenientTurtle(LenientTurtleParser<DecompressingReader<R>>),
+    RdfXml(RdfXmlParser<DecompressingReader<R>>),
 }
 
 impl<R: BufRead> Iterator for TripleReader<R> {
@@ -118,18 +313,35 @@ impl<R: BufRead> Iterator for TripleReader<R> {
     fn next(&mut self) -> Option<Result<Triple, io::Error>> {
         loop {
             if let Some(r) = self.buffer.pop() {
-                return Some(Ok(r));
+                match self.literal_validator.validate_triple(r) {
+                    Ok(r) => return Some(Ok(r)),
+                    Err(error) => {
+                        match invalid_literal(&error, self.position, self.on_error.as_deref()) {
+                            Ok(()) => continue,
+                            Err(error) => return Some(Err(error)),
+                        }
+                    }
+                }
             }
 
+            let on_error = self.on_error.as_deref();
             if let Err(error) = match &mut self.parser {
-                TripleReaderKind::NTriples(parser) => {
+                TripleReaderKind::NTriples(parser, offsets) => {
+                    self.position = Some(offsets.current_line());
                     Self::read(parser, &mut self.buffer, &mut self.mapper)
+                        .map(|r| r.or_else(|e| recover(e, offsets, on_error)))
                 }
-                TripleReaderKind::Turtle(parser) => {
+                TripleReaderKind::Turtle(parser, offsets) => {
+                    self.position = Some(offsets.current_line());
                     Self::read(parser, &mut self.buffer, &mut self.mapper)
+                        .map(|r| r.map_err(|e| offsets.locate(e.into())))
+                }
+                TripleReaderKind::LenientTurtle(parser) => {
+                    parser.read(&mut self.buffer, &mut self.mapper, on_error?)
                 }
                 TripleReaderKind::RdfXml(parser) => {
                     Self::read(parser, &mut self.buffer, &mut self.mapper)
+                        .map(|r| r.map_err(rdf_xml_error))
                 }
             }? {
                 return Some(Err(error));
@@ -139,14 +351,71 @@ impl<R: BufRead> Iterator for TripleReader<R> {
 }
 
 impl<R: BufRead> TripleReader<R> {
+    /// The prefixes declared in the file so far, as a map from prefix name to prefix IRI
+    ///
+    /// The map is updated during the parsing. Only the Turtle format allows to declare prefixes,
+    /// the map is always empty with the other formats.
+    ///
+    /// ```
+    /// use oxigraph::io::{GraphFormat, GraphParser};
+    /// use std::io::Cursor;
+    ///
+    /// let file = "@prefix schema: <http://schema.org/> .
+    /// @prefix ex: <http://example.com/> .
+    /// ex:s a schema:Person .";
+    ///
+    /// let mut reader = GraphParser::from_format(GraphFormat::Turtle).read_triples(Cursor::new(file))?;
+    /// assert!(reader.prefixes().is_empty()); // No prefix at the beginning
+    ///
+    /// reader.next().unwrap()?;
+    /// assert_eq!(reader.prefixes().len(), 2);
+    /// assert_eq!(reader.prefixes()["ex"], "http://example.com/");
+    /// # std::io::Result::Ok(())
+    /// ```
+    pub fn prefixes(&self) -> &HashMap<String, String> {
+        match &self.parser {
+            TripleReaderKind::Turtle(parser, _) => parser.prefixes(),
+            TripleReaderKind::LenientTurtle(parser) => parser.prefixes(),
+            TripleReaderKind::NTriples(..) | TripleReaderKind::RdfXml(_) => &self.no_prefixes,
+        }
+    }
+
+    /// The base IRI used to resolve the relative IRIs at the current step of the parsing
+    ///
+    /// It is the one given with [`GraphParser::with_base_iri`], updated by the `@base` and `BASE` directives
+    /// read so far if the Turtle parser is in [lenient mode](GraphParser::lenient()).
+    /// The other parsers always return the base IRI given to the [`GraphParser`].
+    ///
+    /// ```
+    /// use oxigraph::io::{GraphFormat, GraphParser};
+    /// use std::io::Cursor;
+    ///
+    /// let file = "@base <http://example.com/> .
+    /// <s> <p> <o> .";
+    ///
+    /// let mut reader = GraphParser::from_format(GraphFormat::Turtle)
+    ///     .with_base_iri("http://example.org/")?
+    ///     .lenient(|_| ())
+    ///     .read_triples(Cursor::new(file))?;
+    /// assert_eq!(reader.base_iri(), Some("http://example.org/"));
+    ///
+    /// reader.next().unwrap()?;
+    /// assert_eq!(reader.base_iri(), Some("http://example.com/"));
+    /// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn base_iri(&self) -> Option<&str> {
+        match &self.parser {
+            TripleReaderKind::LenientTurtle(parser) => parser.base_iri(),
+            _ => self.base_iri.as_ref(),
+        }
+        .map(Iri::as_str)
+    }
+
     fn read<P: TriplesParser>(
         parser: &mut P,
         buffer: &mut Vec<Triple>,
         mapper: &mut RioMapper,
-    ) -> Option<Result<(), io::Error>>
-    where
-        io::Error: From<P::Error>,
-    {
+    ) -> Option<Result<(), P::Error>> {
         if parser.is_end() {
             None
         } else if let Err(e) = parser.parse_step(&mut |t| {
@@ -165,6 +434,7 @@ impl<R: BufRead> TripleReader<R> {
 /// It currently supports the following formats:
 /// * [N-Quads](https://www.w3.org/TR/n-quads/) ([`DatasetFormat::NQuads`](super::DatasetFormat::NQuads))
 /// * [TriG](https://www.w3.org/TR/trig/) ([`DatasetFormat::TriG`](super::DatasetFormat::TriG))
+/// * [JSON-LD](https://www.w3.org/TR/json-ld11/) ([`DatasetFormat::JsonLd`](super::DatasetFormat::JsonLd))
 ///
 /// ```
 /// use oxigraph::io::{DatasetFormat, DatasetParser};
@@ -179,9 +449,34 @@ impl<R: BufRead> TripleReader<R> {
 ///assert_eq!(quads[0].subject.to_string(), "<http://example.com/s>");
 /// # std::io::Result::Ok(())
 /// ```
+///
+/// The JSON-LD parser reads the full document before returning quads.
+/// Remote contexts are only resolved if a [`DocumentLoader`] is provided using [`with_document_loader`](DatasetParser::with_document_loader):
+/// ```
+/// use oxigraph::io::{DatasetFormat, DatasetParser};
+/// use std::io::Cursor;
+///
+/// let file = r#"{
+///     "@context": {"@vocab": "http://example.com/"},
+///     "@id": "http://example.com/s",
+///     "p": {"@id": "http://example.com/o"}
+/// }"#;
+///
+/// let parser = DatasetParser::from_format(DatasetFormat::JsonLd);
+/// let quads = parser.read_quads(Cursor::new(file))?.collect::<Result<Vec<_>,_>>()?;
+///
+///assert_eq!(quads.len(), 1);
+///assert_eq!(quads[0].to_string(), "<http://example.com/s> <http://example.com/p> <http://example.com/o>");
+/// # std::io::Result::Ok(())
+/// ```
 pub struct DatasetParser {
     format: DatasetFormat,
     base_iri: Option<Iri<String>>,
+    document_loader: Arc<dyn DocumentLoader>,
+    on_error: Option<ErrorCallback>,
+    literal_validator: LiteralValidator,
+    #[cfg(feature = "compression")]
+    compression: Option<Compression>,
 }
 
 impl DatasetParser {
@@ -190,6 +485,11 @@ impl DatasetParser {
         Self {
             format,
             base_iri: None,
+            document_loader: Arc::new(NoDocumentLoader),
+            on_error: None,
+            literal_validator: LiteralValidator::new(),
+            #[cfg(feature = "compression")]
+            compression: None,
         }
     }
 
@@ -213,21 +513,173 @@ impl DatasetParser {
         Ok(self)
     }
 
+    /// Provides the [`DocumentLoader`] used to resolve the remote contexts of [JSON-LD](https://www.w3.org/TR/json-ld11/) documents
+    ///
+    /// By default, remote contexts are not allowed.
+    pub fn with_document_loader(mut self, document_loader: impl DocumentLoader + 'static) -> Self {
+        self.document_loader = Arc::new(document_loader);
+        self
+    }
+
+    /// Enables the lenient mode: invalid statements are skipped and reported to `on_error` instead of stopping the parsing
+    ///
+    /// The N-Quads parser continues at the next line and the TriG parser after the invalid triple or graph block.
+    /// The JSON-LD parser does not support error recovery and still fails on the first error.
+    ///
+    /// ```
+    /// use oxigraph::io::{DatasetFormat, DatasetParser};
+    /// use std::io::Cursor;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let file = "<http://example.com/s> <http://example.com/p> <http://example.com/o> <http://example.com/g> .
+    /// <http://example.com/s> <http://example.com/p> \"foo\"@ .";
+    ///
+    /// let errors = Arc::new(Mutex::new(Vec::new()));
+    /// let errors_sink = errors.clone();
+    /// let parser = DatasetParser::from_format(DatasetFormat::NQuads)
+    ///     .lenient(move |error| errors_sink.lock().unwrap().push(error));
+    /// let quads = parser.read_quads(Cursor::new(file))?.collect::<Result<Vec<_>,_>>()?;
+    ///
+    /// assert_eq!(quads.len(), 1);
+    /// assert_eq!(errors.lock().unwrap().len(), 1);
+    /// # std::io::Result::Ok(())
+    /// ```
+    pub fn lenient(mut self, on_error: impl Fn(SyntaxError) + Send + Sync + 'static) -> Self {
+        self.on_error = Some(Arc::new(on_error));
+        self
+    }
+
+    /// Sets the compression of the file instead of detecting it from its first bytes
+    ///
+    /// By default, [gzip](https://tools.ietf.org/html/rfc1952), [bzip2](https://sourceware.org/bzip2/)
+    /// and [Zstandard](https://tools.ietf.org/html/rfc8878) compressed files are detected and decompressed.
+    ///
+    /// Requires the `compression` feature.
+    /// ```
+    /// use oxigraph::io::{Compression, DatasetFormat, DatasetParser};
+    /// use std::io::Cursor;
+    ///
+    /// let file = zstd::encode_all(b"<http://example.com/s> <http://example.com/p> <http://example.com/o> <http://example.com/g> .".as_ref(), 0)?;
+    ///
+    /// // The compression would also have been detected without calling with_compression
+    /// let parser = DatasetParser::from_format(DatasetFormat::NQuads).with_compression(Compression::Zstd);
+    /// let quads = parser.read_quads(Cursor::new(file))?.collect::<Result<Vec<_>,_>>()?;
+    ///
+    ///assert_eq!(quads.len(), 1);
+    ///assert_eq!(quads[0].subject.to_string(), "<http://example.com/s>");
+    /// # std::io::Result::Ok(())
+    /// ```
+    #[cfg(feature = "compression")]
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Validates and possibly canonicalizes the read literals using the given [`LiteralValidator`]
+    ///
+    /// The rejected literals are returned as [`SyntaxError`]s or reported to the [`lenient`](DatasetParser::lenient) mode callback.
+    /// ```
+    /// use oxigraph::io::{DatasetFormat, DatasetParser};
+    /// use oxigraph::model::LiteralValidator;
+    /// use std::io::Cursor;
+    ///
+    /// let file = "<http://example.com/s> <http://example.com/p> \"abc\"^^<http://www.w3.org/2001/XMLSchema#integer> <http://example.com/g> .";
+    ///
+    /// let parser = DatasetParser::from_format(DatasetFormat::NQuads)
+    ///     .with_literal_validator(LiteralValidator::new().reject_invalid());
+    /// assert!(parser.read_quads(Cursor::new(file))?.next().unwrap().is_err());
+    /// # std::io::Result::Ok(())
+    /// ```
+    pub fn with_literal_validator(mut self, literal_validator: LiteralValidator) -> Self {
+        self.literal_validator = literal_validator;
+        self
+    }
+
+    pub(crate) fn format(&self) -> DatasetFormat {
+        self.format
+    }
+
+    pub(crate) fn base_iri(&self) -> Option<&Iri<String>> {
+        self.base_iri.as_ref()
+    }
+
+    pub(crate) fn is_lenient(&self) -> bool {
+        self.on_error.is_some()
+    }
+
+    pub(crate) fn validates_literals(&self) -> bool {
+        !self.literal_validator.is_noop()
+    }
+
+    #[cfg(any(feature = "rocksdb", feature = "sled"))]
+    pub(crate) fn error_callback(&self) -> Option<&(dyn Fn(SyntaxError) + Send + Sync)> {
+        self.on_error.as_deref()
+    }
+
+    pub(crate) fn decompress<R: BufRead>(&self, reader: R) -> io::Result<DecompressingReader<R>> {
+        #[cfg(feature = "compression")]
+        return DecompressingReader::new(reader, self.compression);
+        #[cfg(not(feature = "compression"))]
+        return DecompressingReader::new(reader);
+    }
+
     /// Executes the parsing itself on a [`BufRead`](std::io::BufRead) implementation and returns an iterator of quads
     pub fn read_quads<R: BufRead>(&self, reader: R) -> Result<QuadReader<R>, io::Error> {
+        let reader = self.decompress(reader)?;
+        let mut buffer = Vec::new();
+        let parser = match self.format {
+            DatasetFormat::NQuads => {
+                let reader = LineOffsetReader::new(reader).line_by_line(self.validates_literals());
+                let offsets = reader.offsets();
+                QuadReaderKind::NQuads(NQuadsParser::new(reader), offsets)
+            }
+            DatasetFormat::TriG if self.is_lenient() => {
+                QuadReaderKind::LenientTriG(LenientTurtleParser::new(
+                    reader,
+                    self.base_iri.clone(),
+                    true,
+                    self.literal_validator.clone(),
+                ))
+            }
+            DatasetFormat::TriG => {
+                let reader = LineOffsetReader::new(reader).line_by_line(self.validates_literals());
+                let offsets = reader.offsets();
+                QuadReaderKind::TriG(TriGParser::new(reader, self.base_iri.clone()), offsets)
+            }
+            DatasetFormat::JsonLd => {
+                buffer = read_json_ld(
+                    reader,
+                    self.base_iri.as_ref(),
+                    self.document_loader.as_ref(),
+                )?;
+                buffer.reverse();
+                QuadReaderKind::JsonLd
+            }
+        };
         Ok(QuadReader {
             mapper: RioMapper::default(),
-            parser: match self.format {
-                DatasetFormat::NQuads => QuadReaderKind::NQuads(NQuadsParser::new(reader)),
-                DatasetFormat::TriG => {
-                    QuadReaderKind::TriG(TriGParser::new(reader, self.base_iri.clone()))
-                }
+            parser,
+            buffer,
+            no_prefixes: HashMap::new(),
+            base_iri: self.base_iri.clone(),
+            on_error: self.on_error.clone(),
+            // The lenient parser validates the literals itself
+            literal_validator: if self.is_lenient() && self.format == DatasetFormat::TriG {
+                LiteralValidator::new()
+            } else {
+                self.literal_validator.clone()
             },
-            buffer: Vec::new(),
+            position: None,
         })
     }
 }
 
+impl From<DatasetFormat> for DatasetParser {
+    fn from(format: DatasetFormat) -> Self {
+        Self::from_format(format)
+    }
+}
+
 /// An iterator yielding read quads.
 /// Could be built using a [`DatasetParser`].
 ///
@@ -249,11 +701,26 @@ pub struct QuadReader<R: BufRead> {
     mapper: RioMapper,
     parser: QuadReaderKind<R>,
     buffer: Vec<Quad>,
+    no_prefixes: HashMap<String, String>,
+    base_iri: Option<Iri<String>>,
+    on_error: Option<ErrorCallback>,
+    literal_validator: LiteralValidator,
+    /// The position of the beginning of the line where the parser was before reading the statements of the buffer
+    position: Option<TextPosition>,
 }
 
 enum QuadReaderKind<R: BufRead> {
-    NQuads(NQuadsParser<R>),
-    TriG(TriGParser<R>),
+    NQuads(
+        NQuadsParser<LineOffsetReader<DecompressingReader<R>>>,
+        Rc<LineOffsets>,
+    ),
+    TriG(
+        TriGParser<LineOffsetReader<DecompressingReader<R>>>,
+        Rc<LineOffsets>,
+    ),
+    LenientTriG(LenientTurtleParser<DecompressingReader<R>>),
+    /// The JSON-LD quads are all parsed in advance and stored in the buffer
+    JsonLd,
 }
 
 impl<R: BufRead> Iterator for QuadReader<R> {
@@ -262,16 +729,33 @@ impl<R: BufRead> Iterator for QuadReader<R> {
     fn next(&mut self) -> Option<Result<Quad, io::Error>> {
         loop {
             if let Some(r) = self.buffer.pop() {
-                return Some(Ok(r));
+                match self.literal_validator.validate_quad(r) {
+                    Ok(r) => return Some(Ok(r)),
+                    Err(error) => {
+                        match invalid_literal(&error, self.position, self.on_error.as_deref()) {
+                            Ok(()) => continue,
+                            Err(error) => return Some(Err(error)),
+                        }
+                    }
+                }
             }
 
+            let on_error = self.on_error.as_deref();
             if let Err(error) = match &mut self.parser {
-                QuadReaderKind::NQuads(parser) => {
+                QuadReaderKind::NQuads(parser, offsets) => {
+                    self.position = Some(offsets.current_line());
                     Self::read(parser, &mut self.buffer, &mut self.mapper)
+                        .map(|r| r.or_else(|e| recover(e, offsets, on_error)))
                 }
-                QuadReaderKind::TriG(parser) => {
+                QuadReaderKind::TriG(parser, offsets) => {
+                    self.position = Some(offsets.current_line());
                     Self::read(parser, &mut self.buffer, &mut self.mapper)
+                        .map(|r| r.map_err(|e| offsets.locate(e.into())))
                 }
+                QuadReaderKind::LenientTriG(parser) => {
+                    parser.read(&mut self.buffer, &mut self.mapper, on_error?)
+                }
+                QuadReaderKind::JsonLd => None,
             }? {
                 return Some(Err(error));
             }
@@ -280,14 +764,69 @@ impl<R: BufRead> Iterator for QuadReader<R> {
 }
 
 impl<R: BufRead> QuadReader<R> {
+    /// The prefixes declared in the file so far, as a map from prefix name to prefix IRI
+    ///
+    /// The map is updated during the parsing. Only the TriG format allows to declare prefixes,
+    /// the map is always empty with the other formats.
+    ///
+    /// ```
+    /// use oxigraph::io::{DatasetFormat, DatasetParser};
+    /// use std::io::Cursor;
+    ///
+    /// let file = "@prefix ex: <http://example.com/> .
+    /// ex:g { ex:s ex:p ex:o }";
+    ///
+    /// let mut reader = DatasetParser::from_format(DatasetFormat::TriG).read_quads(Cursor::new(file))?;
+    /// assert!(reader.prefixes().is_empty()); // No prefix at the beginning
+    ///
+    /// reader.next().unwrap()?;
+    /// assert_eq!(reader.prefixes().len(), 1);
+    /// assert_eq!(reader.prefixes()["ex"], "http://example.com/");
+    /// # std::io::Result::Ok(())
+    /// ```
+    pub fn prefixes(&self) -> &HashMap<String, String> {
+        match &self.parser {
+            QuadReaderKind::TriG(parser, _) => parser.prefixes(),
+            QuadReaderKind::LenientTriG(parser) => parser.prefixes(),
+            QuadReaderKind::NQuads(..) | QuadReaderKind::JsonLd => &self.no_prefixes,
+        }
+    }
+
+    /// The base IRI used to resolve the relative IRIs at the current step of the parsing
+    ///
+    /// It is the one given with [`DatasetParser::with_base_iri`], updated by the `@base` and `BASE` directives
+    /// read so far if the TriG parser is in [lenient mode](DatasetParser::lenient()).
+    /// The other parsers always return the base IRI given to the [`DatasetParser`].
+    ///
+    /// ```
+    /// use oxigraph::io::{DatasetFormat, DatasetParser};
+    /// use std::io::Cursor;
+    ///
+    /// let file = "BASE <http://example.com/>
+    /// <g> { <s> <p> <o> }";
+    ///
+    /// let mut reader = DatasetParser::from_format(DatasetFormat::TriG)
+    ///     .lenient(|_| ())
+    ///     .read_quads(Cursor::new(file))?;
+    /// assert_eq!(reader.base_iri(), None);
+    ///
+    /// reader.next().unwrap()?;
+    /// assert_eq!(reader.base_iri(), Some("http://example.com/"));
+    /// # std::io::Result::Ok(())
+    /// ```
+    pub fn base_iri(&self) -> Option<&str> {
+        match &self.parser {
+            QuadReaderKind::LenientTriG(parser) => parser.base_iri(),
+            _ => self.base_iri.as_ref(),
+        }
+        .map(Iri::as_str)
+    }
+
     fn read<P: QuadsParser>(
         parser: &mut P,
         buffer: &mut Vec<Quad>,
         mapper: &mut RioMapper,
-    ) -> Option<Result<(), io::Error>>
-    where
-        io::Error: From<P::Error>,
-    {
+    ) -> Option<Result<(), P::Error>> {
         if parser.is_end() {
             None
         } else if let Err(e) = parser.parse_step(&mut |t| {
@@ -301,9 +840,47 @@ impl<R: BufRead> QuadReader<R> {
     }
 }
 
+type ErrorCallback = Arc<dyn Fn(SyntaxError) + Send + Sync>;
+
+/// Reports a syntax error to the lenient mode callback if there is one and returns the error otherwise
+fn recover(
+    error: TurtleError,
+    offsets: &LineOffsets,
+    on_error: Option<&(dyn Fn(SyntaxError) + Send + Sync)>,
+) -> Result<(), io::Error> {
+    match on_error {
+        Some(on_error) if error.source().is_none_or(|e| !e.is::<io::Error>()) => {
+            on_error(SyntaxError::from_turtle(&error, |line, column| {
+                offsets.position(line, column)
+            }));
+            Ok(())
+        }
+        _ => Err(offsets.locate(error.into())),
+    }
+}
+
+/// Reports a rejected literal to the lenient mode callback if there is one and returns it as a syntax error otherwise
+///
+/// `position` is the position of the parser when it started to read the statement containing the literal, if known.
+fn invalid_literal(
+    error: &TypedValueParseError,
+    position: Option<TextPosition>,
+    on_error: Option<&(dyn Fn(SyntaxError) + Send + Sync)>,
+) -> Result<(), io::Error> {
+    let error = SyntaxError::new(error.to_string(), position);
+    if let Some(on_error) = on_error {
+        on_error(error);
+        Ok(())
+    } else {
+        Err(error.into())
+    }
+}
+
 #[derive(Default)]
-struct RioMapper {
+pub(crate) struct RioMapper {
     bnode_map: HashMap<String, BlankNode>,
+    /// Blank nodes generated by the Rio parsers, they are only unique for a given parser
+    generated_bnode_map: HashMap<String, BlankNode>,
 }
 
 impl<'a> RioMapper {
@@ -312,12 +889,21 @@ impl<'a> RioMapper {
     }
 
     fn blank_node(&mut self, node: rio::BlankNode<'a>) -> BlankNode {
-        self.bnode_map
-            .entry(node.id.to_owned())
+        let map = if is_rio_generated_id(node.id) {
+            &mut self.generated_bnode_map
+        } else {
+            &mut self.bnode_map
+        };
+        map.entry(node.id.to_owned())
             .or_insert_with(BlankNode::default)
             .clone()
     }
 
+    /// Forgets the blank nodes generated by the previous Rio parser
+    pub(crate) fn clear_generated_blank_nodes(&mut self) {
+        self.generated_bnode_map.clear();
+    }
+
     fn literal(&self, literal: rio::Literal<'a>) -> Literal {
         match literal {
             rio::Literal::Simple { value } => Literal::new_simple_literal(value),
@@ -330,10 +916,11 @@ impl<'a> RioMapper {
         }
     }
 
-    fn named_or_blank_node(&mut self, node: rio::NamedOrBlankNode<'a>) -> NamedOrBlankNode {
+    fn subject(&mut self, node: rio::Subject<'a>) -> Subject {
         match node {
-            rio::NamedOrBlankNode::NamedNode(node) => self.named_node(node).into(),
-            rio::NamedOrBlankNode::BlankNode(node) => self.blank_node(node).into(),
+            rio::Subject::NamedNode(node) => self.named_node(node).into(),
+            rio::Subject::BlankNode(node) => self.blank_node(node).into(),
+            rio::Subject::Triple(triple) => self.triple(triple).into(),
         }
     }
 
@@ -342,31 +929,37 @@ impl<'a> RioMapper {
             rio::Term::NamedNode(node) => self.named_node(node).into(),
             rio::Term::BlankNode(node) => self.blank_node(node).into(),
             rio::Term::Literal(literal) => self.literal(literal).into(),
+            rio::Term::Triple(triple) => self.triple(triple).into(),
         }
     }
 
-    fn triple(&mut self, triple: &rio::Triple<'a>) -> Triple {
+    pub(crate) fn triple(&mut self, triple: &rio::Triple<'a>) -> Triple {
         Triple {
-            subject: self.named_or_blank_node(triple.subject),
+            subject: self.subject(triple.subject),
             predicate: self.named_node(triple.predicate),
             object: self.term(triple.object),
         }
     }
 
-    fn graph_name(&mut self, graph_name: Option<rio::NamedOrBlankNode<'a>>) -> GraphName {
+    fn graph_name(&mut self, graph_name: Option<rio::GraphName<'a>>) -> GraphName {
         match graph_name {
-            Some(rio::NamedOrBlankNode::NamedNode(node)) => self.named_node(node).into(),
-            Some(rio::NamedOrBlankNode::BlankNode(node)) => self.blank_node(node).into(),
+            Some(rio::GraphName::NamedNode(node)) => self.named_node(node).into(),
+            Some(rio::GraphName::BlankNode(node)) => self.blank_node(node).into(),
             None => GraphName::DefaultGraph,
         }
     }
 
-    fn quad(&mut self, quad: &rio::Quad<'a>) -> Quad {
+    pub(crate) fn quad(&mut self, quad: &rio::Quad<'a>) -> Quad {
         Quad {
-            subject: self.named_or_blank_node(quad.subject),
+            subject: self.subject(quad.subject),
             predicate: self.named_node(quad.predicate),
             object: self.term(quad.object),
             graph_name: self.graph_name(quad.graph_name),
         }
     }
 }
+
+/// Checks if the blank node id has the shape of the ids generated by the Rio parsers (`riog` followed by 8 digits)
+fn is_rio_generated_id(id: &str) -> bool {
+    id.len() == 12 && id.starts_with("riog") && id[4..].bytes().all(|c| c.is_ascii_digit())
+}
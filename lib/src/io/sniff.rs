@@ -0,0 +1,397 @@
+#[cfg(feature = "compression")]
+use crate::io::{compression::DecompressingReader, Compression};
+use crate::io::{DatasetFormat, GraphFormat};
+use std::io;
+use std::io::BufRead;
+#[cfg(feature = "compression")]
+use std::io::Read;
+
+/// Maximal number of bytes of the decompressed content looked at by [`sniff_format`]
+#[cfg(feature = "compression")]
+const DECOMPRESSED_SAMPLE_SIZE: usize = 8192;
+
+/// A serialization format guessed by [`sniff_format`]
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash)]
+pub enum SniffedFormat {
+    /// A graph serialization format
+    Graph(GraphFormat),
+    /// A dataset serialization format
+    Dataset(DatasetFormat),
+}
+
+/// Guesses the serialization format of a file from its first bytes.
+///
+/// It distinguishes [N-Triples](https://www.w3.org/TR/n-triples/), [Turtle](https://www.w3.org/TR/turtle/),
+/// [RDF/XML](https://www.w3.org/TR/rdf-syntax-grammar/), [N-Quads](https://www.w3.org/TR/n-quads/),
+/// [TriG](https://www.w3.org/TR/trig/) and [JSON-LD](https://www.w3.org/TR/json-ld11/).
+/// Only the bytes already buffered by the reader are used and nothing is consumed, so the same reader could then be given to a parser.
+/// With the `compression` feature, the format of the content of [gzip](https://tools.ietf.org/html/rfc1952), [bzip2](https://sourceware.org/bzip2/)
+/// and [Zstandard](https://tools.ietf.org/html/rfc8878) compressed files is also guessed.
+/// The parsers detect these compressions themselves.
+///
+/// It is only a guess: for example a Turtle file only using N-Triples syntax in its first bytes is detected as N-Triples.
+/// Returns `None` if no format is recognized.
+///
+/// ```
+/// use oxigraph::io::{sniff_format, DatasetFormat, GraphFormat, SniffedFormat};
+/// use std::io::Cursor;
+///
+/// let mut file = Cursor::new("@prefix ex: <http://example.com/> .\nex:s ex:p ex:o .");
+/// assert_eq!(sniff_format(&mut file)?, Some(SniffedFormat::Graph(GraphFormat::Turtle)));
+///
+/// let mut file = Cursor::new("<http://example.com/s> <http://example.com/p> <http://example.com/o> <http://example.com/g> .");
+/// assert_eq!(sniff_format(&mut file)?, Some(SniffedFormat::Dataset(DatasetFormat::NQuads)));
+/// # std::io::Result::Ok(())
+/// ```
+pub fn sniff_format(reader: &mut impl BufRead) -> io::Result<Option<SniffedFormat>> {
+    let sample = reader.fill_buf()?;
+    #[cfg(feature = "compression")]
+    {
+        if let Some(compression) = Compression::from_magic_bytes(sample) {
+            return Ok(sniff_text(&decompress_sample(sample, compression)));
+        }
+    }
+    Ok(sniff_text(sample))
+}
+
+/// Decompresses the beginning of a compressed file
+///
+/// The sample is usually truncated so decompression errors are expected at its end.
+#[cfg(feature = "compression")]
+fn decompress_sample(sample: &[u8], compression: Compression) -> Vec<u8> {
+    let mut result = Vec::new();
+    if let Ok(reader) = DecompressingReader::new(sample, Some(compression)) {
+        let mut reader = reader.take(DECOMPRESSED_SAMPLE_SIZE as u64);
+        let mut buffer = [0; 1024];
+        while let Ok(read) = reader.read(&mut buffer) {
+            if read == 0 {
+                break;
+            }
+            result.extend_from_slice(&buffer[..read]);
+        }
+    }
+    result
+}
+
+fn sniff_text(data: &[u8]) -> Option<SniffedFormat> {
+    let data = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+    let start = skip_whitespaces_and_comments(data, 0);
+    match data.get(start..)? {
+        [b'<', b'?', ..] | [b'<', b'!', ..] => {
+            return Some(SniffedFormat::Graph(GraphFormat::RdfXml))
+        }
+        [b'<', next, ..] if *next != b'<' => {
+            // A first tag with attributes is not a valid IRI
+            let tag = &data[start + 1..];
+            let tag = &tag[..tag.iter().position(|c| *c == b'>').unwrap_or(tag.len())];
+            if tag
+                .iter()
+                .any(|c| matches!(*c, b' ' | b'\t' | b'\r' | b'\n' | b'"'))
+            {
+                return Some(SniffedFormat::Graph(GraphFormat::RdfXml));
+            }
+        }
+        [b'{', ..] | [b'[', ..] => {
+            let next = skip_whitespaces_and_comments(data, start + 1);
+            if matches!(
+                (data[start], data.get(next)),
+                (b'{', Some(b'"')) | (b'[', Some(b'{'))
+            ) {
+                return Some(SniffedFormat::Dataset(DatasetFormat::JsonLd));
+            }
+        }
+        [] => return None,
+        _ => (),
+    }
+
+    let mut sniffer = Sniffer::default();
+    sniffer.run(data, start);
+    if sniffer.trig {
+        Some(SniffedFormat::Dataset(DatasetFormat::TriG))
+    } else if sniffer.turtle {
+        Some(SniffedFormat::Graph(GraphFormat::Turtle))
+    } else if sniffer.quads {
+        Some(SniffedFormat::Dataset(DatasetFormat::NQuads))
+    } else if sniffer.triples {
+        Some(SniffedFormat::Graph(GraphFormat::NTriples))
+    } else {
+        None
+    }
+}
+
+/// Looks for the syntax features of the N-Triples, N-Quads, Turtle and TriG files
+#[derive(Default)]
+struct Sniffer {
+    /// A statement with 3 terms has been found
+    triples: bool,
+    /// A statement with 4 terms has been found
+    quads: bool,
+    /// Some syntax only allowed in Turtle and TriG has been found
+    turtle: bool,
+    /// Some syntax only allowed in TriG has been found
+    trig: bool,
+}
+
+impl Sniffer {
+    fn run(&mut self, data: &[u8], mut i: usize) {
+        // Number of terms in the current statement and quoted triple nesting depth
+        let mut terms = 0;
+        let mut depth = 0;
+        loop {
+            i = skip_whitespaces_and_comments(data, i);
+            let c = if let Some(c) = data.get(i) {
+                *c
+            } else {
+                return;
+            };
+            match c {
+                b'<' if data.get(i + 1) == Some(&b'<') => {
+                    depth += 1;
+                    i += 2;
+                }
+                b'>' if data.get(i + 1) == Some(&b'>') && depth > 0 => {
+                    depth -= 1;
+                    if depth == 0 {
+                        terms += 1;
+                    }
+                    i += 2;
+                }
+                b'<' => {
+                    i = if let Some(end) = find(data, i + 1, b'>') {
+                        end + 1
+                    } else {
+                        return; // The sample ends in the middle of the IRI
+                    };
+                    if depth == 0 {
+                        terms += 1;
+                    }
+                }
+                b'_' if data.get(i + 1) == Some(&b':') => {
+                    i = skip_name(data, i + 2);
+                    if depth == 0 {
+                        terms += 1;
+                    }
+                }
+                b'"' | b'\'' => {
+                    i = if let Some(end) = self.skip_string(data, i) {
+                        end
+                    } else {
+                        return; // The sample ends in the middle of the string
+                    };
+                    match data.get(i) {
+                        Some(b'@') => i = skip_name(data, i + 1),
+                        Some(b'^') if data.get(i + 1) == Some(&b'^') => {
+                            if data.get(i + 2) == Some(&b'<') {
+                                i = if let Some(end) = find(data, i + 3, b'>') {
+                                    end + 1
+                                } else {
+                                    return;
+                                };
+                            } else {
+                                self.turtle = true; // Prefixed name datatype
+                                i = skip_name(data, i + 2);
+                            }
+                        }
+                        _ => (),
+                    }
+                    if depth == 0 {
+                        terms += 1;
+                    }
+                }
+                b'.' if !data.get(i + 1).is_some_and(u8::is_ascii_digit) => {
+                    match terms {
+                        3 => self.triples = true,
+                        4 => self.quads = true,
+                        _ => self.turtle = true,
+                    }
+                    terms = 0;
+                    i += 1;
+                }
+                b'{' if data.get(i + 1) == Some(&b'|') => {
+                    self.turtle = true; // Annotation
+                    i += 2;
+                }
+                b'{' | b'}' => {
+                    self.trig = true;
+                    terms = 0;
+                    i += 1;
+                }
+                _ => {
+                    let end = skip_name(data, i).max(i + 1);
+                    if data[i..end].eq_ignore_ascii_case(b"GRAPH") {
+                        self.trig = true;
+                    } else {
+                        // Prefixed names, keywords, numbers, directives and punctuation
+                        self.turtle = true;
+                    }
+                    i = end;
+                }
+            }
+        }
+    }
+
+    /// Returns the position after the string starting at `start`
+    fn skip_string(&mut self, data: &[u8], start: usize) -> Option<usize> {
+        let quote = data[start];
+        if quote == b'\'' {
+            self.turtle = true;
+        }
+        let long = data.get(start + 1) == Some(&quote) && data.get(start + 2) == Some(&quote);
+        let mut i = if long {
+            self.turtle = true;
+            start + 3
+        } else {
+            start + 1
+        };
+        loop {
+            match *data.get(i)? {
+                b'\\' => i += 2,
+                c if c == quote => {
+                    if !long {
+                        return Some(i + 1);
+                    }
+                    if data.get(i + 1) == Some(&quote) && data.get(i + 2) == Some(&quote) {
+                        return Some(i + 3);
+                    }
+                    i += 1;
+                }
+                _ => i += 1,
+            }
+        }
+    }
+}
+
+fn skip_whitespaces_and_comments(data: &[u8], mut i: usize) -> usize {
+    loop {
+        match data.get(i) {
+            Some(b' ') | Some(b'\t') | Some(b'\r') | Some(b'\n') => i += 1,
+            Some(b'#') => i = find(data, i, b'\n').unwrap_or(data.len()),
+            _ => return i,
+        }
+    }
+}
+
+/// Returns the position after the name (blank node label, language tag, prefixed name...) starting at `start`
+///
+/// The names could not end with a dot so the dots at the end are statement ends.
+fn skip_name(data: &[u8], start: usize) -> usize {
+    let mut end = data[start..]
+        .iter()
+        .position(|c| {
+            matches!(
+                *c,
+                b' ' | b'\t'
+                    | b'\r'
+                    | b'\n'
+                    | b'<'
+                    | b'>'
+                    | b'"'
+                    | b'\''
+                    | b'#'
+                    | b';'
+                    | b','
+                    | b'('
+                    | b')'
+                    | b'['
+                    | b']'
+                    | b'{'
+                    | b'}'
+            )
+        })
+        .map_or(data.len(), |p| start + p);
+    while end > start && data[end - 1] == b'.' {
+        end -= 1;
+    }
+    end
+}
+
+fn find(data: &[u8], start: usize, c: u8) -> Option<usize> {
+    Some(start + data.get(start..)?.iter().position(|v| *v == c)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sniff(data: &str) -> Option<SniffedFormat> {
+        sniff_text(data.as_bytes())
+    }
+
+    #[test]
+    fn test_sniff_n_triples_and_n_quads() {
+        assert_eq!(
+            sniff("# comment\n<http://example.com/s> <http://example.com/p> \"o\\\"\"@en .\n_:s <http://example.com/p> \"1\"^^<http://www.w3.org/2001/XMLSchema#integer> .\n<http://example.com/s> <http://exa"),
+            Some(SniffedFormat::Graph(GraphFormat::NTriples))
+        );
+        assert_eq!(
+            sniff("_:s <http://example.com/p> _:o.\n_:s <http://example.com/p> \"o\"@en."),
+            Some(SniffedFormat::Graph(GraphFormat::NTriples))
+        );
+        assert_eq!(
+            sniff("<< _:s <http://example.com/p> _:o >> <http://example.com/p> \"o\" ."),
+            Some(SniffedFormat::Graph(GraphFormat::NTriples))
+        );
+        assert_eq!(
+            sniff("<http://example.com/s> <http://example.com/p> <http://example.com/o> .\n<http://example.com/s> <http://example.com/p> \"o\" <http://example.com/g> ."),
+            Some(SniffedFormat::Dataset(DatasetFormat::NQuads))
+        );
+    }
+
+    #[test]
+    fn test_sniff_turtle_and_trig() {
+        assert_eq!(
+            sniff("@prefix ex: <http://example.com/> .\nex:s ex:p ex:o ."),
+            Some(SniffedFormat::Graph(GraphFormat::Turtle))
+        );
+        assert_eq!(
+            sniff("<http://example.com/s> <http://example.com/p> \"\"\"a\n\"b\"\"\"\" ; a <http://example.com/C> ."),
+            Some(SniffedFormat::Graph(GraphFormat::Turtle))
+        );
+        assert_eq!(
+            sniff("[] <http://example.com/p> (1 2.5) ."),
+            Some(SniffedFormat::Graph(GraphFormat::Turtle))
+        );
+        assert_eq!(
+            sniff("PREFIX ex: <http://example.com/>\nex:g { ex:s ex:p ex:o }"),
+            Some(SniffedFormat::Dataset(DatasetFormat::TriG))
+        );
+        assert_eq!(
+            sniff("GRAPH <http://example.com/g> { <http://example.com/s> <http://example.com/p> <http://example.com/o> }"),
+            Some(SniffedFormat::Dataset(DatasetFormat::TriG))
+        );
+    }
+
+    #[test]
+    fn test_sniff_xml_and_json_ld() {
+        assert_eq!(
+            sniff("<?xml version=\"1.0\"?>\n<rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\"/>"),
+            Some(SniffedFormat::Graph(GraphFormat::RdfXml))
+        );
+        assert_eq!(
+            sniff("<rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\"/>"),
+            Some(SniffedFormat::Graph(GraphFormat::RdfXml))
+        );
+        assert_eq!(
+            sniff("{\n  \"@id\": \"http://example.com/s\"\n}"),
+            Some(SniffedFormat::Dataset(DatasetFormat::JsonLd))
+        );
+        assert_eq!(sniff(" \n# just a comment"), None);
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_sniff_compressed() -> io::Result<()> {
+        use crate::io::compression::CompressingWriter;
+        use std::io::{Cursor, Write};
+
+        let mut writer = CompressingWriter::new(Vec::new(), Some(Compression::Zstd))?;
+        writer.write_all(b"PREFIX ex: <http://example.com/>\nex:s ex:p ex:o .")?;
+        let mut reader = Cursor::new(writer.finish()?);
+        assert_eq!(
+            sniff_format(&mut reader)?,
+            Some(SniffedFormat::Graph(GraphFormat::Turtle))
+        );
+        assert_eq!(reader.position(), 0);
+        Ok(())
+    }
+}
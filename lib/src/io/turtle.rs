@@ -0,0 +1,828 @@
+//! A [Turtle](https://www.w3.org/TR/turtle/) and [TriG](https://www.w3.org/TR/trig/) serializer
+//! that groups the triples by subject and uses the Turtle abbreviations.
+
+use crate::error::invalid_input_error;
+use crate::io::relativize;
+use crate::model::vocab::{rdf, xsd};
+use crate::model::*;
+use oxiri::Iri;
+use rio_api::model as rio;
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::io::Write;
+
+/// Buffers the quads and writes them on [`finish`](TurtleWriter::finish)
+///
+/// The full graph is required to know which blank nodes could be inlined.
+pub(crate) struct TurtleWriter<W: Write> {
+    sink: W,
+    with_graphs: bool,
+    prefixes: Vec<(String, String)>,
+    base_iri: Option<Iri<String>>,
+    quads: Vec<Quad>,
+    seen: HashSet<Quad>,
+}
+
+impl<W: Write> TurtleWriter<W> {
+    /// Builds a new writer. If `with_graphs` is false, only triples in the default graph are allowed.
+    pub fn new(
+        sink: W,
+        prefixes: Vec<(String, String)>,
+        base_iri: Option<Iri<String>>,
+        with_graphs: bool,
+    ) -> Result<Self, io::Error> {
+        for (name, _) in &prefixes {
+            if !is_valid_prefix_name(name) {
+                return Err(invalid_input_error(format!(
+                    "'{}' is not a valid Turtle prefix name",
+                    name
+                )));
+            }
+        }
+        Ok(Self {
+            sink,
+            with_graphs,
+            prefixes,
+            base_iri,
+            quads: Vec::new(),
+            seen: HashSet::new(),
+        })
+    }
+
+    pub fn write(&mut self, quad: QuadRef<'_>) -> Result<(), io::Error> {
+        if !self.with_graphs && !quad.graph_name.is_default_graph() {
+            return Err(invalid_input_error(
+                "Turtle only allows triples in the default graph",
+            ));
+        }
+        let quad = quad.into_owned();
+        if self.seen.insert(quad.clone()) {
+            self.quads.push(quad);
+        }
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<W, io::Error> {
+        if let Some(base_iri) = &self.base_iri {
+            writeln!(self.sink, "@base <{}> .", base_iri.as_str())?;
+        }
+        for (name, iri) in &self.prefixes {
+            writeln!(self.sink, "@prefix {}: <{}> .", name, iri)?;
+        }
+        let mut is_first_block = self.base_iri.is_none() && self.prefixes.is_empty();
+
+        let inlinable = inlinable_blank_nodes(&self.quads);
+        let mut graphs = Vec::<(GraphNameRef<'_>, Description<'_>)>::new();
+        for quad in &self.quads {
+            let quad = quad.as_ref();
+            let position =
+                if let Some(position) = graphs.iter().position(|(g, _)| *g == quad.graph_name) {
+                    position
+                } else {
+                    graphs.push((quad.graph_name, Description::default()));
+                    graphs.len() - 1
+                };
+            graphs[position]
+                .1
+                .add(quad.subject, quad.predicate, quad.object);
+        }
+        // The default graph goes first
+        graphs.sort_by_key(|(g, _)| !g.is_default_graph());
+
+        let formatter = TermFormatter {
+            prefixes: &self.prefixes,
+            base_iri: self.base_iri.as_ref(),
+        };
+        for (graph_name, description) in &graphs {
+            if !is_first_block {
+                writeln!(self.sink)?;
+            }
+            is_first_block = false;
+            let mut writer = GraphWriter {
+                sink: &mut self.sink,
+                formatter: &formatter,
+                description,
+                inlinable: &inlinable,
+                written: HashSet::new(),
+                indent: "",
+            };
+            if graph_name.is_default_graph() {
+                writer.write_graph()?;
+            } else {
+                writeln!(writer.sink, "{} {{", formatter.graph_name(*graph_name))?;
+                writer.indent = "    ";
+                writer.write_graph()?;
+                writeln!(writer.sink, "}}")?;
+            }
+        }
+        Ok(self.sink)
+    }
+}
+
+/// The triples of a graph grouped by subject and predicate, keeping the insertion order
+#[derive(Default)]
+struct Description<'a> {
+    subjects: Vec<SubjectRef<'a>>,
+    predicates: HashMap<SubjectRef<'a>, Vec<(NamedNodeRef<'a>, Vec<TermRef<'a>>)>>,
+}
+
+impl<'a> Description<'a> {
+    fn add(&mut self, subject: SubjectRef<'a>, predicate: NamedNodeRef<'a>, object: TermRef<'a>) {
+        let subjects = &mut self.subjects;
+        let predicates = self.predicates.entry(subject).or_insert_with(|| {
+            subjects.push(subject);
+            Vec::new()
+        });
+        if let Some((_, objects)) = predicates.iter_mut().find(|(p, _)| *p == predicate) {
+            objects.push(object);
+        } else if predicate == rdf::TYPE {
+            // rdf:type is always written first
+            predicates.insert(0, (predicate, vec![object]));
+        } else {
+            predicates.push((predicate, vec![object]));
+        }
+    }
+}
+
+/// Returns the blank nodes that could be written using the `[]` syntax:
+/// the ones that are used once as object, outside of quoted triples, and only described in the same graph.
+fn inlinable_blank_nodes(quads: &[Quad]) -> HashSet<BlankNodeRef<'_>> {
+    let mut usages = HashMap::<BlankNodeRef<'_>, (usize, GraphNameRef<'_>)>::new();
+    let mut blocked = HashSet::new();
+    let mut descriptions = HashMap::<BlankNodeRef<'_>, GraphNameRef<'_>>::new();
+    for quad in quads {
+        let quad = quad.as_ref();
+        match quad.subject {
+            SubjectRef::BlankNode(subject) => {
+                if *descriptions.entry(subject).or_insert(quad.graph_name) != quad.graph_name {
+                    blocked.insert(subject);
+                }
+            }
+            SubjectRef::Triple(triple) => add_quoted_blank_nodes(triple, &mut blocked),
+            SubjectRef::NamedNode(_) => (),
+        }
+        match quad.object {
+            TermRef::BlankNode(object) => {
+                let usage = usages.entry(object).or_insert((0, quad.graph_name));
+                usage.0 += 1;
+                if usage.1 != quad.graph_name {
+                    blocked.insert(object);
+                }
+            }
+            TermRef::Triple(triple) => add_quoted_blank_nodes(triple, &mut blocked),
+            TermRef::NamedNode(_) | TermRef::Literal(_) => (),
+        }
+        if let GraphNameRef::BlankNode(graph_name) = quad.graph_name {
+            blocked.insert(graph_name);
+        }
+    }
+    usages
+        .into_iter()
+        .filter(|(node, (count, graph_name))| {
+            *count == 1
+                && !blocked.contains(node)
+                && descriptions.get(node).is_none_or(|g| g == graph_name)
+        })
+        .map(|(node, _)| node)
+        .collect()
+}
+
+fn add_quoted_blank_nodes<'a>(triple: &'a Triple, blank_nodes: &mut HashSet<BlankNodeRef<'a>>) {
+    match &triple.subject {
+        Subject::BlankNode(subject) => {
+            blank_nodes.insert(subject.as_ref());
+        }
+        Subject::Triple(triple) => add_quoted_blank_nodes(triple, blank_nodes),
+        Subject::NamedNode(_) => (),
+    }
+    match &triple.object {
+        Term::BlankNode(object) => {
+            blank_nodes.insert(object.as_ref());
+        }
+        Term::Triple(triple) => add_quoted_blank_nodes(triple, blank_nodes),
+        Term::NamedNode(_) | Term::Literal(_) => (),
+    }
+}
+
+struct GraphWriter<'a, 'b, W: Write> {
+    sink: &'b mut W,
+    formatter: &'b TermFormatter<'b>,
+    description: &'b Description<'a>,
+    inlinable: &'b HashSet<BlankNodeRef<'a>>,
+    written: HashSet<BlankNodeRef<'a>>,
+    indent: &'static str,
+}
+
+impl<'a, 'b, W: Write> GraphWriter<'a, 'b, W> {
+    fn write_graph(&mut self) -> Result<(), io::Error> {
+        let mut is_first = true;
+        for subject in &self.description.subjects {
+            if let SubjectRef::BlankNode(node) = subject {
+                if self.inlinable.contains(node) {
+                    continue; // Written when used as object
+                }
+            }
+            self.write_subject_block(*subject, &mut is_first)?;
+        }
+        // Inlinable blank nodes that are only used inside of cycles have not been written yet
+        for subject in &self.description.subjects {
+            if let SubjectRef::BlankNode(node) = subject {
+                if !self.written.contains(node) && self.inlinable.contains(node) {
+                    self.write_subject_block(*subject, &mut is_first)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn write_subject_block(
+        &mut self,
+        subject: SubjectRef<'a>,
+        is_first: &mut bool,
+    ) -> Result<(), io::Error> {
+        if !*is_first {
+            writeln!(self.sink)?;
+        }
+        *is_first = false;
+        if let SubjectRef::BlankNode(node) = subject {
+            self.written.insert(node);
+        }
+        write!(
+            self.sink,
+            "{}{} ",
+            self.indent,
+            self.formatter.subject(subject)
+        )?;
+        let separator = format!(" ;\n{}    ", self.indent);
+        self.write_predicates(subject, &separator)?;
+        writeln!(self.sink, " .")
+    }
+
+    fn write_predicates(
+        &mut self,
+        subject: SubjectRef<'a>,
+        separator: &str,
+    ) -> Result<(), io::Error> {
+        let predicates = match self.description.predicates.get(&subject) {
+            Some(predicates) => predicates,
+            None => return Ok(()),
+        };
+        for (i, (predicate, objects)) in predicates.iter().enumerate() {
+            if i > 0 {
+                write!(self.sink, "{}", separator)?;
+            }
+            write!(self.sink, "{} ", self.formatter.predicate(*predicate))?;
+            for (j, object) in objects.iter().enumerate() {
+                if j > 0 {
+                    write!(self.sink, ", ")?;
+                }
+                self.write_object(*object)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_object(&mut self, object: TermRef<'a>) -> Result<(), io::Error> {
+        match object {
+            TermRef::BlankNode(node)
+                if self.inlinable.contains(&node) && !self.written.contains(&node) =>
+            {
+                if let Some((items, cells)) = self.list_items(node) {
+                    self.written.extend(cells);
+                    write!(self.sink, "(")?;
+                    for item in items {
+                        write!(self.sink, " ")?;
+                        self.write_object(item)?;
+                    }
+                    write!(self.sink, " )")
+                } else {
+                    self.written.insert(node);
+                    let subject = SubjectRef::BlankNode(node);
+                    if self.description.predicates.contains_key(&subject) {
+                        write!(self.sink, "[ ")?;
+                        self.write_predicates(subject, " ; ")?;
+                        write!(self.sink, " ]")
+                    } else {
+                        write!(self.sink, "[]")
+                    }
+                }
+            }
+            TermRef::NamedNode(node) if node == rdf::NIL => write!(self.sink, "()"),
+            _ => write!(self.sink, "{}", self.formatter.term(object)),
+        }
+    }
+
+    /// Returns the list items and the list cells if the given blank node is the head of a well-formed list
+    fn list_items(
+        &self,
+        head: BlankNodeRef<'a>,
+    ) -> Option<(Vec<TermRef<'a>>, Vec<BlankNodeRef<'a>>)> {
+        let mut items = Vec::new();
+        let mut cells = Vec::new();
+        let mut current = head;
+        loop {
+            if !self.inlinable.contains(&current)
+                || self.written.contains(&current)
+                || cells.contains(&current)
+            {
+                return None;
+            }
+            let predicates = self
+                .description
+                .predicates
+                .get(&SubjectRef::BlankNode(current))?;
+            if predicates.len() != 2 {
+                return None;
+            }
+            let value = |property| {
+                predicates
+                    .iter()
+                    .find(|(p, _)| *p == property)
+                    .filter(|(_, objects)| objects.len() == 1)
+                    .map(|(_, objects)| objects[0])
+            };
+            let first = value(rdf::FIRST)?;
+            let rest = value(rdf::REST)?;
+            items.push(first);
+            cells.push(current);
+            match rest {
+                TermRef::NamedNode(rest) if rest == rdf::NIL => return Some((items, cells)),
+                TermRef::BlankNode(rest) => current = rest,
+                _ => return None,
+            }
+        }
+    }
+}
+
+/// Serializes terms using the prefixes, the base IRI and the Turtle literal abbreviations
+struct TermFormatter<'a> {
+    prefixes: &'a [(String, String)],
+    base_iri: Option<&'a Iri<String>>,
+}
+
+impl TermFormatter<'_> {
+    fn named_node(&self, node: NamedNodeRef<'_>) -> String {
+        let iri = node.as_str();
+        if let Some((name, local)) = self
+            .prefixes
+            .iter()
+            .filter_map(|(name, prefix)| Some((name, iri.strip_prefix(prefix.as_str())?)))
+            .filter(|(_, local)| is_valid_local_name(local))
+            .min_by_key(|(_, local)| local.len())
+        {
+            return format!("{}:{}", name, local);
+        }
+        if let Some(base_iri) = self.base_iri {
+            if base_iri.as_str() == iri {
+                return "<>".to_owned();
+            }
+            if let Some(relative) = relativize(base_iri, iri) {
+                return format!("<{}>", relative);
+            }
+        }
+        node.to_string()
+    }
+
+    fn predicate(&self, predicate: NamedNodeRef<'_>) -> String {
+        if predicate == rdf::TYPE {
+            "a".to_owned()
+        } else {
+            self.named_node(predicate)
+        }
+    }
+
+    fn subject(&self, subject: SubjectRef<'_>) -> String {
+        match subject {
+            SubjectRef::NamedNode(node) => self.named_node(node),
+            SubjectRef::BlankNode(node) => node.to_string(),
+            SubjectRef::Triple(triple) => self.triple(triple),
+        }
+    }
+
+    fn term(&self, term: TermRef<'_>) -> String {
+        match term {
+            TermRef::NamedNode(node) => self.named_node(node),
+            TermRef::BlankNode(node) => node.to_string(),
+            TermRef::Literal(literal) => self.literal(literal),
+            TermRef::Triple(triple) => self.triple(triple),
+        }
+    }
+
+    fn graph_name(&self, graph_name: GraphNameRef<'_>) -> String {
+        match graph_name {
+            GraphNameRef::NamedNode(node) => self.named_node(node),
+            GraphNameRef::BlankNode(node) => node.to_string(),
+            GraphNameRef::DefaultGraph => String::new(),
+        }
+    }
+
+    fn triple(&self, triple: &Triple) -> String {
+        format!(
+            "<< {} {} {} >>",
+            self.subject(triple.subject.as_ref()),
+            self.predicate(triple.predicate.as_ref()),
+            self.term(triple.object.as_ref())
+        )
+    }
+
+    fn literal(&self, literal: LiteralRef<'_>) -> String {
+        let value = literal.value();
+        let datatype = literal.datatype();
+        let quoted = rio::Literal::Simple { value }.to_string();
+        if let Some(language) = literal.language() {
+            format!("{}@{}", quoted, language)
+        } else if datatype == xsd::STRING {
+            quoted
+        } else if (datatype == xsd::INTEGER && is_turtle_integer(value))
+            || (datatype == xsd::DECIMAL && is_turtle_decimal(value))
+            || (datatype == xsd::DOUBLE && is_turtle_double(value))
+            || (datatype == xsd::BOOLEAN && (value == "true" || value == "false"))
+        {
+            value.to_owned()
+        } else {
+            format!("{}^^{}", quoted, self.named_node(datatype))
+        }
+    }
+}
+
+fn is_turtle_integer(value: &str) -> bool {
+    // [19] 	INTEGER 	::= 	[+-]? [0-9]+
+    let value = value.strip_prefix(&['+', '-'][..]).unwrap_or(value);
+    !value.is_empty() && value.bytes().all(|c| c.is_ascii_digit())
+}
+
+fn is_turtle_decimal(value: &str) -> bool {
+    // [20] 	DECIMAL 	::= 	[+-]? [0-9]* '.' [0-9]+
+    let value = value.strip_prefix(&['+', '-'][..]).unwrap_or(value);
+    if let Some((before, after)) = split_once(value, '.') {
+        before.bytes().all(|c| c.is_ascii_digit())
+            && !after.is_empty()
+            && after.bytes().all(|c| c.is_ascii_digit())
+    } else {
+        false
+    }
+}
+
+fn is_turtle_double(value: &str) -> bool {
+    // [21] 	DOUBLE 	::= 	[+-]? ([0-9]+ '.' [0-9]* EXPONENT | '.' [0-9]+ EXPONENT | [0-9]+ EXPONENT)
+    // [154s] 	EXPONENT 	::= 	[eE] [+-]? [0-9]+
+    let value = value.strip_prefix(&['+', '-'][..]).unwrap_or(value);
+    let (mantissa, exponent) = match value.find(&['e', 'E'][..]) {
+        Some(position) => (&value[..position], &value[position + 1..]),
+        None => return false,
+    };
+    let (before, after) = split_once(mantissa, '.').unwrap_or((mantissa, ""));
+    (!before.is_empty() || !after.is_empty())
+        && before.bytes().all(|c| c.is_ascii_digit())
+        && after.bytes().all(|c| c.is_ascii_digit())
+        && is_turtle_integer(exponent)
+}
+
+fn split_once(value: &str, separator: char) -> Option<(&str, &str)> {
+    let position = value.find(separator)?;
+    Some((&value[..position], &value[position + 1..]))
+}
+
+fn is_valid_prefix_name(name: &str) -> bool {
+    // [167s] 	PN_PREFIX 	::= 	PN_CHARS_BASE ((PN_CHARS | '.')* PN_CHARS)?
+    // We only allow ASCII characters
+    let mut chars = name.chars();
+    match chars.next() {
+        None => true,
+        Some(c) if c.is_ascii_alphabetic() => {
+            !name.ends_with('.')
+                && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        }
+        Some(_) => false,
+    }
+}
+
+fn is_valid_local_name(name: &str) -> bool {
+    // [168s] 	PN_LOCAL 	::= 	(PN_CHARS_U | ':' | [0-9] | PLX) ((PN_CHARS | '.' | ':' | PLX)* (PN_CHARS | ':' | PLX))?
+    // We only allow ASCII characters and do not use escapes
+    let mut chars = name.chars();
+    match chars.next() {
+        None => true,
+        Some(c) if c.is_ascii_alphanumeric() || matches!(c, '_' | ':') => {
+            !name.ends_with('.')
+                && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | ':'))
+        }
+        Some(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::{
+        DatasetFormat, DatasetParser, DatasetSerializer, GraphFormat, GraphParser, GraphSerializer,
+    };
+    use crate::model::*;
+    use regex::Regex;
+    use std::io::Cursor;
+
+    const PREFIXES: &str = "@prefix ex: <http://example.com/> .
+        @prefix rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#> .
+        @prefix xsd: <http://www.w3.org/2001/XMLSchema#> .\n";
+
+    /// The parser returns the triples of a same statement in reverse order
+    /// so the test inputs only contain one triple per statement
+    fn parse(trig: &str) -> Vec<Quad> {
+        DatasetParser::from_format(DatasetFormat::TriG)
+            .read_quads(Cursor::new(format!("{}{}", PREFIXES, trig)))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    }
+
+    fn serialize(quads: &[Quad], serializer: &DatasetSerializer) -> String {
+        let mut buffer = Vec::new();
+        let mut writer = serializer.quad_writer(&mut buffer).unwrap();
+        for quad in quads {
+            writer.write(quad).unwrap();
+        }
+        writer.finish().unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    /// Checks that the serialization is the expected one and that it could be parsed back
+    fn assert_round_trip(trig: &str, expected: &str, serializer: &DatasetSerializer) {
+        let quads = parse(trig);
+        let actual = serialize(&quads, serializer);
+        assert_eq!(normalize_blank_nodes(&actual), expected);
+        let mut parsed = DatasetParser::from_format(DatasetFormat::TriG)
+            .read_quads(Cursor::new(actual.as_str()))
+            .unwrap()
+            .map(|q| normalize_blank_nodes(&q.unwrap().to_string()))
+            .collect::<Vec<_>>();
+        let mut quads = quads.into_iter().map(|q| q.to_string()).collect::<Vec<_>>();
+        quads.sort();
+        quads.dedup();
+        let mut quads = quads
+            .iter()
+            .map(|q| normalize_blank_nodes(q))
+            .collect::<Vec<_>>();
+        parsed.sort();
+        quads.sort();
+        assert_eq!(parsed, quads);
+    }
+
+    /// Blank node identifiers are not stable so we replace them all by the same one
+    fn normalize_blank_nodes(value: &str) -> String {
+        Regex::new("_:[a-zA-Z0-9]+")
+            .unwrap()
+            .replace_all(value, "_:b")
+            .into_owned()
+    }
+
+    fn serializer() -> DatasetSerializer {
+        DatasetSerializer::from_format(DatasetFormat::TriG)
+            .pretty()
+            .with_prefix("ex", "http://example.com/")
+            .unwrap()
+    }
+
+    #[test]
+    fn test_grouping() {
+        assert_round_trip(
+            "ex:s ex:p ex:o1 .
+            ex:s2 ex:p ex:o .
+            ex:s ex:p ex:o2 .
+            ex:s a ex:C .
+            ex:s ex:q ex:o .
+            ex:s ex:p ex:o1 .",
+            "@prefix ex: <http://example.com/> .\n\nex:s a ex:C ;\n    ex:p ex:o1, ex:o2 ;\n    ex:q ex:o .\n\nex:s2 ex:p ex:o .\n",
+            &serializer(),
+        );
+    }
+
+    #[test]
+    fn test_without_prefixes() {
+        assert_round_trip(
+            "<http://example.com/s> <http://example.com/p> \"o\" .",
+            "<http://example.com/s> <http://example.com/p> \"o\" .\n",
+            &DatasetSerializer::from_format(DatasetFormat::TriG).pretty(),
+        );
+    }
+
+    #[test]
+    fn test_not_pretty() {
+        let quads = parse(
+            "ex:s ex:p ex:o1 .
+            ex:g { ex:s ex:p ex:o2 . }",
+        );
+        let mut buffer = Vec::new();
+        let mut writer = DatasetSerializer::from_format(DatasetFormat::TriG)
+            .with_prefix("ex", "http://example.com/")
+            .unwrap()
+            .quad_writer(&mut buffer)
+            .unwrap();
+        writer.write(&quads[0]).unwrap();
+        writer.write(&quads[1]).unwrap();
+        writer.finish().unwrap();
+        let actual = String::from_utf8(buffer).unwrap();
+        assert!(!actual.contains("@prefix"));
+        let parsed = DatasetParser::from_format(DatasetFormat::TriG)
+            .read_quads(Cursor::new(actual.as_str()))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(parsed, quads);
+    }
+
+    #[test]
+    fn test_iris() {
+        assert_round_trip(
+            "<http://example.com/dir/doc> <http://example.com/foo/bar> <http://example.com/dir/a> .
+            <http://example.com/dir/doc> <http://example.com/foo/bar> <http://example.com/dir/doc#b> .
+            <http://example.com/dir/doc> <http://example.com/foo/bar> <http://example.com/a.> .
+            <http://example.com/dir/doc> <http://example.com/foo/bar> <http://example.com/a:b> .
+            <http://example.com/dir/doc> <http://example.com/foo/bar> <http://example.com/> .
+            <http://example.com/dir/doc> <http://example.com/foo/bar> <http://example.com/a%20> .
+            <http://example.com/dir/doc> <http://example.com/foo/bar> <http://example.org/a> .",
+            "@base <http://example.com/dir/doc> .\n@prefix ex: <http://example.com/> .\n@prefix foo: <http://example.com/foo/> .\n\n<> foo:bar <a>, <doc#b>, <http://example.com/a.>, ex:a:b, ex:, <http://example.com/a%20>, <http://example.org/a> .\n",
+            &serializer()
+                .with_prefix("foo", "http://example.com/foo/")
+                .unwrap()
+                .with_base_iri("http://example.com/dir/doc")
+                .unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_literals() {
+        assert_round_trip(
+            r#"ex:s ex:p "a\"b\nc" .
+            ex:s ex:p "a"@en-us .
+            ex:s ex:p 1 .
+            ex:s ex:p -1.5 .
+            ex:s ex:p 1.5e10 .
+            ex:s ex:p true .
+            ex:s ex:p "1.0"^^xsd:integer .
+            ex:s ex:p "1."^^xsd:decimal .
+            ex:s ex:p "1"^^xsd:double .
+            ex:s ex:p "foo"^^xsd:boolean .
+            ex:s ex:p "a"^^ex:dt ."#,
+            "@prefix ex: <http://example.com/> .\n\nex:s ex:p \"a\\\"b\\nc\", \"a\"@en-us, 1, -1.5, 1.5e10, true, \"1.0\"^^<http://www.w3.org/2001/XMLSchema#integer>, \"1.\"^^<http://www.w3.org/2001/XMLSchema#decimal>, \"1\"^^<http://www.w3.org/2001/XMLSchema#double>, \"foo\"^^<http://www.w3.org/2001/XMLSchema#boolean>, \"a\"^^ex:dt .\n",
+            &serializer(),
+        );
+    }
+
+    #[test]
+    fn test_blank_nodes() {
+        assert_round_trip(
+            "ex:s ex:p _:a .
+            _:a ex:p _:b .
+            _:b ex:q ex:o .
+            _:b ex:r 1 .
+            ex:s ex:p _:empty .
+            ex:s ex:shared _:shared .
+            ex:s2 ex:shared _:shared .
+            _:shared ex:p ex:o .
+            _:subject ex:p ex:o .",
+            "@prefix ex: <http://example.com/> .\n\nex:s ex:p [ ex:p [ ex:q ex:o ; ex:r 1 ] ], [] ;\n    ex:shared _:b .\n\nex:s2 ex:shared _:b .\n\n_:b ex:p ex:o .\n\n_:b ex:p ex:o .\n",
+            &serializer(),
+        );
+    }
+
+    #[test]
+    fn test_blank_node_cycle() {
+        assert_round_trip(
+            "_:a ex:p _:b .
+            _:b ex:p _:a .",
+            "@prefix ex: <http://example.com/> .\n\n_:b ex:p [ ex:p _:b ] .\n",
+            &serializer(),
+        );
+    }
+
+    #[test]
+    fn test_lists() {
+        assert_round_trip(
+            "ex:s ex:p _:l1 .
+            _:l1 rdf:first 1 .
+            _:l1 rdf:rest _:l2 .
+            _:l2 rdf:first _:nested .
+            _:nested rdf:first ex:a .
+            _:nested rdf:rest rdf:nil .
+            _:l2 rdf:rest _:l3 .
+            _:l3 rdf:first _:node .
+            _:node ex:p ex:o .
+            _:l3 rdf:rest rdf:nil .
+            ex:s ex:p rdf:nil .
+            ex:s ex:notList _:l .
+            _:l rdf:first 1 .
+            _:l rdf:rest ex:foo .
+            rdf:nil ex:p ex:o .",
+            "@prefix ex: <http://example.com/> .\n\nex:s ex:p ( 1 ( ex:a ) [ ex:p ex:o ] ), () ;\n    ex:notList [ <http://www.w3.org/1999/02/22-rdf-syntax-ns#first> 1 ; <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> ex:foo ] .\n\n<http://www.w3.org/1999/02/22-rdf-syntax-ns#nil> ex:p ex:o .\n",
+            &serializer(),
+        );
+    }
+
+    #[test]
+    fn test_quoted_triples() {
+        assert_round_trip(
+            "<< _:a a ex:C >> ex:p << ex:s ex:p 1 >> .
+            _:a ex:p ex:o .",
+            "@prefix ex: <http://example.com/> .\n\n<< _:b a ex:C >> ex:p << ex:s ex:p 1 >> .\n\n_:b ex:p ex:o .\n",
+            &serializer(),
+        );
+    }
+
+    #[test]
+    fn test_graphs() {
+        assert_round_trip(
+            "ex:g { ex:s ex:p ex:o }
+            ex:g { ex:s ex:q _:a }
+            ex:g { _:a ex:p ex:o }
+            ex:g { ex:s2 ex:p ex:o }
+            ex:s ex:p ex:o .
+            _:g { ex:s ex:p _:shared }
+            ex:s ex:p _:shared .",
+            "@prefix ex: <http://example.com/> .\n\nex:s ex:p ex:o, _:b .\n\nex:g {\n    ex:s ex:p ex:o ;\n        ex:q [ ex:p ex:o ] .\n\n    ex:s2 ex:p ex:o .\n}\n\n_:b {\n    ex:s ex:p _:b .\n}\n",
+            &serializer(),
+        );
+    }
+
+    #[test]
+    fn test_turtle() {
+        let triples = GraphParser::from_format(GraphFormat::Turtle)
+            .read_triples(Cursor::new(format!(
+                "{}ex:s ex:p ex:o . ex:s ex:q ex:o .",
+                PREFIXES
+            )))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let mut buffer = Vec::new();
+        let mut writer = GraphSerializer::from_format(GraphFormat::Turtle)
+            .pretty()
+            .with_prefix("ex", "http://example.com/")
+            .unwrap()
+            .triple_writer(&mut buffer)
+            .unwrap();
+        for triple in &triples {
+            writer.write(triple).unwrap();
+        }
+        writer.finish().unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "@prefix ex: <http://example.com/> .\n\nex:s ex:p ex:o ;\n    ex:q ex:o .\n"
+        );
+    }
+
+    #[test]
+    fn test_prefixes_and_base_round_trip() {
+        let file = "@base <http://example.com/dir/> .\n@prefix ex: <http://example.com/> .\n@prefix foo: <http://example.com/foo/> .\n\n<s> ex:p foo:o, <o> .\n";
+        for parser in [
+            GraphParser::from_format(GraphFormat::Turtle),
+            GraphParser::from_format(GraphFormat::Turtle).lenient(|e| panic!("{}", e)),
+        ] {
+            let is_lenient = parser.is_lenient();
+            let mut reader = parser.read_triples(Cursor::new(file)).unwrap();
+            let triples = (&mut reader).collect::<Result<Vec<_>, _>>().unwrap();
+            let mut prefixes = reader.prefixes().iter().collect::<Vec<_>>();
+            prefixes.sort();
+            assert_eq!(
+                prefixes,
+                vec![
+                    (&"ex".to_owned(), &"http://example.com/".to_owned()),
+                    (&"foo".to_owned(), &"http://example.com/foo/".to_owned())
+                ]
+            );
+            if !is_lenient {
+                // The non lenient parser does not report the base IRI changes
+                assert_eq!(reader.base_iri(), None);
+                continue;
+            }
+            assert_eq!(reader.base_iri(), Some("http://example.com/dir/"));
+
+            let mut serializer = GraphSerializer::from_format(GraphFormat::Turtle)
+                .pretty()
+                .with_base_iri(reader.base_iri().unwrap())
+                .unwrap();
+            for (name, iri) in prefixes {
+                serializer = serializer.with_prefix(name, iri).unwrap();
+            }
+            let mut buffer = Vec::new();
+            let mut writer = serializer.triple_writer(&mut buffer).unwrap();
+            for triple in &triples {
+                writer.write(triple).unwrap();
+            }
+            writer.finish().unwrap();
+            assert_eq!(String::from_utf8(buffer).unwrap(), file);
+        }
+    }
+
+    #[test]
+    fn test_invalid_prefixes() {
+        assert!(GraphSerializer::from_format(GraphFormat::Turtle)
+            .pretty()
+            .with_prefix("1a", "http://example.com/")
+            .unwrap()
+            .triple_writer(Vec::new())
+            .is_err());
+        assert!(GraphSerializer::from_format(GraphFormat::Turtle)
+            .pretty()
+            .with_prefix("a", "foo")
+            .is_err());
+    }
+}
@@ -1,12 +1,20 @@
 //! Utilities to write RDF graphs and datasets
 
-use crate::io::{DatasetFormat, GraphFormat};
+use crate::io::compression::CompressingWriter;
+use crate::io::jsonld::{parse_serialization_context, JsonLdWriter, NoDocumentLoader};
+use crate::io::turtle::TurtleWriter;
+#[cfg(feature = "compression")]
+use crate::io::Compression;
+use crate::io::{DatasetFormat, DocumentLoader, GraphFormat};
 use crate::model::*;
+use oxiri::{Iri, IriParseError};
 use rio_api::formatter::{QuadsFormatter, TriplesFormatter};
+use rio_api::model as rio;
 use rio_turtle::{NQuadsFormatter, NTriplesFormatter, TriGFormatter, TurtleFormatter};
 use rio_xml::RdfXmlFormatter;
 use std::io;
 use std::io::Write;
+use std::sync::Arc;
 
 /// A serializer for RDF graph serialization formats.
 ///
@@ -31,22 +39,129 @@ use std::io::Write;
 ///assert_eq!(buffer.as_slice(), "<http://example.com/s> <http://example.com/p> <http://example.com/o> .\n".as_bytes());
 /// # Result::<_,Box<dyn std::error::Error>>::Ok(())
 /// ```
-#[allow(missing_copy_implementations)]
+///
+/// The Turtle triples are written as soon as they are given to the [`TripleWriter`].
+/// A [`pretty`](GraphSerializer::pretty) serializer groups the triples by subject and uses prefixes, the `a` keyword, `[]` for blank nodes used only once and `()` for lists.
+/// It keeps all the triples in memory until [`finish`](TripleWriter::finish()) is called.
+/// ```
+/// use oxigraph::io::{GraphFormat, GraphSerializer};
+/// use oxigraph::model::*;
+/// use oxigraph::model::vocab::rdf;
+///
+/// let ex = NamedNode::new("http://example.com/s")?;
+/// let bnode = BlankNode::default();
+/// let mut buffer = Vec::new();
+/// let mut writer = GraphSerializer::from_format(GraphFormat::Turtle)
+///     .pretty()
+///     .with_prefix("ex", "http://example.com/")?
+///     .triple_writer(&mut buffer)?;
+/// writer.write(TripleRef::new(&ex, rdf::TYPE, NamedNodeRef::new("http://example.com/C")?))?;
+/// writer.write(TripleRef::new(&ex, NamedNodeRef::new("http://example.com/p")?, &bnode))?;
+/// writer.write(TripleRef::new(&bnode, NamedNodeRef::new("http://example.com/q")?, LiteralRef::new_simple_literal("o")))?;
+/// writer.finish()?;
+///
+///assert_eq!(String::from_utf8(buffer)?, "@prefix ex: <http://example.com/> .\n\nex:s a ex:C ;\n    ex:p [ ex:q \"o\" ] .\n");
+/// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+/// ```
 pub struct GraphSerializer {
     format: GraphFormat,
+    pretty: bool,
+    prefixes: Vec<(String, String)>,
+    base_iri: Option<Iri<String>>,
+    #[cfg(feature = "compression")]
+    compression: Option<Compression>,
 }
 
 impl GraphSerializer {
     /// Builds a serializer for the given format
     pub fn from_format(format: GraphFormat) -> Self {
-        Self { format }
+        Self {
+            format,
+            pretty: false,
+            prefixes: Vec::new(),
+            base_iri: None,
+            #[cfg(feature = "compression")]
+            compression: None,
+        }
+    }
+
+    /// Writes pretty Turtle grouping the triples by subject and using prefixes and the Turtle abbreviations
+    ///
+    /// Warning: all the triples are kept in memory until [`finish`](TripleWriter::finish()) is called
+    /// because the full graph is required to know which blank nodes could be inlined.
+    /// It has no effect on the other formats.
+    pub fn pretty(mut self) -> Self {
+        self.pretty = true;
+        self
+    }
+
+    /// Adds a prefix used to abbreviate the IRIs in the serializations supporting it (only [`pretty`](GraphSerializer::pretty) Turtle at the moment)
+    ///
+    /// The prefix name is validated when [`triple_writer`](GraphSerializer::triple_writer) is called.
+    pub fn with_prefix(
+        mut self,
+        prefix_name: impl Into<String>,
+        prefix_iri: impl Into<String>,
+    ) -> Result<Self, IriParseError> {
+        add_prefix(&mut self.prefixes, prefix_name.into(), prefix_iri.into())?;
+        Ok(self)
+    }
+
+    /// Provides the base IRI used to write relative IRIs in the serializations supporting it (only [`pretty`](GraphSerializer::pretty) Turtle at the moment)
+    pub fn with_base_iri(mut self, base_iri: impl Into<String>) -> Result<Self, IriParseError> {
+        self.base_iri = Some(Iri::parse(base_iri.into())?);
+        Ok(self)
+    }
+
+    /// Compresses the output using the given compression format
+    ///
+    /// Requires the `compression` feature.
+    ///
+    /// ```
+    /// use oxigraph::io::{Compression, GraphFormat, GraphParser, GraphSerializer};
+    /// use oxigraph::model::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut writer = GraphSerializer::from_format(GraphFormat::NTriples)
+    ///     .with_compression(Compression::Gzip)
+    ///     .triple_writer(&mut buffer)?;
+    /// writer.write(&Triple {
+    ///    subject: NamedNode::new("http://example.com/s")?.into(),
+    ///    predicate: NamedNode::new("http://example.com/p")?,
+    ///    object: NamedNode::new("http://example.com/o")?.into()
+    /// })?;
+    /// writer.finish()?;
+    ///
+    /// // The parsers detect the compression
+    /// let triples = GraphParser::from_format(GraphFormat::NTriples)
+    ///     .read_triples(Cursor::new(buffer))?
+    ///     .collect::<Result<Vec<_>,_>>()?;
+    /// assert_eq!(triples[0].subject.to_string(), "<http://example.com/s>");
+    /// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[cfg(feature = "compression")]
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    fn compress<W: Write>(&self, writer: W) -> io::Result<CompressingWriter<W>> {
+        #[cfg(feature = "compression")]
+        return CompressingWriter::new(writer, self.compression);
+        #[cfg(not(feature = "compression"))]
+        return CompressingWriter::new(writer);
     }
 
     /// Returns a `TripleWriter` allowing writing triples into the given [`Write`](std::io::Write) implementation
     pub fn triple_writer<W: Write>(&self, writer: W) -> Result<TripleWriter<W>, io::Error> {
+        let writer = self.compress(writer)?;
         Ok(TripleWriter {
             formatter: match self.format {
                 GraphFormat::NTriples => TripleWriterKind::NTriples(NTriplesFormatter::new(writer)),
+                GraphFormat::Turtle if self.pretty => TripleWriterKind::PrettyTurtle(
+                    TurtleWriter::new(writer, self.prefixes.clone(), self.base_iri.clone(), false)?,
+                ),
                 GraphFormat::Turtle => TripleWriterKind::Turtle(TurtleFormatter::new(writer)),
                 GraphFormat::RdfXml => TripleWriterKind::RdfXml(RdfXmlFormatter::new(writer)?),
             },
@@ -54,6 +169,12 @@ impl GraphSerializer {
     }
 }
 
+impl From<GraphFormat> for GraphSerializer {
+    fn from(format: GraphFormat) -> Self {
+        Self::from_format(format)
+    }
+}
+
 /// Allows writing triples.
 /// Could be built using a [`GraphSerializer`].
 ///
@@ -81,9 +202,10 @@ pub struct TripleWriter<W: Write> {
 }
 
 enum TripleWriterKind<W: Write> {
-    NTriples(NTriplesFormatter<W>),
-    Turtle(TurtleFormatter<W>),
-    RdfXml(RdfXmlFormatter<W>),
+    NTriples(NTriplesFormatter<CompressingWriter<W>>),
+    Turtle(TurtleFormatter<CompressingWriter<W>>),
+    PrettyTurtle(TurtleWriter<CompressingWriter<W>>),
+    RdfXml(RdfXmlFormatter<CompressingWriter<W>>),
 }
 
 impl<W: Write> TripleWriter<W> {
@@ -91,9 +213,18 @@ impl<W: Write> TripleWriter<W> {
     pub fn write<'a>(&mut self, triple: impl Into<TripleRef<'a>>) -> Result<(), io::Error> {
         let triple = triple.into();
         match &mut self.formatter {
-            TripleWriterKind::NTriples(formatter) => formatter.format(&triple.into())?,
-            TripleWriterKind::Turtle(formatter) => formatter.format(&triple.into())?,
-            TripleWriterKind::RdfXml(formatter) => formatter.format(&triple.into())?,
+            TripleWriterKind::NTriples(formatter) => {
+                with_rio_triple(triple, &mut |t| formatter.format(t))?
+            }
+            TripleWriterKind::Turtle(formatter) => {
+                with_rio_triple(triple, &mut |t| formatter.format(t))?
+            }
+            TripleWriterKind::PrettyTurtle(writer) => {
+                writer.write(triple.in_graph(GraphNameRef::DefaultGraph))?
+            }
+            TripleWriterKind::RdfXml(formatter) => {
+                with_rio_triple(triple, &mut |t| formatter.format(t))?
+            }
         }
         Ok(())
     }
@@ -101,10 +232,12 @@ impl<W: Write> TripleWriter<W> {
     /// Writes the last bytes of the file
     pub fn finish(self) -> Result<(), io::Error> {
         match self.formatter {
-            TripleWriterKind::NTriples(formatter) => formatter.finish(),
+            TripleWriterKind::NTriples(formatter) => formatter.finish()?,
             TripleWriterKind::Turtle(formatter) => formatter.finish()?,
+            TripleWriterKind::PrettyTurtle(writer) => writer.finish()?,
             TripleWriterKind::RdfXml(formatter) => formatter.finish()?,
-        };
+        }
+        .finish()?;
         Ok(())
     }
 }
@@ -114,6 +247,7 @@ impl<W: Write> TripleWriter<W> {
 /// It currently supports the following formats:
 /// * [N-Quads](https://www.w3.org/TR/n-quads/) ([`DatasetFormat::NQuads`](super::DatasetFormat::NQuads))
 /// * [TriG](https://www.w3.org/TR/trig/) ([`DatasetFormat::TriG`](super::DatasetFormat::TriG))
+/// * [JSON-LD](https://www.w3.org/TR/json-ld11/) ([`DatasetFormat::JsonLd`](super::DatasetFormat::JsonLd))
 ///
 /// ```
 /// use oxigraph::io::{DatasetFormat, DatasetSerializer};
@@ -132,28 +266,175 @@ impl<W: Write> TripleWriter<W> {
 ///assert_eq!(buffer.as_slice(), "<http://example.com/s> <http://example.com/p> <http://example.com/o> <http://example.com/g> .\n".as_bytes());
 /// # Result::<_,Box<dyn std::error::Error>>::Ok(())
 /// ```
-#[allow(missing_copy_implementations)]
+///
+/// The TriG quads are written as soon as they are given to the [`QuadWriter`].
+/// A [`pretty`](DatasetSerializer::pretty) serializer groups the triples by graph and subject like the pretty Turtle one of [`GraphSerializer`].
+/// It keeps all the quads in memory until [`finish`](QuadWriter::finish()) is called.
+///
+/// JSON-LD documents are written in the [expanded form](https://www.w3.org/TR/json-ld11/#expanded-document-form)
+/// unless a context is provided using [`with_json_ld_context`](DatasetSerializer::with_json_ld_context).
+/// The quads are also kept in memory until [`finish`](QuadWriter::finish()) is called.
+/// ```
+/// use oxigraph::io::{DatasetFormat, DatasetSerializer};
+/// use oxigraph::model::*;
+///
+/// let mut buffer = Vec::new();
+/// let mut writer = DatasetSerializer::from_format(DatasetFormat::JsonLd)
+///     .with_json_ld_context(r#"{"@vocab": "http://example.com/"}"#)
+///     .quad_writer(&mut buffer)?;
+/// writer.write(&Quad {
+///    subject: NamedNode::new("http://example.com/s")?.into(),
+///    predicate: NamedNode::new("http://example.com/p")?,
+///    object: Literal::new_simple_literal("o").into(),
+///    graph_name: GraphName::DefaultGraph,
+/// })?;
+/// writer.finish()?;
+///
+///assert_eq!(buffer.as_slice(), r#"{"@context":{"@vocab":"http://example.com/"},"@id":"http://example.com/s","p":"o"}"#.as_bytes());
+/// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+/// ```
 pub struct DatasetSerializer {
     format: DatasetFormat,
+    pretty: bool,
+    prefixes: Vec<(String, String)>,
+    base_iri: Option<Iri<String>>,
+    json_ld_context: Option<String>,
+    document_loader: Arc<dyn DocumentLoader>,
+    #[cfg(feature = "compression")]
+    compression: Option<Compression>,
 }
 
 impl DatasetSerializer {
     /// Builds a serializer for the given format
     pub fn from_format(format: DatasetFormat) -> Self {
-        Self { format }
+        Self {
+            format,
+            pretty: false,
+            prefixes: Vec::new(),
+            base_iri: None,
+            json_ld_context: None,
+            document_loader: Arc::new(NoDocumentLoader),
+            #[cfg(feature = "compression")]
+            compression: None,
+        }
+    }
+
+    /// Writes pretty TriG grouping the triples by graph and subject and using prefixes and the Turtle abbreviations
+    ///
+    /// Warning: all the quads are kept in memory until [`finish`](QuadWriter::finish()) is called
+    /// because the full dataset is required to know which blank nodes could be inlined.
+    /// It has no effect on the other formats.
+    pub fn pretty(mut self) -> Self {
+        self.pretty = true;
+        self
+    }
+
+    /// Adds a prefix used to abbreviate the IRIs in the serializations supporting it (only [`pretty`](DatasetSerializer::pretty) TriG at the moment)
+    ///
+    /// The prefix name is validated when [`quad_writer`](DatasetSerializer::quad_writer) is called.
+    pub fn with_prefix(
+        mut self,
+        prefix_name: impl Into<String>,
+        prefix_iri: impl Into<String>,
+    ) -> Result<Self, IriParseError> {
+        add_prefix(&mut self.prefixes, prefix_name.into(), prefix_iri.into())?;
+        Ok(self)
+    }
+
+    /// Provides the base IRI used to write relative IRIs in the serializations supporting it (only [`pretty`](DatasetSerializer::pretty) TriG at the moment)
+    pub fn with_base_iri(mut self, base_iri: impl Into<String>) -> Result<Self, IriParseError> {
+        self.base_iri = Some(Iri::parse(base_iri.into())?);
+        Ok(self)
+    }
+
+    /// Provides the [JSON-LD context](https://www.w3.org/TR/json-ld11/#the-context) used to compact [JSON-LD](https://www.w3.org/TR/json-ld11/) documents
+    ///
+    /// It might be the context value itself or a JSON object with a `@context` key.
+    /// The context is validated when [`quad_writer`](DatasetSerializer::quad_writer) is called.
+    pub fn with_json_ld_context(mut self, context: impl Into<String>) -> Self {
+        self.json_ld_context = Some(context.into());
+        self
+    }
+
+    /// Provides the [`DocumentLoader`] used to resolve the remote contexts referred to by the [JSON-LD](https://www.w3.org/TR/json-ld11/) context
+    ///
+    /// By default, remote contexts are not allowed.
+    pub fn with_document_loader(mut self, document_loader: impl DocumentLoader + 'static) -> Self {
+        self.document_loader = Arc::new(document_loader);
+        self
+    }
+
+    /// Compresses the output using the given compression format
+    ///
+    /// Requires the `compression` feature.
+    ///
+    /// ```
+    /// use oxigraph::io::{Compression, DatasetFormat, DatasetParser, DatasetSerializer};
+    /// use oxigraph::model::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut writer = DatasetSerializer::from_format(DatasetFormat::NQuads)
+    ///     .with_compression(Compression::Bzip2)
+    ///     .quad_writer(&mut buffer)?;
+    /// writer.write(&Quad {
+    ///    subject: NamedNode::new("http://example.com/s")?.into(),
+    ///    predicate: NamedNode::new("http://example.com/p")?,
+    ///    object: NamedNode::new("http://example.com/o")?.into(),
+    ///    graph_name: NamedNode::new("http://example.com/g")?.into(),
+    /// })?;
+    /// writer.finish()?;
+    ///
+    /// // The parsers detect the compression
+    /// let quads = DatasetParser::from_format(DatasetFormat::NQuads)
+    ///     .read_quads(Cursor::new(buffer))?
+    ///     .collect::<Result<Vec<_>,_>>()?;
+    /// assert_eq!(quads[0].subject.to_string(), "<http://example.com/s>");
+    /// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[cfg(feature = "compression")]
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    fn compress<W: Write>(&self, writer: W) -> io::Result<CompressingWriter<W>> {
+        #[cfg(feature = "compression")]
+        return CompressingWriter::new(writer, self.compression);
+        #[cfg(not(feature = "compression"))]
+        return CompressingWriter::new(writer);
     }
 
     /// Returns a `QuadWriter` allowing writing triples into the given [`Write`](std::io::Write) implementation
     pub fn quad_writer<W: Write>(&self, writer: W) -> Result<QuadWriter<W>, io::Error> {
+        let writer = self.compress(writer)?;
         Ok(QuadWriter {
             formatter: match self.format {
                 DatasetFormat::NQuads => QuadWriterKind::NQuads(NQuadsFormatter::new(writer)),
+                DatasetFormat::TriG if self.pretty => QuadWriterKind::PrettyTriG(
+                    TurtleWriter::new(writer, self.prefixes.clone(), self.base_iri.clone(), true)?,
+                ),
                 DatasetFormat::TriG => QuadWriterKind::TriG(TriGFormatter::new(writer)),
+                DatasetFormat::JsonLd => QuadWriterKind::JsonLd(JsonLdWriter::new(
+                    writer,
+                    self.json_ld_context
+                        .as_ref()
+                        .map(|context| {
+                            parse_serialization_context(context, self.document_loader.as_ref())
+                        })
+                        .transpose()?,
+                )),
             },
         })
     }
 }
 
+impl From<DatasetFormat> for DatasetSerializer {
+    fn from(format: DatasetFormat) -> Self {
+        Self::from_format(format)
+    }
+}
+
 /// Allows writing triples.
 /// Could be built using a [`DatasetSerializer`].
 ///
@@ -182,8 +463,10 @@ pub struct QuadWriter<W: Write> {
 }
 
 enum QuadWriterKind<W: Write> {
-    NQuads(NQuadsFormatter<W>),
-    TriG(TriGFormatter<W>),
+    NQuads(NQuadsFormatter<CompressingWriter<W>>),
+    TriG(TriGFormatter<CompressingWriter<W>>),
+    PrettyTriG(TurtleWriter<CompressingWriter<W>>),
+    JsonLd(JsonLdWriter<CompressingWriter<W>>),
 }
 
 impl<W: Write> QuadWriter<W> {
@@ -191,8 +474,10 @@ impl<W: Write> QuadWriter<W> {
     pub fn write<'a>(&mut self, quad: impl Into<QuadRef<'a>>) -> Result<(), io::Error> {
         let quad = quad.into();
         match &mut self.formatter {
-            QuadWriterKind::NQuads(formatter) => formatter.format(&quad.into())?,
-            QuadWriterKind::TriG(formatter) => formatter.format(&quad.into())?,
+            QuadWriterKind::NQuads(formatter) => with_rio_quad(quad, &mut |q| formatter.format(q))?,
+            QuadWriterKind::TriG(formatter) => with_rio_quad(quad, &mut |q| formatter.format(q))?,
+            QuadWriterKind::PrettyTriG(writer) => writer.write(quad)?,
+            QuadWriterKind::JsonLd(writer) => writer.write(quad)?,
         }
         Ok(())
     }
@@ -200,9 +485,75 @@ impl<W: Write> QuadWriter<W> {
     /// Writes the last bytes of the file
     pub fn finish(self) -> Result<(), io::Error> {
         match self.formatter {
-            QuadWriterKind::NQuads(formatter) => formatter.finish(),
+            QuadWriterKind::NQuads(formatter) => formatter.finish()?,
             QuadWriterKind::TriG(formatter) => formatter.finish()?,
-        };
+            QuadWriterKind::PrettyTriG(writer) => writer.finish()?,
+            QuadWriterKind::JsonLd(writer) => writer.finish()?,
+        }
+        .finish()?;
         Ok(())
     }
 }
+
+fn add_prefix(
+    prefixes: &mut Vec<(String, String)>,
+    prefix_name: String,
+    prefix_iri: String,
+) -> Result<(), IriParseError> {
+    let prefix_iri = Iri::parse(prefix_iri)?.into_inner();
+    prefixes.retain(|(name, _)| *name != prefix_name);
+    prefixes.push((prefix_name, prefix_iri));
+    Ok(())
+}
+
+// Rio terms only borrow their quoted triples so the conversions are done on the stack
+// and the converted value is given to a callback.
+
+fn with_rio_subject<T>(
+    subject: SubjectRef<'_>,
+    callback: &mut dyn FnMut(rio::Subject<'_>) -> T,
+) -> T {
+    match subject {
+        SubjectRef::NamedNode(node) => callback(rio::NamedNode::from(node).into()),
+        SubjectRef::BlankNode(node) => callback(rio::BlankNode::from(node).into()),
+        SubjectRef::Triple(triple) => {
+            with_rio_triple(triple.as_ref(), &mut |t| callback(rio::Subject::Triple(t)))
+        }
+    }
+}
+
+fn with_rio_term<T>(term: TermRef<'_>, callback: &mut dyn FnMut(rio::Term<'_>) -> T) -> T {
+    match term {
+        TermRef::NamedNode(node) => callback(rio::NamedNode::from(node).into()),
+        TermRef::BlankNode(node) => callback(rio::BlankNode::from(node).into()),
+        TermRef::Literal(literal) => callback(rio::Literal::from(literal).into()),
+        TermRef::Triple(triple) => {
+            with_rio_triple(triple.as_ref(), &mut |t| callback(rio::Term::Triple(t)))
+        }
+    }
+}
+
+fn with_rio_triple<T>(triple: TripleRef<'_>, callback: &mut dyn FnMut(&rio::Triple<'_>) -> T) -> T {
+    with_rio_subject(triple.subject, &mut |subject| {
+        with_rio_term(triple.object, &mut |object| {
+            callback(&rio::Triple {
+                subject,
+                predicate: triple.predicate.into(),
+                object,
+            })
+        })
+    })
+}
+
+fn with_rio_quad<T>(quad: QuadRef<'_>, callback: &mut dyn FnMut(&rio::Quad<'_>) -> T) -> T {
+    with_rio_subject(quad.subject, &mut |subject| {
+        with_rio_term(quad.object, &mut |object| {
+            callback(&rio::Quad {
+                subject,
+                predicate: quad.predicate.into(),
+                object,
+                graph_name: quad.graph_name.into(),
+            })
+        })
+    })
+}
@@ -13,6 +13,11 @@
 //!   However, Sled is still in developpment, less tested and data load seems much slower than RocksDB.
 //!
 //! Oxigraph also provides a set of utility functions for reading, writing and processing RDF files.
+//! The disabled by default `"compression"` feature allows them and the store load and dump methods to read and write [gzip](https://tools.ietf.org/html/rfc1952), [bzip2](https://sourceware.org/bzip2/) and [Zstandard](https://tools.ietf.org/html/rfc8878) compressed files.
+//!
+//! The disabled by default `"arrow"` feature allows to export SPARQL query solutions to [Apache Arrow](https://arrow.apache.org/) record batches and [Apache Parquet](https://parquet.apache.org/) files.
+//!
+//! The disabled by default `"serde"` feature allows to deserialize SPARQL query solutions into Rust structs using [Serde](https://serde.rs/).
 //!
 //! The disabled by default `"sophia"` feature provides [`sophia_api`](https://docs.rs/sophia_api/) traits implemention on Oxigraph terms and stores.
 //!
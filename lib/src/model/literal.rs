@@ -2,7 +2,7 @@ use crate::model::named_node::NamedNode;
 use crate::model::vocab::rdf;
 use crate::model::vocab::xsd;
 use crate::model::xsd::*;
-use crate::model::NamedNodeRef;
+use crate::model::{NamedNodeRef, TypedValue, TypedValueParseError};
 use oxilangtag::{LanguageTag, LanguageTagParseError};
 use rio_api::model as rio;
 use std::borrow::Cow;
@@ -141,6 +141,23 @@ impl Literal {
         })
     }
 
+    /// Parses the value of this literal according to its datatype.
+    ///
+    /// Returns an error if the literal value is not a valid lexical form of its datatype.
+    /// Literals with unsupported datatypes are returned as [`TypedValue::Other`].
+    ///
+    /// ```
+    /// use oxigraph::model::{Literal, TypedValue};
+    /// use oxigraph::model::vocab::xsd;
+    ///
+    /// assert_eq!(Literal::new_typed_literal("true", xsd::BOOLEAN).typed_value()?, TypedValue::Boolean(true));
+    /// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    pub fn typed_value(&self) -> Result<TypedValue<'_>, TypedValueParseError> {
+        self.as_ref().typed_value()
+    }
+
     /// Extract components from this literal
     #[inline]
     pub fn destruct(self) -> (String, Option<NamedNode>, Option<String>) {
@@ -222,6 +239,16 @@ impl From<i32> for Literal {
     }
 }
 
+impl From<i8> for Literal {
+    #[inline]
+    fn from(value: i8) -> Self {
+        Literal(LiteralContent::TypedLiteral {
+            value: value.to_string(),
+            datatype: xsd::INTEGER.into(),
+        })
+    }
+}
+
 impl From<i16> for Literal {
     #[inline]
     fn from(value: i16) -> Self {
@@ -262,11 +289,27 @@ impl From<u16> for Literal {
     }
 }
 
+impl From<u8> for Literal {
+    #[inline]
+    fn from(value: u8) -> Self {
+        Literal(LiteralContent::TypedLiteral {
+            value: value.to_string(),
+            datatype: xsd::INTEGER.into(),
+        })
+    }
+}
+
 impl From<f32> for Literal {
     #[inline]
     fn from(value: f32) -> Self {
         Literal(LiteralContent::TypedLiteral {
-            value: value.to_string(),
+            value: if value == f32::INFINITY {
+                "INF".to_string()
+            } else if value == f32::NEG_INFINITY {
+                "-INF".to_string()
+            } else {
+                value.to_string()
+            },
             datatype: xsd::FLOAT.into(),
         })
     }
@@ -276,7 +319,13 @@ impl From<f64> for Literal {
     #[inline]
     fn from(value: f64) -> Self {
         Literal(LiteralContent::TypedLiteral {
-            value: value.to_string(),
+            value: if value == f64::INFINITY {
+                "INF".to_string()
+            } else if value == f64::NEG_INFINITY {
+                "-INF".to_string()
+            } else {
+                value.to_string()
+            },
             datatype: xsd::DOUBLE.into(),
         })
     }
@@ -292,6 +341,46 @@ impl From<Decimal> for Literal {
     }
 }
 
+impl From<BigInteger> for Literal {
+    #[inline]
+    fn from(value: BigInteger) -> Self {
+        Literal(LiteralContent::TypedLiteral {
+            value: value.to_string(),
+            datatype: xsd::INTEGER.into(),
+        })
+    }
+}
+
+impl From<BigDecimal> for Literal {
+    #[inline]
+    fn from(value: BigDecimal) -> Self {
+        Literal(LiteralContent::TypedLiteral {
+            value: value.to_string(),
+            datatype: xsd::DECIMAL.into(),
+        })
+    }
+}
+
+impl From<HexBinary> for Literal {
+    #[inline]
+    fn from(value: HexBinary) -> Self {
+        Literal(LiteralContent::TypedLiteral {
+            value: value.to_string(),
+            datatype: xsd::HEX_BINARY.into(),
+        })
+    }
+}
+
+impl From<Base64Binary> for Literal {
+    #[inline]
+    fn from(value: Base64Binary) -> Self {
+        Literal(LiteralContent::TypedLiteral {
+            value: value.to_string(),
+            datatype: xsd::BASE_64_BINARY.into(),
+        })
+    }
+}
+
 impl From<DateTime> for Literal {
     #[inline]
     fn from(value: DateTime) -> Self {
@@ -528,6 +617,14 @@ impl<'a> LiteralRef<'a> {
         })
     }
 
+    /// Parses the value of this literal according to its datatype.
+    ///
+    /// See [`Literal::typed_value`].
+    #[inline]
+    pub fn typed_value(self) -> Result<TypedValue<'a>, TypedValueParseError> {
+        TypedValue::parse(self)
+    }
+
     /// Extract components from this literal
     #[inline]
     pub fn destruct(self) -> (&'a str, Option<NamedNodeRef<'a>>, Option<&'a str>) {
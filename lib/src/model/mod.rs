@@ -6,19 +6,25 @@ mod blank_node;
 mod literal;
 mod named_node;
 mod parser;
+#[cfg(feature = "serde")]
+mod serde;
 #[cfg(feature = "sophia")]
 mod sophia;
 mod triple;
+mod typed_value;
+mod validation;
 pub mod vocab;
-pub(crate) mod xsd;
+pub mod xsd;
 
 pub use crate::model::blank_node::{BlankNode, BlankNodeIdParseError, BlankNodeRef};
 pub use crate::model::literal::{Literal, LiteralRef};
 pub use crate::model::named_node::{NamedNode, NamedNodeRef};
 pub use crate::model::parser::TermParseError;
 pub use crate::model::triple::{
-    GraphName, GraphNameRef, NamedOrBlankNode, NamedOrBlankNodeRef, Quad, QuadRef, Term, TermRef,
-    Triple, TripleRef,
+    GraphName, GraphNameRef, NamedOrBlankNode, NamedOrBlankNodeRef, Quad, QuadRef, Subject,
+    SubjectRef, Term, TermRef, Triple, TripleRef,
 };
+pub use crate::model::typed_value::{TypedValue, TypedValueParseError};
+pub use crate::model::validation::LiteralValidator;
 pub use oxilangtag::LanguageTagParseError;
 pub use oxiri::IriParseError;
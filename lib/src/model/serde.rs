@@ -0,0 +1,83 @@
+//! This module provides implementation of [Serde](https://serde.rs/) traits for the `model` module.
+//!
+//! IRIs are deserialized from their string value and XSD values from their lexical representation.
+
+use crate::model::xsd::*;
+use crate::model::*;
+use ::serde::de::{Deserialize, Deserializer, Error, Visitor};
+use std::fmt;
+use std::str::FromStr;
+
+impl<'de> Deserialize<'de> for NamedNode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct NamedNodeVisitor;
+
+        impl<'de> Visitor<'de> for NamedNodeVisitor {
+            type Value = NamedNode;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("an absolute IRI")
+            }
+
+            fn visit_str<E: Error>(self, v: &str) -> Result<NamedNode, E> {
+                NamedNode::new(v).map_err(E::custom)
+            }
+
+            fn visit_string<E: Error>(self, v: String) -> Result<NamedNode, E> {
+                NamedNode::new(v).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_string(NamedNodeVisitor)
+    }
+}
+
+struct FromStrVisitor<T> {
+    expecting: &'static str,
+    target: std::marker::PhantomData<T>,
+}
+
+impl<'de, T: FromStr> Visitor<'de> for FromStrVisitor<T>
+where
+    T::Err: fmt::Display,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.expecting)
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<T, E> {
+        v.parse().map_err(E::custom)
+    }
+}
+
+macro_rules! deserialize_from_str {
+    ($type:ty, $expecting:expr) => {
+        impl<'de> Deserialize<'de> for $type {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.deserialize_str(FromStrVisitor {
+                    expecting: $expecting,
+                    target: std::marker::PhantomData,
+                })
+            }
+        }
+    };
+}
+
+deserialize_from_str!(Decimal, "an xsd:decimal");
+deserialize_from_str!(BigInteger, "an xsd:integer");
+deserialize_from_str!(BigDecimal, "an xsd:decimal");
+deserialize_from_str!(HexBinary, "an xsd:hexBinary");
+deserialize_from_str!(Base64Binary, "an xsd:base64Binary");
+deserialize_from_str!(DateTime, "an xsd:dateTime");
+deserialize_from_str!(Time, "an xsd:time");
+deserialize_from_str!(Date, "an xsd:date");
+deserialize_from_str!(GYearMonth, "an xsd:gYearMonth");
+deserialize_from_str!(GYear, "an xsd:gYear");
+deserialize_from_str!(GMonthDay, "an xsd:gMonthDay");
+deserialize_from_str!(GMonth, "an xsd:gMonth");
+deserialize_from_str!(GDay, "an xsd:gDay");
+deserialize_from_str!(Duration, "an xsd:duration");
+deserialize_from_str!(YearMonthDuration, "an xsd:yearMonthDuration");
+deserialize_from_str!(DayTimeDuration, "an xsd:dayTimeDuration");
@@ -263,6 +263,7 @@ impl TTerm for Term {
             NamedNode(_) => TermKind::Iri,
             BlankNode(_) => TermKind::BlankNode,
             Literal(_) => TermKind::Literal,
+            Triple(_) => panic!("RDF-star is not supported yet by Sophia"),
         }
     }
 
@@ -272,6 +273,7 @@ impl TTerm for Term {
             NamedNode(n) => n.value_raw(),
             BlankNode(n) => n.value_raw(),
             Literal(l) => l.value_raw(),
+            Triple(_) => panic!("RDF-star is not supported yet by Sophia"),
         }
     }
 
@@ -297,6 +299,7 @@ impl TTerm for Term {
             NamedNode(n) => n.as_dyn(),
             BlankNode(n) => n.as_dyn(),
             Literal(l) => l.as_dyn(),
+            Triple(_) => panic!("RDF-star is not supported yet by Sophia"),
         }
     }
 }
@@ -324,6 +327,7 @@ impl<'a> TTerm for TermRef<'a> {
             NamedNode(_) => TermKind::Iri,
             BlankNode(_) => TermKind::BlankNode,
             Literal(_) => TermKind::Literal,
+            Triple(_) => panic!("RDF-star is not supported yet by Sophia"),
         }
     }
 
@@ -333,6 +337,7 @@ impl<'a> TTerm for TermRef<'a> {
             NamedNode(n) => n.value_raw(),
             BlankNode(n) => n.value_raw(),
             Literal(l) => l.value_raw(),
+            Triple(_) => panic!("RDF-star is not supported yet by Sophia"),
         }
     }
 
@@ -358,6 +363,7 @@ impl<'a> TTerm for TermRef<'a> {
             NamedNode(n) => n.as_dyn(),
             BlankNode(n) => n.as_dyn(),
             Literal(l) => l.as_dyn(),
+            Triple(_) => panic!("RDF-star is not supported yet by Sophia"),
         }
     }
 }
@@ -0,0 +1,642 @@
+use crate::model::vocab::{rdf, xsd};
+use crate::model::xsd::*;
+use crate::model::{Literal, LiteralRef, NamedNodeRef};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// The value of an RDF [literal](https://www.w3.org/TR/rdf11-concepts/#dfn-literal) parsed according to its datatype.
+///
+/// It is returned by [`Literal::typed_value`] and [`LiteralRef::typed_value`].
+/// Its [`Display`](fmt::Display) implementation and its conversion to [`Literal`] use the canonical lexical form of the value.
+///
+/// ```
+/// use oxigraph::model::{Literal, TypedValue};
+/// use oxigraph::model::vocab::xsd;
+///
+/// let literal = Literal::new_typed_literal("+01", xsd::INTEGER);
+/// assert_eq!(literal.typed_value()?, TypedValue::Integer(1));
+/// assert_eq!(Literal::from(literal.typed_value()?), Literal::from(1));
+///
+/// assert_eq!(TypedValue::Double(1500.).to_string(), "1.5E3");
+/// assert!(Literal::new_typed_literal("foo", xsd::INTEGER).typed_value().is_err());
+/// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(PartialEq, Debug, Clone)]
+#[non_exhaustive]
+pub enum TypedValue<'a> {
+    /// A [simple literal](https://www.w3.org/TR/rdf11-concepts/#dfn-simple-literal) or a `xsd:string` literal
+    String(&'a str),
+    /// A [language-tagged string](https://www.w3.org/TR/rdf11-concepts/#dfn-language-tagged-string)
+    LanguageTaggedString { value: &'a str, language: &'a str },
+    /// A [`xsd:boolean`](https://www.w3.org/TR/xmlschema11-2/#boolean)
+    Boolean(bool),
+    /// A [`xsd:integer`](https://www.w3.org/TR/xmlschema11-2/#integer)
+    Integer(i64),
+    /// A [`xsd:decimal`](https://www.w3.org/TR/xmlschema11-2/#decimal)
+    Decimal(Decimal),
+    /// A [`xsd:integer`](https://www.w3.org/TR/xmlschema11-2/#integer) that does not fit into an `i64`
+    BigInteger(BigInteger),
+    /// A [`xsd:decimal`](https://www.w3.org/TR/xmlschema11-2/#decimal) that does not fit into a [`Decimal`]
+    BigDecimal(BigDecimal),
+    /// A value of one of the [datatypes derived from `xsd:integer`](DerivedIntegerType) like `xsd:int` or `xsd:nonNegativeInteger`
+    DerivedInteger {
+        value: BigInteger,
+        datatype: DerivedIntegerType,
+    },
+    /// A [`xsd:float`](https://www.w3.org/TR/xmlschema11-2/#float)
+    Float(f32),
+    /// A [`xsd:double`](https://www.w3.org/TR/xmlschema11-2/#double)
+    Double(f64),
+    /// A [`xsd:dateTime`](https://www.w3.org/TR/xmlschema11-2/#dateTime)
+    DateTime(DateTime),
+    /// A [`xsd:time`](https://www.w3.org/TR/xmlschema11-2/#time)
+    Time(Time),
+    /// A [`xsd:date`](https://www.w3.org/TR/xmlschema11-2/#date)
+    Date(Date),
+    /// A [`xsd:gYearMonth`](https://www.w3.org/TR/xmlschema11-2/#gYearMonth)
+    GYearMonth(GYearMonth),
+    /// A [`xsd:gYear`](https://www.w3.org/TR/xmlschema11-2/#gYear)
+    GYear(GYear),
+    /// A [`xsd:gMonthDay`](https://www.w3.org/TR/xmlschema11-2/#gMonthDay)
+    GMonthDay(GMonthDay),
+    /// A [`xsd:gDay`](https://www.w3.org/TR/xmlschema11-2/#gDay)
+    GDay(GDay),
+    /// A [`xsd:gMonth`](https://www.w3.org/TR/xmlschema11-2/#gMonth)
+    GMonth(GMonth),
+    /// A [`xsd:duration`](https://www.w3.org/TR/xmlschema11-2/#duration)
+    Duration(Duration),
+    /// A [`xsd:yearMonthDuration`](https://www.w3.org/TR/xmlschema11-2/#yearMonthDuration)
+    YearMonthDuration(YearMonthDuration),
+    /// A [`xsd:dayTimeDuration`](https://www.w3.org/TR/xmlschema11-2/#dayTimeDuration)
+    DayTimeDuration(DayTimeDuration),
+    /// A [`xsd:hexBinary`](https://www.w3.org/TR/xmlschema11-2/#hexBinary)
+    HexBinary(HexBinary),
+    /// A [`xsd:base64Binary`](https://www.w3.org/TR/xmlschema11-2/#base64Binary)
+    Base64Binary(Base64Binary),
+    /// A [`xsd:anyURI`](https://www.w3.org/TR/xmlschema11-2/#anyURI)
+    AnyUri(&'a str),
+    /// A literal with a datatype not supported by this implementation
+    Other {
+        value: &'a str,
+        datatype: NamedNodeRef<'a>,
+    },
+}
+
+impl<'a> TypedValue<'a> {
+    /// Parses the value of a literal from its lexical form
+    pub(crate) fn parse(literal: LiteralRef<'a>) -> Result<Self, TypedValueParseError> {
+        let value = literal.value();
+        let datatype = literal.datatype();
+        if let Some(language) = literal.language() {
+            return Ok(TypedValue::LanguageTaggedString { value, language });
+        }
+        let error = || TypedValueParseError {
+            value: value.to_owned(),
+            datatype: datatype.as_str().to_owned(),
+        };
+        Ok(match datatype {
+            xsd::STRING => TypedValue::String(value),
+            xsd::BOOLEAN => TypedValue::Boolean(match value {
+                "true" | "1" => true,
+                "false" | "0" => false,
+                _ => return Err(error()),
+            }),
+            xsd::INTEGER => {
+                if !is_integer_lexical_form(value) {
+                    return Err(error());
+                }
+                if let Ok(v) = value.parse() {
+                    TypedValue::Integer(v)
+                } else {
+                    TypedValue::BigInteger(value.parse().map_err(|_| error())?)
+                }
+            }
+            xsd::DECIMAL => {
+                if let Ok(v) = value.parse() {
+                    TypedValue::Decimal(v)
+                } else {
+                    let v = BigDecimal::from_str(value).map_err(|_| error())?;
+                    if let Ok(v) = Decimal::try_from(&v) {
+                        TypedValue::Decimal(v)
+                    } else {
+                        TypedValue::BigDecimal(v)
+                    }
+                }
+            }
+            xsd::FLOAT => {
+                if !is_float_lexical_form(value) {
+                    return Err(error());
+                }
+                TypedValue::Float(value.parse().map_err(|_| error())?)
+            }
+            xsd::DOUBLE => {
+                if !is_float_lexical_form(value) {
+                    return Err(error());
+                }
+                TypedValue::Double(value.parse().map_err(|_| error())?)
+            }
+            xsd::DATE_TIME => TypedValue::DateTime(parse(value).ok_or_else(error)?),
+            xsd::TIME => TypedValue::Time(parse(value).ok_or_else(error)?),
+            xsd::DATE => TypedValue::Date(parse(value).ok_or_else(error)?),
+            xsd::G_YEAR_MONTH => TypedValue::GYearMonth(parse(value).ok_or_else(error)?),
+            xsd::G_YEAR => TypedValue::GYear(parse(value).ok_or_else(error)?),
+            xsd::G_MONTH_DAY => TypedValue::GMonthDay(parse(value).ok_or_else(error)?),
+            xsd::G_DAY => TypedValue::GDay(parse(value).ok_or_else(error)?),
+            xsd::G_MONTH => TypedValue::GMonth(parse(value).ok_or_else(error)?),
+            xsd::DURATION => TypedValue::Duration(parse(value).ok_or_else(error)?),
+            xsd::YEAR_MONTH_DURATION => {
+                TypedValue::YearMonthDuration(parse(value).ok_or_else(error)?)
+            }
+            xsd::DAY_TIME_DURATION => TypedValue::DayTimeDuration(parse(value).ok_or_else(error)?),
+            xsd::HEX_BINARY => TypedValue::HexBinary(parse(value).ok_or_else(error)?),
+            xsd::BASE_64_BINARY => TypedValue::Base64Binary(parse(value).ok_or_else(error)?),
+            xsd::ANY_URI => TypedValue::AnyUri(parse_any_uri(value).ok_or_else(error)?),
+            _ => {
+                if let Some(datatype) = DerivedIntegerType::from_name(datatype) {
+                    if !is_integer_lexical_form(value) {
+                        return Err(error());
+                    }
+                    let value = BigInteger::from_str(value).map_err(|_| error())?;
+                    if !datatype.contains(&value) {
+                        return Err(error());
+                    }
+                    TypedValue::DerivedInteger { value, datatype }
+                } else {
+                    TypedValue::Other { value, datatype }
+                }
+            }
+        })
+    }
+
+    /// The datatype of the value
+    pub fn datatype(&self) -> NamedNodeRef<'a> {
+        match self {
+            TypedValue::String(_) => xsd::STRING,
+            TypedValue::LanguageTaggedString { .. } => rdf::LANG_STRING,
+            TypedValue::Boolean(_) => xsd::BOOLEAN,
+            TypedValue::Integer(_) | TypedValue::BigInteger(_) => xsd::INTEGER,
+            TypedValue::Decimal(_) | TypedValue::BigDecimal(_) => xsd::DECIMAL,
+            TypedValue::DerivedInteger { datatype, .. } => datatype.name(),
+            TypedValue::Float(_) => xsd::FLOAT,
+            TypedValue::Double(_) => xsd::DOUBLE,
+            TypedValue::DateTime(_) => xsd::DATE_TIME,
+            TypedValue::Time(_) => xsd::TIME,
+            TypedValue::Date(_) => xsd::DATE,
+            TypedValue::GYearMonth(_) => xsd::G_YEAR_MONTH,
+            TypedValue::GYear(_) => xsd::G_YEAR,
+            TypedValue::GMonthDay(_) => xsd::G_MONTH_DAY,
+            TypedValue::GDay(_) => xsd::G_DAY,
+            TypedValue::GMonth(_) => xsd::G_MONTH,
+            TypedValue::Duration(_) => xsd::DURATION,
+            TypedValue::YearMonthDuration(_) => xsd::YEAR_MONTH_DURATION,
+            TypedValue::DayTimeDuration(_) => xsd::DAY_TIME_DURATION,
+            TypedValue::HexBinary(_) => xsd::HEX_BINARY,
+            TypedValue::Base64Binary(_) => xsd::BASE_64_BINARY,
+            TypedValue::AnyUri(_) => xsd::ANY_URI,
+            TypedValue::Other { datatype, .. } => *datatype,
+        }
+    }
+}
+
+impl fmt::Display for TypedValue<'_> {
+    /// Formats the value following its canonical lexical representation
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypedValue::String(value)
+            | TypedValue::LanguageTaggedString { value, .. }
+            | TypedValue::AnyUri(value)
+            | TypedValue::Other { value, .. } => f.write_str(value),
+            TypedValue::Boolean(value) => value.fmt(f),
+            TypedValue::Integer(value) => value.fmt(f),
+            TypedValue::Decimal(value) => value.fmt(f),
+            TypedValue::BigInteger(value) => value.fmt(f),
+            TypedValue::BigDecimal(value) => value.fmt(f),
+            TypedValue::DerivedInteger { value, .. } => value.fmt(f),
+            TypedValue::Float(value) => write_canonical_float(value, f),
+            TypedValue::Double(value) => write_canonical_float(value, f),
+            TypedValue::DateTime(value) => value.fmt(f),
+            TypedValue::Time(value) => value.fmt(f),
+            TypedValue::Date(value) => value.fmt(f),
+            TypedValue::GYearMonth(value) => value.fmt(f),
+            TypedValue::GYear(value) => value.fmt(f),
+            TypedValue::GMonthDay(value) => value.fmt(f),
+            TypedValue::GDay(value) => value.fmt(f),
+            TypedValue::GMonth(value) => value.fmt(f),
+            TypedValue::Duration(value) => value.fmt(f),
+            TypedValue::YearMonthDuration(value) => value.fmt(f),
+            TypedValue::DayTimeDuration(value) => value.fmt(f),
+            TypedValue::HexBinary(value) => value.fmt(f),
+            TypedValue::Base64Binary(value) => value.fmt(f),
+        }
+    }
+}
+
+impl From<TypedValue<'_>> for Literal {
+    /// Builds the literal using the canonical lexical form of the value
+    fn from(value: TypedValue<'_>) -> Self {
+        match value {
+            TypedValue::String(value) => Literal::new_simple_literal(value),
+            TypedValue::LanguageTaggedString { value, language } => {
+                Literal::new_language_tagged_literal_unchecked(value, language.to_ascii_lowercase())
+            }
+            value => Literal::new_typed_literal(value.to_string(), value.datatype()),
+        }
+    }
+}
+
+impl From<bool> for TypedValue<'_> {
+    #[inline]
+    fn from(value: bool) -> Self {
+        TypedValue::Boolean(value)
+    }
+}
+
+impl From<i64> for TypedValue<'_> {
+    #[inline]
+    fn from(value: i64) -> Self {
+        TypedValue::Integer(value)
+    }
+}
+
+impl From<Decimal> for TypedValue<'_> {
+    #[inline]
+    fn from(value: Decimal) -> Self {
+        TypedValue::Decimal(value)
+    }
+}
+
+impl From<BigInteger> for TypedValue<'_> {
+    /// Uses [`TypedValue::Integer`] if the value fits into an `i64`
+    #[inline]
+    fn from(value: BigInteger) -> Self {
+        if let Ok(value) = i64::try_from(&value) {
+            TypedValue::Integer(value)
+        } else {
+            TypedValue::BigInteger(value)
+        }
+    }
+}
+
+impl From<BigDecimal> for TypedValue<'_> {
+    /// Uses [`TypedValue::Decimal`] if the value fits into a [`Decimal`]
+    #[inline]
+    fn from(value: BigDecimal) -> Self {
+        if let Ok(value) = Decimal::try_from(&value) {
+            TypedValue::Decimal(value)
+        } else {
+            TypedValue::BigDecimal(value)
+        }
+    }
+}
+
+impl From<f32> for TypedValue<'_> {
+    #[inline]
+    fn from(value: f32) -> Self {
+        TypedValue::Float(value)
+    }
+}
+
+impl From<f64> for TypedValue<'_> {
+    #[inline]
+    fn from(value: f64) -> Self {
+        TypedValue::Double(value)
+    }
+}
+
+impl From<DateTime> for TypedValue<'_> {
+    #[inline]
+    fn from(value: DateTime) -> Self {
+        TypedValue::DateTime(value)
+    }
+}
+
+impl From<Time> for TypedValue<'_> {
+    #[inline]
+    fn from(value: Time) -> Self {
+        TypedValue::Time(value)
+    }
+}
+
+impl From<Date> for TypedValue<'_> {
+    #[inline]
+    fn from(value: Date) -> Self {
+        TypedValue::Date(value)
+    }
+}
+
+impl From<GYearMonth> for TypedValue<'_> {
+    #[inline]
+    fn from(value: GYearMonth) -> Self {
+        TypedValue::GYearMonth(value)
+    }
+}
+
+impl From<GYear> for TypedValue<'_> {
+    #[inline]
+    fn from(value: GYear) -> Self {
+        TypedValue::GYear(value)
+    }
+}
+
+impl From<GMonthDay> for TypedValue<'_> {
+    #[inline]
+    fn from(value: GMonthDay) -> Self {
+        TypedValue::GMonthDay(value)
+    }
+}
+
+impl From<GDay> for TypedValue<'_> {
+    #[inline]
+    fn from(value: GDay) -> Self {
+        TypedValue::GDay(value)
+    }
+}
+
+impl From<GMonth> for TypedValue<'_> {
+    #[inline]
+    fn from(value: GMonth) -> Self {
+        TypedValue::GMonth(value)
+    }
+}
+
+impl From<Duration> for TypedValue<'_> {
+    #[inline]
+    fn from(value: Duration) -> Self {
+        TypedValue::Duration(value)
+    }
+}
+
+impl From<YearMonthDuration> for TypedValue<'_> {
+    #[inline]
+    fn from(value: YearMonthDuration) -> Self {
+        TypedValue::YearMonthDuration(value)
+    }
+}
+
+impl From<DayTimeDuration> for TypedValue<'_> {
+    #[inline]
+    fn from(value: DayTimeDuration) -> Self {
+        TypedValue::DayTimeDuration(value)
+    }
+}
+
+impl From<HexBinary> for TypedValue<'_> {
+    #[inline]
+    fn from(value: HexBinary) -> Self {
+        TypedValue::HexBinary(value)
+    }
+}
+
+impl From<Base64Binary> for TypedValue<'_> {
+    #[inline]
+    fn from(value: Base64Binary) -> Self {
+        TypedValue::Base64Binary(value)
+    }
+}
+
+/// An error raised when the lexical form of a literal is not valid for its datatype
+#[derive(Debug, Clone)]
+pub struct TypedValueParseError {
+    value: String,
+    datatype: String,
+}
+
+impl fmt::Display for TypedValueParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "'{}' is not a valid lexical form for the datatype <{}>",
+            self.value, self.datatype
+        )
+    }
+}
+
+impl Error for TypedValueParseError {}
+
+fn parse<T: FromStr>(value: &str) -> Option<T> {
+    value.parse().ok()
+}
+
+fn is_integer_lexical_form(value: &str) -> bool {
+    let digits = value.strip_prefix(&['+', '-'][..]).unwrap_or(value);
+    !digits.is_empty() && digits.bytes().all(|c| c.is_ascii_digit())
+}
+
+/// Checks the `xsd:float` and `xsd:double` lexical space that is smaller than the one accepted by Rust
+fn is_float_lexical_form(value: &str) -> bool {
+    if value == "NaN" {
+        return true;
+    }
+    let unsigned = value.strip_prefix(&['+', '-'][..]).unwrap_or(value);
+    if unsigned == "INF" {
+        return true;
+    }
+    let (mantissa, exponent) = match unsigned.find(&['e', 'E'][..]) {
+        Some(i) => (&unsigned[..i], Some(&unsigned[i + 1..])),
+        None => (unsigned, None),
+    };
+    let (integer_part, fraction_part) = match mantissa.find('.') {
+        Some(i) => (&mantissa[..i], &mantissa[i + 1..]),
+        None => (mantissa, ""),
+    };
+    (!integer_part.is_empty() || !fraction_part.is_empty())
+        && integer_part.bytes().all(|c| c.is_ascii_digit())
+        && fraction_part.bytes().all(|c| c.is_ascii_digit())
+        && exponent.is_none_or(is_integer_lexical_form)
+}
+
+/// Writes the [canonical representation](https://www.w3.org/TR/xmlschema11-2/#f-doubleCanmap) of a `xsd:float` or a `xsd:double`
+fn write_canonical_float(value: impl fmt::UpperExp, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let value = format!("{:E}", value);
+    match value.as_str() {
+        "NaN" => f.write_str("NaN"),
+        "inf" => f.write_str("INF"),
+        "-inf" => f.write_str("-INF"),
+        value => {
+            let (mantissa, exponent) = value.split_at(value.find('E').unwrap_or(value.len()));
+            if mantissa.contains('.') {
+                write!(f, "{}{}", mantissa, exponent)
+            } else {
+                write!(f, "{}.0{}", mantissa, exponent)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Literal::new_simple_literal("foo").typed_value().unwrap(),
+            TypedValue::String("foo")
+        );
+        assert_eq!(
+            Literal::new_language_tagged_literal_unchecked("foo", "en")
+                .typed_value()
+                .unwrap(),
+            TypedValue::LanguageTaggedString {
+                value: "foo",
+                language: "en"
+            }
+        );
+        assert_eq!(
+            Literal::new_typed_literal("1", xsd::BOOLEAN)
+                .typed_value()
+                .unwrap(),
+            TypedValue::Boolean(true)
+        );
+        assert_eq!(
+            Literal::new_typed_literal("-0012", xsd::INTEGER)
+                .typed_value()
+                .unwrap(),
+            TypedValue::Integer(-12)
+        );
+        assert_eq!(
+            Literal::new_typed_literal("1.50", xsd::DECIMAL)
+                .typed_value()
+                .unwrap(),
+            TypedValue::Decimal(Decimal::from_str("1.5").unwrap())
+        );
+        assert_eq!(
+            Literal::new_typed_literal("-INF", xsd::FLOAT)
+                .typed_value()
+                .unwrap(),
+            TypedValue::Float(f32::NEG_INFINITY)
+        );
+        assert_eq!(
+            Literal::new_typed_literal(".5e1", xsd::DOUBLE)
+                .typed_value()
+                .unwrap(),
+            TypedValue::Double(5.)
+        );
+        assert_eq!(
+            Literal::new_typed_literal("P1D", xsd::DAY_TIME_DURATION)
+                .typed_value()
+                .unwrap(),
+            TypedValue::DayTimeDuration(DayTimeDuration::from_str("PT24H").unwrap())
+        );
+        let big = "123456789012345678901234567890";
+        assert_eq!(
+            Literal::new_typed_literal(big, xsd::INTEGER)
+                .typed_value()
+                .unwrap(),
+            TypedValue::BigInteger(BigInteger::from_str(big).unwrap())
+        );
+        assert_eq!(
+            Literal::new_typed_literal("-00.10000000000000000000", xsd::DECIMAL)
+                .typed_value()
+                .unwrap(),
+            TypedValue::Decimal(Decimal::from_str("-0.1").unwrap())
+        );
+        assert_eq!(
+            Literal::from(
+                Literal::new_typed_literal("1.0000000000000000000000001", xsd::DECIMAL)
+                    .typed_value()
+                    .unwrap()
+            ),
+            Literal::new_typed_literal("1.0000000000000000000000001", xsd::DECIMAL)
+        );
+        assert_eq!(
+            Literal::new_typed_literal("+255", xsd::UNSIGNED_BYTE)
+                .typed_value()
+                .unwrap(),
+            TypedValue::DerivedInteger {
+                value: BigInteger::from(255_i64),
+                datatype: DerivedIntegerType::UnsignedByte
+            }
+        );
+        assert_eq!(
+            Literal::new_typed_literal(" ../foo ", xsd::ANY_URI)
+                .typed_value()
+                .unwrap(),
+            TypedValue::AnyUri("../foo")
+        );
+        assert_eq!(
+            Literal::new_typed_literal("0a", xsd::HEX_BINARY)
+                .typed_value()
+                .unwrap(),
+            TypedValue::HexBinary(vec![10].into())
+        );
+        assert_eq!(
+            Literal::new_typed_literal("foo", xsd::NORMALIZED_STRING)
+                .typed_value()
+                .unwrap(),
+            TypedValue::Other {
+                value: "foo",
+                datatype: xsd::NORMALIZED_STRING
+            }
+        );
+
+        for (value, datatype) in &[
+            ("yes", xsd::BOOLEAN),
+            ("1.0", xsd::INTEGER),
+            ("+", xsd::INTEGER),
+            ("1,5", xsd::DECIMAL),
+            ("inf", xsd::DOUBLE),
+            ("infinity", xsd::FLOAT),
+            ("1e", xsd::DOUBLE),
+            (".", xsd::DOUBLE),
+            ("2020-13-01", xsd::DATE),
+            ("1D", xsd::DURATION),
+            ("256", xsd::UNSIGNED_BYTE),
+            ("-1", xsd::NON_NEGATIVE_INTEGER),
+            ("0", xsd::POSITIVE_INTEGER),
+            ("2147483648", xsd::INT),
+            ("1.0", xsd::LONG),
+            ("0", xsd::HEX_BINARY),
+            ("Zm9v=", xsd::BASE_64_BINARY),
+            ("http://example.com/a b", xsd::ANY_URI),
+        ] {
+            assert!(
+                Literal::new_typed_literal(*value, *datatype)
+                    .typed_value()
+                    .is_err(),
+                "{} should not be a valid {}",
+                value,
+                datatype
+            );
+        }
+    }
+
+    #[test]
+    fn test_canonical_form() {
+        for (value, datatype, canonical) in &[
+            ("1", xsd::BOOLEAN, "true"),
+            ("+01", xsd::INTEGER, "1"),
+            ("-0", xsd::INTEGER, "0"),
+            ("+0010", xsd::SHORT, "10"),
+            ("0fb7", xsd::HEX_BINARY, "0FB7"),
+            ("Zm9v YmFy", xsd::BASE_64_BINARY, "Zm9vYmFy"),
+            ("01.10", xsd::DECIMAL, "1.1"),
+            ("1.0", xsd::DECIMAL, "1"),
+            ("100", xsd::DOUBLE, "1.0E2"),
+            ("0.0015", xsd::DOUBLE, "1.5E-3"),
+            ("-0", xsd::DOUBLE, "-0.0E0"),
+            ("+INF", xsd::DOUBLE, "INF"),
+            ("1.5", xsd::FLOAT, "1.5E0"),
+            ("1.1", xsd::FLOAT, "1.1E0"),
+            (
+                "2020-01-01T00:00:00.0Z",
+                xsd::DATE_TIME,
+                "2020-01-01T00:00:00Z",
+            ),
+        ] {
+            assert_eq!(
+                Literal::new_typed_literal(*value, *datatype)
+                    .typed_value()
+                    .unwrap()
+                    .to_string(),
+                *canonical
+            );
+        }
+        assert_eq!(
+            Literal::from(TypedValue::Double(-1.)),
+            Literal::new_typed_literal("-1.0E0", xsd::DOUBLE)
+        );
+    }
+}
@@ -0,0 +1,324 @@
+use crate::model::{Literal, Quad, Subject, Term, Triple, TypedValueParseError};
+use std::sync::Arc;
+
+/// Checks and rewrites the [literals](https://www.w3.org/TR/rdf11-concepts/#dfn-literal) of the datatypes supported by [`TypedValue`](super::TypedValue).
+///
+/// By default, all the literals are kept as they are.
+/// The ill-typed literals like `"abc"^^xsd:integer` could be rejected with [`reject_invalid`](LiteralValidator::reject_invalid)
+/// or reported with [`warn_invalid`](LiteralValidator::warn_invalid)
+/// and the valid literals written in their canonical lexical form with [`canonicalize`](LiteralValidator::canonicalize).
+///
+/// It could be given to the parsers using [`GraphParser::with_literal_validator`](crate::io::GraphParser::with_literal_validator)
+/// and [`DatasetParser::with_literal_validator`](crate::io::DatasetParser::with_literal_validator),
+/// to the SPARQL updates using [`UpdateOptions::with_literal_validator`](crate::sparql::UpdateOptions::with_literal_validator)
+/// and applied on the quads before they are inserted into a store:
+/// ```
+/// use oxigraph::model::*;
+/// use oxigraph::model::vocab::xsd;
+/// use oxigraph::MemoryStore;
+///
+/// let validator = LiteralValidator::new().reject_invalid().canonicalize();
+/// let ex = NamedNode::new("http://example.com")?;
+///
+/// let store = MemoryStore::new();
+/// store.insert(validator.validate_quad(Quad::new(ex.clone(), ex.clone(), Literal::new_typed_literal("01", xsd::INTEGER), None))?);
+/// assert!(store.contains(&Quad::new(ex.clone(), ex.clone(), Literal::from(1), None)));
+///
+/// assert!(validator.validate_literal(Literal::new_typed_literal("abc", xsd::INTEGER)).is_err());
+/// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Clone, Default)]
+pub struct LiteralValidator {
+    on_invalid: InvalidLiteralAction,
+    canonicalize: bool,
+}
+
+#[derive(Clone, Default)]
+enum InvalidLiteralAction {
+    #[default]
+    Keep,
+    Reject,
+    Warn(Arc<dyn Fn(TypedValueParseError) + Send + Sync>),
+}
+
+impl LiteralValidator {
+    /// Builds a validator keeping all the literals as they are
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns an error for the literals whose lexical form is not valid for their datatype
+    pub fn reject_invalid(mut self) -> Self {
+        self.on_invalid = InvalidLiteralAction::Reject;
+        self
+    }
+
+    /// Keeps the literals whose lexical form is not valid for their datatype and reports them to `on_invalid`
+    ///
+    /// ```
+    /// use oxigraph::model::LiteralValidator;
+    /// use oxigraph::model::Literal;
+    /// use oxigraph::model::vocab::xsd;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let warnings = Arc::new(Mutex::new(Vec::new()));
+    /// let warnings_sink = warnings.clone();
+    /// let validator = LiteralValidator::new()
+    ///     .warn_invalid(move |error| warnings_sink.lock().unwrap().push(error.to_string()));
+    ///
+    /// let literal = Literal::new_typed_literal("abc", xsd::INTEGER);
+    /// assert_eq!(validator.validate_literal(literal.clone())?, literal);
+    /// assert_eq!(warnings.lock().unwrap().len(), 1);
+    /// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn warn_invalid(
+        mut self,
+        on_invalid: impl Fn(TypedValueParseError) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_invalid = InvalidLiteralAction::Warn(Arc::new(on_invalid));
+        self
+    }
+
+    /// Writes the valid literals in their canonical lexical form and the language tags in lower case
+    ///
+    /// The `xsd:string` literals are returned as simple literals.
+    pub fn canonicalize(mut self) -> Self {
+        self.canonicalize = true;
+        self
+    }
+
+    /// Returns `true` if the validator never changes or rejects a literal
+    pub(crate) fn is_noop(&self) -> bool {
+        matches!(self.on_invalid, InvalidLiteralAction::Keep) && !self.canonicalize
+    }
+
+    /// Validates a literal and returns it, possibly rewritten in its canonical form
+    pub fn validate_literal(&self, literal: Literal) -> Result<Literal, TypedValueParseError> {
+        if self.is_noop() {
+            return Ok(literal);
+        }
+        match literal.typed_value() {
+            Ok(value) => Ok(if self.canonicalize {
+                value.into()
+            } else {
+                literal
+            }),
+            Err(error) => match &self.on_invalid {
+                InvalidLiteralAction::Keep => Ok(literal),
+                InvalidLiteralAction::Reject => Err(error),
+                InvalidLiteralAction::Warn(on_invalid) => {
+                    on_invalid(error);
+                    Ok(literal)
+                }
+            },
+        }
+    }
+
+    /// Validates the literals of a term, including the ones in quoted triples
+    pub fn validate_term(&self, term: Term) -> Result<Term, TypedValueParseError> {
+        Ok(match term {
+            Term::Literal(literal) => self.validate_literal(literal)?.into(),
+            Term::Triple(triple) => self.validate_triple(*triple)?.into(),
+            term => term,
+        })
+    }
+
+    /// Validates the literals of a triple, including the ones in quoted triples
+    pub fn validate_triple(&self, triple: Triple) -> Result<Triple, TypedValueParseError> {
+        Ok(Triple {
+            subject: self.validate_subject(triple.subject)?,
+            predicate: triple.predicate,
+            object: self.validate_term(triple.object)?,
+        })
+    }
+
+    /// Validates the literals of a quad, including the ones in quoted triples
+    pub fn validate_quad(&self, quad: Quad) -> Result<Quad, TypedValueParseError> {
+        Ok(Quad {
+            subject: self.validate_subject(quad.subject)?,
+            predicate: quad.predicate,
+            object: self.validate_term(quad.object)?,
+            graph_name: quad.graph_name,
+        })
+    }
+
+    fn validate_subject(&self, subject: Subject) -> Result<Subject, TypedValueParseError> {
+        Ok(match subject {
+            Subject::Triple(triple) => self.validate_triple(*triple)?.into(),
+            subject => subject,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::{GraphFormat, GraphParser, SyntaxError};
+    use crate::model::vocab::xsd;
+    use crate::model::xsd::Decimal;
+    use crate::model::{GraphName, NamedNode};
+    use crate::sparql::UpdateOptions;
+    use crate::store::MemoryStore;
+    use std::io::Cursor;
+    use std::sync::Mutex;
+
+    #[test]
+    fn canonicalize() {
+        let validator = LiteralValidator::new().canonicalize();
+        for (input, output) in [
+            (
+                Literal::new_typed_literal("01", xsd::INTEGER),
+                Literal::from(1),
+            ),
+            (
+                Literal::new_typed_literal("foo", xsd::STRING),
+                Literal::new_simple_literal("foo"),
+            ),
+            (
+                Literal::new_language_tagged_literal_unchecked("foo", "en-GB"),
+                Literal::new_language_tagged_literal_unchecked("foo", "en-gb"),
+            ),
+            (
+                Literal::new_typed_literal("abc", xsd::INTEGER),
+                Literal::new_typed_literal("abc", xsd::INTEGER),
+            ),
+            (
+                Literal::new_typed_literal("foo", NamedNode::new_unchecked("http://example.com")),
+                Literal::new_typed_literal("foo", NamedNode::new_unchecked("http://example.com")),
+            ),
+        ] {
+            assert_eq!(validator.validate_literal(input).unwrap(), output);
+        }
+    }
+
+    #[test]
+    fn quoted_triples() {
+        let ex = NamedNode::new_unchecked("http://example.com");
+        let quoted = Triple::new(
+            ex.clone(),
+            ex.clone(),
+            Literal::new_typed_literal("abc", xsd::BOOLEAN),
+        );
+        let validator = LiteralValidator::new().reject_invalid();
+        assert!(validator
+            .validate_triple(Triple::new(quoted.clone(), ex.clone(), ex.clone()))
+            .is_err());
+        assert!(validator
+            .validate_triple(Triple::new(ex.clone(), ex, quoted))
+            .is_err());
+    }
+
+    #[test]
+    fn load() {
+        let file = "@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+        <http://example.com/s> <http://example.com/p> \"01\"^^xsd:integer, \"1\"^^xsd:integer .
+        <http://example.com/s> <http://example.com/p> \"abc\"^^xsd:integer .
+        <http://example.com/s> <http://example.com/p> \"1.0\"^^xsd:decimal .";
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let errors_sink = errors.clone();
+        let store = MemoryStore::new();
+        store
+            .load_graph(
+                Cursor::new(file),
+                GraphParser::from_format(GraphFormat::Turtle)
+                    .with_literal_validator(LiteralValidator::new().reject_invalid().canonicalize())
+                    .lenient(move |error| errors_sink.lock().unwrap().push(error)),
+                &GraphName::DefaultGraph,
+                None,
+            )
+            .unwrap();
+        assert_eq!(store.len(), 2);
+        assert_eq!(errors.lock().unwrap().len(), 1);
+
+        assert!(store
+            .load_graph(
+                Cursor::new(file),
+                GraphParser::from_format(GraphFormat::Turtle)
+                    .with_literal_validator(LiteralValidator::new().reject_invalid()),
+                &GraphName::DefaultGraph,
+                None,
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn error_positions() {
+        let line = "<http://example.com/s> <http://example.com/p> \"1\"^^<http://www.w3.org/2001/XMLSchema#integer> .\n";
+        let mut file = line.repeat(10_000);
+        file.push_str("<http://example.com/s> <http://example.com/p> \"abc\"^^<http://www.w3.org/2001/XMLSchema#integer> .\n");
+        file.push_str(&line.repeat(10));
+        for format in [GraphFormat::NTriples, GraphFormat::Turtle] {
+            let error = GraphParser::from_format(format)
+                .with_literal_validator(LiteralValidator::new().reject_invalid())
+                .read_triples(Cursor::new(file.as_str()))
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap_err();
+            let position = error
+                .get_ref()
+                .unwrap()
+                .downcast_ref::<SyntaxError>()
+                .unwrap()
+                .position()
+                .unwrap();
+            assert_eq!(position.line(), 10_000);
+            assert_eq!(position.offset(), (line.len() * 10_000) as u64);
+
+            let errors = Arc::new(Mutex::new(Vec::new()));
+            let errors_sink = errors.clone();
+            let triples = GraphParser::from_format(format)
+                .with_literal_validator(LiteralValidator::new().reject_invalid())
+                .lenient(move |error| errors_sink.lock().unwrap().push(error))
+                .read_triples(Cursor::new(file.as_str()))
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(triples.len(), 10_010);
+            let errors = errors.lock().unwrap();
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].position().unwrap().line(), 10_000);
+        }
+    }
+
+    #[test]
+    fn update() {
+        let ex = NamedNode::new_unchecked("http://example.com");
+        let store = MemoryStore::new();
+        let options = UpdateOptions::default()
+            .with_literal_validator(LiteralValidator::new().reject_invalid().canonicalize());
+        store
+            .update_opt(
+                "INSERT DATA { <http://example.com> <http://example.com> \"01\"^^<http://www.w3.org/2001/XMLSchema#integer> }",
+                options.clone(),
+            )
+            .unwrap();
+        store
+            .update_opt(
+                "INSERT { ?s ?p ?v . ?s ?p \"1.0\"^^<http://www.w3.org/2001/XMLSchema#decimal> } WHERE { ?s ?p ?o BIND(STRDT(\"02\", <http://www.w3.org/2001/XMLSchema#integer>) AS ?v) }",
+                options.clone(),
+            )
+            .unwrap();
+        for value in [
+            Literal::from(1),
+            Literal::from(2),
+            Literal::from(Decimal::from(1)),
+        ] {
+            assert!(store.contains(&Quad::new(ex.clone(), ex.clone(), value, None)));
+        }
+        assert_eq!(store.len(), 3);
+
+        assert!(store
+            .update_opt(
+                "INSERT DATA { <http://example.com> <http://example.com> \"abc\"^^<http://www.w3.org/2001/XMLSchema#integer> }",
+                options.clone(),
+            )
+            .is_err());
+        assert!(store
+            .update_opt(
+                "INSERT { ?s ?p ?v } WHERE { ?s ?p ?o BIND(STRDT(\"abc\", <http://www.w3.org/2001/XMLSchema#integer>) AS ?v) }",
+                options,
+            )
+            .is_err());
+        assert_eq!(store.len(), 3);
+    }
+}
@@ -19,6 +19,9 @@ pub mod rdf {
     /// The class of language-tagged string literal values.
     pub const LANG_STRING: NamedNodeRef<'_> =
         NamedNodeRef::new_unchecked("http://www.w3.org/1999/02/22-rdf-syntax-ns#langString");
+    /// The datatype of RDF literals storing JSON content.
+    pub const JSON: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/1999/02/22-rdf-syntax-ns#JSON");
     /// The class of RDF Lists.
     pub const LIST: NamedNodeRef<'_> =
         NamedNodeRef::new_unchecked("http://www.w3.org/1999/02/22-rdf-syntax-ns#List");
@@ -0,0 +1,27 @@
+use super::binary::trim_xml_whitespaces;
+use oxiri::IriRef;
+
+/// Validates a [XML Schema `anyURI`](https://www.w3.org/TR/xmlschema11-2/#anyURI) lexical form and returns its value
+///
+/// The values are required to be valid IRI references.
+pub(crate) fn parse_any_uri(value: &str) -> Option<&str> {
+    let value = trim_xml_whitespaces(value);
+    IriRef::parse(value).ok().map(|iri| iri.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn any_uri() {
+        assert_eq!(
+            parse_any_uri(" http://example.com/foo "),
+            Some("http://example.com/foo")
+        );
+        assert_eq!(parse_any_uri("../foo#bar"), Some("../foo#bar"));
+        assert_eq!(parse_any_uri(""), Some(""));
+        assert_eq!(parse_any_uri("http://example.com/a b"), None);
+        assert_eq!(parse_any_uri("http://[::1"), None);
+    }
+}
@@ -0,0 +1,424 @@
+use super::big_integer::BigInteger;
+use super::decimal::{
+    Decimal, DecimalOverflowError, ParseDecimalError, DECIMAL_PART_DIGITS, PARSE_UNEXPECTED_CHAR,
+    PARSE_UNEXPECTED_END,
+};
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{Signed, ToPrimitive, Zero};
+use std::cmp::{max, Ordering};
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+
+/// Minimal number of digits kept after "." by divisions
+const DIVISION_SCALE: u32 = 18;
+
+/// [XML Schema `decimal` datatype](https://www.w3.org/TR/xmlschema11-2/#decimal) implementation without size or precision limit.
+///
+/// It is used for the values that do not fit into a [`Decimal`](super::Decimal).
+/// Additions, subtractions and multiplications are exact.
+/// Divisions keep at least 18 digits after ".".
+///
+/// ```
+/// use oxigraph::model::xsd::BigDecimal;
+/// use std::str::FromStr;
+///
+/// let a = BigDecimal::from_str("123456789012345678901234567890.5")?;
+/// assert_eq!((a.clone() * a).to_string(), "15241578753238836750495351562659655576514250878776253619990.25");
+/// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Eq, PartialEq, Debug, Clone, Hash, Default)]
+pub struct BigDecimal {
+    digits: BigInt, // never ends with a 0 if scale > 0
+    scale: u32,
+}
+
+impl BigDecimal {
+    /// Constructs the decimal i / 10^n
+    pub fn new(i: impl Into<BigInteger>, n: u32) -> Self {
+        Self::normalized(i.into().as_big_int().clone(), n)
+    }
+
+    /// [op:numeric-divide](https://www.w3.org/TR/xpath-functions/#func-numeric-divide)
+    pub fn checked_div(&self, rhs: &Self) -> Option<Self> {
+        if rhs.digits.is_zero() {
+            return None;
+        }
+        // We keep as many more digits as the divisor has to not lose precision with big divisors
+        let scale = max(DIVISION_SCALE, self.scale) + digits_count(&rhs.digits);
+        let numerator = &self.digits * pow10(scale + rhs.scale - self.scale);
+        Some(Self::normalized(numerator / &rhs.digits, scale))
+    }
+
+    /// [fn:abs](https://www.w3.org/TR/xpath-functions/#func-abs)
+    #[inline]
+    pub fn abs(&self) -> Self {
+        Self {
+            digits: self.digits.abs(),
+            scale: self.scale,
+        }
+    }
+
+    /// [fn:round](https://www.w3.org/TR/xpath-functions/#func-round)
+    pub fn round(&self) -> Self {
+        if self.scale == 0 {
+            return self.clone();
+        }
+        let half = Self {
+            digits: 5.into(),
+            scale: 1,
+        };
+        (self.clone() + half).floor()
+    }
+
+    /// [fn:ceiling](https://www.w3.org/TR/xpath-functions/#func-ceiling)
+    pub fn ceil(&self) -> Self {
+        let (quotient, remainder) = self.digits.div_rem(&pow10(self.scale));
+        Self::normalized(
+            if remainder.is_positive() {
+                quotient + 1
+            } else {
+                quotient
+            },
+            0,
+        )
+    }
+
+    /// [fn:floor](https://www.w3.org/TR/xpath-functions/#func-floor)
+    pub fn floor(&self) -> Self {
+        Self::normalized(self.digits.div_floor(&pow10(self.scale)), 0)
+    }
+
+    /// Returns the integer part of the decimal i.e. rounds it toward 0
+    pub fn trunc(&self) -> BigInteger {
+        BigInteger::from_big_int(&self.digits / pow10(self.scale))
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.digits.is_negative()
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.digits.is_positive()
+    }
+
+    /// Returns if the decimal has no fractional part
+    pub fn is_integer(&self) -> bool {
+        self.scale == 0
+    }
+
+    /// Creates a `f32` from a `BigDecimal` without taking care of precision
+    #[inline]
+    pub fn to_f32(&self) -> f32 {
+        self.to_string().parse().unwrap_or(f32::NAN)
+    }
+
+    /// Creates a `f64` from a `BigDecimal` without taking care of precision
+    #[inline]
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().unwrap_or(f64::NAN)
+    }
+
+    fn normalized(mut digits: BigInt, mut scale: u32) -> Self {
+        if digits.is_zero() {
+            return Self::default();
+        }
+        let ten = BigInt::from(10);
+        while scale > 0 {
+            let (quotient, remainder) = digits.div_rem(&ten);
+            if !remainder.is_zero() {
+                break;
+            }
+            digits = quotient;
+            scale -= 1;
+        }
+        Self { digits, scale }
+    }
+
+    /// Returns the digits of the two decimals rescaled to the same scale
+    fn aligned_digits(&self, other: &Self) -> (BigInt, BigInt, u32) {
+        match self.scale.cmp(&other.scale) {
+            Ordering::Less => (
+                &self.digits * pow10(other.scale - self.scale),
+                other.digits.clone(),
+                other.scale,
+            ),
+            Ordering::Equal => (self.digits.clone(), other.digits.clone(), self.scale),
+            Ordering::Greater => (
+                self.digits.clone(),
+                &other.digits * pow10(self.scale - other.scale),
+                self.scale,
+            ),
+        }
+    }
+}
+
+fn pow10(n: u32) -> BigInt {
+    BigInt::from(10).pow(n)
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn digits_count(value: &BigInt) -> u32 {
+    value.magnitude().to_string().len() as u32
+}
+
+impl From<BigInteger> for BigDecimal {
+    #[inline]
+    fn from(value: BigInteger) -> Self {
+        Self {
+            digits: value.as_big_int().clone(),
+            scale: 0,
+        }
+    }
+}
+
+impl From<i64> for BigDecimal {
+    #[inline]
+    fn from(value: i64) -> Self {
+        Self {
+            digits: value.into(),
+            scale: 0,
+        }
+    }
+}
+
+impl From<Decimal> for BigDecimal {
+    #[inline]
+    #[allow(clippy::cast_possible_truncation)]
+    fn from(value: Decimal) -> Self {
+        Self::normalized(value.as_scaled_i128().into(), DECIMAL_PART_DIGITS as u32)
+    }
+}
+
+impl TryFrom<&BigDecimal> for Decimal {
+    type Error = DecimalOverflowError;
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn try_from(value: &BigDecimal) -> Result<Self, DecimalOverflowError> {
+        let shift = (DECIMAL_PART_DIGITS as u32)
+            .checked_sub(value.scale)
+            .ok_or(DecimalOverflowError)?;
+        Ok(Self::from_scaled_i128(
+            (&value.digits * pow10(shift))
+                .to_i128()
+                .ok_or(DecimalOverflowError)?,
+        ))
+    }
+}
+
+impl TryFrom<BigDecimal> for Decimal {
+    type Error = DecimalOverflowError;
+
+    #[inline]
+    fn try_from(value: BigDecimal) -> Result<Self, DecimalOverflowError> {
+        Self::try_from(&value)
+    }
+}
+
+impl FromStr for BigDecimal {
+    type Err = ParseDecimalError;
+
+    /// Parses decimals lexical mapping
+    fn from_str(input: &str) -> Result<Self, ParseDecimalError> {
+        // (\+|-)?([0-9]+(\.[0-9]*)?|\.[0-9]+)
+        let unsigned = input
+            .strip_prefix(|c| c == '+' || c == '-')
+            .unwrap_or(input);
+        let (before_dot, after_dot) = if let Some(dot) = unsigned.find('.') {
+            (&unsigned[..dot], &unsigned[dot + 1..])
+        } else {
+            (unsigned, "")
+        };
+        if before_dot.is_empty() && after_dot.is_empty() {
+            return Err(PARSE_UNEXPECTED_END);
+        }
+        if !before_dot
+            .bytes()
+            .chain(after_dot.bytes())
+            .all(|c| c.is_ascii_digit())
+        {
+            return Err(PARSE_UNEXPECTED_CHAR);
+        }
+        let mut digits = String::with_capacity(before_dot.len() + after_dot.len() + 1);
+        if input.starts_with('-') {
+            digits.push('-');
+        }
+        digits.push_str(before_dot);
+        digits.push_str(after_dot);
+        Ok(Self::normalized(
+            BigInt::from_str(&digits).map_err(|_| PARSE_UNEXPECTED_CHAR)?,
+            u32::try_from(after_dot.len()).map_err(|_| PARSE_UNEXPECTED_CHAR)?,
+        ))
+    }
+}
+
+impl fmt::Display for BigDecimal {
+    /// Formats the decimal following its canonical representation
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.scale == 0 {
+            return self.digits.fmt(f);
+        }
+        if self.digits.is_negative() {
+            f.write_str("-")?;
+        }
+        let digits = self.digits.magnitude().to_string();
+        let scale = self.scale as usize;
+        if digits.len() > scale {
+            let (before_dot, after_dot) = digits.split_at(digits.len() - scale);
+            write!(f, "{}.{}", before_dot, after_dot)
+        } else {
+            f.write_str("0.")?;
+            for _ in digits.len()..scale {
+                f.write_str("0")?;
+            }
+            f.write_str(&digits)
+        }
+    }
+}
+
+impl PartialOrd for BigDecimal {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigDecimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a, b, _) = self.aligned_digits(other);
+        a.cmp(&b)
+    }
+}
+
+impl Add for BigDecimal {
+    type Output = Self;
+
+    /// [op:numeric-add](https://www.w3.org/TR/xpath-functions/#func-numeric-add)
+    fn add(self, rhs: Self) -> Self {
+        let (a, b, scale) = self.aligned_digits(&rhs);
+        Self::normalized(a + b, scale)
+    }
+}
+
+impl Sub for BigDecimal {
+    type Output = Self;
+
+    /// [op:numeric-subtract](https://www.w3.org/TR/xpath-functions/#func-numeric-subtract)
+    fn sub(self, rhs: Self) -> Self {
+        let (a, b, scale) = self.aligned_digits(&rhs);
+        Self::normalized(a - b, scale)
+    }
+}
+
+impl Mul for BigDecimal {
+    type Output = Self;
+
+    /// [op:numeric-multiply](https://www.w3.org/TR/xpath-functions/#func-numeric-multiply)
+    fn mul(self, rhs: Self) -> Self {
+        Self::normalized(self.digits * rhs.digits, self.scale + rhs.scale)
+    }
+}
+
+impl Neg for BigDecimal {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self {
+            digits: self.digits.neg(),
+            scale: self.scale,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        assert_eq!(BigDecimal::from_str("210").unwrap().to_string(), "210");
+        assert_eq!(BigDecimal::from_str("-1.23").unwrap().to_string(), "-1.23");
+        assert_eq!(
+            BigDecimal::from_str("+100000.00").unwrap().to_string(),
+            "100000"
+        );
+        assert_eq!(BigDecimal::from_str(".12200").unwrap().to_string(), "0.122");
+        assert_eq!(BigDecimal::from_str("-0.0").unwrap().to_string(), "0");
+        assert_eq!(
+            BigDecimal::from_str("-0.000000000000000000000001")
+                .unwrap()
+                .to_string(),
+            "-0.000000000000000000000001"
+        );
+        assert_eq!(
+            BigDecimal::from_str("123456789012345678901234567890.")
+                .unwrap()
+                .to_string(),
+            "123456789012345678901234567890"
+        );
+        assert!(BigDecimal::from_str("").is_err());
+        assert!(BigDecimal::from_str(".").is_err());
+        assert!(BigDecimal::from_str("-").is_err());
+        assert!(BigDecimal::from_str("1.2.3").is_err());
+        assert!(BigDecimal::from_str("1e3").is_err());
+    }
+
+    #[test]
+    fn decimal_conversion() {
+        for value in &["0", "1.5", "-12678967.543233", "0.000000000000000001"] {
+            let decimal = Decimal::from_str(value).unwrap();
+            let big = BigDecimal::from(decimal);
+            assert_eq!(big.to_string(), decimal.to_string());
+            assert_eq!(Decimal::try_from(&big).unwrap(), decimal);
+        }
+        assert!(Decimal::try_from(BigDecimal::from_str("0.0000000000000000001").unwrap()).is_err());
+        assert!(Decimal::try_from(BigDecimal::new(BigInteger::from(i128::MAX), 0)).is_err());
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = BigDecimal::from_str("0.1").unwrap();
+        let b = BigDecimal::from_str("0.2").unwrap();
+        assert_eq!((a.clone() + b.clone()).to_string(), "0.3");
+        assert_eq!((a.clone() - b.clone()).to_string(), "-0.1");
+        assert_eq!((a.clone() * b.clone()).to_string(), "0.02");
+        assert_eq!(a.checked_div(&b).unwrap().to_string(), "0.5");
+        assert_eq!(
+            BigDecimal::from(1)
+                .checked_div(&BigDecimal::from(3))
+                .unwrap()
+                .to_string(),
+            "0.3333333333333333333"
+        );
+        assert_eq!(a.checked_div(&BigDecimal::default()), None);
+        assert!(a < b);
+        assert!(BigDecimal::from(-1) < a);
+        assert_eq!(
+            BigDecimal::from(10),
+            BigDecimal::from_str("10.000").unwrap()
+        );
+    }
+
+    #[test]
+    fn rounding() {
+        for (value, round, ceil, floor) in &[
+            ("2.5", "3", "3", "2"),
+            ("2.4999", "2", "3", "2"),
+            ("-2.5", "-2", "-2", "-3"),
+            ("-2.6", "-3", "-2", "-3"),
+            ("7", "7", "7", "7"),
+        ] {
+            let value = BigDecimal::from_str(value).unwrap();
+            assert_eq!(value.round().to_string(), *round);
+            assert_eq!(value.ceil().to_string(), *ceil);
+            assert_eq!(value.floor().to_string(), *floor);
+        }
+        assert_eq!(
+            BigDecimal::from_str("-2.5").unwrap().trunc(),
+            BigInteger::from(-2_i64)
+        );
+    }
+}
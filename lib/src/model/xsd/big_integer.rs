@@ -0,0 +1,257 @@
+use super::decimal::{
+    DecimalOverflowError, ParseDecimalError, PARSE_UNEXPECTED_CHAR, PARSE_UNEXPECTED_END,
+};
+use num_bigint::BigInt;
+use num_traits::{Signed, ToPrimitive, Zero};
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+
+/// [XML Schema `integer` datatype](https://www.w3.org/TR/xmlschema11-2/#integer) implementation without size limit.
+///
+/// It is used for the values that do not fit into an `i64`.
+///
+/// ```
+/// use oxigraph::model::xsd::BigInteger;
+/// use std::str::FromStr;
+///
+/// let a = BigInteger::from_str("9223372036854775807")?;
+/// assert_eq!((a.clone() + a).to_string(), "18446744073709551614");
+/// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Eq, PartialEq, Ord, PartialOrd, Debug, Clone, Hash, Default)]
+pub struct BigInteger {
+    value: BigInt,
+}
+
+impl BigInteger {
+    /// [op:numeric-integer-divide](https://www.w3.org/TR/xpath-functions/#func-numeric-integer-divide)
+    #[inline]
+    pub fn checked_div(&self, rhs: &Self) -> Option<Self> {
+        if rhs.value.is_zero() {
+            return None;
+        }
+        Some(Self {
+            value: &self.value / &rhs.value,
+        })
+    }
+
+    /// [op:numeric-mod](https://www.w3.org/TR/xpath-functions/#func-numeric-mod)
+    #[inline]
+    pub fn checked_rem(&self, rhs: &Self) -> Option<Self> {
+        if rhs.value.is_zero() {
+            return None;
+        }
+        Some(Self {
+            value: &self.value % &rhs.value,
+        })
+    }
+
+    /// [fn:abs](https://www.w3.org/TR/xpath-functions/#func-abs)
+    #[inline]
+    pub fn abs(&self) -> Self {
+        Self {
+            value: self.value.abs(),
+        }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.value.is_negative()
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.value.is_positive()
+    }
+
+    /// Creates a `f32` from a `BigInteger` without taking care of precision
+    #[inline]
+    pub fn to_f32(&self) -> f32 {
+        self.value.to_f32().unwrap_or(f32::NAN)
+    }
+
+    /// Creates a `f64` from a `BigInteger` without taking care of precision
+    #[inline]
+    pub fn to_f64(&self) -> f64 {
+        self.value.to_f64().unwrap_or(f64::NAN)
+    }
+
+    pub(super) fn as_big_int(&self) -> &BigInt {
+        &self.value
+    }
+
+    pub(super) fn from_big_int(value: BigInt) -> Self {
+        Self { value }
+    }
+}
+
+impl From<i64> for BigInteger {
+    #[inline]
+    fn from(value: i64) -> Self {
+        Self {
+            value: value.into(),
+        }
+    }
+}
+
+impl From<u64> for BigInteger {
+    #[inline]
+    fn from(value: u64) -> Self {
+        Self {
+            value: value.into(),
+        }
+    }
+}
+
+impl From<i128> for BigInteger {
+    #[inline]
+    fn from(value: i128) -> Self {
+        Self {
+            value: value.into(),
+        }
+    }
+}
+
+impl TryFrom<BigInteger> for i64 {
+    type Error = DecimalOverflowError;
+
+    fn try_from(value: BigInteger) -> Result<i64, DecimalOverflowError> {
+        value.value.to_i64().ok_or(DecimalOverflowError)
+    }
+}
+
+impl TryFrom<&BigInteger> for i64 {
+    type Error = DecimalOverflowError;
+
+    fn try_from(value: &BigInteger) -> Result<i64, DecimalOverflowError> {
+        value.value.to_i64().ok_or(DecimalOverflowError)
+    }
+}
+
+impl FromStr for BigInteger {
+    type Err = ParseDecimalError;
+
+    /// Parses integers lexical mapping
+    fn from_str(input: &str) -> Result<Self, ParseDecimalError> {
+        // (\+|-)?[0-9]+
+        let digits = input
+            .strip_prefix(|c| c == '+' || c == '-')
+            .unwrap_or(input);
+        if digits.is_empty() {
+            return Err(PARSE_UNEXPECTED_END);
+        }
+        if !digits.bytes().all(|c| c.is_ascii_digit()) {
+            return Err(PARSE_UNEXPECTED_CHAR);
+        }
+        let value = BigInt::from_str(digits).map_err(|_| PARSE_UNEXPECTED_CHAR)?;
+        Ok(Self {
+            value: if input.starts_with('-') {
+                -value
+            } else {
+                value
+            },
+        })
+    }
+}
+
+impl fmt::Display for BigInteger {
+    /// Formats the integer following its canonical representation
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl Add for BigInteger {
+    type Output = Self;
+
+    /// [op:numeric-add](https://www.w3.org/TR/xpath-functions/#func-numeric-add)
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            value: self.value + rhs.value,
+        }
+    }
+}
+
+impl Sub for BigInteger {
+    type Output = Self;
+
+    /// [op:numeric-subtract](https://www.w3.org/TR/xpath-functions/#func-numeric-subtract)
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            value: self.value - rhs.value,
+        }
+    }
+}
+
+impl Mul for BigInteger {
+    type Output = Self;
+
+    /// [op:numeric-multiply](https://www.w3.org/TR/xpath-functions/#func-numeric-multiply)
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        Self {
+            value: self.value * rhs.value,
+        }
+    }
+}
+
+impl Neg for BigInteger {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self {
+            value: self.value.neg(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        assert_eq!(BigInteger::from_str("0").unwrap().to_string(), "0");
+        assert_eq!(BigInteger::from_str("-0").unwrap().to_string(), "0");
+        assert_eq!(BigInteger::from_str("+0012").unwrap().to_string(), "12");
+        assert_eq!(
+            BigInteger::from_str("-123456789012345678901234567890")
+                .unwrap()
+                .to_string(),
+            "-123456789012345678901234567890"
+        );
+        assert!(BigInteger::from_str("").is_err());
+        assert!(BigInteger::from_str("+").is_err());
+        assert!(BigInteger::from_str("1.0").is_err());
+        assert!(BigInteger::from_str("+-1").is_err());
+        assert!(BigInteger::from_str("1_0").is_err());
+    }
+
+    #[test]
+    fn arithmetic() {
+        let max = BigInteger::from(i64::MAX);
+        assert_eq!(
+            (max.clone() * max.clone()).to_string(),
+            "85070591730234615847396907784232501249"
+        );
+        assert_eq!(
+            (max.clone() + BigInteger::from(1_i64)).to_string(),
+            "9223372036854775808"
+        );
+        assert_eq!(
+            BigInteger::from(i64::MIN) - BigInteger::from(1_i64),
+            BigInteger::from_str("-9223372036854775809").unwrap()
+        );
+        assert_eq!(
+            BigInteger::from(-7_i64).checked_div(&BigInteger::from(2_i64)),
+            Some(BigInteger::from(-3_i64))
+        );
+        assert_eq!(max.checked_div(&BigInteger::default()), None);
+        assert_eq!(i64::try_from(max).unwrap(), i64::MAX);
+        assert!(i64::try_from(BigInteger::from(i128::from(i64::MAX) + 1)).is_err());
+    }
+}
@@ -0,0 +1,174 @@
+use super::parser::XsdParseError;
+use std::fmt;
+use std::str::FromStr;
+
+/// [XML Schema `hexBinary` datatype](https://www.w3.org/TR/xmlschema11-2/#hexBinary) implementation.
+///
+/// Its canonical representation uses upper case hexadecimal digits.
+///
+/// ```
+/// use oxigraph::model::xsd::HexBinary;
+/// use std::str::FromStr;
+///
+/// let value = HexBinary::from_str("0fb7")?;
+/// assert_eq!(value.as_bytes(), &[0x0F, 0xB7]);
+/// assert_eq!(value.to_string(), "0FB7");
+/// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Eq, PartialEq, Ord, PartialOrd, Debug, Clone, Hash, Default)]
+pub struct HexBinary {
+    value: Vec<u8>,
+}
+
+impl HexBinary {
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.value
+    }
+
+    #[inline]
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.value
+    }
+}
+
+impl From<Vec<u8>> for HexBinary {
+    #[inline]
+    fn from(value: Vec<u8>) -> Self {
+        Self { value }
+    }
+}
+
+impl From<Base64Binary> for HexBinary {
+    #[inline]
+    fn from(value: Base64Binary) -> Self {
+        Self { value: value.value }
+    }
+}
+
+impl FromStr for HexBinary {
+    type Err = XsdParseError;
+
+    fn from_str(input: &str) -> Result<Self, XsdParseError> {
+        Ok(Self {
+            value: hex::decode(trim_xml_whitespaces(input))?,
+        })
+    }
+}
+
+impl fmt::Display for HexBinary {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode_upper(&self.value))
+    }
+}
+
+/// [XML Schema `base64Binary` datatype](https://www.w3.org/TR/xmlschema11-2/#base64Binary) implementation.
+///
+/// Its canonical representation does not contain any whitespace.
+///
+/// ```
+/// use oxigraph::model::xsd::Base64Binary;
+/// use std::str::FromStr;
+///
+/// let value = Base64Binary::from_str("SGVs bG8=")?;
+/// assert_eq!(value.as_bytes(), b"Hello");
+/// assert_eq!(value.to_string(), "SGVsbG8=");
+/// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Eq, PartialEq, Ord, PartialOrd, Debug, Clone, Hash, Default)]
+pub struct Base64Binary {
+    value: Vec<u8>,
+}
+
+impl Base64Binary {
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.value
+    }
+
+    #[inline]
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.value
+    }
+}
+
+impl From<Vec<u8>> for Base64Binary {
+    #[inline]
+    fn from(value: Vec<u8>) -> Self {
+        Self { value }
+    }
+}
+
+impl From<HexBinary> for Base64Binary {
+    #[inline]
+    fn from(value: HexBinary) -> Self {
+        Self { value: value.value }
+    }
+}
+
+impl FromStr for Base64Binary {
+    type Err = XsdParseError;
+
+    fn from_str(input: &str) -> Result<Self, XsdParseError> {
+        let input = input
+            .chars()
+            .filter(|c| !is_xml_whitespace(*c))
+            .collect::<String>();
+        if input.len() % 4 != 0 {
+            // The padding is mandatory
+            return Err(base64::DecodeError::InvalidLength.into());
+        }
+        Ok(Self {
+            value: base64::decode(input)?,
+        })
+    }
+}
+
+impl fmt::Display for Base64Binary {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&base64::encode(&self.value))
+    }
+}
+
+/// Applies the `collapse` whitespace facet to values that are not allowed to contain whitespaces
+pub(super) fn trim_xml_whitespaces(input: &str) -> &str {
+    input.trim_matches(is_xml_whitespace)
+}
+
+fn is_xml_whitespace(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n' | '\r')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_binary() {
+        assert_eq!(HexBinary::from_str("").unwrap().as_bytes(), b"");
+        assert_eq!(HexBinary::from_str(" 0aFf\n").unwrap().to_string(), "0AFF");
+        assert!(HexBinary::from_str("0").is_err());
+        assert!(HexBinary::from_str("0g").is_err());
+        assert!(HexBinary::from_str("0a ff").is_err());
+    }
+
+    #[test]
+    fn base64_binary() {
+        assert_eq!(Base64Binary::from_str("").unwrap().as_bytes(), b"");
+        assert_eq!(
+            Base64Binary::from_str("SGVs\nbG8g d29y bGQ=")
+                .unwrap()
+                .as_bytes(),
+            b"Hello world"
+        );
+        assert_eq!(
+            Base64Binary::from(HexBinary::from_str("FF").unwrap()).to_string(),
+            "/w=="
+        );
+        assert!(Base64Binary::from_str("/w").is_err());
+        assert!(Base64Binary::from_str("/w=").is_err());
+        assert!(Base64Binary::from_str("SGVsbG8-").is_err());
+    }
+}
@@ -171,6 +171,39 @@ impl DateTime {
         }
     }
 
+    /// The number of microseconds since 1970-01-01T00:00:00Z
+    ///
+    /// The date times without timezone are considered to be in UTC.
+    #[cfg(feature = "arrow")]
+    pub(crate) fn unix_timestamp_micros(&self) -> Option<i64> {
+        i64::try_from(
+            self.timestamp
+                .unix_timestamp()?
+                .checked_mul(1_000_000)?
+                .as_i128(),
+        )
+        .ok()
+    }
+
+    /// [fn:adjust-dateTime-to-timezone](https://www.w3.org/TR/xpath-functions/#func-adjust-dateTime-to-timezone)
+    ///
+    /// The timezone is removed if `timezone_offset` is `None`.
+    ///
+    /// ```
+    /// use oxigraph::model::xsd::{DateTime, TimezoneOffset};
+    /// use std::str::FromStr;
+    ///
+    /// let date_time = DateTime::from_str("2002-03-07T10:00:00-05:00")?;
+    /// assert_eq!(date_time.adjust(Some(TimezoneOffset::from(-600))).unwrap().to_string(), "2002-03-07T05:00:00-10:00");
+    /// assert_eq!(date_time.adjust(None).unwrap().to_string(), "2002-03-07T10:00:00");
+    /// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn adjust(&self, timezone_offset: Option<TimezoneOffset>) -> Option<Self> {
+        Some(Self {
+            timestamp: self.timestamp.adjust(timezone_offset)?,
+        })
+    }
+
     pub fn is_identical_with(&self, other: &Self) -> bool {
         self.timestamp.is_identical_with(&other.timestamp)
     }
@@ -336,6 +369,36 @@ impl Time {
         .ok()
     }
 
+    /// The number of microseconds since midnight in the time timezone
+    #[cfg(feature = "arrow")]
+    pub(crate) fn micros_since_midnight(&self) -> Option<i64> {
+        i64::try_from(
+            i128::from(self.hour()) * 3_600_000_000
+                + i128::from(self.minute()) * 60_000_000
+                + self.second().checked_mul(1_000_000)?.as_i128(),
+        )
+        .ok()
+    }
+
+    /// [fn:adjust-time-to-timezone](https://www.w3.org/TR/xpath-functions/#func-adjust-time-to-timezone)
+    ///
+    /// The timezone is removed if `timezone_offset` is `None`.
+    pub fn adjust(&self, timezone_offset: Option<TimezoneOffset>) -> Option<Self> {
+        DateTime::new(
+            1972,
+            12,
+            31,
+            self.hour(),
+            self.minute(),
+            self.second(),
+            self.timezone_offset(),
+        )
+        .ok()?
+        .adjust(timezone_offset)?
+        .try_into()
+        .ok()
+    }
+
     pub fn is_identical_with(&self, other: &Self) -> bool {
         self.timestamp.is_identical_with(&other.timestamp)
     }
@@ -488,6 +551,29 @@ impl Date {
             .ok()
     }
 
+    /// The number of days between 1970-01-01 and this date
+    #[cfg(feature = "arrow")]
+    pub(crate) fn unix_days(&self) -> Option<i32> {
+        let offset = i128::from(
+            self.timestamp
+                .timezone_offset
+                .unwrap_or_else(TimezoneOffset::utc)
+                .offset,
+        ) * 60;
+        i32::try_from((self.timestamp.unix_timestamp()?.as_i128() + offset).div_euclid(86400)).ok()
+    }
+
+    /// [fn:adjust-date-to-timezone](https://www.w3.org/TR/xpath-functions/#func-adjust-date-to-timezone)
+    ///
+    /// The timezone is removed if `timezone_offset` is `None`.
+    pub fn adjust(&self, timezone_offset: Option<TimezoneOffset>) -> Option<Self> {
+        DateTime::try_from(*self)
+            .ok()?
+            .adjust(timezone_offset)?
+            .try_into()
+            .ok()
+    }
+
     pub fn is_identical_with(&self, other: &Self) -> bool {
         self.timestamp.is_identical_with(&other.timestamp)
     }
@@ -1054,6 +1140,26 @@ impl From<i16> for TimezoneOffset {
     }
 }
 
+/// Conversion according to [the XPath timezone constraints](https://www.w3.org/TR/xpath-functions/#func-adjust-dateTime-to-timezone): the duration must be an integral number of minutes between -PT14H and PT14H.
+impl TryFrom<DayTimeDuration> for TimezoneOffset {
+    type Error = DateTimeError;
+
+    fn try_from(value: DayTimeDuration) -> Result<Self, DateTimeError> {
+        let seconds = value.all_seconds();
+        let error = || DateTimeError {
+            kind: DateTimeErrorKind::InvalidTimezone(value),
+        };
+        if seconds.checked_rem(60) != Some(Decimal::default()) {
+            return Err(error());
+        }
+        let offset = i16::try_from(seconds.as_i128() / 60).map_err(|_| error())?;
+        if offset.abs() > 14 * 60 {
+            return Err(error());
+        }
+        Ok(Self { offset })
+    }
+}
+
 impl From<TimezoneOffset> for DayTimeDuration {
     fn from(value: TimezoneOffset) -> Self {
         DayTimeDuration::new(i32::from(value.offset) * 60)
@@ -1181,6 +1287,21 @@ impl Timestamp {
         )
     }
 
+    /// The number of seconds since 1970-01-01T00:00:00Z
+    #[cfg(feature = "arrow")]
+    fn unix_timestamp(&self) -> Option<Decimal> {
+        self.value
+            .checked_sub(time_on_timeline(&DateTimeSevenPropertyModel {
+                year: Some(1970),
+                month: Some(1),
+                day: Some(1),
+                hour: Some(0),
+                minute: Some(0),
+                second: Some(Decimal::default()),
+                timezone_offset: Some(TimezoneOffset::utc()),
+            })?)
+    }
+
     fn from_be_bytes(bytes: [u8; 18]) -> Self {
         let mut value = [0; 16];
         value.copy_from_slice(&bytes[0..16]);
@@ -1319,6 +1440,20 @@ impl Timestamp {
         }
     }
 
+    /// Converts the timestamp to the timezone or makes it local if `timezone_offset` is `None`
+    ///
+    /// The timestamps without timezone are considered to be in the target timezone.
+    fn adjust(&self, timezone_offset: Option<TimezoneOffset>) -> Option<Self> {
+        Some(Self {
+            value: match (self.timezone_offset, timezone_offset) {
+                (None, None) | (Some(_), Some(_)) => self.value,
+                (Some(from), None) => self.value.checked_add(i64::from(from.offset) * 60)?,
+                (None, Some(to)) => self.value.checked_sub(i64::from(to.offset) * 60)?,
+            },
+            timezone_offset,
+        })
+    }
+
     fn checked_sub_seconds(&self, seconds: Decimal) -> Option<Self> {
         Some(Self {
             value: self.value.checked_sub(seconds)?,
@@ -1513,6 +1648,7 @@ pub struct DateTimeError {
 #[derive(Debug, Clone)]
 enum DateTimeErrorKind {
     InvalidDayOfMonth { day: u8, month: u8 },
+    InvalidTimezone(DayTimeDuration),
     Overflow,
     SystemTime(SystemTimeError),
 }
@@ -1523,6 +1659,9 @@ impl fmt::Display for DateTimeError {
             DateTimeErrorKind::InvalidDayOfMonth { day, month } => {
                 write!(f, "{} is not a valid day of {}", day, month)
             }
+            DateTimeErrorKind::InvalidTimezone(value) => {
+                write!(f, "{} is not a valid timezone offset", value)
+            }
             DateTimeErrorKind::Overflow => write!(f, "Overflow during date time normalization"),
             DateTimeErrorKind::SystemTime(error) => error.fmt(f),
         }
@@ -2231,6 +2370,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn adjust() {
+        let tz = |v: &str| {
+            Some(TimezoneOffset::try_from(DayTimeDuration::from_str(v).unwrap()).unwrap())
+        };
+        assert_eq!(
+            DateTime::from_str("2002-03-07T10:00:00")
+                .unwrap()
+                .adjust(tz("-PT5H")),
+            Some(DateTime::from_str("2002-03-07T10:00:00-05:00").unwrap())
+        );
+        assert_eq!(
+            DateTime::from_str("2002-03-07T10:00:00-07:00")
+                .unwrap()
+                .adjust(tz("-PT10H"))
+                .unwrap()
+                .to_string(),
+            "2002-03-07T07:00:00-10:00"
+        );
+        assert_eq!(
+            DateTime::from_str("2002-03-07T10:00:00-07:00")
+                .unwrap()
+                .adjust(None)
+                .unwrap()
+                .to_string(),
+            "2002-03-07T10:00:00"
+        );
+        assert_eq!(
+            Date::from_str("2002-03-07-07:00")
+                .unwrap()
+                .adjust(tz("-PT10H"))
+                .unwrap()
+                .to_string(),
+            "2002-03-06-10:00"
+        );
+        assert_eq!(
+            Date::from_str("2002-03-07")
+                .unwrap()
+                .adjust(tz("-PT10H"))
+                .unwrap()
+                .to_string(),
+            "2002-03-07-10:00"
+        );
+        assert_eq!(
+            Time::from_str("10:00:00-07:00")
+                .unwrap()
+                .adjust(tz("PT10H"))
+                .unwrap()
+                .to_string(),
+            "03:00:00+10:00"
+        );
+        assert_eq!(
+            Time::from_str("10:00:00-07:00")
+                .unwrap()
+                .adjust(None)
+                .unwrap()
+                .to_string(),
+            "10:00:00"
+        );
+        assert!(TimezoneOffset::try_from(DayTimeDuration::from_str("PT15H").unwrap()).is_err());
+        assert!(TimezoneOffset::try_from(DayTimeDuration::from_str("PT1H0.5S").unwrap()).is_err());
+    }
+
     #[test]
     fn sub_duration() {
         assert_eq!(
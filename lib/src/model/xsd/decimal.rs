@@ -5,7 +5,7 @@ use std::fmt::Write;
 use std::ops::Neg;
 use std::str::FromStr;
 
-const DECIMAL_PART_DIGITS: usize = 18;
+pub(super) const DECIMAL_PART_DIGITS: usize = 18;
 const DECIMAL_PART_POW: i128 = 1_000_000_000_000_000_000;
 const DECIMAL_PART_POW_MINUS_ONE: i128 = 100_000_000_000_000_000;
 const DECIMAL_PART_HALF_POW: i128 = 1_000_000_000;
@@ -150,12 +150,6 @@ impl Decimal {
         self.value > 0
     }
 
-    /// Creates a `Decimal` from a `f32` without taking care of precision
-    #[inline]
-    pub(crate) fn from_f32(v: f32) -> Self {
-        Self::from_f64(v.into())
-    }
-
     /// Creates a `f32` from a `Decimal` without taking care of precision
     #[inline]
     #[allow(clippy::cast_possible_truncation)]
@@ -165,6 +159,7 @@ impl Decimal {
 
     /// Creates a `Decimal` from a `f64` without taking care of precision
     #[inline]
+    #[cfg(target_arch = "wasm32")]
     #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
     pub(crate) fn from_f64(v: f64) -> Self {
         Self {
@@ -183,6 +178,16 @@ impl Decimal {
         self.value / DECIMAL_PART_POW
     }
 
+    /// Constructs the decimal from its value multiplied by 10^18
+    pub(super) const fn from_scaled_i128(value: i128) -> Self {
+        Self { value }
+    }
+
+    /// Returns the decimal value multiplied by 10^18
+    pub(super) const fn as_scaled_i128(&self) -> i128 {
+        self.value
+    }
+
     #[cfg(test)]
     pub(super) const fn min_value() -> Decimal {
         Self {
@@ -324,12 +329,19 @@ impl FromStr for Decimal {
 
             let mut with_after_dot = false;
             while cursor < input.len() && b'0' <= input[cursor] && input[cursor] <= b'9' {
-                exp = exp.checked_div(10).ok_or(PARSE_UNDERFLOW)?;
-                value = value
-                    .checked_mul(10)
-                    .ok_or(PARSE_OVERFLOW)?
-                    .checked_add((input[cursor] - b'0').into())
-                    .ok_or(PARSE_OVERFLOW)?;
+                if exp == 1 {
+                    // We are out of precision: only trailing zeros are allowed
+                    if input[cursor] != b'0' {
+                        return Err(PARSE_UNDERFLOW);
+                    }
+                } else {
+                    exp /= 10;
+                    value = value
+                        .checked_mul(10)
+                        .ok_or(PARSE_OVERFLOW)?
+                        .checked_add((input[cursor] - b'0').into())
+                        .ok_or(PARSE_OVERFLOW)?;
+                }
                 cursor += 1;
                 with_after_dot = true;
             }
@@ -369,16 +381,16 @@ enum ParseDecimalErrorKind {
     UnexpectedEnd,
 }
 
-const PARSE_OVERFLOW: ParseDecimalError = ParseDecimalError {
+pub(super) const PARSE_OVERFLOW: ParseDecimalError = ParseDecimalError {
     kind: ParseDecimalErrorKind::Overflow,
 };
-const PARSE_UNDERFLOW: ParseDecimalError = ParseDecimalError {
+pub(super) const PARSE_UNDERFLOW: ParseDecimalError = ParseDecimalError {
     kind: ParseDecimalErrorKind::Underflow,
 };
-const PARSE_UNEXPECTED_CHAR: ParseDecimalError = ParseDecimalError {
+pub(super) const PARSE_UNEXPECTED_CHAR: ParseDecimalError = ParseDecimalError {
     kind: ParseDecimalErrorKind::UnexpectedChar,
 };
-const PARSE_UNEXPECTED_END: ParseDecimalError = ParseDecimalError {
+pub(super) const PARSE_UNEXPECTED_END: ParseDecimalError = ParseDecimalError {
     kind: ParseDecimalErrorKind::UnexpectedEnd,
 };
 
@@ -532,6 +544,13 @@ mod tests {
         );
         assert_eq!(Decimal::from_str("0.1220").unwrap().to_string(), "0.122");
         assert_eq!(Decimal::from_str(".12200").unwrap().to_string(), "0.122");
+        assert_eq!(
+            Decimal::from_str("1.00000000000000000000")
+                .unwrap()
+                .to_string(),
+            "1"
+        );
+        assert!(Decimal::from_str("1.0000000000000000001").is_err());
         assert_eq!(
             Decimal::from_str(&Decimal::max_value().to_string()).unwrap(),
             Decimal::max_value()
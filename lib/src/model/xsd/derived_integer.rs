@@ -0,0 +1,157 @@
+use super::big_integer::BigInteger;
+use crate::model::vocab::xsd;
+use crate::model::NamedNodeRef;
+use std::convert::TryFrom;
+
+/// The [XML Schema datatypes derived from `integer`](https://www.w3.org/TR/xmlschema11-2/#built-in-datatypes).
+///
+/// Their values are `xsd:integer` values restricted to a range.
+/// They are promoted to `xsd:integer` by the SPARQL numeric operators.
+///
+/// ```
+/// use oxigraph::model::xsd::DerivedIntegerType;
+/// use oxigraph::model::vocab::xsd;
+///
+/// let datatype = DerivedIntegerType::from_name(xsd::UNSIGNED_BYTE).unwrap();
+/// assert_eq!(datatype.name(), xsd::UNSIGNED_BYTE);
+/// assert!(datatype.contains_i64(255));
+/// assert!(!datatype.contains_i64(256));
+/// assert_eq!(DerivedIntegerType::from_name(xsd::INTEGER), None);
+/// ```
+#[derive(Eq, PartialEq, Ord, PartialOrd, Debug, Clone, Copy, Hash)]
+pub enum DerivedIntegerType {
+    /// [`xsd:long`](https://www.w3.org/TR/xmlschema11-2/#long)
+    Long,
+    /// [`xsd:int`](https://www.w3.org/TR/xmlschema11-2/#int)
+    Int,
+    /// [`xsd:short`](https://www.w3.org/TR/xmlschema11-2/#short)
+    Short,
+    /// [`xsd:byte`](https://www.w3.org/TR/xmlschema11-2/#byte)
+    Byte,
+    /// [`xsd:nonNegativeInteger`](https://www.w3.org/TR/xmlschema11-2/#nonNegativeInteger)
+    NonNegativeInteger,
+    /// [`xsd:positiveInteger`](https://www.w3.org/TR/xmlschema11-2/#positiveInteger)
+    PositiveInteger,
+    /// [`xsd:nonPositiveInteger`](https://www.w3.org/TR/xmlschema11-2/#nonPositiveInteger)
+    NonPositiveInteger,
+    /// [`xsd:negativeInteger`](https://www.w3.org/TR/xmlschema11-2/#negativeInteger)
+    NegativeInteger,
+    /// [`xsd:unsignedLong`](https://www.w3.org/TR/xmlschema11-2/#unsignedLong)
+    UnsignedLong,
+    /// [`xsd:unsignedInt`](https://www.w3.org/TR/xmlschema11-2/#unsignedInt)
+    UnsignedInt,
+    /// [`xsd:unsignedShort`](https://www.w3.org/TR/xmlschema11-2/#unsignedShort)
+    UnsignedShort,
+    /// [`xsd:unsignedByte`](https://www.w3.org/TR/xmlschema11-2/#unsignedByte)
+    UnsignedByte,
+}
+
+impl DerivedIntegerType {
+    /// All the datatypes derived from `xsd:integer`
+    pub const ALL: [Self; 12] = [
+        Self::Long,
+        Self::Int,
+        Self::Short,
+        Self::Byte,
+        Self::NonNegativeInteger,
+        Self::PositiveInteger,
+        Self::NonPositiveInteger,
+        Self::NegativeInteger,
+        Self::UnsignedLong,
+        Self::UnsignedInt,
+        Self::UnsignedShort,
+        Self::UnsignedByte,
+    ];
+
+    /// Returns the datatype with the given IRI if it is derived from `xsd:integer`
+    pub fn from_name(name: NamedNodeRef<'_>) -> Option<Self> {
+        Self::ALL.iter().copied().find(|t| t.name() == name)
+    }
+
+    /// The IRI of the datatype
+    pub fn name(self) -> NamedNodeRef<'static> {
+        match self {
+            Self::Long => xsd::LONG,
+            Self::Int => xsd::INT,
+            Self::Short => xsd::SHORT,
+            Self::Byte => xsd::BYTE,
+            Self::NonNegativeInteger => xsd::NON_NEGATIVE_INTEGER,
+            Self::PositiveInteger => xsd::POSITIVE_INTEGER,
+            Self::NonPositiveInteger => xsd::NON_POSITIVE_INTEGER,
+            Self::NegativeInteger => xsd::NEGATIVE_INTEGER,
+            Self::UnsignedLong => xsd::UNSIGNED_LONG,
+            Self::UnsignedInt => xsd::UNSIGNED_INT,
+            Self::UnsignedShort => xsd::UNSIGNED_SHORT,
+            Self::UnsignedByte => xsd::UNSIGNED_BYTE,
+        }
+    }
+
+    /// Checks if the value is in the value space of the datatype
+    pub fn contains_i64(self, value: i64) -> bool {
+        match self {
+            Self::Long => true,
+            Self::Int => i32::try_from(value).is_ok(),
+            Self::Short => i16::try_from(value).is_ok(),
+            Self::Byte => i8::try_from(value).is_ok(),
+            Self::NonNegativeInteger | Self::UnsignedLong => value >= 0,
+            Self::PositiveInteger => value > 0,
+            Self::NonPositiveInteger => value <= 0,
+            Self::NegativeInteger => value < 0,
+            Self::UnsignedInt => u32::try_from(value).is_ok(),
+            Self::UnsignedShort => u16::try_from(value).is_ok(),
+            Self::UnsignedByte => u8::try_from(value).is_ok(),
+        }
+    }
+
+    /// Checks if the value is in the value space of the datatype
+    pub fn contains(self, value: &BigInteger) -> bool {
+        if let Ok(value) = i64::try_from(value) {
+            return self.contains_i64(value);
+        }
+        match self {
+            Self::NonNegativeInteger | Self::PositiveInteger => value.is_positive(),
+            Self::NonPositiveInteger | Self::NegativeInteger => value.is_negative(),
+            Self::UnsignedLong => value.is_positive() && *value <= BigInteger::from(u64::MAX),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn from_name() {
+        for datatype in &DerivedIntegerType::ALL {
+            assert_eq!(
+                DerivedIntegerType::from_name(datatype.name()),
+                Some(*datatype)
+            );
+        }
+        assert_eq!(DerivedIntegerType::from_name(xsd::DECIMAL), None);
+    }
+
+    #[test]
+    fn contains() {
+        assert!(DerivedIntegerType::Byte.contains_i64(-128));
+        assert!(!DerivedIntegerType::Byte.contains_i64(128));
+        assert!(DerivedIntegerType::Int.contains_i64(i32::MAX.into()));
+        assert!(!DerivedIntegerType::Int.contains_i64(i64::from(i32::MAX) + 1));
+        assert!(DerivedIntegerType::NonNegativeInteger.contains_i64(0));
+        assert!(!DerivedIntegerType::PositiveInteger.contains_i64(0));
+        assert!(DerivedIntegerType::NonPositiveInteger.contains_i64(0));
+        assert!(!DerivedIntegerType::NegativeInteger.contains_i64(0));
+        assert!(!DerivedIntegerType::UnsignedShort.contains_i64(-1));
+
+        let big = BigInteger::from_str("18446744073709551615").unwrap();
+        assert!(DerivedIntegerType::UnsignedLong.contains(&big));
+        assert!(DerivedIntegerType::PositiveInteger.contains(&big));
+        assert!(!DerivedIntegerType::Long.contains(&big));
+        assert!(
+            !DerivedIntegerType::UnsignedLong.contains(&(big.clone() + BigInteger::from(1_i64)))
+        );
+        assert!(DerivedIntegerType::NegativeInteger.contains(&-big));
+    }
+}
@@ -1,9 +1,28 @@
-pub mod date_time;
-pub mod decimal;
+//! Implementations of some [XML Schema datatypes](https://www.w3.org/TR/xmlschema11-2/) used by RDF literals.
+//!
+//! They are used by the SPARQL evaluator and allow to manipulate the values of the typed literals without external date libraries.
+//! Integers and decimals that do not fit into the fixed size [`i64`] and [`Decimal`] encodings are handled by [`BigInteger`] and [`BigDecimal`].
+//! The datatypes derived from `xsd:integer` are described by [`DerivedIntegerType`].
+//! [`HexBinary`] and [`Base64Binary`] hold the octets of the binary datatypes.
+
+mod any_uri;
+mod big_decimal;
+mod big_integer;
+mod binary;
+mod date_time;
+mod decimal;
+mod derived_integer;
 mod duration;
 mod parser;
 
-pub use self::date_time::{Date, DateTime, GDay, GMonth, GMonthDay, GYear, GYearMonth, Time};
-pub use self::decimal::Decimal;
+pub(crate) use self::any_uri::parse_any_uri;
+pub use self::big_decimal::BigDecimal;
+pub use self::big_integer::BigInteger;
+pub use self::binary::{Base64Binary, HexBinary};
+pub use self::date_time::{
+    Date, DateTime, DateTimeError, GDay, GMonth, GMonthDay, GYear, GYearMonth, Time, TimezoneOffset,
+};
+pub use self::decimal::{Decimal, DecimalOverflowError, ParseDecimalError};
+pub use self::derived_integer::DerivedIntegerType;
 pub use self::duration::{DayTimeDuration, Duration, YearMonthDuration};
 pub use self::parser::XsdParseError;
@@ -14,6 +14,8 @@ use super::date_time::DateTimeError;
 use super::decimal::ParseDecimalError;
 use crate::model::xsd::date_time::{GDay, GMonth, GMonthDay, GYear, GYearMonth, TimezoneOffset};
 use crate::model::xsd::duration::{DayTimeDuration, YearMonthDuration};
+use base64::DecodeError;
+use hex::FromHexError;
 use std::error::Error;
 use std::fmt;
 use std::num::ParseIntError;
@@ -34,6 +36,8 @@ enum XsdParseErrorKind {
     ParseDecimal(ParseDecimalError),
     OutOfIntegerRange { value: u8, min: u8, max: u8 },
     DateTime(DateTimeError),
+    HexBinary(FromHexError),
+    Base64Binary(DecodeError),
 }
 
 impl fmt::Display for XsdParseError {
@@ -67,6 +71,12 @@ impl fmt::Display for XsdParseError {
                 value, min, max
             ),
             XsdParseErrorKind::DateTime(error) => error.fmt(f),
+            XsdParseErrorKind::HexBinary(error) => {
+                write!(f, "Error while parsing hexBinary: {}", error)
+            }
+            XsdParseErrorKind::Base64Binary(error) => {
+                write!(f, "Error while parsing base64Binary: {}", error)
+            }
         }
     }
 }
@@ -77,6 +87,8 @@ impl Error for XsdParseError {
             XsdParseErrorKind::ParseInt(error) => Some(error),
             XsdParseErrorKind::ParseDecimal(error) => Some(error),
             XsdParseErrorKind::DateTime(error) => Some(error),
+            XsdParseErrorKind::HexBinary(error) => Some(error),
+            XsdParseErrorKind::Base64Binary(error) => Some(error),
             _ => None,
         }
     }
@@ -128,6 +140,22 @@ impl From<DateTimeError> for XsdParseError {
     }
 }
 
+impl From<FromHexError> for XsdParseError {
+    fn from(error: FromHexError) -> Self {
+        XsdParseError {
+            kind: XsdParseErrorKind::HexBinary(error),
+        }
+    }
+}
+
+impl From<DecodeError> for XsdParseError {
+    fn from(error: DecodeError) -> Self {
+        XsdParseError {
+            kind: XsdParseErrorKind::Base64Binary(error),
+        }
+    }
+}
+
 impl From<Err<XsdParseError>> for XsdParseError {
     fn from(err: Err<XsdParseError>) -> Self {
         match err {
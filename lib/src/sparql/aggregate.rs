@@ -0,0 +1,15 @@
+use crate::model::Term;
+
+/// The state of a custom SPARQL aggregate function for a group of solutions.
+///
+/// A new accumulator is built for each group by the function given to [`QueryOptions`](super::QueryOptions::with_custom_aggregate_function()).
+/// See its documentation for an example.
+pub trait AggregateAccumulator {
+    /// Adds the value of the aggregated expression for a solution of the group.
+    ///
+    /// It is `None` if the expression is unbound or its evaluation failed.
+    fn add(&mut self, element: Option<Term>);
+
+    /// Returns the value of the aggregate for the solutions added so far or `None` if it is an error.
+    fn state(&self) -> Option<Term>;
+}
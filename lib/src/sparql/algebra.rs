@@ -9,7 +9,7 @@ use crate::sparql::model::*;
 use crate::sparql::parser::{parse_query, parse_update, ParseError};
 use oxiri::Iri;
 use rio_api::model as rio;
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashSet};
 use std::convert::TryFrom;
 use std::fmt;
 use std::rc::Rc;
@@ -76,7 +76,7 @@ pub enum Query {
 impl Query {
     /// Parses a SPARQL query with an optional base IRI to resolve relative IRIs in the query
     pub fn parse(query: &str, base_iri: Option<&str>) -> Result<Self, ParseError> {
-        parse_query(query, base_iri)
+        parse_query(query, base_iri, HashSet::new())
     }
 
     /// Returns [the query dataset specification](https://www.w3.org/TR/sparql11-query/#specifyingDataset)
@@ -222,7 +222,7 @@ pub struct Update {
 impl Update {
     /// Parses a SPARQL update with an optional base IRI to resolve relative IRIs in the query
     pub fn parse(update: &str, base_iri: Option<&str>) -> Result<Self, ParseError> {
-        parse_update(update, base_iri)
+        parse_update(update, base_iri, HashSet::new())
     }
 }
 
@@ -295,6 +295,8 @@ impl From<Variable> for NamedNodeOrVariable {
 pub enum TermOrVariable {
     Term(Term),
     Variable(Variable),
+    /// An [RDF-star](https://w3c.github.io/rdf-star/cg-spec/) quoted triple pattern
+    Triple(Box<TriplePattern>),
 }
 
 impl fmt::Display for TermOrVariable {
@@ -302,6 +304,11 @@ impl fmt::Display for TermOrVariable {
         match self {
             TermOrVariable::Term(term) => term.fmt(f),
             TermOrVariable::Variable(var) => var.fmt(f),
+            TermOrVariable::Triple(triple) => write!(
+                f,
+                "<< {} {} {} >>",
+                triple.subject, triple.predicate, triple.object
+            ),
         }
     }
 }
@@ -336,6 +343,12 @@ impl From<Term> for TermOrVariable {
     }
 }
 
+impl From<TriplePattern> for TermOrVariable {
+    fn from(triple: TriplePattern) -> Self {
+        TermOrVariable::Triple(Box::new(triple))
+    }
+}
+
 impl From<NamedNodeOrVariable> for TermOrVariable {
     fn from(element: NamedNodeOrVariable) -> Self {
         match element {
@@ -574,6 +587,119 @@ pub enum Expression {
     FunctionCall(Function, Vec<Expression>),
 }
 
+impl Expression {
+    /// Checks if one of the functions called in the expression, excluding the nested patterns, matches `predicate`
+    pub(crate) fn calls_function(&self, predicate: &impl Fn(&Function) -> bool) -> bool {
+        match self {
+            Expression::NamedNode(_)
+            | Expression::Literal(_)
+            | Expression::Variable(_)
+            | Expression::Bound(_) => false,
+            Expression::Or(a, b)
+            | Expression::And(a, b)
+            | Expression::Equal(a, b)
+            | Expression::SameTerm(a, b)
+            | Expression::Greater(a, b)
+            | Expression::GreaterOrEqual(a, b)
+            | Expression::Less(a, b)
+            | Expression::LessOrEqual(a, b)
+            | Expression::Add(a, b)
+            | Expression::Subtract(a, b)
+            | Expression::Multiply(a, b)
+            | Expression::Divide(a, b) => {
+                a.calls_function(predicate) || b.calls_function(predicate)
+            }
+            Expression::UnaryPlus(e) | Expression::UnaryMinus(e) | Expression::Not(e) => {
+                e.calls_function(predicate)
+            }
+            Expression::In(a, l) => {
+                a.calls_function(predicate) || l.iter().any(|e| e.calls_function(predicate))
+            }
+            // The nested patterns have their own aggregates
+            Expression::Exists(_) => false,
+            Expression::If(a, b, c) => {
+                a.calls_function(predicate)
+                    || b.calls_function(predicate)
+                    || c.calls_function(predicate)
+            }
+            Expression::Coalesce(l) => l.iter().any(|e| e.calls_function(predicate)),
+            Expression::FunctionCall(function, parameters) => {
+                predicate(function) || parameters.iter().any(|e| e.calls_function(predicate))
+            }
+        }
+    }
+
+    /// Replaces the calls to the custom aggregate functions by variables and adds the aggregates to `aggregates`
+    fn replace_custom_aggregates(
+        self,
+        is_aggregate: &impl Fn(&NamedNode) -> bool,
+        aggregates: &mut Vec<(Variable, AggregationFunction)>,
+    ) -> Self {
+        let mut replace =
+            |e: Box<Expression>| Box::new(e.replace_custom_aggregates(is_aggregate, aggregates));
+        match self {
+            Expression::Or(a, b) => Expression::Or(replace(a), replace(b)),
+            Expression::And(a, b) => Expression::And(replace(a), replace(b)),
+            Expression::Equal(a, b) => Expression::Equal(replace(a), replace(b)),
+            Expression::SameTerm(a, b) => Expression::SameTerm(replace(a), replace(b)),
+            Expression::Greater(a, b) => Expression::Greater(replace(a), replace(b)),
+            Expression::GreaterOrEqual(a, b) => Expression::GreaterOrEqual(replace(a), replace(b)),
+            Expression::Less(a, b) => Expression::Less(replace(a), replace(b)),
+            Expression::LessOrEqual(a, b) => Expression::LessOrEqual(replace(a), replace(b)),
+            Expression::Add(a, b) => Expression::Add(replace(a), replace(b)),
+            Expression::Subtract(a, b) => Expression::Subtract(replace(a), replace(b)),
+            Expression::Multiply(a, b) => Expression::Multiply(replace(a), replace(b)),
+            Expression::Divide(a, b) => Expression::Divide(replace(a), replace(b)),
+            Expression::UnaryPlus(e) => Expression::UnaryPlus(replace(e)),
+            Expression::UnaryMinus(e) => Expression::UnaryMinus(replace(e)),
+            Expression::Not(e) => Expression::Not(replace(e)),
+            Expression::If(a, b, c) => Expression::If(replace(a), replace(b), replace(c)),
+            Expression::In(a, l) => Expression::In(
+                replace(a),
+                l.into_iter()
+                    .map(|e| e.replace_custom_aggregates(is_aggregate, aggregates))
+                    .collect(),
+            ),
+            Expression::Coalesce(l) => Expression::Coalesce(
+                l.into_iter()
+                    .map(|e| e.replace_custom_aggregates(is_aggregate, aggregates))
+                    .collect(),
+            ),
+            Expression::FunctionCall(Function::Custom(name), mut parameters)
+                if parameters.len() == 1 && is_aggregate(&name) =>
+            {
+                let aggregate = AggregationFunction::Custom {
+                    name,
+                    expr: Box::new(parameters.pop().unwrap()),
+                    distinct: false,
+                };
+                let variable = aggregates
+                    .iter()
+                    .find_map(|(v, a)| if a == &aggregate { Some(v) } else { None })
+                    .cloned()
+                    .unwrap_or_else(|| {
+                        let variable = Variable::new_random();
+                        aggregates.push((variable.clone(), aggregate));
+                        variable
+                    });
+                Expression::Variable(variable)
+            }
+            Expression::FunctionCall(function, parameters) => Expression::FunctionCall(
+                function,
+                parameters
+                    .into_iter()
+                    .map(|e| e.replace_custom_aggregates(is_aggregate, aggregates))
+                    .collect(),
+            ),
+            e @ Expression::NamedNode(_)
+            | e @ Expression::Literal(_)
+            | e @ Expression::Variable(_)
+            | e @ Expression::Exists(_)
+            | e @ Expression::Bound(_) => e,
+        }
+    }
+}
+
 impl fmt::Display for Expression {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -799,6 +925,11 @@ pub enum Function {
     IsLiteral,
     IsNumeric,
     Regex,
+    Triple,
+    Subject,
+    Predicate,
+    Object,
+    IsTriple,
     Custom(NamedNode),
 }
 
@@ -851,6 +982,11 @@ impl fmt::Display for Function {
             Function::IsLiteral => write!(f, "isLITERAL"),
             Function::IsNumeric => write!(f, "isNUMERIC"),
             Function::Regex => write!(f, "REGEX"),
+            Function::Triple => write!(f, "TRIPLE"),
+            Function::Subject => write!(f, "SUBJECT"),
+            Function::Predicate => write!(f, "PREDICATE"),
+            Function::Object => write!(f, "OBJECT"),
+            Function::IsTriple => write!(f, "isTRIPLE"),
             Function::Custom(iri) => iri.fmt(f),
         }
     }
@@ -1076,26 +1212,14 @@ impl GraphPattern {
         match self {
             GraphPattern::BGP(p) => {
                 for pattern in p {
-                    if let TermOrVariable::Variable(s) = &pattern.subject {
-                        vars.insert(s);
-                    }
-                    if let NamedNodeOrVariable::Variable(p) = &pattern.predicate {
-                        vars.insert(p);
-                    }
-                    if let TermOrVariable::Variable(o) = &pattern.object {
-                        vars.insert(o);
-                    }
+                    add_triple_pattern_variables(pattern, vars)
                 }
             }
             GraphPattern::Path {
                 subject, object, ..
             } => {
-                if let TermOrVariable::Variable(s) = subject {
-                    vars.insert(s);
-                }
-                if let TermOrVariable::Variable(o) = object {
-                    vars.insert(o);
-                }
+                add_term_or_variable_variables(subject, vars);
+                add_term_or_variable_variables(object, vars);
             }
             GraphPattern::Join { left, right }
             | GraphPattern::LeftJoin { left, right, .. }
@@ -1130,6 +1254,235 @@ impl GraphPattern {
             | GraphPattern::Slice { inner, .. } => inner.add_visible_variables(vars),
         }
     }
+
+    /// Rewrites the calls to the custom aggregate functions parsed as regular function calls into aggregates
+    ///
+    /// It happens when the query has been parsed without the knowledge of the custom aggregate functions,
+    /// for example using [`Query::parse`] instead of [`QueryOptions::parse_query`](super::QueryOptions::parse_query).
+    /// The aggregates are built like the parser does: the `SELECT` expressions, `HAVING` conditions and `ORDER BY` conditions
+    /// calling the custom aggregate functions are evaluated on top of a [`GraphPattern::Group`].
+    pub(crate) fn resolve_custom_aggregates(
+        self,
+        is_aggregate: &impl Fn(&NamedNode) -> bool,
+    ) -> Self {
+        match self {
+            GraphPattern::Project { inner, projection } => GraphPattern::Project {
+                inner: Box::new(inner.resolve_selection_aggregates(is_aggregate)),
+                projection,
+            },
+            GraphPattern::Join { left, right } => GraphPattern::Join {
+                left: Box::new(left.resolve_custom_aggregates(is_aggregate)),
+                right: Box::new(right.resolve_custom_aggregates(is_aggregate)),
+            },
+            GraphPattern::LeftJoin { left, right, expr } => GraphPattern::LeftJoin {
+                left: Box::new(left.resolve_custom_aggregates(is_aggregate)),
+                right: Box::new(right.resolve_custom_aggregates(is_aggregate)),
+                expr,
+            },
+            GraphPattern::Union { left, right } => GraphPattern::Union {
+                left: Box::new(left.resolve_custom_aggregates(is_aggregate)),
+                right: Box::new(right.resolve_custom_aggregates(is_aggregate)),
+            },
+            GraphPattern::Minus { left, right } => GraphPattern::Minus {
+                left: Box::new(left.resolve_custom_aggregates(is_aggregate)),
+                right: Box::new(right.resolve_custom_aggregates(is_aggregate)),
+            },
+            GraphPattern::Filter { expr, inner } => GraphPattern::Filter {
+                expr,
+                inner: Box::new(inner.resolve_custom_aggregates(is_aggregate)),
+            },
+            GraphPattern::Graph { graph_name, inner } => GraphPattern::Graph {
+                graph_name,
+                inner: Box::new(inner.resolve_custom_aggregates(is_aggregate)),
+            },
+            GraphPattern::Extend { inner, var, expr } => GraphPattern::Extend {
+                inner: Box::new(inner.resolve_custom_aggregates(is_aggregate)),
+                var,
+                expr,
+            },
+            GraphPattern::OrderBy { inner, condition } => GraphPattern::OrderBy {
+                inner: Box::new(inner.resolve_custom_aggregates(is_aggregate)),
+                condition,
+            },
+            GraphPattern::Distinct { inner } => GraphPattern::Distinct {
+                inner: Box::new(inner.resolve_custom_aggregates(is_aggregate)),
+            },
+            GraphPattern::Reduced { inner } => GraphPattern::Reduced {
+                inner: Box::new(inner.resolve_custom_aggregates(is_aggregate)),
+            },
+            GraphPattern::Slice {
+                inner,
+                start,
+                length,
+            } => GraphPattern::Slice {
+                inner: Box::new(inner.resolve_custom_aggregates(is_aggregate)),
+                start,
+                length,
+            },
+            GraphPattern::Group {
+                inner,
+                by,
+                aggregates,
+            } => GraphPattern::Group {
+                inner: Box::new(inner.resolve_custom_aggregates(is_aggregate)),
+                by,
+                aggregates,
+            },
+            GraphPattern::Service {
+                name,
+                pattern,
+                silent,
+            } => GraphPattern::Service {
+                name,
+                pattern: Box::new(pattern.resolve_custom_aggregates(is_aggregate)),
+                silent,
+            },
+            p @ GraphPattern::BGP(_)
+            | p @ GraphPattern::Path { .. }
+            | p @ GraphPattern::Table { .. } => p,
+        }
+    }
+
+    /// Resolves the custom aggregates of the pattern below a [`GraphPattern::Project`]
+    fn resolve_selection_aggregates(self, is_aggregate: &impl Fn(&NamedNode) -> bool) -> Self {
+        let calls_aggregate = |expr: &Expression| {
+            expr.calls_function(&|function| {
+                if let Function::Custom(name) = function {
+                    is_aggregate(name)
+                } else {
+                    false
+                }
+            })
+        };
+
+        let (mut pattern, order_by) = match self {
+            GraphPattern::OrderBy { inner, condition } => (*inner, Some(condition)),
+            pattern => (pattern, None),
+        };
+        let mut extends = Vec::new();
+        while let GraphPattern::Extend { inner, var, expr } = pattern {
+            extends.push((var, expr));
+            pattern = *inner;
+        }
+        let (mut pattern, having) = match pattern {
+            GraphPattern::Filter { expr, inner }
+                if matches!(*inner, GraphPattern::Group { .. }) =>
+            {
+                (*inner, Some(expr))
+            }
+            pattern => (pattern, None),
+        };
+
+        let has_aggregates = extends.iter().any(|(_, expr)| calls_aggregate(expr))
+            || having.as_ref().is_some_and(calls_aggregate)
+            || order_by.iter().flatten().any(|c| match c {
+                OrderComparator::Asc(e) | OrderComparator::Desc(e) => calls_aggregate(e),
+            });
+        let is_grouped = matches!(pattern, GraphPattern::Group { .. });
+        if has_aggregates && !is_grouped {
+            // Without GROUP BY, the BINDs of the WHERE clause are also Extend patterns:
+            // only the SELECT expressions up to the last one calling an aggregate are evaluated after the grouping
+            let selection_len = extends
+                .iter()
+                .rposition(|(_, expr)| calls_aggregate(expr))
+                .map_or(0, |i| i + 1);
+            for (var, expr) in extends.split_off(selection_len).into_iter().rev() {
+                pattern = GraphPattern::Extend {
+                    inner: Box::new(pattern),
+                    var,
+                    expr,
+                };
+            }
+        }
+        let mut pattern = pattern.resolve_custom_aggregates(is_aggregate);
+
+        let (extends, having, order_by) = if has_aggregates {
+            let mut new_aggregates = Vec::new();
+            let mut replace = |expr: Expression| {
+                expr.replace_custom_aggregates(is_aggregate, &mut new_aggregates)
+            };
+            let extends = extends
+                .into_iter()
+                .map(|(var, expr)| (var, replace(expr)))
+                .collect::<Vec<_>>();
+            let having = having.map(&mut replace);
+            let order_by = order_by.map(|condition| {
+                condition
+                    .into_iter()
+                    .map(|c| match c {
+                        OrderComparator::Asc(e) => OrderComparator::Asc(replace(e)),
+                        OrderComparator::Desc(e) => OrderComparator::Desc(replace(e)),
+                    })
+                    .collect()
+            });
+            pattern = if let GraphPattern::Group {
+                inner,
+                by,
+                mut aggregates,
+            } = pattern
+            {
+                aggregates.extend(new_aggregates);
+                GraphPattern::Group {
+                    inner,
+                    by,
+                    aggregates,
+                }
+            } else {
+                let const_variable = Variable::new_random();
+                GraphPattern::Group {
+                    inner: Box::new(GraphPattern::Extend {
+                        inner: Box::new(pattern),
+                        var: const_variable.clone(),
+                        expr: Literal::from(1).into(),
+                    }),
+                    by: vec![const_variable],
+                    aggregates: new_aggregates,
+                }
+            };
+            (extends, having, order_by)
+        } else {
+            (extends, having, order_by)
+        };
+
+        if let Some(expr) = having {
+            pattern = GraphPattern::Filter {
+                expr,
+                inner: Box::new(pattern),
+            };
+        }
+        for (var, expr) in extends.into_iter().rev() {
+            pattern = GraphPattern::Extend {
+                inner: Box::new(pattern),
+                var,
+                expr,
+            };
+        }
+        if let Some(condition) = order_by {
+            pattern = GraphPattern::OrderBy {
+                inner: Box::new(pattern),
+                condition,
+            };
+        }
+        pattern
+    }
+}
+
+fn add_triple_pattern_variables<'a>(pattern: &'a TriplePattern, vars: &mut BTreeSet<&'a Variable>) {
+    add_term_or_variable_variables(&pattern.subject, vars);
+    if let NamedNodeOrVariable::Variable(p) = &pattern.predicate {
+        vars.insert(p);
+    }
+    add_term_or_variable_variables(&pattern.object, vars);
+}
+
+fn add_term_or_variable_variables<'a>(term: &'a TermOrVariable, vars: &mut BTreeSet<&'a Variable>) {
+    match term {
+        TermOrVariable::Variable(v) => {
+            vars.insert(v);
+        }
+        TermOrVariable::Triple(t) => add_triple_pattern_variables(t, vars),
+        TermOrVariable::Term(_) => (),
+    }
 }
 
 struct SparqlGraphPattern<'a>(&'a GraphPattern);
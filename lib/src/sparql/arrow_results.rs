@@ -0,0 +1,822 @@
+//! Export of SPARQL query solutions to [Apache Arrow](https://arrow.apache.org/) record batches and [Apache Parquet](https://parquet.apache.org/) files
+
+use crate::error::UnwrapInfallible;
+use crate::model::*;
+use crate::sparql::error::EvaluationError;
+use crate::sparql::model::*;
+use crate::store::numeric_encoder::{
+    EncodedTerm, StrContainer, StrEncodingAware, StrId, WriteEncoder,
+};
+use arrow_array::types::{Int32Type, Int8Type};
+use arrow_array::{
+    ArrayRef, BooleanArray, Date32Array, Decimal128Array, DictionaryArray, Float32Array,
+    Float64Array, Int64Array, RecordBatch, RecordBatchReader, StringArray, Time64MicrosecondArray,
+    TimestampMicrosecondArray,
+};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef, TimeUnit};
+use parquet::arrow::ArrowWriter;
+use std::convert::Infallible;
+use std::io::Write;
+use std::iter::FromIterator;
+use std::sync::Arc;
+
+/// Exports SPARQL query solutions to [Apache Arrow](https://arrow.apache.org/) record batches or [Apache Parquet](https://parquet.apache.org/) files.
+///
+/// Each variable is mapped to a main column with the variable name and to a few sidecar columns:
+/// * The main column type is inferred from the first batch of solutions.
+///   If all the bound values of the variable are `xsd:boolean`, `xsd:integer` (or one of its derived types), `xsd:decimal`, `xsd:float`, `xsd:double`, `xsd:dateTime`, `xsd:date` or `xsd:time` literals with the same datatype,
+///   the column has the matching native Arrow type (`Boolean`, `Int64`, `Decimal128(38, 18)`, `Float32`, `Float64`, `Timestamp(Microsecond)`, `Date32` or `Time64(Microsecond)`).
+///   Else it is a `Utf8` column containing the IRIs, the blank node identifiers, the literal lexical forms and the serialization of the triples.
+/// * If the main column has a native type, a `<variable>_string` `Utf8` column contains the string form of the values that do not fit into it,
+///   for example because a later solution binds the variable to a literal of an other datatype or to a decimal with more than 20 integer digits.
+///   The main column is null for these values.
+/// * A `<variable>_kind` dictionary column gives the kind of each term (`iri`, `bnode`, `literal` or `triple`).
+/// * A `<variable>_datatype` dictionary column gives the datatype IRI of each literal.
+/// * A `<variable>_language` dictionary column gives the language tag of each language-tagged string literal.
+///
+/// Unbound values are nulls in all the columns.
+///
+/// ```
+/// use oxigraph::MemoryStore;
+/// use oxigraph::model::*;
+/// use oxigraph::sparql::{ArrowExporter, QueryResults};
+///
+/// let store = MemoryStore::new();
+/// let ex = NamedNode::new("http://example.com")?;
+/// store.insert(Quad::new(ex.clone(), ex.clone(), Literal::from(12), None));
+///
+/// if let QueryResults::Solutions(solutions) = store.query("SELECT ?s ?o WHERE { ?s ?p ?o }")? {
+///     let batches = ArrowExporter::new().record_batches(solutions)?.collect::<Result<Vec<_>, _>>()?;
+///     assert_eq!(batches.len(), 1);
+///     assert_eq!(batches[0].num_rows(), 1);
+///     let schema = batches[0].schema();
+///     let names = schema.fields().iter().map(|f| f.name().as_str()).collect::<Vec<_>>();
+///     assert_eq!(names, ["s", "s_kind", "s_datatype", "s_language", "o", "o_string", "o_kind", "o_datatype", "o_language"]);
+/// }
+/// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ArrowExporter {
+    batch_size: usize,
+}
+
+impl ArrowExporter {
+    pub fn new() -> Self {
+        Self { batch_size: 1024 }
+    }
+
+    /// Sets the maximal number of solutions in each record batch.
+    ///
+    /// The default value is 1024.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Converts the solutions into an iterator of Arrow record batches.
+    ///
+    /// The first batch is read by this method in order to infer the schema.
+    pub fn record_batches(
+        &self,
+        mut solutions: QuerySolutionIter,
+    ) -> Result<RecordBatchIter, EvaluationError> {
+        let first_batch = read_batch(&mut solutions, self.batch_size)?;
+        let variables = solutions.variables().to_vec();
+        let column_types = (0..variables.len())
+            .map(|i| infer_column_type(first_batch.iter().filter_map(|s| s.get(i))))
+            .collect::<Vec<_>>();
+        let mut fields = Vec::new();
+        for (variable, column_type) in variables.iter().zip(&column_types) {
+            let variable = variable.as_str();
+            fields.push(Field::new(variable, column_type.data_type(), true));
+            if *column_type != ColumnType::String {
+                fields.push(Field::new(
+                    format!("{}_string", variable),
+                    DataType::Utf8,
+                    true,
+                ));
+            }
+            fields.push(Field::new(
+                format!("{}_kind", variable),
+                kind_data_type(),
+                true,
+            ));
+            fields.push(Field::new(
+                format!("{}_datatype", variable),
+                iri_data_type(),
+                true,
+            ));
+            fields.push(Field::new(
+                format!("{}_language", variable),
+                iri_data_type(),
+                true,
+            ));
+        }
+        Ok(RecordBatchIter {
+            schema: Arc::new(Schema::new(fields)),
+            column_types,
+            solutions,
+            first_batch: Some(first_batch),
+            batch_size: self.batch_size,
+        })
+    }
+
+    /// Writes the solutions into a Parquet file.
+    ///
+    /// ```
+    /// use oxigraph::MemoryStore;
+    /// use oxigraph::sparql::{ArrowExporter, QueryResults};
+    ///
+    /// let store = MemoryStore::new();
+    /// if let QueryResults::Solutions(solutions) = store.query("SELECT ?s WHERE { ?s ?p ?o }")? {
+    ///     let mut file = Vec::new();
+    ///     ArrowExporter::new().write_parquet(solutions, &mut file)?;
+    ///     assert!(file.starts_with(b"PAR1"));
+    /// }
+    /// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn write_parquet(
+        &self,
+        solutions: QuerySolutionIter,
+        writer: impl Write + Send,
+    ) -> Result<(), EvaluationError> {
+        let batches = self.record_batches(solutions)?;
+        let mut writer =
+            ArrowWriter::try_new(writer, batches.schema(), None).map_err(EvaluationError::wrap)?;
+        for batch in batches {
+            writer
+                .write(&batch.map_err(map_arrow_error)?)
+                .map_err(EvaluationError::wrap)?;
+        }
+        writer.close().map_err(EvaluationError::wrap)?;
+        Ok(())
+    }
+}
+
+impl Default for ArrowExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An iterator over the Arrow record batches built from SPARQL query solutions.
+///
+/// It is returned by [`ArrowExporter::record_batches`].
+pub struct RecordBatchIter {
+    schema: SchemaRef,
+    column_types: Vec<ColumnType>,
+    solutions: QuerySolutionIter,
+    first_batch: Option<Vec<QuerySolution>>,
+    batch_size: usize,
+}
+
+impl RecordBatchIter {
+    fn build_batch(&self, solutions: &[QuerySolution]) -> Result<RecordBatch, ArrowError> {
+        let mut columns: Vec<ArrayRef> = Vec::new();
+        for (i, column_type) in self.column_types.iter().enumerate() {
+            let terms = solutions.iter().map(|s| s.get(i)).collect::<Vec<_>>();
+            if *column_type == ColumnType::String {
+                columns.push(Arc::new(
+                    terms
+                        .iter()
+                        .map(|t| t.map(term_value))
+                        .collect::<StringArray>(),
+                ));
+            } else {
+                let values = terms
+                    .iter()
+                    .map(|t| {
+                        t.and_then(native_value)
+                            .filter(|v| v.column_type() == *column_type)
+                    })
+                    .collect::<Vec<_>>();
+                // The values not fitting into the native column are kept as strings
+                let strings = terms
+                    .iter()
+                    .zip(&values)
+                    .map(|(t, v)| if v.is_none() { t.map(term_value) } else { None })
+                    .collect::<StringArray>();
+                columns.push(build_native_column(*column_type, values)?);
+                columns.push(Arc::new(strings));
+            }
+            columns.push(Arc::new(
+                terms
+                    .iter()
+                    .map(|t| t.map(term_kind))
+                    .collect::<DictionaryArray<Int8Type>>(),
+            ));
+            columns.push(Arc::new(
+                terms
+                    .iter()
+                    .map(|t| t.and_then(term_datatype))
+                    .collect::<DictionaryArray<Int32Type>>(),
+            ));
+            columns.push(Arc::new(
+                terms
+                    .iter()
+                    .map(|t| t.and_then(term_language))
+                    .collect::<DictionaryArray<Int32Type>>(),
+            ));
+        }
+        RecordBatch::try_new(self.schema.clone(), columns)
+    }
+}
+
+fn build_native_column(
+    column_type: ColumnType,
+    values: Vec<Option<NativeValue>>,
+) -> Result<ArrayRef, ArrowError> {
+    Ok(match column_type {
+        ColumnType::Boolean => Arc::new(native_array::<_, BooleanArray>(values, |v| match v {
+            NativeValue::Boolean(v) => Some(v),
+            _ => None,
+        })),
+        ColumnType::Integer => Arc::new(native_array::<_, Int64Array>(values, |v| match v {
+            NativeValue::Integer(v) => Some(v),
+            _ => None,
+        })),
+        ColumnType::Decimal => Arc::new(
+            native_array::<_, Decimal128Array>(values, |v| match v {
+                NativeValue::Decimal(v) => Some(v),
+                _ => None,
+            })
+            .with_precision_and_scale(DECIMAL_PRECISION, DECIMAL_SCALE)?,
+        ),
+        ColumnType::Float => Arc::new(native_array::<_, Float32Array>(values, |v| match v {
+            NativeValue::Float(v) => Some(v),
+            _ => None,
+        })),
+        ColumnType::Double => Arc::new(native_array::<_, Float64Array>(values, |v| match v {
+            NativeValue::Double(v) => Some(v),
+            _ => None,
+        })),
+        ColumnType::DateTime => Arc::new(
+            native_array::<_, TimestampMicrosecondArray>(values, |v| match v {
+                NativeValue::DateTime(v) => Some(v),
+                _ => None,
+            })
+            .with_timezone("UTC"),
+        ),
+        ColumnType::LocalDateTime => Arc::new(native_array::<_, TimestampMicrosecondArray>(
+            values,
+            |v| match v {
+                NativeValue::LocalDateTime(v) => Some(v),
+                _ => None,
+            },
+        )),
+        ColumnType::Date => Arc::new(native_array::<_, Date32Array>(values, |v| match v {
+            NativeValue::Date(v) => Some(v),
+            _ => None,
+        })),
+        ColumnType::Time => Arc::new(native_array::<_, Time64MicrosecondArray>(
+            values,
+            |v| match v {
+                NativeValue::Time(v) => Some(v),
+                _ => None,
+            },
+        )),
+        ColumnType::String => unreachable!("String columns are not native columns"),
+    })
+}
+
+fn native_array<T, A: FromIterator<Option<T>>>(
+    values: Vec<Option<NativeValue>>,
+    extract: impl Fn(NativeValue) -> Option<T>,
+) -> A {
+    values.into_iter().map(|v| v.and_then(&extract)).collect()
+}
+
+impl Iterator for RecordBatchIter {
+    type Item = Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Result<RecordBatch, ArrowError>> {
+        let batch = if let Some(batch) = self.first_batch.take() {
+            batch
+        } else {
+            match read_batch(&mut self.solutions, self.batch_size) {
+                Ok(batch) => batch,
+                Err(error) => return Some(Err(ArrowError::ExternalError(Box::new(error)))),
+            }
+        };
+        if batch.is_empty() {
+            None
+        } else {
+            Some(self.build_batch(&batch))
+        }
+    }
+}
+
+impl RecordBatchReader for RecordBatchIter {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+const DECIMAL_PRECISION: u8 = 38;
+const DECIMAL_SCALE: i8 = 18;
+/// The smallest absolute scaled value not fitting in the decimal precision
+const DECIMAL_OVERFLOW: u128 = 10_u128.pow(DECIMAL_PRECISION as u32);
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+enum ColumnType {
+    Boolean,
+    Integer,
+    Decimal,
+    Float,
+    Double,
+    DateTime,
+    LocalDateTime,
+    Date,
+    Time,
+    String,
+}
+
+impl ColumnType {
+    fn data_type(self) -> DataType {
+        match self {
+            ColumnType::Boolean => DataType::Boolean,
+            ColumnType::Integer => DataType::Int64,
+            ColumnType::Decimal => DataType::Decimal128(DECIMAL_PRECISION, DECIMAL_SCALE),
+            ColumnType::Float => DataType::Float32,
+            ColumnType::Double => DataType::Float64,
+            ColumnType::DateTime => DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+            ColumnType::LocalDateTime => DataType::Timestamp(TimeUnit::Microsecond, None),
+            ColumnType::Date => DataType::Date32,
+            ColumnType::Time => DataType::Time64(TimeUnit::Microsecond),
+            ColumnType::String => DataType::Utf8,
+        }
+    }
+}
+
+enum NativeValue {
+    Boolean(bool),
+    Integer(i64),
+    Decimal(i128),
+    Float(f32),
+    Double(f64),
+    DateTime(i64),
+    LocalDateTime(i64),
+    Date(i32),
+    Time(i64),
+}
+
+impl NativeValue {
+    fn column_type(&self) -> ColumnType {
+        match self {
+            NativeValue::Boolean(_) => ColumnType::Boolean,
+            NativeValue::Integer(_) => ColumnType::Integer,
+            NativeValue::Decimal(_) => ColumnType::Decimal,
+            NativeValue::Float(_) => ColumnType::Float,
+            NativeValue::Double(_) => ColumnType::Double,
+            NativeValue::DateTime(_) => ColumnType::DateTime,
+            NativeValue::LocalDateTime(_) => ColumnType::LocalDateTime,
+            NativeValue::Date(_) => ColumnType::Date,
+            NativeValue::Time(_) => ColumnType::Time,
+        }
+    }
+}
+
+fn native_value(term: &Term) -> Option<NativeValue> {
+    let literal = if let Term::Literal(literal) = term {
+        literal
+    } else {
+        return None;
+    };
+    if literal.is_plain() {
+        return None;
+    }
+    Some(
+        match ValueEncoder
+            .encode_literal(literal.as_ref())
+            .unwrap_infallible()
+        {
+            EncodedTerm::BooleanLiteral(value) => NativeValue::Boolean(value),
+            EncodedTerm::IntegerLiteral(value)
+            | EncodedTerm::DerivedIntegerLiteral { value, .. } => NativeValue::Integer(value),
+            EncodedTerm::DecimalLiteral(value) => {
+                let value = i128::from_be_bytes(value.to_be_bytes());
+                if value.unsigned_abs() >= DECIMAL_OVERFLOW {
+                    return None;
+                }
+                NativeValue::Decimal(value)
+            }
+            EncodedTerm::FloatLiteral(value) => NativeValue::Float(value),
+            EncodedTerm::DoubleLiteral(value) => NativeValue::Double(value),
+            EncodedTerm::DateTimeLiteral(value) => {
+                let timestamp = value.unix_timestamp_micros()?;
+                if value.timezone_offset().is_some() {
+                    NativeValue::DateTime(timestamp)
+                } else {
+                    NativeValue::LocalDateTime(timestamp)
+                }
+            }
+            EncodedTerm::DateLiteral(value) => NativeValue::Date(value.unix_days()?),
+            EncodedTerm::TimeLiteral(value) => NativeValue::Time(value.micros_since_midnight()?),
+            _ => return None,
+        },
+    )
+}
+
+/// Encodes the literal values without keeping the strings that are not needed to build native values
+struct ValueEncoder;
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash)]
+struct DiscardedStr;
+
+impl StrId for DiscardedStr {}
+
+impl StrEncodingAware for ValueEncoder {
+    type Error = Infallible;
+    type StrId = DiscardedStr;
+}
+
+impl StrContainer for ValueEncoder {
+    fn insert_str(&mut self, _: &str) -> Result<DiscardedStr, Infallible> {
+        Ok(DiscardedStr)
+    }
+}
+
+fn infer_column_type<'a>(values: impl Iterator<Item = &'a Term>) -> ColumnType {
+    let mut column_type = None;
+    for value in values {
+        let value_type = native_value(value).map_or(ColumnType::String, |v| v.column_type());
+        match column_type {
+            None => column_type = Some(value_type),
+            Some(t) if t == value_type => (),
+            Some(_) => return ColumnType::String,
+        }
+    }
+    column_type.unwrap_or(ColumnType::String)
+}
+
+fn kind_data_type() -> DataType {
+    DataType::Dictionary(Box::new(DataType::Int8), Box::new(DataType::Utf8))
+}
+
+fn iri_data_type() -> DataType {
+    DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8))
+}
+
+fn term_value(term: &Term) -> String {
+    match term {
+        Term::NamedNode(node) => node.as_str().to_owned(),
+        Term::BlankNode(node) => node.as_str().to_owned(),
+        Term::Literal(literal) => literal.value().to_owned(),
+        Term::Triple(triple) => triple.to_string(),
+    }
+}
+
+fn term_kind(term: &Term) -> &'static str {
+    match term {
+        Term::NamedNode(_) => "iri",
+        Term::BlankNode(_) => "bnode",
+        Term::Literal(_) => "literal",
+        Term::Triple(_) => "triple",
+    }
+}
+
+fn term_datatype(term: &Term) -> Option<&str> {
+    if let Term::Literal(literal) = term {
+        Some(literal.datatype().as_str())
+    } else {
+        None
+    }
+}
+
+fn term_language(term: &Term) -> Option<&str> {
+    if let Term::Literal(literal) = term {
+        literal.language()
+    } else {
+        None
+    }
+}
+
+fn read_batch(
+    solutions: &mut QuerySolutionIter,
+    batch_size: usize,
+) -> Result<Vec<QuerySolution>, EvaluationError> {
+    solutions.by_ref().take(batch_size).collect()
+}
+
+fn map_arrow_error(error: ArrowError) -> EvaluationError {
+    match error {
+        ArrowError::ExternalError(error) => match error.downcast::<EvaluationError>() {
+            Ok(error) => *error,
+            Err(error) => EvaluationError::wrap(ArrowError::ExternalError(error)),
+        },
+        error => EvaluationError::wrap(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::vocab::{rdf, xsd};
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::*;
+    use arrow_array::Array;
+    use std::rc::Rc;
+
+    fn solutions(variables: &[&str], rows: Vec<Vec<Option<Term>>>) -> QuerySolutionIter {
+        QuerySolutionIter::new(
+            Rc::new(
+                variables
+                    .iter()
+                    .map(|v| Variable::new_unchecked(*v))
+                    .collect(),
+            ),
+            Box::new(rows.into_iter().map(Ok)),
+        )
+    }
+
+    fn typed(value: &str, datatype: NamedNodeRef<'_>) -> Option<Term> {
+        Some(Literal::new_typed_literal(value, datatype).into())
+    }
+
+    fn column<'a>(batch: &'a RecordBatch, name: &str) -> &'a ArrayRef {
+        batch.column_by_name(name).unwrap()
+    }
+
+    fn strings(array: &ArrayRef) -> Vec<Option<String>> {
+        let array = array.as_string::<i32>();
+        (0..array.len())
+            .map(|i| {
+                if array.is_null(i) {
+                    None
+                } else {
+                    Some(array.value(i).to_owned())
+                }
+            })
+            .collect()
+    }
+
+    fn dictionary_strings<K: ArrowDictionaryKeyType>(array: &ArrayRef) -> Vec<Option<String>> {
+        let array = array.as_dictionary::<K>();
+        let values = array.values().as_string::<i32>();
+        (0..array.len())
+            .map(|i| array.key(i).map(|k| values.value(k).to_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn test_native_columns() -> Result<(), Box<dyn std::error::Error>> {
+        let batches = ArrowExporter::new()
+            .record_batches(solutions(
+                &["b", "i", "d", "f", "dt", "ldt", "date", "time"],
+                vec![
+                    vec![
+                        typed("true", xsd::BOOLEAN),
+                        typed("42", xsd::INTEGER),
+                        typed("1.5", xsd::DECIMAL),
+                        typed("2.5", xsd::DOUBLE),
+                        typed("1970-01-02T01:00:00+01:00", xsd::DATE_TIME),
+                        typed("1970-01-01T00:00:01.5", xsd::DATE_TIME),
+                        typed("1969-12-31", xsd::DATE),
+                        typed("01:00:00.25", xsd::TIME),
+                    ],
+                    vec![
+                        typed("0", xsd::BOOLEAN),
+                        typed("-1", xsd::INT),
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                    ],
+                ],
+            ))?
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(batches.len(), 1);
+        let batch = &batches[0];
+        let types = ["b", "i", "d", "f", "dt", "ldt", "date", "time"]
+            .iter()
+            .map(|name| column(batch, name).data_type().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            vec![
+                DataType::Boolean,
+                DataType::Int64,
+                DataType::Decimal128(38, 18),
+                DataType::Float64,
+                DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+                DataType::Timestamp(TimeUnit::Microsecond, None),
+                DataType::Date32,
+                DataType::Time64(TimeUnit::Microsecond),
+            ]
+        );
+        let booleans = column(batch, "b").as_boolean();
+        assert!(booleans.value(0));
+        assert!(!booleans.value(1));
+        let integers = column(batch, "i").as_primitive::<Int64Type>();
+        assert_eq!(integers.value(0), 42);
+        assert_eq!(integers.value(1), -1);
+        assert_eq!(
+            dictionary_strings::<Int32Type>(column(batch, "i_datatype")),
+            vec![
+                Some(xsd::INTEGER.as_str().to_owned()),
+                Some(xsd::INT.as_str().to_owned())
+            ]
+        );
+        assert_eq!(column(batch, "i_string").null_count(), 2);
+        assert_eq!(
+            column(batch, "d").as_primitive::<Decimal128Type>().value(0),
+            1_500_000_000_000_000_000
+        );
+        assert!(column(batch, "d").is_null(1));
+        assert_eq!(
+            column(batch, "f").as_primitive::<Float64Type>().value(0),
+            2.5
+        );
+        assert_eq!(
+            column(batch, "dt")
+                .as_primitive::<TimestampMicrosecondType>()
+                .value(0),
+            86_400_000_000
+        );
+        assert_eq!(
+            column(batch, "ldt")
+                .as_primitive::<TimestampMicrosecondType>()
+                .value(0),
+            1_500_000
+        );
+        assert_eq!(
+            column(batch, "date").as_primitive::<Date32Type>().value(0),
+            -1
+        );
+        assert_eq!(
+            column(batch, "time")
+                .as_primitive::<Time64MicrosecondType>()
+                .value(0),
+            3_600_250_000
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_string_columns() -> Result<(), Box<dyn std::error::Error>> {
+        let ex = NamedNode::new("http://example.com")?;
+        let batches = ArrowExporter::new()
+            .with_batch_size(2)
+            .record_batches(solutions(
+                &["v", "empty"],
+                vec![
+                    vec![Some(ex.clone().into()), None],
+                    vec![Some(BlankNode::new("b")?.into()), None],
+                    vec![
+                        Some(Literal::new_language_tagged_literal("foo", "en")?.into()),
+                        None,
+                    ],
+                    vec![
+                        Some(Triple::new(ex.clone(), ex.clone(), ex.clone()).into()),
+                        None,
+                    ],
+                    vec![None, None],
+                ],
+            ))?
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(batches.len(), 3);
+        let mut values = Vec::new();
+        let mut kinds = Vec::new();
+        let mut datatypes = Vec::new();
+        let mut languages = Vec::new();
+        for batch in &batches {
+            assert_eq!(batch.num_columns(), 8);
+            values.extend(strings(column(batch, "v")));
+            kinds.extend(dictionary_strings::<Int8Type>(column(batch, "v_kind")));
+            datatypes.extend(dictionary_strings::<Int32Type>(column(batch, "v_datatype")));
+            languages.extend(dictionary_strings::<Int32Type>(column(batch, "v_language")));
+            assert_eq!(column(batch, "empty").null_count(), batch.num_rows());
+        }
+        assert_eq!(
+            values,
+            vec![
+                Some("http://example.com".to_owned()),
+                Some("b".to_owned()),
+                Some("foo".to_owned()),
+                Some("<http://example.com> <http://example.com> <http://example.com>".to_owned()),
+                None
+            ]
+        );
+        assert_eq!(
+            kinds,
+            vec![
+                Some("iri".to_owned()),
+                Some("bnode".to_owned()),
+                Some("literal".to_owned()),
+                Some("triple".to_owned()),
+                None
+            ]
+        );
+        assert_eq!(
+            datatypes,
+            vec![
+                None,
+                None,
+                Some(rdf::LANG_STRING.as_str().to_owned()),
+                None,
+                None
+            ]
+        );
+        assert_eq!(
+            languages,
+            vec![None, None, Some("en".to_owned()), None, None]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_decimal_out_of_precision() -> Result<(), Box<dyn std::error::Error>> {
+        let batches = ArrowExporter::new()
+            .with_batch_size(2)
+            .record_batches(solutions(
+                &["d"],
+                vec![
+                    vec![typed("1.5", xsd::DECIMAL)],
+                    vec![typed("99999999999999999999.5", xsd::DECIMAL)],
+                    vec![typed("170141183460469231731.5", xsd::DECIMAL)],
+                    vec![typed("-100000000000000000000", xsd::DECIMAL)],
+                ],
+            ))?
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(batches.len(), 2);
+        assert_eq!(
+            batches[0].schema().field(0).data_type(),
+            &DataType::Decimal128(38, 18)
+        );
+        let decimals = column(&batches[0], "d").as_primitive::<Decimal128Type>();
+        assert_eq!(decimals.value(0), 1_500_000_000_000_000_000);
+        assert_eq!(
+            decimals.value(1),
+            99_999_999_999_999_999_999_500_000_000_000_000_000
+        );
+        assert_eq!(strings(column(&batches[0], "d_string")), vec![None, None]);
+        assert_eq!(column(&batches[1], "d").null_count(), 2);
+        assert_eq!(
+            strings(column(&batches[1], "d_string")),
+            vec![
+                Some("170141183460469231731.5".to_owned()),
+                Some("-100000000000000000000".to_owned())
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_type_mismatch_in_later_batch() -> Result<(), Box<dyn std::error::Error>> {
+        let batches = ArrowExporter::new()
+            .with_batch_size(1)
+            .record_batches(solutions(
+                &["v"],
+                vec![
+                    vec![typed("1", xsd::INTEGER)],
+                    vec![typed("foo", xsd::STRING)],
+                    vec![Some(NamedNode::new("http://example.com")?.into())],
+                ],
+            ))?
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(batches.len(), 3);
+        assert_eq!(batches[0].schema().field(0).data_type(), &DataType::Int64);
+        assert_eq!(
+            column(&batches[0], "v")
+                .as_primitive::<Int64Type>()
+                .value(0),
+            1
+        );
+        assert_eq!(strings(column(&batches[0], "v_string")), vec![None]);
+        for (batch, value, kind) in &[
+            (&batches[1], "foo", "literal"),
+            (&batches[2], "http://example.com", "iri"),
+        ] {
+            assert!(column(batch, "v").is_null(0));
+            assert_eq!(
+                strings(column(batch, "v_string")),
+                vec![Some((*value).to_owned())]
+            );
+            assert_eq!(
+                dictionary_strings::<Int8Type>(column(batch, "v_kind")),
+                vec![Some((*kind).to_owned())]
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parquet() -> Result<(), Box<dyn std::error::Error>> {
+        let mut file = Vec::new();
+        ArrowExporter::new().write_parquet(
+            solutions(
+                &["s", "o"],
+                vec![vec![
+                    Some(NamedNode::new("http://example.com")?.into()),
+                    typed("1", xsd::INTEGER),
+                ]],
+            ),
+            &mut file,
+        )?;
+        assert!(file.starts_with(b"PAR1"));
+        assert!(file.ends_with(b"PAR1"));
+        Ok(())
+    }
+}
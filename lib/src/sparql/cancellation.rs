@@ -0,0 +1,49 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// A handle allowing to cancel SPARQL query and update evaluations from another thread.
+///
+/// It should be given to the evaluation using [`QueryOptions::with_cancellation_token`](super::QueryOptions::with_cancellation_token()).
+/// The evaluation then fails with [`EvaluationError::Cancelled`](super::EvaluationError::Cancelled) after [`cancel`](CancellationToken::cancel()) is called.
+///
+/// ```
+/// use oxigraph::MemoryStore;
+/// use oxigraph::model::*;
+/// use oxigraph::sparql::{CancellationToken, EvaluationError, QueryOptions, QueryResults};
+///
+/// let store = MemoryStore::new();
+/// let ex = NamedNode::new("http://example.com")?;
+/// store.insert(Quad::new(ex.clone(), ex.clone(), ex.clone(), None));
+///
+/// let token = CancellationToken::new();
+/// if let QueryResults::Solutions(mut solutions) = store.query_opt(
+///     "SELECT ?s WHERE { ?s ?p ?o }",
+///     QueryOptions::default().with_cancellation_token(token.clone())
+/// )? {
+///     token.cancel();
+///     assert!(matches!(solutions.next(), Some(Err(EvaluationError::Cancelled))));
+/// }
+/// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the evaluations using this token
+    #[inline]
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
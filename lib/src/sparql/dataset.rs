@@ -68,7 +68,7 @@ impl<S: ReadableEncodedStore> DatasetView<S> {
                                 subject,
                                 predicate,
                                 object,
-                                Some(default_graph_graphs[0]),
+                                Some(default_graph_graphs[0].clone()),
                             ))
                             .map(|quad| {
                                 let quad = quad?;
@@ -85,10 +85,10 @@ impl<S: ReadableEncodedStore> DatasetView<S> {
                             .iter()
                             .map(|graph_name| {
                                 self.store.encoded_quads_for_pattern(
-                                    subject,
-                                    predicate,
-                                    object,
-                                    Some(*graph_name),
+                                    subject.clone(),
+                                    predicate.clone(),
+                                    object.clone(),
+                                    Some(graph_name.clone()),
                                 )
                             })
                             .collect::<Vec<_>>();
@@ -128,10 +128,10 @@ impl<S: ReadableEncodedStore> DatasetView<S> {
                 .iter()
                 .map(|graph_name| {
                     self.store.encoded_quads_for_pattern(
-                        subject,
-                        predicate,
-                        object,
-                        Some(*graph_name),
+                        subject.clone(),
+                        predicate.clone(),
+                        object.clone(),
+                        Some(graph_name.clone()),
                     )
                 })
                 .collect::<Vec<_>>();
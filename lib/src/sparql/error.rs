@@ -16,11 +16,29 @@ pub enum EvaluationError {
     Io(io::Error),
     /// An error returned during the query evaluation itself
     Query(QueryError),
+    /// The evaluation has not finished before the [timeout](super::QueryOptions::with_timeout())
+    Timeout,
+    /// The evaluation has been cancelled using a [`CancellationToken`](super::CancellationToken)
+    Cancelled,
+    /// The evaluation has exceeded one of the [resource limits](ResourceLimit) set in [`QueryOptions`](super::QueryOptions)
+    ResourceLimitExceeded(ResourceLimit),
     /// A conflict during a transaction
     #[doc(hidden)]
     Conflict,
 }
 
+/// A resource limit of the evaluation with its maximal value.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum ResourceLimit {
+    /// The maximal number of tuples kept in memory by an operator (see [`QueryOptions::with_max_buffered_tuples`](super::QueryOptions::with_max_buffered_tuples()))
+    BufferedTuples(usize),
+    /// The maximal number of tuples produced by all the operators (see [`QueryOptions::with_max_intermediate_results`](super::QueryOptions::with_max_intermediate_results()))
+    IntermediateResults(usize),
+    /// The maximal number of solutions or triples returned (see [`QueryOptions::with_max_result_rows`](super::QueryOptions::with_max_result_rows()))
+    ResultRows(usize),
+}
+
 #[derive(Debug)]
 pub struct QueryError {
     inner: QueryErrorKind,
@@ -38,11 +56,26 @@ impl fmt::Display for EvaluationError {
             Self::Parsing(error) => error.fmt(f),
             Self::Io(error) => error.fmt(f),
             Self::Query(error) => error.fmt(f),
+            Self::Timeout => write!(f, "The evaluation has timed out"),
+            Self::Cancelled => write!(f, "The evaluation has been cancelled"),
+            Self::ResourceLimitExceeded(limit) => {
+                write!(f, "The evaluation has exceeded the limit of {}", limit)
+            }
             Self::Conflict => write!(f, "Transaction conflict"),
         }
     }
 }
 
+impl fmt::Display for ResourceLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BufferedTuples(max) => write!(f, "{} buffered tuples", max),
+            Self::IntermediateResults(max) => write!(f, "{} intermediate results", max),
+            Self::ResultRows(max) => write!(f, "{} results", max),
+        }
+    }
+}
+
 impl fmt::Display for QueryError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match &self.inner {
@@ -1,12 +1,14 @@
 use crate::model::vocab::{rdf, xsd};
 use crate::model::xsd::*;
-use crate::model::Triple;
-use crate::model::{BlankNode, LiteralRef, NamedNodeRef};
+use crate::model::{BlankNode, LiteralRef, NamedNode, NamedNodeRef, Triple};
 use crate::sparql::algebra::{GraphPattern, Query, QueryDataset};
-use crate::sparql::error::EvaluationError;
+use crate::sparql::error::{EvaluationError, ResourceLimit};
 use crate::sparql::model::*;
 use crate::sparql::plan::*;
 use crate::sparql::service::ServiceHandler;
+use crate::sparql::{
+    AggregateAccumulator, CancellationToken, CustomAggregateFunction, CustomFunction, QueryOptions,
+};
 use crate::store::numeric_encoder::*;
 use crate::store::small_string::SmallString;
 use crate::store::ReadableEncodedStore;
@@ -18,6 +20,7 @@ use rand::random;
 use regex::{Regex, RegexBuilder};
 use sha1::Sha1;
 use sha2::{Sha256, Sha384, Sha512};
+use std::cell::Cell;
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 use std::convert::{TryFrom, TryInto};
@@ -26,6 +29,7 @@ use std::iter::Iterator;
 use std::iter::{empty, once};
 use std::rc::Rc;
 use std::str;
+use std::time::Instant;
 
 const REGEX_SIZE_LIMIT: usize = 1_000_000;
 
@@ -36,6 +40,11 @@ pub(crate) struct SimpleEvaluator<S> {
     base_iri: Option<Rc<Iri<String>>>,
     now: DateTime,
     service_handler: Rc<dyn ServiceHandler<Error = EvaluationError>>,
+    implicit_timezone: Option<TimezoneOffset>,
+    custom_functions: Rc<HashMap<NamedNode, CustomFunction>>,
+    custom_aggregate_functions: Rc<HashMap<NamedNode, CustomAggregateFunction>>,
+    interruption: Interruption,
+    limits: ResourceLimits,
 }
 
 impl<S> Clone for SimpleEvaluator<S> {
@@ -45,6 +54,11 @@ impl<S> Clone for SimpleEvaluator<S> {
             base_iri: self.base_iri.clone(),
             now: self.now,
             service_handler: self.service_handler.clone(),
+            implicit_timezone: self.implicit_timezone,
+            custom_functions: self.custom_functions.clone(),
+            custom_aggregate_functions: self.custom_aggregate_functions.clone(),
+            interruption: self.interruption.clone(),
+            limits: self.limits.clone(),
         }
     }
 }
@@ -53,25 +67,40 @@ impl<S: ReadableEncodedStore<Error = EvaluationError> + 'static> SimpleEvaluator
 where
     for<'a> &'a S: StrContainer<StrId = S::StrId>,
 {
-    pub fn new(
-        dataset: Rc<S>,
-        base_iri: Option<Rc<Iri<String>>>,
-        service_handler: Rc<dyn ServiceHandler<Error = EvaluationError>>,
-    ) -> Self {
+    pub fn new(dataset: Rc<S>, base_iri: Option<Rc<Iri<String>>>, options: QueryOptions) -> Self {
+        let now = DateTime::now().unwrap();
+        let interruption = Interruption::new(&options);
+        let limits = ResourceLimits::new(&options);
         Self {
             dataset,
             base_iri,
-            now: DateTime::now().unwrap(),
-            service_handler,
+            now: options
+                .implicit_timezone
+                .and_then(|timezone| now.adjust(Some(timezone)))
+                .unwrap_or(now),
+            service_handler: options.service_handler,
+            implicit_timezone: options.implicit_timezone,
+            custom_functions: options.custom_functions,
+            custom_aggregate_functions: options.custom_aggregate_functions,
+            interruption,
+            limits,
         }
     }
 
+    /// Uses the deadline and the cancellation token of an already started evaluation
+    pub fn with_interruption(mut self, interruption: Interruption) -> Self {
+        self.interruption = interruption;
+        self
+    }
+
     pub fn evaluate_select_plan(
         &self,
         plan: &PlanNode<S::StrId>,
         variables: Rc<Vec<Variable>>,
     ) -> Result<QueryResults, EvaluationError> {
-        let iter = self.eval_plan(plan, EncodedTuple::with_capacity(variables.len()));
+        let iter = self
+            .limits
+            .limit_result_rows(self.eval_plan(plan, EncodedTuple::with_capacity(variables.len())));
         Ok(QueryResults::Solutions(
             self.decode_bindings(iter, variables),
         ))
@@ -96,13 +125,13 @@ where
     ) -> Result<QueryResults, EvaluationError> {
         let from = EncodedTuple::with_capacity(plan.maybe_bound_variables().len());
         Ok(QueryResults::Graph(QueryTripleIter {
-            iter: Box::new(ConstructIterator {
+            iter: self.limits.limit_result_rows(Box::new(ConstructIterator {
                 eval: self.clone(),
                 iter: self.eval_plan(plan, from),
                 template,
                 buffered_results: Vec::default(),
                 bnodes: Vec::default(),
-            }),
+            })),
         }))
     }
 
@@ -112,11 +141,11 @@ where
     ) -> Result<QueryResults, EvaluationError> {
         let from = EncodedTuple::with_capacity(plan.maybe_bound_variables().len());
         Ok(QueryResults::Graph(QueryTripleIter {
-            iter: Box::new(DescribeIterator {
+            iter: self.limits.limit_result_rows(Box::new(DescribeIterator {
                 eval: self.clone(),
                 iter: self.eval_plan(plan, from),
                 quads: Box::new(empty()),
-            }),
+            })),
         }))
     }
 
@@ -124,6 +153,23 @@ where
         &self,
         node: &PlanNode<S::StrId>,
         from: EncodedTuple<S::StrId>,
+    ) -> EncodedTuplesIterator<S::StrId> {
+        let iter = self.eval_plan_node(node, from);
+        if self.interruption.is_never() && self.limits.max_intermediate_results.is_none() {
+            iter
+        } else {
+            Box::new(CheckedIterator {
+                inner: Some(iter),
+                interruption: self.interruption.clone(),
+                limits: self.limits.clone(),
+            })
+        }
+    }
+
+    fn eval_plan_node(
+        &self,
+        node: &PlanNode<S::StrId>,
+        from: EncodedTuple<S::StrId>,
     ) -> EncodedTuplesIterator<S::StrId> {
         match node {
             PlanNode::Init => Box::new(once(Ok(from))),
@@ -158,17 +204,17 @@ where
                 graph_name,
             } => {
                 let eval = self.clone();
-                let subject = *subject;
-                let predicate = *predicate;
-                let object = *object;
-                let graph_name = *graph_name;
+                let subject = subject.clone();
+                let predicate = predicate.clone();
+                let object = object.clone();
+                let graph_name = graph_name.clone();
                 Box::new(self.eval_plan(child, from).flat_map_ok(move |tuple| {
                     let mut iter: Box<dyn Iterator<Item = _>> =
                         Box::new(eval.dataset.encoded_quads_for_pattern(
-                            get_pattern_value(&subject, &tuple),
-                            get_pattern_value(&predicate, &tuple),
-                            get_pattern_value(&object, &tuple),
-                            get_pattern_value(&graph_name, &tuple),
+                            eval.get_pattern_value(&subject, &tuple),
+                            eval.get_pattern_value(&predicate, &tuple),
+                            eval.get_pattern_value(&object, &tuple),
+                            eval.get_pattern_value(&graph_name, &tuple),
                         ));
                     if subject.is_var() && subject == predicate {
                         iter = Box::new(iter.filter(|quad| match quad {
@@ -208,15 +254,35 @@ where
                             }))
                         }
                     }
-                    let iter: EncodedTuplesIterator<_> = Box::new(iter.map(move |quad| {
-                        let quad = quad?;
-                        let mut new_tuple = tuple.clone();
-                        put_pattern_value(&subject, quad.subject, &mut new_tuple);
-                        put_pattern_value(&predicate, quad.predicate, &mut new_tuple);
-                        put_pattern_value(&object, quad.object, &mut new_tuple);
-                        put_pattern_value(&graph_name, quad.graph_name, &mut new_tuple);
-                        Ok(new_tuple)
-                    }));
+                    let eval = eval.clone();
+                    let subject = subject.clone();
+                    let predicate = predicate.clone();
+                    let object = object.clone();
+                    let graph_name = graph_name.clone();
+                    let iter: EncodedTuplesIterator<_> =
+                        Box::new(iter.filter_map(move |quad| match quad {
+                            Ok(quad) => {
+                                let mut new_tuple = tuple.clone();
+                                if eval.put_pattern_value(&subject, quad.subject, &mut new_tuple)
+                                    && eval.put_pattern_value(
+                                        &predicate,
+                                        quad.predicate,
+                                        &mut new_tuple,
+                                    )
+                                    && eval.put_pattern_value(&object, quad.object, &mut new_tuple)
+                                    && eval.put_pattern_value(
+                                        &graph_name,
+                                        quad.graph_name,
+                                        &mut new_tuple,
+                                    )
+                                {
+                                    Some(Ok(new_tuple))
+                                } else {
+                                    None
+                                }
+                            }
+                            Err(error) => Some(Err(error)),
+                        }));
                     iter
                 }))
             }
@@ -228,15 +294,15 @@ where
                 graph_name,
             } => {
                 let eval = self.clone();
-                let subject = *subject;
+                let subject = subject.clone();
                 let path = path.clone();
-                let object = *object;
-                let graph_name = *graph_name;
+                let object = object.clone();
+                let graph_name = graph_name.clone();
                 Box::new(self.eval_plan(child, from).flat_map_ok(move |tuple| {
-                    let input_subject = get_pattern_value(&subject, &tuple);
-                    let input_object = get_pattern_value(&object, &tuple);
+                    let input_subject = eval.get_pattern_value(&subject, &tuple);
+                    let input_object = eval.get_pattern_value(&object, &tuple);
                     let input_graph_name =
-                        if let Some(graph_name) = get_pattern_value(&graph_name, &tuple) {
+                        if let Some(graph_name) = eval.get_pattern_value(&graph_name, &tuple) {
                             graph_name
                         } else {
                             let result: EncodedTuplesIterator<_> =
@@ -259,31 +325,62 @@ where
                                     Err(error) => Some(Err(error)),
                                 }),
                         ),
-                        (Some(input_subject), None) => Box::new(
-                            eval.eval_path_from(&path, input_subject, input_graph_name)
-                                .map(move |o| {
-                                    let mut new_tuple = tuple.clone();
-                                    put_pattern_value(&object, o?, &mut new_tuple);
-                                    Ok(new_tuple)
-                                }),
-                        ),
-                        (None, Some(input_object)) => Box::new(
-                            eval.eval_path_to(&path, input_object, input_graph_name)
-                                .map(move |s| {
-                                    let mut new_tuple = tuple.clone();
-                                    put_pattern_value(&subject, s?, &mut new_tuple);
-                                    Ok(new_tuple)
-                                }),
-                        ),
+                        (Some(input_subject), None) => {
+                            let eval2 = eval.clone();
+                            let object = object.clone();
+                            Box::new(
+                                eval.eval_path_from(&path, input_subject, input_graph_name)
+                                    .filter_map(move |o| match o {
+                                        Ok(o) => {
+                                            let mut new_tuple = tuple.clone();
+                                            if eval2.put_pattern_value(&object, o, &mut new_tuple) {
+                                                Some(Ok(new_tuple))
+                                            } else {
+                                                None
+                                            }
+                                        }
+                                        Err(error) => Some(Err(error)),
+                                    }),
+                            )
+                        }
+                        (None, Some(input_object)) => {
+                            let eval2 = eval.clone();
+                            let subject = subject.clone();
+                            Box::new(
+                                eval.eval_path_to(&path, input_object, input_graph_name)
+                                    .filter_map(move |s| match s {
+                                        Ok(s) => {
+                                            let mut new_tuple = tuple.clone();
+                                            if eval2.put_pattern_value(&subject, s, &mut new_tuple)
+                                            {
+                                                Some(Ok(new_tuple))
+                                            } else {
+                                                None
+                                            }
+                                        }
+                                        Err(error) => Some(Err(error)),
+                                    }),
+                            )
+                        }
                         (None, None) => {
-                            Box::new(eval.eval_open_path(&path, input_graph_name).map(move |so| {
-                                let mut new_tuple = tuple.clone();
-                                so.map(move |(s, o)| {
-                                    put_pattern_value(&subject, s, &mut new_tuple);
-                                    put_pattern_value(&object, o, &mut new_tuple);
-                                    new_tuple
-                                })
-                            }))
+                            let eval2 = eval.clone();
+                            let subject = subject.clone();
+                            let object = object.clone();
+                            Box::new(eval.eval_open_path(&path, input_graph_name).filter_map(
+                                move |so| match so {
+                                    Ok((s, o)) => {
+                                        let mut new_tuple = tuple.clone();
+                                        if eval2.put_pattern_value(&subject, s, &mut new_tuple)
+                                            && eval2.put_pattern_value(&object, o, &mut new_tuple)
+                                        {
+                                            Some(Ok(new_tuple))
+                                        } else {
+                                            None
+                                        }
+                                    }
+                                    Err(error) => Some(Err(error)),
+                                },
+                            ))
                         }
                     }
                 }))
@@ -291,28 +388,28 @@ where
             PlanNode::Join { left, right } => {
                 //TODO: very dumb implementation
                 let mut errors = Vec::default();
-                let left_values = self
-                    .eval_plan(left, from.clone())
-                    .filter_map(|result| match result {
-                        Ok(result) => Some(result),
-                        Err(error) => {
-                            errors.push(Err(error));
-                            None
-                        }
-                    })
-                    .collect::<Vec<_>>();
+                let left_values = match self
+                    .limits
+                    .buffer(self.eval_plan(left, from.clone()), &mut errors)
+                {
+                    Ok(left_values) => left_values,
+                    Err(error) => return Box::new(once(Err(error))),
+                };
                 Box::new(JoinIterator {
                     left: left_values,
                     right_iter: self.eval_plan(right, from),
-                    buffered_results: errors,
+                    buffered_results: errors.into_iter().map(Err).collect(),
                 })
             }
             PlanNode::AntiJoin { left, right } => {
                 //TODO: dumb implementation
-                let right: Vec<_> = self
-                    .eval_plan(right, from.clone())
-                    .filter_map(|result| result.ok())
-                    .collect();
+                let right = match self
+                    .limits
+                    .buffer(self.eval_plan(right, from.clone()), &mut Vec::new())
+                {
+                    Ok(right) => right,
+                    Err(error) => return Box::new(once(Err(error))),
+                };
                 Box::new(AntiJoinIterator {
                     left_iter: self.eval_plan(left, from),
                     right,
@@ -379,16 +476,14 @@ where
             }
             PlanNode::Sort { child, by } => {
                 let mut errors = Vec::default();
-                let mut values = self
-                    .eval_plan(child, from)
-                    .filter_map(|result| match result {
-                        Ok(result) => Some(result),
-                        Err(error) => {
-                            errors.push(Err(error));
-                            None
-                        }
-                    })
-                    .collect::<Vec<_>>();
+                let mut values = match self.limits.buffer(self.eval_plan(child, from), &mut errors)
+                {
+                    Ok(values) => values,
+                    Err(error) => return Box::new(once(Err(error))),
+                };
+                if let Err(error) = self.interruption.check() {
+                    return Box::new(once(Err(error)));
+                }
                 values.sort_unstable_by(|a, b| {
                     for comp in by {
                         match comp {
@@ -410,14 +505,20 @@ where
                     }
                     Ordering::Equal
                 });
-                Box::new(errors.into_iter().chain(values.into_iter().map(Ok)))
-            }
-            PlanNode::HashDeduplicate { child } => {
-                Box::new(hash_deduplicate(self.eval_plan(child, from)))
+                Box::new(
+                    errors
+                        .into_iter()
+                        .map(Err)
+                        .chain(values.into_iter().map(Ok)),
+                )
             }
+            PlanNode::HashDeduplicate { child } => Box::new(hash_deduplicate(
+                self.eval_plan(child, from),
+                self.limits.clone(),
+            )),
             PlanNode::Skip { child, count } => Box::new(self.eval_plan(child, from).skip(*count)),
             PlanNode::Limit { child, count } => Box::new(self.eval_plan(child, from).take(*count)),
-            PlanNode::Project { child, mapping } => {
+            PlanNode::Project { child, mapping, .. } => {
                 //TODO: use from somewhere?
                 let mapping = mapping.clone();
                 Box::new(
@@ -438,6 +539,7 @@ where
                 child,
                 key_mapping,
                 aggregates,
+                ..
             } => {
                 let tuple_size = from.capacity(); //TODO: not nice
                 let key_mapping = key_mapping.clone();
@@ -447,40 +549,47 @@ where
                     Vec<Option<EncodedTerm<S::StrId>>>,
                     Vec<Box<dyn Accumulator<S::StrId>>>,
                 >::default();
-                self.eval_plan(child, from)
-                    .filter_map(|result| match result {
-                        Ok(result) => Some(result),
-                        Err(error) => {
-                            errors.push(error);
-                            None
-                        }
-                    })
-                    .for_each(|tuple| {
-                        //TODO avoid copy for key?
-                        let key = key_mapping.iter().map(|(v, _)| tuple.get(*v)).collect();
-
-                        let key_accumulators =
-                            accumulators_for_group.entry(key).or_insert_with(|| {
-                                aggregates
-                                    .iter()
-                                    .map(|(aggregate, _)| {
-                                        self.accumulator_for_aggregate(
-                                            &aggregate.function,
-                                            aggregate.distinct,
-                                        )
-                                    })
-                                    .collect::<Vec<_>>()
-                            });
-                        for (i, accumulator) in key_accumulators.iter_mut().enumerate() {
-                            let (aggregate, _) = &aggregates[i];
-                            accumulator.add(
-                                aggregate
-                                    .parameter
-                                    .as_ref()
-                                    .and_then(|parameter| self.eval_expression(parameter, &tuple)),
-                            );
+                for result in self.eval_plan(child, from) {
+                    match result {
+                        Ok(tuple) => {
+                            //TODO avoid copy for key?
+                            let key = key_mapping
+                                .iter()
+                                .map(|(v, _)| tuple.get(*v))
+                                .collect::<Vec<_>>();
+                            if !accumulators_for_group.contains_key(&key) {
+                                if let Err(error) = self
+                                    .limits
+                                    .check_buffered_tuples(accumulators_for_group.len() + 1)
+                                {
+                                    return Box::new(once(Err(error)));
+                                }
+                            }
+
+                            let key_accumulators =
+                                accumulators_for_group.entry(key).or_insert_with(|| {
+                                    aggregates
+                                        .iter()
+                                        .map(|(aggregate, _)| {
+                                            self.accumulator_for_aggregate(
+                                                &aggregate.function,
+                                                aggregate.distinct,
+                                            )
+                                        })
+                                        .collect::<Vec<_>>()
+                                });
+                            for (i, accumulator) in key_accumulators.iter_mut().enumerate() {
+                                let (aggregate, _) = &aggregates[i];
+                                accumulator.add(
+                                    aggregate.parameter.as_ref().and_then(|parameter| {
+                                        self.eval_expression(parameter, &tuple)
+                                    }),
+                                );
+                            }
                         }
-                    });
+                        Err(error) => errors.push(error),
+                    }
+                }
                 if accumulators_for_group.is_empty() {
                     // There is always at least one group
                     accumulators_for_group.insert(vec![None; key_mapping.len()], Vec::default());
@@ -493,8 +602,8 @@ where
                             move |(key, accumulators)| {
                                 let mut result = EncodedTuple::with_capacity(tuple_size);
                                 for (from_position, to_position) in key_mapping.iter() {
-                                    if let Some(value) = key[*from_position] {
-                                        result.set(*to_position, value);
+                                    if let Some(value) = &key[*from_position] {
+                                        result.set(*to_position, value.clone());
                                     }
                                 }
                                 for (i, accumulator) in accumulators.into_iter().enumerate() {
@@ -519,7 +628,7 @@ where
     ) -> Result<EncodedTuplesIterator<S::StrId>, EvaluationError> {
         if let QueryResults::Solutions(iter) = self.service_handler.handle(
             self.dataset.decode_named_node(
-                get_pattern_value(service_name, from)
+                self.get_pattern_value(service_name, from)
                     .ok_or_else(|| EvaluationError::msg("The SERVICE name is not bound"))?,
             )?,
             Query::Select {
@@ -551,18 +660,18 @@ where
             }
             PlanAggregationFunction::Sum => {
                 if distinct {
-                    Box::new(DistinctAccumulator::new(SumAccumulator::default()))
+                    Box::new(DistinctAccumulator::new(SumAccumulator::new(self.clone())))
                 } else {
-                    Box::new(SumAccumulator::default())
+                    Box::new(SumAccumulator::new(self.clone()))
                 }
             }
             PlanAggregationFunction::Min => Box::new(MinAccumulator::new(self.clone())), // DISTINCT does not make sense with min
             PlanAggregationFunction::Max => Box::new(MaxAccumulator::new(self.clone())), // DISTINCT does not make sense with max
             PlanAggregationFunction::Avg => {
                 if distinct {
-                    Box::new(DistinctAccumulator::new(AvgAccumulator::default()))
+                    Box::new(DistinctAccumulator::new(AvgAccumulator::new(self.clone())))
                 } else {
-                    Box::new(AvgAccumulator::default())
+                    Box::new(AvgAccumulator::new(self.clone()))
                 }
             }
             PlanAggregationFunction::Sample => Box::new(SampleAccumulator::default()), // DISTINCT does not make sense with sample
@@ -576,6 +685,17 @@ where
                     Box::new(GroupConcatAccumulator::new(self.clone(), separator.clone()))
                 }
             }
+            PlanAggregationFunction::Custom(name) => {
+                let inner = self.custom_aggregate_functions[name]();
+                if distinct {
+                    Box::new(DistinctAccumulator::new(CustomAccumulator::new(
+                        self.clone(),
+                        inner,
+                    )))
+                } else {
+                    Box::new(CustomAccumulator::new(self.clone(), inner))
+                }
+            }
         }
     }
 
@@ -588,7 +708,7 @@ where
         match path {
             PlanPropertyPath::Path(p) => Box::new(
                 self.dataset
-                    .encoded_quads_for_pattern(Some(start), Some(*p), None, Some(graph_name))
+                    .encoded_quads_for_pattern(Some(start), Some(p.clone()), None, Some(graph_name))
                     .map(|t| Ok(t?.object)),
             ),
             PlanPropertyPath::Reverse(p) => self.eval_path_to(p, start, graph_name),
@@ -596,31 +716,39 @@ where
                 let eval = self.clone();
                 let b = b.clone();
                 Box::new(
-                    self.eval_path_from(a, start, graph_name)
-                        .flat_map_ok(move |middle| eval.eval_path_from(&b, middle, graph_name)),
+                    self.eval_path_from(a, start, graph_name.clone())
+                        .flat_map_ok(move |middle| {
+                            eval.eval_path_from(&b, middle, graph_name.clone())
+                        }),
                 )
             }
             PlanPropertyPath::Alternative(a, b) => Box::new(
-                self.eval_path_from(a, start, graph_name)
+                self.eval_path_from(a, start.clone(), graph_name.clone())
                     .chain(self.eval_path_from(b, start, graph_name)),
             ),
             PlanPropertyPath::ZeroOrMore(p) => {
                 let eval = self.clone();
                 let p = p.clone();
-                Box::new(transitive_closure(Some(Ok(start)), move |e| {
-                    eval.eval_path_from(&p, e, graph_name)
-                }))
+                Box::new(transitive_closure(
+                    &self.interruption,
+                    &self.limits,
+                    Some(Ok(start)),
+                    move |e| eval.eval_path_from(&p, e, graph_name.clone()),
+                ))
             }
             PlanPropertyPath::OneOrMore(p) => {
                 let eval = self.clone();
                 let p = p.clone();
                 Box::new(transitive_closure(
-                    self.eval_path_from(&p, start, graph_name),
-                    move |e| eval.eval_path_from(&p, e, graph_name),
+                    &self.interruption,
+                    &self.limits,
+                    self.eval_path_from(&p, start, graph_name.clone()),
+                    move |e| eval.eval_path_from(&p, e, graph_name.clone()),
                 ))
             }
             PlanPropertyPath::ZeroOrOne(p) => Box::new(hash_deduplicate(
-                once(Ok(start)).chain(self.eval_path_from(p, start, graph_name)),
+                once(Ok(start.clone())).chain(self.eval_path_from(p, start, graph_name)),
+                self.limits.clone(),
             )),
             PlanPropertyPath::NegatedPropertySet(ps) => {
                 let ps = ps.clone();
@@ -651,7 +779,7 @@ where
         match path {
             PlanPropertyPath::Path(p) => Box::new(
                 self.dataset
-                    .encoded_quads_for_pattern(None, Some(*p), Some(end), Some(graph_name))
+                    .encoded_quads_for_pattern(None, Some(p.clone()), Some(end), Some(graph_name))
                     .map(|t| Ok(t?.subject)),
             ),
             PlanPropertyPath::Reverse(p) => self.eval_path_from(p, end, graph_name),
@@ -659,31 +787,39 @@ where
                 let eval = self.clone();
                 let a = a.clone();
                 Box::new(
-                    self.eval_path_to(b, end, graph_name)
-                        .flat_map_ok(move |middle| eval.eval_path_to(&a, middle, graph_name)),
+                    self.eval_path_to(b, end, graph_name.clone())
+                        .flat_map_ok(move |middle| {
+                            eval.eval_path_to(&a, middle, graph_name.clone())
+                        }),
                 )
             }
             PlanPropertyPath::Alternative(a, b) => Box::new(
-                self.eval_path_to(a, end, graph_name)
+                self.eval_path_to(a, end.clone(), graph_name.clone())
                     .chain(self.eval_path_to(b, end, graph_name)),
             ),
             PlanPropertyPath::ZeroOrMore(p) => {
                 let eval = self.clone();
                 let p = p.clone();
-                Box::new(transitive_closure(Some(Ok(end)), move |e| {
-                    eval.eval_path_to(&p, e, graph_name)
-                }))
+                Box::new(transitive_closure(
+                    &self.interruption,
+                    &self.limits,
+                    Some(Ok(end)),
+                    move |e| eval.eval_path_to(&p, e, graph_name.clone()),
+                ))
             }
             PlanPropertyPath::OneOrMore(p) => {
                 let eval = self.clone();
                 let p = p.clone();
                 Box::new(transitive_closure(
-                    self.eval_path_to(&p, end, graph_name),
-                    move |e| eval.eval_path_to(&p, e, graph_name),
+                    &self.interruption,
+                    &self.limits,
+                    self.eval_path_to(&p, end, graph_name.clone()),
+                    move |e| eval.eval_path_to(&p, e, graph_name.clone()),
                 ))
             }
             PlanPropertyPath::ZeroOrOne(p) => Box::new(hash_deduplicate(
-                once(Ok(end)).chain(self.eval_path_to(p, end, graph_name)),
+                once(Ok(end.clone())).chain(self.eval_path_to(p, end, graph_name)),
+                self.limits.clone(),
             )),
             PlanPropertyPath::NegatedPropertySet(ps) => {
                 let ps = ps.clone();
@@ -717,7 +853,7 @@ where
         match path {
             PlanPropertyPath::Path(p) => Box::new(
                 self.dataset
-                    .encoded_quads_for_pattern(None, Some(*p), None, Some(graph_name))
+                    .encoded_quads_for_pattern(None, Some(p.clone()), None, Some(graph_name))
                     .map(|t| t.map(|t| (t.subject, t.object))),
             ),
             PlanPropertyPath::Reverse(p) => Box::new(
@@ -727,26 +863,27 @@ where
             PlanPropertyPath::Sequence(a, b) => {
                 let eval = self.clone();
                 let b = b.clone();
-                Box::new(
-                    self.eval_open_path(a, graph_name)
-                        .flat_map_ok(move |(start, middle)| {
-                            eval.eval_path_from(&b, middle, graph_name)
-                                .map(move |end| Ok((start, end?)))
-                        }),
-                )
+                Box::new(self.eval_open_path(a, graph_name.clone()).flat_map_ok(
+                    move |(start, middle)| {
+                        eval.eval_path_from(&b, middle, graph_name.clone())
+                            .map(move |end| Ok((start.clone(), end?)))
+                    },
+                ))
             }
             PlanPropertyPath::Alternative(a, b) => Box::new(
-                self.eval_open_path(a, graph_name)
+                self.eval_open_path(a, graph_name.clone())
                     .chain(self.eval_open_path(b, graph_name)),
             ),
             PlanPropertyPath::ZeroOrMore(p) => {
                 let eval = self.clone();
                 let p = p.clone();
                 Box::new(transitive_closure(
-                    self.get_subject_or_object_identity_pairs(graph_name), //TODO: avoid to inject everything
+                    &self.interruption,
+                    &self.limits,
+                    self.get_subject_or_object_identity_pairs(graph_name.clone()), //TODO: avoid to inject everything
                     move |(start, middle)| {
-                        eval.eval_path_from(&p, middle, graph_name)
-                            .map(move |end| Ok((start, end?)))
+                        eval.eval_path_from(&p, middle, graph_name.clone())
+                            .map(move |end| Ok((start.clone(), end?)))
                     },
                 ))
             }
@@ -754,16 +891,19 @@ where
                 let eval = self.clone();
                 let p = p.clone();
                 Box::new(transitive_closure(
-                    self.eval_open_path(&p, graph_name),
+                    &self.interruption,
+                    &self.limits,
+                    self.eval_open_path(&p, graph_name.clone()),
                     move |(start, middle)| {
-                        eval.eval_path_from(&p, middle, graph_name)
-                            .map(move |end| Ok((start, end?)))
+                        eval.eval_path_from(&p, middle, graph_name.clone())
+                            .map(move |end| Ok((start.clone(), end?)))
                     },
                 ))
             }
             PlanPropertyPath::ZeroOrOne(p) => Box::new(hash_deduplicate(
-                self.get_subject_or_object_identity_pairs(graph_name)
+                self.get_subject_or_object_identity_pairs(graph_name.clone())
                     .chain(self.eval_open_path(p, graph_name)),
+                self.limits.clone(),
             )),
             PlanPropertyPath::NegatedPropertySet(ps) => {
                 let ps = ps.clone();
@@ -793,7 +933,7 @@ where
         self.dataset
             .encoded_quads_for_pattern(None, None, None, Some(graph_name))
             .flat_map_ok(|t| once(Ok(t.subject)).chain(once(Ok(t.object))))
-            .map(|e| e.map(|e| (e, e)))
+            .map(|e| e.map(|e| (e.clone(), e)))
     }
 
     #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
@@ -803,7 +943,7 @@ where
         tuple: &EncodedTuple<S::StrId>,
     ) -> Option<EncodedTerm<S::StrId>> {
         match expression {
-            PlanExpression::Constant(t) => Some(*t),
+            PlanExpression::Constant(t) => Some(t.clone()),
             PlanExpression::Variable(v) => tuple.get(*v),
             PlanExpression::Exists(node) => {
                 Some(self.eval_plan(node, tuple.clone()).next().is_some().into())
@@ -881,7 +1021,7 @@ where
                 let mut error = false;
                 for possible in l {
                     if let Some(possible) = self.eval_expression(possible, tuple) {
-                        if Some(true) == self.equals(needed, possible) {
+                        if Some(true) == self.equals(needed.clone(), possible) {
                             return Some(true.into());
                         }
                     } else {
@@ -897,8 +1037,18 @@ where
             PlanExpression::Add(a, b) => match self.parse_numeric_operands(a, b, tuple)? {
                 NumericBinaryOperands::Float(v1, v2) => Some((v1 + v2).into()),
                 NumericBinaryOperands::Double(v1, v2) => Some((v1 + v2).into()),
-                NumericBinaryOperands::Integer(v1, v2) => Some(v1.checked_add(v2)?.into()),
-                NumericBinaryOperands::Decimal(v1, v2) => Some(v1.checked_add(v2)?.into()),
+                NumericBinaryOperands::Integer(v1, v2) => {
+                    v1.checked_add(v2).map(Into::into).or_else(|| {
+                        self.build_integer_literal(&(BigInteger::from(v1) + BigInteger::from(v2)))
+                    })
+                }
+                NumericBinaryOperands::Decimal(v1, v2) => {
+                    v1.checked_add(v2).map(Into::into).or_else(|| {
+                        self.build_decimal_literal(&(BigDecimal::from(v1) + BigDecimal::from(v2)))
+                    })
+                }
+                NumericBinaryOperands::BigInteger(v1, v2) => self.build_integer_literal(&(v1 + v2)),
+                NumericBinaryOperands::BigDecimal(v1, v2) => self.build_decimal_literal(&(v1 + v2)),
                 NumericBinaryOperands::Duration(v1, v2) => Some(v1.checked_add(v2)?.into()),
                 NumericBinaryOperands::YearMonthDuration(v1, v2) => {
                     Some(v1.checked_add(v2)?.into())
@@ -934,11 +1084,38 @@ where
                 Some(match self.parse_numeric_operands(a, b, tuple)? {
                     NumericBinaryOperands::Float(v1, v2) => (v1 - v2).into(),
                     NumericBinaryOperands::Double(v1, v2) => (v1 - v2).into(),
-                    NumericBinaryOperands::Integer(v1, v2) => v1.checked_sub(v2)?.into(),
-                    NumericBinaryOperands::Decimal(v1, v2) => v1.checked_sub(v2)?.into(),
-                    NumericBinaryOperands::DateTime(v1, v2) => v1.checked_sub(v2)?.into(),
-                    NumericBinaryOperands::Date(v1, v2) => v1.checked_sub(v2)?.into(),
-                    NumericBinaryOperands::Time(v1, v2) => v1.checked_sub(v2)?.into(),
+                    NumericBinaryOperands::Integer(v1, v2) => {
+                        v1.checked_sub(v2).map(Into::into).or_else(|| {
+                            self.build_integer_literal(
+                                &(BigInteger::from(v1) - BigInteger::from(v2)),
+                            )
+                        })?
+                    }
+                    NumericBinaryOperands::Decimal(v1, v2) => {
+                        v1.checked_sub(v2).map(Into::into).or_else(|| {
+                            self.build_decimal_literal(
+                                &(BigDecimal::from(v1) - BigDecimal::from(v2)),
+                            )
+                        })?
+                    }
+                    NumericBinaryOperands::BigInteger(v1, v2) => {
+                        self.build_integer_literal(&(v1 - v2))?
+                    }
+                    NumericBinaryOperands::BigDecimal(v1, v2) => {
+                        self.build_decimal_literal(&(v1 - v2))?
+                    }
+                    NumericBinaryOperands::DateTime(v1, v2) => v1
+                        .adjust(v1.timezone_offset().or(self.implicit_timezone))?
+                        .checked_sub(v2.adjust(v2.timezone_offset().or(self.implicit_timezone))?)?
+                        .into(),
+                    NumericBinaryOperands::Date(v1, v2) => v1
+                        .adjust(v1.timezone_offset().or(self.implicit_timezone))?
+                        .checked_sub(v2.adjust(v2.timezone_offset().or(self.implicit_timezone))?)?
+                        .into(),
+                    NumericBinaryOperands::Time(v1, v2) => v1
+                        .adjust(v1.timezone_offset().or(self.implicit_timezone))?
+                        .checked_sub(v2.adjust(v2.timezone_offset().or(self.implicit_timezone))?)?
+                        .into(),
                     NumericBinaryOperands::Duration(v1, v2) => v1.checked_sub(v2)?.into(),
                     NumericBinaryOperands::YearMonthDuration(v1, v2) => v1.checked_sub(v2)?.into(),
                     NumericBinaryOperands::DayTimeDuration(v1, v2) => v1.checked_sub(v2)?.into(),
@@ -971,34 +1148,72 @@ where
             PlanExpression::Multiply(a, b) => match self.parse_numeric_operands(a, b, tuple)? {
                 NumericBinaryOperands::Float(v1, v2) => Some((v1 * v2).into()),
                 NumericBinaryOperands::Double(v1, v2) => Some((v1 * v2).into()),
-                NumericBinaryOperands::Integer(v1, v2) => Some(v1.checked_mul(v2)?.into()),
-                NumericBinaryOperands::Decimal(v1, v2) => Some(v1.checked_mul(v2)?.into()),
+                NumericBinaryOperands::Integer(v1, v2) => {
+                    v1.checked_mul(v2).map(Into::into).or_else(|| {
+                        self.build_integer_literal(&(BigInteger::from(v1) * BigInteger::from(v2)))
+                    })
+                }
+                NumericBinaryOperands::Decimal(v1, v2) => {
+                    v1.checked_mul(v2).map(Into::into).or_else(|| {
+                        self.build_decimal_literal(&(BigDecimal::from(v1) * BigDecimal::from(v2)))
+                    })
+                }
+                NumericBinaryOperands::BigInteger(v1, v2) => self.build_integer_literal(&(v1 * v2)),
+                NumericBinaryOperands::BigDecimal(v1, v2) => self.build_decimal_literal(&(v1 * v2)),
                 _ => None,
             },
             PlanExpression::Divide(a, b) => match self.parse_numeric_operands(a, b, tuple)? {
                 NumericBinaryOperands::Float(v1, v2) => Some((v1 / v2).into()),
                 NumericBinaryOperands::Double(v1, v2) => Some((v1 / v2).into()),
-                NumericBinaryOperands::Integer(v1, v2) => {
-                    Some(Decimal::from(v1).checked_div(v2)?.into())
+                NumericBinaryOperands::Integer(v1, v2) => Decimal::from(v1)
+                    .checked_div(v2)
+                    .map(Into::into)
+                    .or_else(|| {
+                        self.build_decimal_literal(
+                            &BigDecimal::from(v1).checked_div(&BigDecimal::from(v2))?,
+                        )
+                    }),
+                NumericBinaryOperands::Decimal(v1, v2) => {
+                    v1.checked_div(v2).map(Into::into).or_else(|| {
+                        self.build_decimal_literal(
+                            &BigDecimal::from(v1).checked_div(&BigDecimal::from(v2))?,
+                        )
+                    })
+                }
+                NumericBinaryOperands::BigInteger(v1, v2) => self.build_decimal_literal(
+                    &BigDecimal::from(v1).checked_div(&BigDecimal::from(v2))?,
+                ),
+                NumericBinaryOperands::BigDecimal(v1, v2) => {
+                    self.build_decimal_literal(&v1.checked_div(&v2)?)
                 }
-                NumericBinaryOperands::Decimal(v1, v2) => Some(v1.checked_div(v2)?.into()),
                 _ => None,
             },
-            PlanExpression::UnaryPlus(e) => match self.eval_expression(e, tuple)? {
+            PlanExpression::UnaryPlus(e) => match self.eval_promoted_expression(e, tuple)? {
                 EncodedTerm::FloatLiteral(value) => Some(value.into()),
                 EncodedTerm::DoubleLiteral(value) => Some(value.into()),
                 EncodedTerm::IntegerLiteral(value) => Some(value.into()),
                 EncodedTerm::DecimalLiteral(value) => Some(value.into()),
+                term @ EncodedTerm::BigIntegerLiteral { .. }
+                | term @ EncodedTerm::BigDecimalLiteral { .. } => Some(term),
                 EncodedTerm::DurationLiteral(value) => Some(value.into()),
                 EncodedTerm::YearMonthDurationLiteral(value) => Some(value.into()),
                 EncodedTerm::DayTimeDurationLiteral(value) => Some(value.into()),
                 _ => None,
             },
-            PlanExpression::UnaryMinus(e) => match self.eval_expression(e, tuple)? {
+            PlanExpression::UnaryMinus(e) => match self.eval_promoted_expression(e, tuple)? {
                 EncodedTerm::FloatLiteral(value) => Some((-value).into()),
                 EncodedTerm::DoubleLiteral(value) => Some((-value).into()),
-                EncodedTerm::IntegerLiteral(value) => Some((-value).into()),
+                EncodedTerm::IntegerLiteral(value) => value
+                    .checked_neg()
+                    .map(Into::into)
+                    .or_else(|| self.build_integer_literal(&(-BigInteger::from(value)))),
                 EncodedTerm::DecimalLiteral(value) => Some((-value).into()),
+                term @ EncodedTerm::BigIntegerLiteral { .. } => {
+                    self.build_integer_literal(&(-self.to_big_integer(&term)?))
+                }
+                term @ EncodedTerm::BigDecimalLiteral { .. } => {
+                    self.build_decimal_literal(&(-self.to_big_decimal(&term)?))
+                }
                 EncodedTerm::DurationLiteral(value) => Some((-value).into()),
                 EncodedTerm::YearMonthDurationLiteral(value) => Some((-value).into()),
                 EncodedTerm::DayTimeDurationLiteral(value) => Some((-value).into()),
@@ -1007,11 +1222,9 @@ where
             PlanExpression::Not(e) => self
                 .to_bool(self.eval_expression(e, tuple)?)
                 .map(|v| (!v).into()),
-            PlanExpression::Str(e) => {
-                Some(self.build_string_literal_from_id(
-                    self.to_string_id(self.eval_expression(e, tuple)?)?,
-                ))
-            }
+            PlanExpression::Str(e) => Some(self.build_string_literal_from_id(
+                self.to_string_id(&self.eval_expression(e, tuple)?)?,
+            )),
             PlanExpression::Lang(e) => match self.eval_expression(e, tuple)? {
                 EncodedTerm::SmallSmallLangStringLiteral { language, .. }
                 | EncodedTerm::BigSmallLangStringLiteral { language, .. } => {
@@ -1026,10 +1239,10 @@ where
             },
             PlanExpression::LangMatches(language_tag, language_range) => {
                 let mut language_tag =
-                    self.to_simple_string(self.eval_expression(language_tag, tuple)?)?;
+                    self.to_simple_string(&self.eval_expression(language_tag, tuple)?)?;
                 language_tag.make_ascii_lowercase();
                 let mut language_range =
-                    self.to_simple_string(self.eval_expression(language_range, tuple)?)?;
+                    self.to_simple_string(&self.eval_expression(language_range, tuple)?)?;
                 language_range.make_ascii_lowercase();
                 Some(
                     if &*language_range == "*" {
@@ -1048,14 +1261,14 @@ where
                     .into(),
                 )
             }
-            PlanExpression::Datatype(e) => self.datatype(self.eval_expression(e, tuple)?),
+            PlanExpression::Datatype(e) => self.datatype(&self.eval_expression(e, tuple)?),
             PlanExpression::Bound(v) => Some(tuple.contains(*v).into()),
             PlanExpression::IRI(e) => {
                 let e = self.eval_expression(e, tuple)?;
                 if e.is_named_node() {
                     Some(e)
                 } else {
-                    let iri = self.to_simple_string(e)?;
+                    let iri = self.to_simple_string(&e)?;
                     self.build_named_node(
                         &if let Some(base_iri) = &self.base_iri {
                             base_iri.resolve(&iri)
@@ -1070,7 +1283,7 @@ where
             PlanExpression::BNode(id) => match id {
                 Some(id) => {
                     let bnode =
-                        BlankNode::new(self.to_simple_string(self.eval_expression(id, tuple)?)?)
+                        BlankNode::new(self.to_simple_string(&self.eval_expression(id, tuple)?)?)
                             .ok()?;
                     Some(
                         self.dataset
@@ -1084,30 +1297,51 @@ where
                 }),
             },
             PlanExpression::Rand => Some(random::<f64>().into()),
-            PlanExpression::Abs(e) => match self.eval_expression(e, tuple)? {
-                EncodedTerm::IntegerLiteral(value) => Some(value.checked_abs()?.into()),
+            PlanExpression::Abs(e) => match self.eval_promoted_expression(e, tuple)? {
+                EncodedTerm::IntegerLiteral(value) => value
+                    .checked_abs()
+                    .map(Into::into)
+                    .or_else(|| self.build_integer_literal(&BigInteger::from(value).abs())),
                 EncodedTerm::DecimalLiteral(value) => Some(value.abs().into()),
+                term @ EncodedTerm::BigIntegerLiteral { .. } => {
+                    self.build_integer_literal(&self.to_big_integer(&term)?.abs())
+                }
+                term @ EncodedTerm::BigDecimalLiteral { .. } => {
+                    self.build_decimal_literal(&self.to_big_decimal(&term)?.abs())
+                }
                 EncodedTerm::FloatLiteral(value) => Some(value.abs().into()),
                 EncodedTerm::DoubleLiteral(value) => Some(value.abs().into()),
                 _ => None,
             },
-            PlanExpression::Ceil(e) => match self.eval_expression(e, tuple)? {
+            PlanExpression::Ceil(e) => match self.eval_promoted_expression(e, tuple)? {
                 EncodedTerm::IntegerLiteral(value) => Some(value.into()),
                 EncodedTerm::DecimalLiteral(value) => Some(value.ceil().into()),
+                term @ EncodedTerm::BigIntegerLiteral { .. } => Some(term),
+                term @ EncodedTerm::BigDecimalLiteral { .. } => {
+                    self.build_decimal_literal(&self.to_big_decimal(&term)?.ceil())
+                }
                 EncodedTerm::FloatLiteral(value) => Some(value.ceil().into()),
                 EncodedTerm::DoubleLiteral(value) => Some(value.ceil().into()),
                 _ => None,
             },
-            PlanExpression::Floor(e) => match self.eval_expression(e, tuple)? {
+            PlanExpression::Floor(e) => match self.eval_promoted_expression(e, tuple)? {
                 EncodedTerm::IntegerLiteral(value) => Some(value.into()),
                 EncodedTerm::DecimalLiteral(value) => Some(value.floor().into()),
+                term @ EncodedTerm::BigIntegerLiteral { .. } => Some(term),
+                term @ EncodedTerm::BigDecimalLiteral { .. } => {
+                    self.build_decimal_literal(&self.to_big_decimal(&term)?.floor())
+                }
                 EncodedTerm::FloatLiteral(value) => Some(value.floor().into()),
                 EncodedTerm::DoubleLiteral(value) => Some(value.floor().into()),
                 _ => None,
             },
-            PlanExpression::Round(e) => match self.eval_expression(e, tuple)? {
+            PlanExpression::Round(e) => match self.eval_promoted_expression(e, tuple)? {
                 EncodedTerm::IntegerLiteral(value) => Some(value.into()),
                 EncodedTerm::DecimalLiteral(value) => Some(value.round().into()),
+                term @ EncodedTerm::BigIntegerLiteral { .. } => Some(term),
+                term @ EncodedTerm::BigDecimalLiteral { .. } => {
+                    self.build_decimal_literal(&self.to_big_decimal(&term)?.round())
+                }
                 EncodedTerm::FloatLiteral(value) => Some(value.round().into()),
                 EncodedTerm::DoubleLiteral(value) => Some(value.round().into()),
                 _ => None,
@@ -1117,7 +1351,7 @@ where
                 let mut language = None;
                 for e in l {
                     let (value, e_language) =
-                        self.to_string_and_language(self.eval_expression(e, tuple)?)?;
+                        self.to_string_and_language(&self.eval_expression(e, tuple)?)?;
                     if let Some(lang) = language {
                         if lang != e_language {
                             language = Some(None)
@@ -1131,17 +1365,19 @@ where
             }
             PlanExpression::SubStr(source, starting_loc, length) => {
                 let (source, language) =
-                    self.to_string_and_language(self.eval_expression(source, tuple)?)?;
+                    self.to_string_and_language(&self.eval_expression(source, tuple)?)?;
 
                 let starting_location: usize = if let EncodedTerm::IntegerLiteral(v) =
-                    self.eval_expression(starting_loc, tuple)?
+                    self.eval_promoted_expression(starting_loc, tuple)?
                 {
                     v.try_into().ok()?
                 } else {
                     return None;
                 };
                 let length: Option<usize> = if let Some(length) = length {
-                    if let EncodedTerm::IntegerLiteral(v) = self.eval_expression(length, tuple)? {
+                    if let EncodedTerm::IntegerLiteral(v) =
+                        self.eval_promoted_expression(length, tuple)?
+                    {
                         Some(v.try_into().ok()?)
                     } else {
                         return None;
@@ -1173,14 +1409,14 @@ where
             }
             PlanExpression::StrLen(arg) => Some(
                 (self
-                    .to_string(self.eval_expression(arg, tuple)?)?
+                    .to_string(&self.eval_expression(arg, tuple)?)?
                     .chars()
                     .count() as i64)
                     .into(),
             ),
             PlanExpression::Replace(arg, pattern, replacement, flags) => {
                 let regex = self.compile_pattern(
-                    self.eval_expression(pattern, tuple)?,
+                    &self.eval_expression(pattern, tuple)?,
                     if let Some(flags) = flags {
                         Some(self.eval_expression(flags, tuple)?)
                     } else {
@@ -1188,30 +1424,30 @@ where
                     },
                 )?;
                 let (text, language) =
-                    self.to_string_and_language(self.eval_expression(arg, tuple)?)?;
+                    self.to_string_and_language(&self.eval_expression(arg, tuple)?)?;
                 let replacement =
-                    self.to_simple_string(self.eval_expression(replacement, tuple)?)?;
+                    self.to_simple_string(&self.eval_expression(replacement, tuple)?)?;
                 self.build_plain_literal(&regex.replace_all(&text, replacement.as_str()), language)
             }
             PlanExpression::UCase(e) => {
                 let (value, language) =
-                    self.to_string_and_language(self.eval_expression(e, tuple)?)?;
+                    self.to_string_and_language(&self.eval_expression(e, tuple)?)?;
                 self.build_plain_literal(&value.to_uppercase(), language)
             }
             PlanExpression::LCase(e) => {
                 let (value, language) =
-                    self.to_string_and_language(self.eval_expression(e, tuple)?)?;
+                    self.to_string_and_language(&self.eval_expression(e, tuple)?)?;
                 self.build_plain_literal(&value.to_lowercase(), language)
             }
             PlanExpression::StrStarts(arg1, arg2) => {
                 let (arg1, arg2, _) = self.to_argument_compatible_strings(
-                    self.eval_expression(arg1, tuple)?,
-                    self.eval_expression(arg2, tuple)?,
+                    &self.eval_expression(arg1, tuple)?,
+                    &self.eval_expression(arg2, tuple)?,
                 )?;
                 Some((&arg1).starts_with(arg2.as_str()).into())
             }
             PlanExpression::EncodeForURI(ltrl) => {
-                let ltlr = self.to_string(self.eval_expression(ltrl, tuple)?)?;
+                let ltlr = self.to_string(&self.eval_expression(ltrl, tuple)?)?;
                 let mut result = Vec::with_capacity(ltlr.len());
                 for c in ltlr.bytes() {
                     match c {
@@ -1239,22 +1475,22 @@ where
             }
             PlanExpression::StrEnds(arg1, arg2) => {
                 let (arg1, arg2, _) = self.to_argument_compatible_strings(
-                    self.eval_expression(arg1, tuple)?,
-                    self.eval_expression(arg2, tuple)?,
+                    &self.eval_expression(arg1, tuple)?,
+                    &self.eval_expression(arg2, tuple)?,
                 )?;
                 Some((&arg1).ends_with(arg2.as_str()).into())
             }
             PlanExpression::Contains(arg1, arg2) => {
                 let (arg1, arg2, _) = self.to_argument_compatible_strings(
-                    self.eval_expression(arg1, tuple)?,
-                    self.eval_expression(arg2, tuple)?,
+                    &self.eval_expression(arg1, tuple)?,
+                    &self.eval_expression(arg2, tuple)?,
                 )?;
                 Some((&arg1).contains(arg2.as_str()).into())
             }
             PlanExpression::StrBefore(arg1, arg2) => {
                 let (arg1, arg2, language) = self.to_argument_compatible_strings(
-                    self.eval_expression(arg1, tuple)?,
-                    self.eval_expression(arg2, tuple)?,
+                    &self.eval_expression(arg1, tuple)?,
+                    &self.eval_expression(arg2, tuple)?,
                 )?;
                 if let Some(position) = (&arg1).find(arg2.as_str()) {
                     self.build_plain_literal(&arg1[..position], language)
@@ -1264,8 +1500,8 @@ where
             }
             PlanExpression::StrAfter(arg1, arg2) => {
                 let (arg1, arg2, language) = self.to_argument_compatible_strings(
-                    self.eval_expression(arg1, tuple)?,
-                    self.eval_expression(arg2, tuple)?,
+                    &self.eval_expression(arg1, tuple)?,
+                    &self.eval_expression(arg2, tuple)?,
                 )?;
                 if let Some(position) = (&arg1).find(arg2.as_str()) {
                     self.build_plain_literal(&arg1[position + arg2.len()..], language)
@@ -1343,6 +1579,54 @@ where
                     None => self.build_string_literal(""),
                 }
             }
+            PlanExpression::CustomFunction(name, parameters) => {
+                let function = self.custom_functions.get(name)?;
+                let parameters = parameters
+                    .iter()
+                    .map(|p| {
+                        self.dataset
+                            .decode_term(self.eval_expression(p, tuple)?)
+                            .ok()
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                self.dataset
+                    .as_ref()
+                    .encode_term(function(&parameters)?.as_ref())
+                    .ok()
+            }
+            PlanExpression::AdjustDateTimeToTimezone(value, timezone) => {
+                if let EncodedTerm::DateTimeLiteral(value) = self.eval_expression(value, tuple)? {
+                    Some(
+                        value
+                            .adjust(Some(self.eval_timezone(timezone, tuple)?))?
+                            .into(),
+                    )
+                } else {
+                    None
+                }
+            }
+            PlanExpression::AdjustDateToTimezone(value, timezone) => {
+                if let EncodedTerm::DateLiteral(value) = self.eval_expression(value, tuple)? {
+                    Some(
+                        value
+                            .adjust(Some(self.eval_timezone(timezone, tuple)?))?
+                            .into(),
+                    )
+                } else {
+                    None
+                }
+            }
+            PlanExpression::AdjustTimeToTimezone(value, timezone) => {
+                if let EncodedTerm::TimeLiteral(value) = self.eval_expression(value, tuple)? {
+                    Some(
+                        value
+                            .adjust(Some(self.eval_timezone(timezone, tuple)?))?
+                            .into(),
+                    )
+                } else {
+                    None
+                }
+            }
             PlanExpression::Now => Some(self.now.into()),
             PlanExpression::UUID => {
                 let mut buffer = String::with_capacity(44);
@@ -1377,12 +1661,12 @@ where
             }
             PlanExpression::StrLang(lexical_form, lang_tag) => {
                 Some(self.build_lang_string_literal_from_id(
-                    self.to_simple_string_id(self.eval_expression(lexical_form, tuple)?)?,
-                    self.build_language_id(self.eval_expression(lang_tag, tuple)?)?,
+                    self.to_simple_string_id(&self.eval_expression(lexical_form, tuple)?)?,
+                    self.build_language_id(&self.eval_expression(lang_tag, tuple)?)?,
                 ))
             }
             PlanExpression::StrDT(lexical_form, datatype) => {
-                let value = self.to_simple_string(self.eval_expression(lexical_form, tuple)?)?;
+                let value = self.to_simple_string(&self.eval_expression(lexical_form, tuple)?)?;
                 let datatype = if let EncodedTerm::NamedNode { iri_id } =
                     self.eval_expression(datatype, tuple)?
                 {
@@ -1411,42 +1695,78 @@ where
                 Some(self.eval_expression(e, tuple)?.is_literal().into())
             }
             PlanExpression::IsNumeric(e) => Some(
-                matches!(self.eval_expression(e, tuple)?,
+                matches!(self.eval_promoted_expression(e, tuple)?,
                     EncodedTerm::FloatLiteral(_)
                     | EncodedTerm::DoubleLiteral(_)
                     | EncodedTerm::IntegerLiteral(_)
-                    | EncodedTerm::DecimalLiteral(_))
+                    | EncodedTerm::DecimalLiteral(_)
+                    | EncodedTerm::BigIntegerLiteral { .. }
+                    | EncodedTerm::BigDecimalLiteral { .. })
                 .into(),
             ),
+            PlanExpression::IsTriple(e) => Some(self.eval_expression(e, tuple)?.is_triple().into()),
+            PlanExpression::Triple(s, p, o) => self.build_triple(
+                self.eval_expression(s, tuple)?,
+                self.eval_expression(p, tuple)?,
+                self.eval_expression(o, tuple)?,
+            ),
+            PlanExpression::Subject(e) => {
+                if let EncodedTerm::Triple(t) = self.eval_expression(e, tuple)? {
+                    Some(t.subject.clone())
+                } else {
+                    None
+                }
+            }
+            PlanExpression::Predicate(e) => {
+                if let EncodedTerm::Triple(t) = self.eval_expression(e, tuple)? {
+                    Some(t.predicate.clone())
+                } else {
+                    None
+                }
+            }
+            PlanExpression::Object(e) => {
+                if let EncodedTerm::Triple(t) = self.eval_expression(e, tuple)? {
+                    Some(t.object.clone())
+                } else {
+                    None
+                }
+            }
             PlanExpression::Regex(text, pattern, flags) => {
                 let regex = self.compile_pattern(
-                    self.eval_expression(pattern, tuple)?,
+                    &self.eval_expression(pattern, tuple)?,
                     if let Some(flags) = flags {
                         Some(self.eval_expression(flags, tuple)?)
                     } else {
                         None
                     },
                 )?;
-                let text = self.to_string(self.eval_expression(text, tuple)?)?;
+                let text = self.to_string(&self.eval_expression(text, tuple)?)?;
                 Some(regex.is_match(&text).into())
             }
-            PlanExpression::BooleanCast(e) => match self.eval_expression(e, tuple)? {
+            PlanExpression::BooleanCast(e) => match self.eval_promoted_expression(e, tuple)? {
                 EncodedTerm::BooleanLiteral(value) => Some(value.into()),
                 EncodedTerm::FloatLiteral(value) => Some((value != 0. && !value.is_nan()).into()),
                 EncodedTerm::DoubleLiteral(value) => Some((value != 0. && !value.is_nan()).into()),
                 EncodedTerm::IntegerLiteral(value) => Some((value != 0).into()),
                 EncodedTerm::DecimalLiteral(value) => Some((value != Decimal::default()).into()),
+                EncodedTerm::BigIntegerLiteral { .. } | EncodedTerm::BigDecimalLiteral { .. } => {
+                    Some(true.into())
+                }
                 EncodedTerm::SmallStringLiteral(value) => parse_boolean_str(&value),
                 EncodedTerm::BigStringLiteral { value_id } => {
                     parse_boolean_str(&*self.dataset.get_str(value_id).ok()??)
                 }
                 _ => None,
             },
-            PlanExpression::DoubleCast(e) => match self.eval_expression(e, tuple)? {
+            PlanExpression::DoubleCast(e) => match self.eval_promoted_expression(e, tuple)? {
                 EncodedTerm::FloatLiteral(value) => Some(f64::from(value).into()),
                 EncodedTerm::DoubleLiteral(value) => Some(value.into()),
                 EncodedTerm::IntegerLiteral(value) => Some((value as f64).into()),
                 EncodedTerm::DecimalLiteral(value) => Some(value.to_f64().into()),
+                term @ EncodedTerm::BigIntegerLiteral { .. }
+                | term @ EncodedTerm::BigDecimalLiteral { .. } => {
+                    Some(self.to_big_decimal(&term)?.to_f64().into())
+                }
                 EncodedTerm::BooleanLiteral(value) => {
                     Some(if value { 1_f64 } else { 0_f64 }.into())
                 }
@@ -1456,11 +1776,15 @@ where
                 }
                 _ => None,
             },
-            PlanExpression::FloatCast(e) => match self.eval_expression(e, tuple)? {
+            PlanExpression::FloatCast(e) => match self.eval_promoted_expression(e, tuple)? {
                 EncodedTerm::FloatLiteral(value) => Some(value.into()),
                 EncodedTerm::DoubleLiteral(value) => Some((value as f32).into()),
                 EncodedTerm::IntegerLiteral(value) => Some((value as f32).into()),
                 EncodedTerm::DecimalLiteral(value) => Some(value.to_f32().into()),
+                term @ EncodedTerm::BigIntegerLiteral { .. }
+                | term @ EncodedTerm::BigDecimalLiteral { .. } => {
+                    Some(self.to_big_decimal(&term)?.to_f32().into())
+                }
                 EncodedTerm::BooleanLiteral(value) => {
                     Some(if value { 1_f32 } else { 0_f32 }.into())
                 }
@@ -1470,29 +1794,31 @@ where
                 }
                 _ => None,
             },
-            PlanExpression::IntegerCast(e) => match self.eval_expression(e, tuple)? {
-                EncodedTerm::FloatLiteral(value) => Some((value as i64).into()),
-                EncodedTerm::DoubleLiteral(value) => Some((value as i64).into()),
-                EncodedTerm::IntegerLiteral(value) => Some(value.into()),
-                EncodedTerm::DecimalLiteral(value) => Some(i64::try_from(value).ok()?.into()),
-                EncodedTerm::BooleanLiteral(value) => Some(if value { 1 } else { 0 }.into()),
-                EncodedTerm::SmallStringLiteral(value) => parse_integer_str(&value),
-                EncodedTerm::BigStringLiteral { value_id } => {
-                    parse_integer_str(&*self.dataset.get_str(value_id).ok()??)
+            PlanExpression::IntegerCast(e) => self.cast_to_integer(self.eval_expression(e, tuple)?),
+            PlanExpression::DerivedIntegerCast(e, datatype) => self.build_derived_integer_literal(
+                &self.cast_to_integer(self.eval_expression(e, tuple)?)?,
+                *datatype,
+            ),
+            PlanExpression::DecimalCast(e) => match self.eval_promoted_expression(e, tuple)? {
+                // The display of floats never uses the exponent notation
+                EncodedTerm::FloatLiteral(value) if value.is_finite() => {
+                    self.parse_decimal_literal(&value.to_string())
+                }
+                EncodedTerm::DoubleLiteral(value) if value.is_finite() => {
+                    self.parse_decimal_literal(&value.to_string())
                 }
-                _ => None,
-            },
-            PlanExpression::DecimalCast(e) => match self.eval_expression(e, tuple)? {
-                EncodedTerm::FloatLiteral(value) => Some(Decimal::from_f32(value).into()),
-                EncodedTerm::DoubleLiteral(value) => Some(Decimal::from_f64(value).into()),
                 EncodedTerm::IntegerLiteral(value) => Some(Decimal::from(value).into()),
                 EncodedTerm::DecimalLiteral(value) => Some(value.into()),
+                term @ EncodedTerm::BigIntegerLiteral { .. } => {
+                    self.build_decimal_literal(&self.to_big_decimal(&term)?)
+                }
+                term @ EncodedTerm::BigDecimalLiteral { .. } => Some(term),
                 EncodedTerm::BooleanLiteral(value) => {
                     Some(Decimal::from(if value { 1 } else { 0 }).into())
                 }
-                EncodedTerm::SmallStringLiteral(value) => parse_decimal_str(&value),
+                EncodedTerm::SmallStringLiteral(value) => self.parse_decimal_literal(&value),
                 EncodedTerm::BigStringLiteral { value_id } => {
-                    parse_decimal_str(&*self.dataset.get_str(value_id).ok()??)
+                    self.parse_decimal_literal(&self.dataset.get_str(value_id).ok()??)
                 }
                 _ => None,
             },
@@ -1555,16 +1881,82 @@ where
                 }
                 _ => None,
             },
-            PlanExpression::StringCast(e) => {
-                Some(self.build_string_literal_from_id(
-                    self.to_string_id(self.eval_expression(e, tuple)?)?,
-                ))
+            PlanExpression::HexBinaryCast(e) => match self.eval_expression(e, tuple)? {
+                term @ EncodedTerm::HexBinaryLiteral { .. } => Some(term),
+                EncodedTerm::Base64BinaryLiteral { value_id } => self.build_hex_binary_literal(
+                    &self
+                        .dataset
+                        .get_str(value_id)
+                        .ok()??
+                        .parse::<Base64Binary>()
+                        .ok()?
+                        .into(),
+                ),
+                term => self.build_hex_binary_literal(&self.to_simple_string(&term)?.parse().ok()?),
+            },
+            PlanExpression::Base64BinaryCast(e) => match self.eval_expression(e, tuple)? {
+                term @ EncodedTerm::Base64BinaryLiteral { .. } => Some(term),
+                EncodedTerm::HexBinaryLiteral { value_id } => self.build_base64_binary_literal(
+                    &self
+                        .dataset
+                        .get_str(value_id)
+                        .ok()??
+                        .parse::<HexBinary>()
+                        .ok()?
+                        .into(),
+                ),
+                term => {
+                    self.build_base64_binary_literal(&self.to_simple_string(&term)?.parse().ok()?)
+                }
+            },
+            PlanExpression::AnyUriCast(e) => match self.eval_expression(e, tuple)? {
+                term @ EncodedTerm::SmallAnyUriLiteral(_)
+                | term @ EncodedTerm::BigAnyUriLiteral { .. } => Some(term),
+                term => self.build_any_uri_literal(parse_any_uri(&self.to_simple_string(&term)?)?),
+            },
+            PlanExpression::StringCast(e) => Some(self.build_string_literal_from_id(
+                self.to_string_id(&self.eval_expression(e, tuple)?)?,
+            )),
+        }
+    }
+
+    /// Evaluates the expression and promotes the values of the datatypes derived from `xsd:integer` to `xsd:integer`
+    fn eval_promoted_expression(
+        &self,
+        expression: &PlanExpression<S::StrId>,
+        tuple: &EncodedTuple<S::StrId>,
+    ) -> Option<EncodedTerm<S::StrId>> {
+        Some(
+            self.eval_expression(expression, tuple)?
+                .promote_derived_integer(),
+        )
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn cast_to_integer(&self, term: EncodedTerm<S::StrId>) -> Option<EncodedTerm<S::StrId>> {
+        match term.promote_derived_integer() {
+            EncodedTerm::FloatLiteral(value) => Some((value as i64).into()),
+            EncodedTerm::DoubleLiteral(value) => Some((value as i64).into()),
+            EncodedTerm::IntegerLiteral(value) => Some(value.into()),
+            EncodedTerm::DecimalLiteral(value) => i64::try_from(value)
+                .map(Into::into)
+                .ok()
+                .or_else(|| self.build_integer_literal(&BigDecimal::from(value).trunc())),
+            term @ EncodedTerm::BigIntegerLiteral { .. } => Some(term),
+            term @ EncodedTerm::BigDecimalLiteral { .. } => {
+                self.build_integer_literal(&self.to_big_decimal(&term)?.trunc())
+            }
+            EncodedTerm::BooleanLiteral(value) => Some(if value { 1 } else { 0 }.into()),
+            EncodedTerm::SmallStringLiteral(value) => self.parse_integer_literal(&value),
+            EncodedTerm::BigStringLiteral { value_id } => {
+                self.parse_integer_literal(&self.dataset.get_str(value_id).ok()??)
             }
+            _ => None,
         }
     }
 
     fn to_bool(&self, term: EncodedTerm<S::StrId>) -> Option<bool> {
-        match term {
+        match term.promote_derived_integer() {
             EncodedTerm::BooleanLiteral(value) => Some(value),
             EncodedTerm::SmallStringLiteral(value) => Some(!value.is_empty()),
             EncodedTerm::BigStringLiteral { value_id } => {
@@ -1574,31 +1966,45 @@ where
             EncodedTerm::DoubleLiteral(value) => Some(value != 0_f64),
             EncodedTerm::IntegerLiteral(value) => Some(value != 0),
             EncodedTerm::DecimalLiteral(value) => Some(value != Decimal::default()),
+            EncodedTerm::BigIntegerLiteral { .. } | EncodedTerm::BigDecimalLiteral { .. } => {
+                Some(true)
+            }
             _ => None,
         }
     }
 
-    fn to_string_id(&self, term: EncodedTerm<S::StrId>) -> Option<SmallStringOrId<S::StrId>> {
-        match term {
+    fn to_string_id(&self, term: &EncodedTerm<S::StrId>) -> Option<SmallStringOrId<S::StrId>> {
+        match *term {
             EncodedTerm::DefaultGraph => None,
             EncodedTerm::NamedNode { iri_id } => Some(iri_id.into()),
             EncodedTerm::NumericalBlankNode { .. }
             | EncodedTerm::SmallBlankNode { .. }
-            | EncodedTerm::BigBlankNode { .. } => None,
+            | EncodedTerm::BigBlankNode { .. }
+            | EncodedTerm::Triple { .. } => None,
             EncodedTerm::SmallStringLiteral(value)
             | EncodedTerm::SmallSmallLangStringLiteral { value, .. }
             | EncodedTerm::SmallBigLangStringLiteral { value, .. }
-            | EncodedTerm::SmallTypedLiteral { value, .. } => Some(value.into()),
+            | EncodedTerm::SmallTypedLiteral { value, .. }
+            | EncodedTerm::SmallAnyUriLiteral(value) => Some(value.into()),
             EncodedTerm::BigStringLiteral { value_id }
             | EncodedTerm::BigSmallLangStringLiteral { value_id, .. }
             | EncodedTerm::BigBigLangStringLiteral { value_id, .. }
-            | EncodedTerm::BigTypedLiteral { value_id, .. } => Some(value_id.into()),
+            | EncodedTerm::BigTypedLiteral { value_id, .. }
+            | EncodedTerm::BigIntegerLiteral { value_id }
+            | EncodedTerm::BigDecimalLiteral { value_id }
+            | EncodedTerm::BigDerivedIntegerLiteral { value_id, .. }
+            | EncodedTerm::HexBinaryLiteral { value_id }
+            | EncodedTerm::Base64BinaryLiteral { value_id }
+            | EncodedTerm::BigAnyUriLiteral { value_id } => Some(value_id.into()),
             EncodedTerm::BooleanLiteral(value) => {
                 self.build_string_id(if value { "true" } else { "false" })
             }
             EncodedTerm::FloatLiteral(value) => self.build_string_id(&value.to_string()),
             EncodedTerm::DoubleLiteral(value) => self.build_string_id(&value.to_string()),
-            EncodedTerm::IntegerLiteral(value) => self.build_string_id(&value.to_string()),
+            EncodedTerm::IntegerLiteral(value)
+            | EncodedTerm::DerivedIntegerLiteral { value, .. } => {
+                self.build_string_id(&value.to_string())
+            }
             EncodedTerm::DecimalLiteral(value) => self.build_string_id(&value.to_string()),
             EncodedTerm::DateTimeLiteral(value) => self.build_string_id(&value.to_string()),
             EncodedTerm::TimeLiteral(value) => self.build_string_id(&value.to_string()),
@@ -1616,8 +2022,8 @@ where
         }
     }
 
-    fn to_simple_string(&self, term: EncodedTerm<S::StrId>) -> Option<String> {
-        match term {
+    fn to_simple_string(&self, term: &EncodedTerm<S::StrId>) -> Option<String> {
+        match *term {
             EncodedTerm::SmallStringLiteral(value) => Some(value.into()),
             EncodedTerm::BigStringLiteral { value_id } => self.dataset.get_str(value_id).ok()?,
             _ => None,
@@ -1626,17 +2032,17 @@ where
 
     fn to_simple_string_id(
         &self,
-        term: EncodedTerm<S::StrId>,
+        term: &EncodedTerm<S::StrId>,
     ) -> Option<SmallStringOrId<S::StrId>> {
-        match term {
+        match *term {
             EncodedTerm::SmallStringLiteral(value) => Some(value.into()),
             EncodedTerm::BigStringLiteral { value_id } => Some(value_id.into()),
             _ => None,
         }
     }
 
-    fn to_string(&self, term: EncodedTerm<S::StrId>) -> Option<String> {
-        match term {
+    fn to_string(&self, term: &EncodedTerm<S::StrId>) -> Option<String> {
+        match *term {
             EncodedTerm::SmallStringLiteral(value)
             | EncodedTerm::SmallSmallLangStringLiteral { value, .. }
             | EncodedTerm::SmallBigLangStringLiteral { value, .. } => Some(value.into()),
@@ -1651,9 +2057,9 @@ where
 
     fn to_string_and_language(
         &self,
-        term: EncodedTerm<S::StrId>,
+        term: &EncodedTerm<S::StrId>,
     ) -> Option<(String, Option<SmallStringOrId<S::StrId>>)> {
-        match term {
+        match *term {
             EncodedTerm::SmallStringLiteral(value) => Some((value.into(), None)),
             EncodedTerm::BigStringLiteral { value_id } => {
                 Some((self.dataset.get_str(value_id).ok()??, None))
@@ -1678,6 +2084,113 @@ where
         }
     }
 
+    fn get_pattern_value(
+        &self,
+        selector: &PatternValue<S::StrId>,
+        tuple: &EncodedTuple<S::StrId>,
+    ) -> Option<EncodedTerm<S::StrId>> {
+        match selector {
+            PatternValue::Constant(term) => Some(term.clone()),
+            PatternValue::Variable(v) => tuple.get(*v),
+            PatternValue::Triple(triple) => self.build_triple(
+                self.get_pattern_value(&triple.subject, tuple)?,
+                self.get_pattern_value(&triple.predicate, tuple)?,
+                self.get_pattern_value(&triple.object, tuple)?,
+            ),
+        }
+    }
+
+    /// Binds the variables of the pattern to the given value
+    ///
+    /// Returns `false` if the value does not match the pattern
+    fn put_pattern_value(
+        &self,
+        selector: &PatternValue<S::StrId>,
+        value: EncodedTerm<S::StrId>,
+        tuple: &mut EncodedTuple<S::StrId>,
+    ) -> bool {
+        match selector {
+            PatternValue::Constant(_) => true, // Already checked by the store lookup
+            PatternValue::Variable(v) => {
+                if let Some(old) = tuple.get(*v) {
+                    value == old
+                } else {
+                    tuple.set(*v, value);
+                    true
+                }
+            }
+            PatternValue::Triple(triple) => {
+                if let EncodedTerm::Triple(value) = value {
+                    self.put_quoted_pattern_value(&triple.subject, value.subject.clone(), tuple)
+                        && self.put_quoted_pattern_value(
+                            &triple.predicate,
+                            value.predicate.clone(),
+                            tuple,
+                        )
+                        && self.put_quoted_pattern_value(
+                            &triple.object,
+                            value.object.clone(),
+                            tuple,
+                        )
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    fn put_quoted_pattern_value(
+        &self,
+        selector: &PatternValue<S::StrId>,
+        value: EncodedTerm<S::StrId>,
+        tuple: &mut EncodedTuple<S::StrId>,
+    ) -> bool {
+        if let PatternValue::Constant(term) = selector {
+            *term == value
+        } else {
+            self.put_pattern_value(selector, value, tuple)
+        }
+    }
+
+    fn get_triple_template_value(
+        &self,
+        selector: &TripleTemplateValue<S::StrId>,
+        tuple: &EncodedTuple<S::StrId>,
+        bnodes: &mut Vec<EncodedTerm<S::StrId>>,
+    ) -> Option<EncodedTerm<S::StrId>> {
+        match selector {
+            TripleTemplateValue::Constant(term) => Some(term.clone()),
+            TripleTemplateValue::Variable(v) => tuple.get(*v),
+            TripleTemplateValue::BlankNode(id) => {
+                if *id >= bnodes.len() {
+                    bnodes.resize_with(*id, new_bnode)
+                }
+                Some(bnodes[*id].clone())
+            }
+            TripleTemplateValue::Triple(triple) => self.build_triple(
+                self.get_triple_template_value(&triple.subject, tuple, bnodes)?,
+                self.get_triple_template_value(&triple.predicate, tuple, bnodes)?,
+                self.get_triple_template_value(&triple.object, tuple, bnodes)?,
+            ),
+        }
+    }
+
+    fn build_triple(
+        &self,
+        subject: EncodedTerm<S::StrId>,
+        predicate: EncodedTerm<S::StrId>,
+        object: EncodedTerm<S::StrId>,
+    ) -> Option<EncodedTerm<S::StrId>> {
+        if (subject.is_named_node() || subject.is_blank_node() || subject.is_triple())
+            && predicate.is_named_node()
+            && !object.is_default_graph()
+        {
+            Some(EncodedTriple::new(subject, predicate, object).into())
+        } else {
+            None
+        }
+    }
+
     fn build_named_node(&self, iri: &str) -> Option<EncodedTerm<S::StrId>> {
         Some(EncodedTerm::NamedNode {
             iri_id: self.dataset.as_ref().encode_str(iri).ok()?,
@@ -1747,37 +2260,114 @@ where
         })
     }
 
-    fn build_language_id(&self, value: EncodedTerm<S::StrId>) -> Option<SmallStringOrId<S::StrId>> {
-        let mut language = self.to_simple_string(value)?;
-        language.make_ascii_lowercase();
-        self.build_string_id(LanguageTag::parse(language).ok()?.as_str())
+    /// Encodes the integer inline if it fits into an `i64`
+    fn build_integer_literal(&self, value: &BigInteger) -> Option<EncodedTerm<S::StrId>> {
+        Some(if let Ok(value) = i64::try_from(value) {
+            EncodedTerm::IntegerLiteral(value)
+        } else {
+            EncodedTerm::BigIntegerLiteral {
+                value_id: self.dataset.as_ref().encode_str(&value.to_string()).ok()?,
+            }
+        })
     }
 
-    fn to_argument_compatible_strings(
-        &self,
-        arg1: EncodedTerm<S::StrId>,
-        arg2: EncodedTerm<S::StrId>,
-    ) -> Option<(String, String, Option<SmallStringOrId<S::StrId>>)> {
-        let (value1, language1) = self.to_string_and_language(arg1)?;
-        let (value2, language2) = self.to_string_and_language(arg2)?;
-        if language2.is_none() || language1 == language2 {
-            Some((value1, value2, language1))
+    /// Encodes the decimal inline if it fits into a `Decimal`
+    fn build_decimal_literal(&self, value: &BigDecimal) -> Option<EncodedTerm<S::StrId>> {
+        Some(if let Ok(value) = Decimal::try_from(value) {
+            EncodedTerm::DecimalLiteral(value)
         } else {
-            None
-        }
+            EncodedTerm::BigDecimalLiteral {
+                value_id: self.dataset.as_ref().encode_str(&value.to_string()).ok()?,
+            }
+        })
     }
 
-    fn compile_pattern(
+    /// Encodes the integer as a value of the derived datatype or returns `None` if it is out of the datatype range
+    fn build_derived_integer_literal(
         &self,
-        pattern: EncodedTerm<S::StrId>,
-        flags: Option<EncodedTerm<S::StrId>>,
-    ) -> Option<Regex> {
+        value: &EncodedTerm<S::StrId>,
+        datatype: DerivedIntegerType,
+    ) -> Option<EncodedTerm<S::StrId>> {
+        match *value {
+            EncodedTerm::IntegerLiteral(value) => {
+                if datatype.contains_i64(value) {
+                    Some(EncodedTerm::DerivedIntegerLiteral { value, datatype })
+                } else {
+                    None
+                }
+            }
+            EncodedTerm::BigIntegerLiteral { value_id } => {
+                if datatype.contains(&self.dataset.get_str(value_id).ok()??.parse().ok()?) {
+                    Some(EncodedTerm::BigDerivedIntegerLiteral { value_id, datatype })
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn build_hex_binary_literal(&self, value: &HexBinary) -> Option<EncodedTerm<S::StrId>> {
+        Some(EncodedTerm::HexBinaryLiteral {
+            value_id: self.dataset.as_ref().encode_str(&value.to_string()).ok()?,
+        })
+    }
+
+    fn build_base64_binary_literal(&self, value: &Base64Binary) -> Option<EncodedTerm<S::StrId>> {
+        Some(EncodedTerm::Base64BinaryLiteral {
+            value_id: self.dataset.as_ref().encode_str(&value.to_string()).ok()?,
+        })
+    }
+
+    fn build_any_uri_literal(&self, value: &str) -> Option<EncodedTerm<S::StrId>> {
+        Some(match self.build_string_id(value)? {
+            SmallStringOrId::Small(value) => EncodedTerm::SmallAnyUriLiteral(value),
+            SmallStringOrId::Big(value_id) => EncodedTerm::BigAnyUriLiteral { value_id },
+        })
+    }
+
+    fn parse_integer_literal(&self, value: &str) -> Option<EncodedTerm<S::StrId>> {
+        parse_integer_str(value).or_else(|| self.build_integer_literal(&value.parse().ok()?))
+    }
+
+    fn parse_decimal_literal(&self, value: &str) -> Option<EncodedTerm<S::StrId>> {
+        parse_decimal_str(value).or_else(|| self.build_decimal_literal(&value.parse().ok()?))
+    }
+
+    fn build_language_id(
+        &self,
+        value: &EncodedTerm<S::StrId>,
+    ) -> Option<SmallStringOrId<S::StrId>> {
+        let mut language = self.to_simple_string(value)?;
+        language.make_ascii_lowercase();
+        self.build_string_id(LanguageTag::parse(language).ok()?.as_str())
+    }
+
+    fn to_argument_compatible_strings(
+        &self,
+        arg1: &EncodedTerm<S::StrId>,
+        arg2: &EncodedTerm<S::StrId>,
+    ) -> Option<(String, String, Option<SmallStringOrId<S::StrId>>)> {
+        let (value1, language1) = self.to_string_and_language(arg1)?;
+        let (value2, language2) = self.to_string_and_language(arg2)?;
+        if language2.is_none() || language1 == language2 {
+            Some((value1, value2, language1))
+        } else {
+            None
+        }
+    }
+
+    fn compile_pattern(
+        &self,
+        pattern: &EncodedTerm<S::StrId>,
+        flags: Option<EncodedTerm<S::StrId>>,
+    ) -> Option<Regex> {
         // TODO Avoid to compile the regex each time
         let pattern = self.to_simple_string(pattern)?;
         let mut regex_builder = RegexBuilder::new(&pattern);
         regex_builder.size_limit(REGEX_SIZE_LIMIT);
         if let Some(flags) = flags {
-            let flags = self.to_simple_string(flags)?;
+            let flags = self.to_simple_string(&flags)?;
             for flag in flags.chars() {
                 match flag {
                     's' => {
@@ -1806,12 +2396,103 @@ where
         e2: &PlanExpression<S::StrId>,
         tuple: &EncodedTuple<S::StrId>,
     ) -> Option<NumericBinaryOperands> {
-        NumericBinaryOperands::new(
+        self.numeric_operands(
             self.eval_expression(e1, tuple)?,
             self.eval_expression(e2, tuple)?,
         )
     }
 
+    fn numeric_operands(
+        &self,
+        a: EncodedTerm<S::StrId>,
+        b: EncodedTerm<S::StrId>,
+    ) -> Option<NumericBinaryOperands> {
+        let a = a.promote_derived_integer();
+        let b = b.promote_derived_integer();
+        if !a.is_big_numeric_literal() && !b.is_big_numeric_literal() {
+            return NumericBinaryOperands::new(a, b);
+        }
+        Some(match (a, b) {
+            (EncodedTerm::FloatLiteral(v1), b) => {
+                NumericBinaryOperands::Float(v1, self.to_big_decimal(&b)?.to_f32())
+            }
+            (EncodedTerm::DoubleLiteral(v1), b) => {
+                NumericBinaryOperands::Double(v1, self.to_big_decimal(&b)?.to_f64())
+            }
+            (a, EncodedTerm::FloatLiteral(v2)) => {
+                NumericBinaryOperands::Float(self.to_big_decimal(&a)?.to_f32(), v2)
+            }
+            (a, EncodedTerm::DoubleLiteral(v2)) => {
+                NumericBinaryOperands::Double(self.to_big_decimal(&a)?.to_f64(), v2)
+            }
+            (
+                a @ (EncodedTerm::IntegerLiteral(_) | EncodedTerm::BigIntegerLiteral { .. }),
+                b @ (EncodedTerm::IntegerLiteral(_) | EncodedTerm::BigIntegerLiteral { .. }),
+            ) => NumericBinaryOperands::BigInteger(
+                self.to_big_integer(&a)?,
+                self.to_big_integer(&b)?,
+            ),
+            (a, b) => NumericBinaryOperands::BigDecimal(
+                self.to_big_decimal(&a)?,
+                self.to_big_decimal(&b)?,
+            ),
+        })
+    }
+
+    fn to_big_integer(&self, term: &EncodedTerm<S::StrId>) -> Option<BigInteger> {
+        match *term {
+            EncodedTerm::IntegerLiteral(value) => Some(value.into()),
+            EncodedTerm::BigIntegerLiteral { value_id } => {
+                self.dataset.get_str(value_id).ok()??.parse().ok()
+            }
+            _ => None,
+        }
+    }
+
+    fn to_big_decimal(&self, term: &EncodedTerm<S::StrId>) -> Option<BigDecimal> {
+        match *term {
+            EncodedTerm::IntegerLiteral(value) => Some(value.into()),
+            EncodedTerm::DecimalLiteral(value) => Some(value.into()),
+            EncodedTerm::BigIntegerLiteral { .. } => Some(self.to_big_integer(term)?.into()),
+            EncodedTerm::BigDecimalLiteral { value_id } => {
+                self.dataset.get_str(value_id).ok()??.parse().ok()
+            }
+            _ => None,
+        }
+    }
+
+    #[allow(clippy::float_cmp)]
+    fn equals_big_numerics(
+        &self,
+        a: &EncodedTerm<S::StrId>,
+        b: &EncodedTerm<S::StrId>,
+    ) -> Option<bool> {
+        if let Some(ordering) = self.partial_cmp_big_numerics(a, b) {
+            Some(ordering == Ordering::Equal)
+        } else if a.is_unknown_typed_literal() || b.is_unknown_typed_literal() {
+            None
+        } else {
+            Some(false)
+        }
+    }
+
+    /// Compares numeric literals when at least one of them is not encoded inline
+    fn partial_cmp_big_numerics(
+        &self,
+        a: &EncodedTerm<S::StrId>,
+        b: &EncodedTerm<S::StrId>,
+    ) -> Option<Ordering> {
+        match (a, b) {
+            (EncodedTerm::FloatLiteral(a), b) => a.partial_cmp(&self.to_big_decimal(b)?.to_f32()),
+            (EncodedTerm::DoubleLiteral(a), b) => a.partial_cmp(&self.to_big_decimal(b)?.to_f64()),
+            (a, EncodedTerm::FloatLiteral(b)) => self.to_big_decimal(a)?.to_f32().partial_cmp(b),
+            (a, EncodedTerm::DoubleLiteral(b)) => self.to_big_decimal(a)?.to_f64().partial_cmp(b),
+            (a, b) => self
+                .to_big_decimal(a)?
+                .partial_cmp(&self.to_big_decimal(b)?),
+        }
+    }
+
     fn decode_bindings(
         &self,
         iter: EncodedTuplesIterator<S::StrId>,
@@ -1861,6 +2542,14 @@ where
         clippy::cast_precision_loss
     )]
     fn equals(&self, a: EncodedTerm<S::StrId>, b: EncodedTerm<S::StrId>) -> Option<bool> {
+        let a = self.with_implicit_timezone(a);
+        let b = self
+            .with_implicit_timezone(b)
+            .promote_derived_integer()
+            .promote_any_uri();
+        if b.is_big_numeric_literal() {
+            return self.equals_big_numerics(&a, &b);
+        }
         match a {
             EncodedTerm::DefaultGraph
             | EncodedTerm::NamedNode { .. }
@@ -1870,7 +2559,8 @@ where
             | EncodedTerm::SmallSmallLangStringLiteral { .. }
             | EncodedTerm::SmallBigLangStringLiteral { .. }
             | EncodedTerm::BigSmallLangStringLiteral { .. }
-            | EncodedTerm::BigBigLangStringLiteral { .. } => Some(a == b),
+            | EncodedTerm::BigBigLangStringLiteral { .. }
+            | EncodedTerm::Triple { .. } => Some(a == b),
             EncodedTerm::SmallStringLiteral(a) => match b {
                 EncodedTerm::SmallStringLiteral(b) => Some(a == b),
                 EncodedTerm::SmallTypedLiteral { .. } | EncodedTerm::BigTypedLiteral { .. } => None,
@@ -1944,6 +2634,26 @@ where
                 _ if b.is_unknown_typed_literal() => None,
                 _ => Some(false),
             },
+            EncodedTerm::BigIntegerLiteral { .. } | EncodedTerm::BigDecimalLiteral { .. } => {
+                self.equals_big_numerics(&a, &b)
+            }
+            EncodedTerm::DerivedIntegerLiteral { .. }
+            | EncodedTerm::BigDerivedIntegerLiteral { .. } => {
+                self.equals(a.promote_derived_integer(), b)
+            }
+            EncodedTerm::HexBinaryLiteral { value_id: a } => match b {
+                EncodedTerm::HexBinaryLiteral { value_id: b } => Some(a == b),
+                _ if b.is_unknown_typed_literal() => None,
+                _ => Some(false),
+            },
+            EncodedTerm::Base64BinaryLiteral { value_id: a } => match b {
+                EncodedTerm::Base64BinaryLiteral { value_id: b } => Some(a == b),
+                _ if b.is_unknown_typed_literal() => None,
+                _ => Some(false),
+            },
+            EncodedTerm::SmallAnyUriLiteral(_) | EncodedTerm::BigAnyUriLiteral { .. } => {
+                self.equals(a.promote_any_uri(), b)
+            }
             EncodedTerm::DateTimeLiteral(a) => match b {
                 EncodedTerm::DateTimeLiteral(b) => Some(a == b),
                 _ if b.is_unknown_typed_literal() => None,
@@ -2049,12 +2759,63 @@ where
         }
     }
 
+    /// Evaluates the timezone parameter of the `fn:adjust-*-to-timezone` functions
+    ///
+    /// The implicit timezone, or UTC if it is not set, is used if the parameter is not given.
+    fn eval_timezone(
+        &self,
+        timezone: &Option<Box<PlanExpression<S::StrId>>>,
+        tuple: &EncodedTuple<S::StrId>,
+    ) -> Option<TimezoneOffset> {
+        Some(if let Some(timezone) = timezone {
+            if let EncodedTerm::DayTimeDurationLiteral(timezone) =
+                self.eval_expression(timezone, tuple)?
+            {
+                TimezoneOffset::try_from(timezone).ok()?
+            } else {
+                return None;
+            }
+        } else {
+            self.implicit_timezone.unwrap_or_else(TimezoneOffset::utc)
+        })
+    }
+
+    /// Gives the implicit timezone to the `xsd:dateTime`, `xsd:date` and `xsd:time` values without timezone
+    fn with_implicit_timezone(&self, term: EncodedTerm<S::StrId>) -> EncodedTerm<S::StrId> {
+        if self.implicit_timezone.is_none() {
+            return term;
+        }
+        match term {
+            EncodedTerm::DateTimeLiteral(value) if value.timezone_offset().is_none() => value
+                .adjust(self.implicit_timezone)
+                .map_or(term, Into::into),
+            EncodedTerm::DateLiteral(value) if value.timezone_offset().is_none() => value
+                .adjust(self.implicit_timezone)
+                .map_or(term, Into::into),
+            EncodedTerm::TimeLiteral(value) if value.timezone_offset().is_none() => value
+                .adjust(self.implicit_timezone)
+                .map_or(term, Into::into),
+            _ => term,
+        }
+    }
+
     #[allow(clippy::cast_precision_loss)]
     fn partial_cmp_literals(
         &self,
         a: EncodedTerm<S::StrId>,
         b: EncodedTerm<S::StrId>,
     ) -> Option<Ordering> {
+        let a = self
+            .with_implicit_timezone(a)
+            .promote_derived_integer()
+            .promote_any_uri();
+        let b = self
+            .with_implicit_timezone(b)
+            .promote_derived_integer()
+            .promote_any_uri();
+        if a.is_big_numeric_literal() || b.is_big_numeric_literal() {
+            return self.partial_cmp_big_numerics(&a, &b);
+        }
         match a {
             EncodedTerm::SmallStringLiteral(a) => match b {
                 EncodedTerm::SmallStringLiteral(b) => a.partial_cmp(&b),
@@ -2194,19 +2955,20 @@ where
         arg: &PlanExpression<S::StrId>,
         tuple: &EncodedTuple<S::StrId>,
     ) -> Option<EncodedTerm<S::StrId>> {
-        let input = self.to_simple_string(self.eval_expression(arg, tuple)?)?;
+        let input = self.to_simple_string(&self.eval_expression(arg, tuple)?)?;
         let hash = hex::encode(H::new().chain(input.as_str()).finalize());
         self.build_string_literal(&hash)
     }
 
-    fn datatype(&self, value: EncodedTerm<S::StrId>) -> Option<EncodedTerm<S::StrId>> {
+    fn datatype(&self, value: &EncodedTerm<S::StrId>) -> Option<EncodedTerm<S::StrId>> {
         //TODO: optimize?
-        match value {
+        match *value {
             EncodedTerm::NamedNode { .. }
             | EncodedTerm::SmallBlankNode { .. }
             | EncodedTerm::BigBlankNode { .. }
             | EncodedTerm::NumericalBlankNode { .. }
-            | EncodedTerm::DefaultGraph => None,
+            | EncodedTerm::DefaultGraph
+            | EncodedTerm::Triple { .. } => None,
             EncodedTerm::SmallStringLiteral(_) | EncodedTerm::BigStringLiteral { .. } => {
                 self.build_named_node(xsd::STRING.as_str())
             }
@@ -2223,8 +2985,23 @@ where
             EncodedTerm::BooleanLiteral(..) => self.build_named_node(xsd::BOOLEAN.as_str()),
             EncodedTerm::FloatLiteral(..) => self.build_named_node(xsd::FLOAT.as_str()),
             EncodedTerm::DoubleLiteral(..) => self.build_named_node(xsd::DOUBLE.as_str()),
-            EncodedTerm::IntegerLiteral(..) => self.build_named_node(xsd::INTEGER.as_str()),
-            EncodedTerm::DecimalLiteral(..) => self.build_named_node(xsd::DECIMAL.as_str()),
+            EncodedTerm::IntegerLiteral(..) | EncodedTerm::BigIntegerLiteral { .. } => {
+                self.build_named_node(xsd::INTEGER.as_str())
+            }
+            EncodedTerm::DecimalLiteral(..) | EncodedTerm::BigDecimalLiteral { .. } => {
+                self.build_named_node(xsd::DECIMAL.as_str())
+            }
+            EncodedTerm::DerivedIntegerLiteral { datatype, .. }
+            | EncodedTerm::BigDerivedIntegerLiteral { datatype, .. } => {
+                self.build_named_node(datatype.name().as_str())
+            }
+            EncodedTerm::HexBinaryLiteral { .. } => self.build_named_node(xsd::HEX_BINARY.as_str()),
+            EncodedTerm::Base64BinaryLiteral { .. } => {
+                self.build_named_node(xsd::BASE_64_BINARY.as_str())
+            }
+            EncodedTerm::SmallAnyUriLiteral(_) | EncodedTerm::BigAnyUriLiteral { .. } => {
+                self.build_named_node(xsd::ANY_URI.as_str())
+            }
             EncodedTerm::DateTimeLiteral(..) => self.build_named_node(xsd::DATE_TIME.as_str()),
             EncodedTerm::TimeLiteral(..) => self.build_named_node(xsd::TIME.as_str()),
             EncodedTerm::DateLiteral(..) => self.build_named_node(xsd::DATE.as_str()),
@@ -2249,6 +3026,8 @@ enum NumericBinaryOperands {
     Double(f64, f64),
     Integer(i64, i64),
     Decimal(Decimal, Decimal),
+    BigInteger(BigInteger, BigInteger),
+    BigDecimal(BigDecimal, BigDecimal),
     Duration(Duration, Duration),
     YearMonthDuration(YearMonthDuration, YearMonthDuration),
     DayTimeDuration(DayTimeDuration, DayTimeDuration),
@@ -2385,27 +3164,6 @@ impl NumericBinaryOperands {
     }
 }
 
-fn get_pattern_value<I: StrId>(
-    selector: &PatternValue<I>,
-    tuple: &EncodedTuple<I>,
-) -> Option<EncodedTerm<I>> {
-    match selector {
-        PatternValue::Constant(term) => Some(*term),
-        PatternValue::Variable(v) => tuple.get(*v),
-    }
-}
-
-fn put_pattern_value<I: StrId>(
-    selector: &PatternValue<I>,
-    value: EncodedTerm<I>,
-    tuple: &mut EncodedTuple<I>,
-) {
-    match selector {
-        PatternValue::Constant(_) => (),
-        PatternValue::Variable(v) => tuple.set(*v, value),
-    }
-}
-
 fn put_variable_value<I: StrId>(
     selector: &Variable,
     variables: &[Variable],
@@ -2637,7 +3395,10 @@ struct ConstructIterator<S: ReadableEncodedStore + 'static> {
     bnodes: Vec<EncodedTerm<S::StrId>>,
 }
 
-impl<S: ReadableEncodedStore<Error = EvaluationError> + 'static> Iterator for ConstructIterator<S> {
+impl<S: ReadableEncodedStore<Error = EvaluationError> + 'static> Iterator for ConstructIterator<S>
+where
+    for<'a> &'a S: StrContainer<StrId = S::StrId>,
+{
     type Item = Result<Triple, EvaluationError>;
 
     fn next(&mut self) -> Option<Result<Triple, EvaluationError>> {
@@ -2652,9 +3413,21 @@ impl<S: ReadableEncodedStore<Error = EvaluationError> + 'static> Iterator for Co
                 };
                 for template in &self.template {
                     if let (Some(subject), Some(predicate), Some(object)) = (
-                        get_triple_template_value(&template.subject, &tuple, &mut self.bnodes),
-                        get_triple_template_value(&template.predicate, &tuple, &mut self.bnodes),
-                        get_triple_template_value(&template.object, &tuple, &mut self.bnodes),
+                        self.eval.get_triple_template_value(
+                            &template.subject,
+                            &tuple,
+                            &mut self.bnodes,
+                        ),
+                        self.eval.get_triple_template_value(
+                            &template.predicate,
+                            &tuple,
+                            &mut self.bnodes,
+                        ),
+                        self.eval.get_triple_template_value(
+                            &template.object,
+                            &tuple,
+                            &mut self.bnodes,
+                        ),
                     ) {
                         self.buffered_results.push(decode_triple(
                             &*self.eval.dataset,
@@ -2670,23 +3443,6 @@ impl<S: ReadableEncodedStore<Error = EvaluationError> + 'static> Iterator for Co
     }
 }
 
-fn get_triple_template_value<I: StrId>(
-    selector: &TripleTemplateValue<I>,
-    tuple: &EncodedTuple<I>,
-    bnodes: &mut Vec<EncodedTerm<I>>,
-) -> Option<EncodedTerm<I>> {
-    match selector {
-        TripleTemplateValue::Constant(term) => Some(*term),
-        TripleTemplateValue::Variable(v) => tuple.get(*v),
-        TripleTemplateValue::BlankNode(id) => {
-            if *id >= bnodes.len() {
-                bnodes.resize_with(*id, new_bnode)
-            }
-            Some(bnodes[*id])
-        }
-    }
-}
-
 fn new_bnode<I: StrId>() -> EncodedTerm<I> {
     EncodedTerm::NumericalBlankNode { id: random() }
 }
@@ -2698,7 +3454,7 @@ fn decode_triple<D: Decoder>(
     object: EncodedTerm<D::StrId>,
 ) -> Result<Triple, EvaluationError> {
     Ok(Triple::new(
-        decoder.decode_named_or_blank_node(subject)?,
+        decoder.decode_subject(subject)?,
         decoder.decode_named_node(predicate)?,
         decoder.decode_term(object)?,
     ))
@@ -2734,7 +3490,7 @@ impl<S: ReadableEncodedStore<Error = EvaluationError> + 'static> Iterator for De
             self.quads = Box::new(tuple.into_iter().flatten().flat_map(move |subject| {
                 eval.dataset
                     .encoded_quads_for_pattern(
-                        Some(subject),
+                        Some(subject.clone()),
                         None,
                         None,
                         Some(EncodedTerm::DefaultGraph),
@@ -2772,66 +3528,241 @@ impl<T1, T2, I1: Iterator<Item = T1>, I2: Iterator<Item = T2>> Iterator
     }
 }
 
-fn transitive_closure<T: Copy + Eq + Hash, NI: Iterator<Item = Result<T, EvaluationError>>>(
+fn transitive_closure<T: Clone + Eq + Hash, NI: Iterator<Item = Result<T, EvaluationError>>>(
+    interruption: &Interruption,
+    limits: &ResourceLimits,
     start: impl IntoIterator<Item = Result<T, EvaluationError>>,
     next: impl Fn(T) -> NI,
 ) -> impl Iterator<Item = Result<T, EvaluationError>> {
     //TODO: optimize
     let mut all = HashSet::<T>::default();
     let mut errors = Vec::default();
-    let mut current = start
-        .into_iter()
-        .filter_map(|e| match e {
-            Ok(e) => {
-                all.insert(e);
-                Some(e)
+    let mut current = Vec::default();
+    let mut result = extend_transitive_closure(
+        interruption,
+        limits,
+        start,
+        &mut all,
+        &mut current,
+        &mut errors,
+    );
+    while result.is_ok() && !current.is_empty() {
+        let mut new = Vec::default();
+        result = extend_transitive_closure(
+            interruption,
+            limits,
+            current.into_iter().flat_map(&next),
+            &mut all,
+            &mut new,
+            &mut errors,
+        );
+        current = new;
+    }
+    if let Err(error) = result {
+        errors.push(error);
+        all.clear();
+    }
+    errors.into_iter().map(Err).chain(all.into_iter().map(Ok))
+}
+
+/// Adds the values not already in `all` to `all` and `new`
+fn extend_transitive_closure<T: Clone + Eq + Hash>(
+    interruption: &Interruption,
+    limits: &ResourceLimits,
+    values: impl IntoIterator<Item = Result<T, EvaluationError>>,
+    all: &mut HashSet<T>,
+    new: &mut Vec<T>,
+    errors: &mut Vec<EvaluationError>,
+) -> Result<(), EvaluationError> {
+    for value in values {
+        interruption.check()?;
+        match value {
+            Ok(value) => {
+                if all.insert(value.clone()) {
+                    limits.check_buffered_tuples(all.len())?;
+                    new.push(value);
+                }
             }
-            Err(error) => {
-                errors.push(error);
-                None
+            Err(error) => errors.push(error),
+        }
+    }
+    Ok(())
+}
+
+/// The deadline and the cancellation token of an evaluation
+#[derive(Clone, Default)]
+pub(crate) struct Interruption {
+    deadline: Option<Instant>,
+    cancellation_token: Option<CancellationToken>,
+}
+
+impl Interruption {
+    pub fn new(options: &QueryOptions) -> Self {
+        Self {
+            deadline: options.timeout.map(|timeout| Instant::now() + timeout),
+            cancellation_token: options.cancellation_token.clone(),
+        }
+    }
+
+    fn is_never(&self) -> bool {
+        self.deadline.is_none() && self.cancellation_token.is_none()
+    }
+
+    pub fn check(&self) -> Result<(), EvaluationError> {
+        if let Some(cancellation_token) = &self.cancellation_token {
+            if cancellation_token.is_cancelled() {
+                return Err(EvaluationError::Cancelled);
             }
-        })
-        .collect::<Vec<_>>();
-
-    while !current.is_empty() {
-        current = current
-            .into_iter()
-            .flat_map(|e| next(e))
-            .filter_map(|e| match e {
-                Ok(e) => {
-                    if all.contains(&e) {
-                        None
-                    } else {
-                        all.insert(e);
-                        Some(e)
-                    }
+        }
+        if let Some(deadline) = self.deadline {
+            if Instant::now() >= deadline {
+                return Err(EvaluationError::Timeout);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The resource limits of an evaluation and the number of intermediate results produced so far
+#[derive(Clone, Default)]
+pub(crate) struct ResourceLimits {
+    max_buffered_tuples: Option<usize>,
+    max_intermediate_results: Option<usize>,
+    max_result_rows: Option<usize>,
+    intermediate_results: Rc<Cell<usize>>,
+}
+
+impl ResourceLimits {
+    fn new(options: &QueryOptions) -> Self {
+        Self {
+            max_buffered_tuples: options.max_buffered_tuples,
+            max_intermediate_results: options.max_intermediate_results,
+            max_result_rows: options.max_result_rows,
+            intermediate_results: Rc::default(),
+        }
+    }
+
+    fn check_buffered_tuples(&self, len: usize) -> Result<(), EvaluationError> {
+        match self.max_buffered_tuples {
+            Some(max) if len > max => Err(EvaluationError::ResourceLimitExceeded(
+                ResourceLimit::BufferedTuples(max),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn count_intermediate_result(&self) -> Result<(), EvaluationError> {
+        if let Some(max) = self.max_intermediate_results {
+            let count = self.intermediate_results.get() + 1;
+            if count > max {
+                return Err(EvaluationError::ResourceLimitExceeded(
+                    ResourceLimit::IntermediateResults(max),
+                ));
+            }
+            self.intermediate_results.set(count);
+        }
+        Ok(())
+    }
+
+    /// Collects the results of an iterator, the errors being pushed to `errors`
+    fn buffer<T>(
+        &self,
+        iter: impl Iterator<Item = Result<T, EvaluationError>>,
+        errors: &mut Vec<EvaluationError>,
+    ) -> Result<Vec<T>, EvaluationError> {
+        let mut values = Vec::new();
+        for result in iter {
+            match result {
+                Ok(value) => {
+                    self.check_buffered_tuples(values.len() + 1)?;
+                    values.push(value);
                 }
-                Err(error) => {
-                    errors.push(error);
-                    None
+                Err(error) => errors.push(error),
+            }
+        }
+        Ok(values)
+    }
+
+    /// Replaces the first result over the maximal number of results by an error and stops the iteration
+    fn limit_result_rows<T: 'static>(
+        &self,
+        iter: Box<dyn Iterator<Item = Result<T, EvaluationError>>>,
+    ) -> Box<dyn Iterator<Item = Result<T, EvaluationError>>> {
+        if let Some(max) = self.max_result_rows {
+            let mut count = 0;
+            Box::new(iter.scan(false, move |exceeded, result| {
+                if *exceeded {
+                    return None;
                 }
-            })
-            .collect();
+                if result.is_ok() {
+                    count += 1;
+                    if count > max {
+                        *exceeded = true;
+                        return Some(Err(EvaluationError::ResourceLimitExceeded(
+                            ResourceLimit::ResultRows(max),
+                        )));
+                    }
+                }
+                Some(result)
+            }))
+        } else {
+            iter
+        }
+    }
+}
+
+/// Stops the iteration with an error when the evaluation is interrupted or exceeds the maximal number of intermediate results
+struct CheckedIterator<I: StrId> {
+    inner: Option<EncodedTuplesIterator<I>>,
+    interruption: Interruption,
+    limits: ResourceLimits,
+}
+
+impl<I: StrId> Iterator for CheckedIterator<I> {
+    type Item = Result<EncodedTuple<I>, EvaluationError>;
+
+    fn next(&mut self) -> Option<Result<EncodedTuple<I>, EvaluationError>> {
+        let inner = self.inner.as_mut()?;
+        if let Err(error) = self.interruption.check() {
+            self.inner = None;
+            return Some(Err(error));
+        }
+        let result = inner.next()?;
+        if result.is_ok() {
+            if let Err(error) = self.limits.count_intermediate_result() {
+                self.inner = None;
+                return Some(Err(error));
+            }
+        }
+        Some(result)
     }
-    errors.into_iter().map(Err).chain(all.into_iter().map(Ok))
 }
 
 fn hash_deduplicate<T: Eq + Hash + Clone>(
     iter: impl Iterator<Item = Result<T, EvaluationError>>,
+    limits: ResourceLimits,
 ) -> impl Iterator<Item = Result<T, EvaluationError>> {
     let mut already_seen = HashSet::with_capacity(iter.size_hint().0);
-    iter.filter(move |e| {
-        if let Ok(e) = e {
-            if already_seen.contains(e) {
-                false
-            } else {
-                already_seen.insert(e.clone());
-                true
-            }
-        } else {
-            true
+    iter.scan(false, move |exceeded, e| {
+        if *exceeded {
+            return None;
         }
+        Some(match e {
+            Ok(e) => {
+                if already_seen.contains(&e) {
+                    None
+                } else if let Err(error) = limits.check_buffered_tuples(already_seen.len() + 1) {
+                    *exceeded = true;
+                    Some(Err(error))
+                } else {
+                    already_seen.insert(e.clone());
+                    Some(Ok(e))
+                }
+            }
+            Err(error) => Some(Err(error)),
+        })
     })
+    .flatten()
 }
 
 trait ResultIterator<T>: Iterator<Item = Result<T, EvaluationError>> + Sized {
@@ -2915,7 +3846,7 @@ impl<I: StrId, T: Accumulator<I>> DistinctAccumulator<I, T> {
 
 impl<I: StrId, T: Accumulator<I>> Accumulator<I> for DistinctAccumulator<I, T> {
     fn add(&mut self, element: Option<EncodedTerm<I>>) {
-        if self.seen.insert(element) {
+        if self.seen.insert(element.clone()) {
             self.inner.add(element)
         }
     }
@@ -2940,29 +3871,52 @@ impl<I: StrId> Accumulator<I> for CountAccumulator {
     }
 }
 
-#[derive(Debug)]
-struct SumAccumulator<I: StrId> {
-    sum: Option<EncodedTerm<I>>,
+struct SumAccumulator<S: ReadableEncodedStore + 'static> {
+    eval: SimpleEvaluator<S>,
+    sum: Option<EncodedTerm<S::StrId>>,
 }
 
-impl<I: StrId> Default for SumAccumulator<I> {
-    fn default() -> Self {
+impl<S: ReadableEncodedStore + 'static> SumAccumulator<S> {
+    fn new(eval: SimpleEvaluator<S>) -> Self {
         Self {
+            eval,
             sum: Some(0.into()),
         }
     }
 }
 
-impl<I: StrId> Accumulator<I> for SumAccumulator<I> {
-    fn add(&mut self, element: Option<EncodedTerm<I>>) {
-        if let Some(sum) = self.sum {
-            if let Some(operands) = element.and_then(|e| NumericBinaryOperands::new(sum, e)) {
+impl<S: ReadableEncodedStore<Error = EvaluationError> + 'static> Accumulator<S::StrId>
+    for SumAccumulator<S>
+where
+    for<'a> &'a S: StrContainer<StrId = S::StrId>,
+{
+    fn add(&mut self, element: Option<EncodedTerm<S::StrId>>) {
+        if let Some(sum) = self.sum.clone() {
+            if let Some(operands) = element.and_then(|e| self.eval.numeric_operands(sum, e)) {
                 //TODO: unify with addition?
                 self.sum = match operands {
                     NumericBinaryOperands::Float(v1, v2) => Some((v1 + v2).into()),
                     NumericBinaryOperands::Double(v1, v2) => Some((v1 + v2).into()),
-                    NumericBinaryOperands::Integer(v1, v2) => v1.checked_add(v2).map(|v| v.into()),
-                    NumericBinaryOperands::Decimal(v1, v2) => v1.checked_add(v2).map(|v| v.into()),
+                    NumericBinaryOperands::Integer(v1, v2) => {
+                        v1.checked_add(v2).map(Into::into).or_else(|| {
+                            self.eval.build_integer_literal(
+                                &(BigInteger::from(v1) + BigInteger::from(v2)),
+                            )
+                        })
+                    }
+                    NumericBinaryOperands::Decimal(v1, v2) => {
+                        v1.checked_add(v2).map(Into::into).or_else(|| {
+                            self.eval.build_decimal_literal(
+                                &(BigDecimal::from(v1) + BigDecimal::from(v2)),
+                            )
+                        })
+                    }
+                    NumericBinaryOperands::BigInteger(v1, v2) => {
+                        self.eval.build_integer_literal(&(v1 + v2))
+                    }
+                    NumericBinaryOperands::BigDecimal(v1, v2) => {
+                        self.eval.build_decimal_literal(&(v1 + v2))
+                    }
                     NumericBinaryOperands::Duration(v1, v2) => v1.checked_add(v2).map(|v| v.into()),
                     _ => None,
                 };
@@ -2972,33 +3926,36 @@ impl<I: StrId> Accumulator<I> for SumAccumulator<I> {
         }
     }
 
-    fn state(&self) -> Option<EncodedTerm<I>> {
-        self.sum
+    fn state(&self) -> Option<EncodedTerm<S::StrId>> {
+        self.sum.clone()
     }
 }
 
-#[derive(Debug)]
-struct AvgAccumulator<I: StrId> {
-    sum: SumAccumulator<I>,
+struct AvgAccumulator<S: ReadableEncodedStore + 'static> {
+    sum: SumAccumulator<S>,
     count: CountAccumulator,
 }
 
-impl<I: StrId> Default for AvgAccumulator<I> {
-    fn default() -> Self {
+impl<S: ReadableEncodedStore + 'static> AvgAccumulator<S> {
+    fn new(eval: SimpleEvaluator<S>) -> Self {
         Self {
-            sum: SumAccumulator::default(),
+            sum: SumAccumulator::new(eval),
             count: CountAccumulator::default(),
         }
     }
 }
 
-impl<I: StrId> Accumulator<I> for AvgAccumulator<I> {
-    fn add(&mut self, element: Option<EncodedTerm<I>>) {
-        self.sum.add(element);
+impl<S: ReadableEncodedStore<Error = EvaluationError> + 'static> Accumulator<S::StrId>
+    for AvgAccumulator<S>
+where
+    for<'a> &'a S: StrContainer<StrId = S::StrId>,
+{
+    fn add(&mut self, element: Option<EncodedTerm<S::StrId>>) {
+        self.sum.add(element.clone());
         self.count.add(element);
     }
 
-    fn state(&self) -> Option<EncodedTerm<I>> {
+    fn state(&self) -> Option<EncodedTerm<S::StrId>> {
         let sum = self.sum.state()?;
         let count = self.count.state()?;
         if count == EncodedTerm::from(0) {
@@ -3006,13 +3963,31 @@ impl<I: StrId> Accumulator<I> for AvgAccumulator<I> {
         } else {
             //TODO: deduplicate?
             //TODO: duration?
-            match NumericBinaryOperands::new(sum, count)? {
+            let eval = &self.sum.eval;
+            match eval.numeric_operands(sum, count)? {
                 NumericBinaryOperands::Float(v1, v2) => Some((v1 / v2).into()),
                 NumericBinaryOperands::Double(v1, v2) => Some((v1 / v2).into()),
-                NumericBinaryOperands::Integer(v1, v2) => {
-                    Decimal::from(v1).checked_div(v2).map(|v| v.into())
+                NumericBinaryOperands::Integer(v1, v2) => Decimal::from(v1)
+                    .checked_div(v2)
+                    .map(Into::into)
+                    .or_else(|| {
+                        eval.build_decimal_literal(
+                            &BigDecimal::from(v1).checked_div(&BigDecimal::from(v2))?,
+                        )
+                    }),
+                NumericBinaryOperands::Decimal(v1, v2) => {
+                    v1.checked_div(v2).map(Into::into).or_else(|| {
+                        eval.build_decimal_literal(
+                            &BigDecimal::from(v1).checked_div(&BigDecimal::from(v2))?,
+                        )
+                    })
+                }
+                NumericBinaryOperands::BigInteger(v1, v2) => eval.build_decimal_literal(
+                    &BigDecimal::from(v1).checked_div(&BigDecimal::from(v2))?,
+                ),
+                NumericBinaryOperands::BigDecimal(v1, v2) => {
+                    eval.build_decimal_literal(&v1.checked_div(&v2)?)
                 }
-                NumericBinaryOperands::Decimal(v1, v2) => v1.checked_div(v2).map(|v| v.into()),
                 _ => None,
             }
         }
@@ -3037,8 +4012,8 @@ where
     for<'a> &'a S: StrContainer<StrId = S::StrId>,
 {
     fn add(&mut self, element: Option<EncodedTerm<S::StrId>>) {
-        if let Some(min) = self.min {
-            if self.eval.cmp_terms(element, min) == Ordering::Less {
+        if let Some(min) = &self.min {
+            if self.eval.cmp_terms(element.clone(), min.clone()) == Ordering::Less {
                 self.min = Some(element)
             }
         } else {
@@ -3047,7 +4022,7 @@ where
     }
 
     fn state(&self) -> Option<EncodedTerm<S::StrId>> {
-        self.min.and_then(|v| v)
+        self.min.clone().and_then(|v| v)
     }
 }
 
@@ -3069,8 +4044,8 @@ where
     for<'a> &'a S: StrContainer<StrId = S::StrId>,
 {
     fn add(&mut self, element: Option<EncodedTerm<S::StrId>>) {
-        if let Some(max) = self.max {
-            if self.eval.cmp_terms(element, max) == Ordering::Greater {
+        if let Some(max) = &self.max {
+            if self.eval.cmp_terms(element.clone(), max.clone()) == Ordering::Greater {
                 self.max = Some(element)
             }
         } else {
@@ -3079,7 +4054,7 @@ where
     }
 
     fn state(&self) -> Option<EncodedTerm<S::StrId>> {
-        self.max.and_then(|v| v)
+        self.max.clone().and_then(|v| v)
     }
 }
 
@@ -3102,7 +4077,7 @@ impl<I: StrId> Accumulator<I> for SampleAccumulator<I> {
     }
 
     fn state(&self) -> Option<EncodedTerm<I>> {
-        self.value
+        self.value.clone()
     }
 }
 
@@ -3133,7 +4108,7 @@ where
     fn add(&mut self, element: Option<EncodedTerm<S::StrId>>) {
         if let Some(concat) = self.concat.as_mut() {
             if let Some(element) = element {
-                if let Some((value, e_language)) = self.eval.to_string_and_language(element) {
+                if let Some((value, e_language)) = self.eval.to_string_and_language(&element) {
                     if let Some(lang) = self.language {
                         if lang != e_language {
                             self.language = Some(None)
@@ -3156,6 +4131,36 @@ where
     }
 }
 
+struct CustomAccumulator<S: ReadableEncodedStore + 'static> {
+    eval: SimpleEvaluator<S>,
+    inner: Box<dyn AggregateAccumulator>,
+}
+
+impl<S: ReadableEncodedStore + 'static> CustomAccumulator<S> {
+    fn new(eval: SimpleEvaluator<S>, inner: Box<dyn AggregateAccumulator>) -> Self {
+        Self { eval, inner }
+    }
+}
+
+impl<S: ReadableEncodedStore<Error = EvaluationError> + 'static> Accumulator<S::StrId>
+    for CustomAccumulator<S>
+where
+    for<'a> &'a S: StrContainer<StrId = S::StrId>,
+{
+    fn add(&mut self, element: Option<EncodedTerm<S::StrId>>) {
+        self.inner
+            .add(element.and_then(|element| self.eval.dataset.decode_term(element).ok()))
+    }
+
+    fn state(&self) -> Option<EncodedTerm<S::StrId>> {
+        self.eval
+            .dataset
+            .as_ref()
+            .encode_term(self.inner.state()?.as_ref())
+            .ok()
+    }
+}
+
 fn generate_uuid(buffer: &mut String) {
     let mut uuid = random::<u128>().to_ne_bytes();
     uuid[6] = (uuid[6] & 0x0F) | 0x40;
@@ -3219,3 +4224,809 @@ fn uuid() {
         buffer
     );
 }
+
+#[test]
+fn big_numerics() -> Result<(), EvaluationError> {
+    use crate::model::{Literal, NamedNode, Quad, Term};
+    use crate::store::MemoryStore;
+
+    let store = MemoryStore::new();
+    let ex = NamedNode::new_unchecked("http://example.com");
+    for value in &["9223372036854775807", "123456789012345678901234567890"] {
+        store.insert(Quad::new(
+            ex.clone(),
+            ex.clone(),
+            Literal::new_typed_literal(*value, xsd::INTEGER),
+            None,
+        ));
+    }
+    let evaluate = |expression: &str| -> Result<Vec<Term>, EvaluationError> {
+        if let QueryResults::Solutions(solutions) = store.query(
+            format!(
+                "SELECT ?r WHERE {{ ?s ?p ?o BIND({} AS ?r) }} ORDER BY ?o",
+                expression
+            )
+            .as_str(),
+        )? {
+            solutions
+                .map(|s| Ok(s?.get("r").cloned().unwrap()))
+                .collect()
+        } else {
+            unreachable!()
+        }
+    };
+    let integer = |value: &str| Term::from(Literal::new_typed_literal(value, xsd::INTEGER));
+    let decimal = |value: &str| Term::from(Literal::new_typed_literal(value, xsd::DECIMAL));
+    let boolean = |value: bool| Term::from(Literal::from(value));
+
+    assert_eq!(
+        evaluate("?o + 1")?,
+        vec![
+            integer("9223372036854775808"),
+            integer("123456789012345678901234567891")
+        ]
+    );
+    assert_eq!(
+        evaluate("?o - 123456789012345678901234567890")?,
+        vec![integer("-123456789003122306864379792083"), integer("0")]
+    );
+    assert_eq!(
+        evaluate("?o * -?o")?,
+        vec![
+            integer("-85070591730234615847396907784232501249"),
+            integer("-15241578753238836750495351562536198787501905199875019052100")
+        ]
+    );
+    assert_eq!(
+        evaluate("?o / 1000000000000000000000000000000")?,
+        vec![
+            decimal("0.000000000009223372036854775807"),
+            decimal("0.12345678901234567890123456789")
+        ]
+    );
+    assert_eq!(
+        evaluate("?o + 0.0000000000000000000001")?,
+        vec![
+            decimal("9223372036854775807.0000000000000000000001"),
+            decimal("123456789012345678901234567890.0000000000000000000001")
+        ]
+    );
+    assert_eq!(
+        evaluate("?o > 9223372036854775807")?,
+        vec![boolean(false), boolean(true)]
+    );
+    assert_eq!(
+        evaluate("?o = 123456789012345678901234567890.0")?,
+        vec![boolean(false), boolean(true)]
+    );
+    assert_eq!(
+        evaluate("<http://www.w3.org/2001/XMLSchema#integer>(STR(?o * 10)) / 10 = ?o")?,
+        vec![boolean(true), boolean(true)]
+    );
+    assert_eq!(
+        evaluate("DATATYPE(ABS(-?o))")?,
+        vec![Term::from(xsd::INTEGER.into_owned()); 2]
+    );
+    let error = Term::from(Literal::from("error"));
+    for (value, datatype, expected) in &[
+        ("1.5E20", xsd::DOUBLE, decimal("150000000000000000000")),
+        (
+            "1E40",
+            xsd::DOUBLE,
+            decimal("10000000000000000000000000000000000000000"),
+        ),
+        ("-1E21", xsd::DOUBLE, decimal("-1000000000000000000000")),
+        (
+            "1E30",
+            xsd::FLOAT,
+            decimal("1000000000000000000000000000000"),
+        ),
+        ("0.1", xsd::FLOAT, decimal("0.1")),
+        ("NaN", xsd::DOUBLE, error.clone()),
+        ("INF", xsd::DOUBLE, error.clone()),
+        ("-INF", xsd::FLOAT, error.clone()),
+    ] {
+        assert_eq!(
+            evaluate(&format!(
+                "COALESCE(<{}>(\"{}\"^^<{}>), \"error\")",
+                xsd::DECIMAL.as_str(),
+                value,
+                datatype.as_str()
+            ))?,
+            vec![expected.clone(); 2]
+        );
+    }
+
+    if let QueryResults::Solutions(mut solutions) =
+        store.query("SELECT (SUM(?o) AS ?s) (AVG(?o) AS ?a) WHERE { ?s ?p ?o }")?
+    {
+        let solution = solutions.next().unwrap()?;
+        assert_eq!(
+            solution.get("s"),
+            Some(&integer("123456789021569050938089343697"))
+        );
+        assert_eq!(
+            solution.get("a"),
+            Some(&decimal("61728394510784525469044671848.5"))
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn quoted_triples() -> Result<(), EvaluationError> {
+    use crate::model::{Literal, NamedNode, Quad, Term, Triple};
+    use crate::store::MemoryStore;
+
+    let store = MemoryStore::new();
+    let s = NamedNode::new_unchecked("http://example.com/s");
+    let p = NamedNode::new_unchecked("http://example.com/p");
+    let o = NamedNode::new_unchecked("http://example.com/o");
+    let a = NamedNode::new_unchecked("http://example.com/a");
+    let quoted = Triple::new(s.clone(), p.clone(), o.clone());
+    store.insert(Quad::new(quoted.clone(), p.clone(), Literal::from(1), None));
+    store.insert(Quad::new(a.clone(), p.clone(), quoted.clone(), None));
+    let evaluate = |query: &str| -> Result<Vec<Option<Term>>, EvaluationError> {
+        if let QueryResults::Solutions(solutions) = store.query(
+            format!(
+                "PREFIX ex: <http://example.com/> SELECT ?r WHERE {{ {} }}",
+                query
+            )
+            .as_str(),
+        )? {
+            solutions.map(|s| Ok(s?.get("r").cloned())).collect()
+        } else {
+            unreachable!()
+        }
+    };
+
+    assert_eq!(
+        evaluate("<< ?r ex:p ex:o >> ?q ?v")?,
+        vec![Some(s.clone().into())]
+    );
+    assert_eq!(
+        evaluate("<< ex:s ?r ex:o >> ?q 1")?,
+        vec![Some(p.clone().into())]
+    );
+    assert_eq!(evaluate("<< ex:s ex:p ex:a >> ?q ?r")?, vec![]);
+    assert_eq!(
+        evaluate("ex:a ?q << ex:s ex:p ?r >>")?,
+        vec![Some(o.clone().into())]
+    );
+    assert_eq!(
+        evaluate("BIND(TRIPLE(ex:s, ex:p, ex:o) AS ?t) ?t ?q ?r")?,
+        vec![Some(Literal::from(1).into())]
+    );
+    assert_eq!(
+        evaluate("BIND(<< ex:s ex:p ex:o >> AS ?t) ?r ?q ?t")?,
+        vec![Some(a.clone().into())]
+    );
+    assert_eq!(
+        evaluate("BIND(TRIPLE(ex:s, ex:p, ex:o) AS ?r)")?,
+        vec![Some(quoted.clone().into())]
+    );
+    assert_eq!(evaluate("BIND(TRIPLE(1, ex:p, ex:o) AS ?r)")?, vec![None]);
+    assert_eq!(
+        evaluate("BIND(TRIPLE(ex:s, \"p\", ex:o) AS ?r)")?,
+        vec![None]
+    );
+    assert_eq!(
+        evaluate("?t ?q 1 BIND(OBJECT(?t) AS ?r)")?,
+        vec![Some(o.into())]
+    );
+    assert_eq!(
+        evaluate("ex:a ?q ?t BIND(SUBJECT(?t) AS ?r)")?,
+        vec![Some(s.into())]
+    );
+    assert_eq!(
+        evaluate("ex:a ?q ?t BIND(isTRIPLE(?t) AS ?r)")?,
+        vec![Some(Literal::from(true).into())]
+    );
+    assert_eq!(evaluate("BIND(PREDICATE(ex:a) AS ?r)")?, vec![None]);
+    Ok(())
+}
+
+#[test]
+fn derived_integers() -> Result<(), EvaluationError> {
+    use crate::model::{Literal, NamedNode, Quad, Term};
+    use crate::store::MemoryStore;
+
+    let store = MemoryStore::new();
+    let ex = NamedNode::new_unchecked("http://example.com");
+    for (value, datatype) in &[
+        ("42", xsd::INT),
+        ("18446744073709551615", xsd::UNSIGNED_LONG),
+        ("300", xsd::BYTE),
+    ] {
+        store.insert(Quad::new(
+            ex.clone(),
+            ex.clone(),
+            Literal::new_typed_literal(*value, *datatype),
+            None,
+        ));
+    }
+    let evaluate = |query: &str| -> Result<Vec<Option<Term>>, EvaluationError> {
+        if let QueryResults::Solutions(solutions) = store.query(query)? {
+            solutions.map(|s| Ok(s?.get("r").cloned())).collect()
+        } else {
+            unreachable!()
+        }
+    };
+    let expression = |expression: &str| {
+        evaluate(&format!(
+            "PREFIX xsd: <http://www.w3.org/2001/XMLSchema#> SELECT ?r WHERE {{ BIND({} AS ?r) }}",
+            expression
+        ))
+    };
+    let typed =
+        |value: &str, datatype| Some(Term::from(Literal::new_typed_literal(value, datatype)));
+
+    assert_eq!(
+        evaluate("SELECT ?r WHERE { ?s ?p ?r FILTER(?r > 18) } ORDER BY ?r")?,
+        vec![
+            typed("42", xsd::INT),
+            typed("18446744073709551615", xsd::UNSIGNED_LONG)
+        ]
+    );
+    assert_eq!(
+        evaluate("SELECT ?r WHERE { ?s ?p ?o FILTER(?o = 42) BIND(DATATYPE(?o) AS ?r) }")?,
+        vec![Some(xsd::INT.into_owned().into())]
+    );
+    assert_eq!(
+        expression("DATATYPE(\"1\"^^xsd:short + \"1\"^^xsd:byte)")?,
+        vec![Some(xsd::INTEGER.into_owned().into())]
+    );
+    assert_eq!(
+        expression("\"18446744073709551615\"^^xsd:unsignedLong + 1")?,
+        vec![typed("18446744073709551616", xsd::INTEGER)]
+    );
+    assert_eq!(
+        expression("\"-5\"^^xsd:negativeInteger < \"0\"^^xsd:nonNegativeInteger")?,
+        vec![Some(Literal::from(true).into())]
+    );
+    assert_eq!(
+        expression("xsd:byte(\"+012\")")?,
+        vec![typed("12", xsd::BYTE)]
+    );
+    assert_eq!(expression("xsd:int(-3.7)")?, vec![typed("-3", xsd::INT)]);
+    assert_eq!(
+        expression("xsd:unsignedLong(\"18446744073709551615\")")?,
+        vec![typed("18446744073709551615", xsd::UNSIGNED_LONG)]
+    );
+    assert_eq!(expression("xsd:unsignedShort(-1)")?, vec![None]);
+    assert_eq!(expression("xsd:positiveInteger(0)")?, vec![None]);
+    assert_eq!(expression("xsd:long(\"1.5\")")?, vec![None]);
+    assert_eq!(
+        expression("xsd:integer(\"7\"^^xsd:unsignedByte)")?,
+        vec![typed("7", xsd::INTEGER)]
+    );
+    assert_eq!(
+        expression("STR(\"007\"^^xsd:short)")?,
+        vec![Some(Literal::new_simple_literal("7").into())]
+    );
+    assert_eq!(
+        expression("isNumeric(\"300\"^^xsd:byte)")?,
+        vec![Some(Literal::from(false).into())]
+    );
+    Ok(())
+}
+
+#[test]
+fn binaries_and_any_uris() -> Result<(), EvaluationError> {
+    use crate::model::{Literal, NamedNode, Quad, Term};
+    use crate::store::MemoryStore;
+
+    let store = MemoryStore::new();
+    let ex = NamedNode::new_unchecked("http://example.com");
+    for (value, datatype) in &[
+        (" 0fb7", xsd::HEX_BINARY),
+        ("D7c=", xsd::BASE_64_BINARY),
+        ("http://example.com/a", xsd::ANY_URI),
+    ] {
+        store.insert(Quad::new(
+            ex.clone(),
+            ex.clone(),
+            Literal::new_typed_literal(*value, *datatype),
+            None,
+        ));
+    }
+    let evaluate = |query: &str| -> Result<Vec<Option<Term>>, EvaluationError> {
+        if let QueryResults::Solutions(solutions) = store.query(query)? {
+            solutions.map(|s| Ok(s?.get("r").cloned())).collect()
+        } else {
+            unreachable!()
+        }
+    };
+    let expression = |expression: &str| {
+        evaluate(&format!(
+            "PREFIX xsd: <http://www.w3.org/2001/XMLSchema#> SELECT ?r WHERE {{ BIND({} AS ?r) }}",
+            expression
+        ))
+    };
+    let typed =
+        |value: &str, datatype| Some(Term::from(Literal::new_typed_literal(value, datatype)));
+    let boolean = |value: bool| Some(Term::from(Literal::from(value)));
+
+    assert_eq!(
+        evaluate("PREFIX xsd: <http://www.w3.org/2001/XMLSchema#> SELECT ?r WHERE { ?s ?p ?r FILTER(?r = \"0FB7\"^^xsd:hexBinary) }")?,
+        vec![typed("0FB7", xsd::HEX_BINARY)]
+    );
+    assert_eq!(
+        evaluate("SELECT ?r WHERE { ?s ?p ?r FILTER(?r = \"http://example.com/a\") }")?,
+        vec![typed("http://example.com/a", xsd::ANY_URI)]
+    );
+    assert_eq!(
+        expression("\"0fb7\"^^xsd:hexBinary = \"0FB7\"^^xsd:hexBinary")?,
+        vec![boolean(true)]
+    );
+    assert_eq!(
+        expression("\"D7c=\"^^xsd:base64Binary = \"0FB7\"^^xsd:hexBinary")?,
+        vec![boolean(false)]
+    );
+    assert_eq!(
+        expression("\"http://example.com/b\"^^xsd:anyURI > \"http://example.com/a\"")?,
+        vec![boolean(true)]
+    );
+    assert_eq!(
+        expression("xsd:hexBinary(\"D7c=\"^^xsd:base64Binary)")?,
+        vec![typed("0FB7", xsd::HEX_BINARY)]
+    );
+    assert_eq!(
+        expression("xsd:base64Binary(\"0fb7\"^^xsd:hexBinary)")?,
+        vec![typed("D7c=", xsd::BASE_64_BINARY)]
+    );
+    assert_eq!(
+        expression("xsd:base64Binary(\"SGVs bG8=\")")?,
+        vec![typed("SGVsbG8=", xsd::BASE_64_BINARY)]
+    );
+    assert_eq!(expression("xsd:hexBinary(\"0g\")")?, vec![None]);
+    assert_eq!(
+        expression("xsd:anyURI(\" http://example.com/thisisaverylargeanyurivalue \")")?,
+        vec![typed(
+            "http://example.com/thisisaverylargeanyurivalue",
+            xsd::ANY_URI
+        )]
+    );
+    assert_eq!(expression("xsd:anyURI(\"a b\")")?, vec![None]);
+    assert_eq!(
+        expression("STR(\"0fb7\"^^xsd:hexBinary)")?,
+        vec![Some(Literal::new_simple_literal("0FB7").into())]
+    );
+    assert_eq!(
+        expression("DATATYPE(\"http://example.com\"^^xsd:anyURI)")?,
+        vec![Some(xsd::ANY_URI.into_owned().into())]
+    );
+    Ok(())
+}
+
+#[test]
+fn implicit_timezone() -> Result<(), EvaluationError> {
+    use crate::model::{Literal, Term};
+    use crate::sparql::QueryOptions;
+    use crate::store::MemoryStore;
+    use std::str::FromStr;
+
+    let store = MemoryStore::new();
+    let expression = |expression: &str, options: QueryOptions| {
+        if let QueryResults::Solutions(solutions) = store.query_opt(
+            format!(
+                "PREFIX xsd: <http://www.w3.org/2001/XMLSchema#> PREFIX fn: <http://www.w3.org/2005/xpath-functions#> SELECT ?r WHERE {{ BIND({} AS ?r) }}",
+                expression
+            ).as_str(),
+            options,
+        )? {
+            solutions
+                .map(|s| Ok(s?.get("r").cloned()))
+                .collect::<Result<Vec<_>, EvaluationError>>()
+        } else {
+            unreachable!()
+        }
+    };
+    let typed =
+        |value: &str, datatype| Some(Term::from(Literal::new_typed_literal(value, datatype)));
+    let boolean = |value: bool| Some(Term::from(Literal::from(value)));
+    let plus_two = || {
+        QueryOptions::default().with_implicit_timezone(
+            TimezoneOffset::try_from(DayTimeDuration::from_str("PT2H").unwrap()).unwrap(),
+        )
+    };
+
+    assert_eq!(
+        expression(
+            "\"2020-01-01T12:00:00\"^^xsd:dateTime = \"2020-01-01T10:00:00Z\"^^xsd:dateTime",
+            QueryOptions::default()
+        )?,
+        vec![boolean(false)]
+    );
+    assert_eq!(
+        expression(
+            "\"2020-01-01T12:00:00\"^^xsd:dateTime = \"2020-01-01T10:00:00Z\"^^xsd:dateTime",
+            plus_two()
+        )?,
+        vec![boolean(true)]
+    );
+    assert_eq!(
+        expression(
+            "\"2020-01-01\"^^xsd:date < \"2020-01-01-01:00\"^^xsd:date",
+            plus_two()
+        )?,
+        vec![boolean(true)]
+    );
+    assert_eq!(
+        expression(
+            "\"12:00:00\"^^xsd:time - \"10:00:00Z\"^^xsd:time",
+            plus_two()
+        )?,
+        vec![typed("PT0S", xsd::DURATION)]
+    );
+    assert_eq!(
+        expression("TZ(NOW())", plus_two())?,
+        vec![typed("+02:00", xsd::STRING)]
+    );
+    assert_eq!(
+        expression(
+            "fn:adjust-dateTime-to-timezone(\"2002-03-07T10:00:00-05:00\"^^xsd:dateTime)",
+            QueryOptions::default()
+        )?,
+        vec![typed("2002-03-07T15:00:00Z", xsd::DATE_TIME)]
+    );
+    assert_eq!(
+        expression(
+            "fn:adjust-dateTime-to-timezone(\"2002-03-07T10:00:00\"^^xsd:dateTime)",
+            plus_two()
+        )?,
+        vec![typed("2002-03-07T10:00:00+02:00", xsd::DATE_TIME)]
+    );
+    assert_eq!(
+        expression(
+            "fn:adjust-date-to-timezone(\"2002-03-07-07:00\"^^xsd:date, \"-PT10H\"^^xsd:dayTimeDuration)",
+            QueryOptions::default()
+        )?,
+        vec![typed("2002-03-06-10:00", xsd::DATE)]
+    );
+    assert_eq!(
+        expression(
+            "fn:adjust-time-to-timezone(\"10:00:00-07:00\"^^xsd:time, \"PT10H\"^^xsd:dayTimeDuration)",
+            QueryOptions::default()
+        )?,
+        vec![typed("03:00:00+10:00", xsd::TIME)]
+    );
+    assert_eq!(
+        expression(
+            "fn:adjust-time-to-timezone(\"10:00:00\"^^xsd:time, \"PT15H\"^^xsd:dayTimeDuration)",
+            QueryOptions::default()
+        )?,
+        vec![None]
+    );
+    assert_eq!(
+        expression(
+            "fn:adjust-date-to-timezone(\"10:00:00\"^^xsd:time)",
+            QueryOptions::default()
+        )?,
+        vec![None]
+    );
+    Ok(())
+}
+
+#[test]
+fn custom_functions() -> Result<(), EvaluationError> {
+    use crate::model::{Literal, NamedNode, Quad, Term};
+    use crate::sparql::QueryOptions;
+    use crate::store::MemoryStore;
+
+    let upper = NamedNode::new_unchecked("http://example.com/upper");
+    let options = || {
+        QueryOptions::default()
+            .with_custom_function(upper.clone(), |args| {
+                if let [Term::Literal(value)] = args {
+                    Some(Literal::from(value.value().to_uppercase()).into())
+                } else {
+                    None
+                }
+            })
+            .with_custom_function(xsd::INTEGER.into_owned(), |_| Some(Literal::from(0).into()))
+    };
+    let store = MemoryStore::new();
+    let ex = NamedNode::new_unchecked("http://example.com");
+    store.insert(Quad::new(
+        ex.clone(),
+        ex.clone(),
+        Literal::from("foo"),
+        None,
+    ));
+    let evaluate = |query: &str| -> Result<Vec<Option<Term>>, EvaluationError> {
+        if let QueryResults::Solutions(solutions) = store.query_opt(query, options())? {
+            solutions.map(|s| Ok(s?.get("r").cloned())).collect()
+        } else {
+            unreachable!()
+        }
+    };
+
+    assert_eq!(
+        evaluate("SELECT (<http://example.com/upper>(?o) AS ?r) WHERE { ?s ?p ?o }")?,
+        vec![Some(Literal::from("FOO").into())]
+    );
+    assert_eq!(
+        evaluate("SELECT (<http://example.com/upper>(?o, ?o) AS ?r) WHERE { ?s ?p ?o }")?,
+        vec![None]
+    );
+    assert_eq!(
+        evaluate("SELECT (<http://www.w3.org/2001/XMLSchema#integer>(\"1\") AS ?r) WHERE {}")?,
+        vec![Some(Literal::from(0).into())]
+    );
+    assert!(store
+        .query("SELECT (<http://example.com/upper>(\"a\") AS ?r) WHERE {}")
+        .is_err());
+
+    store.update_opt(
+        "INSERT { ?s ?p ?u } WHERE { ?s ?p ?o BIND(<http://example.com/upper>(?o) AS ?u) }",
+        options().into(),
+    )?;
+    assert!(store.contains(&Quad::new(ex.clone(), ex, Literal::from("FOO"), None)));
+    Ok(())
+}
+
+#[test]
+fn custom_aggregate_functions() -> Result<(), EvaluationError> {
+    use crate::model::{Literal, NamedNode, Quad, Term};
+    use crate::sparql::{AggregateAccumulator, QueryOptions, UpdateOptions};
+    use crate::store::MemoryStore;
+
+    #[derive(Default)]
+    struct SortedConcatAccumulator {
+        values: Vec<String>,
+    }
+
+    impl AggregateAccumulator for SortedConcatAccumulator {
+        fn add(&mut self, element: Option<Term>) {
+            if let Some(Term::Literal(element)) = element {
+                self.values.push(element.value().to_owned());
+            }
+        }
+
+        fn state(&self) -> Option<Term> {
+            let mut values = self.values.clone();
+            values.sort();
+            Some(Literal::from(values.join(",")).into())
+        }
+    }
+
+    let options = || {
+        QueryOptions::default().with_custom_aggregate_function(
+            NamedNode::new_unchecked("http://example.com/sorted"),
+            || Box::new(SortedConcatAccumulator::default()),
+        )
+    };
+    let store = MemoryStore::new();
+    for (s, o) in &[("a", "c"), ("a", "b"), ("c", "c"), ("b", "d")] {
+        store.insert(Quad::new(
+            NamedNode::new_unchecked(format!("http://example.com/{}", s)),
+            NamedNode::new_unchecked("http://example.com/p"),
+            Literal::from(*o),
+            NamedNode::new_unchecked(format!("http://example.com/g{}", o)),
+        ));
+    }
+    let evaluate = |query: &str| -> Result<Vec<Option<Term>>, EvaluationError> {
+        let options = options();
+        let parsed = options.parse_query(query, None)?;
+        let mut results = Vec::new();
+        // The queries given as strings also call the custom aggregate functions
+        for results_set in [
+            store.query_opt(parsed, options.clone())?,
+            store.query_opt(query, options)?,
+        ] {
+            if let QueryResults::Solutions(solutions) = results_set {
+                results.push(
+                    solutions
+                        .map(|s| Ok(s?.get("r").cloned()))
+                        .collect::<Result<Vec<_>, EvaluationError>>()?,
+                );
+            } else {
+                unreachable!()
+            }
+        }
+        assert_eq!(results[0], results[1]);
+        Ok(results.pop().unwrap())
+    };
+    let string = |value: &str| Some(Literal::from(value).into());
+
+    assert_eq!(
+        evaluate("SELECT (<http://example.com/sorted>(?o) AS ?r) WHERE { GRAPH ?g { ?s ?p ?o } }")?,
+        vec![string("b,c,c,d")]
+    );
+    assert_eq!(
+        evaluate("SELECT (<http://example.com/sorted>(DISTINCT ?o) AS ?r) WHERE { GRAPH ?g { ?s ?p ?o } }")?,
+        vec![string("b,c,d")]
+    );
+    assert_eq!(
+        evaluate("SELECT (<http://example.com/sorted>(?o) AS ?r) WHERE { GRAPH ?g { ?s ?p ?o } } GROUP BY ?s HAVING(COUNT(*) > 1)")?,
+        vec![string("b,c")]
+    );
+    assert_eq!(
+        evaluate("SELECT (<http://example.com/sorted>(?o) AS ?r) WHERE { GRAPH ?g { ?s ?p ?o } BIND(?s AS ?x) }")?,
+        vec![string("b,c,c,d")]
+    );
+    assert_eq!(
+        evaluate("SELECT (?s AS ?r) WHERE { GRAPH ?g { ?s ?p ?o } } GROUP BY ?s HAVING(<http://example.com/sorted>(?o) = \"b,c\")")?,
+        vec![Some(NamedNode::new_unchecked("http://example.com/a").into())]
+    );
+    assert!(store
+        .query("SELECT (<http://example.com/sorted>(DISTINCT ?o) AS ?r) WHERE { ?s ?p ?o }")
+        .is_err());
+    assert!(store
+        .query_opt(
+            "SELECT ?s WHERE { ?s ?p ?o FILTER(<http://example.com/sorted>(?o)) }",
+            options()
+        )
+        .is_err());
+
+    let options = UpdateOptions::from(options());
+    store.update_opt(
+        options.parse_update(
+            "INSERT { <http://example.com/s> <http://example.com/p> ?r } WHERE { { SELECT (<http://example.com/sorted>(DISTINCT ?o) AS ?r) WHERE { GRAPH ?g { ?s ?p ?o } } } }",
+            None,
+        )?,
+        options.clone(),
+    )?;
+    assert!(store.contains(&Quad::new(
+        NamedNode::new_unchecked("http://example.com/s"),
+        NamedNode::new_unchecked("http://example.com/p"),
+        Literal::from("b,c,d"),
+        None
+    )));
+    store.update_opt(
+        "INSERT { <http://example.com/s2> <http://example.com/p> ?r } WHERE { { SELECT (<http://example.com/sorted>(?o) AS ?r) WHERE { GRAPH ?g { ?s ?p ?o } } } }",
+        options,
+    )?;
+    assert!(store.contains(&Quad::new(
+        NamedNode::new_unchecked("http://example.com/s2"),
+        NamedNode::new_unchecked("http://example.com/p"),
+        Literal::from("b,c,c,d"),
+        None
+    )));
+    Ok(())
+}
+
+#[test]
+fn timeout_and_cancellation() -> Result<(), EvaluationError> {
+    use crate::model::{NamedNode, Quad};
+    use crate::sparql::{CancellationToken, QueryOptions};
+    use crate::store::MemoryStore;
+    use std::time::Duration;
+
+    let store = MemoryStore::new();
+    let p = NamedNode::new_unchecked("http://example.com/p");
+    for i in 0..100 {
+        store.insert(Quad::new(
+            NamedNode::new_unchecked(format!("http://example.com/{}", i)),
+            p.clone(),
+            NamedNode::new_unchecked(format!("http://example.com/{}", i + 1)),
+            None,
+        ));
+    }
+    let count = |options: QueryOptions| -> Result<usize, EvaluationError> {
+        if let QueryResults::Solutions(solutions) =
+            store.query_opt("SELECT * WHERE { ?s <http://example.com/p>+ ?o }", options)?
+        {
+            solutions.map(|s| s.map(|_| 1)).sum()
+        } else {
+            unreachable!()
+        }
+    };
+
+    assert_eq!(
+        count(QueryOptions::default().with_timeout(Duration::from_secs(3600)))?,
+        5050
+    );
+    assert!(matches!(
+        count(QueryOptions::default().with_timeout(Duration::from_secs(0))),
+        Err(EvaluationError::Timeout)
+    ));
+    assert!(matches!(
+        store.explain_opt(
+            "SELECT * WHERE { ?s <http://example.com/p> ?o }",
+            QueryOptions::default().with_timeout(Duration::from_secs(0))
+        ),
+        Err(EvaluationError::Timeout)
+    ));
+
+    let token = CancellationToken::new();
+    let options = QueryOptions::default().with_cancellation_token(token.clone());
+    assert_eq!(count(options.clone())?, 5050);
+    token.cancel();
+    assert!(matches!(
+        count(options.clone()),
+        Err(EvaluationError::Cancelled)
+    ));
+    assert!(matches!(
+        store.explain_opt(
+            "SELECT * WHERE { ?s <http://example.com/p> ?o }",
+            options.clone()
+        ),
+        Err(EvaluationError::Cancelled)
+    ));
+    assert!(matches!(
+        store.update_opt(
+            "INSERT { ?s <http://example.com/q> ?o } WHERE { ?s <http://example.com/p> ?o }",
+            options.into()
+        ),
+        Err(EvaluationError::Cancelled)
+    ));
+    assert_eq!(store.len(), 100);
+    Ok(())
+}
+
+#[test]
+fn resource_limits() -> Result<(), EvaluationError> {
+    use crate::model::{NamedNode, Quad};
+    use crate::sparql::{QueryOptions, ResourceLimit};
+    use crate::store::MemoryStore;
+
+    let store = MemoryStore::new();
+    let p = NamedNode::new_unchecked("http://example.com/p");
+    for i in 0..10 {
+        store.insert(Quad::new(
+            NamedNode::new_unchecked(format!("http://example.com/{}", i)),
+            p.clone(),
+            NamedNode::new_unchecked(format!("http://example.com/{}", i + 1)),
+            None,
+        ));
+    }
+    let count = |query: &str, options: QueryOptions| -> Result<usize, EvaluationError> {
+        match store.query_opt(query, options)? {
+            QueryResults::Solutions(solutions) => solutions.map(|s| s.map(|_| 1)).sum(),
+            QueryResults::Graph(triples) => triples.map(|t| t.map(|_| 1)).sum(),
+            QueryResults::Boolean(_) => unreachable!(),
+        }
+    };
+    let exceeded = |result: Result<usize, EvaluationError>, limit: ResourceLimit| match result {
+        Err(EvaluationError::ResourceLimitExceeded(l)) => assert_eq!(l, limit),
+        r => panic!("Unexpected result: {:?}", r),
+    };
+
+    let buffered = || QueryOptions::default().with_max_buffered_tuples(5);
+    for query in &[
+        "SELECT * WHERE { ?s ?p ?o } ORDER BY ?s",
+        "SELECT DISTINCT ?s WHERE { ?s ?p ?o }",
+        "SELECT ?s (COUNT(*) AS ?c) WHERE { ?s ?p ?o } GROUP BY ?s",
+        "SELECT * WHERE { <http://example.com/0> <http://example.com/p>* ?o }",
+        "SELECT * WHERE { ?s <http://example.com/p>+ ?o }",
+    ] {
+        exceeded(count(query, buffered()), ResourceLimit::BufferedTuples(5));
+        assert!(count(query, QueryOptions::default().with_max_buffered_tuples(100)).is_ok());
+    }
+    assert_eq!(
+        count(
+            "SELECT ?o WHERE { <http://example.com/0> <http://example.com/p>* ?o } ORDER BY ?o LIMIT 3",
+            QueryOptions::default().with_max_buffered_tuples(11)
+        )?,
+        3
+    );
+
+    exceeded(
+        count(
+            "SELECT * WHERE { ?s ?p ?o . ?o ?p ?o2 }",
+            QueryOptions::default().with_max_intermediate_results(10),
+        ),
+        ResourceLimit::IntermediateResults(10),
+    );
+    assert_eq!(
+        count(
+            "SELECT * WHERE { ?s ?p ?o . ?o ?p ?o2 }",
+            QueryOptions::default().with_max_intermediate_results(1000),
+        )?,
+        9
+    );
+
+    let rows = || QueryOptions::default().with_max_result_rows(9);
+    exceeded(
+        count("SELECT * WHERE { ?s ?p ?o }", rows()),
+        ResourceLimit::ResultRows(9),
+    );
+    exceeded(
+        count("CONSTRUCT WHERE { ?s ?p ?o }", rows()),
+        ResourceLimit::ResultRows(9),
+    );
+    assert_eq!(count("SELECT * WHERE { ?s ?p ?o } LIMIT 9", rows())?, 9);
+    Ok(())
+}
@@ -0,0 +1,765 @@
+use crate::model::Term;
+use crate::sparql::error::EvaluationError;
+use crate::sparql::eval::Interruption;
+use crate::sparql::json_results::write_escaped_json_string;
+use crate::sparql::model::Variable;
+use crate::sparql::plan::*;
+use crate::store::numeric_encoder::{Decoder, EncodedTerm, StrId};
+use crate::store::ReadableEncodedStore;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::io;
+use std::io::Write;
+
+/// Maximal number of quads counted to estimate the cardinality of a quad pattern
+const MAX_COUNTED_QUADS: usize = 100_000;
+
+/// The plan chosen to evaluate a SPARQL query.
+///
+/// It is returned by [`MemoryStore::explain`](super::super::MemoryStore::explain()) and the `explain` methods of the other stores.
+/// It is a tree of [`PlanNodeExplanation`] whose leaves are evaluated first.
+/// The quad patterns of a basic graph pattern are nested in the order chosen by the query optimizer, the first evaluated being the deepest one.
+///
+/// It could be written as an indented text tree using its [`Display`](std::fmt::Display) implementation
+/// or as JSON using [`write_json`](QueryExplanation::write_json()).
+///
+/// ```
+/// use oxigraph::MemoryStore;
+/// use oxigraph::model::*;
+///
+/// let store = MemoryStore::new();
+/// let ex = NamedNode::new("http://example.com")?;
+/// store.insert(Quad::new(ex.clone(), ex.clone(), ex.clone(), None));
+///
+/// let explanation = store.explain("SELECT ?s WHERE { ?s <http://example.com> ?o . ?o ?p <http://example.com> }")?;
+/// assert_eq!(explanation.root().name(), "Project");
+///
+/// // The second evaluated pattern
+/// let second = &explanation.root().children()[0];
+/// assert_eq!(second.name(), "QuadPatternJoin");
+/// assert_eq!(second.attribute("pattern"), Some("?o ?p <http://example.com>"));
+/// assert_eq!(second.attribute("index"), Some("dosp"));
+///
+/// // The first evaluated pattern
+/// let first = &second.children()[0];
+/// assert_eq!(first.attribute("pattern"), Some("?s <http://example.com> ?o"));
+/// assert_eq!(first.attribute("index"), Some("dpos"));
+/// assert_eq!(first.estimated_cardinality(), Some(1));
+///
+/// println!("{}", explanation);
+/// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct QueryExplanation {
+    root: PlanNodeExplanation,
+}
+
+impl QueryExplanation {
+    /// The root operator of the plan, the one returning the query results
+    #[inline]
+    pub fn root(&self) -> &PlanNodeExplanation {
+        &self.root
+    }
+
+    /// Writes the plan as JSON.
+    ///
+    /// Each operator is serialized as an object with the `name`, `attributes`, `estimated_cardinality` and `children` keys.
+    ///
+    /// ```
+    /// use oxigraph::MemoryStore;
+    ///
+    /// let mut json = Vec::new();
+    /// MemoryStore::new().explain("SELECT * WHERE { ?s ?p ?o } LIMIT 1")?.write_json(&mut json)?;
+    /// assert!(json.starts_with(b"{\"name\":\"Limit\""));
+    /// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn write_json(&self, mut writer: impl Write) -> io::Result<()> {
+        self.root.write_json(&mut writer)
+    }
+}
+
+impl fmt::Display for QueryExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.root.fmt_indented(f, 0)
+    }
+}
+
+/// An operator of a [`QueryExplanation`].
+#[derive(Debug, Clone)]
+pub struct PlanNodeExplanation {
+    name: &'static str,
+    attributes: Vec<(&'static str, String)>,
+    estimated_cardinality: Option<usize>,
+    children: Vec<PlanNodeExplanation>,
+}
+
+impl PlanNodeExplanation {
+    /// The name of the operator like `QuadPatternJoin`, `LeftJoin` or `Sort`
+    #[inline]
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    /// The operator parameters like the quad `pattern`, the `index` used to look it up and the `join` algorithm of a `QuadPatternJoin`
+    #[inline]
+    pub fn attributes(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.attributes
+            .iter()
+            .map(|(key, value)| (*key, value.as_str()))
+    }
+
+    /// Returns the value of an [attribute](PlanNodeExplanation::attributes())
+    #[inline]
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes()
+            .find(|(k, _)| *k == key)
+            .map(|(_, value)| value)
+    }
+
+    /// A rough estimation of the number of solutions returned by the operator or `None` if it could not be estimated.
+    ///
+    /// The quad patterns cardinalities are computed from the number of quads matching their constants,
+    /// assuming a uniform distribution of the values for the variables already bound.
+    #[inline]
+    pub fn estimated_cardinality(&self) -> Option<usize> {
+        self.estimated_cardinality
+    }
+
+    /// The operators whose results are consumed by this operator
+    #[inline]
+    pub fn children(&self) -> &[PlanNodeExplanation] {
+        &self.children
+    }
+
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        write!(f, "{:indent$}{}", "", self.name, indent = 2 * depth)?;
+        let mut parameters = self
+            .attributes
+            .iter()
+            .map(|(key, value)| format!("{}: {}", key, value))
+            .collect::<Vec<_>>();
+        if let Some(cardinality) = self.estimated_cardinality {
+            parameters.push(format!("estimated cardinality: {}", cardinality));
+        }
+        if !parameters.is_empty() {
+            write!(f, " ({})", parameters.join(", "))?;
+        }
+        writeln!(f)?;
+        for child in &self.children {
+            child.fmt_indented(f, depth + 1)?;
+        }
+        Ok(())
+    }
+
+    fn write_json(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(b"{\"name\":")?;
+        write_escaped_json_string(self.name, &mut *writer)?;
+        writer.write_all(b",\"attributes\":{")?;
+        for (i, (key, value)) in self.attributes.iter().enumerate() {
+            if i > 0 {
+                writer.write_all(b",")?;
+            }
+            write_escaped_json_string(key, &mut *writer)?;
+            writer.write_all(b":")?;
+            write_escaped_json_string(value, &mut *writer)?;
+        }
+        writer.write_all(b"},\"estimated_cardinality\":")?;
+        if let Some(cardinality) = self.estimated_cardinality {
+            write!(writer, "{}", cardinality)?;
+        } else {
+            writer.write_all(b"null")?;
+        }
+        writer.write_all(b",\"children\":[")?;
+        for (i, child) in self.children.iter().enumerate() {
+            if i > 0 {
+                writer.write_all(b",")?;
+            }
+            child.write_json(writer)?;
+        }
+        writer.write_all(b"]}")
+    }
+}
+
+pub(crate) struct PlanExplainer<'a, S: ReadableEncodedStore> {
+    dataset: &'a S,
+    interruption: Interruption,
+}
+
+impl<'a, S: ReadableEncodedStore<Error = EvaluationError>> PlanExplainer<'a, S> {
+    pub fn explain(
+        dataset: &'a S,
+        plan: &PlanNode<S::StrId>,
+        variables: &[Variable],
+        interruption: Interruption,
+    ) -> Result<QueryExplanation, EvaluationError> {
+        Ok(QueryExplanation {
+            root: Self {
+                dataset,
+                interruption,
+            }
+            .explain_node(plan, variables, &BTreeSet::new())?,
+        })
+    }
+
+    /// `bound` are the variables bound by the parent operator before evaluating this one
+    fn explain_node(
+        &self,
+        node: &PlanNode<S::StrId>,
+        variables: &[Variable],
+        bound: &BTreeSet<usize>,
+    ) -> Result<PlanNodeExplanation, EvaluationError> {
+        Ok(match node {
+            PlanNode::Init => explanation("Init", Vec::new(), Some(1), Vec::new()),
+            PlanNode::StaticBindings { tuples } => {
+                explanation("StaticBindings", Vec::new(), Some(tuples.len()), Vec::new())
+            }
+            PlanNode::Service {
+                service_name,
+                graph_pattern,
+                silent,
+                ..
+            } => explanation(
+                "Service",
+                vec![
+                    ("name", self.format_pattern_value(service_name, variables)?),
+                    ("pattern", graph_pattern.to_string()),
+                    ("silent", silent.to_string()),
+                ],
+                None,
+                Vec::new(),
+            ),
+            PlanNode::QuadPatternJoin {
+                child,
+                subject,
+                predicate,
+                object,
+                graph_name,
+            } => {
+                let child_explanation = self.explain_node(child, variables, bound)?;
+                let mut bound = bound.clone();
+                child.add_maybe_bound_variables(&mut bound);
+                let bound = &bound;
+                let pattern_cardinality =
+                    self.estimate_quad_pattern(subject, predicate, object, graph_name, bound)?;
+                let child = child_explanation;
+                let cardinality = child
+                    .estimated_cardinality
+                    .map(|c| c.saturating_mul(pattern_cardinality));
+                explanation(
+                    "QuadPatternJoin",
+                    vec![
+                        (
+                            "pattern",
+                            format!(
+                                "{} {} {}",
+                                self.format_pattern_value(subject, variables)?,
+                                self.format_pattern_value(predicate, variables)?,
+                                self.format_pattern_value(object, variables)?
+                            ),
+                        ),
+                        ("graph", self.format_graph_name(graph_name, variables)?),
+                        (
+                            "index",
+                            index_name(
+                                is_bound(subject, bound),
+                                is_bound(predicate, bound),
+                                is_bound(object, bound),
+                                graph_name,
+                                bound,
+                            ),
+                        ),
+                        ("join", "index nested loop".to_owned()),
+                    ],
+                    cardinality,
+                    vec![child],
+                )
+            }
+            PlanNode::PathPatternJoin {
+                child,
+                subject,
+                path,
+                object,
+                graph_name,
+            } => explanation(
+                "PathPatternJoin",
+                vec![
+                    (
+                        "pattern",
+                        format!(
+                            "{} {} {}",
+                            self.format_pattern_value(subject, variables)?,
+                            self.format_path(path)?,
+                            self.format_pattern_value(object, variables)?
+                        ),
+                    ),
+                    ("graph", self.format_graph_name(graph_name, variables)?),
+                    ("join", "index nested loop".to_owned()),
+                ],
+                None,
+                vec![self.explain_node(child, variables, bound)?],
+            ),
+            PlanNode::Join { left, right } => {
+                let left_explanation = self.explain_node(left, variables, bound)?;
+                let right_explanation = self.explain_node(right, variables, bound)?;
+                let shares_variables = !left
+                    .maybe_bound_variables()
+                    .is_disjoint(&right.maybe_bound_variables());
+                let cardinality = match (
+                    left_explanation.estimated_cardinality,
+                    right_explanation.estimated_cardinality,
+                ) {
+                    (Some(left), Some(right)) => Some(if shares_variables {
+                        left.max(right)
+                    } else {
+                        left.saturating_mul(right)
+                    }),
+                    _ => None,
+                };
+                explanation(
+                    "Join",
+                    vec![("join", "nested loop".to_owned())],
+                    cardinality,
+                    vec![left_explanation, right_explanation],
+                )
+            }
+            PlanNode::AntiJoin { left, right } => {
+                let left = self.explain_node(left, variables, bound)?;
+                let right = self.explain_node(right, variables, bound)?;
+                explanation(
+                    "AntiJoin",
+                    vec![("join", "nested loop".to_owned())],
+                    left.estimated_cardinality,
+                    vec![left, right],
+                )
+            }
+            PlanNode::LeftJoin {
+                left,
+                right,
+                possible_problem_vars,
+            } => {
+                let mut right_bound = bound.clone();
+                left.add_maybe_bound_variables(&mut right_bound);
+                for variable in possible_problem_vars.iter() {
+                    right_bound.remove(variable);
+                }
+                let left = self.explain_node(left, variables, bound)?;
+                let right = self.explain_node(right, variables, &right_bound)?;
+                explanation(
+                    "LeftJoin",
+                    vec![("join", "index nested loop".to_owned())],
+                    left.estimated_cardinality,
+                    vec![left, right],
+                )
+            }
+            PlanNode::Filter { child, expression } => {
+                let child = self.explain_node(child, variables, bound)?;
+                explanation(
+                    "Filter",
+                    vec![(
+                        "variables",
+                        format_expression_variables(expression, variables),
+                    )],
+                    child.estimated_cardinality,
+                    vec![child],
+                )
+            }
+            PlanNode::Union { children } => {
+                let children = children
+                    .iter()
+                    .map(|child| self.explain_node(child, variables, bound))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let cardinality = children.iter().try_fold(0, |sum: usize, child| {
+                    Some(sum.saturating_add(child.estimated_cardinality?))
+                });
+                explanation("Union", Vec::new(), cardinality, children)
+            }
+            PlanNode::Extend {
+                child,
+                position,
+                expression,
+            } => {
+                let child = self.explain_node(child, variables, bound)?;
+                explanation(
+                    "Extend",
+                    vec![
+                        ("variable", variables[*position].to_string()),
+                        (
+                            "variables",
+                            format_expression_variables(expression, variables),
+                        ),
+                    ],
+                    child.estimated_cardinality,
+                    vec![child],
+                )
+            }
+            PlanNode::Sort { child, by } => {
+                let child = self.explain_node(child, variables, bound)?;
+                let by = by
+                    .iter()
+                    .map(|comparator| match comparator {
+                        Comparator::Asc(expression) => format!(
+                            "ASC({})",
+                            format_expression_variables(expression, variables)
+                        ),
+                        Comparator::Desc(expression) => format!(
+                            "DESC({})",
+                            format_expression_variables(expression, variables)
+                        ),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                explanation(
+                    "Sort",
+                    vec![("by", by)],
+                    child.estimated_cardinality,
+                    vec![child],
+                )
+            }
+            PlanNode::HashDeduplicate { child } => {
+                let child = self.explain_node(child, variables, bound)?;
+                explanation(
+                    "HashDeduplicate",
+                    Vec::new(),
+                    child.estimated_cardinality,
+                    vec![child],
+                )
+            }
+            PlanNode::Skip { child, count } => {
+                let child = self.explain_node(child, variables, bound)?;
+                explanation(
+                    "Skip",
+                    vec![("count", count.to_string())],
+                    child
+                        .estimated_cardinality
+                        .map(|c| c.saturating_sub(*count)),
+                    vec![child],
+                )
+            }
+            PlanNode::Limit { child, count } => {
+                let child = self.explain_node(child, variables, bound)?;
+                explanation(
+                    "Limit",
+                    vec![("count", count.to_string())],
+                    child.estimated_cardinality.map(|c| c.min(*count)),
+                    vec![child],
+                )
+            }
+            PlanNode::Project {
+                child,
+                mapping,
+                child_variables,
+            } => {
+                let child = self.explain_node(child, child_variables, &BTreeSet::new())?;
+                explanation(
+                    "Project",
+                    vec![(
+                        "variables",
+                        format_variables(mapping.iter().map(|(_, o)| *o), variables),
+                    )],
+                    child.estimated_cardinality,
+                    vec![child],
+                )
+            }
+            PlanNode::Aggregate {
+                child,
+                key_mapping,
+                aggregates,
+                child_variables,
+            } => {
+                let child = self.explain_node(child, child_variables, &BTreeSet::new())?;
+                let aggregates = aggregates
+                    .iter()
+                    .map(|(aggregate, output)| {
+                        let function = match &aggregate.function {
+                            PlanAggregationFunction::Count => "COUNT".to_owned(),
+                            PlanAggregationFunction::Sum => "SUM".to_owned(),
+                            PlanAggregationFunction::Min => "MIN".to_owned(),
+                            PlanAggregationFunction::Max => "MAX".to_owned(),
+                            PlanAggregationFunction::Avg => "AVG".to_owned(),
+                            PlanAggregationFunction::Sample => "SAMPLE".to_owned(),
+                            PlanAggregationFunction::GroupConcat { .. } => {
+                                "GROUP_CONCAT".to_owned()
+                            }
+                            PlanAggregationFunction::Custom(name) => name.to_string(),
+                        };
+                        format!(
+                            "{}({}{}) AS {}",
+                            function,
+                            if aggregate.distinct { "DISTINCT " } else { "" },
+                            aggregate.parameter.as_ref().map_or_else(
+                                || "*".to_owned(),
+                                |parameter| format_expression_variables(parameter, child_variables)
+                            ),
+                            variables[*output]
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                explanation(
+                    "Aggregate",
+                    vec![
+                        (
+                            "group by",
+                            format_variables(key_mapping.iter().map(|(_, o)| *o), variables),
+                        ),
+                        ("aggregates", aggregates),
+                    ],
+                    if key_mapping.is_empty() {
+                        Some(1)
+                    } else {
+                        child.estimated_cardinality
+                    },
+                    vec![child],
+                )
+            }
+        })
+    }
+
+    /// Estimates the number of solutions of the pattern for each solution of its child
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    fn estimate_quad_pattern(
+        &self,
+        subject: &PatternValue<S::StrId>,
+        predicate: &PatternValue<S::StrId>,
+        object: &PatternValue<S::StrId>,
+        graph_name: &PatternValue<S::StrId>,
+        bound: &BTreeSet<usize>,
+    ) -> Result<usize, EvaluationError> {
+        self.interruption.check()?;
+        let mut count = 0;
+        for quad in self
+            .dataset
+            .encoded_quads_for_pattern(
+                constant(subject),
+                constant(predicate),
+                constant(object),
+                constant(graph_name),
+            )
+            .take(MAX_COUNTED_QUADS)
+        {
+            quad?;
+            self.interruption.check()?;
+            count += 1;
+        }
+        let values = [subject, predicate, object, graph_name];
+        let free_count = values
+            .iter()
+            .filter(|value| constant(value).is_none())
+            .count();
+        let bound_count = values
+            .iter()
+            .filter(|value| constant(value).is_none() && is_bound(value, bound))
+            .count();
+        Ok(if count == 0 || bound_count == 0 {
+            count
+        } else {
+            // We assume that each bound variable divides the number of results evenly
+            ((count as f64).powf((free_count - bound_count) as f64 / free_count as f64)).ceil()
+                as usize
+        })
+    }
+
+    fn format_pattern_value(
+        &self,
+        value: &PatternValue<S::StrId>,
+        variables: &[Variable],
+    ) -> Result<String, EvaluationError> {
+        Ok(match value {
+            PatternValue::Constant(term) => self.format_term(term.clone())?,
+            PatternValue::Variable(v) => variables[*v].to_string(),
+            PatternValue::Triple(triple) => format!(
+                "<< {} {} {} >>",
+                self.format_pattern_value(&triple.subject, variables)?,
+                self.format_pattern_value(&triple.predicate, variables)?,
+                self.format_pattern_value(&triple.object, variables)?
+            ),
+        })
+    }
+
+    fn format_graph_name(
+        &self,
+        graph_name: &PatternValue<S::StrId>,
+        variables: &[Variable],
+    ) -> Result<String, EvaluationError> {
+        if let PatternValue::Constant(EncodedTerm::DefaultGraph) = graph_name {
+            Ok("DEFAULT".to_owned())
+        } else {
+            self.format_pattern_value(graph_name, variables)
+        }
+    }
+
+    fn format_term(&self, term: EncodedTerm<S::StrId>) -> Result<String, EvaluationError> {
+        Ok(Term::to_string(&self.dataset.decode_term(term)?))
+    }
+
+    fn format_path(&self, path: &PlanPropertyPath<S::StrId>) -> Result<String, EvaluationError> {
+        Ok(match path {
+            PlanPropertyPath::Path(p) => self.format_term(p.clone())?,
+            PlanPropertyPath::Reverse(p) => format!("^{}", self.format_path(p)?),
+            PlanPropertyPath::Sequence(a, b) => {
+                format!("({} / {})", self.format_path(a)?, self.format_path(b)?)
+            }
+            PlanPropertyPath::Alternative(a, b) => {
+                format!("({} | {})", self.format_path(a)?, self.format_path(b)?)
+            }
+            PlanPropertyPath::ZeroOrMore(p) => format!("{}*", self.format_path(p)?),
+            PlanPropertyPath::OneOrMore(p) => format!("{}+", self.format_path(p)?),
+            PlanPropertyPath::ZeroOrOne(p) => format!("{}?", self.format_path(p)?),
+            PlanPropertyPath::NegatedPropertySet(ps) => format!(
+                "!({})",
+                ps.iter()
+                    .map(|p| self.format_term(p.clone()))
+                    .collect::<Result<Vec<_>, _>>()?
+                    .join(" | ")
+            ),
+        })
+    }
+}
+
+fn explanation(
+    name: &'static str,
+    attributes: Vec<(&'static str, String)>,
+    estimated_cardinality: Option<usize>,
+    children: Vec<PlanNodeExplanation>,
+) -> PlanNodeExplanation {
+    PlanNodeExplanation {
+        name,
+        attributes,
+        estimated_cardinality,
+        children,
+    }
+}
+
+fn constant<I: StrId>(value: &PatternValue<I>) -> Option<EncodedTerm<I>> {
+    if let PatternValue::Constant(term) = value {
+        Some(term.clone())
+    } else {
+        None
+    }
+}
+
+fn is_bound<I: StrId>(value: &PatternValue<I>, bound: &BTreeSet<usize>) -> bool {
+    match value {
+        PatternValue::Constant(_) => true,
+        PatternValue::Variable(v) => bound.contains(v),
+        PatternValue::Triple(_) => false,
+    }
+}
+
+/// The name of the index of the on-disk stores used to look up the quad pattern
+///
+/// The first letter is `d` for the default graph and `g` for a given named graph.
+fn index_name<I: StrId>(
+    subject: bool,
+    predicate: bool,
+    object: bool,
+    graph_name: &PatternValue<I>,
+    bound: &BTreeSet<usize>,
+) -> String {
+    let order = match (subject, predicate, object) {
+        (true, false, true) | (false, false, true) => "osp",
+        (false, true, _) => "pos",
+        _ => "spo",
+    };
+    match graph_name {
+        PatternValue::Constant(EncodedTerm::DefaultGraph) => format!("d{}", order),
+        graph_name if is_bound(graph_name, bound) => format!("g{}", order),
+        _ => format!("{}g", order),
+    }
+}
+
+fn format_variables(keys: impl Iterator<Item = usize>, variables: &[Variable]) -> String {
+    keys.map(|key| variables[key].to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn format_expression_variables<I: StrId>(
+    expression: &PlanExpression<I>,
+    variables: &[Variable],
+) -> String {
+    let mut keys = BTreeSet::new();
+    expression.add_maybe_bound_variables(&mut keys);
+    format_variables(keys.into_iter(), variables)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{GraphName, NamedNode, Quad};
+    use crate::store::MemoryStore;
+
+    fn store() -> MemoryStore {
+        let store = MemoryStore::new();
+        let p = NamedNode::new_unchecked("http://example.com/p");
+        let g = NamedNode::new_unchecked("http://example.com/g");
+        for i in 0..100 {
+            let s = NamedNode::new_unchecked(format!("http://example.com/s{}", i));
+            let o = NamedNode::new_unchecked(format!("http://example.com/o{}", i % 10));
+            store.insert(Quad::new(s.clone(), p.clone(), o.clone(), None));
+            store.insert(Quad::new(o, p.clone(), s, GraphName::from(g.clone())));
+        }
+        store
+    }
+
+    #[test]
+    fn text() {
+        let explanation = store()
+            .explain("SELECT ?s ?o WHERE { ?s <http://example.com/p> ?o . ?o ?p ?s } ORDER BY ?s LIMIT 5")
+            .unwrap();
+        assert_eq!(
+            explanation.to_string(),
+            "Limit (count: 5, estimated cardinality: 5)
+  Project (variables: ?s ?o, estimated cardinality: 500)
+    Sort (by: ASC(?s), estimated cardinality: 500)
+      QuadPatternJoin (pattern: ?o ?p ?s, graph: DEFAULT, index: dosp, join: index nested loop, estimated cardinality: 500)
+        QuadPatternJoin (pattern: ?s <http://example.com/p> ?o, graph: DEFAULT, index: dpos, join: index nested loop, estimated cardinality: 100)
+          Init (estimated cardinality: 1)
+"
+        );
+    }
+
+    #[test]
+    fn indexes_and_cardinalities() {
+        let explanation = store()
+            .explain("SELECT * WHERE { GRAPH <http://example.com/g> { <http://example.com/o1> ?p ?s } ?s ?p ?o }")
+            .unwrap();
+        let join = &explanation.root().children()[0];
+        assert_eq!(join.name(), "Join");
+        assert_eq!(join.estimated_cardinality(), Some(100));
+        let graph = &join.children()[0];
+        assert_eq!(graph.attribute("graph"), Some("<http://example.com/g>"));
+        assert_eq!(graph.attribute("index"), Some("gspo"));
+        assert_eq!(graph.estimated_cardinality(), Some(10));
+        let default = &join.children()[1];
+        assert_eq!(default.attribute("graph"), Some("DEFAULT"));
+        assert_eq!(default.attribute("index"), Some("dspo"));
+        assert_eq!(default.estimated_cardinality(), Some(100));
+
+        let explanation = store()
+            .explain("SELECT * WHERE { GRAPH ?g { <http://example.com/o1> ?p ?s } OPTIONAL { ?s ?p2 ?o2 } }")
+            .unwrap();
+        let left_join = &explanation.root().children()[0];
+        assert_eq!(left_join.name(), "LeftJoin");
+        assert_eq!(left_join.estimated_cardinality(), Some(10));
+        assert_eq!(left_join.children()[0].attribute("index"), Some("spog"));
+        assert_eq!(left_join.children()[1].attribute("index"), Some("dspo"));
+    }
+
+    #[test]
+    fn json() {
+        let mut json = Vec::new();
+        store()
+            .explain("ASK { ?s <http://example.com/p>* \"a\\\"b\" }")
+            .unwrap()
+            .write_json(&mut json)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(json).unwrap(),
+            "{\"name\":\"Project\",\"attributes\":{\"variables\":\"?s\"},\"estimated_cardinality\":null,\"children\":[{\"name\":\"PathPatternJoin\",\"attributes\":{\"pattern\":\"?s <http://example.com/p>* \\\"a\\\\\\\"b\\\"\",\"graph\":\"DEFAULT\",\"join\":\"index nested loop\"},\"estimated_cardinality\":null,\"children\":[{\"name\":\"Init\",\"attributes\":{},\"estimated_cardinality\":1,\"children\":[]}]}]}"
+        );
+    }
+}
@@ -1,10 +1,17 @@
 //! Implementation of [SPARQL Query Results JSON Format](https://www.w3.org/TR/sparql11-results-json/)
 
-use crate::error::invalid_input_error;
+use crate::error::{invalid_data_error, invalid_input_error};
+use crate::model::vocab::rdf;
 use crate::model::*;
 use crate::sparql::error::EvaluationError;
 use crate::sparql::model::*;
-use std::io::Write;
+use std::char;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::io;
+use std::io::{BufRead, Write};
+use std::rc::Rc;
 
 pub fn write_json_results(
     results: QueryResults,
@@ -47,30 +54,8 @@ pub fn write_json_results(
                         sink.write_all(b",")?;
                     }
                     write_escaped_json_string(variable.as_str(), &mut sink)?;
-                    match value {
-                        Term::NamedNode(uri) => {
-                            sink.write_all(b":{\"type\":\"uri\",\"value\":")?;
-                            write_escaped_json_string(uri.as_str(), &mut sink)?;
-                            sink.write_all(b"}")?;
-                        }
-                        Term::BlankNode(bnode) => {
-                            sink.write_all(b":{\"type\":\"bnode\",\"value\":")?;
-                            write_escaped_json_string(bnode.as_str(), &mut sink)?;
-                            sink.write_all(b"}")?;
-                        }
-                        Term::Literal(literal) => {
-                            sink.write_all(b":{\"type\":\"literal\",\"value\":")?;
-                            write_escaped_json_string(literal.value(), &mut sink)?;
-                            if let Some(language) = literal.language() {
-                                sink.write_all(b",\"xml:lang\":")?;
-                                write_escaped_json_string(language, &mut sink)?;
-                            } else if !literal.is_plain() {
-                                sink.write_all(b",\"datatype\":")?;
-                                write_escaped_json_string(literal.datatype().as_str(), &mut sink)?;
-                            }
-                            sink.write_all(b"}")?;
-                        }
-                    }
+                    sink.write_all(b":")?;
+                    write_json_term(value.as_ref(), &mut sink)?;
                 }
                 sink.write_all(b"}")?;
             }
@@ -84,7 +69,44 @@ pub fn write_json_results(
     }
 }
 
-fn write_escaped_json_string(s: &str, mut sink: impl Write) -> Result<(), EvaluationError> {
+fn write_json_term(term: TermRef<'_>, sink: &mut impl Write) -> Result<(), EvaluationError> {
+    match term {
+        TermRef::NamedNode(uri) => {
+            sink.write_all(b"{\"type\":\"uri\",\"value\":")?;
+            write_escaped_json_string(uri.as_str(), &mut *sink)?;
+            sink.write_all(b"}")?;
+        }
+        TermRef::BlankNode(bnode) => {
+            sink.write_all(b"{\"type\":\"bnode\",\"value\":")?;
+            write_escaped_json_string(bnode.as_str(), &mut *sink)?;
+            sink.write_all(b"}")?;
+        }
+        TermRef::Literal(literal) => {
+            sink.write_all(b"{\"type\":\"literal\",\"value\":")?;
+            write_escaped_json_string(literal.value(), &mut *sink)?;
+            if let Some(language) = literal.language() {
+                sink.write_all(b",\"xml:lang\":")?;
+                write_escaped_json_string(language, &mut *sink)?;
+            } else if !literal.is_plain() {
+                sink.write_all(b",\"datatype\":")?;
+                write_escaped_json_string(literal.datatype().as_str(), &mut *sink)?;
+            }
+            sink.write_all(b"}")?;
+        }
+        TermRef::Triple(triple) => {
+            sink.write_all(b"{\"type\":\"triple\",\"value\":{\"subject\":")?;
+            write_json_term(triple.subject.as_ref().into(), sink)?;
+            sink.write_all(b",\"predicate\":")?;
+            write_json_term(triple.predicate.as_ref().into(), sink)?;
+            sink.write_all(b",\"object\":")?;
+            write_json_term(triple.object.as_ref(), sink)?;
+            sink.write_all(b"}}")?;
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn write_escaped_json_string(s: &str, mut sink: impl Write) -> io::Result<()> {
     sink.write_all(b"\"")?;
     for c in s.chars() {
         match c {
@@ -118,3 +140,686 @@ fn write_escaped_json_string(s: &str, mut sink: impl Write) -> Result<(), Evalua
     sink.write_all(b"\"")?;
     Ok(())
 }
+
+pub fn read_json_results(source: impl BufRead + 'static) -> Result<QueryResults, io::Error> {
+    let mut reader = JsonLexer { reader: source };
+    let mut variables = None;
+    let mut buffered_bindings = None;
+    let mut boolean = None;
+
+    reader.expect(&JsonToken::ObjectStart)?;
+    while let Some(key) = reader.next_key()? {
+        match key.as_str() {
+            "head" => variables = Some(reader.read_head()?),
+            "boolean" => {
+                boolean = Some(match reader.next_token()? {
+                    JsonToken::Boolean(value) => value,
+                    token => {
+                        return Err(invalid_data_error(format!(
+                            "Unexpected boolean value: {}",
+                            token
+                        )))
+                    }
+                })
+            }
+            "results" => {
+                reader.expect(&JsonToken::ObjectStart)?;
+                while let Some(key) = reader.next_key()? {
+                    if key != "bindings" {
+                        reader.skip_value()?;
+                        continue;
+                    }
+                    reader.expect(&JsonToken::ArrayStart)?;
+                    if let Some(variables) = variables.take() {
+                        // We stream the solutions
+                        let (variables, mapping) = build_mapping(variables)?;
+                        return Ok(QueryResults::Solutions(QuerySolutionIter::new(
+                            variables,
+                            Box::new(ResultsIterator {
+                                reader,
+                                mapping,
+                                first: true,
+                                end: false,
+                            }),
+                        )));
+                    }
+                    // The head is after the results, we have to keep the solutions in memory
+                    let mut bindings = Vec::new();
+                    while let Some(binding) = reader.next_binding(bindings.is_empty())? {
+                        bindings.push(binding);
+                    }
+                    buffered_bindings = Some(bindings);
+                }
+            }
+            _ => reader.skip_value()?,
+        }
+    }
+    reader.expect(&JsonToken::Eof)?;
+
+    if let Some(boolean) = boolean {
+        return Ok(QueryResults::Boolean(boolean));
+    }
+    let variables = variables
+        .ok_or_else(|| invalid_data_error("SPARQL JSON results should contain a head object"))?;
+    let bindings = buffered_bindings.ok_or_else(|| {
+        invalid_data_error("SPARQL JSON results should contain a boolean value or a results object")
+    })?;
+    let (variables, mapping) = build_mapping(variables)?;
+    let solutions = bindings
+        .into_iter()
+        .map(|binding| map_binding(binding, &mapping).map_err(EvaluationError::from))
+        .collect::<Vec<_>>();
+    Ok(QueryResults::Solutions(QuerySolutionIter::new(
+        variables,
+        Box::new(solutions.into_iter()),
+    )))
+}
+
+fn build_mapping(
+    variables: Vec<String>,
+) -> Result<(Rc<Vec<Variable>>, HashMap<String, usize>), io::Error> {
+    let mut mapping = HashMap::with_capacity(variables.len());
+    for (i, variable) in variables.iter().enumerate() {
+        if mapping.insert(variable.clone(), i).is_some() {
+            return Err(invalid_data_error(format!(
+                "The variable {} is declared twice in the head",
+                variable
+            )));
+        }
+    }
+    let variables = variables
+        .into_iter()
+        .map(Variable::new)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(invalid_data_error)?;
+    Ok((Rc::new(variables), mapping))
+}
+
+fn map_binding(
+    binding: Vec<(String, Term)>,
+    mapping: &HashMap<String, usize>,
+) -> Result<Vec<Option<Term>>, io::Error> {
+    let mut values = vec![None; mapping.len()];
+    for (variable, value) in binding {
+        let i = *mapping.get(&variable).ok_or_else(|| {
+            invalid_data_error(format!(
+                "The variable {} is used in a binding but not declared in the head",
+                variable
+            ))
+        })?;
+        values[i] = Some(value);
+    }
+    Ok(values)
+}
+
+struct ResultsIterator<R: BufRead> {
+    reader: JsonLexer<R>,
+    mapping: HashMap<String, usize>,
+    first: bool,
+    end: bool,
+}
+
+impl<R: BufRead> Iterator for ResultsIterator<R> {
+    type Item = Result<Vec<Option<Term>>, EvaluationError>;
+
+    fn next(&mut self) -> Option<Result<Vec<Option<Term>>, EvaluationError>> {
+        if self.end {
+            return None;
+        }
+        let binding = match self.reader.next_binding(self.first) {
+            Ok(Some(binding)) => binding,
+            Ok(None) => {
+                self.end = true;
+                return self
+                    .reader
+                    .read_end_after_bindings()
+                    .err()
+                    .map(|error| Err(error.into()));
+            }
+            Err(error) => {
+                self.end = true;
+                return Some(Err(error.into()));
+            }
+        };
+        self.first = false;
+        Some(map_binding(binding, &self.mapping).map_err(EvaluationError::from))
+    }
+}
+
+#[derive(Eq, PartialEq, Debug)]
+enum JsonToken {
+    ObjectStart,
+    ObjectEnd,
+    ArrayStart,
+    ArrayEnd,
+    Colon,
+    Comma,
+    String(String),
+    Number(String),
+    Boolean(bool),
+    Null,
+    Eof,
+}
+
+impl fmt::Display for JsonToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonToken::ObjectStart => f.write_str("'{'"),
+            JsonToken::ObjectEnd => f.write_str("'}'"),
+            JsonToken::ArrayStart => f.write_str("'['"),
+            JsonToken::ArrayEnd => f.write_str("']'"),
+            JsonToken::Colon => f.write_str("':'"),
+            JsonToken::Comma => f.write_str("','"),
+            JsonToken::String(value) => write!(f, "string {:?}", value),
+            JsonToken::Number(value) => write!(f, "number {}", value),
+            JsonToken::Boolean(value) => write!(f, "{}", value),
+            JsonToken::Null => f.write_str("null"),
+            JsonToken::Eof => f.write_str("end of file"),
+        }
+    }
+}
+
+/// A minimal streaming JSON lexer
+struct JsonLexer<R: BufRead> {
+    reader: R,
+}
+
+impl<R: BufRead> JsonLexer<R> {
+    fn peek_byte(&mut self) -> io::Result<Option<u8>> {
+        Ok(self.reader.fill_buf()?.first().copied())
+    }
+
+    fn next_byte(&mut self) -> io::Result<Option<u8>> {
+        let byte = self.peek_byte()?;
+        if byte.is_some() {
+            self.reader.consume(1);
+        }
+        Ok(byte)
+    }
+
+    fn next_token(&mut self) -> io::Result<JsonToken> {
+        let byte = loop {
+            match self.next_byte()? {
+                Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') => (),
+                Some(byte) => break byte,
+                None => return Ok(JsonToken::Eof),
+            }
+        };
+        Ok(match byte {
+            b'{' => JsonToken::ObjectStart,
+            b'}' => JsonToken::ObjectEnd,
+            b'[' => JsonToken::ArrayStart,
+            b']' => JsonToken::ArrayEnd,
+            b':' => JsonToken::Colon,
+            b',' => JsonToken::Comma,
+            b'"' => JsonToken::String(self.read_string()?),
+            b't' => {
+                self.read_keyword(b"rue")?;
+                JsonToken::Boolean(true)
+            }
+            b'f' => {
+                self.read_keyword(b"alse")?;
+                JsonToken::Boolean(false)
+            }
+            b'n' => {
+                self.read_keyword(b"ull")?;
+                JsonToken::Null
+            }
+            b'-' | b'0'..=b'9' => {
+                let mut number = vec![byte];
+                while let Some(byte) = self.peek_byte()? {
+                    if !matches!(byte, b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E') {
+                        break;
+                    }
+                    number.push(byte);
+                    self.reader.consume(1);
+                }
+                JsonToken::Number(String::from_utf8(number).map_err(invalid_data_error)?)
+            }
+            _ => {
+                return Err(invalid_data_error(format!(
+                    "Unexpected character in JSON: {:?}",
+                    char::from(byte)
+                )))
+            }
+        })
+    }
+
+    fn read_keyword(&mut self, rest: &[u8]) -> io::Result<()> {
+        for expected in rest {
+            if self.next_byte()? != Some(*expected) {
+                return Err(invalid_data_error("Invalid JSON keyword"));
+            }
+        }
+        Ok(())
+    }
+
+    fn read_string(&mut self) -> io::Result<String> {
+        let mut buffer = Vec::new();
+        loop {
+            match self.next_byte()? {
+                Some(b'"') => return String::from_utf8(buffer).map_err(invalid_data_error),
+                Some(b'\\') => match self.next_byte()? {
+                    Some(b'"') => buffer.push(b'"'),
+                    Some(b'\\') => buffer.push(b'\\'),
+                    Some(b'/') => buffer.push(b'/'),
+                    Some(b'b') => buffer.push(8),
+                    Some(b'f') => buffer.push(12),
+                    Some(b'n') => buffer.push(b'\n'),
+                    Some(b'r') => buffer.push(b'\r'),
+                    Some(b't') => buffer.push(b'\t'),
+                    Some(b'u') => {
+                        let c = self.read_unicode_escape()?;
+                        buffer.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                    }
+                    _ => return Err(invalid_data_error("Invalid JSON string escape sequence")),
+                },
+                Some(byte) => buffer.push(byte),
+                None => {
+                    return Err(invalid_data_error(
+                        "Unexpected end of file in a JSON string",
+                    ))
+                }
+            }
+        }
+    }
+
+    fn read_unicode_escape(&mut self) -> io::Result<char> {
+        let high = self.read_hex_code_unit()?;
+        if !(0xD800..0xDC00).contains(&high) {
+            return char::from_u32(high.into())
+                .ok_or_else(|| invalid_data_error("Invalid JSON unicode escape sequence"));
+        }
+        // It is a surrogate pair
+        if self.next_byte()? != Some(b'\\') || self.next_byte()? != Some(b'u') {
+            return Err(invalid_data_error("Unpaired surrogate in JSON string"));
+        }
+        let low = self.read_hex_code_unit()?;
+        char::decode_utf16([high, low].iter().copied())
+            .next()
+            .and_then(Result::ok)
+            .ok_or_else(|| invalid_data_error("Invalid surrogate pair in JSON string"))
+    }
+
+    fn read_hex_code_unit(&mut self) -> io::Result<u16> {
+        let mut value = 0_u32;
+        for _ in 0..4 {
+            let digit = self
+                .next_byte()?
+                .and_then(|byte| char::from(byte).to_digit(16))
+                .ok_or_else(|| invalid_data_error("Invalid JSON unicode escape sequence"))?;
+            value = value * 16 + digit;
+        }
+        u16::try_from(value).map_err(invalid_data_error)
+    }
+
+    fn expect(&mut self, expected: &JsonToken) -> io::Result<()> {
+        let token = self.next_token()?;
+        if token == *expected {
+            Ok(())
+        } else {
+            Err(invalid_data_error(format!(
+                "Expecting {} in the SPARQL JSON results, found {}",
+                expected, token
+            )))
+        }
+    }
+
+    /// Reads the next key of an object whose '{' has already been read or returns `None` at the end of the object
+    fn next_key(&mut self) -> io::Result<Option<String>> {
+        let mut token = self.next_token()?;
+        if token == JsonToken::Comma {
+            token = self.next_token()?;
+        }
+        match token {
+            JsonToken::ObjectEnd => Ok(None),
+            JsonToken::String(key) => {
+                self.expect(&JsonToken::Colon)?;
+                Ok(Some(key))
+            }
+            token => Err(invalid_data_error(format!(
+                "Expecting an object key in the SPARQL JSON results, found {}",
+                token
+            ))),
+        }
+    }
+
+    fn next_string(&mut self) -> io::Result<String> {
+        match self.next_token()? {
+            JsonToken::String(value) => Ok(value),
+            token => Err(invalid_data_error(format!(
+                "Expecting a string in the SPARQL JSON results, found {}",
+                token
+            ))),
+        }
+    }
+
+    /// Skips a value we do not care about
+    fn skip_value(&mut self) -> io::Result<()> {
+        let mut depth = 0_usize;
+        loop {
+            match self.next_token()? {
+                JsonToken::ObjectStart | JsonToken::ArrayStart => depth += 1,
+                JsonToken::ObjectEnd | JsonToken::ArrayEnd => {
+                    depth = depth
+                        .checked_sub(1)
+                        .ok_or_else(|| invalid_data_error("Unbalanced JSON brackets"))?;
+                }
+                JsonToken::Eof => return Err(invalid_data_error("Unexpected end of file in JSON")),
+                _ => (),
+            }
+            if depth == 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Reads the end of the document after the bindings array, the other keys being ignored
+    fn read_end_after_bindings(&mut self) -> io::Result<()> {
+        // The end of the results object and then of the root object
+        for _ in 0..2 {
+            while self.next_key()?.is_some() {
+                self.skip_value()?;
+            }
+        }
+        self.expect(&JsonToken::Eof)
+    }
+
+    fn read_head(&mut self) -> io::Result<Vec<String>> {
+        let mut variables = Vec::new();
+        self.expect(&JsonToken::ObjectStart)?;
+        while let Some(key) = self.next_key()? {
+            if key != "vars" {
+                self.skip_value()?; // e.g. link
+                continue;
+            }
+            self.expect(&JsonToken::ArrayStart)?;
+            loop {
+                match self.next_token()? {
+                    JsonToken::String(variable) => variables.push(variable),
+                    JsonToken::Comma => (),
+                    JsonToken::ArrayEnd => break,
+                    token => {
+                        return Err(invalid_data_error(format!(
+                            "Expecting a variable name in the SPARQL JSON results, found {}",
+                            token
+                        )))
+                    }
+                }
+            }
+        }
+        Ok(variables)
+    }
+
+    /// Reads the next binding of the bindings array whose '[' has already been read or returns `None` at the end of the array
+    fn next_binding(&mut self, first: bool) -> io::Result<Option<Vec<(String, Term)>>> {
+        match self.next_token()? {
+            JsonToken::ArrayEnd => return Ok(None),
+            JsonToken::ObjectStart if first => (),
+            JsonToken::Comma if !first => self.expect(&JsonToken::ObjectStart)?,
+            token => {
+                return Err(invalid_data_error(format!(
+                    "Expecting a binding object in the SPARQL JSON results, found {}",
+                    token
+                )))
+            }
+        }
+        let mut binding = Vec::new();
+        while let Some(variable) = self.next_key()? {
+            binding.push((variable, self.read_term()?));
+        }
+        Ok(Some(binding))
+    }
+
+    fn read_term(&mut self) -> io::Result<Term> {
+        let mut kind = None;
+        let mut value = None;
+        let mut triple = None;
+        let mut lang = None;
+        let mut datatype = None;
+        self.expect(&JsonToken::ObjectStart)?;
+        while let Some(key) = self.next_key()? {
+            match key.as_str() {
+                "type" => kind = Some(self.next_string()?),
+                "value" => match self.next_token()? {
+                    JsonToken::String(v) => value = Some(v),
+                    JsonToken::ObjectStart => triple = Some(self.read_triple()?),
+                    token => {
+                        return Err(invalid_data_error(format!(
+                            "Expecting a term value in the SPARQL JSON results, found {}",
+                            token
+                        )))
+                    }
+                },
+                "xml:lang" => lang = Some(self.next_string()?),
+                "datatype" => datatype = Some(self.next_string()?),
+                _ => self.skip_value()?,
+            }
+        }
+        let kind = kind.ok_or_else(|| {
+            invalid_data_error("Terms in the SPARQL JSON results should have a type")
+        })?;
+        if kind == "triple" {
+            return Ok(triple
+                .ok_or_else(|| invalid_data_error("Quoted triples should have an object value"))?
+                .into());
+        }
+        let value = value.ok_or_else(|| {
+            invalid_data_error(format!(
+                "The {} term in the SPARQL JSON results should have a string value",
+                kind
+            ))
+        })?;
+        match kind.as_str() {
+            "uri" => Ok(NamedNode::new(&value)
+                .map_err(|e| invalid_data_error(format!("Invalid IRI value '{}': {}", value, e)))?
+                .into()),
+            "bnode" => Ok(BlankNode::new(&value)
+                .map_err(|e| {
+                    invalid_data_error(format!("Invalid blank node value '{}': {}", value, e))
+                })?
+                .into()),
+            "literal" | "typed-literal" => Ok(match (datatype, lang) {
+                (Some(datatype), lang) if datatype == rdf::LANG_STRING.as_str() => {
+                    let lang = lang.ok_or_else(|| {
+                        invalid_data_error(
+                            "rdf:langString literals in the SPARQL JSON results should have an xml:lang",
+                        )
+                    })?;
+                    Literal::new_language_tagged_literal(value, &lang).map_err(|e| {
+                        invalid_data_error(format!("Invalid xml:lang value '{}': {}", lang, e))
+                    })?
+                }
+                (Some(datatype), Some(_)) => {
+                    return Err(invalid_data_error(format!(
+                        "The literals with the datatype {} should not have an xml:lang",
+                        datatype
+                    )))
+                }
+                (Some(datatype), None) => Literal::new_typed_literal(
+                    value,
+                    NamedNode::new(&datatype).map_err(|e| {
+                        invalid_data_error(format!("Invalid datatype IRI '{}': {}", datatype, e))
+                    })?,
+                ),
+                (None, Some(lang)) => {
+                    Literal::new_language_tagged_literal(value, &lang).map_err(|e| {
+                        invalid_data_error(format!("Invalid xml:lang value '{}': {}", lang, e))
+                    })?
+                }
+                (None, None) => Literal::new_simple_literal(value),
+            }
+            .into()),
+            _ => Err(invalid_data_error(format!(
+                "Unexpected term type in the SPARQL JSON results: '{}'",
+                kind
+            ))),
+        }
+    }
+
+    /// Reads a quoted triple object whose '{' has already been read
+    fn read_triple(&mut self) -> io::Result<Triple> {
+        let mut subject = None;
+        let mut predicate = None;
+        let mut object = None;
+        while let Some(key) = self.next_key()? {
+            match key.as_str() {
+                "subject" => subject = Some(self.read_term()?),
+                "predicate" => predicate = Some(self.read_term()?),
+                "object" => object = Some(self.read_term()?),
+                _ => self.skip_value()?,
+            }
+        }
+        let subject = match subject {
+            Some(Term::NamedNode(node)) => Subject::from(node),
+            Some(Term::BlankNode(node)) => Subject::from(node),
+            Some(Term::Triple(triple)) => Subject::from(triple),
+            Some(Term::Literal(_)) => {
+                return Err(invalid_data_error(
+                    "The subject of a quoted triple should not be a literal",
+                ))
+            }
+            None => return Err(invalid_data_error("Quoted triples should have a subject")),
+        };
+        let predicate = match predicate {
+            Some(Term::NamedNode(node)) => node,
+            Some(_) => {
+                return Err(invalid_data_error(
+                    "The predicate of a quoted triple should be an IRI",
+                ))
+            }
+            None => return Err(invalid_data_error("Quoted triples should have a predicate")),
+        };
+        let object =
+            object.ok_or_else(|| invalid_data_error("Quoted triples should have an object"))?;
+        Ok(Triple::new(subject, predicate, object))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::vocab::xsd;
+    use std::io::Cursor;
+
+    fn solutions(results: QueryResults) -> Vec<Vec<Option<Term>>> {
+        if let QueryResults::Solutions(solutions) = results {
+            solutions
+                .map(|s| s.unwrap().values().map(|v| v.cloned()).collect())
+                .collect()
+        } else {
+            panic!("Solutions expected")
+        }
+    }
+
+    #[test]
+    fn test_boolean() -> io::Result<()> {
+        for (data, value) in &[
+            ("{\"head\":{},\"boolean\":true}", true),
+            (
+                "{ \"boolean\" : false , \"head\" : { \"link\" : [] } }",
+                false,
+            ),
+        ] {
+            if let QueryResults::Boolean(result) = read_json_results(Cursor::new(*data))? {
+                assert_eq!(result, *value);
+            } else {
+                panic!("Boolean expected")
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_round_trip() -> io::Result<()> {
+        let ex = NamedNode::new_unchecked("http://example.com/\u{e9}");
+        let quoted = Triple::new(
+            BlankNode::new_unchecked("b"),
+            ex.clone(),
+            Literal::new_language_tagged_literal_unchecked("a\"\n\u{1F600}", "en"),
+        );
+        let rows = vec![
+            vec![Some(ex.clone().into()), None],
+            vec![
+                Some(Literal::new_typed_literal("1", xsd::INTEGER).into()),
+                Some(quoted.into()),
+            ],
+            vec![None, Some(Literal::new_simple_literal("").into())],
+        ];
+        let results = QueryResults::Solutions(QuerySolutionIter::new(
+            Rc::new(vec![
+                Variable::new_unchecked("a"),
+                Variable::new_unchecked("b"),
+            ]),
+            Box::new(rows.clone().into_iter().map(Ok)),
+        ));
+        let mut buffer = Vec::new();
+        write_json_results(results, &mut buffer).unwrap();
+        assert_eq!(solutions(read_json_results(Cursor::new(buffer))?), rows);
+        Ok(())
+    }
+
+    #[test]
+    fn test_escapes_and_results_before_head() -> io::Result<()> {
+        let data = r#"{
+            "results": { "bindings": [
+                { "s": { "value": "\ud83d\ude00\u00e9\/", "type": "literal", "extra": [1.5e3, null, {}] } },
+                { "s": { "type": "typed-literal", "datatype": "http://www.w3.org/2001/XMLSchema#integer", "value": "2" } }
+            ] },
+            "head": { "vars": [ "s" ] }
+        }"#;
+        assert_eq!(
+            solutions(read_json_results(Cursor::new(data))?),
+            vec![
+                vec![Some(Literal::new_simple_literal("\u{1F600}\u{e9}/").into())],
+                vec![Some(Literal::new_typed_literal("2", xsd::INTEGER).into())]
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_lang_string_datatype_and_ignored_keys() -> io::Result<()> {
+        let data = r#"{
+            "head": { "vars": [ "s" ] },
+            "results": { "bindings": [
+                { "s": { "type": "literal", "value": "a", "xml:lang": "en", "datatype": "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString" } }
+            ], "distinct": false },
+            "link": []
+        }"#;
+        assert_eq!(
+            solutions(read_json_results(Cursor::new(data))?),
+            vec![vec![Some(
+                Literal::new_language_tagged_literal("a", "en")
+                    .unwrap()
+                    .into()
+            )]]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors() {
+        for data in &[
+            "",
+            "{\"head\":{\"vars\":[\"s\"]}}",
+            "{\"head\":{\"vars\":[\"s\"]},\"results\":{\"bindings\":[{\"o\":{\"type\":\"uri\",\"value\":\"http://example.com\"}}]}}",
+            "{\"head\":{\"vars\":[\"s\"]},\"results\":{\"bindings\":[{\"s\":{\"type\":\"foo\",\"value\":\"bar\"}}]}}",
+            "{\"head\":{\"vars\":[\"s\"]},\"results\":{\"bindings\":[{\"s\":{\"type\":\"literal\",\"value\":\"\\ud83d\"}}]}}",
+            "{\"head\":{\"vars\":[\"s\"]},\"results\":{\"bindings\":[]}} {}",
+            "{\"head\":{\"vars\":[\"s\"]},\"results\":{\"bindings\":[]}}}",
+            "{\"head\":{\"vars\":[\"s\"]},\"results\":{\"bindings\":[]},\"foo\":}",
+            "{\"results\":{\"bindings\":[]},\"head\":{\"vars\":[\"s\"]}} {}",
+            "{\"head\":{\"vars\":[\"s\",\"s\"]},\"results\":{\"bindings\":[]}}",
+            "{\"head\":{\"vars\":[\"s\"]},\"results\":{\"bindings\":[{\"s\":{\"type\":\"literal\",\"value\":\"a\",\"datatype\":\"http://www.w3.org/1999/02/22-rdf-syntax-ns#langString\"}}]}}",
+            "{\"head\":{\"vars\":[\"s\"]},\"results\":{\"bindings\":[{\"s\":{\"type\":\"literal\",\"value\":\"a\",\"xml:lang\":\"en\",\"datatype\":\"http://www.w3.org/2001/XMLSchema#string\"}}]}}",
+        ] {
+            let is_err = match read_json_results(Cursor::new(*data)) {
+                Ok(QueryResults::Solutions(mut solutions)) => solutions.any(|s| s.is_err()),
+                Ok(_) => false,
+                Err(_) => true,
+            };
+            assert!(is_err, "{} should be invalid", data);
+        }
+    }
+}
@@ -2,25 +2,42 @@
 //!
 //! Stores execute SPARQL. See [`MemoryStore`](super::store::memory::MemoryStore::query()) for an example.
 
+mod aggregate;
 pub mod algebra;
+#[cfg(feature = "arrow")]
+mod arrow_results;
+mod cancellation;
 mod csv_results;
 mod dataset;
 mod error;
 mod eval;
+mod explain;
 mod http;
 mod json_results;
 mod model;
 mod parser;
 mod plan;
 mod plan_builder;
+#[cfg(feature = "serde")]
+mod serde;
 mod service;
 mod update;
 mod xml_results;
 
+use crate::model::xsd::TimezoneOffset;
+use crate::model::{LiteralValidator, NamedNode, Term};
+pub use crate::sparql::aggregate::AggregateAccumulator;
+use crate::sparql::algebra::{GraphPattern, GraphUpdateOperation};
 pub use crate::sparql::algebra::{Query, Update};
+#[cfg(feature = "arrow")]
+pub use crate::sparql::arrow_results::{ArrowExporter, RecordBatchIter};
+pub use crate::sparql::cancellation::CancellationToken;
+pub use crate::sparql::csv_results::CsvResultsParser;
 use crate::sparql::dataset::DatasetView;
-pub use crate::sparql::error::EvaluationError;
-use crate::sparql::eval::SimpleEvaluator;
+pub use crate::sparql::error::{EvaluationError, ResourceLimit};
+use crate::sparql::eval::{Interruption, SimpleEvaluator};
+use crate::sparql::explain::PlanExplainer;
+pub use crate::sparql::explain::{PlanNodeExplanation, QueryExplanation};
 pub use crate::sparql::model::QueryResults;
 pub use crate::sparql::model::QueryResultsFormat;
 pub use crate::sparql::model::QuerySolution;
@@ -28,35 +45,34 @@ pub use crate::sparql::model::QuerySolutionIter;
 pub use crate::sparql::model::QueryTripleIter;
 pub use crate::sparql::model::{Variable, VariableNameParseError};
 pub use crate::sparql::parser::ParseError;
+use crate::sparql::parser::{parse_query, parse_update};
 use crate::sparql::plan_builder::PlanBuilder;
 pub use crate::sparql::service::ServiceHandler;
 use crate::sparql::service::{EmptyServiceHandler, ErrorConversionServiceHandler};
 use crate::sparql::update::SimpleUpdateEvaluator;
 use crate::store::numeric_encoder::StrContainer;
 use crate::store::{ReadableEncodedStore, StoreOrParseError, WritableEncodedStore};
+use std::collections::HashMap;
 use std::convert::TryInto;
 use std::io;
 use std::rc::Rc;
+use std::time::Duration;
 
 pub(crate) fn evaluate_query<R: ReadableEncodedStore + 'static>(
     store: R,
     query: impl TryInto<Query, Error = impl Into<EvaluationError>>,
     options: QueryOptions,
 ) -> Result<QueryResults, EvaluationError> {
-    match query.try_into().map_err(|e| e.into())? {
+    match resolve_query_custom_aggregates(query.try_into().map_err(|e| e.into())?, &options) {
         Query::Select {
             pattern,
             base_iri,
             dataset,
         } => {
             let dataset = DatasetView::new(store, &dataset)?;
-            let (plan, variables) = PlanBuilder::build(&dataset, &pattern)?;
-            SimpleEvaluator::new(
-                Rc::new(dataset),
-                base_iri.map(Rc::new),
-                options.service_handler,
-            )
-            .evaluate_select_plan(&plan, Rc::new(variables))
+            let (plan, variables) = PlanBuilder::build(&dataset, &pattern, &options)?;
+            SimpleEvaluator::new(Rc::new(dataset), base_iri.map(Rc::new), options)
+                .evaluate_select_plan(&plan, Rc::new(variables))
         }
         Query::Ask {
             pattern,
@@ -64,13 +80,9 @@ pub(crate) fn evaluate_query<R: ReadableEncodedStore + 'static>(
             dataset,
         } => {
             let dataset = DatasetView::new(store, &dataset)?;
-            let (plan, _) = PlanBuilder::build(&dataset, &pattern)?;
-            SimpleEvaluator::new(
-                Rc::new(dataset),
-                base_iri.map(Rc::new),
-                options.service_handler,
-            )
-            .evaluate_ask_plan(&plan)
+            let (plan, _) = PlanBuilder::build(&dataset, &pattern, &options)?;
+            SimpleEvaluator::new(Rc::new(dataset), base_iri.map(Rc::new), options)
+                .evaluate_ask_plan(&plan)
         }
         Query::Construct {
             template,
@@ -79,14 +91,10 @@ pub(crate) fn evaluate_query<R: ReadableEncodedStore + 'static>(
             dataset,
         } => {
             let dataset = DatasetView::new(store, &dataset)?;
-            let (plan, variables) = PlanBuilder::build(&dataset, &pattern)?;
+            let (plan, variables) = PlanBuilder::build(&dataset, &pattern, &options)?;
             let construct = PlanBuilder::build_graph_template(&dataset, &template, variables)?;
-            SimpleEvaluator::new(
-                Rc::new(dataset),
-                base_iri.map(Rc::new),
-                options.service_handler,
-            )
-            .evaluate_construct_plan(&plan, construct)
+            SimpleEvaluator::new(Rc::new(dataset), base_iri.map(Rc::new), options)
+                .evaluate_construct_plan(&plan, construct)
         }
         Query::Describe {
             pattern,
@@ -94,17 +102,39 @@ pub(crate) fn evaluate_query<R: ReadableEncodedStore + 'static>(
             dataset,
         } => {
             let dataset = DatasetView::new(store, &dataset)?;
-            let (plan, _) = PlanBuilder::build(&dataset, &pattern)?;
-            SimpleEvaluator::new(
-                Rc::new(dataset),
-                base_iri.map(Rc::new),
-                options.service_handler,
-            )
-            .evaluate_describe_plan(&plan)
+            let (plan, _) = PlanBuilder::build(&dataset, &pattern, &options)?;
+            SimpleEvaluator::new(Rc::new(dataset), base_iri.map(Rc::new), options)
+                .evaluate_describe_plan(&plan)
         }
     }
 }
 
+#[allow(clippy::needless_pass_by_value)]
+pub(crate) fn explain_query<R: ReadableEncodedStore + 'static>(
+    store: R,
+    query: impl TryInto<Query, Error = impl Into<EvaluationError>>,
+    options: QueryOptions,
+) -> Result<QueryExplanation, EvaluationError> {
+    let query = resolve_query_custom_aggregates(query.try_into().map_err(|e| e.into())?, &options);
+    let (pattern, dataset) = match &query {
+        Query::Select {
+            pattern, dataset, ..
+        }
+        | Query::Construct {
+            pattern, dataset, ..
+        }
+        | Query::Describe {
+            pattern, dataset, ..
+        } => (pattern, dataset),
+        Query::Ask {
+            pattern, dataset, ..
+        } => (pattern.as_ref(), dataset),
+    };
+    let dataset = DatasetView::new(store, dataset)?;
+    let (plan, variables) = PlanBuilder::build(&dataset, pattern, &options)?;
+    PlanExplainer::explain(&dataset, &plan, &variables, Interruption::new(&options))
+}
+
 /// Options for SPARQL query evaluation.
 ///
 ///
@@ -113,8 +143,19 @@ pub(crate) fn evaluate_query<R: ReadableEncodedStore + 'static>(
 #[derive(Clone)]
 pub struct QueryOptions {
     pub(crate) service_handler: Rc<dyn ServiceHandler<Error = EvaluationError>>,
+    pub(crate) implicit_timezone: Option<TimezoneOffset>,
+    pub(crate) custom_functions: Rc<HashMap<NamedNode, CustomFunction>>,
+    pub(crate) custom_aggregate_functions: Rc<HashMap<NamedNode, CustomAggregateFunction>>,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) cancellation_token: Option<CancellationToken>,
+    pub(crate) max_buffered_tuples: Option<usize>,
+    pub(crate) max_intermediate_results: Option<usize>,
+    pub(crate) max_result_rows: Option<usize>,
 }
 
+pub(crate) type CustomFunction = Rc<dyn Fn(&[Term]) -> Option<Term>>;
+pub(crate) type CustomAggregateFunction = Rc<dyn Fn() -> Box<dyn AggregateAccumulator>>;
+
 impl Default for QueryOptions {
     #[inline]
     fn default() -> Self {
@@ -124,6 +165,14 @@ impl Default for QueryOptions {
             } else {
                 Rc::new(EmptyServiceHandler)
             },
+            implicit_timezone: None,
+            custom_functions: Rc::new(HashMap::new()),
+            custom_aggregate_functions: Rc::new(HashMap::new()),
+            timeout: None,
+            cancellation_token: None,
+            max_buffered_tuples: None,
+            max_intermediate_results: None,
+            max_result_rows: None,
         }
     }
 }
@@ -142,12 +191,239 @@ impl QueryOptions {
         self.service_handler = Rc::new(EmptyServiceHandler);
         self
     }
+
+    /// Sets the [implicit timezone](https://www.w3.org/TR/xpath-functions/#comp.time) of the query evaluation.
+    ///
+    /// It is used to compare and subtract `xsd:dateTime`, `xsd:date` and `xsd:time` values without timezone with values that have one,
+    /// as the timezone of `NOW()` and as the default target of the `fn:adjust-*-to-timezone` functions.
+    /// By default the values without timezone are not comparable with values that have one and `NOW()` returns a UTC value.
+    ///
+    /// ```
+    /// use oxigraph::MemoryStore;
+    /// use oxigraph::model::xsd::{DayTimeDuration, TimezoneOffset};
+    /// use oxigraph::sparql::{QueryOptions, QueryResults};
+    /// use std::convert::TryFrom;
+    /// use std::str::FromStr;
+    ///
+    /// let store = MemoryStore::new();
+    /// let timezone = TimezoneOffset::try_from(DayTimeDuration::from_str("PT2H")?)?;
+    /// if let QueryResults::Boolean(result) = store.query_opt(
+    ///     "ASK { FILTER(\"2020-01-01T12:00:00\"^^<http://www.w3.org/2001/XMLSchema#dateTime> = \"2020-01-01T10:00:00Z\"^^<http://www.w3.org/2001/XMLSchema#dateTime>) }",
+    ///     QueryOptions::default().with_implicit_timezone(timezone)
+    /// )? {
+    ///     assert!(result);
+    /// }
+    /// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    pub fn with_implicit_timezone(mut self, timezone: TimezoneOffset) -> Self {
+        self.implicit_timezone = Some(timezone);
+        self
+    }
+
+    /// Sets the maximal duration of the evaluation.
+    ///
+    /// The duration starts when the evaluation starts and includes the consumption of the lazily evaluated results.
+    /// After it, the evaluation fails with [`EvaluationError::Timeout`].
+    ///
+    /// ```
+    /// use oxigraph::MemoryStore;
+    /// use oxigraph::sparql::{EvaluationError, QueryOptions};
+    /// use std::time::Duration;
+    ///
+    /// let store = MemoryStore::new();
+    /// assert!(matches!(
+    ///     store.query_opt("ASK { ?s ?p ?o }", QueryOptions::default().with_timeout(Duration::from_secs(0))),
+    ///     Err(EvaluationError::Timeout)
+    /// ));
+    /// ```
+    #[inline]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Allows to cancel the evaluation from another thread using a [`CancellationToken`].
+    ///
+    /// After the cancellation, the evaluation fails with [`EvaluationError::Cancelled`].
+    #[inline]
+    pub fn with_cancellation_token(mut self, cancellation_token: CancellationToken) -> Self {
+        self.cancellation_token = Some(cancellation_token);
+        self
+    }
+
+    /// Sets the maximal number of tuples an operator could keep in memory.
+    ///
+    /// It bounds the solutions sorted by `ORDER BY`, deduplicated by `DISTINCT`, grouped by `GROUP BY` or aggregates,
+    /// the terms reached by the `*` and `+` property paths and the left sides of the joins.
+    /// The evaluation fails with [`EvaluationError::ResourceLimitExceeded`] when an operator goes over it.
+    ///
+    /// ```
+    /// use oxigraph::MemoryStore;
+    /// use oxigraph::model::*;
+    /// use oxigraph::sparql::{EvaluationError, QueryOptions, QueryResults, ResourceLimit};
+    ///
+    /// let store = MemoryStore::new();
+    /// for i in 0..10 {
+    ///     let ex = NamedNode::new(format!("http://example.com/{}", i))?;
+    ///     store.insert(Quad::new(ex.clone(), ex.clone(), ex, None));
+    /// }
+    ///
+    /// if let QueryResults::Solutions(mut solutions) = store.query_opt(
+    ///     "SELECT ?s WHERE { ?s ?p ?o } ORDER BY ?s",
+    ///     QueryOptions::default().with_max_buffered_tuples(5)
+    /// )? {
+    ///     assert!(matches!(
+    ///         solutions.next(),
+    ///         Some(Err(EvaluationError::ResourceLimitExceeded(ResourceLimit::BufferedTuples(5))))
+    ///     ));
+    /// }
+    /// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    pub fn with_max_buffered_tuples(mut self, max: usize) -> Self {
+        self.max_buffered_tuples = Some(max);
+        self
+    }
+
+    /// Sets the maximal number of tuples produced by all the operators of the evaluation together.
+    ///
+    /// A tuple is counted once for each operator it goes through, so this limit bounds the total work of the evaluation, joins included.
+    /// The evaluation fails with [`EvaluationError::ResourceLimitExceeded`] when it is exceeded.
+    #[inline]
+    pub fn with_max_intermediate_results(mut self, max: usize) -> Self {
+        self.max_intermediate_results = Some(max);
+        self
+    }
+
+    /// Sets the maximal number of solutions of a `SELECT` query or of triples of a `CONSTRUCT` or `DESCRIBE` query.
+    ///
+    /// The results iterator returns an [`EvaluationError::ResourceLimitExceeded`] error instead of the first result over the limit.
+    /// Use a `LIMIT` clause to silently truncate the results instead.
+    #[inline]
+    pub fn with_max_result_rows(mut self, max: usize) -> Self {
+        self.max_result_rows = Some(max);
+        self
+    }
+
+    /// Adds a custom SPARQL evaluation function.
+    ///
+    /// It is called with the values of the function arguments in the query and returns `None` if the evaluation fails
+    /// like the built-in SPARQL functions do on an invalid argument.
+    /// A custom function takes precedence over a built-in function with the same name.
+    ///
+    /// Example with a function serializing terms to N-Triples:
+    /// ```
+    /// use oxigraph::MemoryStore;
+    /// use oxigraph::model::*;
+    /// use oxigraph::sparql::{QueryOptions, QueryResults};
+    ///
+    /// let store = MemoryStore::new();
+    ///
+    /// if let QueryResults::Solutions(mut solutions) = store.query_opt(
+    ///     "SELECT (<http://www.w3.org/ns/formats/N-Triples>(1) AS ?nt) WHERE {}",
+    ///     QueryOptions::default().with_custom_function(
+    ///         NamedNode::new("http://www.w3.org/ns/formats/N-Triples")?,
+    ///         |args| args.get(0).map(|t| Literal::from(t.to_string()).into())
+    ///     )
+    /// )? {
+    ///     assert_eq!(
+    ///         solutions.next().unwrap()?.get("nt"),
+    ///         Some(&Literal::from("\"1\"^^<http://www.w3.org/2001/XMLSchema#integer>").into())
+    ///     );
+    /// }
+    /// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    pub fn with_custom_function(
+        mut self,
+        name: NamedNode,
+        evaluator: impl Fn(&[Term]) -> Option<Term> + 'static,
+    ) -> Self {
+        Rc::make_mut(&mut self.custom_functions).insert(name, Rc::new(evaluator));
+        self
+    }
+
+    /// Adds a custom SPARQL aggregate function.
+    ///
+    /// `accumulator` is called to build a new [`AggregateAccumulator`] for each group of solutions.
+    /// The values are deduplicated before being given to the accumulator if the aggregate is called with `DISTINCT`.
+    ///
+    /// The SPARQL syntax does not distinguish the custom aggregate calls from the custom function calls.
+    /// The calls to the registered aggregates parsed as function calls, for example by [`Query::parse`], are turned into aggregates
+    /// when the query is evaluated with these options.
+    /// [`parse_query`](QueryOptions::parse_query()) and [`UpdateOptions::parse_update`] parse them directly as aggregates.
+    ///
+    /// Example with an aggregate returning the median of numbers:
+    /// ```
+    /// use oxigraph::MemoryStore;
+    /// use oxigraph::model::*;
+    /// use oxigraph::sparql::{AggregateAccumulator, QueryOptions, QueryResults};
+    ///
+    /// #[derive(Default)]
+    /// struct MedianAccumulator {
+    ///     values: Vec<f64>,
+    ///     failed: bool,
+    /// }
+    ///
+    /// impl AggregateAccumulator for MedianAccumulator {
+    ///     fn add(&mut self, element: Option<Term>) {
+    ///         match element.as_ref().and_then(|t| if let Term::Literal(l) = t { l.value().parse().ok() } else { None }) {
+    ///             Some(value) => self.values.push(value),
+    ///             None => self.failed = true,
+    ///         }
+    ///     }
+    ///
+    ///     fn state(&self) -> Option<Term> {
+    ///         if self.failed || self.values.is_empty() {
+    ///             return None;
+    ///         }
+    ///         let mut values = self.values.clone();
+    ///         values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    ///         Some(Literal::from(values[values.len() / 2]).into())
+    ///     }
+    /// }
+    ///
+    /// let store = MemoryStore::new();
+    /// let ex = NamedNode::new("http://example.com")?;
+    /// for i in &[1, 5, 2] {
+    ///     store.insert(Quad::new(ex.clone(), ex.clone(), Literal::from(*i), None));
+    /// }
+    ///
+    /// let options = QueryOptions::default().with_custom_aggregate_function(
+    ///     NamedNode::new("http://example.com/median")?,
+    ///     || Box::new(MedianAccumulator::default())
+    /// );
+    /// if let QueryResults::Solutions(mut solutions) = store.query_opt("SELECT (<http://example.com/median>(?o) AS ?m) WHERE { ?s ?p ?o }", options)? {
+    ///     assert_eq!(solutions.next().unwrap()?.get("m"), Some(&Literal::from(2.).into()));
+    /// }
+    /// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    pub fn with_custom_aggregate_function(
+        mut self,
+        name: NamedNode,
+        accumulator: impl Fn() -> Box<dyn AggregateAccumulator> + 'static,
+    ) -> Self {
+        Rc::make_mut(&mut self.custom_aggregate_functions).insert(name, Rc::new(accumulator));
+        self
+    }
+
+    /// Parses a SPARQL query like [`Query::parse`] but with the calls to the [custom aggregate functions](QueryOptions::with_custom_aggregate_function()) parsed as aggregates.
+    pub fn parse_query(&self, query: &str, base_iri: Option<&str>) -> Result<Query, ParseError> {
+        parse_query(
+            query,
+            base_iri,
+            self.custom_aggregate_functions.keys().cloned().collect(),
+        )
+    }
 }
 
 /// Options for SPARQL update evaluation
 #[derive(Clone)]
 pub struct UpdateOptions {
     query_options: QueryOptions,
+    literal_validator: LiteralValidator,
 }
 
 impl UpdateOptions {
@@ -162,6 +438,42 @@ impl UpdateOptions {
     pub fn query_options_mut(&mut self) -> &mut QueryOptions {
         &mut self.query_options
     }
+
+    /// Validates the literals inserted by the updates using the given [`LiteralValidator`]
+    ///
+    /// It applies to the `INSERT DATA` and `INSERT` operations and to the files loaded by `LOAD`.
+    /// The rejected literals make the update fail.
+    ///
+    /// ```
+    /// use oxigraph::model::*;
+    /// use oxigraph::sparql::UpdateOptions;
+    /// use oxigraph::MemoryStore;
+    ///
+    /// let store = MemoryStore::new();
+    /// let options = UpdateOptions::default().with_literal_validator(LiteralValidator::new().reject_invalid().canonicalize());
+    /// store.update_opt("INSERT DATA { <http://example.com> <http://example.com> \"01\"^^<http://www.w3.org/2001/XMLSchema#integer> }", options.clone())?;
+    /// assert!(store.contains(&Quad::new(NamedNode::new("http://example.com")?, NamedNode::new("http://example.com")?, Literal::from(1), None)));
+    /// assert!(store.update_opt("INSERT DATA { <http://example.com> <http://example.com> \"abc\"^^<http://www.w3.org/2001/XMLSchema#integer> }", options).is_err());
+    /// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    pub fn with_literal_validator(mut self, literal_validator: LiteralValidator) -> Self {
+        self.literal_validator = literal_validator;
+        self
+    }
+
+    /// Parses a SPARQL update like [`Update::parse`] but with the calls to the [custom aggregate functions](QueryOptions::with_custom_aggregate_function()) parsed as aggregates.
+    pub fn parse_update(&self, update: &str, base_iri: Option<&str>) -> Result<Update, ParseError> {
+        parse_update(
+            update,
+            base_iri,
+            self.query_options
+                .custom_aggregate_functions
+                .keys()
+                .cloned()
+                .collect(),
+        )
+    }
 }
 
 impl Default for UpdateOptions {
@@ -169,6 +481,7 @@ impl Default for UpdateOptions {
     fn default() -> Self {
         Self {
             query_options: QueryOptions::default(),
+            literal_validator: LiteralValidator::default(),
         }
     }
 }
@@ -176,7 +489,10 @@ impl Default for UpdateOptions {
 impl From<QueryOptions> for UpdateOptions {
     #[inline]
     fn from(query_options: QueryOptions) -> Self {
-        Self { query_options }
+        Self {
+            query_options,
+            literal_validator: LiteralValidator::default(),
+        }
     }
 }
 
@@ -192,6 +508,71 @@ pub(crate) fn evaluate_update<
 where
     io::Error: From<StoreOrParseError<W::Error>>,
 {
+    let mut update = update;
+    if !options.query_options.custom_aggregate_functions.is_empty() {
+        for operation in &mut update.operations {
+            if let GraphUpdateOperation::DeleteInsert { pattern, .. } = operation {
+                **pattern = resolve_custom_aggregates(
+                    std::mem::take(pattern.as_mut()),
+                    &options.query_options,
+                );
+            }
+        }
+    }
     SimpleUpdateEvaluator::new(read, write, update.base_iri.map(Rc::new), options)
         .eval_all(&update.operations)
 }
+
+/// Rewrites the calls to the custom aggregate functions of the queries not parsed with [`QueryOptions::parse_query`]
+fn resolve_query_custom_aggregates(query: Query, options: &QueryOptions) -> Query {
+    if options.custom_aggregate_functions.is_empty() {
+        return query;
+    }
+    match query {
+        Query::Select {
+            dataset,
+            pattern,
+            base_iri,
+        } => Query::Select {
+            dataset,
+            pattern: resolve_custom_aggregates(pattern, options),
+            base_iri,
+        },
+        Query::Construct {
+            template,
+            dataset,
+            pattern,
+            base_iri,
+        } => Query::Construct {
+            template,
+            dataset,
+            pattern: resolve_custom_aggregates(pattern, options),
+            base_iri,
+        },
+        Query::Describe {
+            dataset,
+            pattern,
+            base_iri,
+        } => Query::Describe {
+            dataset,
+            pattern: resolve_custom_aggregates(pattern, options),
+            base_iri,
+        },
+        Query::Ask {
+            dataset,
+            pattern,
+            base_iri,
+        } => Query::Ask {
+            dataset,
+            pattern: Rc::new(resolve_custom_aggregates(
+                Rc::try_unwrap(pattern).unwrap_or_else(|p| p.as_ref().clone()),
+                options,
+            )),
+            base_iri,
+        },
+    }
+}
+
+fn resolve_custom_aggregates(pattern: GraphPattern, options: &QueryOptions) -> GraphPattern {
+    pattern.resolve_custom_aggregates(&|name| options.custom_aggregate_functions.contains_key(name))
+}
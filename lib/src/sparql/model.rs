@@ -2,11 +2,15 @@ use crate::error::invalid_input_error;
 use crate::io::GraphFormat;
 use crate::io::GraphSerializer;
 use crate::model::*;
-use crate::sparql::csv_results::{read_tsv_results, write_csv_results, write_tsv_results};
+use crate::sparql::csv_results::{
+    read_tsv_results, write_csv_results, write_tsv_results, CsvResultsParser,
+};
 use crate::sparql::error::EvaluationError;
-use crate::sparql::json_results::write_json_results;
+use crate::sparql::json_results::{read_json_results, write_json_results};
 use crate::sparql::xml_results::{read_xml_results, write_xml_results};
 use rand::random;
+#[cfg(feature = "serde")]
+use serde::de::DeserializeOwned;
 use std::error::Error;
 use std::io::{BufRead, Write};
 use std::rc::Rc;
@@ -24,18 +28,16 @@ pub enum QueryResults {
 
 impl QueryResults {
     /// Reads a SPARQL query results serialization
+    ///
+    /// The CSV results are read with the default [`CsvResultsParser`](super::CsvResultsParser) heuristics.
     pub fn read(
         reader: impl BufRead + 'static,
         format: QueryResultsFormat,
     ) -> Result<Self, io::Error> {
         match format {
             QueryResultsFormat::Xml => read_xml_results(reader),
-            QueryResultsFormat::Json => Err(invalid_input_error(
-                "JSON SPARQL results format parsing has not been implemented yet",
-            )), //TODO: implement
-            QueryResultsFormat::Csv => Err(invalid_input_error(
-                "CSV and TSV SPARQL results format parsing is not implemented",
-            )),
+            QueryResultsFormat::Json => read_json_results(reader),
+            QueryResultsFormat::Csv => CsvResultsParser::new().read_results(reader),
             QueryResultsFormat::Tsv => read_tsv_results(reader),
         }
     }
@@ -212,6 +214,26 @@ impl QueryResultsFormat {
             None
         }
     }
+
+    /// Looks for a known format from a file extension.
+    ///
+    /// It supports some file extension aliases and is case insensitive.
+    ///
+    /// Example:
+    /// ```
+    /// use oxigraph::sparql::QueryResultsFormat;
+    ///
+    /// assert_eq!(QueryResultsFormat::from_extension("json"), Some(QueryResultsFormat::Json))
+    /// ```
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "srx" | "xml" => Some(QueryResultsFormat::Xml),
+            "srj" | "json" => Some(QueryResultsFormat::Json),
+            "csv" => Some(QueryResultsFormat::Csv),
+            "tsv" => Some(QueryResultsFormat::Tsv),
+            _ => None,
+        }
+    }
 }
 
 /// An iterator over [`QuerySolution`]s
@@ -257,6 +279,46 @@ impl QuerySolutionIter {
     pub fn variables(&self) -> &[Variable] {
         &*self.variables
     }
+
+    /// Deserializes each solution into a Rust value using [Serde](https://serde.rs/).
+    ///
+    /// The solutions are deserialized as maps from the bound variable names to their values or as sequences of their values.
+    /// Literals are converted to Rust numbers and booleans according to their datatypes
+    /// and the other terms to strings or to the [`NamedNode`] and [`xsd`](crate::model::xsd) types.
+    ///
+    /// Requires the `serde` feature.
+    ///
+    /// ```
+    /// use oxigraph::MemoryStore;
+    /// use oxigraph::model::*;
+    /// use oxigraph::sparql::QueryResults;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Person {
+    ///     id: NamedNode,
+    ///     age: u8,
+    ///     name: Option<String>,
+    /// }
+    ///
+    /// let store = MemoryStore::new();
+    /// let alice = NamedNode::new("http://example.com/alice")?;
+    /// store.insert(Quad::new(alice.clone(), NamedNode::new("http://example.com/age")?, Literal::from(31), None));
+    ///
+    /// if let QueryResults::Solutions(solutions) = store.query("SELECT ?id ?age ?name WHERE { ?id <http://example.com/age> ?age }")? {
+    ///     let people = solutions.deserialize::<Person>().collect::<Result<Vec<_>, _>>()?;
+    ///     assert_eq!(people[0].id, alice);
+    ///     assert_eq!(people[0].age, 31);
+    ///     assert_eq!(people[0].name, None);
+    /// }
+    /// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn deserialize<T: DeserializeOwned>(
+        self,
+    ) -> impl Iterator<Item = Result<T, EvaluationError>> {
+        self.map(|solution| T::deserialize(&solution?))
+    }
 }
 
 impl Iterator for QuerySolutionIter {
@@ -328,6 +390,16 @@ impl QuerySolution {
     pub fn values(&self) -> impl Iterator<Item = Option<&Term>> {
         self.values.iter().map(|v| v.as_ref())
     }
+
+    /// Deserializes the solution into a Rust value using [Serde](https://serde.rs/).
+    ///
+    /// See [`QuerySolutionIter::deserialize`] for the conversion rules.
+    ///
+    /// Requires the `serde` feature.
+    #[cfg(feature = "serde")]
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, EvaluationError> {
+        T::deserialize(self)
+    }
 }
 
 /// A utility trait to get values for a given variable or tuple position
@@ -1,3 +1,5 @@
+use crate::error::invalid_data_error;
+use crate::io::TextPosition;
 use crate::model::vocab::rdf;
 use crate::model::vocab::xsd;
 use crate::model::*;
@@ -9,13 +11,20 @@ use peg::str::LineCol;
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::error::Error;
+use std::io;
 use std::rc::Rc;
 use std::str::Chars;
 use std::str::FromStr;
 use std::{char, fmt};
 
 /// Parses a SPARQL query with an optional base IRI to resolve relative IRIs in the query
-pub fn parse_query(query: &str, base_iri: Option<&str>) -> Result<Query, ParseError> {
+///
+/// The calls to the functions named in `custom_aggregate_functions` are parsed as aggregates.
+pub fn parse_query(
+    query: &str,
+    base_iri: Option<&str>,
+    custom_aggregate_functions: HashSet<NamedNode>,
+) -> Result<Query, ParseError> {
     let mut state = ParserState {
         base_iri: if let Some(base_iri) = base_iri {
             Some(Iri::parse(base_iri.to_owned()).map_err(|e| ParseError {
@@ -28,19 +37,23 @@ pub fn parse_query(query: &str, base_iri: Option<&str>) -> Result<Query, ParseEr
         used_bnodes: HashSet::default(),
         currently_used_bnodes: HashSet::default(),
         aggregates: Vec::default(),
+        custom_aggregate_functions,
     };
 
     Ok(
-        parser::QueryUnit(&unescape_unicode_codepoints(query), &mut state).map_err(|e| {
-            ParseError {
-                inner: ParseErrorKind::Parser(e),
-            }
-        })?,
+        parser::QueryUnit(&unescape_unicode_codepoints(query), &mut state)
+            .map_err(|e| ParseError::from_peg(e, query))?,
     )
 }
 
 /// Parses a SPARQL update with an optional base IRI to resolve relative IRIs in the query
-pub fn parse_update(update: &str, base_iri: Option<&str>) -> Result<Update, ParseError> {
+///
+/// The calls to the functions named in `custom_aggregate_functions` are parsed as aggregates.
+pub fn parse_update(
+    update: &str,
+    base_iri: Option<&str>,
+    custom_aggregate_functions: HashSet<NamedNode>,
+) -> Result<Update, ParseError> {
     let mut state = ParserState {
         base_iri: if let Some(base_iri) = base_iri {
             Some(Iri::parse(base_iri.to_owned()).map_err(|e| ParseError {
@@ -53,14 +66,11 @@ pub fn parse_update(update: &str, base_iri: Option<&str>) -> Result<Update, Pars
         used_bnodes: HashSet::default(),
         currently_used_bnodes: HashSet::default(),
         aggregates: Vec::default(),
+        custom_aggregate_functions,
     };
 
-    let operations =
-        parser::UpdateInit(&unescape_unicode_codepoints(update), &mut state).map_err(|e| {
-            ParseError {
-                inner: ParseErrorKind::Parser(e),
-            }
-        })?;
+    let operations = parser::UpdateInit(&unescape_unicode_codepoints(update), &mut state)
+        .map_err(|e| ParseError::from_peg(e, update))?;
     Ok(Update {
         operations,
         base_iri: state.base_iri,
@@ -68,6 +78,17 @@ pub fn parse_update(update: &str, base_iri: Option<&str>) -> Result<Update, Pars
 }
 
 /// Error returned during SPARQL parsing.
+///
+/// The position of syntax errors in the query is available using [`position`](ParseError::position):
+/// ```
+/// use oxigraph::sparql::Query;
+///
+/// let error = Query::parse("SELECT * WHERE {\n  ?s ?p ?o )\n}", None).unwrap_err();
+/// let position = error.position().unwrap();
+/// assert_eq!(position.line(), 1);
+/// assert_eq!(position.column(), 11);
+/// assert_eq!(position.offset(), 28);
+/// ```
 #[derive(Debug)]
 pub struct ParseError {
     inner: ParseErrorKind,
@@ -76,7 +97,35 @@ pub struct ParseError {
 #[derive(Debug)]
 enum ParseErrorKind {
     InvalidBaseIri(IriParseError),
-    Parser(peg::error::ParseError<LineCol>),
+    Parser {
+        error: peg::error::ParseError<LineCol>,
+        position: TextPosition,
+    },
+}
+
+impl ParseError {
+    fn from_peg(error: peg::error::ParseError<LineCol>, input: &str) -> Self {
+        // The parser location is relative to the input with unescaped code points
+        let offset = original_offset(input, error.location.offset);
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let position = TextPosition::new(
+            before.matches('\n').count() as u64,
+            (offset - line_start) as u64,
+            offset as u64,
+        );
+        Self {
+            inner: ParseErrorKind::Parser { error, position },
+        }
+    }
+
+    /// The position of the error in the query if it is a syntax error
+    pub fn position(&self) -> Option<TextPosition> {
+        match &self.inner {
+            ParseErrorKind::InvalidBaseIri(_) => None,
+            ParseErrorKind::Parser { position, .. } => Some(*position),
+        }
+    }
 }
 
 impl fmt::Display for ParseError {
@@ -85,13 +134,25 @@ impl fmt::Display for ParseError {
             ParseErrorKind::InvalidBaseIri(e) => {
                 write!(f, "Invalid SPARQL base IRI provided: {}", e)
             }
-            ParseErrorKind::Parser(e) => e.fmt(f),
+            ParseErrorKind::Parser { error, position } => write!(
+                f,
+                "error at line {} column {}: expected {}",
+                position.line() + 1,
+                position.column() + 1,
+                error.expected
+            ),
         }
     }
 }
 
 impl Error for ParseError {}
 
+impl From<ParseError> for io::Error {
+    fn from(error: ParseError) -> Self {
+        invalid_data_error(error)
+    }
+}
+
 struct FocusedTriplePattern<F> {
     focus: F,
     patterns: Vec<TriplePattern>,
@@ -142,34 +203,155 @@ impl From<PropertyPathExpression> for VariableOrPropertyPath {
     }
 }
 
+impl From<NamedNodeOrVariable> for VariableOrPropertyPath {
+    fn from(p: NamedNodeOrVariable) -> Self {
+        match p {
+            NamedNodeOrVariable::NamedNode(p) => PropertyPathExpression::NamedNode(p).into(),
+            NamedNodeOrVariable::Variable(v) => v.into(),
+        }
+    }
+}
+
+/// An object with its RDF-star annotations (`{| ... |}`)
+struct AnnotatedTerm {
+    term: TermOrVariable,
+    annotations: Vec<(NamedNodeOrVariable, Vec<AnnotatedTerm>)>,
+}
+
+impl From<TermOrVariable> for AnnotatedTerm {
+    fn from(term: TermOrVariable) -> Self {
+        Self {
+            term,
+            annotations: Vec::new(),
+        }
+    }
+}
+
+/// An object with its RDF-star annotations (`{| ... |}`) in a property path context
+struct AnnotatedTermPath {
+    term: TermOrVariable,
+    annotations: Vec<(VariableOrPropertyPath, Vec<AnnotatedTermPath>)>,
+}
+
+impl From<TermOrVariable> for AnnotatedTermPath {
+    fn from(term: TermOrVariable) -> Self {
+        Self {
+            term,
+            annotations: Vec::new(),
+        }
+    }
+}
+
+impl From<AnnotatedTerm> for AnnotatedTermPath {
+    fn from(term: AnnotatedTerm) -> Self {
+        Self {
+            term: term.term,
+            annotations: term
+                .annotations
+                .into_iter()
+                .map(|(p, os)| (p.into(), os.into_iter().map(Self::from).collect()))
+                .collect(),
+        }
+    }
+}
+
+fn add_to_triple_patterns(
+    subject: TermOrVariable,
+    predicate: NamedNodeOrVariable,
+    object: AnnotatedTerm,
+    patterns: &mut Vec<TriplePattern>,
+) {
+    let triple = TriplePattern::new(subject, predicate, object.term);
+    for (p, os) in object.annotations {
+        for o in os {
+            add_to_triple_patterns(triple.clone().into(), p.clone(), o, patterns)
+        }
+    }
+    patterns.push(triple)
+}
+
 fn add_to_triple_or_path_patterns(
     subject: TermOrVariable,
     predicate: impl Into<VariableOrPropertyPath>,
-    object: TermOrVariable,
+    object: AnnotatedTermPath,
     patterns: &mut Vec<TripleOrPathPattern>,
-) {
+) -> Result<(), &'static str> {
     match predicate.into() {
         VariableOrPropertyPath::Variable(p) => {
-            patterns.push(TriplePattern::new(subject, p, object).into())
+            add_triple_to_triple_or_path_patterns(subject, p, object, patterns)?
         }
         VariableOrPropertyPath::PropertyPath(p) => match p {
             PropertyPathExpression::NamedNode(p) => {
-                patterns.push(TriplePattern::new(subject, p, object).into())
+                add_triple_to_triple_or_path_patterns(subject, p, object, patterns)?
             }
             PropertyPathExpression::Reverse(p) => {
-                add_to_triple_or_path_patterns(object, *p, subject, patterns)
+                if !object.annotations.is_empty() {
+                    return Err("Annotations are not allowed on property paths");
+                }
+                add_to_triple_or_path_patterns(object.term, *p, subject.into(), patterns)?
             }
             PropertyPathExpression::Sequence(a, b) => {
-                let middle = BlankNode::default();
-                add_to_triple_or_path_patterns(subject, *a, middle.clone().into(), patterns);
-                add_to_triple_or_path_patterns(middle.into(), *b, object, patterns);
+                if !object.annotations.is_empty() {
+                    return Err("Annotations are not allowed on property paths");
+                }
+                let middle = TermOrVariable::from(BlankNode::default());
+                add_to_triple_or_path_patterns(subject, *a, middle.clone().into(), patterns)?;
+                add_to_triple_or_path_patterns(middle, *b, object, patterns)?;
             }
-            path => patterns.push(TripleOrPathPattern::Path {
-                subject,
-                path,
-                object,
-            }),
+            path => {
+                if !object.annotations.is_empty() {
+                    return Err("Annotations are not allowed on property paths");
+                }
+                patterns.push(TripleOrPathPattern::Path {
+                    subject,
+                    path,
+                    object: object.term,
+                })
+            }
+        },
+    }
+    Ok(())
+}
+
+fn add_triple_to_triple_or_path_patterns(
+    subject: TermOrVariable,
+    predicate: impl Into<NamedNodeOrVariable>,
+    object: AnnotatedTermPath,
+    patterns: &mut Vec<TripleOrPathPattern>,
+) -> Result<(), &'static str> {
+    let triple = TriplePattern::new(subject, predicate, object.term);
+    for (p, os) in object.annotations {
+        for o in os {
+            add_to_triple_or_path_patterns(triple.clone().into(), p.clone(), o, patterns)?
+        }
+    }
+    patterns.push(triple.into());
+    Ok(())
+}
+
+/// Converts a ground quoted triple pattern into a [`Triple`]
+fn triple_pattern_to_triple(pattern: TriplePattern) -> Option<Triple> {
+    Some(Triple {
+        subject: match pattern.subject {
+            TermOrVariable::Term(Term::NamedNode(t)) => t.into(),
+            TermOrVariable::Term(Term::BlankNode(t)) => t.into(),
+            TermOrVariable::Triple(t) => triple_pattern_to_triple(*t)?.into(),
+            _ => return None,
         },
+        predicate: if let NamedNodeOrVariable::NamedNode(t) = pattern.predicate {
+            t
+        } else {
+            return None;
+        },
+        object: term_or_variable_to_term(pattern.object)?,
+    })
+}
+
+fn term_or_variable_to_term(term: TermOrVariable) -> Option<Term> {
+    match term {
+        TermOrVariable::Term(t) => Some(t),
+        TermOrVariable::Triple(t) => Some(triple_pattern_to_triple(*t)?.into()),
+        TermOrVariable::Variable(_) => None,
     }
 }
 
@@ -488,6 +670,7 @@ pub struct ParserState {
     used_bnodes: HashSet<BlankNode>,
     currently_used_bnodes: HashSet<BlankNode>,
     aggregates: Vec<Vec<(Variable, AggregationFunction)>>,
+    custom_aggregate_functions: HashSet<NamedNode>,
 }
 
 impl ParserState {
@@ -521,6 +704,24 @@ pub fn unescape_unicode_codepoints(input: &str) -> Cow<'_, str> {
     }
 }
 
+/// Maps an offset in the output of [`unescape_unicode_codepoints`] to an offset in its input
+fn original_offset(input: &str, unescaped_offset: usize) -> usize {
+    if !needs_unescape_unicode_codepoints(input) {
+        return unescaped_offset;
+    }
+    let mut iter = UnescapeUnicodeCharIterator::new(input);
+    let mut unescaped_read = 0;
+    while unescaped_read < unescaped_offset {
+        if let Some(c) = iter.next() {
+            unescaped_read += c.len_utf8();
+        } else {
+            break;
+        }
+    }
+    // The buffer contains the input characters that are not returned yet
+    input.len() - iter.iter.as_str().len() - iter.buffer.len()
+}
+
 fn needs_unescape_unicode_codepoints(input: &str) -> bool {
     let bytes = input.as_bytes();
     for i in 1..bytes.len() {
@@ -1141,6 +1342,7 @@ parser! {
                 subject: match q.subject {
                     TermOrVariable::Term(Term::NamedNode(t)) => t.into(),
                     TermOrVariable::Term(Term::BlankNode(t)) => t.into(),
+                    TermOrVariable::Triple(t) => triple_pattern_to_triple(*t).ok_or(())?.into(),
                     _ => return Err(())
                 },
                 predicate: if let NamedNodeOrVariable::NamedNode(t) = q.predicate {
@@ -1148,11 +1350,7 @@ parser! {
                 } else {
                     return Err(())
                 },
-                object: if let TermOrVariable::Term(t) = q.object {
-                    t
-                } else {
-                    return Err(())
-                },
+                object: term_or_variable_to_term(q.object).ok_or(())?,
                 graph_name: match q.graph_name {
                     Some(NamedNodeOrVariable::NamedNode(t)) => t.into(),
                     None => GraphName::DefaultGraph,
@@ -1301,12 +1499,34 @@ parser! {
 
         //[65]
         rule DataBlockValue() -> Option<Term> =
+            t:QuotedTriple() { Some(t.into()) } /
             i:iri() { Some(i.into()) } /
             l:RDFLiteral() { Some(l.into()) } /
             l:NumericLiteral() { Some(l.into()) } /
             l:BooleanLiteral() { Some(l.into()) } /
             i("UNDEF") { None }
 
+        rule QuotedTriple() -> Triple = "<<" _ s:DataValueTerm() _ p:QuotedTriple_predicate() _ o:DataValueTerm() _ ">>" {?
+            match s {
+                Term::NamedNode(s) => Ok(Subject::from(s)),
+                Term::BlankNode(s) => Ok(Subject::from(s)),
+                Term::Triple(s) => Ok(Subject::Triple(s)),
+                Term::Literal(_) => Err("Literals are not allowed in subject position"),
+            }.map(|subject| Triple {
+                subject,
+                predicate: p,
+                object: o
+            })
+        }
+        rule QuotedTriple_predicate() -> NamedNode = i:iri() { i } / "a" { rdf::TYPE.into_owned() }
+
+        rule DataValueTerm() -> Term =
+            t:QuotedTriple() { t.into() } /
+            i:iri() { i.into() } /
+            l:RDFLiteral() { l.into() } /
+            l:NumericLiteral() { l.into() } /
+            l:BooleanLiteral() { l.into() }
+
         //[66]
         rule MinusGraphPattern() -> PartialGraphPattern = i("MINUS") _ p: GroupGraphPattern() {
             PartialGraphPattern::Minus(p)
@@ -1360,7 +1580,7 @@ parser! {
                 let mut patterns = po.patterns;
                 for (p, os) in po.focus {
                     for o in os {
-                        patterns.push(TriplePattern::new(s.clone(), p.clone(), o))
+                        add_to_triple_patterns(s.clone(), p.clone(), o, &mut patterns)
                     }
                 }
                 patterns
@@ -1370,26 +1590,26 @@ parser! {
                 patterns.extend(po.patterns);
                 for (p, os) in po.focus {
                     for o in os {
-                        patterns.push(TriplePattern::new(s.focus.clone(), p.clone(), o))
+                        add_to_triple_patterns(s.focus.clone(), p.clone(), o, &mut patterns)
                     }
                 }
                 patterns
             }
 
         //[76]
-        rule PropertyList() -> FocusedTriplePattern<Vec<(NamedNodeOrVariable,Vec<TermOrVariable>)>> =
+        rule PropertyList() -> FocusedTriplePattern<Vec<(NamedNodeOrVariable,Vec<AnnotatedTerm>)>> =
             PropertyListNotEmpty() /
             { FocusedTriplePattern::default() }
 
         //[77]
-        rule PropertyListNotEmpty() -> FocusedTriplePattern<Vec<(NamedNodeOrVariable,Vec<TermOrVariable>)>> = l:PropertyListNotEmpty_item() **<1,> (";" _) {
-            l.into_iter().fold(FocusedTriplePattern::<Vec<(NamedNodeOrVariable,Vec<TermOrVariable>)>>::default(), |mut a, b| {
+        rule PropertyListNotEmpty() -> FocusedTriplePattern<Vec<(NamedNodeOrVariable,Vec<AnnotatedTerm>)>> = l:PropertyListNotEmpty_item() **<1,> (";" _) {
+            l.into_iter().fold(FocusedTriplePattern::<Vec<(NamedNodeOrVariable,Vec<AnnotatedTerm>)>>::default(), |mut a, b| {
                 a.focus.push(b.focus);
                 a.patterns.extend(b.patterns);
                 a
             })
         }
-        rule PropertyListNotEmpty_item() -> FocusedTriplePattern<(NamedNodeOrVariable,Vec<TermOrVariable>)> = p:Verb() _ o:ObjectList() _ {
+        rule PropertyListNotEmpty_item() -> FocusedTriplePattern<(NamedNodeOrVariable,Vec<AnnotatedTerm>)> = p:Verb() _ o:ObjectList() _ {
             FocusedTriplePattern {
                 focus: (p, o.focus),
                 patterns: o.patterns
@@ -1400,47 +1620,59 @@ parser! {
         rule Verb() -> NamedNodeOrVariable = VarOrIri() / "a" { rdf::TYPE.into_owned().into() }
 
         //[79]
-        rule ObjectList() -> FocusedTriplePattern<Vec<TermOrVariable>> = o:ObjectList_item() **<1,> ("," _) {
-            o.into_iter().fold(FocusedTriplePattern::<Vec<TermOrVariable>>::default(), |mut a, b| {
+        rule ObjectList() -> FocusedTriplePattern<Vec<AnnotatedTerm>> = o:ObjectList_item() **<1,> ("," _) {
+            o.into_iter().fold(FocusedTriplePattern::<Vec<AnnotatedTerm>>::default(), |mut a, b| {
                 a.focus.push(b.focus);
-                a.patterns.extend_from_slice(&b.patterns);
+                a.patterns.extend(b.patterns);
                 a
             })
         }
-        rule ObjectList_item() -> FocusedTriplePattern<TermOrVariable> = o:Object() _ { o }
+        rule ObjectList_item() -> FocusedTriplePattern<AnnotatedTerm> = o:Object() _ { o }
 
         //[80]
-        rule Object() -> FocusedTriplePattern<TermOrVariable> = GraphNode()
+        rule Object() -> FocusedTriplePattern<AnnotatedTerm> = g:GraphNode() _ a:AnnotationPattern()? {
+            if let Some(a) = a {
+                let mut patterns = g.patterns;
+                patterns.extend(a.patterns);
+                FocusedTriplePattern {
+                    focus: AnnotatedTerm {
+                        term: g.focus,
+                        annotations: a.focus
+                    },
+                    patterns
+                }
+            } else {
+                FocusedTriplePattern {
+                    focus: g.focus.into(),
+                    patterns: g.patterns
+                }
+            }
+        }
 
         //[81]
         rule TriplesSameSubjectPath() -> Vec<TripleOrPathPattern> =
-            s:VarOrTerm() _ po:PropertyListPathNotEmpty() {
+            s:VarOrTerm() _ po:PropertyListPathNotEmpty() {?
                 let mut patterns = po.patterns;
-                for (p, os) in po.focus {
-                    for o in os {
-                        add_to_triple_or_path_patterns(s.clone(), p.clone(), o, &mut patterns);
-                    }
-                }
-                patterns
+                po.focus.into_iter().try_for_each(|(p, os)| os.into_iter().try_for_each(|o| {
+                    add_to_triple_or_path_patterns(s.clone(), p.clone(), o, &mut patterns)
+                })).map(|_| patterns)
             } /
-            s:TriplesNodePath() _ po:PropertyListPath() {
+            s:TriplesNodePath() _ po:PropertyListPath() {?
                 let mut patterns = s.patterns;
                     patterns.extend(po.patterns);
-                for (p, os) in po.focus {
-                    for o in os {
-                        add_to_triple_or_path_patterns(s.focus.clone(), p.clone(), o, &mut patterns);
-                    }
-                }
-                patterns
+                let subject = s.focus;
+                po.focus.into_iter().try_for_each(|(p, os)| os.into_iter().try_for_each(|o| {
+                    add_to_triple_or_path_patterns(subject.clone(), p.clone(), o, &mut patterns)
+                })).map(|_| patterns)
             }
 
         //[82]
-        rule PropertyListPath() -> FocusedTripleOrPathPattern<Vec<(VariableOrPropertyPath,Vec<TermOrVariable>)>> =
+        rule PropertyListPath() -> FocusedTripleOrPathPattern<Vec<(VariableOrPropertyPath,Vec<AnnotatedTermPath>)>> =
             PropertyListPathNotEmpty() /
             { FocusedTripleOrPathPattern::default() }
 
         //[83]
-        rule PropertyListPathNotEmpty() -> FocusedTripleOrPathPattern<Vec<(VariableOrPropertyPath,Vec<TermOrVariable>)>> = hp:(VerbPath() / VerbSimple()) _ ho:ObjectListPath() _ t:PropertyListPathNotEmpty_item()* {
+        rule PropertyListPathNotEmpty() -> FocusedTripleOrPathPattern<Vec<(VariableOrPropertyPath,Vec<AnnotatedTermPath>)>> = hp:(VerbPath() / VerbSimple()) _ ho:ObjectListPath() _ t:PropertyListPathNotEmpty_item()* {
                 t.into_iter().flat_map(|e| e.into_iter()).fold(FocusedTripleOrPathPattern {
                     focus: vec![(hp, ho.focus)],
                     patterns: ho.patterns
@@ -1450,12 +1682,12 @@ parser! {
                     a
                 })
         }
-        rule PropertyListPathNotEmpty_item() -> Option<FocusedTriplePattern<(VariableOrPropertyPath,Vec<TermOrVariable>)>> = ";" _ c:PropertyListPathNotEmpty_item_content()? {
+        rule PropertyListPathNotEmpty_item() -> Option<FocusedTriplePattern<(VariableOrPropertyPath,Vec<AnnotatedTermPath>)>> = ";" _ c:PropertyListPathNotEmpty_item_content()? {
             c
         }
-        rule PropertyListPathNotEmpty_item_content() -> FocusedTriplePattern<(VariableOrPropertyPath,Vec<TermOrVariable>)> = p:(VerbPath() / VerbSimple()) _ o:ObjectList() _ {
+        rule PropertyListPathNotEmpty_item_content() -> FocusedTriplePattern<(VariableOrPropertyPath,Vec<AnnotatedTermPath>)> = p:(VerbPath() / VerbSimple()) _ o:ObjectList() _ {
             FocusedTriplePattern {
-                focus: (p, o.focus),
+                focus: (p, o.focus.into_iter().map(AnnotatedTermPath::from).collect()),
                 patterns: o.patterns
             }
         }
@@ -1471,17 +1703,34 @@ parser! {
         }
 
         //[86]
-        rule ObjectListPath() -> FocusedTripleOrPathPattern<Vec<TermOrVariable>> = o:ObjectPath_item() **<1,> ("," _) {
-            o.into_iter().fold(FocusedTripleOrPathPattern::<Vec<TermOrVariable>>::default(), |mut a, b| {
+        rule ObjectListPath() -> FocusedTripleOrPathPattern<Vec<AnnotatedTermPath>> = o:ObjectPath_item() **<1,> ("," _) {
+            o.into_iter().fold(FocusedTripleOrPathPattern::<Vec<AnnotatedTermPath>>::default(), |mut a, b| {
                 a.focus.push(b.focus);
                 a.patterns.extend(b.patterns);
                 a
             })
         }
-        rule ObjectPath_item() -> FocusedTripleOrPathPattern<TermOrVariable> = o:ObjectPath() _ { o }
+        rule ObjectPath_item() -> FocusedTripleOrPathPattern<AnnotatedTermPath> = o:ObjectPath() _ { o }
 
         //[87]
-        rule ObjectPath() -> FocusedTripleOrPathPattern<TermOrVariable> = GraphNodePath()
+        rule ObjectPath() -> FocusedTripleOrPathPattern<AnnotatedTermPath> = g:GraphNodePath() _ a:AnnotationPatternPath()? {
+            if let Some(a) = a {
+                let mut patterns = g.patterns;
+                patterns.extend(a.patterns);
+                FocusedTripleOrPathPattern {
+                    focus: AnnotatedTermPath {
+                        term: g.focus,
+                        annotations: a.focus
+                    },
+                    patterns
+                }
+            } else {
+                FocusedTripleOrPathPattern {
+                    focus: g.focus.into(),
+                    patterns: g.patterns
+                }
+            }
+        }
 
         //[88]
         rule Path() -> PropertyPathExpression = PathAlternative()
@@ -1567,7 +1816,7 @@ parser! {
             let mut bnode = TermOrVariable::from(BlankNode::default());
             for (p, os) in po.focus {
                 for o in os {
-                    patterns.push(TriplePattern::new(bnode.clone(), p.clone(), o));
+                    add_to_triple_patterns(bnode.clone(), p.clone(), o, &mut patterns);
                 }
             }
             FocusedTriplePattern {
@@ -1580,18 +1829,15 @@ parser! {
         rule TriplesNodePath() -> FocusedTripleOrPathPattern<TermOrVariable> = CollectionPath() / BlankNodePropertyListPath()
 
         //[101]
-        rule BlankNodePropertyListPath() -> FocusedTripleOrPathPattern<TermOrVariable> = "[" _ po:PropertyListPathNotEmpty() _ "]" {
+        rule BlankNodePropertyListPath() -> FocusedTripleOrPathPattern<TermOrVariable> = "[" _ po:PropertyListPathNotEmpty() _ "]" {?
             let mut patterns: Vec<TripleOrPathPattern> = Vec::default();
             let mut bnode = TermOrVariable::from(BlankNode::default());
-            for (p, os) in po.focus {
-                for o in os {
-                    add_to_triple_or_path_patterns(bnode.clone(), p.clone(), o, &mut patterns);
-                }
-            }
-            FocusedTripleOrPathPattern {
+            po.focus.into_iter().try_for_each(|(p, os)| os.into_iter().try_for_each(|o| {
+                add_to_triple_or_path_patterns(bnode.clone(), p.clone(), o, &mut patterns)
+            })).map(|_| FocusedTripleOrPathPattern {
                 focus: bnode,
                 patterns
-            }
+            })
         }
 
         //[102]
@@ -1642,9 +1888,27 @@ parser! {
 
         //[106]
         rule VarOrTerm() -> TermOrVariable =
+            t:QuotedTP() { t.into() } /
             v:Var() { v.into() } /
             t:GraphTerm() { t.into() }
 
+        rule QuotedTP() -> TriplePattern = "<<" _ s:qtSubjectOrObject() _ p:Verb() _ o:qtSubjectOrObject() _ ">>" {
+            TriplePattern::new(s, p, o)
+        }
+
+        rule qtSubjectOrObject() -> TermOrVariable =
+            t:QuotedTP() { t.into() } /
+            v:Var() { v.into() } /
+            b:BlankNode() { b.into() } /
+            i:iri() { i.into() } /
+            l:RDFLiteral() { l.into() } /
+            l:NumericLiteral() { l.into() } /
+            l:BooleanLiteral() { l.into() }
+
+        rule AnnotationPattern() -> FocusedTriplePattern<Vec<(NamedNodeOrVariable,Vec<AnnotatedTerm>)>> = "{|" _ a:PropertyListNotEmpty() _ "|}" { a }
+
+        rule AnnotationPatternPath() -> FocusedTripleOrPathPattern<Vec<(VariableOrPropertyPath,Vec<AnnotatedTermPath>)>> = "{|" _ a:PropertyListPathNotEmpty() _ "|}" { a }
+
         //[107]
         rule VarOrIri() -> NamedNodeOrVariable =
             v:Var() { v.into() } /
@@ -1735,6 +1999,8 @@ parser! {
         //[119]
         rule PrimaryExpression() -> Expression =
             BrackettedExpression() /
+            ExprQuotedTP() /
+            a:CustomAggregate() {? state.new_aggregation(a).map(|v| v.into()) } /
             iriOrFunction() /
             v:Var() { v.into() } /
             l:RDFLiteral() { l.into() } /
@@ -1742,6 +2008,21 @@ parser! {
             l:BooleanLiteral() { l.into() } /
             BuiltInCall()
 
+        rule ExprQuotedTP() -> Expression = "<<" _ s:ExprVarOrTerm() _ p:Verb() _ o:ExprVarOrTerm() _ ">>" {
+            Expression::FunctionCall(Function::Triple, vec![s, match p {
+                NamedNodeOrVariable::NamedNode(p) => p.into(),
+                NamedNodeOrVariable::Variable(p) => p.into(),
+            }, o])
+        }
+
+        rule ExprVarOrTerm() -> Expression =
+            ExprQuotedTP() /
+            i:iri() { i.into() } /
+            l:RDFLiteral() { l.into() } /
+            l:NumericLiteral() { l.into() } /
+            l:BooleanLiteral() { l.into() } /
+            v:Var() { v.into() }
+
         //[120]
         rule BrackettedExpression() -> Expression = "(" _ e:Expression() _ ")" { e }
 
@@ -1798,6 +2079,11 @@ parser! {
             i("isBLANK") "(" _ e:Expression() _ ")" { Expression::FunctionCall(Function::IsBlank, vec![e]) } /
             i("isLITERAL") "(" _ e:Expression() _ ")" { Expression::FunctionCall(Function::IsLiteral, vec![e]) } /
             i("isNUMERIC") "(" _ e:Expression() _ ")" { Expression::FunctionCall(Function::IsNumeric, vec![e]) } /
+            i("TRIPLE") _ "(" _ s:Expression() _ "," _ p:Expression() _ "," _ o:Expression() _ ")" { Expression::FunctionCall(Function::Triple, vec![s, p, o]) } /
+            i("SUBJECT") _ "(" _ e:Expression() _ ")" { Expression::FunctionCall(Function::Subject, vec![e]) } /
+            i("PREDICATE") _ "(" _ e:Expression() _ ")" { Expression::FunctionCall(Function::Predicate, vec![e]) } /
+            i("OBJECT") _ "(" _ e:Expression() _ ")" { Expression::FunctionCall(Function::Object, vec![e]) } /
+            i("isTRIPLE") _ "(" _ e:Expression() _ ")" { Expression::FunctionCall(Function::IsTriple, vec![e]) } /
             RegexExpression() /
             ExistsFunc() /
             NotExistsFunc()
@@ -1847,6 +2133,18 @@ parser! {
             name:iri() _ "(" _ i("DISTINCT") _ e:Expression() _ ")" { AggregationFunction::Custom { name, expr: Box::new(e), distinct: true } } /
             name:iri() _ "(" _ e:Expression() _ ")" { AggregationFunction::Custom { name, expr: Box::new(e), distinct: false } }
 
+        // DISTINCT is only allowed in the calls to aggregates so the calls using it are always aggregates
+        rule CustomAggregate() -> AggregationFunction =
+            name:iri() _ "(" _ i("DISTINCT") _ e:Expression() _ ")" { AggregationFunction::Custom { name, expr: Box::new(e), distinct: true } } /
+            name:CustomAggregateName() _ "(" _ e:Expression() _ ")" { AggregationFunction::Custom { name, expr: Box::new(e), distinct: false } }
+        rule CustomAggregateName() -> NamedNode = name:iri() {?
+            if state.custom_aggregate_functions.contains(&name) {
+                Ok(name)
+            } else {
+                Err("custom aggregate function")
+            }
+        }
+
         //[128]
         rule iriOrFunction() -> Expression = i: iri() _ a: ArgList()? {
             match a {
@@ -2070,12 +2368,78 @@ parser! {
         //comment
         rule comment() = quiet! { ['#'] (!['\r' | '\n'] [_])* }
 
-        rule i(literal: &'static str) = input: $([_]*<{literal.len()}>) {?
+        // The failures are reported at the keyword beginning and not at the end of the consumed characters
+        rule i(literal: &'static str) = quiet! { input: $([_]*<{literal.len()}>) {?
             if input.eq_ignore_ascii_case(literal) {
                 Ok(())
             } else {
                 Err(literal)
             }
-        }
+        } } / "" {? Err(literal) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_position() {
+        let error =
+            parse_query("SELECT * WHERE { ?s ?p ?o . ) }", None, HashSet::new()).unwrap_err();
+        let position = error.position().unwrap();
+        assert_eq!(position.line(), 0);
+        assert_eq!(position.column(), 28);
+        assert_eq!(position.offset(), 28);
+
+        let error = parse_update(
+            "INSERT DATA {\n  <http://example.com/s> <http://example.com/p> ) .\n}",
+            None,
+            HashSet::new(),
+        )
+        .unwrap_err();
+        let position = error.position().unwrap();
+        assert_eq!(position.line(), 1);
+        assert_eq!(position.column(), 48);
+        assert_eq!(position.offset(), 62);
+
+        assert!(
+            parse_query("SELECT * WHERE {}", Some("foo"), HashSet::new())
+                .unwrap_err()
+                .position()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_error_position_with_escaped_codepoints() {
+        let error = parse_query(
+            "SELECT * WHERE { <http://example.com/\\u00e9> ?p ?o . ) }",
+            None,
+            HashSet::new(),
+        )
+        .unwrap_err();
+        let position = error.position().unwrap();
+        assert_eq!(position.column(), 53);
+        assert_eq!(position.offset(), 53);
+    }
+
+    #[test]
+    fn test_custom_aggregate_functions() {
+        let name = NamedNode::new_unchecked("http://example.com/agg");
+        let query = "SELECT (<http://example.com/agg>(?o) AS ?a) WHERE { ?s ?p ?o }";
+        let is_aggregate = |query: &Query| {
+            if let Query::Select { pattern, .. } = query {
+                pattern.to_string().contains("(group ")
+            } else {
+                false
+            }
+        };
+        assert!(!is_aggregate(
+            &parse_query(query, None, HashSet::new()).unwrap()
+        ));
+        assert!(is_aggregate(
+            &parse_query(query, None, vec![name].into_iter().collect()).unwrap()
+        ));
     }
 }
@@ -1,3 +1,5 @@
+use crate::model::xsd::DerivedIntegerType;
+use crate::model::NamedNode;
 use crate::sparql::algebra::GraphPattern;
 use crate::sparql::model::Variable;
 use crate::store::numeric_encoder::{EncodedTerm, StrId};
@@ -74,12 +76,14 @@ pub enum PlanNode<I: StrId> {
     Project {
         child: Rc<PlanNode<I>>,
         mapping: Rc<Vec<(usize, usize)>>, // pairs of (variable key in child, variable key in output)
+        child_variables: Rc<Vec<Variable>>,
     },
     Aggregate {
         // By definition the group by key are the range 0..key_mapping.len()
         child: Rc<PlanNode<I>>,
         key_mapping: Rc<Vec<(usize, usize)>>, // aggregate key pairs of (variable key in child, variable key in output)
         aggregates: Rc<Vec<(PlanAggregation<I>, usize)>>,
+        child_variables: Rc<Vec<Variable>>,
     },
 }
 
@@ -110,18 +114,10 @@ impl<I: StrId> PlanNode<I> {
                 object,
                 graph_name,
             } => {
-                if let PatternValue::Variable(var) = subject {
-                    set.insert(*var);
-                }
-                if let PatternValue::Variable(var) = predicate {
-                    set.insert(*var);
-                }
-                if let PatternValue::Variable(var) = object {
-                    set.insert(*var);
-                }
-                if let PatternValue::Variable(var) = graph_name {
-                    set.insert(*var);
-                }
+                subject.add_variables(set);
+                predicate.add_variables(set);
+                object.add_variables(set);
+                graph_name.add_variables(set);
                 child.add_maybe_bound_variables(set);
             }
             PlanNode::PathPatternJoin {
@@ -131,15 +127,9 @@ impl<I: StrId> PlanNode<I> {
                 graph_name,
                 ..
             } => {
-                if let PatternValue::Variable(var) = subject {
-                    set.insert(*var);
-                }
-                if let PatternValue::Variable(var) = object {
-                    set.insert(*var);
-                }
-                if let PatternValue::Variable(var) = graph_name {
-                    set.insert(*var);
-                }
+                subject.add_variables(set);
+                object.add_variables(set);
+                graph_name.add_variables(set);
                 child.add_maybe_bound_variables(set);
             }
             PlanNode::Filter { child, expression } => {
@@ -171,7 +161,7 @@ impl<I: StrId> PlanNode<I> {
             | PlanNode::HashDeduplicate { child }
             | PlanNode::Skip { child, .. }
             | PlanNode::Limit { child, .. } => child.add_maybe_bound_variables(set),
-            PlanNode::Project { mapping, child } => {
+            PlanNode::Project { mapping, child, .. } => {
                 let child_bound = child.maybe_bound_variables();
                 for (child_i, output_i) in mapping.iter() {
                     if child_bound.contains(child_i) {
@@ -193,19 +183,41 @@ impl<I: StrId> PlanNode<I> {
     }
 }
 
-#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash)]
+#[derive(Eq, PartialEq, Debug, Clone, Hash)]
 pub enum PatternValue<I: StrId> {
     Constant(EncodedTerm<I>),
     Variable(usize),
+    Triple(Box<TriplePatternValue<I>>),
 }
 
 impl<I: StrId> PatternValue<I> {
     pub fn is_var(&self) -> bool {
         match self {
-            PatternValue::Constant(_) => false,
+            PatternValue::Constant(_) | PatternValue::Triple(_) => false,
             PatternValue::Variable(_) => true,
         }
     }
+
+    fn add_variables(&self, set: &mut BTreeSet<usize>) {
+        match self {
+            PatternValue::Constant(_) => (),
+            PatternValue::Variable(v) => {
+                set.insert(*v);
+            }
+            PatternValue::Triple(t) => {
+                t.subject.add_variables(set);
+                t.predicate.add_variables(set);
+                t.object.add_variables(set);
+            }
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Hash)]
+pub struct TriplePatternValue<I: StrId> {
+    pub subject: PatternValue<I>,
+    pub predicate: PatternValue<I>,
+    pub object: PatternValue<I>,
 }
 
 #[derive(Eq, PartialEq, Debug, Clone, Hash)]
@@ -269,6 +281,9 @@ pub enum PlanExpression<I: StrId> {
     Seconds(Box<PlanExpression<I>>),
     Timezone(Box<PlanExpression<I>>),
     Tz(Box<PlanExpression<I>>),
+    AdjustDateTimeToTimezone(Box<PlanExpression<I>>, Option<Box<PlanExpression<I>>>),
+    AdjustDateToTimezone(Box<PlanExpression<I>>, Option<Box<PlanExpression<I>>>),
+    AdjustTimeToTimezone(Box<PlanExpression<I>>, Option<Box<PlanExpression<I>>>),
     Now,
     UUID,
     StrUUID,
@@ -290,6 +305,15 @@ pub enum PlanExpression<I: StrId> {
     IsBlank(Box<PlanExpression<I>>),
     IsLiteral(Box<PlanExpression<I>>),
     IsNumeric(Box<PlanExpression<I>>),
+    IsTriple(Box<PlanExpression<I>>),
+    Triple(
+        Box<PlanExpression<I>>,
+        Box<PlanExpression<I>>,
+        Box<PlanExpression<I>>,
+    ),
+    Subject(Box<PlanExpression<I>>),
+    Predicate(Box<PlanExpression<I>>),
+    Object(Box<PlanExpression<I>>),
     Regex(
         Box<PlanExpression<I>>,
         Box<PlanExpression<I>>,
@@ -300,13 +324,18 @@ pub enum PlanExpression<I: StrId> {
     FloatCast(Box<PlanExpression<I>>),
     DecimalCast(Box<PlanExpression<I>>),
     IntegerCast(Box<PlanExpression<I>>),
+    DerivedIntegerCast(Box<PlanExpression<I>>, DerivedIntegerType),
     DateCast(Box<PlanExpression<I>>),
     TimeCast(Box<PlanExpression<I>>),
     DateTimeCast(Box<PlanExpression<I>>),
     DurationCast(Box<PlanExpression<I>>),
     YearMonthDurationCast(Box<PlanExpression<I>>),
     DayTimeDurationCast(Box<PlanExpression<I>>),
+    HexBinaryCast(Box<PlanExpression<I>>),
+    Base64BinaryCast(Box<PlanExpression<I>>),
+    AnyUriCast(Box<PlanExpression<I>>),
     StringCast(Box<PlanExpression<I>>),
+    CustomFunction(NamedNode, Vec<PlanExpression<I>>),
 }
 
 impl<I: StrId> PlanExpression<I> {
@@ -354,18 +383,29 @@ impl<I: StrId> PlanExpression<I> {
             | PlanExpression::IsBlank(e)
             | PlanExpression::IsLiteral(e)
             | PlanExpression::IsNumeric(e)
+            | PlanExpression::IsTriple(e)
+            | PlanExpression::Subject(e)
+            | PlanExpression::Predicate(e)
+            | PlanExpression::Object(e)
             | PlanExpression::BooleanCast(e)
             | PlanExpression::DoubleCast(e)
             | PlanExpression::FloatCast(e)
             | PlanExpression::DecimalCast(e)
             | PlanExpression::IntegerCast(e)
+            | PlanExpression::DerivedIntegerCast(e, _)
             | PlanExpression::DateCast(e)
             | PlanExpression::TimeCast(e)
             | PlanExpression::DateTimeCast(e)
             | PlanExpression::DurationCast(e)
             | PlanExpression::YearMonthDurationCast(e)
             | PlanExpression::DayTimeDurationCast(e)
-            | PlanExpression::StringCast(e) => e.add_maybe_bound_variables(set),
+            | PlanExpression::HexBinaryCast(e)
+            | PlanExpression::Base64BinaryCast(e)
+            | PlanExpression::AnyUriCast(e)
+            | PlanExpression::StringCast(e)
+            | PlanExpression::AdjustDateTimeToTimezone(e, None)
+            | PlanExpression::AdjustDateToTimezone(e, None)
+            | PlanExpression::AdjustTimeToTimezone(e, None) => e.add_maybe_bound_variables(set),
             PlanExpression::Or(a, b)
             | PlanExpression::And(a, b)
             | PlanExpression::Equal(a, b)
@@ -387,11 +427,15 @@ impl<I: StrId> PlanExpression<I> {
             | PlanExpression::StrDT(a, b)
             | PlanExpression::SameTerm(a, b)
             | PlanExpression::SubStr(a, b, None)
-            | PlanExpression::Regex(a, b, None) => {
+            | PlanExpression::Regex(a, b, None)
+            | PlanExpression::AdjustDateTimeToTimezone(a, Some(b))
+            | PlanExpression::AdjustDateToTimezone(a, Some(b))
+            | PlanExpression::AdjustTimeToTimezone(a, Some(b)) => {
                 a.add_maybe_bound_variables(set);
                 b.add_maybe_bound_variables(set);
             }
             PlanExpression::If(a, b, c)
+            | PlanExpression::Triple(a, b, c)
             | PlanExpression::SubStr(a, b, Some(c))
             | PlanExpression::Regex(a, b, Some(c))
             | PlanExpression::Replace(a, b, c, None) => {
@@ -406,7 +450,9 @@ impl<I: StrId> PlanExpression<I> {
                 d.add_maybe_bound_variables(set);
             }
 
-            PlanExpression::Concat(es) | PlanExpression::Coalesce(es) => {
+            PlanExpression::Concat(es)
+            | PlanExpression::Coalesce(es)
+            | PlanExpression::CustomFunction(_, es) => {
                 for e in es {
                     e.add_maybe_bound_variables(set);
                 }
@@ -440,6 +486,7 @@ pub enum PlanAggregationFunction {
     Avg,
     Sample,
     GroupConcat { separator: Rc<String> },
+    Custom(NamedNode),
 }
 
 #[derive(Eq, PartialEq, Debug, Clone, Hash)]
@@ -460,18 +507,19 @@ pub enum Comparator<I: StrId> {
     Desc(PlanExpression<I>),
 }
 
-#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash)]
+#[derive(Eq, PartialEq, Debug, Clone, Hash)]
 pub struct TripleTemplate<I: StrId> {
     pub subject: TripleTemplateValue<I>,
     pub predicate: TripleTemplateValue<I>,
     pub object: TripleTemplateValue<I>,
 }
 
-#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash)]
+#[derive(Eq, PartialEq, Debug, Clone, Hash)]
 pub enum TripleTemplateValue<I: StrId> {
     Constant(EncodedTerm<I>),
     BlankNode(usize),
     Variable(usize),
+    Triple(Box<TripleTemplate<I>>),
 }
 
 #[derive(Eq, PartialEq, Debug, Clone, Hash)]
@@ -526,7 +574,7 @@ impl<I: StrId> EncodedTuple<I> {
                                 return None;
                             }
                         }
-                        None => result[key] = Some(*self_value),
+                        None => result[key] = Some(self_value.clone()),
                     }
                 }
             }
@@ -541,7 +589,7 @@ impl<I: StrId> EncodedTuple<I> {
                                 return None;
                             }
                         }
-                        None => result[key] = Some(*other_value),
+                        None => result[key] = Some(other_value.clone()),
                     }
                 }
             }
@@ -1,26 +1,36 @@
+use crate::model::xsd::DerivedIntegerType;
 use crate::model::{BlankNode, Literal, NamedNode, Term};
 use crate::sparql::algebra::*;
 use crate::sparql::error::EvaluationError;
 use crate::sparql::model::*;
 use crate::sparql::plan::*;
+use crate::sparql::{CustomAggregateFunction, CustomFunction, QueryOptions};
 use crate::store::numeric_encoder::{EncodedTerm, WriteEncoder};
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::rc::Rc;
 
-pub(crate) struct PlanBuilder<E: WriteEncoder> {
+pub(crate) struct PlanBuilder<'a, E: WriteEncoder> {
     encoder: E,
+    custom_functions: &'a HashMap<NamedNode, CustomFunction>,
+    custom_aggregate_functions: &'a HashMap<NamedNode, CustomAggregateFunction>,
 }
 
-impl<E: WriteEncoder<Error = EvaluationError>> PlanBuilder<E> {
+impl<'a, E: WriteEncoder<Error = EvaluationError>> PlanBuilder<'a, E> {
     pub fn build(
         encoder: E,
         pattern: &GraphPattern,
+        options: &'a QueryOptions,
     ) -> Result<(PlanNode<E::StrId>, Vec<Variable>), EvaluationError> {
         let mut variables = Vec::default();
-        let plan = PlanBuilder { encoder }.build_for_graph_pattern(
+        let plan = PlanBuilder {
+            encoder,
+            custom_functions: &options.custom_functions,
+            custom_aggregate_functions: &options.custom_aggregate_functions,
+        }
+        .build_for_graph_pattern(
             pattern,
             &mut variables,
-            PatternValue::Constant(EncodedTerm::DefaultGraph),
+            &PatternValue::Constant(EncodedTerm::DefaultGraph),
         )?;
         Ok((plan, variables))
     }
@@ -30,14 +40,19 @@ impl<E: WriteEncoder<Error = EvaluationError>> PlanBuilder<E> {
         template: &[TriplePattern],
         mut variables: Vec<Variable>,
     ) -> Result<Vec<TripleTemplate<E::StrId>>, EvaluationError> {
-        PlanBuilder { encoder }.build_for_graph_template(template, &mut variables)
+        PlanBuilder {
+            encoder,
+            custom_functions: &HashMap::new(),
+            custom_aggregate_functions: &HashMap::new(),
+        }
+        .build_for_graph_template(template, &mut variables)
     }
 
     fn build_for_graph_pattern(
         &mut self,
         pattern: &GraphPattern,
         variables: &mut Vec<Variable>,
-        graph_name: PatternValue<E::StrId>,
+        graph_name: &PatternValue<E::StrId>,
     ) -> Result<PlanNode<E::StrId>, EvaluationError> {
         Ok(match pattern {
             GraphPattern::BGP(p) => self.build_for_bgp(p, variables, graph_name)?,
@@ -50,7 +65,7 @@ impl<E: WriteEncoder<Error = EvaluationError>> PlanBuilder<E> {
                 subject: self.pattern_value_from_term_or_variable(subject, variables)?,
                 path: Rc::new(self.build_for_path(path)?),
                 object: self.pattern_value_from_term_or_variable(object, variables)?,
-                graph_name,
+                graph_name: graph_name.clone(),
             },
             GraphPattern::Join { left, right } => {
                 //TODO: improve
@@ -66,7 +81,7 @@ impl<E: WriteEncoder<Error = EvaluationError>> PlanBuilder<E> {
                         subject: self.pattern_value_from_term_or_variable(subject, variables)?,
                         path: Rc::new(self.build_for_path(path)?),
                         object: self.pattern_value_from_term_or_variable(object, variables)?,
-                        graph_name,
+                        graph_name: graph_name.clone(),
                     }
                 } else {
                     PlanNode::Join {
@@ -125,7 +140,7 @@ impl<E: WriteEncoder<Error = EvaluationError>> PlanBuilder<E> {
             GraphPattern::Graph { graph_name, inner } => {
                 let graph_name =
                     self.pattern_value_from_named_node_or_variable(graph_name, variables)?;
-                self.build_for_graph_pattern(inner, variables, graph_name)?
+                self.build_for_graph_pattern(inner, variables, &graph_name)?
             }
             GraphPattern::Extend { inner, var, expr } => PlanNode::Extend {
                 child: Rc::new(self.build_for_graph_pattern(inner, variables, graph_name)?),
@@ -161,34 +176,31 @@ impl<E: WriteEncoder<Error = EvaluationError>> PlanBuilder<E> {
                 let mut inner_variables = by.clone();
                 let inner_graph_name =
                     self.convert_pattern_value_id(graph_name, variables, &mut inner_variables);
-
+                let child =
+                    self.build_for_graph_pattern(inner, &mut inner_variables, &inner_graph_name)?;
+                let key_mapping = by
+                    .iter()
+                    .map(|k| {
+                        (
+                            variable_key(&mut inner_variables, k),
+                            variable_key(variables, k),
+                        )
+                    })
+                    .collect();
+                let aggregates = aggregates
+                    .iter()
+                    .map(|(v, a)| {
+                        Ok((
+                            self.build_for_aggregate(a, &mut inner_variables, graph_name)?,
+                            variable_key(variables, v),
+                        ))
+                    })
+                    .collect::<Result<Vec<_>, EvaluationError>>()?;
                 PlanNode::Aggregate {
-                    child: Rc::new(self.build_for_graph_pattern(
-                        inner,
-                        &mut inner_variables,
-                        inner_graph_name,
-                    )?),
-                    key_mapping: Rc::new(
-                        by.iter()
-                            .map(|k| {
-                                (
-                                    variable_key(&mut inner_variables, k),
-                                    variable_key(variables, k),
-                                )
-                            })
-                            .collect(),
-                    ),
-                    aggregates: Rc::new(
-                        aggregates
-                            .iter()
-                            .map(|(v, a)| {
-                                Ok((
-                                    self.build_for_aggregate(a, &mut inner_variables, graph_name)?,
-                                    variable_key(variables, v),
-                                ))
-                            })
-                            .collect::<Result<Vec<_>, EvaluationError>>()?,
-                    ),
+                    child: Rc::new(child),
+                    key_mapping: Rc::new(key_mapping),
+                    aggregates: Rc::new(aggregates),
+                    child_variables: Rc::new(inner_variables),
                 }
             }
             GraphPattern::Table {
@@ -218,12 +230,11 @@ impl<E: WriteEncoder<Error = EvaluationError>> PlanBuilder<E> {
                 let mut inner_variables = projection.clone();
                 let inner_graph_name =
                     self.convert_pattern_value_id(graph_name, variables, &mut inner_variables);
+                let child =
+                    self.build_for_graph_pattern(inner, &mut inner_variables, &inner_graph_name)?;
                 PlanNode::Project {
-                    child: Rc::new(self.build_for_graph_pattern(
-                        inner,
-                        &mut inner_variables,
-                        inner_graph_name,
-                    )?),
+                    child: Rc::new(child),
+                    child_variables: Rc::new(inner_variables),
                     mapping: Rc::new(
                         projection
                             .iter()
@@ -268,7 +279,7 @@ impl<E: WriteEncoder<Error = EvaluationError>> PlanBuilder<E> {
         &mut self,
         p: &[TriplePattern],
         variables: &mut Vec<Variable>,
-        graph_name: PatternValue<E::StrId>,
+        graph_name: &PatternValue<E::StrId>,
     ) -> Result<PlanNode<E::StrId>, EvaluationError> {
         let mut plan = PlanNode::Init;
         for pattern in sort_bgp(p) {
@@ -278,7 +289,7 @@ impl<E: WriteEncoder<Error = EvaluationError>> PlanBuilder<E> {
                 predicate: self
                     .pattern_value_from_named_node_or_variable(&pattern.predicate, variables)?,
                 object: self.pattern_value_from_term_or_variable(&pattern.object, variables)?,
-                graph_name,
+                graph_name: graph_name.clone(),
             }
         }
         Ok(plan)
@@ -326,7 +337,7 @@ impl<E: WriteEncoder<Error = EvaluationError>> PlanBuilder<E> {
         &mut self,
         expression: &Expression,
         variables: &mut Vec<Variable>,
-        graph_name: PatternValue<E::StrId>,
+        graph_name: &PatternValue<E::StrId>,
     ) -> Result<PlanExpression<E::StrId>, EvaluationError> {
         Ok(match expression {
             Expression::NamedNode(node) => PlanExpression::Constant(self.build_named_node(node)?),
@@ -605,8 +616,39 @@ impl<E: WriteEncoder<Error = EvaluationError>> PlanBuilder<E> {
                         None => None,
                     },
                 ),
+                Function::Triple => PlanExpression::Triple(
+                    Box::new(self.build_for_expression(&parameters[0], variables, graph_name)?),
+                    Box::new(self.build_for_expression(&parameters[1], variables, graph_name)?),
+                    Box::new(self.build_for_expression(&parameters[2], variables, graph_name)?),
+                ),
+                Function::Subject => PlanExpression::Subject(Box::new(self.build_for_expression(
+                    &parameters[0],
+                    variables,
+                    graph_name,
+                )?)),
+                Function::Predicate => PlanExpression::Predicate(Box::new(
+                    self.build_for_expression(&parameters[0], variables, graph_name)?,
+                )),
+                Function::Object => PlanExpression::Object(Box::new(self.build_for_expression(
+                    &parameters[0],
+                    variables,
+                    graph_name,
+                )?)),
+                Function::IsTriple => PlanExpression::IsTriple(Box::new(
+                    self.build_for_expression(&parameters[0], variables, graph_name)?,
+                )),
                 Function::Custom(name) => {
-                    if name == "http://www.w3.org/2001/XMLSchema#boolean" {
+                    if self.custom_functions.contains_key(name) {
+                        PlanExpression::CustomFunction(
+                            name.clone(),
+                            self.expression_list(parameters, variables, graph_name)?,
+                        )
+                    } else if self.custom_aggregate_functions.contains_key(name) {
+                        return Err(EvaluationError::msg(format!(
+                            "The custom aggregate function {} is called outside of an aggregation",
+                            name
+                        )));
+                    } else if name == "http://www.w3.org/2001/XMLSchema#boolean" {
                         self.build_cast(
                             parameters,
                             PlanExpression::BooleanCast,
@@ -646,6 +688,15 @@ impl<E: WriteEncoder<Error = EvaluationError>> PlanBuilder<E> {
                             graph_name,
                             "integer",
                         )?
+                    } else if let Some(datatype) = DerivedIntegerType::from_name(name.as_ref()) {
+                        self.build_cast(
+                            parameters,
+                            |e| PlanExpression::DerivedIntegerCast(e, datatype),
+                            variables,
+                            graph_name,
+                            name.as_str()
+                                .trim_start_matches("http://www.w3.org/2001/XMLSchema#"),
+                        )?
                     } else if name == "http://www.w3.org/2001/XMLSchema#date" {
                         self.build_cast(
                             parameters,
@@ -694,6 +745,30 @@ impl<E: WriteEncoder<Error = EvaluationError>> PlanBuilder<E> {
                             graph_name,
                             "dayTimeDuration",
                         )?
+                    } else if name == "http://www.w3.org/2001/XMLSchema#hexBinary" {
+                        self.build_cast(
+                            parameters,
+                            PlanExpression::HexBinaryCast,
+                            variables,
+                            graph_name,
+                            "hexBinary",
+                        )?
+                    } else if name == "http://www.w3.org/2001/XMLSchema#base64Binary" {
+                        self.build_cast(
+                            parameters,
+                            PlanExpression::Base64BinaryCast,
+                            variables,
+                            graph_name,
+                            "base64Binary",
+                        )?
+                    } else if name == "http://www.w3.org/2001/XMLSchema#anyURI" {
+                        self.build_cast(
+                            parameters,
+                            PlanExpression::AnyUriCast,
+                            variables,
+                            graph_name,
+                            "anyURI",
+                        )?
                     } else if name == "http://www.w3.org/2001/XMLSchema#string" {
                         self.build_cast(
                             parameters,
@@ -702,6 +777,36 @@ impl<E: WriteEncoder<Error = EvaluationError>> PlanBuilder<E> {
                             graph_name,
                             "string",
                         )?
+                    } else if name
+                        == "http://www.w3.org/2005/xpath-functions#adjust-dateTime-to-timezone"
+                    {
+                        self.build_adjust_to_timezone(
+                            parameters,
+                            PlanExpression::AdjustDateTimeToTimezone,
+                            variables,
+                            graph_name,
+                            "adjust-dateTime-to-timezone",
+                        )?
+                    } else if name
+                        == "http://www.w3.org/2005/xpath-functions#adjust-date-to-timezone"
+                    {
+                        self.build_adjust_to_timezone(
+                            parameters,
+                            PlanExpression::AdjustDateToTimezone,
+                            variables,
+                            graph_name,
+                            "adjust-date-to-timezone",
+                        )?
+                    } else if name
+                        == "http://www.w3.org/2005/xpath-functions#adjust-time-to-timezone"
+                    {
+                        self.build_adjust_to_timezone(
+                            parameters,
+                            PlanExpression::AdjustTimeToTimezone,
+                            variables,
+                            graph_name,
+                            "adjust-time-to-timezone",
+                        )?
                     } else {
                         return Err(EvaluationError::msg(format!(
                             "Not supported custom function {}",
@@ -730,8 +835,8 @@ impl<E: WriteEncoder<Error = EvaluationError>> PlanBuilder<E> {
         parameters: &[Expression],
         constructor: impl Fn(Box<PlanExpression<E::StrId>>) -> PlanExpression<E::StrId>,
         variables: &mut Vec<Variable>,
-        graph_name: PatternValue<E::StrId>,
-        name: &'static str,
+        graph_name: &PatternValue<E::StrId>,
+        name: &str,
     ) -> Result<PlanExpression<E::StrId>, EvaluationError> {
         if parameters.len() == 1 {
             Ok(constructor(Box::new(self.build_for_expression(
@@ -747,11 +852,40 @@ impl<E: WriteEncoder<Error = EvaluationError>> PlanBuilder<E> {
         }
     }
 
+    fn build_adjust_to_timezone(
+        &mut self,
+        parameters: &[Expression],
+        constructor: impl Fn(
+            Box<PlanExpression<E::StrId>>,
+            Option<Box<PlanExpression<E::StrId>>>,
+        ) -> PlanExpression<E::StrId>,
+        variables: &mut Vec<Variable>,
+        graph_name: &PatternValue<E::StrId>,
+        name: &str,
+    ) -> Result<PlanExpression<E::StrId>, EvaluationError> {
+        match parameters {
+            [value] => Ok(constructor(
+                Box::new(self.build_for_expression(value, variables, graph_name)?),
+                None,
+            )),
+            [value, timezone] => Ok(constructor(
+                Box::new(self.build_for_expression(value, variables, graph_name)?),
+                Some(Box::new(
+                    self.build_for_expression(timezone, variables, graph_name)?,
+                )),
+            )),
+            _ => Err(EvaluationError::msg(format!(
+                "The fn:{} function takes one or two parameters",
+                name
+            ))),
+        }
+    }
+
     fn expression_list(
         &mut self,
         l: &[Expression],
         variables: &mut Vec<Variable>,
-        graph_name: PatternValue<E::StrId>,
+        graph_name: &PatternValue<E::StrId>,
     ) -> Result<Vec<PlanExpression<E::StrId>>, EvaluationError> {
         l.iter()
             .map(|e| self.build_for_expression(e, variables, graph_name))
@@ -775,6 +909,12 @@ impl<E: WriteEncoder<Error = EvaluationError>> PlanBuilder<E> {
                 //TODO: very bad hack to convert bnode to variable
             }
             TermOrVariable::Term(term) => PatternValue::Constant(self.build_term(term)?),
+            TermOrVariable::Triple(triple) => PatternValue::Triple(Box::new(TriplePatternValue {
+                subject: self.pattern_value_from_term_or_variable(&triple.subject, variables)?,
+                predicate: self
+                    .pattern_value_from_named_node_or_variable(&triple.predicate, variables)?,
+                object: self.pattern_value_from_term_or_variable(&triple.object, variables)?,
+            })),
         })
     }
 
@@ -820,7 +960,7 @@ impl<E: WriteEncoder<Error = EvaluationError>> PlanBuilder<E> {
         &mut self,
         aggregate: &AggregationFunction,
         variables: &mut Vec<Variable>,
-        graph_name: PatternValue<E::StrId>,
+        graph_name: &PatternValue<E::StrId>,
     ) -> Result<PlanAggregation<E::StrId>, EvaluationError> {
         match aggregate {
             AggregationFunction::Count { expr, distinct } => Ok(PlanAggregation {
@@ -867,9 +1007,24 @@ impl<E: WriteEncoder<Error = EvaluationError>> PlanBuilder<E> {
                 parameter: Some(self.build_for_expression(expr, variables, graph_name)?),
                 distinct: *distinct,
             }),
-            AggregationFunction::Custom { .. } => Err(EvaluationError::msg(
-                "Custom aggregation functions are not supported yet",
-            )),
+            AggregationFunction::Custom {
+                name,
+                expr,
+                distinct,
+            } => {
+                if self.custom_aggregate_functions.contains_key(name) {
+                    Ok(PlanAggregation {
+                        function: PlanAggregationFunction::Custom(name.clone()),
+                        parameter: Some(self.build_for_expression(expr, variables, graph_name)?),
+                        distinct: *distinct,
+                    })
+                } else {
+                    Err(EvaluationError::msg(format!(
+                        "Not supported custom aggregate function {}",
+                        name
+                    )))
+                }
+            }
         }
     }
 
@@ -914,6 +1069,22 @@ impl<E: WriteEncoder<Error = EvaluationError>> PlanBuilder<E> {
                 TripleTemplateValue::BlankNode(bnode_key(bnodes, bnode))
             }
             TermOrVariable::Term(term) => TripleTemplateValue::Constant(self.build_term(term)?),
+            TermOrVariable::Triple(triple) => {
+                TripleTemplateValue::Triple(Box::new(TripleTemplate {
+                    subject: self.template_value_from_term_or_variable(
+                        &triple.subject,
+                        variables,
+                        bnodes,
+                    )?,
+                    predicate: self
+                        .template_value_from_named_node_or_variable(&triple.predicate, variables)?,
+                    object: self.template_value_from_term_or_variable(
+                        &triple.object,
+                        variables,
+                        bnodes,
+                    )?,
+                }))
+            }
         })
     }
 
@@ -934,15 +1105,20 @@ impl<E: WriteEncoder<Error = EvaluationError>> PlanBuilder<E> {
 
     fn convert_pattern_value_id(
         &self,
-        from_value: PatternValue<E::StrId>,
+        from_value: &PatternValue<E::StrId>,
         from: &[Variable],
         to: &mut Vec<Variable>,
     ) -> PatternValue<E::StrId> {
         match from_value {
-            PatternValue::Constant(v) => PatternValue::Constant(v),
+            PatternValue::Constant(v) => PatternValue::Constant(v.clone()),
             PatternValue::Variable(from_id) => {
-                PatternValue::Variable(self.convert_variable_id(from_id, from, to))
+                PatternValue::Variable(self.convert_variable_id(*from_id, from, to))
             }
+            PatternValue::Triple(triple) => PatternValue::Triple(Box::new(TriplePatternValue {
+                subject: self.convert_pattern_value_id(&triple.subject, from, to),
+                predicate: self.convert_pattern_value_id(&triple.predicate, from, to),
+                object: self.convert_pattern_value_id(&triple.object, from, to),
+            })),
         }
     }
 
@@ -1010,7 +1186,7 @@ impl<E: WriteEncoder<Error = EvaluationError>> PlanBuilder<E> {
             | PlanNode::Limit { child, .. } => {
                 self.add_left_join_problematic_variables(&*child, set)
             }
-            PlanNode::Project { mapping, child } => {
+            PlanNode::Project { mapping, child, .. } => {
                 let mut child_bound = BTreeSet::new();
                 self.add_left_join_problematic_variables(&*child, &mut child_bound);
                 for (child_i, output_i) in mapping.iter() {
@@ -1104,17 +1280,8 @@ fn count_pattern_binds(
     assigned_blank_nodes: &HashSet<&BlankNode>,
 ) -> u8 {
     let mut count = 12;
-    if let TermOrVariable::Variable(v) = &pattern.subject {
-        if !assigned_variables.contains(v) {
-            count -= 4;
-        }
-    } else if let TermOrVariable::Term(Term::BlankNode(bnode)) = &pattern.subject {
-        if !assigned_blank_nodes.contains(bnode) {
-            count -= 4;
-        }
-    } else {
-        count -= 1;
-    }
+    count -=
+        term_or_variable_bind_penalty(&pattern.subject, assigned_variables, assigned_blank_nodes);
     if let NamedNodeOrVariable::Variable(v) = &pattern.predicate {
         if !assigned_variables.contains(v) {
             count -= 4;
@@ -1122,18 +1289,51 @@ fn count_pattern_binds(
     } else {
         count -= 1;
     }
-    if let TermOrVariable::Variable(v) = &pattern.object {
-        if !assigned_variables.contains(v) {
-            count -= 4;
+    count -=
+        term_or_variable_bind_penalty(&pattern.object, assigned_variables, assigned_blank_nodes);
+    count
+}
+
+fn term_or_variable_bind_penalty(
+    term: &TermOrVariable,
+    assigned_variables: &HashSet<&Variable>,
+    assigned_blank_nodes: &HashSet<&BlankNode>,
+) -> u8 {
+    match term {
+        TermOrVariable::Variable(v) => {
+            if assigned_variables.contains(v) {
+                0
+            } else {
+                4
+            }
         }
-    } else if let TermOrVariable::Term(Term::BlankNode(bnode)) = &pattern.object {
-        if !assigned_blank_nodes.contains(bnode) {
-            count -= 4;
+        TermOrVariable::Term(Term::BlankNode(bnode)) => {
+            if assigned_blank_nodes.contains(bnode) {
+                0
+            } else {
+                4
+            }
+        }
+        TermOrVariable::Term(_) => 1,
+        TermOrVariable::Triple(triple) => {
+            if term_or_variable_bind_penalty(
+                &triple.subject,
+                assigned_variables,
+                assigned_blank_nodes,
+            ) == 4
+                || matches!(&triple.predicate, NamedNodeOrVariable::Variable(v) if !assigned_variables.contains(v))
+                || term_or_variable_bind_penalty(
+                    &triple.object,
+                    assigned_variables,
+                    assigned_blank_nodes,
+                ) == 4
+            {
+                4
+            } else {
+                1
+            }
         }
-    } else {
-        count -= 1;
     }
-    count
 }
 
 fn add_pattern_variables<'a>(
@@ -1141,17 +1341,26 @@ fn add_pattern_variables<'a>(
     variables: &mut HashSet<&'a Variable>,
     blank_nodes: &mut HashSet<&'a BlankNode>,
 ) {
-    if let TermOrVariable::Variable(v) = &pattern.subject {
-        variables.insert(v);
-    } else if let TermOrVariable::Term(Term::BlankNode(bnode)) = &pattern.subject {
-        blank_nodes.insert(bnode);
-    }
+    add_term_or_variable_variables(&pattern.subject, variables, blank_nodes);
     if let NamedNodeOrVariable::Variable(v) = &pattern.predicate {
         variables.insert(v);
     }
-    if let TermOrVariable::Variable(v) = &pattern.object {
-        variables.insert(v);
-    } else if let TermOrVariable::Term(Term::BlankNode(bnode)) = &pattern.object {
-        blank_nodes.insert(bnode);
+    add_term_or_variable_variables(&pattern.object, variables, blank_nodes);
+}
+
+fn add_term_or_variable_variables<'a>(
+    term: &'a TermOrVariable,
+    variables: &mut HashSet<&'a Variable>,
+    blank_nodes: &mut HashSet<&'a BlankNode>,
+) {
+    match term {
+        TermOrVariable::Variable(v) => {
+            variables.insert(v);
+        }
+        TermOrVariable::Term(Term::BlankNode(bnode)) => {
+            blank_nodes.insert(bnode);
+        }
+        TermOrVariable::Term(_) => (),
+        TermOrVariable::Triple(triple) => add_pattern_variables(triple, variables, blank_nodes),
     }
 }
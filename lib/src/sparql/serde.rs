@@ -0,0 +1,480 @@
+//! This module provides [Serde](https://serde.rs/) deserializers for SPARQL query solutions.
+//!
+//! A solution is deserialized as a map from the bound variable names to their values
+//! or as a sequence of its values, bound or not.
+//! Literals are deserialized to Rust numbers and booleans following their datatypes.
+//! The other terms and literals are deserialized as strings: the IRI for named nodes, the identifier for blank nodes, the lexical form for literals.
+
+use crate::model::{vocab::xsd, *};
+use crate::sparql::error::EvaluationError;
+use crate::sparql::model::*;
+use serde::de::value::StringDeserializer;
+use serde::de::{
+    DeserializeSeed, Deserializer, Error, IntoDeserializer, MapAccess, SeqAccess, Unexpected,
+    Visitor,
+};
+use serde::forward_to_deserialize_any;
+use std::fmt;
+
+impl Error for EvaluationError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::msg(msg.to_string())
+    }
+}
+
+impl<'de> Deserializer<'de> for QuerySolutionIter {
+    type Error = EvaluationError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, EvaluationError> {
+        visitor.visit_seq(SolutionsSeqAccess { solutions: self })
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct SolutionsSeqAccess {
+    solutions: QuerySolutionIter,
+}
+
+impl<'de> SeqAccess<'de> for SolutionsSeqAccess {
+    type Error = EvaluationError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, EvaluationError> {
+        match self.solutions.next() {
+            Some(solution) => seed.deserialize(&solution?).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        let (min, max) = self.solutions.size_hint();
+        if Some(min) == max {
+            max
+        } else {
+            None
+        }
+    }
+}
+
+impl<'de> Deserializer<'de> for &QuerySolution {
+    type Error = EvaluationError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, EvaluationError> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, EvaluationError> {
+        visitor.visit_seq(SolutionSeqAccess {
+            values: Box::new(self.values()),
+        })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _: usize,
+        visitor: V,
+    ) -> Result<V::Value, EvaluationError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: usize,
+        visitor: V,
+    ) -> Result<V::Value, EvaluationError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, EvaluationError> {
+        visitor.visit_map(SolutionMapAccess {
+            bindings: Box::new(self.iter()),
+            value: None,
+        })
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, EvaluationError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct struct enum identifier ignored_any
+    }
+}
+
+struct SolutionSeqAccess<'a> {
+    values: Box<dyn Iterator<Item = Option<&'a Term>> + 'a>,
+}
+
+impl<'de, 'a> SeqAccess<'de> for SolutionSeqAccess<'a> {
+    type Error = EvaluationError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, EvaluationError> {
+        match self.values.next() {
+            Some(value) => seed.deserialize(TermDeserializer { term: value }).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+struct SolutionMapAccess<'a> {
+    bindings: Box<dyn Iterator<Item = (&'a Variable, &'a Term)> + 'a>,
+    value: Option<&'a Term>,
+}
+
+impl<'de, 'a> MapAccess<'de> for SolutionMapAccess<'a> {
+    type Error = EvaluationError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, EvaluationError> {
+        match self.bindings.next() {
+            Some((variable, value)) => {
+                self.value = Some(value);
+                seed.deserialize(variable_deserializer(variable)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, EvaluationError> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| EvaluationError::msg("A map value has been requested before its key"))?;
+        seed.deserialize(TermDeserializer { term: Some(value) })
+    }
+}
+
+fn variable_deserializer(variable: &Variable) -> StringDeserializer<EvaluationError> {
+    variable.as_str().to_owned().into_deserializer()
+}
+
+/// Deserializes a term, `None` if the variable is unbound
+struct TermDeserializer<'a> {
+    term: Option<&'a Term>,
+}
+
+impl<'a> TermDeserializer<'a> {
+    fn term(&self) -> Result<&'a Term, EvaluationError> {
+        self.term
+            .ok_or_else(|| EvaluationError::msg("The variable is not bound"))
+    }
+
+    /// Returns the literal if it has one of the given datatypes
+    fn literal(
+        &self,
+        datatypes: &[NamedNodeRef<'_>],
+        expected: &dyn serde::de::Expected,
+    ) -> Result<&'a Literal, EvaluationError> {
+        match self.term()? {
+            Term::Literal(literal) if datatypes.contains(&literal.datatype()) => Ok(literal),
+            term => Err(EvaluationError::invalid_type(
+                Unexpected::Other(&term.to_string()),
+                expected,
+            )),
+        }
+    }
+
+    fn string_value(&self) -> Result<String, EvaluationError> {
+        Ok(match self.term()? {
+            Term::NamedNode(node) => node.as_str().to_owned(),
+            Term::BlankNode(node) => node.as_str().to_owned(),
+            Term::Literal(literal) => literal.value().to_owned(),
+            Term::Triple(triple) => triple.to_string(),
+        })
+    }
+}
+
+const INTEGER_DATATYPES: [NamedNodeRef<'static>; 13] = [
+    xsd::INTEGER,
+    xsd::LONG,
+    xsd::INT,
+    xsd::SHORT,
+    xsd::BYTE,
+    xsd::NON_NEGATIVE_INTEGER,
+    xsd::NON_POSITIVE_INTEGER,
+    xsd::NEGATIVE_INTEGER,
+    xsd::POSITIVE_INTEGER,
+    xsd::UNSIGNED_LONG,
+    xsd::UNSIGNED_INT,
+    xsd::UNSIGNED_SHORT,
+    xsd::UNSIGNED_BYTE,
+];
+
+const NUMERIC_DATATYPES: [NamedNodeRef<'static>; 16] = [
+    xsd::FLOAT,
+    xsd::DOUBLE,
+    xsd::DECIMAL,
+    xsd::INTEGER,
+    xsd::LONG,
+    xsd::INT,
+    xsd::SHORT,
+    xsd::BYTE,
+    xsd::NON_NEGATIVE_INTEGER,
+    xsd::NON_POSITIVE_INTEGER,
+    xsd::NEGATIVE_INTEGER,
+    xsd::POSITIVE_INTEGER,
+    xsd::UNSIGNED_LONG,
+    xsd::UNSIGNED_INT,
+    xsd::UNSIGNED_SHORT,
+    xsd::UNSIGNED_BYTE,
+];
+
+macro_rules! deserialize_number {
+    ($deserialize:ident, $visit:ident, $type:ty, $datatypes:expr) => {
+        fn $deserialize<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, EvaluationError> {
+            let literal = self.literal(&$datatypes, &visitor)?;
+            let value = literal.value().parse::<$type>().map_err(|_| {
+                EvaluationError::invalid_value(Unexpected::Str(literal.value()), &visitor)
+            })?;
+            visitor.$visit(value)
+        }
+    };
+}
+
+impl<'de, 'a> Deserializer<'de> for TermDeserializer<'a> {
+    type Error = EvaluationError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, EvaluationError> {
+        match self.term()? {
+            Term::Literal(literal) => {
+                let datatype = literal.datatype();
+                if datatype == xsd::BOOLEAN {
+                    self.deserialize_bool(visitor)
+                } else if INTEGER_DATATYPES.contains(&datatype) {
+                    self.deserialize_i64(visitor)
+                } else if NUMERIC_DATATYPES.contains(&datatype) {
+                    self.deserialize_f64(visitor)
+                } else {
+                    visitor.visit_str(literal.value())
+                }
+            }
+            _ => visitor.visit_string(self.string_value()?),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, EvaluationError> {
+        let literal = self.literal(&[xsd::BOOLEAN], &visitor)?;
+        match literal.value() {
+            "true" | "1" => visitor.visit_bool(true),
+            "false" | "0" => visitor.visit_bool(false),
+            value => Err(EvaluationError::invalid_value(
+                Unexpected::Str(value),
+                &visitor,
+            )),
+        }
+    }
+
+    deserialize_number!(deserialize_i8, visit_i8, i8, INTEGER_DATATYPES);
+    deserialize_number!(deserialize_i16, visit_i16, i16, INTEGER_DATATYPES);
+    deserialize_number!(deserialize_i32, visit_i32, i32, INTEGER_DATATYPES);
+    deserialize_number!(deserialize_i64, visit_i64, i64, INTEGER_DATATYPES);
+    deserialize_number!(deserialize_i128, visit_i128, i128, INTEGER_DATATYPES);
+    deserialize_number!(deserialize_u8, visit_u8, u8, INTEGER_DATATYPES);
+    deserialize_number!(deserialize_u16, visit_u16, u16, INTEGER_DATATYPES);
+    deserialize_number!(deserialize_u32, visit_u32, u32, INTEGER_DATATYPES);
+    deserialize_number!(deserialize_u64, visit_u64, u64, INTEGER_DATATYPES);
+    deserialize_number!(deserialize_u128, visit_u128, u128, INTEGER_DATATYPES);
+    deserialize_number!(deserialize_f32, visit_f32, f32, NUMERIC_DATATYPES);
+    deserialize_number!(deserialize_f64, visit_f64, f64, NUMERIC_DATATYPES);
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, EvaluationError> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, EvaluationError> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, EvaluationError> {
+        visitor.visit_string(self.string_value()?)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, EvaluationError> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, EvaluationError> {
+        visitor.visit_byte_buf(self.string_value()?.into_bytes())
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, EvaluationError> {
+        if self.term.is_some() {
+            visitor.visit_some(self)
+        } else {
+            visitor.visit_none()
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, EvaluationError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, EvaluationError> {
+        visitor.visit_enum(self.string_value()?.into_deserializer())
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, EvaluationError> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        unit unit_struct seq tuple tuple_struct map struct identifier
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::xsd::{Date, Decimal};
+    use serde::Deserialize;
+    use std::rc::Rc;
+    use std::str::FromStr;
+
+    fn solutions(rows: Vec<Vec<Option<Term>>>) -> QuerySolutionIter {
+        QuerySolutionIter::new(
+            Rc::new(vec![
+                Variable::new_unchecked("id"),
+                Variable::new_unchecked("name"),
+                Variable::new_unchecked("age"),
+                Variable::new_unchecked("birth"),
+            ]),
+            Box::new(rows.into_iter().map(Ok)),
+        )
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Person {
+        id: NamedNode,
+        name: String,
+        age: Option<u8>,
+        birth: Option<Date>,
+    }
+
+    #[test]
+    fn test_struct() -> Result<(), EvaluationError> {
+        let people = solutions(vec![
+            vec![
+                Some(NamedNode::new_unchecked("http://example.com/alice").into()),
+                Some(Literal::new_language_tagged_literal_unchecked("Alice", "en").into()),
+                Some(Literal::new_typed_literal("31", xsd::INT).into()),
+                Some(Literal::new_typed_literal("1990-01-01", xsd::DATE).into()),
+            ],
+            vec![
+                Some(NamedNode::new_unchecked("http://example.com/bob").into()),
+                Some(Literal::from("Bob").into()),
+                None,
+                None,
+            ],
+        ])
+        .deserialize::<Person>()
+        .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            people,
+            vec![
+                Person {
+                    id: NamedNode::new_unchecked("http://example.com/alice"),
+                    name: "Alice".to_owned(),
+                    age: Some(31),
+                    birth: Some(Date::from_str("1990-01-01").unwrap()),
+                },
+                Person {
+                    id: NamedNode::new_unchecked("http://example.com/bob"),
+                    name: "Bob".to_owned(),
+                    age: None,
+                    birth: None,
+                }
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_tuple() -> Result<(), EvaluationError> {
+        let rows =
+            Vec::<(String, Option<bool>, Decimal, Option<f64>)>::deserialize(solutions(vec![
+                vec![
+                    Some(BlankNode::new_unchecked("b").into()),
+                    Some(Literal::from(true).into()),
+                    Some(Literal::new_typed_literal("1.5", xsd::DECIMAL).into()),
+                    Some(Literal::from(2).into()),
+                ],
+            ]))?;
+        assert_eq!(
+            rows,
+            vec![(
+                "b".to_owned(),
+                Some(true),
+                Decimal::from_str("1.5").unwrap(),
+                Some(2.)
+            )]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors() {
+        let solution = |term: Term| {
+            solutions(vec![vec![
+                Some(NamedNode::new_unchecked("http://example.com/alice").into()),
+                Some(Literal::from("Alice").into()),
+                Some(term),
+                None,
+            ]])
+            .deserialize::<Person>()
+            .next()
+            .unwrap()
+        };
+        assert!(solution(Literal::from(31).into()).is_ok());
+        assert!(solution(Literal::from(-1).into()).is_err());
+        assert!(solution(Literal::from(1000).into()).is_err());
+        assert!(solution(Literal::from("31").into()).is_err());
+        assert!(solution(NamedNode::new_unchecked("http://example.com/age").into()).is_err());
+
+        let missing_name = solutions(vec![vec![
+            Some(NamedNode::new_unchecked("http://example.com/alice").into()),
+            None,
+            None,
+            None,
+        ]])
+        .deserialize::<Person>()
+        .next()
+        .unwrap();
+        assert!(missing_name.is_err());
+    }
+}
@@ -1,18 +1,20 @@
 use crate::error::{invalid_data_error, invalid_input_error};
-use crate::io::GraphFormat;
-use crate::model::{BlankNode, GraphNameRef, NamedNode, NamedOrBlankNode, Quad, Term};
+use crate::io::{GraphFormat, GraphParser};
+use crate::model::{
+    BlankNode, GraphNameRef, LiteralValidator, NamedNode, Quad, Subject, Term, Triple,
+};
 use crate::sparql::algebra::{
     GraphPattern, GraphTarget, GraphUpdateOperation, NamedNodeOrVariable, QuadPattern,
-    QueryDataset, TermOrVariable,
+    QueryDataset, TermOrVariable, TriplePattern,
 };
 use crate::sparql::dataset::{DatasetStrId, DatasetView};
-use crate::sparql::eval::SimpleEvaluator;
+use crate::sparql::eval::{Interruption, SimpleEvaluator};
 use crate::sparql::http::Client;
 use crate::sparql::plan::EncodedTuple;
 use crate::sparql::plan_builder::PlanBuilder;
 use crate::sparql::{EvaluationError, UpdateOptions, Variable};
 use crate::store::numeric_encoder::{
-    EncodedQuad, EncodedTerm, ReadEncoder, StrContainer, StrLookup, WriteEncoder,
+    Decoder, EncodedQuad, EncodedTerm, ReadEncoder, StrContainer, StrLookup, WriteEncoder,
 };
 use crate::store::{load_graph, ReadableEncodedStore, StoreOrParseError, WritableEncodedStore};
 use http::header::{ACCEPT, CONTENT_TYPE, USER_AGENT};
@@ -27,6 +29,7 @@ pub(crate) struct SimpleUpdateEvaluator<'a, R, W> {
     write: &'a mut W,
     base_iri: Option<Rc<Iri<String>>>,
     options: UpdateOptions,
+    interruption: Interruption,
     client: Client,
 }
 
@@ -48,6 +51,7 @@ where
             read,
             write,
             base_iri,
+            interruption: Interruption::new(&options.query_options),
             options,
             client: Client::new(),
         }
@@ -55,6 +59,7 @@ where
 
     pub fn eval_all(&mut self, updates: &[GraphUpdateOperation]) -> Result<(), EvaluationError> {
         for update in updates {
+            self.interruption.check()?;
             self.eval(update)?;
         }
         Ok(())
@@ -90,6 +95,17 @@ where
     fn eval_insert_data(&mut self, data: &[Quad]) -> Result<(), EvaluationError> {
         let mut bnodes = HashMap::new();
         for quad in data {
+            let validated;
+            let quad = if self.options.literal_validator.is_noop() {
+                quad
+            } else {
+                validated = self
+                    .options
+                    .literal_validator
+                    .validate_quad(quad.clone())
+                    .map_err(invalid_data_error)?;
+                &validated
+            };
             if let Some(quad) = self.encode_quad_for_insertion(quad, &mut bnodes)? {
                 self.write.insert_encoded(&quad).map_err(to_eval_error)?;
             }
@@ -113,17 +129,70 @@ where
         using: &QueryDataset,
         algebra: &GraphPattern,
     ) -> Result<(), EvaluationError> {
+        let validate_insertions = !insert.is_empty() && !self.options.literal_validator.is_noop();
+        let validated_insert;
+        let insert = if validate_insertions {
+            validated_insert = insert
+                .iter()
+                .map(|quad| validate_quad_pattern(&self.options.literal_validator, quad))
+                .collect::<Result<Vec<_>, _>>()?;
+            validated_insert.as_slice()
+        } else {
+            insert
+        };
         let dataset = Rc::new(DatasetView::new(self.read.clone(), using)?);
-        let (plan, variables) = PlanBuilder::build(dataset.as_ref(), algebra)?;
+        let (plan, variables) =
+            PlanBuilder::build(dataset.as_ref(), algebra, &self.options.query_options)?;
         let evaluator = SimpleEvaluator::<DatasetView<R>>::new(
             dataset.clone(),
             self.base_iri.clone(),
-            self.options.query_options.service_handler.clone(),
-        );
+            self.options.query_options.clone(),
+        )
+        .with_interruption(self.interruption.clone());
         let mut bnodes = HashMap::new();
+        let has_quoted_triples = delete.iter().chain(insert).any(|quad| {
+            matches!(quad.subject, TermOrVariable::Triple(_))
+                || matches!(quad.object, TermOrVariable::Triple(_))
+        });
         for tuple in evaluator.eval_plan(&plan, EncodedTuple::with_capacity(variables.len())) {
+            let tuple = tuple?;
+            // Quoted triples are built from the decoded terms
+            let terms = if has_quoted_triples {
+                tuple
+                    .iter()
+                    .map(|t| t.map(|t| dataset.decode_term(t)).transpose())
+                    .collect::<Result<Vec<_>, _>>()?
+            } else {
+                Vec::new()
+            };
+            // The inserted literals are validated
+            let validated_terms = if validate_insertions {
+                tuple
+                    .iter()
+                    .map(|t| {
+                        Ok(match t {
+                            Some(t) if t.is_literal() || t.is_triple() => {
+                                let term = dataset.decode_term(t)?;
+                                let validated = self
+                                    .options
+                                    .literal_validator
+                                    .validate_term(term.clone())
+                                    .map_err(invalid_data_error)?;
+                                if validated == term {
+                                    None
+                                } else {
+                                    Some(validated)
+                                }
+                            }
+                            _ => None,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, EvaluationError>>()?
+            } else {
+                Vec::new()
+            };
             // We map the tuple to only get store strings
-            let tuple = tuple?
+            let tuple = tuple
                 .into_iter()
                 .map(|t| {
                     Ok(if let Some(t) = t {
@@ -157,15 +226,45 @@ where
 
             for quad in delete {
                 if let Some(quad) =
-                    self.encode_quad_pattern_for_deletion(quad, &variables, &tuple)?
+                    self.encode_quad_pattern_for_deletion(quad, &variables, &tuple, &terms)?
                 {
                     self.write.remove_encoded(&quad).map_err(to_eval_error)?;
                 }
             }
+            let validated_tuple;
+            let validated_terms_for_triples;
+            let (insert_tuple, insert_terms) = if validated_terms.iter().any(Option::is_some) {
+                let mut tuple = tuple.clone();
+                let mut terms = terms.clone();
+                for (i, term) in validated_terms.into_iter().enumerate() {
+                    if let Some(term) = term {
+                        tuple[i] = Some(
+                            self.write
+                                .encode_term(term.as_ref())
+                                .map_err(to_eval_error)?,
+                        );
+                        if has_quoted_triples {
+                            terms[i] = Some(term);
+                        }
+                    }
+                }
+                validated_tuple = tuple;
+                validated_terms_for_triples = terms;
+                (
+                    validated_tuple.as_slice(),
+                    validated_terms_for_triples.as_slice(),
+                )
+            } else {
+                (tuple.as_slice(), terms.as_slice())
+            };
             for quad in insert {
-                if let Some(quad) =
-                    self.encode_quad_pattern_for_insertion(quad, &variables, &tuple, &mut bnodes)?
-                {
+                if let Some(quad) = self.encode_quad_pattern_for_insertion(
+                    quad,
+                    &variables,
+                    insert_tuple,
+                    insert_terms,
+                    &mut bnodes,
+                )? {
                     self.write.insert_encoded(&quad).map_err(to_eval_error)?;
                 }
             }
@@ -219,7 +318,8 @@ where
         load_graph(
             self.write,
             response.into_body(),
-            format,
+            GraphParser::from_format(format)
+                .with_literal_validator(self.options.literal_validator.clone()),
             to_graph_name,
             Some(from.as_str()),
         )
@@ -285,12 +385,11 @@ where
     ) -> Result<Option<EncodedQuad<R::StrId>>, EvaluationError> {
         Ok(Some(EncodedQuad {
             subject: match &quad.subject {
-                NamedOrBlankNode::NamedNode(subject) => {
-                    self.write.encode_named_node(subject.as_ref())
-                }
-                NamedOrBlankNode::BlankNode(subject) => self
+                Subject::NamedNode(subject) => self.write.encode_named_node(subject.as_ref()),
+                Subject::BlankNode(subject) => self
                     .write
                     .encode_blank_node(bnodes.entry(subject.clone()).or_default().as_ref()),
+                Subject::Triple(subject) => self.write.encode_triple(subject.as_ref().as_ref()),
             }
             .map_err(to_eval_error)?,
             predicate: self
@@ -303,6 +402,7 @@ where
                     .write
                     .encode_blank_node(bnodes.entry(object.clone()).or_default().as_ref()),
                 Term::Literal(object) => self.write.encode_literal(object.as_ref()),
+                Term::Triple(object) => self.write.encode_triple(object.as_ref().as_ref()),
             }
             .map_err(to_eval_error)?,
             graph_name: self
@@ -317,13 +417,18 @@ where
         quad: &QuadPattern,
         variables: &[Variable],
         values: &[Option<EncodedTerm<R::StrId>>],
+        terms: &[Option<Term>],
         bnodes: &mut HashMap<BlankNode, BlankNode>,
     ) -> Result<Option<EncodedQuad<R::StrId>>, EvaluationError> {
         Ok(Some(EncodedQuad {
-            subject: if let Some(subject) =
-                self.encode_term_for_insertion(&quad.subject, variables, values, bnodes, |t| {
-                    t.is_named_node() || t.is_blank_node()
-                })? {
+            subject: if let Some(subject) = self.encode_term_for_insertion(
+                &quad.subject,
+                variables,
+                values,
+                terms,
+                bnodes,
+                |t| t.is_named_node() || t.is_blank_node() || t.is_triple(),
+            )? {
                 subject
             } else {
                 return Ok(None);
@@ -335,10 +440,14 @@ where
             } else {
                 return Ok(None);
             },
-            object: if let Some(object) =
-                self.encode_term_for_insertion(&quad.object, variables, values, bnodes, |t| {
-                    !t.is_default_graph()
-                })? {
+            object: if let Some(object) = self.encode_term_for_insertion(
+                &quad.object,
+                variables,
+                values,
+                terms,
+                bnodes,
+                |t| !t.is_default_graph(),
+            )? {
                 object
             } else {
                 return Ok(None);
@@ -362,6 +471,7 @@ where
         term: &TermOrVariable,
         variables: &[Variable],
         values: &[Option<EncodedTerm<R::StrId>>],
+        terms: &[Option<Term>],
         bnodes: &mut HashMap<BlankNode, BlankNode>,
         validate: impl FnOnce(&EncodedTerm<R::StrId>) -> bool,
     ) -> Result<Option<EncodedTerm<R::StrId>>, EvaluationError> {
@@ -382,7 +492,7 @@ where
                     .and_then(|i| values.get(i))
                 {
                     if validate(term) {
-                        Some(*term)
+                        Some(term.clone())
                     } else {
                         None
                     }
@@ -390,6 +500,17 @@ where
                     None
                 }
             }
+            TermOrVariable::Triple(triple) => {
+                if let Some(triple) = quoted_triple(triple, variables, terms, Some(bnodes))? {
+                    Some(
+                        self.write
+                            .encode_triple(triple.as_ref())
+                            .map_err(to_eval_error)?,
+                    )
+                } else {
+                    None
+                }
+            }
         })
     }
 
@@ -412,7 +533,7 @@ where
                     .and_then(|i| values.get(i))
                 {
                     if term.is_named_node() {
-                        Some(*term)
+                        Some(term.clone())
                     } else {
                         None
                     }
@@ -430,7 +551,7 @@ where
         Ok(Some(EncodedQuad {
             subject: if let Some(subject) = self
                 .read
-                .get_encoded_named_or_blank_node(quad.subject.as_ref())
+                .get_encoded_subject(quad.subject.as_ref())
                 .map_err(to_eval_error)?
             {
                 subject
@@ -472,10 +593,11 @@ where
         quad: &QuadPattern,
         variables: &[Variable],
         values: &[Option<EncodedTerm<R::StrId>>],
+        terms: &[Option<Term>],
     ) -> Result<Option<EncodedQuad<R::StrId>>, EvaluationError> {
         Ok(Some(EncodedQuad {
             subject: if let Some(subject) =
-                self.encode_term_for_deletion(&quad.subject, variables, values)?
+                self.encode_term_for_deletion(&quad.subject, variables, values, terms)?
             {
                 subject
             } else {
@@ -489,7 +611,7 @@ where
                 return Ok(None);
             },
             object: if let Some(object) =
-                self.encode_term_for_deletion(&quad.object, variables, values)?
+                self.encode_term_for_deletion(&quad.object, variables, values, terms)?
             {
                 object
             } else {
@@ -514,6 +636,7 @@ where
         term: &TermOrVariable,
         variables: &[Variable],
         values: &[Option<EncodedTerm<R::StrId>>],
+        terms: &[Option<Term>],
     ) -> Result<Option<EncodedTerm<R::StrId>>, EvaluationError> {
         match term {
             TermOrVariable::Term(term) => {
@@ -533,11 +656,20 @@ where
                     .position(|v2| v == v2)
                     .and_then(|i| values.get(i))
                 {
-                    Some(*term)
+                    Some(term.clone())
                 } else {
                     None
                 },
             ),
+            TermOrVariable::Triple(triple) => {
+                if let Some(triple) = quoted_triple(triple, variables, terms, None)? {
+                    self.read
+                        .get_encoded_triple(triple.as_ref())
+                        .map_err(to_eval_error)
+                } else {
+                    Ok(None)
+                }
+            }
         }
     }
 
@@ -559,7 +691,7 @@ where
                     .and_then(|i| values.get(i))
                 {
                     if term.is_named_node() {
-                        Some(*term)
+                        Some(term.clone())
                     } else {
                         None
                     }
@@ -571,6 +703,106 @@ where
     }
 }
 
+/// Builds a quoted triple from a template and the decoded solution terms
+///
+/// The blank nodes are renamed using `bnodes` or rejected if it is `None`
+fn quoted_triple(
+    pattern: &TriplePattern,
+    variables: &[Variable],
+    terms: &[Option<Term>],
+    mut bnodes: Option<&mut HashMap<BlankNode, BlankNode>>,
+) -> Result<Option<Triple>, EvaluationError> {
+    let subject = match quoted_term(&pattern.subject, variables, terms, bnodes.as_deref_mut())? {
+        Some(Term::NamedNode(subject)) => subject.into(),
+        Some(Term::BlankNode(subject)) => subject.into(),
+        Some(Term::Triple(subject)) => Subject::Triple(subject),
+        Some(Term::Literal(_)) | None => return Ok(None),
+    };
+    let predicate = match &pattern.predicate {
+        NamedNodeOrVariable::NamedNode(predicate) => predicate.clone(),
+        NamedNodeOrVariable::Variable(v) => {
+            if let Some(Term::NamedNode(predicate)) = variable_term(v, variables, terms) {
+                predicate.clone()
+            } else {
+                return Ok(None);
+            }
+        }
+    };
+    let object = if let Some(object) = quoted_term(&pattern.object, variables, terms, bnodes)? {
+        object
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(Triple::new(subject, predicate, object)))
+}
+
+fn quoted_term(
+    term: &TermOrVariable,
+    variables: &[Variable],
+    terms: &[Option<Term>],
+    bnodes: Option<&mut HashMap<BlankNode, BlankNode>>,
+) -> Result<Option<Term>, EvaluationError> {
+    Ok(match term {
+        TermOrVariable::Term(Term::BlankNode(bnode)) => {
+            if let Some(bnodes) = bnodes {
+                Some(bnodes.entry(bnode.clone()).or_default().clone().into())
+            } else {
+                return Err(EvaluationError::msg(
+                    "Blank node are not allowed in deletion patterns",
+                ));
+            }
+        }
+        TermOrVariable::Term(term) => Some(term.clone()),
+        TermOrVariable::Variable(v) => variable_term(v, variables, terms).cloned(),
+        TermOrVariable::Triple(triple) => {
+            quoted_triple(triple, variables, terms, bnodes)?.map(Term::from)
+        }
+    })
+}
+
+fn variable_term<'a>(
+    variable: &Variable,
+    variables: &[Variable],
+    terms: &'a [Option<Term>],
+) -> Option<&'a Term> {
+    variables
+        .iter()
+        .position(|v| v == variable)
+        .and_then(|i| terms.get(i))
+        .and_then(|t| t.as_ref())
+}
+
+fn validate_quad_pattern(
+    validator: &LiteralValidator,
+    quad: &QuadPattern,
+) -> Result<QuadPattern, EvaluationError> {
+    Ok(QuadPattern {
+        subject: validate_term_or_variable(validator, &quad.subject)?,
+        predicate: quad.predicate.clone(),
+        object: validate_term_or_variable(validator, &quad.object)?,
+        graph_name: quad.graph_name.clone(),
+    })
+}
+
+fn validate_term_or_variable(
+    validator: &LiteralValidator,
+    term: &TermOrVariable,
+) -> Result<TermOrVariable, EvaluationError> {
+    Ok(match term {
+        TermOrVariable::Term(term) => TermOrVariable::Term(
+            validator
+                .validate_term(term.clone())
+                .map_err(invalid_data_error)?,
+        ),
+        TermOrVariable::Variable(variable) => TermOrVariable::Variable(variable.clone()),
+        TermOrVariable::Triple(triple) => TermOrVariable::Triple(Box::new(TriplePattern {
+            subject: validate_term_or_variable(validator, &triple.subject)?,
+            predicate: triple.predicate.clone(),
+            object: validate_term_or_variable(validator, &triple.object)?,
+        })),
+    })
+}
+
 fn to_eval_error(e: impl Into<EvaluationError>) -> EvaluationError {
     e.into()
 }
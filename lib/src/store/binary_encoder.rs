@@ -1,7 +1,8 @@
 use crate::error::invalid_data_error;
 use crate::model::xsd::*;
-use crate::store::numeric_encoder::StrId;
+use crate::store::numeric_encoder::{Decoder, StrContainer, StrId, WriteEncoder};
 use crate::store::small_string::SmallString;
+use crate::store::{ReadableEncodedStore, WritableEncodedStore};
 use siphasher::sip128::{Hasher128, SipHasher24};
 use std::hash::Hasher;
 use std::io;
@@ -10,15 +11,17 @@ use std::mem::size_of;
 
 type EncodedTerm = crate::store::numeric_encoder::EncodedTerm<StrHash>;
 type EncodedQuad = crate::store::numeric_encoder::EncodedQuad<StrHash>;
+type EncodedTriple = crate::store::numeric_encoder::EncodedTriple<StrHash>;
 
-pub const LATEST_STORAGE_VERSION: u64 = 0;
+pub const LATEST_STORAGE_VERSION: u64 = 1;
 pub const WRITTEN_TERM_MAX_SIZE: usize = size_of::<u8>() + 2 * size_of::<StrHash>();
 
 // Encoded term type blocks
 // 1-7: usual named nodes (except prefixes c.f. later)
 // 8-15: blank nodes
 // 16-47: literals
-// 48-64: future use
+// 48-55: quoted triples
+// 56-63: literals (continued)
 // 64-127: default named node prefixes
 // 128-255: custom named node prefixes
 const TYPE_NAMED_NODE_ID: u8 = 1;
@@ -50,6 +53,29 @@ const TYPE_G_MONTH_LITERAL: u8 = 41;
 const TYPE_DURATION_LITERAL: u8 = 42;
 const TYPE_YEAR_MONTH_DURATION_LITERAL: u8 = 43;
 const TYPE_DAY_TIME_DURATION_LITERAL: u8 = 44;
+const TYPE_BIG_INTEGER_LITERAL: u8 = 45;
+const TYPE_BIG_DECIMAL_LITERAL: u8 = 46;
+const TYPE_DERIVED_INTEGER_LITERAL: u8 = 47;
+const TYPE_TRIPLE: u8 = 48;
+const TYPE_BIG_DERIVED_INTEGER_LITERAL: u8 = 56;
+const TYPE_HEX_BINARY_LITERAL: u8 = 57;
+const TYPE_BASE64_BINARY_LITERAL: u8 = 58;
+const TYPE_SMALL_ANY_URI_LITERAL: u8 = 59;
+const TYPE_BIG_ANY_URI_LITERAL: u8 = 60;
+
+// Ids of the datatypes derived from xsd:integer
+const DERIVED_INTEGER_TYPE_LONG: u8 = 0;
+const DERIVED_INTEGER_TYPE_INT: u8 = 1;
+const DERIVED_INTEGER_TYPE_SHORT: u8 = 2;
+const DERIVED_INTEGER_TYPE_BYTE: u8 = 3;
+const DERIVED_INTEGER_TYPE_NON_NEGATIVE_INTEGER: u8 = 4;
+const DERIVED_INTEGER_TYPE_POSITIVE_INTEGER: u8 = 5;
+const DERIVED_INTEGER_TYPE_NON_POSITIVE_INTEGER: u8 = 6;
+const DERIVED_INTEGER_TYPE_NEGATIVE_INTEGER: u8 = 7;
+const DERIVED_INTEGER_TYPE_UNSIGNED_LONG: u8 = 8;
+const DERIVED_INTEGER_TYPE_UNSIGNED_INT: u8 = 9;
+const DERIVED_INTEGER_TYPE_UNSIGNED_SHORT: u8 = 10;
+const DERIVED_INTEGER_TYPE_UNSIGNED_BYTE: u8 = 11;
 
 #[derive(Eq, PartialEq, Debug, Copy, Clone, Hash)]
 #[repr(transparent)]
@@ -262,6 +288,12 @@ impl<R: Read> TermReader for R {
                     id_id: StrHash::from_be_bytes(buffer),
                 })
             }
+            TYPE_TRIPLE => {
+                Ok(
+                    EncodedTriple::new(self.read_term()?, self.read_term()?, self.read_term()?)
+                        .into(),
+                )
+            }
             TYPE_SMALL_SMALL_LANG_STRING_LITERAL => {
                 let mut language_buffer = [0; 16];
                 self.read_exact(&mut language_buffer)?;
@@ -360,6 +392,66 @@ impl<R: Read> TermReader for R {
                 self.read_exact(&mut buffer)?;
                 Ok(EncodedTerm::DecimalLiteral(Decimal::from_be_bytes(buffer)))
             }
+            TYPE_BIG_INTEGER_LITERAL => {
+                let mut buffer = [0; 16];
+                self.read_exact(&mut buffer)?;
+                Ok(EncodedTerm::BigIntegerLiteral {
+                    value_id: StrHash::from_be_bytes(buffer),
+                })
+            }
+            TYPE_BIG_DECIMAL_LITERAL => {
+                let mut buffer = [0; 16];
+                self.read_exact(&mut buffer)?;
+                Ok(EncodedTerm::BigDecimalLiteral {
+                    value_id: StrHash::from_be_bytes(buffer),
+                })
+            }
+            TYPE_DERIVED_INTEGER_LITERAL => {
+                let datatype = read_derived_integer_type(self)?;
+                let mut buffer = [0; 8];
+                self.read_exact(&mut buffer)?;
+                Ok(EncodedTerm::DerivedIntegerLiteral {
+                    value: i64::from_be_bytes(buffer),
+                    datatype,
+                })
+            }
+            TYPE_BIG_DERIVED_INTEGER_LITERAL => {
+                let datatype = read_derived_integer_type(self)?;
+                let mut buffer = [0; 16];
+                self.read_exact(&mut buffer)?;
+                Ok(EncodedTerm::BigDerivedIntegerLiteral {
+                    value_id: StrHash::from_be_bytes(buffer),
+                    datatype,
+                })
+            }
+            TYPE_HEX_BINARY_LITERAL => {
+                let mut buffer = [0; 16];
+                self.read_exact(&mut buffer)?;
+                Ok(EncodedTerm::HexBinaryLiteral {
+                    value_id: StrHash::from_be_bytes(buffer),
+                })
+            }
+            TYPE_BASE64_BINARY_LITERAL => {
+                let mut buffer = [0; 16];
+                self.read_exact(&mut buffer)?;
+                Ok(EncodedTerm::Base64BinaryLiteral {
+                    value_id: StrHash::from_be_bytes(buffer),
+                })
+            }
+            TYPE_SMALL_ANY_URI_LITERAL => {
+                let mut buffer = [0; 16];
+                self.read_exact(&mut buffer)?;
+                Ok(EncodedTerm::SmallAnyUriLiteral(
+                    SmallString::from_be_bytes(buffer).map_err(invalid_data_error)?,
+                ))
+            }
+            TYPE_BIG_ANY_URI_LITERAL => {
+                let mut buffer = [0; 16];
+                self.read_exact(&mut buffer)?;
+                Ok(EncodedTerm::BigAnyUriLiteral {
+                    value_id: StrHash::from_be_bytes(buffer),
+                })
+            }
             TYPE_DATE_TIME_LITERAL => {
                 let mut buffer = [0; 18];
                 self.read_exact(&mut buffer)?;
@@ -432,80 +524,163 @@ impl<R: Read> TermReader for R {
     }
 }
 
+fn read_derived_integer_type(read: &mut impl Read) -> Result<DerivedIntegerType, io::Error> {
+    let mut buffer = [0];
+    read.read_exact(&mut buffer)?;
+    Ok(match buffer[0] {
+        DERIVED_INTEGER_TYPE_LONG => DerivedIntegerType::Long,
+        DERIVED_INTEGER_TYPE_INT => DerivedIntegerType::Int,
+        DERIVED_INTEGER_TYPE_SHORT => DerivedIntegerType::Short,
+        DERIVED_INTEGER_TYPE_BYTE => DerivedIntegerType::Byte,
+        DERIVED_INTEGER_TYPE_NON_NEGATIVE_INTEGER => DerivedIntegerType::NonNegativeInteger,
+        DERIVED_INTEGER_TYPE_POSITIVE_INTEGER => DerivedIntegerType::PositiveInteger,
+        DERIVED_INTEGER_TYPE_NON_POSITIVE_INTEGER => DerivedIntegerType::NonPositiveInteger,
+        DERIVED_INTEGER_TYPE_NEGATIVE_INTEGER => DerivedIntegerType::NegativeInteger,
+        DERIVED_INTEGER_TYPE_UNSIGNED_LONG => DerivedIntegerType::UnsignedLong,
+        DERIVED_INTEGER_TYPE_UNSIGNED_INT => DerivedIntegerType::UnsignedInt,
+        DERIVED_INTEGER_TYPE_UNSIGNED_SHORT => DerivedIntegerType::UnsignedShort,
+        DERIVED_INTEGER_TYPE_UNSIGNED_BYTE => DerivedIntegerType::UnsignedByte,
+        _ => {
+            return Err(invalid_data_error(
+                "the term buffer has an invalid integer datatype id",
+            ))
+        }
+    })
+}
+
+fn write_derived_integer_type(sink: &mut Vec<u8>, datatype: DerivedIntegerType) {
+    sink.push(match datatype {
+        DerivedIntegerType::Long => DERIVED_INTEGER_TYPE_LONG,
+        DerivedIntegerType::Int => DERIVED_INTEGER_TYPE_INT,
+        DerivedIntegerType::Short => DERIVED_INTEGER_TYPE_SHORT,
+        DerivedIntegerType::Byte => DERIVED_INTEGER_TYPE_BYTE,
+        DerivedIntegerType::NonNegativeInteger => DERIVED_INTEGER_TYPE_NON_NEGATIVE_INTEGER,
+        DerivedIntegerType::PositiveInteger => DERIVED_INTEGER_TYPE_POSITIVE_INTEGER,
+        DerivedIntegerType::NonPositiveInteger => DERIVED_INTEGER_TYPE_NON_POSITIVE_INTEGER,
+        DerivedIntegerType::NegativeInteger => DERIVED_INTEGER_TYPE_NEGATIVE_INTEGER,
+        DerivedIntegerType::UnsignedLong => DERIVED_INTEGER_TYPE_UNSIGNED_LONG,
+        DerivedIntegerType::UnsignedInt => DERIVED_INTEGER_TYPE_UNSIGNED_INT,
+        DerivedIntegerType::UnsignedShort => DERIVED_INTEGER_TYPE_UNSIGNED_SHORT,
+        DerivedIntegerType::UnsignedByte => DERIVED_INTEGER_TYPE_UNSIGNED_BYTE,
+    })
+}
+
+/// Migrates the quads written with the storage version 0 to the version 1 encoding.
+///
+/// The version 1 introduced dedicated encodings for the `xsd:hexBinary`, `xsd:base64Binary` and `xsd:anyURI` literals,
+/// for the integers and decimals that do not fit into the fixed size encodings and for the datatypes derived from `xsd:integer`.
+/// These literals were previously stored as generic typed literals, so the quads containing them are re-encoded.
+/// The version 0 already stored the valid values of the datatypes derived from `xsd:integer` as plain `xsd:integer` values:
+/// they are kept as is because their original datatype is lost.
+pub(crate) fn migrate_from_v0<S, W>(store: &S, writer: &mut W) -> Result<(), io::Error>
+where
+    S: ReadableEncodedStore<StrId = StrHash, Error = io::Error>,
+    W: WritableEncodedStore<StrId = StrHash, Error = io::Error> + StrContainer,
+{
+    let mut to_migrate = Vec::new();
+    for quad in store.encoded_quads_for_pattern(None, None, None, None) {
+        let quad = quad?;
+        if has_generic_typed_literal(&quad.subject)
+            || has_generic_typed_literal(&quad.object)
+            || has_generic_typed_literal(&quad.graph_name)
+        {
+            let new_quad = writer.encode_quad(store.decode_quad(&quad)?.as_ref())?;
+            if new_quad != quad {
+                to_migrate.push((quad, new_quad));
+            }
+        }
+    }
+    for (old_quad, new_quad) in to_migrate {
+        writer.remove_encoded(&old_quad)?;
+        writer.insert_encoded(&new_quad)?;
+    }
+    Ok(())
+}
+
+fn has_generic_typed_literal(term: &EncodedTerm) -> bool {
+    match term {
+        EncodedTerm::SmallTypedLiteral { .. } | EncodedTerm::BigTypedLiteral { .. } => true,
+        EncodedTerm::Triple(triple) => {
+            has_generic_typed_literal(&triple.subject) || has_generic_typed_literal(&triple.object)
+        }
+        _ => false,
+    }
+}
+
 pub fn write_spog_quad(sink: &mut Vec<u8>, quad: &EncodedQuad) {
-    write_term(sink, quad.subject);
-    write_term(sink, quad.predicate);
-    write_term(sink, quad.object);
-    write_term(sink, quad.graph_name);
+    write_term(sink, &quad.subject);
+    write_term(sink, &quad.predicate);
+    write_term(sink, &quad.object);
+    write_term(sink, &quad.graph_name);
 }
 
 pub fn write_posg_quad(sink: &mut Vec<u8>, quad: &EncodedQuad) {
-    write_term(sink, quad.predicate);
-    write_term(sink, quad.object);
-    write_term(sink, quad.subject);
-    write_term(sink, quad.graph_name);
+    write_term(sink, &quad.predicate);
+    write_term(sink, &quad.object);
+    write_term(sink, &quad.subject);
+    write_term(sink, &quad.graph_name);
 }
 
 pub fn write_ospg_quad(sink: &mut Vec<u8>, quad: &EncodedQuad) {
-    write_term(sink, quad.object);
-    write_term(sink, quad.subject);
-    write_term(sink, quad.predicate);
-    write_term(sink, quad.graph_name);
+    write_term(sink, &quad.object);
+    write_term(sink, &quad.subject);
+    write_term(sink, &quad.predicate);
+    write_term(sink, &quad.graph_name);
 }
 
 pub fn write_gspo_quad(sink: &mut Vec<u8>, quad: &EncodedQuad) {
-    write_term(sink, quad.graph_name);
-    write_term(sink, quad.subject);
-    write_term(sink, quad.predicate);
-    write_term(sink, quad.object);
+    write_term(sink, &quad.graph_name);
+    write_term(sink, &quad.subject);
+    write_term(sink, &quad.predicate);
+    write_term(sink, &quad.object);
 }
 
 pub fn write_gpos_quad(sink: &mut Vec<u8>, quad: &EncodedQuad) {
-    write_term(sink, quad.graph_name);
-    write_term(sink, quad.predicate);
-    write_term(sink, quad.object);
-    write_term(sink, quad.subject);
+    write_term(sink, &quad.graph_name);
+    write_term(sink, &quad.predicate);
+    write_term(sink, &quad.object);
+    write_term(sink, &quad.subject);
 }
 
 pub fn write_gosp_quad(sink: &mut Vec<u8>, quad: &EncodedQuad) {
-    write_term(sink, quad.graph_name);
-    write_term(sink, quad.object);
-    write_term(sink, quad.subject);
-    write_term(sink, quad.predicate);
+    write_term(sink, &quad.graph_name);
+    write_term(sink, &quad.object);
+    write_term(sink, &quad.subject);
+    write_term(sink, &quad.predicate);
 }
 
 pub fn write_spo_quad(sink: &mut Vec<u8>, quad: &EncodedQuad) {
-    write_term(sink, quad.subject);
-    write_term(sink, quad.predicate);
-    write_term(sink, quad.object);
+    write_term(sink, &quad.subject);
+    write_term(sink, &quad.predicate);
+    write_term(sink, &quad.object);
 }
 
 pub fn write_pos_quad(sink: &mut Vec<u8>, quad: &EncodedQuad) {
-    write_term(sink, quad.predicate);
-    write_term(sink, quad.object);
-    write_term(sink, quad.subject);
+    write_term(sink, &quad.predicate);
+    write_term(sink, &quad.object);
+    write_term(sink, &quad.subject);
 }
 
 pub fn write_osp_quad(sink: &mut Vec<u8>, quad: &EncodedQuad) {
-    write_term(sink, quad.object);
-    write_term(sink, quad.subject);
-    write_term(sink, quad.predicate);
+    write_term(sink, &quad.object);
+    write_term(sink, &quad.subject);
+    write_term(sink, &quad.predicate);
 }
 
-pub fn encode_term(t: EncodedTerm) -> Vec<u8> {
+pub fn encode_term(t: &EncodedTerm) -> Vec<u8> {
     let mut vec = Vec::with_capacity(WRITTEN_TERM_MAX_SIZE);
     write_term(&mut vec, t);
     vec
 }
 
-pub fn encode_term_pair(t1: EncodedTerm, t2: EncodedTerm) -> Vec<u8> {
+pub fn encode_term_pair(t1: &EncodedTerm, t2: &EncodedTerm) -> Vec<u8> {
     let mut vec = Vec::with_capacity(2 * WRITTEN_TERM_MAX_SIZE);
     write_term(&mut vec, t1);
     write_term(&mut vec, t2);
     vec
 }
 
-pub fn encode_term_triple(t1: EncodedTerm, t2: EncodedTerm, t3: EncodedTerm) -> Vec<u8> {
+pub fn encode_term_triple(t1: &EncodedTerm, t2: &EncodedTerm, t3: &EncodedTerm) -> Vec<u8> {
     let mut vec = Vec::with_capacity(3 * WRITTEN_TERM_MAX_SIZE);
     write_term(&mut vec, t1);
     write_term(&mut vec, t2);
@@ -514,10 +689,10 @@ pub fn encode_term_triple(t1: EncodedTerm, t2: EncodedTerm, t3: EncodedTerm) ->
 }
 
 pub fn encode_term_quad(
-    t1: EncodedTerm,
-    t2: EncodedTerm,
-    t3: EncodedTerm,
-    t4: EncodedTerm,
+    t1: &EncodedTerm,
+    t2: &EncodedTerm,
+    t3: &EncodedTerm,
+    t4: &EncodedTerm,
 ) -> Vec<u8> {
     let mut vec = Vec::with_capacity(4 * WRITTEN_TERM_MAX_SIZE);
     write_term(&mut vec, t1);
@@ -527,7 +702,7 @@ pub fn encode_term_quad(
     vec
 }
 
-pub fn write_term(sink: &mut Vec<u8>, term: EncodedTerm) {
+pub fn write_term(sink: &mut Vec<u8>, term: &EncodedTerm) {
     match term {
         EncodedTerm::DefaultGraph => (),
         EncodedTerm::NamedNode { iri_id } => {
@@ -608,6 +783,40 @@ pub fn write_term(sink: &mut Vec<u8>, term: EncodedTerm) {
             sink.push(TYPE_DECIMAL_LITERAL);
             sink.extend_from_slice(&value.to_be_bytes())
         }
+        EncodedTerm::BigIntegerLiteral { value_id } => {
+            sink.push(TYPE_BIG_INTEGER_LITERAL);
+            sink.extend_from_slice(&value_id.to_be_bytes());
+        }
+        EncodedTerm::BigDecimalLiteral { value_id } => {
+            sink.push(TYPE_BIG_DECIMAL_LITERAL);
+            sink.extend_from_slice(&value_id.to_be_bytes());
+        }
+        EncodedTerm::DerivedIntegerLiteral { value, datatype } => {
+            sink.push(TYPE_DERIVED_INTEGER_LITERAL);
+            write_derived_integer_type(sink, *datatype);
+            sink.extend_from_slice(&value.to_be_bytes())
+        }
+        EncodedTerm::BigDerivedIntegerLiteral { value_id, datatype } => {
+            sink.push(TYPE_BIG_DERIVED_INTEGER_LITERAL);
+            write_derived_integer_type(sink, *datatype);
+            sink.extend_from_slice(&value_id.to_be_bytes());
+        }
+        EncodedTerm::HexBinaryLiteral { value_id } => {
+            sink.push(TYPE_HEX_BINARY_LITERAL);
+            sink.extend_from_slice(&value_id.to_be_bytes());
+        }
+        EncodedTerm::Base64BinaryLiteral { value_id } => {
+            sink.push(TYPE_BASE64_BINARY_LITERAL);
+            sink.extend_from_slice(&value_id.to_be_bytes());
+        }
+        EncodedTerm::SmallAnyUriLiteral(value) => {
+            sink.push(TYPE_SMALL_ANY_URI_LITERAL);
+            sink.extend_from_slice(&value.to_be_bytes())
+        }
+        EncodedTerm::BigAnyUriLiteral { value_id } => {
+            sink.push(TYPE_BIG_ANY_URI_LITERAL);
+            sink.extend_from_slice(&value_id.to_be_bytes());
+        }
         EncodedTerm::DateTimeLiteral(value) => {
             sink.push(TYPE_DATE_TIME_LITERAL);
             sink.extend_from_slice(&value.to_be_bytes())
@@ -652,6 +861,12 @@ pub fn write_term(sink: &mut Vec<u8>, term: EncodedTerm) {
             sink.push(TYPE_DAY_TIME_DURATION_LITERAL);
             sink.extend_from_slice(&value.to_be_bytes())
         }
+        EncodedTerm::Triple(value) => {
+            sink.push(TYPE_TRIPLE);
+            write_term(sink, &value.subject);
+            write_term(sink, &value.predicate);
+            write_term(sink, &value.object);
+        }
     }
 }
 
@@ -660,7 +875,7 @@ mod tests {
     use super::*;
     use crate::store::numeric_encoder::*;
     use std::collections::HashMap;
-    use std::convert::Infallible;
+    use std::convert::{Infallible, TryFrom};
 
     struct MemoryStrStore {
         id2str: HashMap<StrHash, String>,
@@ -738,6 +953,20 @@ mod tests {
             )
             .into(),
             Literal::new_typed_literal("-1.32", xsd::DECIMAL).into(),
+            Literal::new_typed_literal("-123456789012345678901234567890", xsd::INTEGER).into(),
+            Literal::new_typed_literal("1.0000000000000000000001", xsd::DECIMAL).into(),
+            Literal::new_typed_literal("-12", xsd::INT).into(),
+            Literal::new_typed_literal("18446744073709551615", xsd::UNSIGNED_LONG).into(),
+            Literal::new_typed_literal("300", xsd::BYTE).into(),
+            Literal::new_typed_literal("0FB7", xsd::HEX_BINARY).into(),
+            Literal::new_typed_literal("SGVsbG8=", xsd::BASE_64_BINARY).into(),
+            Literal::new_typed_literal("0fb7", xsd::BASE_64_BINARY).into(),
+            Literal::new_typed_literal("http://example.com", xsd::ANY_URI).into(),
+            Literal::new_typed_literal(
+                "http://example.com/thisisaverylargeanyurivalue",
+                xsd::ANY_URI,
+            )
+            .into(),
             Literal::new_typed_literal("2020-01-01T01:01:01Z", xsd::DATE_TIME).into(),
             Literal::new_typed_literal("2020-01-01", xsd::DATE).into(),
             Literal::new_typed_literal("01:01:01Z", xsd::TIME).into(),
@@ -755,18 +984,99 @@ mod tests {
                 NamedNode::new_unchecked("http://foo.com"),
             )
             .into(),
+            Triple::new(
+                NamedNode::new_unchecked("http://foo.com"),
+                NamedNode::new_unchecked("http://bar.com"),
+                Literal::from(true),
+            )
+            .into(),
+            Triple::new(
+                Triple::new(
+                    BlankNode::default(),
+                    NamedNode::new_unchecked("http://bar.com"),
+                    BlankNode::new_unchecked("bnode"),
+                ),
+                NamedNode::new_unchecked("http://bar.com"),
+                Literal::new_language_tagged_literal_unchecked("foo \"bar\"\n", "fr"),
+            )
+            .into(),
         ];
         for term in terms {
             let encoded = store.encode_term(term.as_ref()).unwrap();
             assert_eq!(
-                Some(encoded),
+                Some(encoded.clone()),
                 store.get_encoded_term(term.as_ref()).unwrap()
             );
-            assert_eq!(term, store.decode_term(encoded).unwrap());
+            assert_eq!(term, store.decode_term(encoded.clone()).unwrap());
 
             let mut buffer = Vec::new();
-            write_term(&mut buffer, encoded);
+            write_term(&mut buffer, &encoded);
             assert_eq!(encoded, Cursor::new(&buffer).read_term().unwrap());
         }
     }
+
+    #[test]
+    fn test_derived_integer_type_ids() {
+        for (i, datatype) in DerivedIntegerType::ALL.iter().enumerate() {
+            let mut buffer = Vec::new();
+            write_derived_integer_type(&mut buffer, *datatype);
+            assert_eq!(buffer, vec![u8::try_from(i).unwrap()]);
+            assert_eq!(
+                read_derived_integer_type(&mut Cursor::new(&buffer)).unwrap(),
+                *datatype
+            );
+        }
+        assert!(read_derived_integer_type(&mut Cursor::new(&[12])).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "sled")]
+    fn test_migrate_from_v0() -> io::Result<()> {
+        use super::{EncodedQuad, EncodedTerm};
+        use crate::model::vocab::xsd;
+        use crate::model::*;
+        use crate::store::{SledStore, WritableEncodedStore};
+
+        let store = SledStore::new()?;
+        let mut writer = &store;
+        let ex = NamedNodeRef::new_unchecked("http://example.com");
+        let encoded_ex = writer.encode_named_node(ex)?;
+        let literals = [
+            ("0fb7", xsd::HEX_BINARY),
+            ("SGVsbG8=", xsd::BASE_64_BINARY),
+            ("http://example.com/foo", xsd::ANY_URI),
+            ("123456789012345678901234567890", xsd::INTEGER),
+            ("18446744073709551615", xsd::UNSIGNED_LONG),
+        ];
+        for (value, datatype) in &literals {
+            // The version 0 generic typed literal encoding
+            let datatype_id = writer.insert_str(datatype.as_str())?;
+            let object = if let Ok(value) = SmallString::try_from(*value) {
+                EncodedTerm::SmallTypedLiteral { value, datatype_id }
+            } else {
+                EncodedTerm::BigTypedLiteral {
+                    value_id: writer.insert_str(value)?,
+                    datatype_id,
+                }
+            };
+            writer.insert_encoded(&EncodedQuad::new(
+                encoded_ex.clone(),
+                encoded_ex.clone(),
+                object,
+                EncodedTerm::DefaultGraph,
+            ))?;
+        }
+        for (value, datatype) in &literals {
+            let literal = Literal::new_typed_literal(*value, *datatype);
+            assert!(!store.contains(QuadRef::new(ex, ex, &literal, GraphNameRef::DefaultGraph))?);
+        }
+
+        migrate_from_v0(&store, &mut writer)?;
+        for (value, datatype) in &literals {
+            let literal = Literal::new_typed_literal(*value, *datatype);
+            assert!(store.contains(QuadRef::new(ex, ex, &literal, GraphNameRef::DefaultGraph))?);
+        }
+        assert_eq!(store.len(), literals.len());
+        Ok(())
+    }
 }
@@ -0,0 +1,535 @@
+//! Parallel loading of the line based formats (N-Triples and N-Quads) into the persistent stores.
+//!
+//! The input is split into chunks at line boundaries. The chunks are parsed and encoded by a pool of threads
+//! and the encoded quads are written into the store by the calling thread.
+
+use crate::error::{invalid_input_error, UnwrapInfallible};
+use crate::io::{
+    DatasetFormat, DatasetParser, GraphFormat, GraphParser, SyntaxError, TextPosition,
+};
+use crate::model::GraphNameRef;
+use crate::store::binary_encoder::StrHash;
+use crate::store::numeric_encoder::*;
+use crate::store::{load_dataset, load_graph, StoreOrParseError, WritableEncodedStore};
+use oxiri::Iri;
+use rand::random;
+use rio_api::model as rio;
+use rio_api::parser::{QuadsParser, TriplesParser};
+use rio_turtle::{NQuadsParser, NTriplesParser, TurtleError};
+use siphasher::sip128::{Hasher128, SipHasher24};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::hash::Hasher;
+use std::io;
+use std::io::{BufRead, Read};
+use std::num::NonZeroUsize;
+use std::sync::mpsc::{channel, sync_channel, Receiver, Sender, TrySendError};
+use std::sync::Mutex;
+use std::thread;
+
+/// Minimal number of bytes in a chunk sent to the parsing threads
+const CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+pub(crate) fn bulk_load_graph<S: WritableEncodedStore<StrId = StrHash> + StrContainer>(
+    store: &mut S,
+    reader: impl BufRead,
+    parser: GraphParser,
+    to_graph_name: GraphNameRef<'_>,
+    base_iri: Option<&str>,
+) -> Result<HashMap<String, String>, StoreOrParseError<S::Error>> {
+    if parser.format() != GraphFormat::NTriples || parser.validates_literals() {
+        return load_graph(store, reader, parser, to_graph_name, base_iri);
+    }
+    if let Some(base_iri) = base_iri {
+        Iri::parse(base_iri).map_err(invalid_input_error)?;
+    }
+    let to_graph_name = store
+        .encode_graph_name(to_graph_name)
+        .map_err(StoreOrParseError::Store)?;
+    let reader = parser.decompress(reader)?;
+    load_chunks(
+        store,
+        ChunkReader::new(reader, CHUNK_SIZE),
+        &LineFormat::NTriples { to_graph_name },
+        parser.error_callback(),
+    )?;
+    Ok(HashMap::new())
+}
+
+pub(crate) fn bulk_load_dataset<S: WritableEncodedStore<StrId = StrHash> + StrContainer>(
+    store: &mut S,
+    reader: impl BufRead,
+    parser: DatasetParser,
+    base_iri: Option<&str>,
+) -> Result<HashMap<String, String>, StoreOrParseError<S::Error>> {
+    if parser.format() != DatasetFormat::NQuads || parser.validates_literals() {
+        return load_dataset(store, reader, parser, base_iri);
+    }
+    if let Some(base_iri) = base_iri {
+        Iri::parse(base_iri).map_err(invalid_input_error)?;
+    }
+    let reader = parser.decompress(reader)?;
+    load_chunks(
+        store,
+        ChunkReader::new(reader, CHUNK_SIZE),
+        &LineFormat::NQuads,
+        parser.error_callback(),
+    )?;
+    Ok(HashMap::new())
+}
+
+enum LineFormat {
+    NTriples { to_graph_name: EncodedTerm<StrHash> },
+    NQuads,
+}
+
+/// Parses the chunks on a pool of threads and writes their content into the store
+fn load_chunks<S: WritableEncodedStore<StrId = StrHash> + StrContainer>(
+    store: &mut S,
+    mut chunks: ChunkReader<impl Read>,
+    format: &LineFormat,
+    on_error: Option<&(dyn Fn(SyntaxError) + Send + Sync)>,
+) -> Result<(), StoreOrParseError<S::Error>> {
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let blank_nodes = BlankNodeIds::new();
+    let (chunk_sender, chunk_receiver) = sync_channel::<Chunk>(2 * threads);
+    let chunk_receiver = Mutex::new(chunk_receiver);
+    let (result_sender, result_receiver) = channel();
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            let chunk_receiver = &chunk_receiver;
+            let result_sender = result_sender.clone();
+            let blank_nodes = &blank_nodes;
+            scope.spawn(move || {
+                parse_chunks(
+                    chunk_receiver,
+                    &result_sender,
+                    format,
+                    blank_nodes,
+                    on_error,
+                )
+            });
+        }
+        drop(result_sender);
+
+        let mut pending = 0;
+        while let Some(mut chunk) = chunks.next_chunk()? {
+            // We write the parsed chunks while the parsing threads are busy
+            loop {
+                match chunk_sender.try_send(chunk) {
+                    Ok(()) => {
+                        pending += 1;
+                        break;
+                    }
+                    Err(TrySendError::Full(c)) => {
+                        chunk = c;
+                        write_chunk(store, &receive(&result_receiver)?)?;
+                        pending -= 1;
+                    }
+                    Err(TrySendError::Disconnected(_)) => {
+                        return Err(
+                            io::Error::other("The bulk load parsing threads have stopped").into(),
+                        )
+                    }
+                }
+            }
+            while let Ok(result) = result_receiver.try_recv() {
+                write_chunk(store, &result?)?;
+                pending -= 1;
+            }
+        }
+        drop(chunk_sender);
+        for _ in 0..pending {
+            write_chunk(store, &receive(&result_receiver)?)?;
+        }
+        Ok(())
+    })
+}
+
+fn receive(receiver: &Receiver<io::Result<EncodedChunk>>) -> io::Result<EncodedChunk> {
+    receiver
+        .recv()
+        .map_err(|_| io::Error::other("The bulk load parsing threads have stopped"))?
+}
+
+fn write_chunk<S: WritableEncodedStore<StrId = StrHash> + StrContainer>(
+    store: &mut S,
+    chunk: &EncodedChunk,
+) -> Result<(), StoreOrParseError<S::Error>> {
+    for value in chunk.strings.values() {
+        store.insert_str(value).map_err(StoreOrParseError::Store)?;
+    }
+    for quad in &chunk.quads {
+        store
+            .insert_encoded(quad)
+            .map_err(StoreOrParseError::Store)?;
+    }
+    Ok(())
+}
+
+/// The parsing threads loop
+fn parse_chunks(
+    chunks: &Mutex<Receiver<Chunk>>,
+    results: &Sender<io::Result<EncodedChunk>>,
+    format: &LineFormat,
+    blank_nodes: &BlankNodeIds,
+    on_error: Option<&(dyn Fn(SyntaxError) + Send + Sync)>,
+) {
+    loop {
+        let chunk = match chunks.lock().unwrap().recv() {
+            Ok(chunk) => chunk,
+            Err(_) => return, // No more chunks
+        };
+        if results
+            .send(parse_chunk(&chunk, format, blank_nodes, on_error))
+            .is_err()
+        {
+            return; // The load has been aborted
+        }
+    }
+}
+
+fn parse_chunk(
+    chunk: &Chunk,
+    format: &LineFormat,
+    blank_nodes: &BlankNodeIds,
+    on_error: Option<&(dyn Fn(SyntaxError) + Send + Sync)>,
+) -> io::Result<EncodedChunk> {
+    let mut encoder = ChunkEncoder::default();
+    let mut quads = Vec::new();
+    let mut bnodes_map = HashMap::new();
+    let handle_error = |error: TurtleError| {
+        let syntax_error =
+            SyntaxError::from_turtle(&error, |line, column| Some(chunk.position(line, column)));
+        if let Some(on_error) = on_error {
+            on_error(syntax_error);
+            Ok(())
+        } else {
+            let kind = io::Error::from(error).kind();
+            Err(io::Error::new(kind, syntax_error))
+        }
+    };
+    match format {
+        LineFormat::NTriples { to_graph_name } => {
+            let mut parser = NTriplesParser::new(chunk.data.as_slice());
+            while !parser.is_end() {
+                if let Err(error) = parser.parse_step(&mut |t| {
+                    blank_nodes.add_triple(&t, &mut bnodes_map);
+                    quads.push(
+                        encoder
+                            .encode_rio_triple_in_graph(t, to_graph_name.clone(), &mut bnodes_map)
+                            .unwrap_infallible(),
+                    );
+                    Ok(())
+                }) {
+                    handle_error(error)?;
+                }
+            }
+        }
+        LineFormat::NQuads => {
+            let mut parser = NQuadsParser::new(chunk.data.as_slice());
+            while !parser.is_end() {
+                if let Err(error) = parser.parse_step(&mut |q| {
+                    blank_nodes.add_quad(&q, &mut bnodes_map);
+                    quads.push(
+                        encoder
+                            .encode_rio_quad(q, &mut bnodes_map)
+                            .unwrap_infallible(),
+                    );
+                    Ok(())
+                }) {
+                    handle_error(error)?;
+                }
+            }
+        }
+    }
+    Ok(EncodedChunk {
+        strings: encoder.strings,
+        quads,
+    })
+}
+
+/// Allocates the blank node ids from the blank node labels
+///
+/// The ids only depend on the label and on random keys shared by all the chunks of a file.
+/// It allows to keep the blank nodes consistent in the file while keeping them distinct between files.
+struct BlankNodeIds {
+    key0: u64,
+    key1: u64,
+}
+
+impl BlankNodeIds {
+    fn new() -> Self {
+        Self {
+            key0: random(),
+            key1: random(),
+        }
+    }
+
+    fn id(&self, label: &str) -> u128 {
+        let mut hasher = SipHasher24::new_with_keys(self.key0, self.key1);
+        hasher.write(label.as_bytes());
+        hasher.finish128().into()
+    }
+
+    fn add_blank_node(&self, node: rio::BlankNode<'_>, map: &mut HashMap<String, u128>) {
+        if !map.contains_key(node.id) {
+            map.insert(node.id.to_owned(), self.id(node.id));
+        }
+    }
+
+    fn add_triple(&self, triple: &rio::Triple<'_>, map: &mut HashMap<String, u128>) {
+        match triple.subject {
+            rio::Subject::NamedNode(_) => (),
+            rio::Subject::BlankNode(node) => self.add_blank_node(node, map),
+            rio::Subject::Triple(triple) => self.add_triple(triple, map),
+        }
+        match triple.object {
+            rio::Term::NamedNode(_) | rio::Term::Literal(_) => (),
+            rio::Term::BlankNode(node) => self.add_blank_node(node, map),
+            rio::Term::Triple(triple) => self.add_triple(triple, map),
+        }
+    }
+
+    fn add_quad(&self, quad: &rio::Quad<'_>, map: &mut HashMap<String, u128>) {
+        self.add_triple(
+            &rio::Triple {
+                subject: quad.subject,
+                predicate: quad.predicate,
+                object: quad.object,
+            },
+            map,
+        );
+        if let Some(rio::GraphName::BlankNode(node)) = quad.graph_name {
+            self.add_blank_node(node, map);
+        }
+    }
+}
+
+/// Collects the strings of the terms encoded by a parsing thread
+#[derive(Default)]
+struct ChunkEncoder {
+    strings: HashMap<StrHash, String>,
+}
+
+impl StrEncodingAware for ChunkEncoder {
+    type Error = Infallible;
+    type StrId = StrHash;
+}
+
+impl StrContainer for ChunkEncoder {
+    fn insert_str(&mut self, value: &str) -> Result<StrHash, Infallible> {
+        let key = StrHash::new(value);
+        self.strings.entry(key).or_insert_with(|| value.to_owned());
+        Ok(key)
+    }
+}
+
+struct EncodedChunk {
+    strings: HashMap<StrHash, String>,
+    quads: Vec<EncodedQuad<StrHash>>,
+}
+
+/// A sequence of full lines of the file
+struct Chunk {
+    data: Vec<u8>,
+    /// The line number of the first line of the chunk in the file
+    line: u64,
+    /// The offset of the beginning of the chunk in the file
+    offset: u64,
+}
+
+impl Chunk {
+    /// The position in the file of a line and column in the chunk
+    fn position(&self, line: u64, column: u64) -> TextPosition {
+        let line_start = self
+            .data
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c == b'\n')
+            .zip(0..line)
+            .last()
+            .map_or(0, |((i, _), _)| i + 1);
+        TextPosition::new(
+            self.line + line,
+            column,
+            self.offset + line_start as u64 + column,
+        )
+    }
+}
+
+/// Splits a file into chunks of full lines
+struct ChunkReader<R: Read> {
+    reader: R,
+    chunk_size: usize,
+    /// The beginning of the next chunk
+    remainder: Vec<u8>,
+    line: u64,
+    offset: u64,
+    end: bool,
+}
+
+impl<R: Read> ChunkReader<R> {
+    fn new(reader: R, chunk_size: usize) -> Self {
+        Self {
+            reader,
+            chunk_size,
+            remainder: Vec::new(),
+            line: 0,
+            offset: 0,
+            end: false,
+        }
+    }
+
+    fn next_chunk(&mut self) -> io::Result<Option<Chunk>> {
+        let mut data = std::mem::take(&mut self.remainder);
+        let mut size = self.chunk_size;
+        let end = loop {
+            if !self.end && data.len() < size {
+                let to_read = size - data.len();
+                if (&mut self.reader)
+                    .take(to_read as u64)
+                    .read_to_end(&mut data)?
+                    < to_read
+                {
+                    self.end = true;
+                }
+            }
+            if self.end {
+                break data.len();
+            }
+            if let Some(i) = data.iter().rposition(|c| *c == b'\n') {
+                break i + 1;
+            }
+            // The line is longer than the chunk size
+            size += self.chunk_size;
+        };
+        if end == 0 {
+            return Ok(None);
+        }
+        self.remainder = data.split_off(end);
+        let chunk = Chunk {
+            data,
+            line: self.line,
+            offset: self.offset,
+        };
+        self.line += chunk.data.iter().filter(|c| **c == b'\n').count() as u64;
+        self.offset += chunk.data.len() as u64;
+        Ok(Some(chunk))
+    }
+}
+
+#[cfg(all(test, feature = "sled"))]
+mod tests {
+    use super::*;
+    use crate::model::*;
+    use crate::SledStore;
+    use std::io::Cursor;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_chunk_reader() -> io::Result<()> {
+        let data = b"a\nbb\nccc\ndddd\n\ne";
+        let mut reader = ChunkReader::new(data.as_ref(), 3);
+        let mut chunks = Vec::new();
+        while let Some(chunk) = reader.next_chunk()? {
+            chunks.push((chunk.data, chunk.line, chunk.offset));
+        }
+        assert_eq!(
+            chunks,
+            vec![
+                (b"a\n".to_vec(), 0, 0),
+                (b"bb\n".to_vec(), 1, 2),
+                (b"ccc\n".to_vec(), 2, 5),
+                (b"dddd\n\n".to_vec(), 3, 9),
+                (b"e".to_vec(), 5, 15),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_blank_nodes_consistency() -> io::Result<()> {
+        let file = "_:a <http://example.com/p> _:b .\n_:b <http://example.com/p> _:a .\n<< _:a <http://example.com/p> _:b >> <http://example.com/p> <http://example.com/o> .\n";
+        let store = SledStore::new()?;
+        let mut this = &store;
+        load_chunks(
+            &mut this,
+            ChunkReader::new(file.as_bytes(), 10),
+            &LineFormat::NTriples {
+                to_graph_name: EncodedTerm::DefaultGraph,
+            },
+            None,
+        )?;
+        let triples = store
+            .quads_for_pattern(None, None, None, None)
+            .map(|q| q.map(|q| Triple::new(q.subject, q.predicate, q.object)))
+            .collect::<io::Result<Vec<_>>>()?;
+        assert_eq!(triples.len(), 3);
+        let first = triples.iter().find(|t| t.subject.is_blank_node()).unwrap();
+        let second = triples
+            .iter()
+            .find(|t| Term::from(t.subject.clone()) == first.object)
+            .unwrap();
+        assert_eq!(Term::from(second.subject.clone()), first.object);
+        assert_eq!(Term::from(first.subject.clone()), second.object);
+        let quoted = triples.iter().find(|t| t.subject.is_triple()).unwrap();
+        if let Subject::Triple(quoted) = &quoted.subject {
+            assert!(**quoted == *first || **quoted == *second);
+        }
+
+        // Another load gets other blank nodes
+        store.bulk_load_graph(
+            Cursor::new(file),
+            GraphFormat::NTriples,
+            GraphNameRef::DefaultGraph,
+            None,
+        )?;
+        assert_eq!(store.len(), 6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_error_position() -> io::Result<()> {
+        let file = "<http://example.com/s> <http://example.com/p> <http://example.com/o> .\n<http://example.com/s> <http://example.com/p> foo .\n<http://example.com/s> <http://example.com/p> <http://example.com/o2> .\n";
+        let store = SledStore::new()?;
+        let error = store
+            .bulk_load_graph(
+                Cursor::new(file),
+                GraphFormat::NTriples,
+                GraphNameRef::DefaultGraph,
+                None,
+            )
+            .unwrap_err();
+        let position = error
+            .get_ref()
+            .unwrap()
+            .downcast_ref::<SyntaxError>()
+            .unwrap()
+            .position()
+            .unwrap();
+        assert_eq!(position.line(), 1);
+        assert_eq!(position.column(), 46);
+        assert_eq!(position.offset(), 117);
+
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let errors_sink = errors.clone();
+        let store = SledStore::new()?;
+        let mut this = &store;
+        load_chunks(
+            &mut this,
+            ChunkReader::new(file.as_bytes(), 10),
+            &LineFormat::NTriples {
+                to_graph_name: EncodedTerm::DefaultGraph,
+            },
+            GraphParser::from_format(GraphFormat::NTriples)
+                .lenient(move |error| errors_sink.lock().unwrap().push(error))
+                .error_callback(),
+        )?;
+        assert_eq!(store.len(), 2);
+        let errors = errors.lock().unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].position().unwrap().offset(), 117);
+        Ok(())
+    }
+}
@@ -1,11 +1,11 @@
 //! In-memory store.
 
 use crate::error::{invalid_input_error, UnwrapInfallible};
-use crate::io::{DatasetFormat, DatasetParser, GraphFormat, GraphParser};
+use crate::io::{DatasetParser, DatasetSerializer, GraphParser, GraphSerializer};
 use crate::model::*;
 use crate::sparql::{
-    evaluate_query, evaluate_update, EvaluationError, Query, QueryOptions, QueryResults, Update,
-    UpdateOptions,
+    evaluate_query, evaluate_update, explain_query, EvaluationError, Query, QueryExplanation,
+    QueryOptions, QueryResults, Update, UpdateOptions,
 };
 use crate::store::numeric_encoder::{
     Decoder, ReadEncoder, StrContainer, StrEncodingAware, StrId, StrLookup, WriteEncoder,
@@ -62,6 +62,7 @@ type TripleMap<T> = HashMap<T, HashMap<T, HashSet<T>>>;
 type QuadMap<T> = HashMap<T, TripleMap<T>>;
 type EncodedTerm = crate::store::numeric_encoder::EncodedTerm<LargeSpur>;
 type EncodedQuad = crate::store::numeric_encoder::EncodedQuad<LargeSpur>;
+type EncodedTriple = crate::store::numeric_encoder::EncodedTriple<LargeSpur>;
 
 #[derive(Default)]
 struct MemoryStoreIndexes {
@@ -129,6 +130,36 @@ impl MemoryStore {
         evaluate_query(self.clone(), query, options)
     }
 
+    /// Returns the plan used to evaluate a [SPARQL 1.1 query](https://www.w3.org/TR/sparql11-query/) without evaluating it.
+    ///
+    /// The default query options are used.
+    ///
+    /// Usage example:
+    /// ```
+    /// use oxigraph::MemoryStore;
+    ///
+    /// let store = MemoryStore::new();
+    /// let explanation = store.explain("SELECT ?s WHERE { ?s ?p ?o } ORDER BY ?s")?;
+    /// assert_eq!(explanation.root().name(), "Project");
+    /// assert_eq!(explanation.root().children()[0].name(), "Sort");
+    /// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn explain(
+        &self,
+        query: impl TryInto<Query, Error = impl Into<EvaluationError>>,
+    ) -> Result<QueryExplanation, EvaluationError> {
+        self.explain_opt(query, QueryOptions::default())
+    }
+
+    /// Returns the plan used to evaluate a [SPARQL 1.1 query](https://www.w3.org/TR/sparql11-query/) with some options without evaluating it.
+    pub fn explain_opt(
+        &self,
+        query: impl TryInto<Query, Error = impl Into<EvaluationError>>,
+        options: QueryOptions,
+    ) -> Result<QueryExplanation, EvaluationError> {
+        explain_query(self.clone(), query, options)
+    }
+
     /// Retrieves quads with a filter on each quad component
     ///
     /// Usage example:
@@ -150,7 +181,7 @@ impl MemoryStore {
     /// ```
     pub fn quads_for_pattern(
         &self,
-        subject: Option<NamedOrBlankNodeRef<'_>>,
+        subject: Option<SubjectRef<'_>>,
         predicate: Option<NamedNodeRef<'_>>,
         object: Option<TermRef<'_>>,
         graph_name: Option<GraphNameRef<'_>>,
@@ -159,7 +190,12 @@ impl MemoryStore {
             get_encoded_quad_pattern(self, subject, predicate, object, graph_name)
                 .unwrap_infallible()
         {
-            self.encoded_quads_for_pattern_inner(subject, predicate, object, graph_name)
+            self.encoded_quads_for_pattern_inner(
+                subject.as_ref(),
+                predicate.as_ref(),
+                object.as_ref(),
+                graph_name.as_ref(),
+            )
         } else {
             Vec::new()
         };
@@ -321,6 +357,23 @@ impl MemoryStore {
     /// # Result::<_,Box<dyn std::error::Error>>::Ok(())
     /// ```
     ///
+    /// A [`GraphParser`] could be given instead of a format in order to configure the parsing.
+    /// For example, its [lenient mode](GraphParser::lenient()) allows to skip the invalid statements:
+    /// ```
+    /// use oxigraph::MemoryStore;
+    /// use oxigraph::io::{GraphFormat, GraphParser};
+    /// use oxigraph::model::*;
+    ///
+    /// let store = MemoryStore::new();
+    /// let file = b"<http://example.com> <http://example.com> <http://example.com> .
+    /// <http://example.com> <http://example.com> .";
+    /// let parser = GraphParser::from_format(GraphFormat::NTriples)
+    ///     .lenient(|error| eprintln!("Skipped invalid triple: {}", error));
+    /// store.load_graph(file.as_ref(), parser, &GraphName::DefaultGraph, None)?;
+    /// assert_eq!(store.len(), 1);
+    /// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    ///
     /// Warning: This functions saves the triples during the parsing.
     /// If the parsing fails in the middle of the file, the triples read before stay in the store.
     /// Use a (memory greedy) [transaction](MemoryStore::transaction()) if you do not want that.
@@ -330,15 +383,48 @@ impl MemoryStore {
     pub fn load_graph<'a>(
         &self,
         reader: impl BufRead,
-        format: GraphFormat,
+        parser: impl Into<GraphParser>,
         to_graph_name: impl Into<GraphNameRef<'a>>,
         base_iri: Option<&str>,
     ) -> Result<(), io::Error> {
-        let mut store = self;
-        load_graph(&mut store, reader, format, to_graph_name.into(), base_iri)?;
+        self.load_graph_with_prefixes(reader, parser, to_graph_name, base_iri)?;
         Ok(())
     }
 
+    /// Loads a graph file (i.e. triples) into the store like [`load_graph`](MemoryStore::load_graph()) and returns the prefixes declared in it
+    ///
+    /// The prefixes are returned as a map from prefix name to prefix IRI.
+    /// Only the Turtle and TriG formats allow to declare prefixes.
+    ///
+    /// ```
+    /// use oxigraph::MemoryStore;
+    /// use oxigraph::io::GraphFormat;
+    /// use oxigraph::model::*;
+    ///
+    /// let store = MemoryStore::new();
+    /// let file = b"@prefix ex: <http://example.com/> . ex:s ex:p ex:o .";
+    /// let prefixes = store.load_graph_with_prefixes(file.as_ref(), GraphFormat::Turtle, &GraphName::DefaultGraph, None)?;
+    /// assert_eq!(prefixes["ex"], "http://example.com/");
+    /// assert_eq!(store.len(), 1);
+    /// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn load_graph_with_prefixes<'a>(
+        &self,
+        reader: impl BufRead,
+        parser: impl Into<GraphParser>,
+        to_graph_name: impl Into<GraphNameRef<'a>>,
+        base_iri: Option<&str>,
+    ) -> Result<HashMap<String, String>, io::Error> {
+        let mut store = self;
+        Ok(load_graph(
+            &mut store,
+            reader,
+            parser.into(),
+            to_graph_name.into(),
+            base_iri,
+        )?)
+    }
+
     /// Loads a dataset file (i.e. quads) into the store.
     ///
     /// Usage example:
@@ -359,6 +445,9 @@ impl MemoryStore {
     /// # Result::<_,Box<dyn std::error::Error>>::Ok(())
     /// ```
     ///
+    /// A [`DatasetParser`] could be given instead of a format in order to configure the parsing,
+    /// for example to enable its [lenient mode](DatasetParser::lenient()).
+    ///
     /// Warning: This functions saves the quads during the parsing.
     /// If the parsing fails in the middle of the file, the quads read before stay in the store.
     /// Use a (memory greedy) [transaction](MemoryStore::transaction()) if you do not want that.
@@ -368,14 +457,39 @@ impl MemoryStore {
     pub fn load_dataset(
         &self,
         reader: impl BufRead,
-        format: DatasetFormat,
+        parser: impl Into<DatasetParser>,
         base_iri: Option<&str>,
     ) -> Result<(), io::Error> {
-        let mut store = self;
-        load_dataset(&mut store, reader, format, base_iri)?;
+        self.load_dataset_with_prefixes(reader, parser, base_iri)?;
         Ok(())
     }
 
+    /// Loads a dataset file (i.e. quads) into the store like [`load_dataset`](MemoryStore::load_dataset()) and returns the prefixes declared in it
+    ///
+    /// The prefixes are returned as a map from prefix name to prefix IRI.
+    /// Only the Turtle and TriG formats allow to declare prefixes.
+    ///
+    /// ```
+    /// use oxigraph::MemoryStore;
+    /// use oxigraph::io::DatasetFormat;
+    ///
+    /// let store = MemoryStore::new();
+    /// let file = b"@prefix ex: <http://example.com/> . ex:g { ex:s ex:p ex:o }";
+    /// let prefixes = store.load_dataset_with_prefixes(file.as_ref(), DatasetFormat::TriG, None)?;
+    /// assert_eq!(prefixes["ex"], "http://example.com/");
+    /// assert_eq!(store.len(), 1);
+    /// # Result::<_,Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn load_dataset_with_prefixes(
+        &self,
+        reader: impl BufRead,
+        parser: impl Into<DatasetParser>,
+        base_iri: Option<&str>,
+    ) -> Result<HashMap<String, String>, io::Error> {
+        let mut store = self;
+        Ok(load_dataset(&mut store, reader, parser.into(), base_iri)?)
+    }
+
     /// Adds a quad to this store.
     #[allow(clippy::needless_pass_by_value)]
     pub fn insert(&self, quad: impl Into<Quad>) {
@@ -423,14 +537,14 @@ impl MemoryStore {
     pub fn dump_graph<'a>(
         &self,
         writer: impl Write,
-        format: GraphFormat,
+        serializer: impl Into<GraphSerializer>,
         from_graph_name: impl Into<GraphNameRef<'a>>,
     ) -> Result<(), io::Error> {
         dump_graph(
             self.quads_for_pattern(None, None, None, Some(from_graph_name.into()))
                 .map(|q| Ok(q.into())),
             writer,
-            format,
+            &serializer.into(),
         )
     }
 
@@ -451,8 +565,12 @@ impl MemoryStore {
     /// assert_eq!(file, buffer.as_slice());
     /// # std::io::Result::Ok(())
     /// ```
-    pub fn dump_dataset(&self, writer: impl Write, format: DatasetFormat) -> Result<(), io::Error> {
-        dump_dataset(self.iter().map(Ok), writer, format)
+    pub fn dump_dataset(
+        &self,
+        writer: impl Write,
+        serializer: impl Into<DatasetSerializer>,
+    ) -> Result<(), io::Error> {
+        dump_dataset(self.iter().map(Ok), writer, &serializer.into())
     }
 
     /// Removes a graph from this store.
@@ -477,7 +595,7 @@ impl MemoryStore {
             .get_encoded_graph_name(graph_name.into())
             .unwrap_infallible()
         {
-            for quad in self.encoded_quads_for_pattern_inner(None, None, None, Some(graph_name)) {
+            for quad in self.encoded_quads_for_pattern_inner(None, None, None, Some(&graph_name)) {
                 let mut this = self;
                 this.remove_encoded(&quad).unwrap_infallible();
             }
@@ -538,17 +656,22 @@ impl MemoryStore {
 
     fn encoded_quads_for_pattern_inner(
         &self,
-        subject: Option<EncodedTerm>,
-        predicate: Option<EncodedTerm>,
-        object: Option<EncodedTerm>,
-        graph_name: Option<EncodedTerm>,
+        subject: Option<&EncodedTerm>,
+        predicate: Option<&EncodedTerm>,
+        object: Option<&EncodedTerm>,
+        graph_name: Option<&EncodedTerm>,
     ) -> Vec<EncodedQuad> {
         match subject {
             Some(subject) => match predicate {
                 Some(predicate) => match object {
                     Some(object) => match graph_name {
                         Some(graph_name) => {
-                            let quad = EncodedQuad::new(subject, predicate, object, graph_name);
+                            let quad = EncodedQuad::new(
+                                subject.clone(),
+                                predicate.clone(),
+                                object.clone(),
+                                graph_name.clone(),
+                            );
                             if self.contains_encoded(&quad) {
                                 vec![quad]
                             } else {
@@ -618,251 +741,265 @@ impl MemoryStore {
         default.chain(named).collect()
     }
 
-    fn encoded_quads_for_subject(&self, subject: EncodedTerm) -> Vec<EncodedQuad> {
+    fn encoded_quads_for_subject(&self, subject: &EncodedTerm) -> Vec<EncodedQuad> {
         let indexes = self.indexes();
-        let default = option_pair_map_flatten(indexes.default_spo.get(&subject))
-            .map(|(p, o)| EncodedQuad::new(subject, p, o, EncodedTerm::DefaultGraph));
-        let named = option_triple_map_flatten(indexes.spog.get(&subject))
-            .map(|(p, o, g)| EncodedQuad::new(subject, p, o, g));
+        let default = option_pair_map_flatten(indexes.default_spo.get(subject))
+            .map(|(p, o)| EncodedQuad::new(subject.clone(), p, o, EncodedTerm::DefaultGraph));
+        let named = option_triple_map_flatten(indexes.spog.get(subject))
+            .map(|(p, o, g)| EncodedQuad::new(subject.clone(), p, o, g));
         default.chain(named).collect()
     }
 
     fn encoded_quads_for_subject_predicate(
         &self,
-        subject: EncodedTerm,
-        predicate: EncodedTerm,
+        subject: &EncodedTerm,
+        predicate: &EncodedTerm,
     ) -> Vec<EncodedQuad> {
         let indexes = self.indexes();
         let default = option_set_flatten(
             indexes
                 .default_spo
-                .get(&subject)
-                .and_then(|po| po.get(&predicate)),
+                .get(subject)
+                .and_then(|po| po.get(predicate)),
         )
-        .map(|o| EncodedQuad::new(subject, predicate, o, EncodedTerm::DefaultGraph));
-        let named = option_pair_map_flatten(
-            indexes
-                .spog
-                .get(&subject)
-                .and_then(|pog| pog.get(&predicate)),
-        )
-        .map(|(o, g)| EncodedQuad::new(subject, predicate, o, g));
+        .map(|o| {
+            EncodedQuad::new(
+                subject.clone(),
+                predicate.clone(),
+                o,
+                EncodedTerm::DefaultGraph,
+            )
+        });
+        let named =
+            option_pair_map_flatten(indexes.spog.get(subject).and_then(|pog| pog.get(predicate)))
+                .map(|(o, g)| EncodedQuad::new(subject.clone(), predicate.clone(), o, g));
         default.chain(named).collect()
     }
 
     fn encoded_quads_for_subject_predicate_object(
         &self,
-        subject: EncodedTerm,
-        predicate: EncodedTerm,
-        object: EncodedTerm,
+        subject: &EncodedTerm,
+        predicate: &EncodedTerm,
+        object: &EncodedTerm,
     ) -> Vec<EncodedQuad> {
         let indexes = self.indexes();
         let default = indexes
             .default_spo
-            .get(&subject)
-            .and_then(|po| po.get(&predicate))
-            .and_then(|o| o.get(&object))
-            .map(|_| EncodedQuad::new(subject, predicate, object, EncodedTerm::DefaultGraph))
+            .get(subject)
+            .and_then(|po| po.get(predicate))
+            .and_then(|o| o.get(object))
+            .map(|_| {
+                EncodedQuad::new(
+                    subject.clone(),
+                    predicate.clone(),
+                    object.clone(),
+                    EncodedTerm::DefaultGraph,
+                )
+            })
             .into_iter();
         let named = option_set_flatten(
             indexes
                 .spog
-                .get(&subject)
-                .and_then(|pog| pog.get(&predicate))
-                .and_then(|og| og.get(&object)),
+                .get(subject)
+                .and_then(|pog| pog.get(predicate))
+                .and_then(|og| og.get(object)),
         )
-        .map(|g| EncodedQuad::new(subject, predicate, object, g));
+        .map(|g| EncodedQuad::new(subject.clone(), predicate.clone(), object.clone(), g));
         default.chain(named).collect()
     }
 
     fn encoded_quads_for_subject_object(
         &self,
-        subject: EncodedTerm,
-        object: EncodedTerm,
+        subject: &EncodedTerm,
+        object: &EncodedTerm,
     ) -> Vec<EncodedQuad> {
         let indexes = self.indexes();
         let default = option_set_flatten(
             indexes
                 .default_osp
-                .get(&object)
-                .and_then(|sp| sp.get(&subject)),
+                .get(object)
+                .and_then(|sp| sp.get(subject)),
         )
-        .map(|p| EncodedQuad::new(subject, p, object, EncodedTerm::DefaultGraph));
+        .map(|p| {
+            EncodedQuad::new(
+                subject.clone(),
+                p,
+                object.clone(),
+                EncodedTerm::DefaultGraph,
+            )
+        });
         let named =
-            option_pair_map_flatten(indexes.ospg.get(&object).and_then(|spg| spg.get(&subject)))
-                .map(|(p, g)| EncodedQuad::new(subject, p, object, g));
+            option_pair_map_flatten(indexes.ospg.get(object).and_then(|spg| spg.get(subject)))
+                .map(|(p, g)| EncodedQuad::new(subject.clone(), p, object.clone(), g));
         default.chain(named).collect()
     }
 
-    fn encoded_quads_for_predicate(&self, predicate: EncodedTerm) -> Vec<EncodedQuad> {
+    fn encoded_quads_for_predicate(&self, predicate: &EncodedTerm) -> Vec<EncodedQuad> {
         let indexes = self.indexes();
-        let default = option_pair_map_flatten(indexes.default_pos.get(&predicate))
-            .map(|(o, s)| EncodedQuad::new(s, predicate, o, EncodedTerm::DefaultGraph));
-        let named = option_triple_map_flatten(indexes.posg.get(&predicate))
-            .map(|(o, s, g)| EncodedQuad::new(s, predicate, o, g));
+        let default = option_pair_map_flatten(indexes.default_pos.get(predicate))
+            .map(|(o, s)| EncodedQuad::new(s, predicate.clone(), o, EncodedTerm::DefaultGraph));
+        let named = option_triple_map_flatten(indexes.posg.get(predicate))
+            .map(|(o, s, g)| EncodedQuad::new(s, predicate.clone(), o, g));
         default.chain(named).collect()
     }
 
     fn encoded_quads_for_predicate_object(
         &self,
-        predicate: EncodedTerm,
-        object: EncodedTerm,
+        predicate: &EncodedTerm,
+        object: &EncodedTerm,
     ) -> Vec<EncodedQuad> {
         let indexes = self.indexes();
         let default = option_set_flatten(
             indexes
                 .default_pos
-                .get(&predicate)
-                .and_then(|os| os.get(&object)),
-        )
-        .map(|s| EncodedQuad::new(s, predicate, object, EncodedTerm::DefaultGraph));
-        let named = option_pair_map_flatten(
-            indexes
-                .posg
-                .get(&predicate)
-                .and_then(|osg| osg.get(&object)),
+                .get(predicate)
+                .and_then(|os| os.get(object)),
         )
-        .map(|(s, g)| EncodedQuad::new(s, predicate, object, g));
+        .map(|s| {
+            EncodedQuad::new(
+                s,
+                predicate.clone(),
+                object.clone(),
+                EncodedTerm::DefaultGraph,
+            )
+        });
+        let named =
+            option_pair_map_flatten(indexes.posg.get(predicate).and_then(|osg| osg.get(object)))
+                .map(|(s, g)| EncodedQuad::new(s, predicate.clone(), object.clone(), g));
         default.chain(named).collect()
     }
 
-    fn encoded_quads_for_object(&self, object: EncodedTerm) -> Vec<EncodedQuad> {
+    fn encoded_quads_for_object(&self, object: &EncodedTerm) -> Vec<EncodedQuad> {
         let indexes = self.indexes();
-        let default = option_pair_map_flatten(indexes.default_osp.get(&object))
-            .map(|(s, p)| EncodedQuad::new(s, p, object, EncodedTerm::DefaultGraph));
-        let named = option_triple_map_flatten(indexes.ospg.get(&object))
-            .map(|(s, p, g)| EncodedQuad::new(s, p, object, g));
+        let default = option_pair_map_flatten(indexes.default_osp.get(object))
+            .map(|(s, p)| EncodedQuad::new(s, p, object.clone(), EncodedTerm::DefaultGraph));
+        let named = option_triple_map_flatten(indexes.ospg.get(object))
+            .map(|(s, p, g)| EncodedQuad::new(s, p, object.clone(), g));
         default.chain(named).collect()
     }
 
-    fn encoded_quads_for_graph(&self, graph_name: EncodedTerm) -> Vec<EncodedQuad> {
+    fn encoded_quads_for_graph(&self, graph_name: &EncodedTerm) -> Vec<EncodedQuad> {
         let indexes = self.indexes();
         option_triple_map_flatten(if graph_name.is_default_graph() {
             Some(&indexes.default_spo)
         } else {
-            indexes.gspo.get(&graph_name)
+            indexes.gspo.get(graph_name)
         })
-        .map(|(s, p, o)| EncodedQuad::new(s, p, o, graph_name))
+        .map(|(s, p, o)| EncodedQuad::new(s, p, o, graph_name.clone()))
         .collect()
     }
 
     fn encoded_quads_for_subject_graph(
         &self,
-        subject: EncodedTerm,
-        graph_name: EncodedTerm,
+        subject: &EncodedTerm,
+        graph_name: &EncodedTerm,
     ) -> Vec<EncodedQuad> {
         let indexes = self.indexes();
         option_pair_map_flatten(if graph_name.is_default_graph() {
-            indexes.default_spo.get(&subject)
+            indexes.default_spo.get(subject)
         } else {
             indexes
                 .gspo
-                .get(&graph_name)
-                .and_then(|spo| spo.get(&subject))
+                .get(graph_name)
+                .and_then(|spo| spo.get(subject))
         })
-        .map(|(p, o)| EncodedQuad::new(subject, p, o, graph_name))
+        .map(|(p, o)| EncodedQuad::new(subject.clone(), p, o, graph_name.clone()))
         .collect()
     }
 
     fn encoded_quads_for_subject_predicate_graph(
         &self,
-        subject: EncodedTerm,
-        predicate: EncodedTerm,
-        graph_name: EncodedTerm,
+        subject: &EncodedTerm,
+        predicate: &EncodedTerm,
+        graph_name: &EncodedTerm,
     ) -> Vec<EncodedQuad> {
         let indexes = self.indexes();
         option_set_flatten(
             if graph_name.is_default_graph() {
-                indexes.default_spo.get(&subject)
+                indexes.default_spo.get(subject)
             } else {
                 indexes
                     .gspo
-                    .get(&graph_name)
-                    .and_then(|spo| spo.get(&subject))
+                    .get(graph_name)
+                    .and_then(|spo| spo.get(subject))
             }
-            .and_then(|po| po.get(&predicate)),
+            .and_then(|po| po.get(predicate)),
         )
-        .map(|o| EncodedQuad::new(subject, predicate, o, graph_name))
+        .map(|o| EncodedQuad::new(subject.clone(), predicate.clone(), o, graph_name.clone()))
         .collect()
     }
 
     fn encoded_quads_for_subject_object_graph(
         &self,
-        subject: EncodedTerm,
-        object: EncodedTerm,
-        graph_name: EncodedTerm,
+        subject: &EncodedTerm,
+        object: &EncodedTerm,
+        graph_name: &EncodedTerm,
     ) -> Vec<EncodedQuad> {
         let indexes = self.indexes();
         option_set_flatten(
             if graph_name.is_default_graph() {
-                indexes.default_osp.get(&object)
+                indexes.default_osp.get(object)
             } else {
-                indexes
-                    .gosp
-                    .get(&graph_name)
-                    .and_then(|osp| osp.get(&object))
+                indexes.gosp.get(graph_name).and_then(|osp| osp.get(object))
             }
-            .and_then(|sp| sp.get(&subject)),
+            .and_then(|sp| sp.get(subject)),
         )
-        .map(|p| EncodedQuad::new(subject, p, object, graph_name))
+        .map(|p| EncodedQuad::new(subject.clone(), p, object.clone(), graph_name.clone()))
         .collect()
     }
 
     fn encoded_quads_for_predicate_graph(
         &self,
-        predicate: EncodedTerm,
-        graph_name: EncodedTerm,
+        predicate: &EncodedTerm,
+        graph_name: &EncodedTerm,
     ) -> Vec<EncodedQuad> {
         let indexes = self.indexes();
         option_pair_map_flatten(if graph_name.is_default_graph() {
-            indexes.default_pos.get(&predicate)
+            indexes.default_pos.get(predicate)
         } else {
             indexes
                 .gpos
-                .get(&graph_name)
-                .and_then(|pos| pos.get(&predicate))
+                .get(graph_name)
+                .and_then(|pos| pos.get(predicate))
         })
-        .map(|(o, s)| EncodedQuad::new(s, predicate, o, graph_name))
+        .map(|(o, s)| EncodedQuad::new(s, predicate.clone(), o, graph_name.clone()))
         .collect()
     }
 
     fn encoded_quads_for_predicate_object_graph(
         &self,
-        predicate: EncodedTerm,
-        object: EncodedTerm,
-        graph_name: EncodedTerm,
+        predicate: &EncodedTerm,
+        object: &EncodedTerm,
+        graph_name: &EncodedTerm,
     ) -> Vec<EncodedQuad> {
         let indexes = self.indexes();
         option_set_flatten(
             if graph_name.is_default_graph() {
-                indexes.default_pos.get(&predicate)
+                indexes.default_pos.get(predicate)
             } else {
                 indexes
                     .gpos
-                    .get(&graph_name)
-                    .and_then(|pos| pos.get(&predicate))
+                    .get(graph_name)
+                    .and_then(|pos| pos.get(predicate))
             }
-            .and_then(|os| os.get(&object)),
+            .and_then(|os| os.get(object)),
         )
-        .map(|s| EncodedQuad::new(s, predicate, object, graph_name))
+        .map(|s| EncodedQuad::new(s, predicate.clone(), object.clone(), graph_name.clone()))
         .collect()
     }
 
     fn encoded_quads_for_object_graph(
         &self,
-        object: EncodedTerm,
-        graph_name: EncodedTerm,
+        object: &EncodedTerm,
+        graph_name: &EncodedTerm,
     ) -> Vec<EncodedQuad> {
         let indexes = self.indexes();
         option_pair_map_flatten(if graph_name.is_default_graph() {
-            indexes.default_osp.get(&object)
+            indexes.default_osp.get(object)
         } else {
-            indexes
-                .gosp
-                .get(&graph_name)
-                .and_then(|osp| osp.get(&object))
+            indexes.gosp.get(graph_name).and_then(|osp| osp.get(object))
         })
-        .map(|(s, p)| EncodedQuad::new(s, p, object, graph_name))
+        .map(|(s, p)| EncodedQuad::new(s, p, object.clone(), graph_name.clone()))
         .collect()
     }
 }
@@ -901,7 +1038,12 @@ impl<'a> ReadableEncodedStore for MemoryStore {
     ) -> EncodedQuadsIter {
         EncodedQuadsIter {
             iter: self
-                .encoded_quads_for_pattern_inner(subject, predicate, object, graph_name)
+                .encoded_quads_for_pattern_inner(
+                    subject.as_ref(),
+                    predicate.as_ref(),
+                    object.as_ref(),
+                    graph_name.as_ref(),
+                )
                 .into_iter(),
         }
     }
@@ -927,64 +1069,64 @@ impl WritableEncodedStore for MemoryStoreIndexes {
         if quad.graph_name.is_default_graph() {
             insert_into_triple_map(
                 &mut self.default_spo,
-                quad.subject,
-                quad.predicate,
-                quad.object,
+                quad.subject.clone(),
+                quad.predicate.clone(),
+                quad.object.clone(),
             );
             insert_into_triple_map(
                 &mut self.default_pos,
-                quad.predicate,
-                quad.object,
-                quad.subject,
+                quad.predicate.clone(),
+                quad.object.clone(),
+                quad.subject.clone(),
             );
             insert_into_triple_map(
                 &mut self.default_osp,
-                quad.object,
-                quad.subject,
-                quad.predicate,
+                quad.object.clone(),
+                quad.subject.clone(),
+                quad.predicate.clone(),
             );
         } else {
             insert_into_quad_map(
                 &mut self.gspo,
-                quad.graph_name,
-                quad.subject,
-                quad.predicate,
-                quad.object,
+                quad.graph_name.clone(),
+                quad.subject.clone(),
+                quad.predicate.clone(),
+                quad.object.clone(),
             );
             insert_into_quad_map(
                 &mut self.gpos,
-                quad.graph_name,
-                quad.predicate,
-                quad.object,
-                quad.subject,
+                quad.graph_name.clone(),
+                quad.predicate.clone(),
+                quad.object.clone(),
+                quad.subject.clone(),
             );
             insert_into_quad_map(
                 &mut self.gosp,
-                quad.graph_name,
-                quad.object,
-                quad.subject,
-                quad.predicate,
+                quad.graph_name.clone(),
+                quad.object.clone(),
+                quad.subject.clone(),
+                quad.predicate.clone(),
             );
             insert_into_quad_map(
                 &mut self.spog,
-                quad.subject,
-                quad.predicate,
-                quad.object,
-                quad.graph_name,
+                quad.subject.clone(),
+                quad.predicate.clone(),
+                quad.object.clone(),
+                quad.graph_name.clone(),
             );
             insert_into_quad_map(
                 &mut self.posg,
-                quad.predicate,
-                quad.object,
-                quad.subject,
-                quad.graph_name,
+                quad.predicate.clone(),
+                quad.object.clone(),
+                quad.subject.clone(),
+                quad.graph_name.clone(),
             );
             insert_into_quad_map(
                 &mut self.ospg,
-                quad.object,
-                quad.subject,
-                quad.predicate,
-                quad.graph_name,
+                quad.object.clone(),
+                quad.subject.clone(),
+                quad.predicate.clone(),
+                quad.graph_name.clone(),
             );
         }
         Ok(())
@@ -1099,52 +1241,31 @@ fn option_set_flatten<T: Clone>(i: Option<&HashSet<T>>) -> impl Iterator<Item =
     i.into_iter().flat_map(|s| s.iter().cloned())
 }
 
-fn option_pair_map_flatten<T: Copy>(
+fn option_pair_map_flatten<T: Clone>(
     i: Option<&HashMap<T, HashSet<T>>>,
 ) -> impl Iterator<Item = (T, T)> + '_ {
     i.into_iter().flat_map(|kv| {
-        kv.iter().flat_map(|(k, vs)| {
-            let k = *k;
-            vs.iter().map(move |v| (k, *v))
-        })
+        kv.iter()
+            .flat_map(|(k, vs)| vs.iter().map(move |v| (k.clone(), v.clone())))
     })
 }
 
-fn triple_map_flatten<T: Copy>(spo: &TripleMap<T>) -> impl Iterator<Item = (T, T, T)> + '_ {
+fn triple_map_flatten<T: Clone>(spo: &TripleMap<T>) -> impl Iterator<Item = (T, T, T)> + '_ {
     spo.iter().flat_map(|(s, po)| {
-        let s = *s;
-        po.iter().flat_map(move |(p, os)| {
-            let p = *p;
-            os.iter().map(move |o| (s, p, *o))
-        })
+        po.iter()
+            .flat_map(move |(p, os)| os.iter().map(move |o| (s.clone(), p.clone(), o.clone())))
     })
 }
 
-fn option_triple_map_flatten<T: Copy>(
+fn option_triple_map_flatten<T: Clone>(
     i: Option<&TripleMap<T>>,
 ) -> impl Iterator<Item = (T, T, T)> + '_ {
-    i.into_iter().flat_map(|spo| {
-        spo.iter().flat_map(|(s, po)| {
-            let s = *s;
-            po.iter().flat_map(move |(p, os)| {
-                let p = *p;
-                os.iter().map(move |o| (s, p, *o))
-            })
-        })
-    })
+    i.into_iter().flat_map(|spo| triple_map_flatten(spo))
 }
 
-fn quad_map_flatten<T: Copy>(gspo: &QuadMap<T>) -> impl Iterator<Item = (T, T, T, T)> + '_ {
-    gspo.iter().flat_map(|(g, spo)| {
-        let g = *g;
-        spo.iter().flat_map(move |(s, po)| {
-            let s = *s;
-            po.iter().flat_map(move |(p, os)| {
-                let p = *p;
-                os.iter().map(move |o| (g, s, p, *o))
-            })
-        })
-    })
+fn quad_map_flatten<T: Clone>(gspo: &QuadMap<T>) -> impl Iterator<Item = (T, T, T, T)> + '_ {
+    gspo.iter()
+        .flat_map(|(g, spo)| triple_map_flatten(spo).map(move |(s, p, o)| (g.clone(), s, p, o)))
 }
 
 /// Allows inserting and deleting quads during an ACID transaction with the [`MemoryStore`].
@@ -1189,12 +1310,12 @@ impl MemoryTransaction {
     pub fn load_graph<'a>(
         &mut self,
         reader: impl BufRead,
-        format: GraphFormat,
+        parser: impl Into<GraphParser>,
         to_graph_name: impl Into<GraphNameRef<'a>>,
         base_iri: Option<&str>,
     ) -> Result<(), io::Error> {
         let to_graph_name = to_graph_name.into();
-        let mut parser = GraphParser::from_format(format);
+        let mut parser = parser.into();
         if let Some(base_iri) = base_iri {
             parser = parser
                 .with_base_iri(base_iri)
@@ -1236,10 +1357,10 @@ impl MemoryTransaction {
     pub fn load_dataset(
         &mut self,
         reader: impl BufRead,
-        format: DatasetFormat,
+        parser: impl Into<DatasetParser>,
         base_iri: Option<&str>,
     ) -> Result<(), io::Error> {
-        let mut parser = DatasetParser::from_format(format);
+        let mut parser = parser.into();
         if let Some(base_iri) = base_iri {
             parser = parser
                 .with_base_iri(base_iri)
@@ -1378,7 +1499,7 @@ fn distinguish(
             .iter()
             .map(|b| {
                 let mut hash_prime = hash.clone();
-                hash_prime.insert(*b, hash_tuple((hash_prime[b], 22)));
+                hash_prime.insert(b.clone(), hash_tuple((hash_prime[b], 22)));
                 let (hash_prime_prime, partition_prime) = hash_bnodes(g, hash_prime);
                 distinguish(g, &hash_prime_prime, &partition_prime)
             })
@@ -1406,11 +1527,12 @@ fn hash_bnodes(
     let mut to_hash = Vec::new();
     let mut partition: HashMap<u64, Vec<EncodedTerm>> = HashMap::new();
     let mut partition_len = 0;
+    let quads_with_quoted_bnodes = quads_with_quoted_bnodes(g);
     loop {
         //TODO: improve termination
         let mut new_hashes = HashMap::new();
         for (bnode, old_hash) in &hashes {
-            for q in g.encoded_quads_for_subject(*bnode) {
+            for q in g.encoded_quads_for_subject(bnode) {
                 to_hash.push((
                     hash_term(q.predicate, &hashes, g),
                     hash_term(q.object, &hashes, g),
@@ -1418,7 +1540,7 @@ fn hash_bnodes(
                     0,
                 ));
             }
-            for q in g.encoded_quads_for_object(*bnode) {
+            for q in g.encoded_quads_for_object(bnode) {
                 to_hash.push((
                     hash_term(q.subject, &hashes, g),
                     hash_term(q.predicate, &hashes, g),
@@ -1426,7 +1548,7 @@ fn hash_bnodes(
                     1,
                 ));
             }
-            for q in g.encoded_quads_for_graph(*bnode) {
+            for q in g.encoded_quads_for_graph(bnode) {
                 to_hash.push((
                     hash_term(q.subject, &hashes, g),
                     hash_term(q.predicate, &hashes, g),
@@ -1434,11 +1556,22 @@ fn hash_bnodes(
                     2,
                 ));
             }
+            for q in quads_with_quoted_bnodes.get(bnode).into_iter().flatten() {
+                to_hash.push((
+                    hash_term(q.subject.clone(), &hashes, g),
+                    hash_term(q.predicate.clone(), &hashes, g),
+                    hash_tuple((
+                        hash_term(q.object.clone(), &hashes, g),
+                        hash_term(q.graph_name.clone(), &hashes, g),
+                    )),
+                    3,
+                ));
+            }
             to_hash.sort_unstable();
             let hash = hash_tuple((old_hash, &to_hash));
             to_hash.clear();
-            new_hashes.insert(*bnode, hash);
-            partition.entry(hash).or_default().push(*bnode);
+            new_hashes.insert(bnode.clone(), hash);
+            partition.entry(hash).or_default().push(bnode.clone());
         }
         if partition.len() == partition_len {
             let mut partition: Vec<_> = partition.into_iter().collect();
@@ -1454,17 +1587,38 @@ fn hash_bnodes(
 fn bnodes(g: &MemoryStore) -> HashSet<EncodedTerm> {
     let mut bnodes = HashSet::new();
     for q in g.encoded_quads() {
-        if q.subject.is_blank_node() {
-            bnodes.insert(q.subject);
-        }
-        if q.object.is_blank_node() {
-            bnodes.insert(q.object);
+        add_bnodes(&q.subject, &mut bnodes);
+        add_bnodes(&q.object, &mut bnodes);
+        add_bnodes(&q.graph_name, &mut bnodes);
+    }
+    bnodes
+}
+
+/// Adds the blank nodes of a term, including the ones nested in quoted triples
+fn add_bnodes(term: &EncodedTerm, bnodes: &mut HashSet<EncodedTerm>) {
+    if term.is_blank_node() {
+        bnodes.insert(term.clone());
+    } else if let EncodedTerm::Triple(triple) = term {
+        add_bnodes(&triple.subject, bnodes);
+        add_bnodes(&triple.object, bnodes);
+    }
+}
+
+/// Maps the blank nodes used in quoted triples to the quads quoting them
+fn quads_with_quoted_bnodes(g: &MemoryStore) -> HashMap<EncodedTerm, Vec<EncodedQuad>> {
+    let mut quads: HashMap<EncodedTerm, Vec<EncodedQuad>> = HashMap::new();
+    let mut bnodes = HashSet::new();
+    for q in g.encoded_quads() {
+        for term in [&q.subject, &q.object] {
+            if term.is_triple() {
+                add_bnodes(term, &mut bnodes);
+            }
         }
-        if q.graph_name.is_blank_node() {
-            bnodes.insert(q.graph_name);
+        for bnode in bnodes.drain() {
+            quads.entry(bnode).or_default().push(q.clone());
         }
     }
-    bnodes
+    quads
 }
 
 fn label(g: &MemoryStore, hashes: &HashMap<EncodedTerm, u64>) -> Vec<String> {
@@ -1492,6 +1646,13 @@ fn map_term(term: EncodedTerm, bnodes_hash: &HashMap<EncodedTerm, u64>) -> Encod
         EncodedTerm::NumericalBlankNode {
             id: (*bnodes_hash.get(&term).unwrap()).into(),
         }
+    } else if let EncodedTerm::Triple(triple) = term {
+        EncodedTriple::new(
+            map_term(triple.subject.clone(), bnodes_hash),
+            map_term(triple.predicate.clone(), bnodes_hash),
+            map_term(triple.object.clone(), bnodes_hash),
+        )
+        .into()
     } else {
         term
     }
@@ -1500,6 +1661,12 @@ fn map_term(term: EncodedTerm, bnodes_hash: &HashMap<EncodedTerm, u64>) -> Encod
 fn hash_term(term: EncodedTerm, bnodes_hash: &HashMap<EncodedTerm, u64>, g: &MemoryStore) -> u64 {
     if term.is_blank_node() {
         *bnodes_hash.get(&term).unwrap()
+    } else if let EncodedTerm::Triple(triple) = term {
+        hash_tuple((
+            hash_term(triple.subject.clone(), bnodes_hash, g),
+            hash_term(triple.predicate.clone(), bnodes_hash, g),
+            hash_term(triple.object.clone(), bnodes_hash, g),
+        ))
     } else if let Ok(term) = g.decode_term(term) {
         hash_tuple(term)
     } else {
@@ -1512,3 +1679,65 @@ fn hash_tuple(v: impl Hash) -> u64 {
     v.hash(&mut hasher);
     hasher.finish()
 }
+
+#[test]
+fn quoted_triples() {
+    let s = NamedNode::new("http://example.com/s").unwrap();
+    let p = NamedNode::new("http://example.com/p").unwrap();
+    let quoted = Triple::new(s.clone(), p.clone(), Literal::from(1));
+    let nested = Triple::new(quoted.clone(), p.clone(), BlankNode::default());
+    let quad = Quad::new(nested.clone(), p.clone(), quoted.clone(), None);
+
+    let store = MemoryStore::new();
+    store.insert(quad.clone());
+    assert!(store.contains(&quad));
+    assert_eq!(
+        store
+            .quads_for_pattern(Some(Subject::from(nested).as_ref()), None, None, None)
+            .collect::<Vec<_>>(),
+        vec![quad.clone()]
+    );
+    assert_eq!(
+        store
+            .quads_for_pattern(None, None, Some(Term::from(quoted).as_ref()), None)
+            .collect::<Vec<_>>(),
+        vec![quad.clone()]
+    );
+    store.remove(&quad);
+    assert!(!store.contains(&quad));
+    assert_eq!(store.len(), 0);
+}
+
+#[test]
+fn isomorphism_with_quoted_triples() -> io::Result<()> {
+    use crate::io::GraphFormat;
+
+    let load = |data: &str| -> io::Result<MemoryStore> {
+        let store = MemoryStore::new();
+        store.load_graph(
+            data.as_bytes(),
+            GraphFormat::Turtle,
+            &GraphName::DefaultGraph,
+            None,
+        )?;
+        Ok(store)
+    };
+    let data = "@prefix ex: <http://example.com/> .
+        << ex:s ex:p _:x >> ex:q ex:o .
+        _:x ex:r 1 .
+        << _:y ex:p << _:z ex:p ex:o >> >> ex:q _:y .";
+    assert!(load(data)?.is_isomorphic(&load(data)?));
+    assert!(!load(data)?.is_isomorphic(&load(
+        "@prefix ex: <http://example.com/> .
+        << ex:s ex:p _:x >> ex:q ex:o .
+        _:w ex:r 1 .
+        << _:y ex:p << _:z ex:p ex:o >> >> ex:q _:y ."
+    )?));
+    assert!(!load(data)?.is_isomorphic(&load(
+        "@prefix ex: <http://example.com/> .
+        << ex:s ex:p _:x >> ex:q ex:o .
+        _:x ex:r 1 .
+        << _:y ex:p << _:y ex:p ex:o >> >> ex:q _:y ."
+    )?));
+    Ok(())
+}
@@ -2,6 +2,8 @@
 
 #[cfg(any(feature = "rocksdb", feature = "sled"))]
 mod binary_encoder;
+#[cfg(any(feature = "rocksdb", feature = "sled"))]
+mod bulk_load;
 pub mod memory;
 pub(crate) mod numeric_encoder;
 #[cfg(feature = "rocksdb")]
@@ -19,7 +21,10 @@ pub use crate::store::rocksdb::RocksDbStore;
 pub use crate::store::sled::SledStore;
 
 use crate::error::invalid_input_error;
-use crate::io::{DatasetFormat, DatasetSerializer, GraphFormat, GraphSerializer};
+use crate::io::{
+    rdf_xml_error, DatasetFormat, DatasetParser, DatasetSerializer, GraphFormat, GraphParser,
+    GraphSerializer, LineOffsetReader, LineOffsets,
+};
 use crate::model::*;
 use crate::store::numeric_encoder::*;
 use oxiri::Iri;
@@ -53,31 +58,67 @@ pub(crate) trait WritableEncodedStore: StrEncodingAware {
 pub(crate) fn load_graph<S: WritableEncodedStore + StrContainer>(
     store: &mut S,
     reader: impl BufRead,
-    format: GraphFormat,
+    parser: GraphParser,
     to_graph_name: GraphNameRef<'_>,
     base_iri: Option<&str>,
-) -> Result<(), StoreOrParseError<S::Error>> {
+) -> Result<HashMap<String, String>, StoreOrParseError<S::Error>> {
     let base_iri = if let Some(base_iri) = base_iri {
         Some(Iri::parse(base_iri.into()).map_err(invalid_input_error)?)
     } else {
-        None
+        parser.base_iri().cloned()
     };
-    match format {
-        GraphFormat::NTriples => {
-            load_from_triple_parser(store, NTriplesParser::new(reader), to_graph_name)
+    match parser.format() {
+        GraphFormat::NTriples if !parser.is_lenient() && !parser.validates_literals() => {
+            let reader = LineOffsetReader::new(parser.decompress(reader)?);
+            let offsets = reader.offsets();
+            load_from_triple_parser(store, &mut NTriplesParser::new(reader), to_graph_name)
+                .map_err(|e| e.locate(&offsets))?;
+            Ok(HashMap::new())
+        }
+        GraphFormat::Turtle if !parser.is_lenient() && !parser.validates_literals() => {
+            let reader = LineOffsetReader::new(parser.decompress(reader)?);
+            let offsets = reader.offsets();
+            let mut parser = TurtleParser::new(reader, base_iri);
+            load_from_triple_parser(store, &mut parser, to_graph_name)
+                .map_err(|e| e.locate(&offsets))?;
+            Ok(parser.prefixes().clone())
         }
-        GraphFormat::Turtle => {
-            load_from_triple_parser(store, TurtleParser::new(reader, base_iri), to_graph_name)
+        GraphFormat::RdfXml if !parser.validates_literals() => {
+            load_from_triple_parser(
+                store,
+                &mut RdfXmlParser::new(parser.decompress(reader)?, base_iri),
+                to_graph_name,
+            )?;
+            Ok(HashMap::new())
         }
-        GraphFormat::RdfXml => {
-            load_from_triple_parser(store, RdfXmlParser::new(reader, base_iri), to_graph_name)
+        _ => {
+            let parser = if let Some(base_iri) = base_iri {
+                parser
+                    .with_base_iri(base_iri.into_inner())
+                    .map_err(invalid_input_error)?
+            } else {
+                parser
+            };
+            let to_graph_name = store
+                .encode_graph_name(to_graph_name)
+                .map_err(StoreOrParseError::Store)?;
+            let mut triples = parser.read_triples(reader)?;
+            for triple in &mut triples {
+                let quad = store
+                    .encode_triple_in_graph(triple?.as_ref(), to_graph_name.clone())
+                    .map_err(StoreOrParseError::Store)?;
+                store
+                    .insert_encoded(&quad)
+                    .map_err(StoreOrParseError::Store)?;
+            }
+            Ok(triples.prefixes().clone())
         }
     }
 }
 
 fn load_from_triple_parser<S: WritableEncodedStore + StrContainer, P: TriplesParser>(
     store: &mut S,
-    mut parser: P,
+    parser: &mut P,
     to_graph_name: GraphNameRef<'_>,
 ) -> Result<(), StoreOrParseError<S::Error>>
 where
@@ -89,7 +130,7 @@ where
         .map_err(StoreOrParseError::Store)?;
     parser.parse_all(&mut move |t| {
         let quad = store
-            .encode_rio_triple_in_graph(t, to_graph_name, &mut bnode_map)
+            .encode_rio_triple_in_graph(t, to_graph_name.clone(), &mut bnode_map)
             .map_err(StoreOrParseError::Store)?;
         store
             .insert_encoded(&quad)
@@ -101,9 +142,9 @@ where
 fn dump_graph(
     triples: impl Iterator<Item = Result<Triple, io::Error>>,
     writer: impl Write,
-    format: GraphFormat,
+    serializer: &GraphSerializer,
 ) -> Result<(), io::Error> {
-    let mut writer = GraphSerializer::from_format(format).triple_writer(writer)?;
+    let mut writer = serializer.triple_writer(writer)?;
     for triple in triples {
         writer.write(&triple?)?;
     }
@@ -113,23 +154,54 @@ fn dump_graph(
 fn load_dataset<S: WritableEncodedStore + StrContainer>(
     store: &mut S,
     reader: impl BufRead,
-    format: DatasetFormat,
+    parser: DatasetParser,
     base_iri: Option<&str>,
-) -> Result<(), StoreOrParseError<S::Error>> {
+) -> Result<HashMap<String, String>, StoreOrParseError<S::Error>> {
     let base_iri = if let Some(base_iri) = base_iri {
         Some(Iri::parse(base_iri.into()).map_err(invalid_input_error)?)
     } else {
-        None
+        parser.base_iri().cloned()
     };
-    match format {
-        DatasetFormat::NQuads => load_from_quad_parser(store, NQuadsParser::new(reader)),
-        DatasetFormat::TriG => load_from_quad_parser(store, TriGParser::new(reader, base_iri)),
+    match parser.format() {
+        DatasetFormat::NQuads if !parser.is_lenient() && !parser.validates_literals() => {
+            let reader = LineOffsetReader::new(parser.decompress(reader)?);
+            let offsets = reader.offsets();
+            load_from_quad_parser(store, &mut NQuadsParser::new(reader))
+                .map_err(|e| e.locate(&offsets))?;
+            Ok(HashMap::new())
+        }
+        DatasetFormat::TriG if !parser.is_lenient() && !parser.validates_literals() => {
+            let reader = LineOffsetReader::new(parser.decompress(reader)?);
+            let offsets = reader.offsets();
+            let mut parser = TriGParser::new(reader, base_iri);
+            load_from_quad_parser(store, &mut parser).map_err(|e| e.locate(&offsets))?;
+            Ok(parser.prefixes().clone())
+        }
+        _ => {
+            let parser = if let Some(base_iri) = base_iri {
+                parser
+                    .with_base_iri(base_iri.into_inner())
+                    .map_err(invalid_input_error)?
+            } else {
+                parser
+            };
+            let mut quads = parser.read_quads(reader)?;
+            for quad in &mut quads {
+                let quad = store
+                    .encode_quad(quad?.as_ref())
+                    .map_err(StoreOrParseError::Store)?;
+                store
+                    .insert_encoded(&quad)
+                    .map_err(StoreOrParseError::Store)?;
+            }
+            Ok(quads.prefixes().clone())
+        }
     }
 }
 
 fn load_from_quad_parser<S: WritableEncodedStore + StrContainer, P: QuadsParser>(
     store: &mut S,
-    mut parser: P,
+    parser: &mut P,
 ) -> Result<(), StoreOrParseError<S::Error>>
 where
     StoreOrParseError<S::Error>: From<P::Error>,
@@ -149,9 +221,9 @@ where
 fn dump_dataset(
     quads: impl Iterator<Item = Result<Quad, io::Error>>,
     writer: impl Write,
-    format: DatasetFormat,
+    serializer: &DatasetSerializer,
 ) -> Result<(), io::Error> {
-    let mut writer = DatasetSerializer::from_format(format).quad_writer(writer)?;
+    let mut writer = serializer.quad_writer(writer)?;
     for quad in quads {
         writer.write(&quad?)?;
     }
@@ -163,6 +235,16 @@ pub(crate) enum StoreOrParseError<S> {
     Parse(io::Error),
 }
 
+impl<S> StoreOrParseError<S> {
+    /// Adds the error position to the syntax errors
+    fn locate(self, offsets: &LineOffsets) -> Self {
+        match self {
+            Self::Store(error) => Self::Store(error),
+            Self::Parse(error) => Self::Parse(offsets.locate(error)),
+        }
+    }
+}
+
 impl<S> From<TurtleError> for StoreOrParseError<S> {
     fn from(error: TurtleError) -> Self {
         Self::Parse(error.into())
@@ -171,7 +253,7 @@ impl<S> From<TurtleError> for StoreOrParseError<S> {
 
 impl<S> From<RdfXmlError> for StoreOrParseError<S> {
     fn from(error: RdfXmlError) -> Self {
-        Self::Parse(error.into())
+        Self::Parse(rdf_xml_error(error))
     }
 }
 
@@ -208,7 +290,7 @@ type QuadPattern<I> = (
 
 fn get_encoded_quad_pattern<E: ReadEncoder>(
     encoder: &E,
-    subject: Option<NamedOrBlankNodeRef<'_>>,
+    subject: Option<SubjectRef<'_>>,
     predicate: Option<NamedNodeRef<'_>>,
     object: Option<TermRef<'_>>,
     graph_name: Option<GraphNameRef<'_>>,
@@ -216,7 +298,7 @@ fn get_encoded_quad_pattern<E: ReadEncoder>(
     Ok(Some((
         if let Some(subject) = transpose(
             subject
-                .map(|t| encoder.get_encoded_named_or_blank_node(t))
+                .map(|t| encoder.get_encoded_subject(t))
                 .transpose()?,
         ) {
             subject
@@ -13,11 +13,13 @@ use std::error::Error;
 use std::fmt::Debug;
 use std::hash::Hash;
 use std::hash::Hasher;
+use std::str::FromStr;
+use std::sync::Arc;
 use std::{fmt, io, str};
 
 pub trait StrId: Eq + Debug + Copy + Hash {}
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum EncodedTerm<I: StrId> {
     DefaultGraph,
     NamedNode {
@@ -63,6 +65,36 @@ pub enum EncodedTerm<I: StrId> {
     DoubleLiteral(f64),
     IntegerLiteral(i64),
     DecimalLiteral(Decimal),
+    /// An integer that does not fit into an `i64`, stored in its canonical lexical form
+    BigIntegerLiteral {
+        value_id: I,
+    },
+    /// A decimal that does not fit into a `Decimal`, stored in its canonical lexical form
+    BigDecimalLiteral {
+        value_id: I,
+    },
+    /// A value of a datatype derived from `xsd:integer` like `xsd:int`
+    DerivedIntegerLiteral {
+        value: i64,
+        datatype: DerivedIntegerType,
+    },
+    /// A value of a datatype derived from `xsd:integer` that does not fit into an `i64`, stored in its canonical lexical form
+    BigDerivedIntegerLiteral {
+        value_id: I,
+        datatype: DerivedIntegerType,
+    },
+    /// A `xsd:hexBinary` value stored in its canonical lexical form
+    HexBinaryLiteral {
+        value_id: I,
+    },
+    /// A `xsd:base64Binary` value stored in its canonical lexical form
+    Base64BinaryLiteral {
+        value_id: I,
+    },
+    SmallAnyUriLiteral(SmallString),
+    BigAnyUriLiteral {
+        value_id: I,
+    },
     DateTimeLiteral(DateTime),
     TimeLiteral(Time),
     DateLiteral(Date),
@@ -74,6 +106,7 @@ pub enum EncodedTerm<I: StrId> {
     DurationLiteral(Duration),
     YearMonthDurationLiteral(YearMonthDuration),
     DayTimeDurationLiteral(DayTimeDuration),
+    Triple(Arc<EncodedTriple<I>>),
 }
 
 impl<I: StrId> PartialEq for EncodedTerm<I> {
@@ -176,6 +209,67 @@ impl<I: StrId> PartialEq for EncodedTerm<I> {
             }
             (Self::IntegerLiteral(a), Self::IntegerLiteral(b)) => a == b,
             (Self::DecimalLiteral(a), Self::DecimalLiteral(b)) => a == b,
+            (
+                Self::BigIntegerLiteral {
+                    value_id: value_id_a,
+                },
+                Self::BigIntegerLiteral {
+                    value_id: value_id_b,
+                },
+            ) => value_id_a == value_id_b,
+            (
+                Self::BigDecimalLiteral {
+                    value_id: value_id_a,
+                },
+                Self::BigDecimalLiteral {
+                    value_id: value_id_b,
+                },
+            ) => value_id_a == value_id_b,
+            (
+                Self::DerivedIntegerLiteral {
+                    value: value_a,
+                    datatype: datatype_a,
+                },
+                Self::DerivedIntegerLiteral {
+                    value: value_b,
+                    datatype: datatype_b,
+                },
+            ) => value_a == value_b && datatype_a == datatype_b,
+            (
+                Self::BigDerivedIntegerLiteral {
+                    value_id: value_id_a,
+                    datatype: datatype_a,
+                },
+                Self::BigDerivedIntegerLiteral {
+                    value_id: value_id_b,
+                    datatype: datatype_b,
+                },
+            ) => value_id_a == value_id_b && datatype_a == datatype_b,
+            (
+                Self::HexBinaryLiteral {
+                    value_id: value_id_a,
+                },
+                Self::HexBinaryLiteral {
+                    value_id: value_id_b,
+                },
+            ) => value_id_a == value_id_b,
+            (
+                Self::Base64BinaryLiteral {
+                    value_id: value_id_a,
+                },
+                Self::Base64BinaryLiteral {
+                    value_id: value_id_b,
+                },
+            ) => value_id_a == value_id_b,
+            (Self::SmallAnyUriLiteral(a), Self::SmallAnyUriLiteral(b)) => a == b,
+            (
+                Self::BigAnyUriLiteral {
+                    value_id: value_id_a,
+                },
+                Self::BigAnyUriLiteral {
+                    value_id: value_id_b,
+                },
+            ) => value_id_a == value_id_b,
             (Self::DateTimeLiteral(a), Self::DateTimeLiteral(b)) => a.is_identical_with(b),
             (Self::TimeLiteral(a), Self::TimeLiteral(b)) => a.is_identical_with(b),
             (Self::DateLiteral(a), Self::DateLiteral(b)) => a.is_identical_with(b),
@@ -187,6 +281,7 @@ impl<I: StrId> PartialEq for EncodedTerm<I> {
             (Self::DurationLiteral(a), Self::DurationLiteral(b)) => a == b,
             (Self::YearMonthDurationLiteral(a), Self::YearMonthDurationLiteral(b)) => a == b,
             (Self::DayTimeDurationLiteral(a), Self::DayTimeDurationLiteral(b)) => a == b,
+            (Self::Triple(a), Self::Triple(b)) => a == b,
             (_, _) => false,
         }
     }
@@ -239,6 +334,20 @@ impl<I: StrId> Hash for EncodedTerm<I> {
             Self::DoubleLiteral(value) => state.write(&value.to_ne_bytes()),
             Self::IntegerLiteral(value) => value.hash(state),
             Self::DecimalLiteral(value) => value.hash(state),
+            Self::BigIntegerLiteral { value_id } => value_id.hash(state),
+            Self::BigDecimalLiteral { value_id } => value_id.hash(state),
+            Self::DerivedIntegerLiteral { value, datatype } => {
+                value.hash(state);
+                datatype.hash(state);
+            }
+            Self::BigDerivedIntegerLiteral { value_id, datatype } => {
+                value_id.hash(state);
+                datatype.hash(state);
+            }
+            Self::HexBinaryLiteral { value_id } => value_id.hash(state),
+            Self::Base64BinaryLiteral { value_id } => value_id.hash(state),
+            Self::SmallAnyUriLiteral(value) => value.hash(state),
+            Self::BigAnyUriLiteral { value_id } => value_id.hash(state),
             Self::DateTimeLiteral(value) => value.hash(state),
             Self::TimeLiteral(value) => value.hash(state),
             Self::DateLiteral(value) => value.hash(state),
@@ -250,6 +359,7 @@ impl<I: StrId> Hash for EncodedTerm<I> {
             Self::DurationLiteral(value) => value.hash(state),
             Self::YearMonthDurationLiteral(value) => value.hash(state),
             Self::DayTimeDurationLiteral(value) => value.hash(state),
+            Self::Triple(value) => value.hash(state),
         }
     }
 }
@@ -282,6 +392,14 @@ impl<I: StrId> EncodedTerm<I> {
             | Self::DoubleLiteral(_)
             | Self::IntegerLiteral(_)
             | Self::DecimalLiteral(_)
+            | Self::BigIntegerLiteral { .. }
+            | Self::BigDecimalLiteral { .. }
+            | Self::DerivedIntegerLiteral { .. }
+            | Self::BigDerivedIntegerLiteral { .. }
+            | Self::HexBinaryLiteral { .. }
+            | Self::Base64BinaryLiteral { .. }
+            | Self::SmallAnyUriLiteral(_)
+            | Self::BigAnyUriLiteral { .. }
             | Self::DateTimeLiteral(_)
             | Self::TimeLiteral(_)
             | Self::DateLiteral(_)
@@ -295,15 +413,49 @@ impl<I: StrId> EncodedTerm<I> {
             | Self::DayTimeDurationLiteral(_))
     }
 
+    pub fn is_triple(&self) -> bool {
+        matches!(self, Self::Triple(_))
+    }
+
     pub fn is_unknown_typed_literal(&self) -> bool {
         matches!(self, Self::SmallTypedLiteral { .. } | Self::BigTypedLiteral { .. })
     }
 
+    pub fn is_big_numeric_literal(&self) -> bool {
+        matches!(self, Self::BigIntegerLiteral { .. } | Self::BigDecimalLiteral { .. })
+    }
+
+    /// Promotes the values of the datatypes derived from `xsd:integer` to `xsd:integer` values
+    ///
+    /// The other terms are returned unchanged.
+    pub fn promote_derived_integer(self) -> Self {
+        match self {
+            Self::DerivedIntegerLiteral { value, .. } => Self::IntegerLiteral(value),
+            Self::BigDerivedIntegerLiteral { value_id, .. } => Self::BigIntegerLiteral { value_id },
+            term => term,
+        }
+    }
+
+    /// Promotes the `xsd:anyURI` values to `xsd:string` values
+    ///
+    /// The other terms are returned unchanged.
+    pub fn promote_any_uri(self) -> Self {
+        match self {
+            Self::SmallAnyUriLiteral(value) => Self::SmallStringLiteral(value),
+            Self::BigAnyUriLiteral { value_id } => Self::BigStringLiteral { value_id },
+            term => term,
+        }
+    }
+
     pub fn is_default_graph(&self) -> bool {
         matches!(self, Self::DefaultGraph)
     }
 
     pub fn map_id<J: StrId>(self, mapping: impl Fn(I) -> J) -> EncodedTerm<J> {
+        self.map_id_with(&mapping)
+    }
+
+    fn map_id_with<J: StrId>(self, mapping: &dyn Fn(I) -> J) -> EncodedTerm<J> {
         match self {
             Self::DefaultGraph { .. } => EncodedTerm::DefaultGraph,
             Self::NamedNode { iri_id } => EncodedTerm::NamedNode {
@@ -356,6 +508,31 @@ impl<I: StrId> EncodedTerm<I> {
             Self::DoubleLiteral(value) => EncodedTerm::DoubleLiteral(value),
             Self::IntegerLiteral(value) => EncodedTerm::IntegerLiteral(value),
             Self::DecimalLiteral(value) => EncodedTerm::DecimalLiteral(value),
+            Self::BigIntegerLiteral { value_id } => EncodedTerm::BigIntegerLiteral {
+                value_id: mapping(value_id),
+            },
+            Self::BigDecimalLiteral { value_id } => EncodedTerm::BigDecimalLiteral {
+                value_id: mapping(value_id),
+            },
+            Self::DerivedIntegerLiteral { value, datatype } => {
+                EncodedTerm::DerivedIntegerLiteral { value, datatype }
+            }
+            Self::BigDerivedIntegerLiteral { value_id, datatype } => {
+                EncodedTerm::BigDerivedIntegerLiteral {
+                    value_id: mapping(value_id),
+                    datatype,
+                }
+            }
+            Self::HexBinaryLiteral { value_id } => EncodedTerm::HexBinaryLiteral {
+                value_id: mapping(value_id),
+            },
+            Self::Base64BinaryLiteral { value_id } => EncodedTerm::Base64BinaryLiteral {
+                value_id: mapping(value_id),
+            },
+            Self::SmallAnyUriLiteral(value) => EncodedTerm::SmallAnyUriLiteral(value),
+            Self::BigAnyUriLiteral { value_id } => EncodedTerm::BigAnyUriLiteral {
+                value_id: mapping(value_id),
+            },
             Self::DateTimeLiteral(value) => EncodedTerm::DateTimeLiteral(value),
             Self::DateLiteral(value) => EncodedTerm::DateLiteral(value),
             Self::TimeLiteral(value) => EncodedTerm::TimeLiteral(value),
@@ -367,12 +544,25 @@ impl<I: StrId> EncodedTerm<I> {
             Self::DurationLiteral(value) => EncodedTerm::DurationLiteral(value),
             Self::YearMonthDurationLiteral(value) => EncodedTerm::YearMonthDurationLiteral(value),
             Self::DayTimeDurationLiteral(value) => EncodedTerm::DayTimeDurationLiteral(value),
+            Self::Triple(triple) => EncodedTriple {
+                subject: triple.subject.clone().map_id_with(mapping),
+                predicate: triple.predicate.clone().map_id_with(mapping),
+                object: triple.object.clone().map_id_with(mapping),
+            }
+            .into(),
         }
     }
 
     pub fn try_map_id<J: StrId, E>(
         self,
         mut mapping: impl FnMut(I) -> Result<J, E>,
+    ) -> Result<EncodedTerm<J>, E> {
+        self.try_map_id_with(&mut mapping)
+    }
+
+    fn try_map_id_with<J: StrId, E>(
+        self,
+        mapping: &mut dyn FnMut(I) -> Result<J, E>,
     ) -> Result<EncodedTerm<J>, E> {
         Ok(match self {
             Self::DefaultGraph { .. } => EncodedTerm::DefaultGraph,
@@ -426,6 +616,31 @@ impl<I: StrId> EncodedTerm<I> {
             Self::DoubleLiteral(value) => EncodedTerm::DoubleLiteral(value),
             Self::IntegerLiteral(value) => EncodedTerm::IntegerLiteral(value),
             Self::DecimalLiteral(value) => EncodedTerm::DecimalLiteral(value),
+            Self::BigIntegerLiteral { value_id } => EncodedTerm::BigIntegerLiteral {
+                value_id: mapping(value_id)?,
+            },
+            Self::BigDecimalLiteral { value_id } => EncodedTerm::BigDecimalLiteral {
+                value_id: mapping(value_id)?,
+            },
+            Self::DerivedIntegerLiteral { value, datatype } => {
+                EncodedTerm::DerivedIntegerLiteral { value, datatype }
+            }
+            Self::BigDerivedIntegerLiteral { value_id, datatype } => {
+                EncodedTerm::BigDerivedIntegerLiteral {
+                    value_id: mapping(value_id)?,
+                    datatype,
+                }
+            }
+            Self::HexBinaryLiteral { value_id } => EncodedTerm::HexBinaryLiteral {
+                value_id: mapping(value_id)?,
+            },
+            Self::Base64BinaryLiteral { value_id } => EncodedTerm::Base64BinaryLiteral {
+                value_id: mapping(value_id)?,
+            },
+            Self::SmallAnyUriLiteral(value) => EncodedTerm::SmallAnyUriLiteral(value),
+            Self::BigAnyUriLiteral { value_id } => EncodedTerm::BigAnyUriLiteral {
+                value_id: mapping(value_id)?,
+            },
             Self::DateTimeLiteral(value) => EncodedTerm::DateTimeLiteral(value),
             Self::DateLiteral(value) => EncodedTerm::DateLiteral(value),
             Self::TimeLiteral(value) => EncodedTerm::TimeLiteral(value),
@@ -437,6 +652,12 @@ impl<I: StrId> EncodedTerm<I> {
             Self::DurationLiteral(value) => EncodedTerm::DurationLiteral(value),
             Self::YearMonthDurationLiteral(value) => EncodedTerm::YearMonthDurationLiteral(value),
             Self::DayTimeDurationLiteral(value) => EncodedTerm::DayTimeDurationLiteral(value),
+            Self::Triple(triple) => EncodedTriple {
+                subject: triple.subject.clone().try_map_id_with(mapping)?,
+                predicate: triple.predicate.clone().try_map_id_with(mapping)?,
+                object: triple.object.clone().try_map_id_with(mapping)?,
+            }
+            .into(),
         })
     }
 }
@@ -524,7 +745,30 @@ impl<I: StrId> From<DayTimeDuration> for EncodedTerm<I> {
     }
 }
 
-#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash)]
+impl<I: StrId> From<EncodedTriple<I>> for EncodedTerm<I> {
+    fn from(value: EncodedTriple<I>) -> Self {
+        Self::Triple(Arc::new(value))
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Hash)]
+pub struct EncodedTriple<I: StrId> {
+    pub subject: EncodedTerm<I>,
+    pub predicate: EncodedTerm<I>,
+    pub object: EncodedTerm<I>,
+}
+
+impl<I: StrId> EncodedTriple<I> {
+    pub fn new(subject: EncodedTerm<I>, predicate: EncodedTerm<I>, object: EncodedTerm<I>) -> Self {
+        Self {
+            subject,
+            predicate,
+            object,
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Hash)]
 pub struct EncodedQuad<I: StrId> {
     pub subject: EncodedTerm<I>,
     pub predicate: EncodedTerm<I>,
@@ -677,20 +921,44 @@ pub(crate) trait ReadEncoder: StrEncodingAware {
                 }
                 "http://www.w3.org/2001/XMLSchema#float" => parse_float_str(value),
                 "http://www.w3.org/2001/XMLSchema#double" => parse_double_str(value),
-                "http://www.w3.org/2001/XMLSchema#integer"
-                | "http://www.w3.org/2001/XMLSchema#byte"
-                | "http://www.w3.org/2001/XMLSchema#short"
-                | "http://www.w3.org/2001/XMLSchema#int"
-                | "http://www.w3.org/2001/XMLSchema#long"
-                | "http://www.w3.org/2001/XMLSchema#unsignedByte"
-                | "http://www.w3.org/2001/XMLSchema#unsignedShort"
-                | "http://www.w3.org/2001/XMLSchema#unsignedInt"
-                | "http://www.w3.org/2001/XMLSchema#unsignedLong"
-                | "http://www.w3.org/2001/XMLSchema#positiveInteger"
-                | "http://www.w3.org/2001/XMLSchema#negativeInteger"
-                | "http://www.w3.org/2001/XMLSchema#nonPositiveInteger"
-                | "http://www.w3.org/2001/XMLSchema#nonNegativeInteger" => parse_integer_str(value),
-                "http://www.w3.org/2001/XMLSchema#decimal" => parse_decimal_str(value),
+                "http://www.w3.org/2001/XMLSchema#integer" => {
+                    if let Some(term) = parse_integer_str(value) {
+                        Some(term)
+                    } else if let Ok(value) = BigInteger::from_str(value) {
+                        Some(EncodedTerm::BigIntegerLiteral {
+                            value_id: if let Some(value_id) =
+                                self.get_encoded_str(&value.to_string())?
+                            {
+                                value_id
+                            } else {
+                                return Ok(None);
+                            },
+                        })
+                    } else {
+                        None
+                    }
+                }
+                "http://www.w3.org/2001/XMLSchema#decimal" => {
+                    if let Some(term) = parse_decimal_str(value) {
+                        Some(term)
+                    } else if let Ok(value) = BigDecimal::from_str(value) {
+                        Some(if let Ok(value) = Decimal::try_from(&value) {
+                            EncodedTerm::DecimalLiteral(value)
+                        } else {
+                            EncodedTerm::BigDecimalLiteral {
+                                value_id: if let Some(value_id) =
+                                    self.get_encoded_str(&value.to_string())?
+                                {
+                                    value_id
+                                } else {
+                                    return Ok(None);
+                                },
+                            }
+                        })
+                    } else {
+                        None
+                    }
+                }
                 "http://www.w3.org/2001/XMLSchema#dateTime"
                 | "http://www.w3.org/2001/XMLSchema#dateTimeStamp" => parse_date_time_str(value),
                 "http://www.w3.org/2001/XMLSchema#time" => parse_time_str(value),
@@ -707,7 +975,78 @@ pub(crate) trait ReadEncoder: StrEncodingAware {
                 "http://www.w3.org/2001/XMLSchema#dayTimeDuration" => {
                     parse_day_time_duration_str(value)
                 }
-                _ => None,
+                "http://www.w3.org/2001/XMLSchema#hexBinary" => {
+                    if let Ok(value) = HexBinary::from_str(value) {
+                        Some(EncodedTerm::HexBinaryLiteral {
+                            value_id: if let Some(value_id) =
+                                self.get_encoded_str(&value.to_string())?
+                            {
+                                value_id
+                            } else {
+                                return Ok(None);
+                            },
+                        })
+                    } else {
+                        None
+                    }
+                }
+                "http://www.w3.org/2001/XMLSchema#base64Binary" => {
+                    if let Ok(value) = Base64Binary::from_str(value) {
+                        Some(EncodedTerm::Base64BinaryLiteral {
+                            value_id: if let Some(value_id) =
+                                self.get_encoded_str(&value.to_string())?
+                            {
+                                value_id
+                            } else {
+                                return Ok(None);
+                            },
+                        })
+                    } else {
+                        None
+                    }
+                }
+                "http://www.w3.org/2001/XMLSchema#anyURI" => {
+                    if let Some(value) = parse_any_uri(value) {
+                        Some(if let Ok(value) = SmallString::try_from(value) {
+                            EncodedTerm::SmallAnyUriLiteral(value)
+                        } else {
+                            EncodedTerm::BigAnyUriLiteral {
+                                value_id: if let Some(value_id) = self.get_encoded_str(value)? {
+                                    value_id
+                                } else {
+                                    return Ok(None);
+                                },
+                            }
+                        })
+                    } else {
+                        None
+                    }
+                }
+                _ => match DerivedIntegerType::from_name(literal.datatype()) {
+                    Some(datatype) => {
+                        if let Some(term) = parse_derived_integer_str(value, datatype) {
+                            Some(term)
+                        } else if let Ok(value) = BigInteger::from_str(value) {
+                            if datatype.contains(&value) {
+                                Some(EncodedTerm::BigDerivedIntegerLiteral {
+                                    value_id: if let Some(value_id) =
+                                        self.get_encoded_str(&value.to_string())?
+                                    {
+                                        value_id
+                                    } else {
+                                        return Ok(None);
+                                    },
+                                    datatype,
+                                })
+                            } else {
+                                None
+                            }
+                        } else {
+                            None
+                        }
+                    }
+                    None => None,
+                },
             } {
                 Some(term) => term,
                 None => {
@@ -743,6 +1082,32 @@ pub(crate) trait ReadEncoder: StrEncodingAware {
         ))
     }
 
+    fn get_encoded_triple(
+        &self,
+        triple: TripleRef<'_>,
+    ) -> Result<Option<EncodedTerm<Self::StrId>>, Self::Error> {
+        Ok(Some(
+            EncodedTriple {
+                subject: if let Some(subject) = self.get_encoded_subject(triple.subject)? {
+                    subject
+                } else {
+                    return Ok(None);
+                },
+                predicate: if let Some(predicate) = self.get_encoded_named_node(triple.predicate)? {
+                    predicate
+                } else {
+                    return Ok(None);
+                },
+                object: if let Some(object) = self.get_encoded_term(triple.object)? {
+                    object
+                } else {
+                    return Ok(None);
+                },
+            }
+            .into(),
+        ))
+    }
+
     fn get_encoded_named_or_blank_node(
         &self,
         term: NamedOrBlankNodeRef<'_>,
@@ -753,6 +1118,17 @@ pub(crate) trait ReadEncoder: StrEncodingAware {
         }
     }
 
+    fn get_encoded_subject(
+        &self,
+        term: SubjectRef<'_>,
+    ) -> Result<Option<EncodedTerm<Self::StrId>>, Self::Error> {
+        match term {
+            SubjectRef::NamedNode(named_node) => self.get_encoded_named_node(named_node),
+            SubjectRef::BlankNode(blank_node) => self.get_encoded_blank_node(blank_node),
+            SubjectRef::Triple(triple) => self.get_encoded_triple(triple.as_ref()),
+        }
+    }
+
     fn get_encoded_term(
         &self,
         term: TermRef<'_>,
@@ -761,6 +1137,7 @@ pub(crate) trait ReadEncoder: StrEncodingAware {
             TermRef::NamedNode(named_node) => self.get_encoded_named_node(named_node),
             TermRef::BlankNode(blank_node) => self.get_encoded_blank_node(blank_node),
             TermRef::Literal(literal) => self.get_encoded_literal(literal),
+            TermRef::Triple(triple) => self.get_encoded_triple(triple.as_ref()),
         }
     }
 
@@ -780,7 +1157,7 @@ pub(crate) trait ReadEncoder: StrEncodingAware {
         quad: QuadRef<'_>,
     ) -> Result<Option<EncodedQuad<Self::StrId>>, Self::Error> {
         Ok(Some(EncodedQuad {
-            subject: if let Some(subject) = self.get_encoded_named_or_blank_node(quad.subject)? {
+            subject: if let Some(subject) = self.get_encoded_subject(quad.subject)? {
                 subject
             } else {
                 return Ok(None);
@@ -846,13 +1223,26 @@ pub(crate) trait WriteEncoder: StrEncodingAware {
         self.encode_rio_literal(literal.into())
     }
 
-    fn encode_named_or_blank_node(
+    fn encode_triple(
         &mut self,
-        term: NamedOrBlankNodeRef<'_>,
+        triple: TripleRef<'_>,
+    ) -> Result<EncodedTerm<Self::StrId>, Self::Error> {
+        Ok(EncodedTriple {
+            subject: self.encode_subject(triple.subject)?,
+            predicate: self.encode_named_node(triple.predicate)?,
+            object: self.encode_term(triple.object)?,
+        }
+        .into())
+    }
+
+    fn encode_subject(
+        &mut self,
+        term: SubjectRef<'_>,
     ) -> Result<EncodedTerm<Self::StrId>, Self::Error> {
         match term {
-            NamedOrBlankNodeRef::NamedNode(named_node) => self.encode_named_node(named_node),
-            NamedOrBlankNodeRef::BlankNode(blank_node) => self.encode_blank_node(blank_node),
+            SubjectRef::NamedNode(named_node) => self.encode_named_node(named_node),
+            SubjectRef::BlankNode(blank_node) => self.encode_blank_node(blank_node),
+            SubjectRef::Triple(triple) => self.encode_triple(triple.as_ref()),
         }
     }
 
@@ -861,6 +1251,7 @@ pub(crate) trait WriteEncoder: StrEncodingAware {
             TermRef::NamedNode(named_node) => self.encode_named_node(named_node),
             TermRef::BlankNode(blank_node) => self.encode_blank_node(blank_node),
             TermRef::Literal(literal) => self.encode_literal(literal),
+            TermRef::Triple(triple) => self.encode_triple(triple.as_ref()),
         }
     }
 
@@ -877,7 +1268,7 @@ pub(crate) trait WriteEncoder: StrEncodingAware {
 
     fn encode_quad(&mut self, quad: QuadRef<'_>) -> Result<EncodedQuad<Self::StrId>, Self::Error> {
         Ok(EncodedQuad {
-            subject: self.encode_named_or_blank_node(quad.subject)?,
+            subject: self.encode_subject(quad.subject)?,
             predicate: self.encode_named_node(quad.predicate)?,
             object: self.encode_term(quad.object)?,
             graph_name: self.encode_graph_name(quad.graph_name)?,
@@ -890,7 +1281,7 @@ pub(crate) trait WriteEncoder: StrEncodingAware {
         graph_name: EncodedTerm<Self::StrId>,
     ) -> Result<EncodedQuad<Self::StrId>, Self::Error> {
         Ok(EncodedQuad {
-            subject: self.encode_named_or_blank_node(triple.subject)?,
+            subject: self.encode_subject(triple.subject)?,
             predicate: self.encode_named_node(triple.predicate)?,
             object: self.encode_term(triple.object)?,
             graph_name,
@@ -969,22 +1360,32 @@ pub(crate) trait WriteEncoder: StrEncodingAware {
                     }
                     "http://www.w3.org/2001/XMLSchema#float" => parse_float_str(value),
                     "http://www.w3.org/2001/XMLSchema#double" => parse_double_str(value),
-                    "http://www.w3.org/2001/XMLSchema#integer"
-                    | "http://www.w3.org/2001/XMLSchema#byte"
-                    | "http://www.w3.org/2001/XMLSchema#short"
-                    | "http://www.w3.org/2001/XMLSchema#int"
-                    | "http://www.w3.org/2001/XMLSchema#long"
-                    | "http://www.w3.org/2001/XMLSchema#unsignedByte"
-                    | "http://www.w3.org/2001/XMLSchema#unsignedShort"
-                    | "http://www.w3.org/2001/XMLSchema#unsignedInt"
-                    | "http://www.w3.org/2001/XMLSchema#unsignedLong"
-                    | "http://www.w3.org/2001/XMLSchema#positiveInteger"
-                    | "http://www.w3.org/2001/XMLSchema#negativeInteger"
-                    | "http://www.w3.org/2001/XMLSchema#nonPositiveInteger"
-                    | "http://www.w3.org/2001/XMLSchema#nonNegativeInteger" => {
-                        parse_integer_str(value)
+                    "http://www.w3.org/2001/XMLSchema#integer" => {
+                        if let Some(term) = parse_integer_str(value) {
+                            Some(term)
+                        } else if let Ok(value) = BigInteger::from_str(value) {
+                            Some(EncodedTerm::BigIntegerLiteral {
+                                value_id: self.encode_str(&value.to_string())?,
+                            })
+                        } else {
+                            None
+                        }
+                    }
+                    "http://www.w3.org/2001/XMLSchema#decimal" => {
+                        if let Some(term) = parse_decimal_str(value) {
+                            Some(term)
+                        } else if let Ok(value) = BigDecimal::from_str(value) {
+                            Some(if let Ok(value) = Decimal::try_from(&value) {
+                                EncodedTerm::DecimalLiteral(value)
+                            } else {
+                                EncodedTerm::BigDecimalLiteral {
+                                    value_id: self.encode_str(&value.to_string())?,
+                                }
+                            })
+                        } else {
+                            None
+                        }
                     }
-                    "http://www.w3.org/2001/XMLSchema#decimal" => parse_decimal_str(value),
                     "http://www.w3.org/2001/XMLSchema#dateTime"
                     | "http://www.w3.org/2001/XMLSchema#dateTimeStamp" => {
                         parse_date_time_str(value)
@@ -1003,7 +1404,58 @@ pub(crate) trait WriteEncoder: StrEncodingAware {
                     "http://www.w3.org/2001/XMLSchema#dayTimeDuration" => {
                         parse_day_time_duration_str(value)
                     }
-                    _ => None,
+                    "http://www.w3.org/2001/XMLSchema#hexBinary" => {
+                        if let Ok(value) = HexBinary::from_str(value) {
+                            Some(EncodedTerm::HexBinaryLiteral {
+                                value_id: self.encode_str(&value.to_string())?,
+                            })
+                        } else {
+                            None
+                        }
+                    }
+                    "http://www.w3.org/2001/XMLSchema#base64Binary" => {
+                        if let Ok(value) = Base64Binary::from_str(value) {
+                            Some(EncodedTerm::Base64BinaryLiteral {
+                                value_id: self.encode_str(&value.to_string())?,
+                            })
+                        } else {
+                            None
+                        }
+                    }
+                    "http://www.w3.org/2001/XMLSchema#anyURI" => {
+                        if let Some(value) = parse_any_uri(value) {
+                            Some(if let Ok(value) = SmallString::try_from(value) {
+                                EncodedTerm::SmallAnyUriLiteral(value)
+                            } else {
+                                EncodedTerm::BigAnyUriLiteral {
+                                    value_id: self.encode_str(value)?,
+                                }
+                            })
+                        } else {
+                            None
+                        }
+                    }
+                    _ => match DerivedIntegerType::from_name(NamedNodeRef::new_unchecked(
+                        datatype.iri,
+                    )) {
+                        Some(datatype) => {
+                            if let Some(term) = parse_derived_integer_str(value, datatype) {
+                                Some(term)
+                            } else if let Ok(value) = BigInteger::from_str(value) {
+                                if datatype.contains(&value) {
+                                    Some(EncodedTerm::BigDerivedIntegerLiteral {
+                                        value_id: self.encode_str(&value.to_string())?,
+                                        datatype,
+                                    })
+                                } else {
+                                    None
+                                }
+                            } else {
+                                None
+                            }
+                        }
+                        None => None,
+                    },
                 } {
                     Some(v) => v,
                     None => {
@@ -1024,16 +1476,30 @@ pub(crate) trait WriteEncoder: StrEncodingAware {
         })
     }
 
-    fn encode_rio_named_or_blank_node(
+    fn encode_rio_triple(
         &mut self,
-        term: rio::NamedOrBlankNode<'_>,
+        triple: &rio::Triple<'_>,
+        bnodes_map: &mut HashMap<String, u128>,
+    ) -> Result<EncodedTerm<Self::StrId>, Self::Error> {
+        Ok(EncodedTriple {
+            subject: self.encode_rio_subject(triple.subject, bnodes_map)?,
+            predicate: self.encode_rio_named_node(triple.predicate)?,
+            object: self.encode_rio_term(triple.object, bnodes_map)?,
+        }
+        .into())
+    }
+
+    fn encode_rio_subject(
+        &mut self,
+        term: rio::Subject<'_>,
         bnodes_map: &mut HashMap<String, u128>,
     ) -> Result<EncodedTerm<Self::StrId>, Self::Error> {
         match term {
-            rio::NamedOrBlankNode::NamedNode(named_node) => self.encode_rio_named_node(named_node),
-            rio::NamedOrBlankNode::BlankNode(blank_node) => {
+            rio::Subject::NamedNode(named_node) => self.encode_rio_named_node(named_node),
+            rio::Subject::BlankNode(blank_node) => {
                 self.encode_rio_blank_node(blank_node, bnodes_map)
             }
+            rio::Subject::Triple(triple) => self.encode_rio_triple(triple, bnodes_map),
         }
     }
 
@@ -1046,6 +1512,21 @@ pub(crate) trait WriteEncoder: StrEncodingAware {
             rio::Term::NamedNode(named_node) => self.encode_rio_named_node(named_node),
             rio::Term::BlankNode(blank_node) => self.encode_rio_blank_node(blank_node, bnodes_map),
             rio::Term::Literal(literal) => self.encode_rio_literal(literal),
+            rio::Term::Triple(triple) => self.encode_rio_triple(triple, bnodes_map),
+        }
+    }
+
+    fn encode_rio_graph_name(
+        &mut self,
+        name: Option<rio::GraphName<'_>>,
+        bnodes_map: &mut HashMap<String, u128>,
+    ) -> Result<EncodedTerm<Self::StrId>, Self::Error> {
+        match name {
+            Some(rio::GraphName::NamedNode(named_node)) => self.encode_rio_named_node(named_node),
+            Some(rio::GraphName::BlankNode(blank_node)) => {
+                self.encode_rio_blank_node(blank_node, bnodes_map)
+            }
+            None => Ok(EncodedTerm::DefaultGraph),
         }
     }
 
@@ -1055,13 +1536,10 @@ pub(crate) trait WriteEncoder: StrEncodingAware {
         bnodes_map: &mut HashMap<String, u128>,
     ) -> Result<EncodedQuad<Self::StrId>, Self::Error> {
         Ok(EncodedQuad {
-            subject: self.encode_rio_named_or_blank_node(quad.subject, bnodes_map)?,
+            subject: self.encode_rio_subject(quad.subject, bnodes_map)?,
             predicate: self.encode_rio_named_node(quad.predicate)?,
             object: self.encode_rio_term(quad.object, bnodes_map)?,
-            graph_name: match quad.graph_name {
-                Some(graph_name) => self.encode_rio_named_or_blank_node(graph_name, bnodes_map)?,
-                None => EncodedTerm::DefaultGraph,
-            },
+            graph_name: self.encode_rio_graph_name(quad.graph_name, bnodes_map)?,
         })
     }
 
@@ -1072,7 +1550,7 @@ pub(crate) trait WriteEncoder: StrEncodingAware {
         bnodes_map: &mut HashMap<String, u128>,
     ) -> Result<EncodedQuad<Self::StrId>, Self::Error> {
         Ok(EncodedQuad {
-            subject: self.encode_rio_named_or_blank_node(triple.subject, bnodes_map)?,
+            subject: self.encode_rio_subject(triple.subject, bnodes_map)?,
             predicate: self.encode_rio_named_node(triple.predicate)?,
             object: self.encode_rio_term(triple.object, bnodes_map)?,
             graph_name,
@@ -1108,6 +1586,17 @@ pub fn parse_integer_str<I: StrId>(value: &str) -> Option<EncodedTerm<I>> {
     value.parse().map(EncodedTerm::IntegerLiteral).ok()
 }
 
+pub fn parse_derived_integer_str<I: StrId>(
+    value: &str,
+    datatype: DerivedIntegerType,
+) -> Option<EncodedTerm<I>> {
+    value
+        .parse()
+        .ok()
+        .filter(|value| datatype.contains_i64(*value))
+        .map(|value| EncodedTerm::DerivedIntegerLiteral { value, datatype })
+}
+
 pub fn parse_decimal_str<I: StrId>(value: &str) -> Option<EncodedTerm<I>> {
     value.parse().map(EncodedTerm::DecimalLiteral).ok()
 }
@@ -1165,6 +1654,20 @@ pub(crate) trait Decoder: StrLookup {
         encoded: EncodedTerm<Self::StrId>,
     ) -> Result<Term, DecoderError<Self::Error>>;
 
+    fn decode_subject(
+        &self,
+        encoded: EncodedTerm<Self::StrId>,
+    ) -> Result<Subject, DecoderError<Self::Error>> {
+        match self.decode_term(encoded)? {
+            Term::NamedNode(named_node) => Ok(named_node.into()),
+            Term::BlankNode(blank_node) => Ok(blank_node.into()),
+            Term::Literal(_) => Err(DecoderError::Decoder {
+                msg: "A literal has ben found instead of a named node".to_owned(),
+            }),
+            Term::Triple(triple) => Ok(triple.into()),
+        }
+    }
+
     fn decode_named_or_blank_node(
         &self,
         encoded: EncodedTerm<Self::StrId>,
@@ -1175,6 +1678,9 @@ pub(crate) trait Decoder: StrLookup {
             Term::Literal(_) => Err(DecoderError::Decoder {
                 msg: "A literal has ben found instead of a named node".to_owned(),
             }),
+            Term::Triple(_) => Err(DecoderError::Decoder {
+                msg: "A triple has been found instead of a named node".to_owned(),
+            }),
         }
     }
 
@@ -1190,6 +1696,9 @@ pub(crate) trait Decoder: StrLookup {
             Term::Literal(_) => Err(DecoderError::Decoder {
                 msg: "A literal has ben found instead of a named node".to_owned(),
             }),
+            Term::Triple(_) => Err(DecoderError::Decoder {
+                msg: "A triple has been found instead of a named node".to_owned(),
+            }),
         }
     }
 
@@ -1198,9 +1707,9 @@ pub(crate) trait Decoder: StrLookup {
         encoded: &EncodedQuad<Self::StrId>,
     ) -> Result<Triple, DecoderError<Self::Error>> {
         Ok(Triple::new(
-            self.decode_named_or_blank_node(encoded.subject)?,
-            self.decode_named_node(encoded.predicate)?,
-            self.decode_term(encoded.object)?,
+            self.decode_subject(encoded.subject.clone())?,
+            self.decode_named_node(encoded.predicate.clone())?,
+            self.decode_term(encoded.object.clone())?,
         ))
     }
 
@@ -1209,12 +1718,12 @@ pub(crate) trait Decoder: StrLookup {
         encoded: &EncodedQuad<Self::StrId>,
     ) -> Result<Quad, DecoderError<Self::Error>> {
         Ok(Quad::new(
-            self.decode_named_or_blank_node(encoded.subject)?,
-            self.decode_named_node(encoded.predicate)?,
-            self.decode_term(encoded.object)?,
-            match encoded.graph_name {
+            self.decode_subject(encoded.subject.clone())?,
+            self.decode_named_node(encoded.predicate.clone())?,
+            self.decode_term(encoded.object.clone())?,
+            match &encoded.graph_name {
                 EncodedTerm::DefaultGraph => None,
-                graph_name => Some(self.decode_named_or_blank_node(graph_name)?),
+                graph_name => Some(self.decode_named_or_blank_node(graph_name.clone())?),
             },
         ))
     }
@@ -1286,6 +1795,41 @@ impl<S: StrLookup> Decoder for S {
             EncodedTerm::DoubleLiteral(value) => Ok(Literal::from(value).into()),
             EncodedTerm::IntegerLiteral(value) => Ok(Literal::from(value).into()),
             EncodedTerm::DecimalLiteral(value) => Ok(Literal::from(value).into()),
+            EncodedTerm::BigIntegerLiteral { value_id } => Ok(Literal::new_typed_literal(
+                get_required_str(self, value_id)?,
+                vocab::xsd::INTEGER,
+            )
+            .into()),
+            EncodedTerm::BigDecimalLiteral { value_id } => Ok(Literal::new_typed_literal(
+                get_required_str(self, value_id)?,
+                vocab::xsd::DECIMAL,
+            )
+            .into()),
+            EncodedTerm::DerivedIntegerLiteral { value, datatype } => {
+                Ok(Literal::new_typed_literal(value.to_string(), datatype.name()).into())
+            }
+            EncodedTerm::BigDerivedIntegerLiteral { value_id, datatype } => Ok(
+                Literal::new_typed_literal(get_required_str(self, value_id)?, datatype.name())
+                    .into(),
+            ),
+            EncodedTerm::HexBinaryLiteral { value_id } => Ok(Literal::new_typed_literal(
+                get_required_str(self, value_id)?,
+                vocab::xsd::HEX_BINARY,
+            )
+            .into()),
+            EncodedTerm::Base64BinaryLiteral { value_id } => Ok(Literal::new_typed_literal(
+                get_required_str(self, value_id)?,
+                vocab::xsd::BASE_64_BINARY,
+            )
+            .into()),
+            EncodedTerm::SmallAnyUriLiteral(value) => {
+                Ok(Literal::new_typed_literal(value, vocab::xsd::ANY_URI).into())
+            }
+            EncodedTerm::BigAnyUriLiteral { value_id } => Ok(Literal::new_typed_literal(
+                get_required_str(self, value_id)?,
+                vocab::xsd::ANY_URI,
+            )
+            .into()),
             EncodedTerm::DateTimeLiteral(value) => Ok(Literal::from(value).into()),
             EncodedTerm::DateLiteral(value) => Ok(Literal::from(value).into()),
             EncodedTerm::TimeLiteral(value) => Ok(Literal::from(value).into()),
@@ -1297,6 +1841,12 @@ impl<S: StrLookup> Decoder for S {
             EncodedTerm::DurationLiteral(value) => Ok(Literal::from(value).into()),
             EncodedTerm::YearMonthDurationLiteral(value) => Ok(Literal::from(value).into()),
             EncodedTerm::DayTimeDurationLiteral(value) => Ok(Literal::from(value).into()),
+            EncodedTerm::Triple(triple) => Ok(Triple::new(
+                self.decode_subject(triple.subject.clone())?,
+                self.decode_named_node(triple.predicate.clone())?,
+                self.decode_term(triple.object.clone())?,
+            )
+            .into()),
         }
     }
 }
@@ -1,13 +1,14 @@
 //! Store based on the [RocksDB](https://rocksdb.org/) key-value database.
 
 use crate::error::invalid_data_error;
-use crate::io::{DatasetFormat, GraphFormat};
+use crate::io::{DatasetParser, DatasetSerializer, GraphParser, GraphSerializer};
 use crate::model::*;
 use crate::sparql::{
-    evaluate_query, evaluate_update, EvaluationError, Query, QueryOptions, QueryResults, Update,
-    UpdateOptions,
+    evaluate_query, evaluate_update, explain_query, EvaluationError, Query, QueryExplanation,
+    QueryOptions, QueryResults, Update, UpdateOptions,
 };
 use crate::store::binary_encoder::*;
+use crate::store::bulk_load::{bulk_load_dataset, bulk_load_graph};
 use crate::store::numeric_encoder::{
     Decoder, ReadEncoder, StrContainer, StrEncodingAware, StrLookup, WriteEncoder,
 };
@@ -96,7 +97,16 @@ impl RocksDbStore {
             db: Arc::new(DB::open_cf(&options, path, &COLUMN_FAMILIES).map_err(map_err)?),
         };
 
-        let version = this.ensure_version()?;
+        let mut version = this.ensure_version()?;
+        if version == 0 {
+            let mut writer = this.auto_batch_writer();
+            migrate_from_v0(&this, &mut writer)?;
+            writer.apply()?;
+            version = 1;
+            this.db
+                .put("oxversion", &version.to_be_bytes())
+                .map_err(map_err)?;
+        }
         if version != LATEST_STORAGE_VERSION {
             return Err(invalid_data_error(format!(
                 "The RocksDB database is still using the encoding version {}, please upgrade it",
@@ -141,12 +151,31 @@ impl RocksDbStore {
         evaluate_query(self.clone(), query, options)
     }
 
+    /// Returns the plan used to evaluate a [SPARQL 1.1 query](https://www.w3.org/TR/sparql11-query/) without evaluating it.
+    ///
+    /// See [`MemoryStore`](super::memory::MemoryStore::explain()) for a usage example.
+    pub fn explain(
+        &self,
+        query: impl TryInto<Query, Error = impl Into<EvaluationError>>,
+    ) -> Result<QueryExplanation, EvaluationError> {
+        self.explain_opt(query, QueryOptions::default())
+    }
+
+    /// Returns the plan used to evaluate a [SPARQL 1.1 query](https://www.w3.org/TR/sparql11-query/) with some options without evaluating it.
+    pub fn explain_opt(
+        &self,
+        query: impl TryInto<Query, Error = impl Into<EvaluationError>>,
+        options: QueryOptions,
+    ) -> Result<QueryExplanation, EvaluationError> {
+        explain_query(self.clone(), query, options)
+    }
+
     /// Retrieves quads with a filter on each quad component
     ///
     /// See [`MemoryStore`](super::memory::MemoryStore::quads_for_pattern()) for a usage example.
     pub fn quads_for_pattern(
         &self,
-        subject: Option<NamedOrBlankNodeRef<'_>>,
+        subject: Option<SubjectRef<'_>>,
         predicate: Option<NamedNodeRef<'_>>,
         object: Option<TermRef<'_>>,
         graph_name: Option<GraphNameRef<'_>>,
@@ -263,6 +292,8 @@ impl RocksDbStore {
     /// Warning: This functions saves the triples in batch. If the parsing fails in the middle of the file,
     /// only a part of it may be written. Use a (memory greedy) [transaction](RocksDbStore::transaction()) if you do not want that.
     ///
+    /// A [`GraphParser`] in [lenient mode](GraphParser::lenient()) could be given instead of a format in order to skip the invalid statements.
+    ///
     /// See [`MemoryStore`](super::memory::MemoryStore::load_graph()) for a usage example.
     ///
     /// Errors related to parameter validation like the base IRI use the [`InvalidInput`](std::io::ErrorKind::InvalidInput) error kind.
@@ -271,19 +302,37 @@ impl RocksDbStore {
     pub fn load_graph<'a>(
         &self,
         reader: impl BufRead,
-        format: GraphFormat,
+        parser: impl Into<GraphParser>,
         to_graph_name: impl Into<GraphNameRef<'a>>,
         base_iri: Option<&str>,
     ) -> Result<(), io::Error> {
+        self.load_graph_with_prefixes(reader, parser, to_graph_name, base_iri)?;
+        Ok(())
+    }
+
+    /// Loads a graph file (i.e. triples) into the store like [`load_graph`](RocksDbStore::load_graph()) and returns the prefixes declared in it
+    ///
+    /// The prefixes are returned as a map from prefix name to prefix IRI.
+    /// Only the Turtle and TriG formats allow to declare prefixes.
+    ///
+    /// See [`MemoryStore`](super::memory::MemoryStore::load_graph_with_prefixes()) for a usage example.
+    pub fn load_graph_with_prefixes<'a>(
+        &self,
+        reader: impl BufRead,
+        parser: impl Into<GraphParser>,
+        to_graph_name: impl Into<GraphNameRef<'a>>,
+        base_iri: Option<&str>,
+    ) -> Result<HashMap<String, String>, io::Error> {
         let mut transaction = self.auto_batch_writer();
-        load_graph(
+        let prefixes = load_graph(
             &mut transaction,
             reader,
-            format,
+            parser.into(),
             to_graph_name.into(),
             base_iri,
         )?;
-        Ok(transaction.apply()?)
+        transaction.apply()?;
+        Ok(prefixes)
     }
 
     /// Loads a dataset file (i.e. quads) into the store.
@@ -291,6 +340,8 @@ impl RocksDbStore {
     /// Warning: This functions saves the quads in batch. If the parsing fails in the middle of the file,
     /// only a part of it may be written. Use a (memory greedy) [transaction](RocksDbStore::transaction()) if you do not want that.
     ///
+    /// A [`DatasetParser`] in [lenient mode](DatasetParser::lenient()) could be given instead of a format in order to skip the invalid statements.
+    ///
     /// See [`MemoryStore`](super::memory::MemoryStore::load_dataset()) for a usage example.
     ///
     /// Errors related to parameter validation like the base IRI use the [`InvalidInput`](std::io::ErrorKind::InvalidInput) error kind.
@@ -299,12 +350,92 @@ impl RocksDbStore {
     pub fn load_dataset(
         &self,
         reader: impl BufRead,
-        format: DatasetFormat,
+        parser: impl Into<DatasetParser>,
         base_iri: Option<&str>,
     ) -> Result<(), io::Error> {
+        self.load_dataset_with_prefixes(reader, parser, base_iri)?;
+        Ok(())
+    }
+
+    /// Loads a dataset file (i.e. quads) into the store like [`load_dataset`](RocksDbStore::load_dataset()) and returns the prefixes declared in it
+    ///
+    /// The prefixes are returned as a map from prefix name to prefix IRI.
+    /// Only the Turtle and TriG formats allow to declare prefixes.
+    ///
+    /// See [`MemoryStore`](super::memory::MemoryStore::load_dataset_with_prefixes()) for a usage example.
+    pub fn load_dataset_with_prefixes(
+        &self,
+        reader: impl BufRead,
+        parser: impl Into<DatasetParser>,
+        base_iri: Option<&str>,
+    ) -> Result<HashMap<String, String>, io::Error> {
         let mut transaction = self.auto_batch_writer();
-        load_dataset(&mut transaction, reader, format, base_iri)?;
-        Ok(transaction.apply()?)
+        let prefixes = load_dataset(&mut transaction, reader, parser.into(), base_iri)?;
+        transaction.apply()?;
+        Ok(prefixes)
+    }
+
+    /// Loads a graph file (i.e. triples) into the store using all the available CPU cores.
+    ///
+    /// The [N-Triples](crate::io::GraphFormat::NTriples) files are split into chunks of lines
+    /// that are parsed in parallel. The blank nodes are kept consistent across the chunks of the file.
+    /// The other formats are loaded like with [`load_graph`](RocksDbStore::load_graph()).
+    /// The files read with a [`LiteralValidator`](crate::model::LiteralValidator) are also loaded like with [`load_graph`](RocksDbStore::load_graph()).
+    ///
+    /// Warning: This functions saves the triples in a not atomic way and in no particular order.
+    /// If the parsing fails in the middle of the file, only a part of it may be written to the store.
+    ///
+    /// A [`GraphParser`] in [lenient mode](GraphParser::lenient()) could be given instead of a format in order to skip the invalid statements.
+    /// The errors might then be reported out of order.
+    ///
+    /// Errors related to parameter validation like the base IRI use the [`InvalidInput`](std::io::ErrorKind::InvalidInput) error kind.
+    /// Errors related to a bad syntax in the loaded file use the [`InvalidData`](std::io::ErrorKind::InvalidData) or [`UnexpectedEof`](std::io::ErrorKind::UnexpectedEof) error kinds.
+    /// Errors related to data loading into the store use the other error kinds.
+    pub fn bulk_load_graph<'a>(
+        &self,
+        reader: impl BufRead,
+        parser: impl Into<GraphParser>,
+        to_graph_name: impl Into<GraphNameRef<'a>>,
+        base_iri: Option<&str>,
+    ) -> Result<(), io::Error> {
+        let mut transaction = self.auto_batch_writer();
+        bulk_load_graph(
+            &mut transaction,
+            reader,
+            parser.into(),
+            to_graph_name.into(),
+            base_iri,
+        )?;
+        transaction.apply()?;
+        Ok(())
+    }
+
+    /// Loads a dataset file (i.e. quads) into the store using all the available CPU cores.
+    ///
+    /// The [N-Quads](crate::io::DatasetFormat::NQuads) files are split into chunks of lines
+    /// that are parsed in parallel. The blank nodes are kept consistent across the chunks of the file.
+    /// The other formats are loaded like with [`load_dataset`](RocksDbStore::load_dataset()).
+    /// The files read with a [`LiteralValidator`](crate::model::LiteralValidator) are also loaded like with [`load_dataset`](RocksDbStore::load_dataset()).
+    ///
+    /// Warning: This functions saves the quads in a not atomic way and in no particular order.
+    /// If the parsing fails in the middle of the file, only a part of it may be written to the store.
+    ///
+    /// A [`DatasetParser`] in [lenient mode](DatasetParser::lenient()) could be given instead of a format in order to skip the invalid statements.
+    /// The errors might then be reported out of order.
+    ///
+    /// Errors related to parameter validation like the base IRI use the [`InvalidInput`](std::io::ErrorKind::InvalidInput) error kind.
+    /// Errors related to a bad syntax in the loaded file use the [`InvalidData`](std::io::ErrorKind::InvalidData) or [`UnexpectedEof`](std::io::ErrorKind::UnexpectedEof) error kinds.
+    /// Errors related to data loading into the store use the other error kinds.
+    pub fn bulk_load_dataset(
+        &self,
+        reader: impl BufRead,
+        parser: impl Into<DatasetParser>,
+        base_iri: Option<&str>,
+    ) -> Result<(), io::Error> {
+        let mut transaction = self.auto_batch_writer();
+        bulk_load_dataset(&mut transaction, reader, parser.into(), base_iri)?;
+        transaction.apply()?;
+        Ok(())
     }
 
     /// Adds a quad to this store.
@@ -334,22 +465,26 @@ impl RocksDbStore {
     pub fn dump_graph<'a>(
         &self,
         writer: impl Write,
-        format: GraphFormat,
+        serializer: impl Into<GraphSerializer>,
         from_graph_name: impl Into<GraphNameRef<'a>>,
     ) -> Result<(), io::Error> {
         dump_graph(
             self.quads_for_pattern(None, None, None, Some(from_graph_name.into()))
                 .map(|q| Ok(q?.into())),
             writer,
-            format,
+            &serializer.into(),
         )
     }
 
     /// Dumps the store into a file.
     ///    
     /// See [`MemoryStore`](super::memory::MemoryStore::dump_dataset()) for a usage example.
-    pub fn dump_dataset(&self, writer: impl Write, syntax: DatasetFormat) -> Result<(), io::Error> {
-        dump_dataset(self.iter(), writer, syntax)
+    pub fn dump_dataset(
+        &self,
+        writer: impl Write,
+        serializer: impl Into<DatasetSerializer>,
+    ) -> Result<(), io::Error> {
+        dump_dataset(self.iter(), writer, &serializer.into())
     }
 
     /// Removes a graph from this store.
@@ -458,7 +593,7 @@ impl RocksDbStore {
         )
     }
 
-    fn quads_for_subject(&self, subject: EncodedTerm) -> DecodingIndexesIterator {
+    fn quads_for_subject(&self, subject: &EncodedTerm) -> DecodingIndexesIterator {
         DecodingIndexesIterator::pair(
             self.dspo_quads(encode_term(subject)),
             self.spog_quads(encode_term(subject)),
@@ -467,8 +602,8 @@ impl RocksDbStore {
 
     fn quads_for_subject_predicate(
         &self,
-        subject: EncodedTerm,
-        predicate: EncodedTerm,
+        subject: &EncodedTerm,
+        predicate: &EncodedTerm,
     ) -> DecodingIndexesIterator {
         DecodingIndexesIterator::pair(
             self.dspo_quads(encode_term_pair(subject, predicate)),
@@ -478,9 +613,9 @@ impl RocksDbStore {
 
     fn quads_for_subject_predicate_object(
         &self,
-        subject: EncodedTerm,
-        predicate: EncodedTerm,
-        object: EncodedTerm,
+        subject: &EncodedTerm,
+        predicate: &EncodedTerm,
+        object: &EncodedTerm,
     ) -> DecodingIndexesIterator {
         DecodingIndexesIterator::pair(
             self.dspo_quads(encode_term_triple(subject, predicate, object)),
@@ -490,8 +625,8 @@ impl RocksDbStore {
 
     fn quads_for_subject_object(
         &self,
-        subject: EncodedTerm,
-        object: EncodedTerm,
+        subject: &EncodedTerm,
+        object: &EncodedTerm,
     ) -> DecodingIndexesIterator {
         DecodingIndexesIterator::pair(
             self.dosp_quads(encode_term_pair(object, subject)),
@@ -499,7 +634,7 @@ impl RocksDbStore {
         )
     }
 
-    fn quads_for_predicate(&self, predicate: EncodedTerm) -> DecodingIndexesIterator {
+    fn quads_for_predicate(&self, predicate: &EncodedTerm) -> DecodingIndexesIterator {
         DecodingIndexesIterator::pair(
             self.dpos_quads(encode_term(predicate)),
             self.posg_quads(encode_term(predicate)),
@@ -508,8 +643,8 @@ impl RocksDbStore {
 
     fn quads_for_predicate_object(
         &self,
-        predicate: EncodedTerm,
-        object: EncodedTerm,
+        predicate: &EncodedTerm,
+        object: &EncodedTerm,
     ) -> DecodingIndexesIterator {
         DecodingIndexesIterator::pair(
             self.dpos_quads(encode_term_pair(predicate, object)),
@@ -517,14 +652,14 @@ impl RocksDbStore {
         )
     }
 
-    fn quads_for_object(&self, object: EncodedTerm) -> DecodingIndexesIterator {
+    fn quads_for_object(&self, object: &EncodedTerm) -> DecodingIndexesIterator {
         DecodingIndexesIterator::pair(
             self.dosp_quads(encode_term(object)),
             self.ospg_quads(encode_term(object)),
         )
     }
 
-    fn quads_for_graph(&self, graph_name: EncodedTerm) -> DecodingIndexesIterator {
+    fn quads_for_graph(&self, graph_name: &EncodedTerm) -> DecodingIndexesIterator {
         DecodingIndexesIterator::new(if graph_name.is_default_graph() {
             self.dspo_quads(Vec::default())
         } else {
@@ -534,8 +669,8 @@ impl RocksDbStore {
 
     fn quads_for_subject_graph(
         &self,
-        subject: EncodedTerm,
-        graph_name: EncodedTerm,
+        subject: &EncodedTerm,
+        graph_name: &EncodedTerm,
     ) -> DecodingIndexesIterator {
         DecodingIndexesIterator::new(if graph_name.is_default_graph() {
             self.dspo_quads(encode_term(subject))
@@ -546,9 +681,9 @@ impl RocksDbStore {
 
     fn quads_for_subject_predicate_graph(
         &self,
-        subject: EncodedTerm,
-        predicate: EncodedTerm,
-        graph_name: EncodedTerm,
+        subject: &EncodedTerm,
+        predicate: &EncodedTerm,
+        graph_name: &EncodedTerm,
     ) -> DecodingIndexesIterator {
         DecodingIndexesIterator::new(if graph_name.is_default_graph() {
             self.dspo_quads(encode_term_pair(subject, predicate))
@@ -559,10 +694,10 @@ impl RocksDbStore {
 
     fn quads_for_subject_predicate_object_graph(
         &self,
-        subject: EncodedTerm,
-        predicate: EncodedTerm,
-        object: EncodedTerm,
-        graph_name: EncodedTerm,
+        subject: &EncodedTerm,
+        predicate: &EncodedTerm,
+        object: &EncodedTerm,
+        graph_name: &EncodedTerm,
     ) -> DecodingIndexesIterator {
         DecodingIndexesIterator::new(if graph_name.is_default_graph() {
             self.dspo_quads(encode_term_triple(subject, predicate, object))
@@ -573,9 +708,9 @@ impl RocksDbStore {
 
     fn quads_for_subject_object_graph(
         &self,
-        subject: EncodedTerm,
-        object: EncodedTerm,
-        graph_name: EncodedTerm,
+        subject: &EncodedTerm,
+        object: &EncodedTerm,
+        graph_name: &EncodedTerm,
     ) -> DecodingIndexesIterator {
         DecodingIndexesIterator::new(if graph_name.is_default_graph() {
             self.dosp_quads(encode_term_pair(object, subject))
@@ -586,8 +721,8 @@ impl RocksDbStore {
 
     fn quads_for_predicate_graph(
         &self,
-        predicate: EncodedTerm,
-        graph_name: EncodedTerm,
+        predicate: &EncodedTerm,
+        graph_name: &EncodedTerm,
     ) -> DecodingIndexesIterator {
         DecodingIndexesIterator::new(if graph_name.is_default_graph() {
             self.dpos_quads(encode_term(predicate))
@@ -598,9 +733,9 @@ impl RocksDbStore {
 
     fn quads_for_predicate_object_graph(
         &self,
-        predicate: EncodedTerm,
-        object: EncodedTerm,
-        graph_name: EncodedTerm,
+        predicate: &EncodedTerm,
+        object: &EncodedTerm,
+        graph_name: &EncodedTerm,
     ) -> DecodingIndexesIterator {
         DecodingIndexesIterator::new(if graph_name.is_default_graph() {
             self.dpos_quads(encode_term_pair(predicate, object))
@@ -611,8 +746,8 @@ impl RocksDbStore {
 
     fn quads_for_object_graph(
         &self,
-        object: EncodedTerm,
-        graph_name: EncodedTerm,
+        object: &EncodedTerm,
+        graph_name: &EncodedTerm,
     ) -> DecodingIndexesIterator {
         DecodingIndexesIterator::new(if graph_name.is_default_graph() {
             self.dosp_quads(encode_term(object))
@@ -753,55 +888,55 @@ impl ReadableEncodedStore for RocksDbStore {
         object: Option<EncodedTerm>,
         graph_name: Option<EncodedTerm>,
     ) -> DecodingIndexesIterator {
-        match subject {
-            Some(subject) => match predicate {
-                Some(predicate) => match object {
-                    Some(object) => match graph_name {
+        match &subject {
+            Some(subject) => match &predicate {
+                Some(predicate) => match &object {
+                    Some(object) => match &graph_name {
                         Some(graph_name) => self.quads_for_subject_predicate_object_graph(
                             subject, predicate, object, graph_name,
                         ),
                         None => self.quads_for_subject_predicate_object(subject, predicate, object),
                     },
-                    None => match graph_name {
+                    None => match &graph_name {
                         Some(graph_name) => {
                             self.quads_for_subject_predicate_graph(subject, predicate, graph_name)
                         }
                         None => self.quads_for_subject_predicate(subject, predicate),
                     },
                 },
-                None => match object {
-                    Some(object) => match graph_name {
+                None => match &object {
+                    Some(object) => match &graph_name {
                         Some(graph_name) => {
                             self.quads_for_subject_object_graph(subject, object, graph_name)
                         }
                         None => self.quads_for_subject_object(subject, object),
                     },
-                    None => match graph_name {
+                    None => match &graph_name {
                         Some(graph_name) => self.quads_for_subject_graph(subject, graph_name),
                         None => self.quads_for_subject(subject),
                     },
                 },
             },
-            None => match predicate {
-                Some(predicate) => match object {
-                    Some(object) => match graph_name {
+            None => match &predicate {
+                Some(predicate) => match &object {
+                    Some(object) => match &graph_name {
                         Some(graph_name) => {
                             self.quads_for_predicate_object_graph(predicate, object, graph_name)
                         }
 
                         None => self.quads_for_predicate_object(predicate, object),
                     },
-                    None => match graph_name {
+                    None => match &graph_name {
                         Some(graph_name) => self.quads_for_predicate_graph(predicate, graph_name),
                         None => self.quads_for_predicate(predicate),
                     },
                 },
-                None => match object {
-                    Some(object) => match graph_name {
+                None => match &object {
+                    Some(object) => match &graph_name {
                         Some(graph_name) => self.quads_for_object_graph(object, graph_name),
                         None => self.quads_for_object(object),
                     },
-                    None => match graph_name {
+                    None => match &graph_name {
                         Some(graph_name) => self.quads_for_graph(graph_name),
                         None => self.quads(),
                     },
@@ -959,11 +1094,11 @@ impl RocksDbTransaction<'_> {
     pub fn load_graph<'a>(
         &mut self,
         reader: impl BufRead,
-        syntax: GraphFormat,
+        parser: impl Into<GraphParser>,
         to_graph_name: impl Into<GraphNameRef<'a>>,
         base_iri: Option<&str>,
     ) -> Result<(), io::Error> {
-        load_graph(self, reader, syntax, to_graph_name.into(), base_iri)?;
+        load_graph(self, reader, parser.into(), to_graph_name.into(), base_iri)?;
         Ok(())
     }
 
@@ -984,10 +1119,10 @@ impl RocksDbTransaction<'_> {
     pub fn load_dataset(
         &mut self,
         reader: impl BufRead,
-        format: DatasetFormat,
+        parser: impl Into<DatasetParser>,
         base_iri: Option<&str>,
     ) -> Result<(), io::Error> {
-        load_dataset(self, reader, format, base_iri)?;
+        load_dataset(self, reader, parser.into(), base_iri)?;
         Ok(())
     }
 
@@ -1274,7 +1409,7 @@ fn store() -> Result<(), io::Error> {
     use std::env::temp_dir;
     use std::fs::remove_dir_all;
 
-    let main_s = NamedOrBlankNode::from(BlankNode::default());
+    let main_s = Subject::from(BlankNode::default());
     let main_p = NamedNode::new("http://example.com").unwrap();
     let main_o = Term::from(Literal::from(1));
     let main_g = GraphName::from(BlankNode::default());
@@ -1471,3 +1606,44 @@ fn store() -> Result<(), io::Error> {
     remove_dir_all(&repo_path)?;
     Ok(())
 }
+
+#[test]
+fn quoted_triples() -> Result<(), io::Error> {
+    use crate::model::*;
+    use rand::random;
+    use std::env::temp_dir;
+    use std::fs::remove_dir_all;
+
+    let s = NamedNode::new("http://example.com/s").unwrap();
+    let p = NamedNode::new("http://example.com/p").unwrap();
+    let quoted = Triple::new(s.clone(), p.clone(), Literal::from(1));
+    let nested = Triple::new(quoted.clone(), p.clone(), BlankNode::default());
+    let quad = Quad::new(nested.clone(), p.clone(), quoted.clone(), None);
+
+    let mut repo_path = temp_dir();
+    repo_path.push(random::<u128>().to_string());
+
+    {
+        let store = RocksDbStore::open(&repo_path)?;
+        store.insert(&quad)?;
+        assert!(store.contains(&quad)?);
+        assert_eq!(
+            store
+                .quads_for_pattern(Some(Subject::from(nested).as_ref()), None, None, None)
+                .collect::<Result<Vec<_>, _>>()?,
+            vec![quad.clone()]
+        );
+        assert_eq!(
+            store
+                .quads_for_pattern(None, None, Some(Term::from(quoted).as_ref()), None)
+                .collect::<Result<Vec<_>, _>>()?,
+            vec![quad.clone()]
+        );
+        store.remove(&quad)?;
+        assert!(!store.contains(&quad)?);
+        assert_eq!(store.len(), 0);
+    }
+
+    remove_dir_all(&repo_path)?;
+    Ok(())
+}
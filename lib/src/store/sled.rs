@@ -1,13 +1,14 @@
 //! Store based on the [Sled](https://sled.rs/) key-value database.
 
 use crate::error::invalid_data_error;
-use crate::io::{DatasetFormat, GraphFormat};
+use crate::io::{DatasetParser, DatasetSerializer, GraphParser, GraphSerializer};
 use crate::model::*;
 use crate::sparql::{
-    evaluate_query, evaluate_update, EvaluationError, Query, QueryOptions, QueryResults, Update,
-    UpdateOptions,
+    evaluate_query, evaluate_update, explain_query, EvaluationError, Query, QueryExplanation,
+    QueryOptions, QueryResults, Update, UpdateOptions,
 };
 use crate::store::binary_encoder::*;
+use crate::store::bulk_load::{bulk_load_dataset, bulk_load_graph};
 use crate::store::numeric_encoder::{
     Decoder, ReadEncoder, StrContainer, StrEncodingAware, StrLookup, WriteEncoder,
 };
@@ -20,6 +21,7 @@ use sled::transaction::{
     UnabortableTransactionError,
 };
 use sled::{Config, Db, Iter, Tree};
+use std::collections::HashMap;
 use std::convert::TryInto;
 use std::error::Error;
 use std::io::{BufRead, Write};
@@ -109,7 +111,13 @@ impl SledStore {
             dosp: db.open_tree("dosp")?,
         };
 
-        let version = this.ensure_version()?;
+        let mut version = this.ensure_version()?;
+        if version == 0 {
+            migrate_from_v0(&this, &mut &this)?;
+            version = 1;
+            this.default.insert("oxversion", &version.to_be_bytes())?;
+            this.default.flush()?;
+        }
         if version != LATEST_STORAGE_VERSION {
             return Err(invalid_data_error(format!(
                 "The Sled database is still using the encoding version {}, please upgrade it",
@@ -151,12 +159,31 @@ impl SledStore {
         evaluate_query(self.clone(), query, options)
     }
 
+    /// Returns the plan used to evaluate a [SPARQL 1.1 query](https://www.w3.org/TR/sparql11-query/) without evaluating it.
+    ///
+    /// See [`MemoryStore`](super::memory::MemoryStore::explain()) for a usage example.
+    pub fn explain(
+        &self,
+        query: impl TryInto<Query, Error = impl Into<EvaluationError>>,
+    ) -> Result<QueryExplanation, EvaluationError> {
+        self.explain_opt(query, QueryOptions::default())
+    }
+
+    /// Returns the plan used to evaluate a [SPARQL 1.1 query](https://www.w3.org/TR/sparql11-query/) with some options without evaluating it.
+    pub fn explain_opt(
+        &self,
+        query: impl TryInto<Query, Error = impl Into<EvaluationError>>,
+        options: QueryOptions,
+    ) -> Result<QueryExplanation, EvaluationError> {
+        explain_query(self.clone(), query, options)
+    }
+
     /// Retrieves quads with a filter on each quad component
     ///
     /// See [`MemoryStore`](super::memory::MemoryStore::quads_for_pattern()) for a usage example.
     pub fn quads_for_pattern(
         &self,
-        subject: Option<NamedOrBlankNodeRef<'_>>,
+        subject: Option<SubjectRef<'_>>,
         predicate: Option<NamedNodeRef<'_>>,
         object: Option<TermRef<'_>>,
         graph_name: Option<GraphNameRef<'_>>,
@@ -295,6 +322,8 @@ impl SledStore {
     /// It might leave the store in a bad state if a crash happens during a triple insertion.
     /// Use a (memory greedy) [transaction](SledStore::transaction()) if you do not want that.
     ///
+    /// A [`GraphParser`] in [lenient mode](GraphParser::lenient()) could be given instead of a format in order to skip the invalid statements.
+    ///
     /// See [`MemoryStore`](super::memory::MemoryStore::load_graph()) for a usage example.
     ///
     /// Errors related to parameter validation like the base IRI use the [`InvalidInput`](std::io::ErrorKind::InvalidInput) error kind.
@@ -303,15 +332,37 @@ impl SledStore {
     pub fn load_graph<'a>(
         &self,
         reader: impl BufRead,
-        format: GraphFormat,
+        parser: impl Into<GraphParser>,
         to_graph_name: impl Into<GraphNameRef<'a>>,
         base_iri: Option<&str>,
     ) -> Result<(), io::Error> {
-        let mut this = self;
-        load_graph(&mut this, reader, format, to_graph_name.into(), base_iri)?;
+        self.load_graph_with_prefixes(reader, parser, to_graph_name, base_iri)?;
         Ok(())
     }
 
+    /// Loads a graph file (i.e. triples) into the store like [`load_graph`](SledStore::load_graph()) and returns the prefixes declared in it
+    ///
+    /// The prefixes are returned as a map from prefix name to prefix IRI.
+    /// Only the Turtle and TriG formats allow to declare prefixes.
+    ///
+    /// See [`MemoryStore`](super::memory::MemoryStore::load_graph_with_prefixes()) for a usage example.
+    pub fn load_graph_with_prefixes<'a>(
+        &self,
+        reader: impl BufRead,
+        parser: impl Into<GraphParser>,
+        to_graph_name: impl Into<GraphNameRef<'a>>,
+        base_iri: Option<&str>,
+    ) -> Result<HashMap<String, String>, io::Error> {
+        let mut this = self;
+        Ok(load_graph(
+            &mut this,
+            reader,
+            parser.into(),
+            to_graph_name.into(),
+            base_iri,
+        )?)
+    }
+
     /// Loads a dataset file (i.e. quads) into the store.
     ///
     /// Warning: This functions saves the triples in a not atomic way. If the parsing fails in the middle of the file,
@@ -320,6 +371,8 @@ impl SledStore {
     /// It might leave the store in a bad state if a crash happens during a quad insertion.
     /// Use a (memory greedy) [transaction](SledStore::transaction()) if you do not want that.
     ///
+    /// A [`DatasetParser`] in [lenient mode](DatasetParser::lenient()) could be given instead of a format in order to skip the invalid statements.
+    ///
     /// See [`MemoryStore`](super::memory::MemoryStore::load_dataset()) for a usage example.
     ///
     /// Errors related to parameter validation like the base IRI use the [`InvalidInput`](std::io::ErrorKind::InvalidInput) error kind.
@@ -328,11 +381,87 @@ impl SledStore {
     pub fn load_dataset(
         &self,
         reader: impl BufRead,
-        format: DatasetFormat,
+        parser: impl Into<DatasetParser>,
+        base_iri: Option<&str>,
+    ) -> Result<(), io::Error> {
+        self.load_dataset_with_prefixes(reader, parser, base_iri)?;
+        Ok(())
+    }
+
+    /// Loads a dataset file (i.e. quads) into the store like [`load_dataset`](SledStore::load_dataset()) and returns the prefixes declared in it
+    ///
+    /// The prefixes are returned as a map from prefix name to prefix IRI.
+    /// Only the Turtle and TriG formats allow to declare prefixes.
+    ///
+    /// See [`MemoryStore`](super::memory::MemoryStore::load_dataset_with_prefixes()) for a usage example.
+    pub fn load_dataset_with_prefixes(
+        &self,
+        reader: impl BufRead,
+        parser: impl Into<DatasetParser>,
+        base_iri: Option<&str>,
+    ) -> Result<HashMap<String, String>, io::Error> {
+        let mut this = self;
+        Ok(load_dataset(&mut this, reader, parser.into(), base_iri)?)
+    }
+
+    /// Loads a graph file (i.e. triples) into the store using all the available CPU cores.
+    ///
+    /// The [N-Triples](crate::io::GraphFormat::NTriples) files are split into chunks of lines
+    /// that are parsed in parallel. The blank nodes are kept consistent across the chunks of the file.
+    /// The other formats are loaded like with [`load_graph`](SledStore::load_graph()).
+    /// The files read with a [`LiteralValidator`](crate::model::LiteralValidator) are also loaded like with [`load_graph`](SledStore::load_graph()).
+    ///
+    /// Warning: This functions saves the triples in a not atomic way and in no particular order.
+    /// If the parsing fails in the middle of the file, only a part of it may be written to the store.
+    ///
+    /// A [`GraphParser`] in [lenient mode](GraphParser::lenient()) could be given instead of a format in order to skip the invalid statements.
+    /// The errors might then be reported out of order.
+    ///
+    /// Errors related to parameter validation like the base IRI use the [`InvalidInput`](std::io::ErrorKind::InvalidInput) error kind.
+    /// Errors related to a bad syntax in the loaded file use the [`InvalidData`](std::io::ErrorKind::InvalidData) or [`UnexpectedEof`](std::io::ErrorKind::UnexpectedEof) error kinds.
+    /// Errors related to data loading into the store use the other error kinds.
+    pub fn bulk_load_graph<'a>(
+        &self,
+        reader: impl BufRead,
+        parser: impl Into<GraphParser>,
+        to_graph_name: impl Into<GraphNameRef<'a>>,
+        base_iri: Option<&str>,
+    ) -> Result<(), io::Error> {
+        let mut this = self;
+        bulk_load_graph(
+            &mut this,
+            reader,
+            parser.into(),
+            to_graph_name.into(),
+            base_iri,
+        )?;
+        Ok(())
+    }
+
+    /// Loads a dataset file (i.e. quads) into the store using all the available CPU cores.
+    ///
+    /// The [N-Quads](crate::io::DatasetFormat::NQuads) files are split into chunks of lines
+    /// that are parsed in parallel. The blank nodes are kept consistent across the chunks of the file.
+    /// The other formats are loaded like with [`load_dataset`](SledStore::load_dataset()).
+    /// The files read with a [`LiteralValidator`](crate::model::LiteralValidator) are also loaded like with [`load_dataset`](SledStore::load_dataset()).
+    ///
+    /// Warning: This functions saves the quads in a not atomic way and in no particular order.
+    /// If the parsing fails in the middle of the file, only a part of it may be written to the store.
+    ///
+    /// A [`DatasetParser`] in [lenient mode](DatasetParser::lenient()) could be given instead of a format in order to skip the invalid statements.
+    /// The errors might then be reported out of order.
+    ///
+    /// Errors related to parameter validation like the base IRI use the [`InvalidInput`](std::io::ErrorKind::InvalidInput) error kind.
+    /// Errors related to a bad syntax in the loaded file use the [`InvalidData`](std::io::ErrorKind::InvalidData) or [`UnexpectedEof`](std::io::ErrorKind::UnexpectedEof) error kinds.
+    /// Errors related to data loading into the store use the other error kinds.
+    pub fn bulk_load_dataset(
+        &self,
+        reader: impl BufRead,
+        parser: impl Into<DatasetParser>,
         base_iri: Option<&str>,
     ) -> Result<(), io::Error> {
         let mut this = self;
-        load_dataset(&mut this, reader, format, base_iri)?;
+        bulk_load_dataset(&mut this, reader, parser.into(), base_iri)?;
         Ok(())
     }
 
@@ -367,22 +496,26 @@ impl SledStore {
     pub fn dump_graph<'a>(
         &self,
         writer: impl Write,
-        format: GraphFormat,
+        serializer: impl Into<GraphSerializer>,
         from_graph_name: impl Into<GraphNameRef<'a>>,
     ) -> Result<(), io::Error> {
         dump_graph(
             self.quads_for_pattern(None, None, None, Some(from_graph_name.into()))
                 .map(|q| Ok(q?.into())),
             writer,
-            format,
+            &serializer.into(),
         )
     }
 
     /// Dumps the store into a file.
     ///    
     /// See [`MemoryStore`](super::memory::MemoryStore::dump_dataset()) for a usage example.
-    pub fn dump_dataset(&self, writer: impl Write, format: DatasetFormat) -> Result<(), io::Error> {
-        dump_dataset(self.iter(), writer, format)
+    pub fn dump_dataset(
+        &self,
+        writer: impl Write,
+        serializer: impl Into<DatasetSerializer>,
+    ) -> Result<(), io::Error> {
+        dump_dataset(self.iter(), writer, &serializer.into())
     }
 
     /// Removes a graph from this store.
@@ -432,7 +565,7 @@ impl SledStore {
         )
     }
 
-    fn quads_for_subject(&self, subject: EncodedTerm) -> DecodingQuadsIterator {
+    fn quads_for_subject(&self, subject: &EncodedTerm) -> DecodingQuadsIterator {
         DecodingQuadsIterator::pair(
             self.dspo_quads(encode_term(subject)),
             self.spog_quads(encode_term(subject)),
@@ -441,8 +574,8 @@ impl SledStore {
 
     fn quads_for_subject_predicate(
         &self,
-        subject: EncodedTerm,
-        predicate: EncodedTerm,
+        subject: &EncodedTerm,
+        predicate: &EncodedTerm,
     ) -> DecodingQuadsIterator {
         DecodingQuadsIterator::pair(
             self.dspo_quads(encode_term_pair(subject, predicate)),
@@ -452,9 +585,9 @@ impl SledStore {
 
     fn quads_for_subject_predicate_object(
         &self,
-        subject: EncodedTerm,
-        predicate: EncodedTerm,
-        object: EncodedTerm,
+        subject: &EncodedTerm,
+        predicate: &EncodedTerm,
+        object: &EncodedTerm,
     ) -> DecodingQuadsIterator {
         DecodingQuadsIterator::pair(
             self.dspo_quads(encode_term_triple(subject, predicate, object)),
@@ -464,8 +597,8 @@ impl SledStore {
 
     fn quads_for_subject_object(
         &self,
-        subject: EncodedTerm,
-        object: EncodedTerm,
+        subject: &EncodedTerm,
+        object: &EncodedTerm,
     ) -> DecodingQuadsIterator {
         DecodingQuadsIterator::pair(
             self.dosp_quads(encode_term_pair(object, subject)),
@@ -473,7 +606,7 @@ impl SledStore {
         )
     }
 
-    fn quads_for_predicate(&self, predicate: EncodedTerm) -> DecodingQuadsIterator {
+    fn quads_for_predicate(&self, predicate: &EncodedTerm) -> DecodingQuadsIterator {
         DecodingQuadsIterator::pair(
             self.dpos_quads(encode_term(predicate)),
             self.posg_quads(encode_term(predicate)),
@@ -482,8 +615,8 @@ impl SledStore {
 
     fn quads_for_predicate_object(
         &self,
-        predicate: EncodedTerm,
-        object: EncodedTerm,
+        predicate: &EncodedTerm,
+        object: &EncodedTerm,
     ) -> DecodingQuadsIterator {
         DecodingQuadsIterator::pair(
             self.dpos_quads(encode_term_pair(predicate, object)),
@@ -491,14 +624,14 @@ impl SledStore {
         )
     }
 
-    fn quads_for_object(&self, object: EncodedTerm) -> DecodingQuadsIterator {
+    fn quads_for_object(&self, object: &EncodedTerm) -> DecodingQuadsIterator {
         DecodingQuadsIterator::pair(
             self.dosp_quads(encode_term(object)),
             self.ospg_quads(encode_term(object)),
         )
     }
 
-    fn quads_for_graph(&self, graph_name: EncodedTerm) -> DecodingQuadsIterator {
+    fn quads_for_graph(&self, graph_name: &EncodedTerm) -> DecodingQuadsIterator {
         DecodingQuadsIterator::new(if graph_name.is_default_graph() {
             self.dspo_quads(Vec::default())
         } else {
@@ -508,8 +641,8 @@ impl SledStore {
 
     fn quads_for_subject_graph(
         &self,
-        subject: EncodedTerm,
-        graph_name: EncodedTerm,
+        subject: &EncodedTerm,
+        graph_name: &EncodedTerm,
     ) -> DecodingQuadsIterator {
         DecodingQuadsIterator::new(if graph_name.is_default_graph() {
             self.dspo_quads(encode_term(subject))
@@ -520,9 +653,9 @@ impl SledStore {
 
     fn quads_for_subject_predicate_graph(
         &self,
-        subject: EncodedTerm,
-        predicate: EncodedTerm,
-        graph_name: EncodedTerm,
+        subject: &EncodedTerm,
+        predicate: &EncodedTerm,
+        graph_name: &EncodedTerm,
     ) -> DecodingQuadsIterator {
         DecodingQuadsIterator::new(if graph_name.is_default_graph() {
             self.dspo_quads(encode_term_pair(subject, predicate))
@@ -533,10 +666,10 @@ impl SledStore {
 
     fn quads_for_subject_predicate_object_graph(
         &self,
-        subject: EncodedTerm,
-        predicate: EncodedTerm,
-        object: EncodedTerm,
-        graph_name: EncodedTerm,
+        subject: &EncodedTerm,
+        predicate: &EncodedTerm,
+        object: &EncodedTerm,
+        graph_name: &EncodedTerm,
     ) -> DecodingQuadsIterator {
         DecodingQuadsIterator::new(if graph_name.is_default_graph() {
             self.dspo_quads(encode_term_triple(subject, predicate, object))
@@ -547,9 +680,9 @@ impl SledStore {
 
     fn quads_for_subject_object_graph(
         &self,
-        subject: EncodedTerm,
-        object: EncodedTerm,
-        graph_name: EncodedTerm,
+        subject: &EncodedTerm,
+        object: &EncodedTerm,
+        graph_name: &EncodedTerm,
     ) -> DecodingQuadsIterator {
         DecodingQuadsIterator::new(if graph_name.is_default_graph() {
             self.dosp_quads(encode_term_pair(object, subject))
@@ -560,8 +693,8 @@ impl SledStore {
 
     fn quads_for_predicate_graph(
         &self,
-        predicate: EncodedTerm,
-        graph_name: EncodedTerm,
+        predicate: &EncodedTerm,
+        graph_name: &EncodedTerm,
     ) -> DecodingQuadsIterator {
         DecodingQuadsIterator::new(if graph_name.is_default_graph() {
             self.dpos_quads(encode_term(predicate))
@@ -572,9 +705,9 @@ impl SledStore {
 
     fn quads_for_predicate_object_graph(
         &self,
-        predicate: EncodedTerm,
-        object: EncodedTerm,
-        graph_name: EncodedTerm,
+        predicate: &EncodedTerm,
+        object: &EncodedTerm,
+        graph_name: &EncodedTerm,
     ) -> DecodingQuadsIterator {
         DecodingQuadsIterator::new(if graph_name.is_default_graph() {
             self.dpos_quads(encode_term_pair(predicate, object))
@@ -585,8 +718,8 @@ impl SledStore {
 
     fn quads_for_object_graph(
         &self,
-        object: EncodedTerm,
-        graph_name: EncodedTerm,
+        object: &EncodedTerm,
+        graph_name: &EncodedTerm,
     ) -> DecodingQuadsIterator {
         DecodingQuadsIterator::new(if graph_name.is_default_graph() {
             self.dosp_quads(encode_term(object))
@@ -687,54 +820,54 @@ impl ReadableEncodedStore for SledStore {
         object: Option<EncodedTerm>,
         graph_name: Option<EncodedTerm>,
     ) -> DecodingQuadsIterator {
-        match subject {
-            Some(subject) => match predicate {
-                Some(predicate) => match object {
-                    Some(object) => match graph_name {
+        match &subject {
+            Some(subject) => match &predicate {
+                Some(predicate) => match &object {
+                    Some(object) => match &graph_name {
                         Some(graph_name) => self.quads_for_subject_predicate_object_graph(
                             subject, predicate, object, graph_name,
                         ),
                         None => self.quads_for_subject_predicate_object(subject, predicate, object),
                     },
-                    None => match graph_name {
+                    None => match &graph_name {
                         Some(graph_name) => {
                             self.quads_for_subject_predicate_graph(subject, predicate, graph_name)
                         }
                         None => self.quads_for_subject_predicate(subject, predicate),
                     },
                 },
-                None => match object {
-                    Some(object) => match graph_name {
+                None => match &object {
+                    Some(object) => match &graph_name {
                         Some(graph_name) => {
                             self.quads_for_subject_object_graph(subject, object, graph_name)
                         }
                         None => self.quads_for_subject_object(subject, object),
                     },
-                    None => match graph_name {
+                    None => match &graph_name {
                         Some(graph_name) => self.quads_for_subject_graph(subject, graph_name),
                         None => self.quads_for_subject(subject),
                     },
                 },
             },
-            None => match predicate {
-                Some(predicate) => match object {
-                    Some(object) => match graph_name {
+            None => match &predicate {
+                Some(predicate) => match &object {
+                    Some(object) => match &graph_name {
                         Some(graph_name) => {
                             self.quads_for_predicate_object_graph(predicate, object, graph_name)
                         }
                         None => self.quads_for_predicate_object(predicate, object),
                     },
-                    None => match graph_name {
+                    None => match &graph_name {
                         Some(graph_name) => self.quads_for_predicate_graph(predicate, graph_name),
                         None => self.quads_for_predicate(predicate),
                     },
                 },
-                None => match object {
-                    Some(object) => match graph_name {
+                None => match &object {
+                    Some(object) => match &graph_name {
                         Some(graph_name) => self.quads_for_object_graph(object, graph_name),
                         None => self.quads_for_object(object),
                     },
-                    None => match graph_name {
+                    None => match &graph_name {
                         Some(graph_name) => self.quads_for_graph(graph_name),
                         None => self.quads(),
                     },
@@ -874,12 +1007,18 @@ impl SledTransaction<'_> {
     pub fn load_graph<'a>(
         &self,
         reader: impl BufRead,
-        format: GraphFormat,
+        parser: impl Into<GraphParser>,
         to_graph_name: impl Into<GraphNameRef<'a>>,
         base_iri: Option<&str>,
     ) -> Result<(), SledUnabortableTransactionError> {
         let mut this = self;
-        load_graph(&mut this, reader, format, to_graph_name.into(), base_iri)?;
+        load_graph(
+            &mut this,
+            reader,
+            parser.into(),
+            to_graph_name.into(),
+            base_iri,
+        )?;
         Ok(())
     }
 
@@ -900,11 +1039,11 @@ impl SledTransaction<'_> {
     pub fn load_dataset(
         &self,
         reader: impl BufRead,
-        format: DatasetFormat,
+        parser: impl Into<DatasetParser>,
         base_iri: Option<&str>,
     ) -> Result<(), SledUnabortableTransactionError> {
         let mut this = self;
-        load_dataset(&mut this, reader, format, base_iri)?;
+        load_dataset(&mut this, reader, parser.into(), base_iri)?;
         Ok(())
     }
 
@@ -1296,7 +1435,7 @@ impl Iterator for SledQuadIter {
 fn store() -> Result<(), io::Error> {
     use crate::model::*;
 
-    let main_s = NamedOrBlankNode::from(BlankNode::default());
+    let main_s = Subject::from(BlankNode::default());
     let main_p = NamedNode::new("http://example.com").unwrap();
     let main_o = Term::from(Literal::from(1));
     let main_g = GraphName::from(BlankNode::default());
@@ -1489,3 +1628,34 @@ fn store() -> Result<(), io::Error> {
 
     Ok(())
 }
+
+#[test]
+fn quoted_triples() -> Result<(), io::Error> {
+    use crate::model::*;
+
+    let s = NamedNode::new("http://example.com/s").unwrap();
+    let p = NamedNode::new("http://example.com/p").unwrap();
+    let quoted = Triple::new(s.clone(), p.clone(), Literal::from(1));
+    let nested = Triple::new(quoted.clone(), p.clone(), BlankNode::default());
+    let quad = Quad::new(nested.clone(), p.clone(), quoted.clone(), None);
+
+    let store = SledStore::new()?;
+    store.insert(&quad)?;
+    assert!(store.contains(&quad)?);
+    assert_eq!(
+        store
+            .quads_for_pattern(Some(Subject::from(nested).as_ref()), None, None, None)
+            .collect::<Result<Vec<_>, _>>()?,
+        vec![quad.clone()]
+    );
+    assert_eq!(
+        store
+            .quads_for_pattern(None, None, Some(Term::from(quoted).as_ref()), None)
+            .collect::<Result<Vec<_>, _>>()?,
+        vec![quad.clone()]
+    );
+    store.remove(&quad)?;
+    assert!(!store.contains(&quad)?);
+    assert_eq!(store.len(), 0);
+    Ok(())
+}
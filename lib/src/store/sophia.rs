@@ -25,7 +25,10 @@ macro_rules! sparql_to_hashset {
         (|| -> Result<HashSet<Term>, EvaluationError> {
             if let QueryResults::Solutions(solutions) = $store.query($sparql)? {
                 solutions
-                    .map(|r| r.map(|v| v.get(0).unwrap().clone()))
+                    .filter_map(|r| {
+                        r.map(|v| Some(v.get(0).unwrap().clone()).filter(|t| !t.is_triple()))
+                            .transpose()
+                    })
                     .collect()
             } else {
                 unreachable!()
@@ -44,7 +47,7 @@ macro_rules! impl_dataset {
             fn quads(&self) -> DQuadSource<'_, Self> {
                 Box::new(
                     self.quads_for_pattern(None, None, None, None)
-                        .map($quad_map),
+                        .filter_map($quad_map),
                 )
             }
             fn quads_with_s<'s, TS>(&'s self, s: &'s TS) -> DQuadSource<'s, Self>
@@ -56,7 +59,10 @@ macro_rules! impl_dataset {
                 if s.is_none() {
                     Box::new(empty())
                 } else {
-                    Box::new(self.quads_for_pattern(s, None, None, None).map($quad_map))
+                    Box::new(
+                        self.quads_for_pattern(s, None, None, None)
+                            .filter_map($quad_map),
+                    )
                 }
             }
             fn quads_with_p<'s, TP>(&'s self, p: &'s TP) -> DQuadSource<'s, Self>
@@ -68,7 +74,10 @@ macro_rules! impl_dataset {
                 if p.is_none() {
                     Box::new(empty())
                 } else {
-                    Box::new(self.quads_for_pattern(None, p, None, None).map($quad_map))
+                    Box::new(
+                        self.quads_for_pattern(None, p, None, None)
+                            .filter_map($quad_map),
+                    )
                 }
             }
             fn quads_with_o<'s, TS>(&'s self, o: &'s TS) -> DQuadSource<'s, Self>
@@ -80,7 +89,10 @@ macro_rules! impl_dataset {
                 if o.is_none() {
                     Box::new(empty())
                 } else {
-                    Box::new(self.quads_for_pattern(None, None, o, None).map($quad_map))
+                    Box::new(
+                        self.quads_for_pattern(None, None, o, None)
+                            .filter_map($quad_map),
+                    )
                 }
             }
             fn quads_with_g<'s, TS>(&'s self, g: Option<&'s TS>) -> DQuadSource<'s, Self>
@@ -92,7 +104,10 @@ macro_rules! impl_dataset {
                 if g.is_none() {
                     Box::new(empty())
                 } else {
-                    Box::new(self.quads_for_pattern(None, None, None, g).map($quad_map))
+                    Box::new(
+                        self.quads_for_pattern(None, None, None, g)
+                            .filter_map($quad_map),
+                    )
                 }
             }
             fn quads_with_sp<'s, TS, TP>(&'s self, s: &'s TS, p: &'s TP) -> DQuadSource<'s, Self>
@@ -107,7 +122,10 @@ macro_rules! impl_dataset {
                 if s.is_none() || p.is_none() {
                     Box::new(empty())
                 } else {
-                    Box::new(self.quads_for_pattern(s, p, None, None).map($quad_map))
+                    Box::new(
+                        self.quads_for_pattern(s, p, None, None)
+                            .filter_map($quad_map),
+                    )
                 }
             }
             fn quads_with_so<'s, TS, TO>(&'s self, s: &'s TS, o: &'s TO) -> DQuadSource<'s, Self>
@@ -122,7 +140,10 @@ macro_rules! impl_dataset {
                 if s.is_none() || o.is_none() {
                     Box::new(empty())
                 } else {
-                    Box::new(self.quads_for_pattern(s, None, o, None).map($quad_map))
+                    Box::new(
+                        self.quads_for_pattern(s, None, o, None)
+                            .filter_map($quad_map),
+                    )
                 }
             }
             fn quads_with_sg<'s, TS, TG>(
@@ -141,7 +162,10 @@ macro_rules! impl_dataset {
                 if s.is_none() || g.is_none() {
                     Box::new(empty())
                 } else {
-                    Box::new(self.quads_for_pattern(s, None, None, g).map($quad_map))
+                    Box::new(
+                        self.quads_for_pattern(s, None, None, g)
+                            .filter_map($quad_map),
+                    )
                 }
             }
             fn quads_with_po<'s, TP, TO>(&'s self, p: &'s TP, o: &'s TO) -> DQuadSource<'s, Self>
@@ -156,7 +180,10 @@ macro_rules! impl_dataset {
                 if p.is_none() || o.is_none() {
                     Box::new(empty())
                 } else {
-                    Box::new(self.quads_for_pattern(None, p, o, None).map($quad_map))
+                    Box::new(
+                        self.quads_for_pattern(None, p, o, None)
+                            .filter_map($quad_map),
+                    )
                 }
             }
             fn quads_with_pg<'s, TP, TG>(
@@ -175,7 +202,10 @@ macro_rules! impl_dataset {
                 if p.is_none() || g.is_none() {
                     Box::new(empty())
                 } else {
-                    Box::new(self.quads_for_pattern(None, p, None, g).map($quad_map))
+                    Box::new(
+                        self.quads_for_pattern(None, p, None, g)
+                            .filter_map($quad_map),
+                    )
                 }
             }
             fn quads_with_og<'s, TO, TG>(
@@ -194,7 +224,10 @@ macro_rules! impl_dataset {
                 if o.is_none() || g.is_none() {
                     Box::new(empty())
                 } else {
-                    Box::new(self.quads_for_pattern(None, None, o, g).map($quad_map))
+                    Box::new(
+                        self.quads_for_pattern(None, None, o, g)
+                            .filter_map($quad_map),
+                    )
                 }
             }
             fn quads_with_spo<'s, TS, TP, TO>(
@@ -217,7 +250,7 @@ macro_rules! impl_dataset {
                 if s.is_none() || p.is_none() || o.is_none() {
                     Box::new(empty())
                 } else {
-                    Box::new(self.quads_for_pattern(s, p, o, None).map($quad_map))
+                    Box::new(self.quads_for_pattern(s, p, o, None).filter_map($quad_map))
                 }
             }
             fn quads_with_spg<'s, TS, TP, TG>(
@@ -240,7 +273,7 @@ macro_rules! impl_dataset {
                 if s.is_none() || p.is_none() || g.is_none() {
                     Box::new(empty())
                 } else {
-                    Box::new(self.quads_for_pattern(s, p, None, g).map($quad_map))
+                    Box::new(self.quads_for_pattern(s, p, None, g).filter_map($quad_map))
                 }
             }
             fn quads_with_sog<'s, TS, TO, TG>(
@@ -263,7 +296,7 @@ macro_rules! impl_dataset {
                 if s.is_none() || o.is_none() || g.is_none() {
                     Box::new(empty())
                 } else {
-                    Box::new(self.quads_for_pattern(s, None, o, g).map($quad_map))
+                    Box::new(self.quads_for_pattern(s, None, o, g).filter_map($quad_map))
                 }
             }
             fn quads_with_pog<'s, TP, TO, TG>(
@@ -286,7 +319,7 @@ macro_rules! impl_dataset {
                 if p.is_none() || o.is_none() || g.is_none() {
                     Box::new(empty())
                 } else {
-                    Box::new(self.quads_for_pattern(None, p, o, g).map($quad_map))
+                    Box::new(self.quads_for_pattern(None, p, o, g).filter_map($quad_map))
                 }
             }
             fn quads_with_spog<'s, TS, TP, TO, TG>(
@@ -313,7 +346,7 @@ macro_rules! impl_dataset {
                 if s.is_none() || p.is_none() || o.is_none() || g.is_none() {
                     Box::new(empty())
                 } else {
-                    Box::new(self.quads_for_pattern(s, p, o, g).map($quad_map))
+                    Box::new(self.quads_for_pattern(s, p, o, g).filter_map($quad_map))
                 }
             }
             fn subjects(&self) -> DResultTermSet<Self>
@@ -484,16 +517,31 @@ mod memory {
 
     #[cfg(test)]
     sophia_api::test_dataset_impl!(test, MemoryStore, false, false);
+
+    #[test]
+    fn quoted_triples_are_skipped() -> Result<(), Infallible> {
+        let ex = NamedNode::new_unchecked("http://example.com");
+        let quoted = Triple::new(ex.clone(), ex.clone(), ex.clone());
+        let store = MemoryStore::new();
+        store.insert(Quad::new(ex.clone(), ex.clone(), ex.clone(), None));
+        store.insert(Quad::new(quoted.clone(), ex.clone(), ex.clone(), None));
+        store.insert(Quad::new(ex.clone(), ex.clone(), quoted, None));
+        assert_eq!(Dataset::quads(&store).count(), 1);
+        assert_eq!(store.quads_with_p(&ex).count(), 1);
+        assert_eq!(store.subjects()?.len(), 1);
+        assert_eq!(store.objects()?.len(), 1);
+        Ok(())
+    }
 }
 
 #[cfg(feature = "sled")]
 mod sled {
     use super::*;
 
-    impl_dataset!(SledStore, std::io::Error, io_quad_map, io_err_map);
+    impl_dataset!(SledStore, io::Error, io_quad_map, io_err_map);
 
     impl MutableDataset for SledStore {
-        type MutationError = std::io::Error;
+        type MutationError = io::Error;
         fn insert<TS, TP, TO, TG>(
             &mut self,
             s: &TS,
@@ -563,10 +611,10 @@ mod sled {
 #[cfg(feature = "rocksdb")]
 mod rocksdb {
     use super::*;
-    impl_dataset!(RocksDbStore, std::io::Error, io_quad_map, io_err_map);
+    impl_dataset!(RocksDbStore, io::Error, io_quad_map, io_err_map);
 
     impl MutableDataset for RocksDbStore {
-        type MutationError = std::io::Error;
+        type MutationError = io::Error;
         fn insert<TS, TP, TO, TG>(
             &mut self,
             s: &TS,
@@ -621,9 +669,12 @@ mod rocksdb {
 
 // helper functions
 
-fn infallible_quad_map<'a>(q: Quad) -> Result<StreamedSophiaQuad<'a>, Infallible> {
+fn infallible_quad_map<'a>(q: Quad) -> Option<Result<StreamedSophiaQuad<'a>, Infallible>> {
+    if is_quoting(&q) {
+        return None;
+    }
     let q: SophiaQuad = q.into();
-    Ok(StreamedQuad::by_value(q))
+    Some(Ok(StreamedQuad::by_value(q)))
 }
 
 fn infallible_err_map(_: EvaluationError) -> Infallible {
@@ -632,23 +683,31 @@ fn infallible_err_map(_: EvaluationError) -> Infallible {
 
 #[cfg(any(feature = "rocksdb", feature = "sled"))]
 fn io_quad_map<'a>(
-    res: Result<Quad, std::io::Error>,
-) -> Result<StreamedSophiaQuad<'a>, std::io::Error> {
-    res.map(|q| {
-        let q: SophiaQuad = q.into();
-        StreamedQuad::by_value(q)
-    })
+    res: Result<Quad, io::Error>,
+) -> Option<Result<StreamedSophiaQuad<'a>, io::Error>> {
+    match res {
+        Ok(q) if is_quoting(&q) => None,
+        res => Some(res.map(|q| {
+            let q: SophiaQuad = q.into();
+            StreamedQuad::by_value(q)
+        })),
+    }
+}
+
+/// Quads using quoted triples are skipped because Sophia does not support RDF-star yet
+fn is_quoting(quad: &Quad) -> bool {
+    quad.subject.is_triple() || quad.object.is_triple()
 }
 
 #[cfg(any(feature = "rocksdb", feature = "sled"))]
-fn io_err_map(err: EvaluationError) -> std::io::Error {
+fn io_err_map(err: EvaluationError) -> io::Error {
     match err {
         EvaluationError::Io(err) => err,
         _ => panic!("Unexpected error"),
     }
 }
 
-fn convert_subject<'a, T>(term: &'a T, buffer: &'a mut String) -> Option<NamedOrBlankNodeRef<'a>>
+fn convert_subject<'a, T>(term: &'a T, buffer: &'a mut String) -> Option<SubjectRef<'a>>
 where
     T: TTerm + ?Sized + 'a,
 {
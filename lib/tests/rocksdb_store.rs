@@ -1,4 +1,4 @@
-#![cfg(features = "rocksdb")]
+#![cfg(feature = "rocksdb")]
 
 use oxigraph::model::vocab::{rdf, xsd};
 use oxigraph::model::*;
@@ -78,7 +78,7 @@ fn test_backward_compatibility() -> io::Result<()> {
 
 fn reset_dir(dir: &str) -> io::Result<()> {
     assert!(Command::new("git")
-        .args(&["checkout", "HEAD", "--", dir])
+        .args(["checkout", "HEAD", "--", dir])
         .status()?
         .success());
     Ok(())
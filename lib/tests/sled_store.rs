@@ -1,4 +1,4 @@
-#![cfg(features = "sled")]
+#![cfg(feature = "sled")]
 
 use oxigraph::io::{DatasetFormat, GraphFormat};
 use oxigraph::model::vocab::{rdf, xsd};
@@ -86,6 +86,19 @@ fn test_load_graph() -> io::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_load_graph_with_prefixes() -> io::Result<()> {
+    let store = SledStore::new()?;
+    let prefixes =
+        store.load_graph_with_prefixes(Cursor::new(DATA), GraphFormat::Turtle, None, None)?;
+    assert_eq!(prefixes.len(), 3);
+    assert_eq!(prefixes["schema"], "http://schema.org/");
+    for q in quads(GraphNameRef::DefaultGraph) {
+        assert!(store.contains(q)?);
+    }
+    Ok(())
+}
+
 #[test]
 fn test_load_dataset() -> io::Result<()> {
     let store = SledStore::new()?;
@@ -174,7 +187,11 @@ fn test_backward_compatibility() -> io::Result<()> {
 
 fn reset_dir(dir: &str) -> io::Result<()> {
     assert!(Command::new("git")
-        .args(&["checkout", "HEAD", "--", dir])
+        .args(["checkout", "HEAD", "--", dir])
+        .status()?
+        .success());
+    assert!(Command::new("git")
+        .args(["clean", "-f", "--", dir])
         .status()?
         .success());
     Ok(())
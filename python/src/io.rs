@@ -145,7 +145,7 @@ pub fn serialize(input: &PyAny, output: PyObject, mime_type: &str) -> PyResult<(
     }
 }
 
-#[pyclass(name = "TripleReader", module = "oxigraph")]
+#[pyclass(unsendable, name = "TripleReader", module = "oxigraph")]
 pub struct PyTripleReader {
     inner: TripleReader<BufReader<PyFileLike>>,
 }
@@ -164,7 +164,7 @@ impl PyIterProtocol for PyTripleReader {
     }
 }
 
-#[pyclass(name = "QuadReader", module = "oxigraph")]
+#[pyclass(unsendable, name = "QuadReader", module = "oxigraph")]
 pub struct PyQuadReader {
     inner: QuadReader<BufReader<PyFileLike>>,
 }
@@ -45,6 +45,12 @@ impl From<PyNamedNode> for NamedOrBlankNode {
     }
 }
 
+impl From<PyNamedNode> for Subject {
+    fn from(node: PyNamedNode) -> Self {
+        node.inner.into()
+    }
+}
+
 impl From<PyNamedNode> for Term {
     fn from(node: PyNamedNode) -> Self {
         node.inner.into()
@@ -144,6 +150,12 @@ impl From<PyBlankNode> for NamedOrBlankNode {
     }
 }
 
+impl From<PyBlankNode> for Subject {
+    fn from(node: PyBlankNode) -> Self {
+        node.inner.into()
+    }
+}
+
 impl From<PyBlankNode> for Term {
     fn from(node: PyBlankNode) -> Self {
         node.inner.into()
@@ -419,11 +431,29 @@ impl From<PyNamedOrBlankNode> for NamedOrBlankNode {
     }
 }
 
+#[derive(FromPyObject)]
+pub enum PySubject {
+    NamedNode(PyNamedNode),
+    BlankNode(PyBlankNode),
+    Triple(PyTriple),
+}
+
+impl From<PySubject> for Subject {
+    fn from(node: PySubject) -> Self {
+        match node {
+            PySubject::NamedNode(node) => node.into(),
+            PySubject::BlankNode(node) => node.into(),
+            PySubject::Triple(triple) => triple.into(),
+        }
+    }
+}
+
 #[derive(FromPyObject)]
 enum PyTerm {
     NamedNode(PyNamedNode),
     BlankNode(PyBlankNode),
     Literal(PyLiteral),
+    Triple(PyTriple),
 }
 
 impl From<PyTerm> for Term {
@@ -432,6 +462,7 @@ impl From<PyTerm> for Term {
             PyTerm::NamedNode(node) => node.into(),
             PyTerm::BlankNode(node) => node.into(),
             PyTerm::Literal(literal) => literal.into(),
+            PyTerm::Triple(triple) => triple.into(),
         }
     }
 }
@@ -439,11 +470,11 @@ impl From<PyTerm> for Term {
 /// An RDF `triple <https://www.w3.org/TR/rdf11-concepts/#dfn-rdf-triple>`_
 ///
 /// :param subject: the triple subject
-/// :type subject: NamedNode or BlankNode
+/// :type subject: NamedNode or BlankNode or Triple
 /// :param predicate: the triple predicate
 /// :type predicate: NamedNode
 /// :param object: the triple object
-/// :type object: NamedNode or BlankNode or Literal
+/// :type object: NamedNode or BlankNode or Literal or Triple
 ///
 /// The :py:func:`str` function provides a serialization compatible with NTriples, Turtle and SPARQL:
 ///
@@ -472,6 +503,18 @@ impl From<PyTriple> for Triple {
     }
 }
 
+impl From<PyTriple> for Subject {
+    fn from(node: PyTriple) -> Self {
+        node.inner.into()
+    }
+}
+
+impl From<PyTriple> for Term {
+    fn from(node: PyTriple) -> Self {
+        node.inner.into()
+    }
+}
+
 impl<'a> From<&'a PyTriple> for TripleRef<'a> {
     fn from(node: &'a PyTriple) -> Self {
         node.inner.as_ref()
@@ -481,18 +524,18 @@ impl<'a> From<&'a PyTriple> for TripleRef<'a> {
 #[pymethods]
 impl PyTriple {
     #[new]
-    fn new(subject: PyNamedOrBlankNode, predicate: PyNamedNode, object: PyTerm) -> Self {
+    fn new(subject: PySubject, predicate: PyNamedNode, object: PyTerm) -> Self {
         Triple::new(subject, predicate, object).into()
     }
 
     /// :return: the triple subject
-    /// :rtype: NamedNode or BlankNode
+    /// :rtype: NamedNode or BlankNode or Triple
     ///
     /// >>> Triple(NamedNode('http://example.com'), NamedNode('http://example.com/p'), Literal('1')).subject
     /// <NamedNode value=http://example.com>
     #[getter]
     fn subject(&self, py: Python<'_>) -> PyObject {
-        named_or_blank_node_to_python(py, self.inner.subject.clone())
+        subject_to_python(py, self.inner.subject.clone())
     }
 
     /// :return: the triple predicate
@@ -506,7 +549,7 @@ impl PyTriple {
     }
 
     /// :return: the triple object
-    /// :rtype: NamedNode or BlankNode or Literal
+    /// :rtype: NamedNode or BlankNode or Literal or Triple
     ///
     /// >>> Triple(NamedNode('http://example.com'), NamedNode('http://example.com/p'), Literal('1')).object
     /// <Literal value=1 datatype=<NamedNode value=http://www.w3.org/2001/XMLSchema#string>>
@@ -524,13 +567,7 @@ impl PyObjectProtocol for PyTriple {
 
     fn __repr__(&self) -> String {
         let mut buffer = String::new();
-        buffer.push_str("<Triple subject=");
-        term_repr(self.inner.subject.as_ref().into(), &mut buffer);
-        buffer.push_str(" predicate=");
-        named_node_repr(self.inner.predicate.as_ref(), &mut buffer);
-        buffer.push_str(" object=");
-        term_repr(self.inner.object.as_ref(), &mut buffer);
-        buffer.push('>');
+        triple_repr(self.inner.as_ref(), &mut buffer);
         buffer
     }
 
@@ -552,10 +589,7 @@ impl PyMappingProtocol<'p> for PyTriple {
     fn __getitem__(&self, input: usize) -> PyResult<PyObject> {
         let gil = Python::acquire_gil();
         match input {
-            0 => Ok(named_or_blank_node_to_python(
-                gil.python(),
-                self.inner.subject.clone(),
-            )),
+            0 => Ok(subject_to_python(gil.python(), self.inner.subject.clone())),
             1 => Ok(PyNamedNode::from(self.inner.predicate.clone()).into_py(gil.python())),
             2 => Ok(term_to_python(gil.python(), self.inner.object.clone())),
             _ => Err(PyIndexError::new_err("A triple has only 3 elements")),
@@ -597,11 +631,11 @@ impl From<PyGraphName> for GraphName {
 /// in a `RDF dataset <https://www.w3.org/TR/rdf11-concepts/#dfn-rdf-dataset>`_
 ///
 /// :param subject: the quad subject
-/// :type subject: NamedNode or BlankNode
+/// :type subject: NamedNode or BlankNode or Triple
 /// :param predicate: the quad predicate
 /// :type predicate: NamedNode
 /// :param object: the quad object
-/// :type object: NamedNode or BlankNode or Literal
+/// :type object: NamedNode or BlankNode or Literal or Triple
 /// :param graph: the quad graph name. If not present, the default graph is assumed.
 /// :type graph: NamedNode or BlankNode or DefaultGraph or None, optional
 ///
@@ -645,7 +679,7 @@ impl<'a> From<&'a PyQuad> for QuadRef<'a> {
 impl PyQuad {
     #[new]
     fn new(
-        subject: PyNamedOrBlankNode,
+        subject: PySubject,
         predicate: PyNamedNode,
         object: PyTerm,
         graph_name: Option<PyGraphName>,
@@ -660,13 +694,13 @@ impl PyQuad {
     }
 
     /// :return: the quad subject
-    /// :rtype: NamedNode or BlankNode
+    /// :rtype: NamedNode or BlankNode or Triple
     ///
     /// >>> Quad(NamedNode('http://example.com'), NamedNode('http://example.com/p'), Literal('1'), NamedNode('http://example.com/g')).subject
     /// <NamedNode value=http://example.com>
     #[getter]
     fn subject(&self, py: Python<'_>) -> PyObject {
-        named_or_blank_node_to_python(py, self.inner.subject.clone())
+        subject_to_python(py, self.inner.subject.clone())
     }
 
     /// :return: the quad predicate
@@ -680,7 +714,7 @@ impl PyQuad {
     }
 
     /// :return: the quad object
-    /// :rtype: NamedNode or BlankNode or Literal
+    /// :rtype: NamedNode or BlankNode or Literal or Triple
     ///
     /// >>> Quad(NamedNode('http://example.com'), NamedNode('http://example.com/p'), Literal('1'), NamedNode('http://example.com/g')).object
     /// <Literal value=1 datatype=<NamedNode value=http://www.w3.org/2001/XMLSchema#string>>
@@ -748,10 +782,7 @@ impl PyMappingProtocol<'p> for PyQuad {
     fn __getitem__(&self, input: usize) -> PyResult<PyObject> {
         let gil = Python::acquire_gil();
         match input {
-            0 => Ok(named_or_blank_node_to_python(
-                gil.python(),
-                self.inner.subject.clone(),
-            )),
+            0 => Ok(subject_to_python(gil.python(), self.inner.subject.clone())),
             1 => Ok(PyNamedNode::from(self.inner.predicate.clone()).into_py(gil.python())),
             2 => Ok(term_to_python(gil.python(), self.inner.object.clone())),
             3 => Ok(graph_name_to_python(
@@ -879,21 +910,23 @@ impl<'a> TryFrom<&'a PyAny> for PyNamedNodeRef<'a> {
     }
 }
 
-pub enum PyNamedOrBlankNodeRef<'a> {
+pub enum PySubjectRef<'a> {
     NamedNode(PyRef<'a, PyNamedNode>),
     BlankNode(PyRef<'a, PyBlankNode>),
+    Triple(PyRef<'a, PyTriple>),
 }
 
-impl<'a> From<&'a PyNamedOrBlankNodeRef<'a>> for NamedOrBlankNodeRef<'a> {
-    fn from(value: &'a PyNamedOrBlankNodeRef<'a>) -> Self {
+impl<'a> From<&'a PySubjectRef<'a>> for SubjectRef<'a> {
+    fn from(value: &'a PySubjectRef<'a>) -> Self {
         match value {
-            PyNamedOrBlankNodeRef::NamedNode(value) => value.inner.as_ref().into(),
-            PyNamedOrBlankNodeRef::BlankNode(value) => value.inner.as_ref().into(),
+            PySubjectRef::NamedNode(value) => value.inner.as_ref().into(),
+            PySubjectRef::BlankNode(value) => value.inner.as_ref().into(),
+            PySubjectRef::Triple(value) => (&value.inner).into(),
         }
     }
 }
 
-impl<'a> TryFrom<&'a PyAny> for PyNamedOrBlankNodeRef<'a> {
+impl<'a> TryFrom<&'a PyAny> for PySubjectRef<'a> {
     type Error = PyErr;
 
     fn try_from(value: &'a PyAny) -> PyResult<Self> {
@@ -901,19 +934,22 @@ impl<'a> TryFrom<&'a PyAny> for PyNamedOrBlankNodeRef<'a> {
             Ok(Self::NamedNode(node.borrow()))
         } else if let Ok(node) = value.downcast::<PyCell<PyBlankNode>>() {
             Ok(Self::BlankNode(node.borrow()))
+        } else if let Ok(node) = value.downcast::<PyCell<PyTriple>>() {
+            Ok(Self::Triple(node.borrow()))
         } else {
             Err(PyTypeError::new_err(format!(
-                "{} is not an RDF named or blank node",
+                "{} is not an RDF named or blank node or triple",
                 value.get_type().name()?,
             )))
         }
     }
 }
 
-pub fn named_or_blank_node_to_python(py: Python<'_>, node: NamedOrBlankNode) -> PyObject {
+pub fn subject_to_python(py: Python<'_>, node: Subject) -> PyObject {
     match node {
-        NamedOrBlankNode::NamedNode(node) => PyNamedNode::from(node).into_py(py),
-        NamedOrBlankNode::BlankNode(node) => PyBlankNode::from(node).into_py(py),
+        Subject::NamedNode(node) => PyNamedNode::from(node).into_py(py),
+        Subject::BlankNode(node) => PyBlankNode::from(node).into_py(py),
+        Subject::Triple(triple) => PyTriple::from(*triple).into_py(py),
     }
 }
 
@@ -921,6 +957,7 @@ pub enum PyTermRef<'a> {
     NamedNode(PyRef<'a, PyNamedNode>),
     BlankNode(PyRef<'a, PyBlankNode>),
     Literal(PyRef<'a, PyLiteral>),
+    Triple(PyRef<'a, PyTriple>),
 }
 
 impl<'a> From<&'a PyTermRef<'a>> for TermRef<'a> {
@@ -929,6 +966,7 @@ impl<'a> From<&'a PyTermRef<'a>> for TermRef<'a> {
             PyTermRef::NamedNode(value) => value.inner.as_ref().into(),
             PyTermRef::BlankNode(value) => value.inner.as_ref().into(),
             PyTermRef::Literal(value) => value.inner.as_ref().into(),
+            PyTermRef::Triple(value) => (&value.inner).into(),
         }
     }
 }
@@ -949,6 +987,8 @@ impl<'a> TryFrom<&'a PyAny> for PyTermRef<'a> {
             Ok(Self::BlankNode(node.borrow()))
         } else if let Ok(node) = value.downcast::<PyCell<PyLiteral>>() {
             Ok(Self::Literal(node.borrow()))
+        } else if let Ok(node) = value.downcast::<PyCell<PyTriple>>() {
+            Ok(Self::Triple(node.borrow()))
         } else {
             Err(PyTypeError::new_err(format!(
                 "{} is not an RDF term",
@@ -963,6 +1003,7 @@ pub fn term_to_python(py: Python<'_>, term: Term) -> PyObject {
         Term::NamedNode(node) => PyNamedNode::from(node).into_py(py),
         Term::BlankNode(node) => PyBlankNode::from(node).into_py(py),
         Term::Literal(literal) => PyLiteral::from(literal).into_py(py),
+        Term::Triple(triple) => PyTriple::from(*triple).into_py(py),
     }
 }
 
@@ -1082,9 +1123,20 @@ pub fn term_repr(term: TermRef<'_>, buffer: &mut String) {
         TermRef::NamedNode(node) => named_node_repr(node, buffer),
         TermRef::BlankNode(node) => blank_node_repr(node, buffer),
         TermRef::Literal(literal) => literal_repr(literal, buffer),
+        TermRef::Triple(triple) => triple_repr(triple.as_ref(), buffer),
     }
 }
 
+fn triple_repr(triple: TripleRef<'_>, buffer: &mut String) {
+    buffer.push_str("<Triple subject=");
+    term_repr(triple.subject.into(), buffer);
+    buffer.push_str(" predicate=");
+    named_node_repr(triple.predicate, buffer);
+    buffer.push_str(" object=");
+    term_repr(triple.object, buffer);
+    buffer.push('>');
+}
+
 fn graph_name_repr(term: GraphNameRef<'_>, buffer: &mut String) {
     match term {
         GraphNameRef::NamedNode(node) => named_node_repr(node, buffer),
@@ -85,11 +85,11 @@ impl PySledStore {
     /// Looks for the quads matching a given pattern
     ///
     /// :param subject: the quad subject or :py:const:`None` to match everything.
-    /// :type subject: NamedNode or BlankNode or None
+    /// :type subject: NamedNode or BlankNode or Triple or None
     /// :param predicate: the quad predicate or :py:const:`None` to match everything.
     /// :type predicate: NamedNode or None
     /// :param object: the quad object or :py:const:`None` to match everything.
-    /// :type object: NamedNode or BlankNode or Literal or None
+    /// :type object: NamedNode or BlankNode or Literal or Triple or None
     /// :param graph: the quad graph name. To match only the default graph, use :py:class:`DefaultGraph`. To match everything use :py:const:`None`.
     /// :type graph: NamedNode or BlankNode or DefaultGraph or None
     /// :return: an iterator of the quads matching the pattern
@@ -10,7 +10,7 @@ pub fn extract_quads_pattern<'a>(
     object: &'a PyAny,
     graph_name: Option<&'a PyAny>,
 ) -> PyResult<(
-    Option<PyNamedOrBlankNodeRef<'a>>,
+    Option<PySubjectRef<'a>>,
     Option<PyNamedNodeRef<'a>>,
     Option<PyTermRef<'a>>,
     Option<PyGraphNameRef<'a>>,
@@ -16,18 +16,22 @@ use async_std::net::{TcpListener, TcpStream};
 use async_std::prelude::*;
 use async_std::task::{block_on, spawn};
 use http_types::{
-    bail_status, headers, Body, Error, Method, Mime, Request, Response, Result, StatusCode,
+    bail_status, format_err_status, headers, Body, Error, Method, Mime, Request, Response, Result,
+    StatusCode,
 };
-use oxigraph::io::{DatasetFormat, GraphFormat};
+use oxigraph::io::{Compression, DatasetFormat, DatasetParser, GraphFormat, GraphParser};
 use oxigraph::model::{GraphName, NamedNode, NamedOrBlankNode};
 use oxigraph::sparql::algebra::GraphUpdateOperation;
-use oxigraph::sparql::{Query, QueryResults, QueryResultsFormat, Update};
+use oxigraph::sparql::{
+    EvaluationError, Query, QueryOptions, QueryResults, QueryResultsFormat, Update,
+};
 #[cfg(feature = "rocksdb")]
 use oxigraph::RocksDbStore as Store;
 #[cfg(all(feature = "sled", not(feature = "rocksdb")))]
 use oxigraph::SledStore as Store;
 use std::io::BufReader;
 use std::str::FromStr;
+use std::time::Duration;
 use url::form_urlencoded;
 
 const MAX_SPARQL_BODY_SIZE: u64 = 1_048_576;
@@ -45,21 +49,30 @@ struct Args {
     /// directory in which persist the data
     #[argh(option, short = 'f')]
     file: String,
+
+    /// maximal duration in seconds of the evaluation of a SPARQL query or update
+    #[argh(option, short = 't')]
+    timeout: Option<u64>,
 }
 
 #[async_std::main]
 pub async fn main() -> Result<()> {
     let args: Args = argh::from_env();
     let store = Store::open(args.file)?;
+    let timeout = args.timeout.map(Duration::from_secs);
 
     println!("Listening for requests at http://{}", &args.bind);
     http_server(&args.bind, move |request| {
-        handle_request(request, store.clone())
+        handle_request(request, store.clone(), timeout)
     })
     .await
 }
 
-async fn handle_request(request: Request, store: Store) -> Result<Response> {
+async fn handle_request(
+    request: Request,
+    store: Store,
+    timeout: Option<Duration>,
+) -> Result<Response> {
     let mut response = match (request.url().path(), request.method()) {
         ("/", Method::Get) => {
             let mut response = Response::new(StatusCode::Ok);
@@ -75,16 +88,30 @@ async fn handle_request(request: Request, store: Store) -> Result<Response> {
         }
         ("/", Method::Post) => {
             if let Some(content_type) = request.content_type() {
+                let compression = match content_encoding_compression(&request) {
+                    Ok(compression) => compression,
+                    Err(message) => {
+                        return Ok(simple_response(StatusCode::UnsupportedMediaType, message))
+                    }
+                };
                 match if let Some(format) = GraphFormat::from_media_type(content_type.essence()) {
+                    let mut parser = GraphParser::from_format(format);
+                    if let Some(compression) = compression {
+                        parser = parser.with_compression(compression);
+                    }
                     store.load_graph(
                         BufReader::new(SyncAsyncReader::from(request)),
-                        format,
+                        parser,
                         &GraphName::DefaultGraph,
                         None,
                     )
                 } else if let Some(format) = DatasetFormat::from_media_type(content_type.essence())
                 {
-                    store.load_dataset(BufReader::new(SyncAsyncReader::from(request)), format, None)
+                    let mut parser = DatasetParser::from_format(format);
+                    if let Some(compression) = compression {
+                        parser = parser.with_compression(compression);
+                    }
+                    store.load_dataset(BufReader::new(SyncAsyncReader::from(request)), parser, None)
                 } else {
                     return Ok(simple_response(
                         StatusCode::UnsupportedMediaType,
@@ -101,7 +128,7 @@ async fn handle_request(request: Request, store: Store) -> Result<Response> {
             }
         }
         ("/query", Method::Get) => {
-            configure_and_evaluate_sparql_query(store, url_query(&request), None, request)?
+            configure_and_evaluate_sparql_query(store, url_query(&request), None, request, timeout)?
         }
         ("/query", Method::Post) => {
             if let Some(content_type) = request.content_type() {
@@ -118,6 +145,7 @@ async fn handle_request(request: Request, store: Store) -> Result<Response> {
                         url_query(&request),
                         Some(buffer),
                         request,
+                        timeout,
                     )?
                 } else if content_type.essence() == "application/x-www-form-urlencoded" {
                     let mut buffer = Vec::new();
@@ -127,7 +155,7 @@ async fn handle_request(request: Request, store: Store) -> Result<Response> {
                         .take(MAX_SPARQL_BODY_SIZE)
                         .read_to_end(&mut buffer)
                         .await?;
-                    configure_and_evaluate_sparql_query(store, buffer, None, request)?
+                    configure_and_evaluate_sparql_query(store, buffer, None, request, timeout)?
                 } else {
                     simple_response(
                         StatusCode::UnsupportedMediaType,
@@ -153,6 +181,7 @@ async fn handle_request(request: Request, store: Store) -> Result<Response> {
                         url_query(&request),
                         Some(buffer),
                         request,
+                        timeout,
                     )?
                 } else if content_type.essence() == "application/x-www-form-urlencoded" {
                     let mut buffer = Vec::new();
@@ -162,7 +191,7 @@ async fn handle_request(request: Request, store: Store) -> Result<Response> {
                         .take(MAX_SPARQL_BODY_SIZE)
                         .read_to_end(&mut buffer)
                         .await?;
-                    configure_and_evaluate_sparql_update(store, buffer, None, request)?
+                    configure_and_evaluate_sparql_update(store, buffer, None, request, timeout)?
                 } else {
                     simple_response(
                         StatusCode::UnsupportedMediaType,
@@ -179,6 +208,53 @@ async fn handle_request(request: Request, store: Store) -> Result<Response> {
     Ok(response)
 }
 
+/// Parses the possibly comma separated list of codings of the `Content-Encoding` header.
+///
+/// Only a single supported compression is allowed, possibly mixed with `identity` codings.
+fn content_encoding_compression(
+    request: &Request,
+) -> std::result::Result<Option<Compression>, String> {
+    let mut compression = None;
+    for content_encoding in request
+        .header(headers::CONTENT_ENCODING)
+        .into_iter()
+        .flatten()
+    {
+        for coding in content_encoding.as_str().split(',') {
+            let coding = coding.trim();
+            if coding.is_empty() || coding.eq_ignore_ascii_case("identity") {
+                continue;
+            }
+            let found = Compression::from_content_encoding(coding)
+                .ok_or_else(|| format!("Not supported Content-Encoding given: {}", coding))?;
+            if compression.is_some() {
+                return Err("Stacked Content-Encoding are not supported".into());
+            }
+            compression = Some(found);
+        }
+    }
+    Ok(compression)
+}
+
+/// Parses the value of a boolean parameter, an empty value meaning `true`.
+fn parse_boolean_parameter(name: &str, value: &str) -> Result<bool> {
+    match value {
+        "" | "true" => Ok(true),
+        "false" => Ok(false),
+        _ => bail_status!(400, "Invalid {} parameter value: {}", name, value),
+    }
+}
+
+/// Parses the `timeout` parameter, a duration in seconds, capped by the server maximal timeout.
+fn request_timeout(value: &str, max_timeout: Option<Duration>) -> Result<Duration> {
+    let timeout = Duration::from_secs(
+        value
+            .parse()
+            .map_err(|_| format_err_status!(400, "Invalid timeout: {}", value))?,
+    );
+    Ok(max_timeout.map_or(timeout, |max_timeout| timeout.min(max_timeout)))
+}
+
 fn simple_response(status: StatusCode, body: impl Into<Body>) -> Response {
     let mut response = Response::new(status);
     response.set_body(body);
@@ -199,9 +275,11 @@ fn configure_and_evaluate_sparql_query(
     encoded: Vec<u8>,
     mut query: Option<String>,
     request: Request,
+    mut timeout: Option<Duration>,
 ) -> Result<Response> {
     let mut default_graph_uris = Vec::new();
     let mut named_graph_uris = Vec::new();
+    let mut explain = false;
     for (k, v) in form_urlencoded::parse(&encoded) {
         match k.as_ref() {
             "query" => {
@@ -212,6 +290,8 @@ fn configure_and_evaluate_sparql_query(
             }
             "default-graph-uri" => default_graph_uris.push(v.into_owned()),
             "named-graph-uri" => named_graph_uris.push(v.into_owned()),
+            "timeout" => timeout = Some(request_timeout(&v, timeout)?),
+            "explain" => explain = parse_boolean_parameter("explain", &v)?,
             _ => {
                 return Ok(simple_response(
                     StatusCode::BadRequest,
@@ -221,7 +301,15 @@ fn configure_and_evaluate_sparql_query(
         }
     }
     if let Some(query) = query {
-        evaluate_sparql_query(store, query, default_graph_uris, named_graph_uris, request)
+        evaluate_sparql_query(
+            store,
+            query,
+            default_graph_uris,
+            named_graph_uris,
+            explain,
+            request,
+            timeout,
+        )
     } else {
         Ok(simple_response(
             StatusCode::BadRequest,
@@ -235,7 +323,9 @@ fn evaluate_sparql_query(
     query: String,
     default_graph_uris: Vec<String>,
     named_graph_uris: Vec<String>,
+    explain: bool,
     request: Request,
+    timeout: Option<Duration>,
 ) -> Result<Response> {
     let mut query = Query::parse(&query, Some(base_url(&request))).map_err(bad_request)?;
     let default_graph_uris = default_graph_uris
@@ -256,7 +346,40 @@ fn evaluate_sparql_query(
             .set_available_named_graphs(named_graph_uris);
     }
 
-    let results = store.query(query)?;
+    let mut options = QueryOptions::default();
+    if let Some(timeout) = timeout {
+        options = options.with_timeout(timeout);
+    }
+    if explain {
+        let explanation = store
+            .explain_opt(query, options)
+            .map_err(evaluation_error)?;
+        let json =
+            content_negotiation(request, &["text/plain", "application/json"], |media_type| {
+                match media_type {
+                    "text/plain" => Some(false),
+                    "application/json" => Some(true),
+                    _ => None,
+                }
+            })?;
+        let mut response = if json {
+            let mut body = Vec::default();
+            explanation.write_json(&mut body)?;
+            Response::from(body)
+        } else {
+            Response::from(explanation.to_string())
+        };
+        response.insert_header(
+            headers::CONTENT_TYPE,
+            if json {
+                "application/json"
+            } else {
+                "text/plain"
+            },
+        );
+        return Ok(response);
+    }
+    let results = store.query_opt(query, options).map_err(evaluation_error)?;
     //TODO: stream
     if let QueryResults::Graph(_) = results {
         let format = content_negotiation(
@@ -269,7 +392,9 @@ fn evaluate_sparql_query(
             GraphFormat::from_media_type,
         )?;
         let mut body = Vec::default();
-        results.write_graph(&mut body, format)?;
+        results
+            .write_graph(&mut body, format)
+            .map_err(evaluation_error)?;
         let mut response = Response::from(body);
         response.insert_header(headers::CONTENT_TYPE, format.media_type());
         Ok(response)
@@ -285,7 +410,7 @@ fn evaluate_sparql_query(
             QueryResultsFormat::from_media_type,
         )?;
         let mut body = Vec::default();
-        results.write(&mut body, format)?;
+        results.write(&mut body, format).map_err(evaluation_error)?;
         let mut response = Response::from(body);
         response.insert_header(headers::CONTENT_TYPE, format.media_type());
         Ok(response)
@@ -297,6 +422,7 @@ fn configure_and_evaluate_sparql_update(
     encoded: Vec<u8>,
     mut update: Option<String>,
     request: Request,
+    mut timeout: Option<Duration>,
 ) -> Result<Response> {
     let mut default_graph_uris = Vec::new();
     let mut named_graph_uris = Vec::new();
@@ -310,6 +436,7 @@ fn configure_and_evaluate_sparql_update(
             }
             "using-graph-uri" => default_graph_uris.push(v.into_owned()),
             "using-named-graph-uri" => named_graph_uris.push(v.into_owned()),
+            "timeout" => timeout = Some(request_timeout(&v, timeout)?),
             _ => {
                 return Ok(simple_response(
                     StatusCode::BadRequest,
@@ -319,7 +446,14 @@ fn configure_and_evaluate_sparql_update(
         }
     }
     if let Some(update) = update {
-        evaluate_sparql_update(store, update, default_graph_uris, named_graph_uris, request)
+        evaluate_sparql_update(
+            store,
+            update,
+            default_graph_uris,
+            named_graph_uris,
+            request,
+            timeout,
+        )
     } else {
         Ok(simple_response(
             StatusCode::BadRequest,
@@ -334,6 +468,7 @@ fn evaluate_sparql_update(
     default_graph_uris: Vec<String>,
     named_graph_uris: Vec<String>,
     request: Request,
+    timeout: Option<Duration>,
 ) -> Result<Response> {
     let mut update = Update::parse(&update, Some(base_url(&request))).map_err(|e| {
         let mut e = Error::from(e);
@@ -365,7 +500,13 @@ fn evaluate_sparql_update(
             }
         }
     }
-    store.update(update)?;
+    let mut options = QueryOptions::default();
+    if let Some(timeout) = timeout {
+        options = options.with_timeout(timeout);
+    }
+    store
+        .update_opt(update, options.into())
+        .map_err(evaluation_error)?;
     Ok(Response::new(StatusCode::NoContent))
 }
 
@@ -447,6 +588,16 @@ fn content_negotiation<F>(
         .ok_or_else(|| Error::from_str(StatusCode::InternalServerError, "Unknown mime type"))
 }
 
+fn evaluation_error(e: EvaluationError) -> Error {
+    let status = match e {
+        EvaluationError::Timeout => StatusCode::ServiceUnavailable,
+        _ => StatusCode::InternalServerError,
+    };
+    let mut e = Error::from(e);
+    e.set_status(status);
+    e
+}
+
 fn bad_request(e: impl Into<Error>) -> Error {
     let mut e = e.into();
     e.set_status(StatusCode::BadRequest);
@@ -477,10 +628,13 @@ mod tests {
     use crate::handle_request;
     use async_std::task::block_on;
     use http_types::{Method, Request, StatusCode, Url};
+    use oxigraph::io::{Compression, GraphFormat, GraphSerializer};
+    use oxigraph::model::{NamedNode, Triple};
     use std::collections::hash_map::DefaultHasher;
     use std::env::temp_dir;
     use std::fs::remove_dir_all;
     use std::hash::{Hash, Hasher};
+    use std::time::Duration;
 
     #[test]
     fn get_ui() {
@@ -506,6 +660,70 @@ mod tests {
         exec(request, StatusCode::BadRequest)
     }
 
+    #[test]
+    fn post_compressed_file() {
+        let mut body = Vec::new();
+        let mut writer = GraphSerializer::from_format(GraphFormat::NTriples)
+            .with_compression(Compression::Gzip)
+            .triple_writer(&mut body)
+            .unwrap();
+        let ex = NamedNode::new("http://example.com").unwrap();
+        writer
+            .write(&Triple::new(ex.clone(), ex.clone(), ex))
+            .unwrap();
+        writer.finish().unwrap();
+
+        let mut request = Request::new(Method::Post, Url::parse("http://localhost/").unwrap());
+        request.insert_header("Content-Type", "application/n-triples");
+        request.insert_header("Content-Encoding", "gzip");
+        request.set_body(body);
+        exec(request, StatusCode::NoContent)
+    }
+
+    #[test]
+    fn post_unsupported_encoding() {
+        let mut request = Request::new(Method::Post, Url::parse("http://localhost/").unwrap());
+        request.insert_header("Content-Type", "text/turtle");
+        request.insert_header("Content-Encoding", "br");
+        request.set_body("<http://example.com> <http://example.com> <http://example.com> .");
+        exec(request, StatusCode::UnsupportedMediaType)
+    }
+
+    #[test]
+    fn post_identity_and_gzip_encoding() {
+        let mut body = Vec::default();
+        let mut writer = GraphSerializer::from_format(GraphFormat::NTriples)
+            .with_compression(Compression::Gzip)
+            .triple_writer(&mut body)
+            .unwrap();
+        let ex = NamedNode::new("http://example.com").unwrap();
+        writer
+            .write(&Triple::new(ex.clone(), ex.clone(), ex))
+            .unwrap();
+        writer.finish().unwrap();
+
+        let mut request = Request::new(Method::Post, Url::parse("http://localhost/").unwrap());
+        request.insert_header("Content-Type", "application/n-triples");
+        request.insert_header("Content-Encoding", "identity, gzip");
+        request.set_body(body);
+        exec(request, StatusCode::NoContent)
+    }
+
+    #[test]
+    fn post_stacked_encoding() {
+        let mut request = Request::new(Method::Post, Url::parse("http://localhost/").unwrap());
+        request.insert_header("Content-Type", "text/turtle");
+        request.insert_header("Content-Encoding", "gzip, br");
+        request.set_body("<http://example.com> <http://example.com> <http://example.com> .");
+        exec(request, StatusCode::UnsupportedMediaType);
+
+        let mut request = Request::new(Method::Post, Url::parse("http://localhost/").unwrap());
+        request.insert_header("Content-Type", "text/turtle");
+        request.insert_header("Content-Encoding", "gzip, gzip");
+        request.set_body("<http://example.com> <http://example.com> <http://example.com> .");
+        exec(request, StatusCode::UnsupportedMediaType)
+    }
+
     #[test]
     fn post_unsupported_file() {
         let mut request = Request::new(Method::Post, Url::parse("http://localhost/").unwrap());
@@ -527,6 +745,144 @@ mod tests {
         );
     }
 
+    #[test]
+    fn get_query_timeout() {
+        exec_with_timeout(
+            Request::new(
+                Method::Get,
+                Url::parse(
+                    "http://localhost/query?query=SELECT%20*%20WHERE%20{%20?s%20?p%20?o%20}",
+                )
+                .unwrap(),
+            ),
+            Some(Duration::from_secs(0)),
+            StatusCode::ServiceUnavailable,
+        );
+    }
+
+    #[test]
+    fn get_query_request_timeout() {
+        exec(
+            Request::new(
+                Method::Get,
+                Url::parse(
+                    "http://localhost/query?query=SELECT%20*%20WHERE%20{%20?s%20?p%20?o%20}&timeout=0",
+                )
+                .unwrap(),
+            ),
+            StatusCode::ServiceUnavailable,
+        );
+    }
+
+    #[test]
+    fn get_query_request_timeout_above_maximum() {
+        exec_with_timeout(
+            Request::new(
+                Method::Get,
+                Url::parse(
+                    "http://localhost/query?query=SELECT%20*%20WHERE%20{%20?s%20?p%20?o%20}&timeout=60",
+                )
+                .unwrap(),
+            ),
+            Some(Duration::from_secs(0)),
+            StatusCode::ServiceUnavailable,
+        );
+    }
+
+    #[test]
+    fn get_query_bad_timeout() {
+        exec(
+            Request::new(
+                Method::Get,
+                Url::parse(
+                    "http://localhost/query?query=SELECT%20*%20WHERE%20{%20?s%20?p%20?o%20}&timeout=foo",
+                )
+                .unwrap(),
+            ),
+            StatusCode::BadRequest,
+        );
+    }
+
+    #[test]
+    fn get_query_explain() {
+        let body = exec_and_read_body(
+            Request::new(
+                Method::Get,
+                Url::parse(
+                    "http://localhost/query?query=SELECT%20*%20WHERE%20{%20?s%20?p%20?o%20}&explain",
+                )
+                .unwrap(),
+            ),
+            None,
+            StatusCode::Ok,
+        );
+        assert_eq!(
+            body,
+            "Project (variables: ?o ?p ?s, estimated cardinality: 0)\n  QuadPatternJoin (pattern: ?s ?p ?o, graph: DEFAULT, index: dspo, join: index nested loop, estimated cardinality: 0)\n    Init (estimated cardinality: 1)\n"
+        );
+    }
+
+    #[test]
+    fn get_query_explain_false() {
+        let body = exec_and_read_body(
+            Request::new(
+                Method::Get,
+                Url::parse(
+                    "http://localhost/query?query=SELECT%20*%20WHERE%20{%20?s%20?p%20?o%20}&explain=false",
+                )
+                .unwrap(),
+            ),
+            None,
+            StatusCode::Ok,
+        );
+        assert!(body.contains("<sparql"), "{}", body);
+    }
+
+    #[test]
+    fn get_query_bad_explain() {
+        exec(
+            Request::new(
+                Method::Get,
+                Url::parse(
+                    "http://localhost/query?query=SELECT%20*%20WHERE%20{%20?s%20?p%20?o%20}&explain=foo",
+                )
+                .unwrap(),
+            ),
+            StatusCode::BadRequest,
+        );
+    }
+
+    #[test]
+    fn get_query_explain_timeout() {
+        exec_with_timeout(
+            Request::new(
+                Method::Get,
+                Url::parse(
+                    "http://localhost/query?query=SELECT%20*%20WHERE%20{%20?s%20?p%20?o%20}&explain=true",
+                )
+                .unwrap(),
+            ),
+            Some(Duration::from_secs(0)),
+            StatusCode::ServiceUnavailable,
+        );
+    }
+
+    #[test]
+    fn get_query_explain_json() {
+        let mut request = Request::new(
+            Method::Get,
+            Url::parse(
+                "http://localhost/query?query=SELECT%20*%20WHERE%20{%20?s%20?p%20?o%20}&explain",
+            )
+            .unwrap(),
+        );
+        request.insert_header("Accept", "application/json");
+        assert_eq!(
+            exec_and_read_body(request, None, StatusCode::Ok),
+            r#"{"name":"Project","attributes":{"variables":"?o ?p ?s"},"estimated_cardinality":0,"children":[{"name":"QuadPatternJoin","attributes":{"pattern":"?s ?p ?o","graph":"DEFAULT","index":"dspo","join":"index nested loop"},"estimated_cardinality":0,"children":[{"name":"Init","attributes":{},"estimated_cardinality":1,"children":[]}]}]}"#
+        );
+    }
+
     #[test]
     fn get_bad_query() {
         exec(
@@ -589,6 +945,15 @@ mod tests {
         exec(request, StatusCode::NoContent)
     }
 
+    #[test]
+    fn post_update_request_timeout() {
+        let mut request =
+            Request::new(Method::Post, Url::parse("http://localhost/update").unwrap());
+        request.insert_header("Content-Type", "application/x-www-form-urlencoded");
+        request.set_body("update=DELETE%20WHERE%20{%20?s%20?p%20?o%20}&timeout=0");
+        exec(request, StatusCode::ServiceUnavailable)
+    }
+
     #[test]
     fn post_bad_update() {
         let mut request =
@@ -599,6 +964,18 @@ mod tests {
     }
 
     fn exec(request: Request, expected_status: StatusCode) {
+        exec_with_timeout(request, None, expected_status)
+    }
+
+    fn exec_with_timeout(request: Request, timeout: Option<Duration>, expected_status: StatusCode) {
+        exec_and_read_body(request, timeout, expected_status);
+    }
+
+    fn exec_and_read_body(
+        request: Request,
+        timeout: Option<Duration>,
+        expected_status: StatusCode,
+    ) -> String {
         let mut path = temp_dir();
         path.push("temp-oxigraph-server-test");
         let mut s = DefaultHasher::new();
@@ -606,11 +983,12 @@ mod tests {
         path.push(&s.finish().to_string());
 
         let store = Store::open(&path).unwrap();
-        let (code, message) = match block_on(handle_request(request, store)) {
-            Ok(r) => (r.status(), "".to_string()),
+        let (code, body) = match block_on(handle_request(request, store, timeout)) {
+            Ok(mut r) => (r.status(), block_on(r.body_string()).unwrap()),
             Err(e) => (e.status(), e.to_string()),
         };
-        assert_eq!(code, expected_status, "Error message: {}", message);
-        remove_dir_all(&path).unwrap()
+        assert_eq!(code, expected_status, "Error message: {}", body);
+        remove_dir_all(&path).unwrap();
+        body
     }
 }
@@ -1,8 +1,9 @@
 use anyhow::{anyhow, Result};
-use oxigraph::io::{DatasetFormat, GraphFormat};
+use oxigraph::io::{DatasetFormat, DatasetParser, GraphFormat};
 use oxigraph::model::{GraphName, GraphNameRef};
 use oxigraph::MemoryStore;
 use std::fs::File;
+use std::io;
 use std::io::{BufRead, BufReader, Read};
 use std::path::PathBuf;
 
@@ -22,6 +23,11 @@ pub fn read_file(url: &str) -> Result<impl BufRead> {
             "http://www.w3.org/2009/sparql/docs/tests/",
             "rdf-tests/sparql11/",
         ))
+    } else if url.starts_with("https://w3c.github.io/json-ld-api/tests/") {
+        Ok(url.replace(
+            "https://w3c.github.io/json-ld-api/tests/",
+            "json-ld-api/tests/",
+        ))
     } else if url.starts_with("https://github.com/oxigraph/oxigraph/tests/") {
         Ok(url.replace(
             "https://github.com/oxigraph/oxigraph/tests/",
@@ -50,31 +56,40 @@ pub fn load_to_store<'a>(
             GraphFormat::NTriples,
             to_graph_name,
             Some(url),
-        )?
+        )?;
     } else if url.ends_with(".ttl") {
         store.load_graph(
             read_file(url)?,
             GraphFormat::Turtle,
             to_graph_name,
             Some(url),
-        )?
+        )?;
     } else if url.ends_with(".rdf") {
         store.load_graph(
             read_file(url)?,
             GraphFormat::RdfXml,
             to_graph_name,
             Some(url),
-        )?
+        )?;
     } else if url.ends_with(".nq") {
-        store.load_dataset(read_file(url)?, DatasetFormat::NQuads, Some(url))?
+        store.load_dataset(read_file(url)?, DatasetFormat::NQuads, Some(url))?;
     } else if url.ends_with(".trig") {
-        store.load_dataset(read_file(url)?, DatasetFormat::TriG, Some(url))?
+        store.load_dataset(read_file(url)?, DatasetFormat::TriG, Some(url))?;
+    } else if url.ends_with(".jsonld") {
+        store.load_dataset(read_file(url)?, json_ld_parser(), Some(url))?;
     } else {
         return Err(anyhow!("Serialization type not found for {}", url));
     }
     Ok(())
 }
 
+/// JSON-LD parser resolving the remote contexts from the local test files
+pub fn json_ld_parser() -> DatasetParser {
+    DatasetParser::from_format(DatasetFormat::JsonLd).with_document_loader(|iri: &str| {
+        read_file_to_string(iri).map_err(|e| io::Error::new(io::ErrorKind::NotFound, e.to_string()))
+    })
+}
+
 pub fn load_store(url: &str) -> Result<MemoryStore> {
     let store = MemoryStore::new();
     load_to_store(url, &store, &GraphName::DefaultGraph)?;
@@ -74,10 +74,37 @@ impl Iterator for TestManifest {
     fn next(&mut self) -> Option<Result<Test>> {
         match self.tests_to_do.pop() {
             Some(Term::NamedNode(test_node)) => {
-                let kind = match object_for_subject_predicate(&self.graph, &test_node, rdf::TYPE) {
-                    Some(Term::NamedNode(c)) => c,
-                    _ => return self.next(), //We ignore the test
+                let kinds = objects_for_subject_predicate(&self.graph, &test_node, rdf::TYPE)
+                    .filter_map(|c| match c {
+                        Term::NamedNode(c) => Some(c),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                // JSON-LD tests have both a kind (ToRDFTest, FromRDFTest...) and an expected outcome
+                let kind = match kinds
+                    .iter()
+                    .find(|c| c.as_ref() == jld::NEGATIVE_EVALUATION_TEST)
+                    .or_else(|| {
+                        kinds.iter().find(|c| {
+                            c.as_ref() == jld::TO_RDF_TEST || c.as_ref() == jld::FROM_RDF_TEST
+                        })
+                    })
+                    .or_else(|| kinds.first())
+                {
+                    Some(c) => c.clone(),
+                    None => return self.next(), //We ignore the test
                 };
+                if let Some(Term::BlankNode(option)) =
+                    object_for_subject_predicate(&self.graph, &test_node, jld::OPTION)
+                {
+                    if let Some(Term::Literal(version)) =
+                        object_for_subject_predicate(&self.graph, &option, jld::SPEC_VERSION)
+                    {
+                        if version.value() == "json-ld-1.0" {
+                            return self.next(); //We ignore the tests specific to JSON-LD 1.0
+                        }
+                    }
+                }
                 let name = match object_for_subject_predicate(&self.graph, &test_node, mf::NAME) {
                     Some(Term::Literal(c)) => Some(c.value().to_string()),
                     _ => None,
@@ -245,8 +272,10 @@ impl Iterator for TestManifest {
             None => {
                 match self.manifests_to_do.pop() {
                     Some(url) => {
-                        let manifest =
-                            NamedOrBlankNodeRef::from(NamedNodeRef::new(url.as_str()).unwrap());
+                        // JSON-LD manifests use their IRI without the file extension as base IRI
+                        let manifest = NamedOrBlankNodeRef::from(
+                            NamedNodeRef::new(url.strip_suffix(".jsonld").unwrap_or(&url)).unwrap(),
+                        );
                         if let Err(error) =
                             load_to_store(&url, &self.graph, GraphNameRef::DefaultGraph)
                         {
@@ -326,7 +355,7 @@ impl<'a> Iterator for RdfListIterator<'a> {
 
 fn object_for_subject_predicate<'a>(
     store: &MemoryStore,
-    subject: impl Into<NamedOrBlankNodeRef<'a>>,
+    subject: impl Into<SubjectRef<'a>>,
     predicate: impl Into<NamedNodeRef<'a>>,
 ) -> Option<Term> {
     objects_for_subject_predicate(store, subject, predicate).next()
@@ -334,7 +363,7 @@ fn object_for_subject_predicate<'a>(
 
 fn objects_for_subject_predicate<'a>(
     store: &MemoryStore,
-    subject: impl Into<NamedOrBlankNodeRef<'a>>,
+    subject: impl Into<SubjectRef<'a>>,
     predicate: impl Into<NamedNodeRef<'a>>,
 ) -> impl Iterator<Item = Term> {
     store
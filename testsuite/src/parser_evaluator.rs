@@ -1,8 +1,10 @@
-use crate::files::load_store;
+use crate::files::{json_ld_parser, load_store};
 use crate::manifest::Test;
 use crate::report::{store_diff, TestResult};
 use anyhow::{anyhow, Result};
 use chrono::Utc;
+use oxigraph::io::{DatasetFormat, DatasetSerializer};
+use oxigraph::MemoryStore;
 
 pub fn evaluate_parser_tests(
     manifest: impl Iterator<Item = Result<Test>>,
@@ -29,6 +31,7 @@ fn evaluate_parser_test(test: &Test) -> Result<()> {
         || test.kind == "http://www.w3.org/ns/rdftest#TestNQuadsPositiveSyntax"
         || test.kind == "http://www.w3.org/ns/rdftest#TestTurtlePositiveSyntax"
         || test.kind == "http://www.w3.org/ns/rdftest#TestTrigPositiveSyntax"
+        || test.kind == "https://w3c.github.io/json-ld-api/tests/vocab#PositiveSyntaxTest"
     {
         match load_store(action) {
             Ok(_) => Ok(()),
@@ -41,14 +44,23 @@ fn evaluate_parser_test(test: &Test) -> Result<()> {
         || test.kind == "http://www.w3.org/ns/rdftest#TestTrigNegativeSyntax"
         || test.kind == "http://www.w3.org/ns/rdftest#TestTrigNegativeEval"
         || test.kind == "http://www.w3.org/ns/rdftest#TestXMLNegativeSyntax"
+        || test.kind == "https://w3c.github.io/json-ld-api/tests/vocab#NegativeEvaluationTest"
     {
-        match load_store(action) {
+        match load_store(action).and_then(|store| {
+            if action.ends_with(".jsonld") {
+                Ok(store)
+            } else {
+                // The error is expected during the JSON-LD serialization
+                to_json_ld(&store).map(|_| store)
+            }
+        }) {
             Ok(_) => Err(anyhow!("File parsed with an error even if it should not",)),
             Err(_) => Ok(()),
         }
     } else if test.kind == "http://www.w3.org/ns/rdftest#TestTurtleEval"
         || test.kind == "http://www.w3.org/ns/rdftest#TestTrigEval"
         || test.kind == "http://www.w3.org/ns/rdftest#TestXMLEval"
+        || test.kind == "https://w3c.github.io/json-ld-api/tests/vocab#ToRDFTest"
     {
         match load_store(action) {
             Ok(actual_graph) => {
@@ -72,7 +84,39 @@ fn evaluate_parser_test(test: &Test) -> Result<()> {
             }
             Err(e) => Err(anyhow!("Parse error on file {}: {}", action, e)),
         }
+    } else if test.kind == "https://w3c.github.io/json-ld-api/tests/vocab#FromRDFTest" {
+        let input =
+            load_store(action).map_err(|e| anyhow!("Parse error on file {}: {}", action, e))?;
+        let actual_graph = MemoryStore::new();
+        actual_graph
+            .load_dataset(to_json_ld(&input)?.as_slice(), json_ld_parser(), None)
+            .map_err(|e| anyhow!("Parse error on the serialization of {}: {}", action, e))?;
+        let result = test
+            .result
+            .as_deref()
+            .ok_or_else(|| anyhow!("No tests result found"))?;
+        let expected_graph =
+            load_store(result).map_err(|e| anyhow!("Parse error on file {}: {}", result, e))?;
+        if expected_graph.is_isomorphic(&actual_graph) {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "The two files are not isomorphic. Diff:\n{}",
+                store_diff(&expected_graph, &actual_graph)
+            ))
+        }
     } else {
         Err(anyhow!("Unsupported test type: {}", test.kind))
     }
 }
+
+fn to_json_ld(store: &MemoryStore) -> Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    let mut writer =
+        DatasetSerializer::from_format(DatasetFormat::JsonLd).quad_writer(&mut buffer)?;
+    for quad in store.iter() {
+        writer.write(&quad)?;
+    }
+    writer.finish()?;
+    Ok(buffer)
+}
@@ -74,3 +74,19 @@ pub mod ut {
     pub const REQUEST: NamedNodeRef<'_> =
         NamedNodeRef::new_unchecked("http://www.w3.org/2009/sparql/tests/test-update#request");
 }
+
+pub mod jld {
+    use oxigraph::model::NamedNodeRef;
+
+    pub const NEGATIVE_EVALUATION_TEST: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(
+        "https://w3c.github.io/json-ld-api/tests/vocab#NegativeEvaluationTest",
+    );
+    pub const TO_RDF_TEST: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("https://w3c.github.io/json-ld-api/tests/vocab#ToRDFTest");
+    pub const FROM_RDF_TEST: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("https://w3c.github.io/json-ld-api/tests/vocab#FromRDFTest");
+    pub const OPTION: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("https://w3c.github.io/json-ld-api/tests/vocab#option");
+    pub const SPEC_VERSION: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("https://w3c.github.io/json-ld-api/tests/vocab#specVersion");
+}
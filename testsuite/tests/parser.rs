@@ -41,3 +41,13 @@ fn trig_w3c_testsuite() -> Result<()> {
 fn rdf_xml_w3c_testsuite() -> Result<()> {
     run_testsuite("http://www.w3.org/2013/RDFXMLTests/manifest.ttl")
 }
+
+#[test]
+fn json_ld_to_rdf_w3c_testsuite() -> Result<()> {
+    run_testsuite("https://w3c.github.io/json-ld-api/tests/toRdf-manifest.jsonld")
+}
+
+#[test]
+fn json_ld_from_rdf_w3c_testsuite() -> Result<()> {
+    run_testsuite("https://w3c.github.io/json-ld-api/tests/fromRdf-manifest.jsonld")
+}